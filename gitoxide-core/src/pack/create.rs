@@ -2,7 +2,16 @@ use std::{ffi::OsStr, io, path::Path, str::FromStr, time::Instant};
 
 use anyhow::anyhow;
 use gix::{
-    hash, hash::ObjectId, interrupt, objs::bstr::ByteVec, odb::pack, parallel::InOrderIter, prelude::Finalize,
+    bstr::{BString, ByteSlice},
+    config::tree::Pack,
+    hash,
+    hash::ObjectId,
+    interrupt,
+    objs::bstr::ByteVec,
+    odb::pack,
+    odb::pack::data::output::entry::delta_from_counts,
+    parallel::InOrderIter,
+    prelude::Finalize,
     progress, traverse, Count, NestedProgress, Progress,
 };
 
@@ -62,6 +71,17 @@ pub struct Context<W> {
     /// If true, delta objects may refer to their base as reference, allowing it not to be included in the created back.
     /// Otherwise these have to be recompressed in order to make the pack self-contained.
     pub thin: bool,
+    /// If true, decode all objects and search for similar ones to store them as deltas against each other, instead of
+    /// copying them from existing packs as they are.
+    ///
+    /// This takes longer, but can produce much smaller packs. It cannot be combined with `thin`.
+    pub delta_compression: bool,
+    /// The amount of objects to try as delta base for each object, overriding `pack.window` (defaulting to 10).
+    /// Only used if `delta_compression` is enabled.
+    pub window: Option<usize>,
+    /// The maximum length of delta chains, overriding `pack.depth` (defaulting to 50).
+    /// Only used if `delta_compression` is enabled.
+    pub depth: Option<usize>,
     /// If set, don't use more than this amount of threads.
     /// Otherwise, usually use as many threads as there are logical cores.
     /// A value of 0 is interpreted as no-limit
@@ -94,6 +114,9 @@ pub fn create<W, P>(
         expansion,
         nondeterministic_thread_count,
         thin,
+        delta_compression,
+        window,
+        depth,
         thread_limit,
         statistics,
         pack_cache_size_in_bytes,
@@ -108,7 +131,16 @@ where
 {
     type ObjectIdIter = dyn Iterator<Item = Result<ObjectId, Box<dyn std::error::Error + Send + Sync>>> + Send;
 
+    if thin && delta_compression {
+        anyhow::bail!("Thin packs can't be created with delta compression");
+    }
     let repo = gix::discover(repository_path)?.into_sync();
+    // Open the repository once more as the object database of `repo` must not be shared to be turned into a handle.
+    let delta_repo = if delta_compression {
+        Some(gix::open(repo.git_dir())?)
+    } else {
+        None
+    };
     progress.init(Some(2), progress::steps());
     let tips = tips.into_iter();
     let make_cancellation_err = || anyhow!("Cancelled by user");
@@ -221,9 +253,37 @@ where
 
     progress.inc();
     let num_objects = counts.len();
-    let mut in_order_entries = {
+    let (mut in_order_entries, delta_entries) = if let Some(repo) = delta_repo {
+        let config = repo.config_snapshot();
+        let config_usize = |key: &'static gix::config::tree::keys::UnsignedInteger| {
+            config
+                .integer(key)
+                .map(|value| key.try_into_usize(Ok(value)))
+                .transpose()
+        };
+        let options = delta_from_counts::Options {
+            window: window.map_or_else(|| config_usize(&Pack::WINDOW).map(|window| window.unwrap_or(10)), Ok)?,
+            depth: depth.map_or_else(|| config_usize(&Pack::DEPTH).map(|depth| depth.unwrap_or(50)), Ok)?,
+            thread_limit,
+        };
+        let hints = delta_hints(&repo, &counts)?;
+
+        let mut progress = progress.add_child("compressing deltas");
+        progress.init(Some(num_objects), progress::count("objects"));
+        let progress = progress::ThroughputOnDrop::new(progress);
+        let (entries, outcome) = pack::data::output::entry::delta_from_counts(
+            &counts,
+            |count| hints.get(&count.id).copied().unwrap_or_default(),
+            handle,
+            &progress,
+            &interrupt::IS_INTERRUPTED,
+            options,
+        )?;
+        stats.deltas = Some(outcome);
+        (None, Some(entries))
+    } else {
         let progress = progress.add_child("creating entries");
-        InOrderIter::from(pack::data::output::entry::iter_from_counts(
+        let entries = InOrderIter::from(pack::data::output::entry::iter_from_counts(
             counts,
             handle,
             Box::new(progress),
@@ -234,7 +294,14 @@ where
                 chunk_size,
                 version: Default::default(),
             },
-        ))
+        ));
+        (Some(entries), None)
+    };
+    type EntriesIter<'a> = dyn Iterator<Item = Result<Vec<pack::data::output::Entry>, pack::data::output::entry::iter_from_counts::Error>>
+        + 'a;
+    let entries: Box<EntriesIter<'_>> = match in_order_entries.as_mut() {
+        Some(in_order_entries) => Box::new(in_order_entries.by_ref()),
+        None => Box::new(delta_entries.map(Ok).into_iter()),
     };
 
     let mut entries_progress = progress.add_child("consuming");
@@ -257,7 +324,7 @@ where
     };
     let mut interruptible_output_iter = interrupt::Iter::new(
         pack::data::output::bytes::FromEntriesIter::new(
-            in_order_entries.by_ref().inspect(|e| {
+            entries.inspect(|e| {
                 if let Ok(entries) = e {
                     entries_progress.inc_by(entries.len());
                 }
@@ -284,7 +351,9 @@ where
     } else {
         writeln!(out, "{pack_name}")?;
     }
-    stats.entries = in_order_entries.inner.finalize()?;
+    if let Some(in_order_entries) = in_order_entries {
+        stats.entries = in_order_entries.inner.finalize()?;
+    }
 
     write_progress.show_throughput(start);
     entries_progress.show_throughput(start);
@@ -296,6 +365,69 @@ where
     Ok(())
 }
 
+/// Learn the first path at which each tree and blob in `counts` is seen when traversing the trees of the commits
+/// in `counts`, to help finding similar objects and to honor the `delta` attribute.
+fn delta_hints(
+    repo: &gix::Repository,
+    counts: &[pack::data::output::Count],
+) -> anyhow::Result<gix::hashtable::HashMap<ObjectId, delta_from_counts::Hint>> {
+    let index = repo.index_or_load_from_head_or_empty()?;
+    let mut attributes = repo.attributes_only(
+        &index,
+        if repo.is_bare() {
+            gix::worktree::stack::state::attributes::Source::IdMapping
+        } else {
+            gix::worktree::stack::state::attributes::Source::WorktreeThenIdMapping
+        },
+    )?;
+    let mut delta_attribute = attributes.selected_attribute_matches(Some("delta"));
+
+    let wanted: gix::hashtable::HashSet<_> = counts.iter().map(|count| count.id).collect();
+    let mut seen_trees = gix::hashtable::HashSet::default();
+    let mut out = gix::hashtable::HashMap::default();
+    let mut stack = Vec::<(ObjectId, BString)>::new();
+    for count in counts {
+        if repo.find_header(count.id)?.kind() == gix::object::Kind::Commit {
+            stack.push((repo.find_commit(count.id)?.tree_id()?.detach(), BString::default()));
+        }
+        while let Some((tree_id, tree_path)) = stack.pop() {
+            if !seen_trees.insert(tree_id) {
+                continue;
+            }
+            let tree = repo.find_tree(tree_id)?;
+            for entry in tree.decode()?.entries.iter() {
+                if entry.mode.is_commit() {
+                    continue;
+                }
+                let mut path = tree_path.clone();
+                if !path.is_empty() {
+                    path.push(b'/');
+                }
+                path.extend_from_slice(entry.filename);
+                if wanted.contains(entry.oid) && !out.contains_key(entry.oid) {
+                    let no_delta = attributes
+                        .at_entry(path.as_bstr(), Some(crate::is_dir_to_mode(entry.mode.is_tree())))?
+                        .matching_attributes(&mut delta_attribute)
+                        && delta_attribute
+                            .iter_selected()
+                            .any(|attr| attr.assignment.state == gix::attrs::StateRef::Unset);
+                    out.insert(
+                        entry.oid.to_owned(),
+                        delta_from_counts::Hint {
+                            name_hash: pack::bitmap::name_hash(path.as_ref()),
+                            no_delta,
+                        },
+                    );
+                }
+                if entry.mode.is_tree() {
+                    stack.push((entry.oid.to_owned(), path));
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
 fn print(stats: Statistics, format: OutputFormat, out: impl std::io::Write) -> anyhow::Result<()> {
     match format {
         OutputFormat::Human => human_output(stats, out).map_err(Into::into),
@@ -320,6 +452,7 @@ fn human_output(
                 objects_copied_from_pack,
                 ref_delta_objects,
             },
+        deltas,
     }: Statistics,
     mut out: impl std::io::Write,
 ) -> std::io::Result<()> {
@@ -336,6 +469,23 @@ fn human_output(
         width = width
     )?;
     writeln!(out, "generation phase")?;
+    if let Some(delta_from_counts::Outcome {
+        delta_objects,
+        base_objects,
+        missing_objects,
+    }) = deltas
+    {
+        #[rustfmt::skip]
+        writeln!(
+            out,
+            "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
+            "delta objects", delta_objects,
+            "base objects", base_objects,
+            "missing objects", missing_objects,
+            width = width
+        )?;
+        return Ok(());
+    }
     #[rustfmt::skip]
     writeln!(
         out,
@@ -354,6 +504,7 @@ fn human_output(
 struct Statistics {
     counts: pack::data::output::count::objects::Outcome,
     entries: pack::data::output::entry::iter_from_counts::Outcome,
+    deltas: Option<delta_from_counts::Outcome>,
 }
 
 pub mod input_iteration {
@@ -133,6 +133,8 @@ where
             shallow: &Default::default(),
             tags: Default::default(),
             reject_shallow_remote: true,
+            filter: None,
+            packfile_uri_protocols: Vec::new(),
         },
     )
     .await?;
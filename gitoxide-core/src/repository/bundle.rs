@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use anyhow::bail;
+use gix::{bundle::Header, NestedProgress};
+
+use crate::OutputFormat;
+
+pub fn create(
+    repo: gix::Repository,
+    path: &Path,
+    specs: Vec<gix::bstr::BString>,
+    mut progress: impl NestedProgress + 'static,
+    mut err: impl std::io::Write,
+    options: gix::repository::create_bundle::Options,
+) -> anyhow::Result<()> {
+    let out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let header = repo.create_bundle(specs, out, &mut progress, &gix::interrupt::IS_INTERRUPTED, options)?;
+    writeln!(
+        err,
+        "Wrote bundle with {} reference(s) and {} prerequisite(s) to '{}'",
+        header.refs.len(),
+        header.prerequisites.len(),
+        path.display()
+    )?;
+    Ok(())
+}
+
+pub fn verify(
+    repo: gix::Repository,
+    path: &Path,
+    mut out: impl std::io::Write,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    let bundle = gix::bundle::File::at(path)?;
+    if bundle.header.object_hash != repo.object_hash() {
+        bail!(
+            "The bundle uses {} object ids, but the repository uses {}",
+            bundle.header.object_hash,
+            repo.object_hash()
+        );
+    }
+    let missing = repo.missing_bundle_prerequisites(&bundle.header);
+    if !missing.is_empty() {
+        for prerequisite in &missing {
+            writeln!(out, "{} {}", prerequisite.id, prerequisite.comment)?;
+        }
+        bail!("The repository lacks {} prerequisite commit(s)", missing.len());
+    }
+    write_header(&bundle.header, &mut out)?;
+    writeln!(out, "{} is okay", path.display())?;
+    Ok(())
+}
+
+pub fn list_heads(path: &Path, mut out: impl std::io::Write, format: OutputFormat) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    let bundle = gix::bundle::File::at(path)?;
+    for r in &bundle.header.refs {
+        writeln!(out, "{} {}", r.id, r.name)?;
+    }
+    Ok(())
+}
+
+pub fn unbundle(
+    repo: gix::Repository,
+    path: &Path,
+    mut progress: impl NestedProgress + 'static,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    let bundle = gix::bundle::File::at(path)?;
+    repo.unbundle(&bundle, &mut progress, &gix::interrupt::IS_INTERRUPTED)?;
+    for r in &bundle.header.refs {
+        writeln!(out, "{} {}", r.id, r.name)?;
+    }
+    Ok(())
+}
+
+fn write_header(header: &Header, mut out: impl std::io::Write) -> std::io::Result<()> {
+    writeln!(out, "The bundle contains {} reference(s):", header.refs.len())?;
+    for r in &header.refs {
+        writeln!(out, "{} {}", r.id, r.name)?;
+    }
+    if header.prerequisites.is_empty() {
+        writeln!(out, "The bundle records a complete history.")
+    } else {
+        writeln!(out, "The bundle requires {} commit(s):", header.prerequisites.len())?;
+        for prerequisite in &header.prerequisites {
+            writeln!(out, "{} {}", prerequisite.id, prerequisite.comment)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+pub(crate) mod function {
+    use std::ffi::OsString;
+
+    use anyhow::{bail, Context};
+    use gix::repository::write_commit_graph::{Mode, Options};
+
+    use crate::OutputFormat;
+
+    /// Write a commit-graph with all commits reachable from `specs`, or from all references if `specs` is empty.
+    pub fn write(
+        repo: gix::Repository,
+        specs: Vec<OsString>,
+        split: bool,
+        changed_paths: bool,
+        mut out: impl std::io::Write,
+        format: OutputFormat,
+    ) -> anyhow::Result<()> {
+        if format != OutputFormat::Human {
+            bail!("Only human output is currently supported");
+        }
+        let mut tips = Vec::new();
+        if specs.is_empty() {
+            for reference in repo.references()?.all()? {
+                let mut reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+                let id = reference.peel_to_id_in_place()?;
+                if let Ok(commit) = id.object()?.peel_to_kind(gix::object::Kind::Commit) {
+                    tips.push(commit.id);
+                }
+            }
+        } else {
+            for spec in specs {
+                let spec = gix::path::os_str_into_bstr(&spec)?;
+                let id = repo
+                    .rev_parse_single(spec)
+                    .context("Only single revisions are supported")?
+                    .object()?
+                    .peel_to_kind(gix::object::Kind::Commit)
+                    .context("Need committish as starting point")?
+                    .id;
+                tips.push(id);
+            }
+        }
+
+        let mode = if split {
+            Mode::Split { size_multiple: 2 }
+        } else {
+            Mode::Single
+        };
+        let outcome = repo.write_commit_graph(tips, Options { mode, changed_paths })?;
+        match outcome.checksum {
+            Some(checksum) => writeln!(
+                out,
+                "Wrote {} commits into {checksum}, the commit-graph has {} layer(s)",
+                outcome.num_commits, outcome.num_layers
+            )?,
+            None => writeln!(out, "The commit-graph is up to date")?,
+        }
+        Ok(())
+    }
+}
@@ -124,7 +124,7 @@ fn resolve_revspec(
     let result = repo.rev_parse(revspec.as_bstr());
 
     match result {
-        Err(gix::revision::spec::parse::Error::FindReference(gix::refs::file::find::existing::Error::NotFound {
+        Err(gix::revision::spec::parse::Error::FindReference(gix::refs::store::find::existing::Error::NotFound {
             name,
         })) => {
             let root = repo.workdir().map(ToOwned::to_owned);
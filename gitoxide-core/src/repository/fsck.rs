@@ -1,36 +1,151 @@
-use anyhow::Context;
-use gix::{objs::Kind, ObjectId};
+use std::sync::atomic::AtomicBool;
 
-pub fn function(mut repo: gix::Repository, spec: Option<String>, mut out: impl std::io::Write) -> anyhow::Result<()> {
-    let spec = spec.unwrap_or("HEAD".into());
+use anyhow::{bail, Context as _};
+use gix_fsck::{
+    check::Problem,
+    message::{Id, Severities, Severity},
+};
 
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+pub struct Context {
+    /// Print all unreachable objects, instead of only the dangling ones.
+    pub unreachable: bool,
+    /// Print dangling objects.
+    pub dangling: bool,
+    /// Write dangling objects into `.git/lost-found`.
+    pub lost_found: bool,
+}
+
+pub fn function(
+    mut repo: gix::Repository,
+    specs: Vec<String>,
+    mut progress: impl gix::NestedProgress + 'static,
+    mut out: impl std::io::Write,
+    should_interrupt: &AtomicBool,
+    Context {
+        unreachable,
+        dangling,
+        lost_found,
+    }: Context,
+) -> anyhow::Result<()> {
     repo.object_cache_size_if_unset(4 * 1024 * 1024);
     // We expect to be finding a bunch of non-existent objects here - never refresh the ODB
     repo.objects.refresh_never();
 
-    let id = repo
-        .rev_parse_single(spec.as_str())
-        .context("Only single revisions are supported")?;
-    let commits: gix::revision::Walk<'_> = id
-        .object()?
-        .peel_to_kind(gix::object::Kind::Commit)
-        .context("Need committish as starting point")?
-        .id()
-        .ancestors()
-        .all()?;
-
-    let on_missing = |oid: &ObjectId, kind: Kind| {
-        writeln!(out, "{oid}: {kind}").expect("failed to write output");
+    let tips = if specs.is_empty() {
+        let mut tips = gix_fsck::tips::from_refs(&repo.refs)?;
+        if let Some(worktree) = repo.worktree() {
+            let index = worktree.index()?;
+            tips.extend(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| !entry.mode.is_submodule())
+                    .map(|entry| entry.id),
+            );
+            let mut trees: Vec<_> = index.tree().into_iter().collect();
+            while let Some(tree) = trees.pop() {
+                if tree.num_entries.is_some() {
+                    tips.push(tree.id);
+                }
+                trees.extend(&tree.children);
+            }
+        }
+        tips
+    } else {
+        specs
+            .iter()
+            .map(|spec| {
+                repo.rev_parse_single(spec.as_str())
+                    .map(gix::Id::detach)
+                    .with_context(|| format!("Only single revisions are supported, got '{spec}'"))
+            })
+            .collect::<anyhow::Result<_>>()?
     };
 
-    let mut check = gix_fsck::Connectivity::new(&repo.objects, on_missing);
-    // Walk all commits, checking each one for connectivity
-    for commit in commits {
-        let commit = commit?;
-        check.check_commit(&commit.id)?;
-        // Note that we leave parent-iteration to the commits iterator, as it will
-        // correctly handle shallow repositories which are expected to have the commits
-        // along the shallow boundary missing.
+    let options = gix_fsck::check::Options {
+        severities: severities(&repo)?,
+        skip_list: match repo.config_snapshot().trusted_path("fsck.skipList").transpose()? {
+            Some(path) => gix_fsck::skip_list::parse(
+                &std::fs::read(&path).with_context(|| format!("Could not read skip-list at '{}'", path.display()))?,
+            )?,
+            None => Default::default(),
+        },
+        shallow_commits: repo
+            .shallow_commits()?
+            .map(|commits| commits.iter().copied().collect())
+            .unwrap_or_default(),
+    };
+    let objects = repo.objects.iter()?.collect::<Result<Vec<_>, _>>()?;
+    let outcome = gix_fsck::check(objects, &repo.objects, tips, &mut progress, should_interrupt, &options)?;
+
+    for problem in &outcome.problems {
+        match problem {
+            Problem::Unreadable { id, source } => writeln!(out, "error: {id}: object is unreadable: {source}")?,
+            Problem::HashMismatch { id, kind, actual } => {
+                writeln!(out, "error: {kind} {id}: hash mismatch, content hashes to {actual}")?;
+            }
+            Problem::Message(msg) => writeln!(out, "{msg}")?,
+            Problem::Missing {
+                id,
+                kind,
+                referenced_by,
+            } => {
+                let kind = kind.map_or_else(|| "object".into(), |kind| kind.to_string());
+                match referenced_by {
+                    Some(referenced_by) => writeln!(out, "missing {kind} {id} referenced by {referenced_by}")?,
+                    None => writeln!(out, "missing {kind} {id}")?,
+                }
+            }
+            Problem::KindMismatch {
+                id,
+                expected,
+                actual,
+                referenced_by,
+            } => writeln!(
+                out,
+                "error: {id} is a {actual}, but {referenced_by} refers to it as {expected}"
+            )?,
+        }
+    }
+    let (label, objects) = if unreachable {
+        ("unreachable", &outcome.unreachable)
+    } else {
+        ("dangling", &outcome.dangling)
+    };
+    if unreachable || dangling {
+        for (id, kind) in objects {
+            writeln!(out, "{label} {kind} {id}")?;
+        }
+    }
+    if lost_found {
+        gix_fsck::lost_found::write(repo.git_dir(), &repo.objects, &outcome.dangling)?;
+    }
+
+    let num_errors = outcome.problems.iter().filter(|p| p.is_error()).count();
+    if num_errors != 0 {
+        bail!("Found {num_errors} error(s) in {} objects", outcome.num_objects);
     }
     Ok(())
 }
+
+/// Obtain the severity of each message from `fsck.<msg-id>`.
+fn severities(repo: &gix::Repository) -> anyhow::Result<Severities> {
+    let mut severities = Severities::default();
+    let config = repo.config_snapshot();
+    for section in config.plumbing().sections_by_name("fsck").into_iter().flatten() {
+        for name in section.value_names() {
+            let Some(id) = Id::from_name(name.as_ref().as_bytes()) else {
+                continue;
+            };
+            let value = section.value(name.as_ref()).unwrap_or_default();
+            let severity = Severity::from_name(value.as_ref())
+                .with_context(|| format!("Invalid severity '{value}' for 'fsck.{}'", id.as_str()))?;
+            if !severities.set(id, severity) {
+                bail!("Cannot demote '{}' to '{value}'", id.as_str());
+            }
+        }
+    }
+    Ok(severities)
+}
@@ -1,17 +1,28 @@
-use anyhow::bail;
-use gix::bstr::{BString, ByteSlice};
+use gix::bstr::{BStr, BString, ByteSlice};
 
-pub fn log(mut repo: gix::Repository, out: &mut dyn std::io::Write, path: Option<BString>) -> anyhow::Result<()> {
+pub fn log(
+    mut repo: gix::Repository,
+    out: &mut dyn std::io::Write,
+    path: Option<BString>,
+    show_notes: bool,
+) -> anyhow::Result<()> {
     repo.object_cache_size_if_unset(repo.compute_object_cache_size_for_tree_diffs(&**repo.index_or_empty()?));
 
+    let notes = show_notes
+        .then(|| repo.notes().map_err(anyhow::Error::from))
+        .transpose()?;
     if let Some(path) = path {
-        log_file(repo, out, path)
+        log_file(&repo, out, path, notes.as_ref())
     } else {
-        log_all(repo, out)
+        log_all(&repo, out, notes.as_ref())
     }
 }
 
-fn log_all(repo: gix::Repository, out: &mut dyn std::io::Write) -> Result<(), anyhow::Error> {
+fn log_all(
+    repo: &gix::Repository,
+    out: &mut dyn std::io::Write,
+    notes: Option<&gix::note::Platform<'_>>,
+) -> Result<(), anyhow::Error> {
     let head = repo.head()?.peel_to_commit_in_place()?;
     let topo = gix::traverse::commit::topo::Builder::from_iters(&repo.objects, [head.id], None::<Vec<gix::ObjectId>>)
         .build()?;
@@ -19,21 +30,63 @@ fn log_all(repo: gix::Repository, out: &mut dyn std::io::Write) -> Result<(), an
     for info in topo {
         let info = info?;
 
-        write_info(&repo, &mut *out, &info)?;
+        write_info(repo, &mut *out, &info, notes)?;
     }
 
     Ok(())
 }
 
-fn log_file(_repo: gix::Repository, _out: &mut dyn std::io::Write, _path: BString) -> anyhow::Result<()> {
-    bail!("File-based lookup isn't yet implemented in a way that is competitively fast");
+fn log_file(
+    repo: &gix::Repository,
+    out: &mut dyn std::io::Write,
+    path: BString,
+    notes: Option<&gix::note::Platform<'_>>,
+) -> anyhow::Result<()> {
+    let head = repo.head()?.peel_to_commit_in_place()?;
+    // Changed-path Bloom filters let us skip most commits that didn't touch `path` without diffing trees.
+    let commits = gix::traverse::commit::Simple::new([head.id], &repo.objects)
+        .sorting(gix::traverse::commit::simple::Sorting::ByCommitTime(
+            gix::traverse::commit::simple::CommitTimeOrder::NewestFirst,
+        ))?
+        .commit_graph(repo.commit_graph_if_enabled()?)
+        .changed_path(Some(path.clone()));
+
+    for info in commits {
+        let info = info?;
+        let entry_id = path_entry_id(repo, info.id, path.as_ref())?;
+        let mut parent_entry_ids = Vec::with_capacity(info.parent_ids.len());
+        for parent_id in info.parent_ids.iter() {
+            parent_entry_ids.push(path_entry_id(repo, *parent_id, path.as_ref())?);
+        }
+        // Like `git log`, only show commits that differ from all of their parents at `path`.
+        let is_changed = if parent_entry_ids.is_empty() {
+            entry_id.is_some()
+        } else {
+            parent_entry_ids
+                .iter()
+                .all(|parent_entry_id| *parent_entry_id != entry_id)
+        };
+        if is_changed {
+            write_info(repo, &mut *out, &info, notes)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn path_entry_id(repo: &gix::Repository, commit: gix::ObjectId, path: &BStr) -> anyhow::Result<Option<gix::ObjectId>> {
+    let tree = repo.find_commit(commit)?.tree()?;
+    Ok(tree
+        .lookup_entry(path.split(|b| *b == b'/'))?
+        .map(|entry| entry.object_id()))
 }
 
 fn write_info(
     repo: &gix::Repository,
     mut out: impl std::io::Write,
     info: &gix::traverse::commit::Info,
-) -> Result<(), std::io::Error> {
+    notes: Option<&gix::note::Platform<'_>>,
+) -> anyhow::Result<()> {
     let commit = repo.find_commit(info.id).unwrap();
 
     let message = commit.message_raw_sloppy();
@@ -45,6 +98,15 @@ fn write_info(
         info.id.to_hex_with_len(8),
         title.map_or_else(|| "<no message>".into(), BString::from)
     )?;
+    if let Some(note) = notes
+        .map(|notes| notes.find(info.id).map_err(anyhow::Error::from))
+        .transpose()?
+        .flatten()
+    {
+        for line in note.data.lines() {
+            writeln!(out, "    {}", line.as_bstr())?;
+        }
+    }
 
     Ok(())
 }
@@ -22,6 +22,7 @@ pub mod archive;
 pub mod cat;
 pub use cat::function::cat;
 pub mod blame;
+pub mod bundle;
 pub mod commit;
 pub mod config;
 mod credential;
@@ -44,7 +45,7 @@ pub use clone::function::clone;
 pub use fetch::function::fetch;
 
 pub mod commitgraph;
-mod fsck;
+pub mod fsck;
 pub use fsck::function as fsck;
 pub mod index;
 pub mod log;
@@ -54,9 +55,17 @@ pub use merge_base::merge_base;
 pub mod merge;
 pub mod odb;
 pub mod remote;
+#[cfg(feature = "blocking-client")]
+mod receive_pack;
+#[cfg(feature = "blocking-client")]
+pub use receive_pack::receive_pack;
 pub mod revision;
 pub mod status;
 pub mod submodule;
 pub mod tree;
+#[cfg(feature = "blocking-client")]
+mod upload_pack;
+#[cfg(feature = "blocking-client")]
+pub use upload_pack::upload_pack;
 pub mod verify;
 pub mod worktree;
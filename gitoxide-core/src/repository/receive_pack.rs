@@ -0,0 +1,21 @@
+use std::sync::atomic::AtomicBool;
+
+/// Accept a push into `repo` from a client connected through `input` and `out` like `git receive-pack` does.
+///
+/// `git_protocol` is the value of the `GIT_PROTOCOL` environment variable, and is used to determine the protocol version.
+pub fn receive_pack(
+    repo: gix::Repository,
+    git_protocol: Option<&str>,
+    input: impl std::io::Read,
+    out: impl std::io::Write,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    repo.receive_pack(
+        input,
+        out,
+        super::upload_pack::protocol_version(git_protocol),
+        &mut (),
+        should_interrupt,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::protocol::transport::Protocol;
+
+/// Serve `repo` to a client connected through `input` and `out` like `git upload-pack` does.
+///
+/// `git_protocol` is the value of the `GIT_PROTOCOL` environment variable, and is used to determine the protocol version.
+pub fn upload_pack(
+    repo: gix::Repository,
+    git_protocol: Option<&str>,
+    input: impl std::io::Read,
+    out: impl std::io::Write,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    repo.upload_pack(input, out, protocol_version(git_protocol), should_interrupt)?;
+    Ok(())
+}
+
+/// Parse the protocol version requested by the client from the value of the `GIT_PROTOCOL` environment variable.
+pub(crate) fn protocol_version(git_protocol: Option<&str>) -> Protocol {
+    git_protocol
+        .into_iter()
+        .flat_map(|value| value.split(':'))
+        .filter_map(|parameter| parameter.strip_prefix("version="))
+        .filter_map(|version| match version {
+            "1" => Some(Protocol::V1),
+            "2" => Some(Protocol::V2),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(Protocol::V0)
+}
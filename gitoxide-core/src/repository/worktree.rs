@@ -1,5 +1,9 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use gix::bstr::BStr;
+
 use crate::OutputFormat;
-use anyhow::bail;
 
 pub fn list(repo: gix::Repository, out: &mut dyn std::io::Write, format: OutputFormat) -> anyhow::Result<()> {
     if format != OutputFormat::Human {
@@ -17,12 +21,126 @@ pub fn list(repo: gix::Repository, out: &mut dyn std::io::Write, format: OutputF
         )?;
     }
     for proxy in repo.worktrees()? {
-        writeln!(
+        let base = proxy.base();
+        write!(
             out,
             "{base} [{name}]",
-            base = proxy.base()?.display(),
+            base = base
+                .as_deref()
+                .map_or("<unknown>".into(), |base| base.display().to_string()),
             name = proxy.id()
         )?;
+        if proxy.is_locked() {
+            write!(out, " locked")?;
+        } else if let Some(reason) = proxy.prunable() {
+            write!(out, " prunable ({reason:?})")?;
+        }
+        writeln!(out)?;
     }
     Ok(())
 }
+
+pub fn add(
+    repo: gix::Repository,
+    path: PathBuf,
+    commitish: Option<String>,
+    out: &mut dyn std::io::Write,
+    options: gix::repository::add_worktree::Options,
+) -> anyhow::Result<()> {
+    let worktree_repo = repo.add_worktree(
+        &path,
+        commitish.as_deref().map(Into::into),
+        &options,
+        &gix::interrupt::IS_INTERRUPTED,
+    )?;
+    writeln!(
+        out,
+        "Prepared worktree '{}' at {} ({})",
+        worktree_repo
+            .git_dir()
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default(),
+        path.display(),
+        worktree_repo
+            .head_name()?
+            .map_or_else(|| "<detached>".to_string(), |name| name.shorten().to_string())
+    )?;
+    Ok(())
+}
+
+pub fn remove(repo: gix::Repository, worktree: &str, force: bool) -> anyhow::Result<()> {
+    find(&repo, worktree)?.remove(force)?;
+    Ok(())
+}
+
+pub fn move_to(repo: gix::Repository, worktree: &str, new_path: &Path) -> anyhow::Result<()> {
+    find(&repo, worktree)?.move_to(new_path)?;
+    Ok(())
+}
+
+pub fn lock(repo: gix::Repository, worktree: &str, reason: Option<&str>) -> anyhow::Result<()> {
+    find(&repo, worktree)?.lock(reason.map(Into::into))?;
+    Ok(())
+}
+
+pub fn unlock(repo: gix::Repository, worktree: &str) -> anyhow::Result<()> {
+    find(&repo, worktree)?.unlock()?;
+    Ok(())
+}
+
+pub fn prune(
+    repo: gix::Repository,
+    dry_run: bool,
+    out: &mut dyn std::io::Write,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    for (id, reason) in repo.prune_worktrees(dry_run)? {
+        writeln!(
+            out,
+            "{prefix} worktrees/{id}: {reason:?}",
+            prefix = if dry_run { "Would remove" } else { "Removed" }
+        )?;
+    }
+    Ok(())
+}
+
+pub fn repair(
+    repo: gix::Repository,
+    paths: Vec<PathBuf>,
+    out: &mut dyn std::io::Write,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    for repair in repo.repair_worktrees(paths)? {
+        match repair {
+            gix::worktree::Repair::DotGitFile { path } => writeln!(out, "Repaired .git file at {}", path.display())?,
+            gix::worktree::Repair::GitDirFile { id } => writeln!(out, "Repaired gitdir file of worktrees/{id}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Find the worktree identified by `worktree`, which is either its id or the path to its working tree.
+fn find<'repo>(repo: &'repo gix::Repository, worktree: &str) -> anyhow::Result<gix::worktree::Proxy<'repo>> {
+    let path = gix::path::realpath(worktree).ok();
+    repo.worktrees()?
+        .into_iter()
+        .find(|proxy| {
+            proxy.id() == <&BStr>::from(worktree)
+                || path.as_deref().is_some_and(|path| {
+                    proxy
+                        .base()
+                        .ok()
+                        .and_then(|base| gix::path::realpath(base).ok())
+                        .as_deref()
+                        == Some(path)
+                })
+        })
+        .with_context(|| format!("'{worktree}' is not a linked worktree"))
+}
@@ -49,6 +49,64 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
     ))
 }
 
+/// Create an EWAH bitmap from `words` of uncompressed bits, where bit `n` is `words[n / 64] & (1 << (n % 64))`.
+///
+/// Trailing words without set bits are omitted, just like `git` does.
+pub fn encode(words: &[u64]) -> Vec {
+    let words = &words[..words.iter().rposition(|w| *w != 0).map_or(0, |pos| pos + 1)];
+    let mut out = Vec {
+        num_bits: 0,
+        bits: vec![0],
+        rlw: 0,
+    };
+    for word in words {
+        out.push_word(*word);
+    }
+    out
+}
+
+/// Encoding and writing.
+mod encode {
+    use super::{access::*, Vec};
+
+    impl Vec {
+        pub(super) fn push_word(&mut self, word: u64) {
+            self.num_bits += 64;
+            if word == 0 || word == u64::MAX {
+                let run_bit = word == u64::MAX;
+                let rlw = self.bits[self.rlw as usize];
+                let can_extend_run = rlw_literal_words(&rlw) == 0
+                    && (rlw_running_len(&rlw) == 0 || rlw_runbit_is_set(&rlw) == run_bit)
+                    && rlw_running_len(&rlw) < RLW_LARGEST_RUNNING_COUNT;
+                if !can_extend_run {
+                    self.rlw = self.bits.len() as u64;
+                    self.bits.push(0);
+                }
+                let rlw = &mut self.bits[self.rlw as usize];
+                let running_len = rlw_running_len(rlw) + 1;
+                *rlw = (*rlw & !(RLW_LARGEST_RUNNING_COUNT << 1)) | (running_len << 1) | u64::from(run_bit);
+            } else {
+                if rlw_literal_words(&self.bits[self.rlw as usize]) == RLW_LARGEST_LITERAL_COUNT {
+                    self.rlw = self.bits.len() as u64;
+                    self.bits.push(0);
+                }
+                self.bits[self.rlw as usize] += 1 << (1 + RLW_RUNNING_BITS);
+                self.bits.push(word);
+            }
+        }
+
+        /// Write the on-disk representation of this bitmap to `out`, as understood by [`decode()`](super::decode()).
+        pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(&(self.bits.len() as u32).to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(&(self.rlw as u32).to_be_bytes())
+        }
+    }
+}
+
 mod access {
     use super::Vec;
 
@@ -86,6 +144,20 @@ mod access {
             Some(())
         }
 
+        /// Decompress this bitmap into words of 64 bits each, where bit `n` is `words[n / 64] & (1 << (n % 64))`.
+        ///
+        /// Note that the last words may be omitted if they have no bits set.
+        pub fn to_words(&self) -> std::vec::Vec<u64> {
+            let mut out = std::vec::Vec::new();
+            let mut iter = self.bits.iter();
+            while let Some(word) = iter.next() {
+                let run = if rlw_runbit_is_set(word) { u64::MAX } else { 0 };
+                out.extend(std::iter::repeat(run).take(rlw_running_len(word) as usize));
+                out.extend(iter.by_ref().take(rlw_literal_words(word) as usize).copied());
+            }
+            out
+        }
+
         /// The amount of bits we are currently holding.
         pub fn num_bits(&self) -> usize {
             self.num_bits.try_into().expect("we are not on 16 bit systems")
@@ -98,27 +170,27 @@ mod access {
     }
 
     #[inline]
-    fn rlw_running_len(w: &u64) -> u64 {
+    pub(super) fn rlw_running_len(w: &u64) -> u64 {
         (w >> 1) & RLW_LARGEST_RUNNING_COUNT
     }
 
     #[inline]
-    fn rlw_literal_words(w: &u64) -> u64 {
+    pub(super) fn rlw_literal_words(w: &u64) -> u64 {
         w >> (1 + RLW_RUNNING_BITS)
     }
 
     #[inline]
-    fn rlw_runbit_is_set(w: &u64) -> bool {
+    pub(super) fn rlw_runbit_is_set(w: &u64) -> bool {
         w & 1 == 1
     }
 
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_RUNNING_BITS: u64 = 4 * 8;
+    pub(super) const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - RLW_RUNNING_BITS - 1)) - 1;
 }
 
 /// A growable collection of u64 that are seen as stream of individual bits.
-#[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Vec {
     num_bits: u32,
     bits: std::vec::Vec<u64>,
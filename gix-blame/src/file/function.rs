@@ -155,6 +155,18 @@ pub fn file(
             .take()
             .filter(|(id, _)| *id == suspect)
             .map(|(_, entry)| entry);
+
+        if path_is_unchanged_in_first_parent(cache.as_ref(), &suspect, file_path) {
+            // The changed-path Bloom filter tells us that `file_path` is the same in the first parent,
+            // so blame can be passed on without looking at a single tree.
+            stats.commits_skipped_by_bloom_filter += 1;
+            let (first_parent_id, first_parent_commit_time) = parent_ids[0];
+            previous_entry = entry.map(|entry| (first_parent_id, entry));
+            pass_blame_from_to(suspect, first_parent_id, &mut hunks_to_blame);
+            queue.insert(first_parent_commit_time, first_parent_id);
+            continue 'outer;
+        }
+
         if entry.is_none() {
             entry = find_path_entry_in_commit(
                 &odb,
@@ -667,6 +679,19 @@ fn find_path_entry_in_commit(
     Ok(res.map(|e| e.oid))
 }
 
+/// Return `true` if the changed-path Bloom filter of `commit` in `cache` proves that `file_path` wasn't changed
+/// compared to its first parent.
+fn path_is_unchanged_in_first_parent(
+    cache: Option<&gix_commitgraph::Graph>,
+    commit: &gix_hash::oid,
+    file_path: &BStr,
+) -> bool {
+    cache
+        .and_then(|cache| cache.commit_by_id(commit))
+        .and_then(|commit| commit.maybe_contains_path(file_path))
+        == Some(false)
+}
+
 type CommitTime = i64;
 
 fn commit_time(commit: gix_traverse::commit::Either<'_, '_>) -> Result<CommitTime, gix_object::decode::Error> {
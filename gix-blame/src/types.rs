@@ -44,6 +44,9 @@ pub struct Statistics {
     /// The amount of blobs there were compared to each other to learn what changed between commits.
     /// Note that in order to diff a blob, one needs to load both versions from the database.
     pub blobs_diffed: usize,
+    /// The amount of commits that were known not to change the blamed file thanks to the changed-path Bloom filters
+    /// of the commit-graph, which saved looking up the file in their trees.
+    pub commits_skipped_by_bloom_filter: usize,
 }
 
 impl Outcome {
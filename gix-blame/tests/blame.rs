@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use gix_hash::ObjectId;
 use gix_object::bstr;
@@ -185,27 +185,28 @@ macro_rules! mktest {
                 suspect,
             } = Fixture::new()?;
 
-            let lines_blamed = gix_blame::file(
-                &odb,
-                suspect,
-                None,
-                &mut resource_cache,
-                format!("{}.txt", $case).as_str().into(),
-                gix_blame::Options {
-                    diff_algorithm: gix_diff::blob::Algorithm::Histogram,
-                    range: None,
-                    since: None,
-                },
-            )?
-            .entries;
-
-            assert_eq!(lines_blamed.len(), $number_of_lines);
-
             let git_dir = fixture_path().join(".git");
             let baseline = Baseline::collect(git_dir.join(format!("{}.baseline", $case)))?;
-
             assert_eq!(baseline.len(), $number_of_lines);
-            assert_eq!(lines_blamed, baseline);
+
+            for use_commitgraph in [false, true] {
+                let lines_blamed = gix_blame::file(
+                    &odb,
+                    suspect,
+                    use_commitgraph.then(|| commit_graph(&git_dir)),
+                    &mut resource_cache,
+                    format!("{}.txt", $case).as_str().into(),
+                    gix_blame::Options {
+                        diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                        range: None,
+                        since: None,
+                    },
+                )?
+                .entries;
+
+                assert_eq!(lines_blamed.len(), $number_of_lines);
+                assert_eq!(lines_blamed, baseline, "commit-graph used: {use_commitgraph}");
+            }
             Ok(())
         }
     };
@@ -342,6 +343,45 @@ fn since() {
     assert_eq!(lines_blamed, baseline);
 }
 
+#[test]
+fn bloom_filters_avoid_looking_up_the_file_in_commits_that_did_not_change_it() -> gix_testtools::Result {
+    let Fixture {
+        odb,
+        mut resource_cache,
+        suspect,
+    } = Fixture::new()?;
+    let git_dir = fixture_path().join(".git");
+
+    let mut outcomes = Vec::new();
+    for cache in [None, Some(commit_graph(&git_dir))] {
+        outcomes.push(gix_blame::file(
+            &odb,
+            suspect,
+            cache,
+            &mut resource_cache,
+            "simple.txt".into(),
+            gix_blame::Options {
+                diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                range: None,
+                since: None,
+            },
+        )?);
+    }
+    let (without_graph, with_graph) = (&outcomes[0], &outcomes[1]);
+    assert_eq!(without_graph.statistics.commits_skipped_by_bloom_filter, 0);
+    assert_eq!(with_graph.entries, without_graph.entries);
+    assert!(
+        with_graph.statistics.commits_skipped_by_bloom_filter > 0,
+        "most commits in the fixture don't touch 'simple.txt'"
+    );
+    assert!(with_graph.statistics.trees_decoded < without_graph.statistics.trees_decoded);
+    Ok(())
+}
+
+fn commit_graph(git_dir: &Path) -> gix_commitgraph::Graph {
+    gix_commitgraph::at(git_dir.join("objects").join("info")).expect("fixture writes a commit-graph")
+}
+
 fn fixture_path() -> PathBuf {
     gix_testtools::scripted_fixture_read_only("make_blame_repo.sh").unwrap()
 }
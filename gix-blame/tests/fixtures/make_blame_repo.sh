@@ -252,3 +252,5 @@ git blame --porcelain empty-lines-histogram.txt > .git/empty-lines-histogram.bas
 git config --local diff.algorithm myers
 
 git blame --porcelain empty-lines-myers.txt > .git/empty-lines-myers.baseline
+
+git commit-graph write --no-progress --reachable --changed-paths
@@ -0,0 +1,123 @@
+use std::io::BufRead;
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{Header, Prerequisite, Ref, Version, V2_SIGNATURE, V3_SIGNATURE};
+
+/// The error returned by [`Header::from_read()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Expected a bundle signature, but got {signature:?}")]
+    UnknownSignature { signature: BString },
+    #[error("The bundle capability {capability:?} is unknown or not supported")]
+    UnknownCapability { capability: BString },
+    #[error("Capabilities are only supported in v3 bundles and must come right after the signature: {line:?}")]
+    UnexpectedCapability { line: BString },
+    #[error("The object format {name:?} is unknown")]
+    UnknownObjectFormat { name: BString },
+    #[error("Could not parse bundle header line {line:?}")]
+    InvalidLine { line: BString },
+    #[error("The object id in line {line:?} doesn't match the object format of the bundle")]
+    ObjectFormatMismatch { line: BString },
+    #[error("The bundle header ended before the empty line that separates it from the pack")]
+    UnexpectedEof,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Decoding
+impl Header {
+    /// Read a header from `read`, which is left positioned right at the start of the pack that follows it.
+    pub fn from_read(mut read: impl BufRead) -> Result<Self, Error> {
+        let mut line = Vec::new();
+        read.read_until(b'\n', &mut line)?;
+        let version = match line.as_slice() {
+            V2_SIGNATURE => Version::V2,
+            V3_SIGNATURE => Version::V3,
+            _ => {
+                return Err(Error::UnknownSignature {
+                    signature: line.trim_end_with(|c| c == '\n' || c == '\r').into(),
+                })
+            }
+        };
+
+        let mut header = Header {
+            version,
+            object_hash: gix_hash::Kind::Sha1,
+            filter: None,
+            prerequisites: Vec::new(),
+            refs: Vec::new(),
+        };
+        let mut may_have_capabilities = version == Version::V3;
+        loop {
+            line.clear();
+            if read.read_until(b'\n', &mut line)? == 0 {
+                return Err(Error::UnexpectedEof);
+            }
+            let Some(line) = line.strip_suffix(b"\n") else {
+                return Err(Error::UnexpectedEof);
+            };
+            if line.is_empty() {
+                break;
+            }
+            if let Some(capability) = line.strip_prefix(b"@") {
+                if !may_have_capabilities {
+                    return Err(Error::UnexpectedCapability { line: line.into() });
+                }
+                header.parse_capability(capability)?;
+                continue;
+            }
+            may_have_capabilities = false;
+
+            let (prerequisite, line_without_marker) = match line.strip_prefix(b"-") {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let (hex, rest) = match line_without_marker.find_byte(b' ') {
+                Some(pos) => (&line_without_marker[..pos], Some(&line_without_marker[pos + 1..])),
+                None => (line_without_marker, None),
+            };
+            let id = ObjectId::from_hex(hex).map_err(|_| Error::InvalidLine { line: line.into() })?;
+            if id.kind() != header.object_hash {
+                return Err(Error::ObjectFormatMismatch { line: line.into() });
+            }
+            if prerequisite {
+                header.prerequisites.push(Prerequisite {
+                    id,
+                    comment: rest.unwrap_or_default().into(),
+                });
+            } else {
+                let name = rest
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| Error::InvalidLine { line: line.into() })?;
+                header.refs.push(Ref { id, name: name.into() });
+            }
+        }
+        Ok(header)
+    }
+
+    fn parse_capability(&mut self, capability: &[u8]) -> Result<(), Error> {
+        let (key, value) = match capability.find_byte(b'=') {
+            Some(pos) => (&capability[..pos], Some(&capability[pos + 1..])),
+            None => (capability, None),
+        };
+        match (key, value) {
+            (b"object-format", Some(name)) => {
+                self.object_hash = match name {
+                    b"sha1" => gix_hash::Kind::Sha1,
+                    b"sha256" => gix_hash::Kind::Sha256,
+                    _ => return Err(Error::UnknownObjectFormat { name: name.into() }),
+                }
+            }
+            (b"filter", Some(filter)) => self.filter = Some(filter.into()),
+            _ => {
+                return Err(Error::UnknownCapability {
+                    capability: capability.into(),
+                })
+            }
+        }
+        Ok(())
+    }
+}
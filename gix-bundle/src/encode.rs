@@ -0,0 +1,50 @@
+use std::io::Write;
+
+use crate::{Header, Version, V2_SIGNATURE, V3_SIGNATURE};
+
+/// Encoding
+impl Header {
+    /// Write this header to `out`, including the empty line that separates it from the pack.
+    ///
+    /// Note that the object format is only written for [`Version::V3`], which is required to store
+    /// anything but SHA1 object ids, just like a [filter](Header::filter).
+    pub fn write_to(&self, mut out: impl Write) -> std::io::Result<()> {
+        match self.version {
+            Version::V2 => out.write_all(V2_SIGNATURE)?,
+            Version::V3 => {
+                out.write_all(V3_SIGNATURE)?;
+                let object_format = match self.object_hash {
+                    gix_hash::Kind::Sha1 => "sha1",
+                    gix_hash::Kind::Sha256 => "sha256",
+                };
+                writeln!(out, "@object-format={object_format}")?;
+                if let Some(filter) = &self.filter {
+                    out.write_all(b"@filter=")?;
+                    out.write_all(filter)?;
+                    out.write_all(b"\n")?;
+                }
+            }
+        }
+        for prerequisite in &self.prerequisites {
+            write!(out, "-{}", prerequisite.id)?;
+            if !prerequisite.comment.is_empty() {
+                out.write_all(b" ")?;
+                out.write_all(&prerequisite.comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for r in &self.refs {
+            write!(out, "{} ", r.id)?;
+            out.write_all(&r.name)?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")
+    }
+
+    /// Return this header as it would be written by [`write_to()`](Self::write_to()).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_to(&mut out).expect("writing to a vec never fails");
+        out
+    }
+}
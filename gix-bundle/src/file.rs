@@ -0,0 +1,64 @@
+use std::{
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use crate::{File, Header, V2_SIGNATURE, V3_SIGNATURE};
+
+/// The error returned by [`File::at()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open bundle at '{}'", path.display())]
+    Open { path: PathBuf, source: std::io::Error },
+    #[error("Could not read the header of bundle at '{}'", path.display())]
+    Decode {
+        path: PathBuf,
+        source: crate::decode::Error,
+    },
+}
+
+/// Initialization
+impl File {
+    /// Open the bundle at `path` and parse its header.
+    pub fn at(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let mut read = std::fs::File::open(&path)
+            .map(BufReader::new)
+            .map_err(|source| Error::Open {
+                path: path.clone(),
+                source,
+            })?;
+        let header = Header::from_read(&mut read).map_err(|source| Error::Decode {
+            path: path.clone(),
+            source,
+        })?;
+        let pack_offset = read.stream_position().map_err(|source| Error::Open {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(File {
+            header,
+            path,
+            pack_offset,
+        })
+    }
+}
+
+/// Access
+impl File {
+    /// Open the bundle file once more and return a reader positioned at the start of its pack.
+    pub fn pack(&self) -> std::io::Result<BufReader<std::fs::File>> {
+        let mut file = std::fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.pack_offset))?;
+        Ok(BufReader::new(file))
+    }
+}
+
+/// Return `true` if `path` is a file that starts with the signature of a bundle, without parsing it any further.
+pub fn is_bundle(path: impl AsRef<Path>) -> bool {
+    let mut signature = [0u8; 16];
+    std::fs::File::open(path.as_ref())
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .is_ok_and(|()| signature == V2_SIGNATURE || signature == V3_SIGNATURE)
+}
@@ -0,0 +1,81 @@
+//! Read and write [bundles](https://git-scm.com/docs/gitformat-bundle), files that contain a pack along with the references
+//! pointing into it, to transfer objects between repositories that can't connect to each other.
+//!
+//! A bundle starts with a [`Header`] that lists the commits a repository must have to make use of the pack,
+//! called prerequisites, and the references the bundle provides. The pack follows right after.
+//!
+//! * [`Header::from_read()`] and [`Header::write_to()`] decode and encode headers.
+//! * [`File::at()`] opens bundles on disk and provides access to their pack.
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The first line of a version 2 bundle.
+pub const V2_SIGNATURE: &[u8] = b"# v2 git bundle\n";
+/// The first line of a version 3 bundle.
+pub const V3_SIGNATURE: &[u8] = b"# v3 git bundle\n";
+
+/// The version of the bundle format.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Version {
+    /// The original format, which only supports SHA1.
+    #[default]
+    V2,
+    /// The format that supports capabilities, like the object format or a filter that was used to create the pack.
+    V3,
+}
+
+/// The header of a bundle, which is everything in front of the pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// The version of the bundle format.
+    pub version: Version,
+    /// The kind of hash used for all object ids in the bundle, which can only be `Sha1` in [`Version::V2`].
+    pub object_hash: gix_hash::Kind,
+    /// The filter that was used to omit objects from the pack, like `blob:none`, which is only supported in [`Version::V3`].
+    pub filter: Option<BString>,
+    /// The commits a repository must have to make use of the pack, as objects in the pack may refer to them.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references the bundle provides, along with the objects they point to.
+    pub refs: Vec<Ref>,
+}
+
+/// A commit that must exist in a repository to make use of the pack of a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prerequisite {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// A comment for human consumption, typically the subject of the commit, or empty if there is none.
+    pub comment: BString,
+}
+
+/// A reference provided by a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ref {
+    /// The object the reference points to.
+    pub id: ObjectId,
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+}
+
+/// A bundle on disk, with its header already parsed.
+#[derive(Debug, Clone)]
+pub struct File {
+    /// The parsed header.
+    pub header: Header,
+    /// The path to the bundle.
+    pub path: PathBuf,
+    /// The offset in bytes at which the pack starts.
+    pub pack_offset: u64,
+}
+
+///
+pub mod decode;
+mod encode;
+///
+pub mod file;
+pub use file::is_bundle;
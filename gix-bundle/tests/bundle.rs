@@ -0,0 +1,113 @@
+use gix_bundle::{decode, File, Header, Version};
+use gix_hash::ObjectId;
+
+pub use gix_testtools::Result;
+
+fn bundle(name: &str) -> Result<(Vec<u8>, File)> {
+    let path = gix_testtools::scripted_fixture_read_only("make_bundles.sh")?.join(name);
+    let data = std::fs::read(&path)?;
+    Ok((data, File::at(path)?))
+}
+
+#[test]
+fn round_trip() -> Result {
+    for name in ["full.bundle", "incremental.bundle", "v3.bundle"] {
+        let (data, bundle) = bundle(name)?;
+        let header_len = bundle.pack_offset as usize;
+        assert_eq!(
+            bundle.header.to_bytes(),
+            &data[..header_len],
+            "{name}: writing reproduces what git wrote"
+        );
+        assert_eq!(
+            &data[header_len..][..4],
+            b"PACK",
+            "the pack starts right after the header"
+        );
+
+        let mut pack = bundle.pack()?;
+        let mut signature = [0u8; 4];
+        std::io::Read::read_exact(&mut pack, &mut signature)?;
+        assert_eq!(&signature, b"PACK");
+        assert!(gix_bundle::is_bundle(&bundle.path));
+    }
+    Ok(())
+}
+
+#[test]
+fn refs_and_prerequisites() -> Result {
+    let (_data, full) = bundle("full.bundle")?;
+    assert_eq!(full.header.version, Version::V2);
+    assert_eq!(full.header.object_hash, gix_hash::Kind::Sha1);
+    assert!(full.header.prerequisites.is_empty(), "everything is contained");
+    assert_eq!(
+        full.header.refs.iter().map(|r| r.name.to_string()).collect::<Vec<_>>(),
+        ["refs/heads/feature", "refs/heads/main", "refs/tags/v1", "HEAD"]
+    );
+
+    let (_data, incremental) = bundle("incremental.bundle")?;
+    assert_eq!(incremental.header.prerequisites.len(), 1);
+    let prerequisite = &incremental.header.prerequisites[0];
+    assert_eq!(
+        prerequisite.comment, "second",
+        "the subject of the commit is used as comment"
+    );
+    assert_eq!(
+        prerequisite.id, full.header.refs[0].id,
+        "the excluded commit is required, it's where 'feature' points to"
+    );
+    assert_eq!(incremental.header.refs.len(), 1);
+
+    let (_data, v3) = bundle("v3.bundle")?;
+    assert_eq!(v3.header.version, Version::V3);
+    assert_eq!(v3.header.filter, None);
+    Ok(())
+}
+
+#[test]
+fn capabilities() -> Result {
+    let header = Header::from_read(&b"# v3 git bundle\n@object-format=sha256\n@filter=blob:none\n\n"[..])?;
+    assert_eq!(header.object_hash, gix_hash::Kind::Sha256);
+    assert_eq!(header.filter.as_ref().expect("set"), "blob:none");
+    assert_eq!(
+        Header::from_read(&header.to_bytes()[..])?,
+        header,
+        "capabilities round-trip"
+    );
+
+    let id = ObjectId::null(gix_hash::Kind::Sha1);
+    let err =
+        Header::from_read(format!("# v3 git bundle\n@object-format=sha256\n{id} HEAD\n\n").as_bytes()).unwrap_err();
+    assert!(matches!(err, decode::Error::ObjectFormatMismatch { .. }), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn invalid_headers() {
+    for (input, is_expected) in [
+        (
+            &b"# v4 git bundle\n\n"[..],
+            (|err| matches!(err, decode::Error::UnknownSignature { .. })) as fn(&decode::Error) -> bool,
+        ),
+        (b"# v3 git bundle\n@unknown\n\n", |err| {
+            matches!(err, decode::Error::UnknownCapability { .. })
+        }),
+        (b"# v2 git bundle\n@object-format=sha1\n\n", |err| {
+            matches!(err, decode::Error::UnexpectedCapability { .. })
+        }),
+        (b"# v3 git bundle\n@object-format=md5\n\n", |err| {
+            matches!(err, decode::Error::UnknownObjectFormat { .. })
+        }),
+        (b"# v2 git bundle\nnot-hex refs/heads/main\n\n", |err| {
+            matches!(err, decode::Error::InvalidLine { .. })
+        }),
+        (
+            b"# v2 git bundle\n0000000000000000000000000000000000000000\n\n",
+            |err| matches!(err, decode::Error::InvalidLine { .. }),
+        ),
+        (b"# v2 git bundle\n", |err| matches!(err, decode::Error::UnexpectedEof)),
+    ] {
+        let err = Header::from_read(input).unwrap_err();
+        assert!(is_expected(&err), "{input:?}: {err:?}");
+    }
+}
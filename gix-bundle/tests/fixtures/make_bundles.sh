@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  echo one > file
+  git add file
+  git commit -q -m "first"
+  git tag -a v1 -m "annotated"
+  echo two >> file
+  git commit -q -am "second"
+  git branch feature
+  echo three >> file
+  git commit -q -am "third"
+
+  git bundle create -q ../full.bundle --all
+  git bundle create -q ../incremental.bundle main~1..main feature
+  git bundle create -q --version=3 ../v3.bundle main
+)
@@ -26,6 +26,11 @@ impl Graph {
         r.file.id_at(r.pos)
     }
 
+    /// Return all files this graph consists of, with the files of base graphs first.
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
+
     /// Iterate over commits in unsorted order.
     pub fn iter_commits(&self) -> impl Iterator<Item = Commit<'_>> {
         self.files.iter().flat_map(File::iter_commits)
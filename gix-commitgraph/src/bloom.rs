@@ -0,0 +1,214 @@
+//! Changed-path Bloom filters, which allow to learn that a commit didn't change a path compared to its first parent
+//! without diffing any trees.
+//!
+//! A commit's filter contains all paths that changed compared to its first parent, or to the empty tree for root commits,
+//! along with all of their leading directories. Lookups may yield false positives, but never false negatives.
+use bstr::{BStr, ByteSlice};
+
+const SEED0: u32 = 0x293a_e76f;
+const SEED1: u32 = 0x7e64_6e2c;
+const BITS_PER_WORD: u64 = 8;
+
+/// The settings with which all Bloom filters of a commit-graph file are computed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// The version of the murmur3 hash used to compute keys.
+    ///
+    /// Version `1` is what `git` writes by default, but it treats bytes larger than `0x7f` as signed. Version `2` fixes this.
+    pub hash_version: u32,
+    /// The amount of bits to set for each path.
+    pub num_hashes: u32,
+    /// The amount of bits per path in each filter, which determines the size of the filter.
+    pub bits_per_entry: u32,
+    /// Commits with more changed paths than this get a filter that matches every path.
+    ///
+    /// It isn't stored in commit-graph files, and only matters when computing new filters.
+    pub max_changed_paths: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10,
+            max_changed_paths: 512,
+        }
+    }
+}
+
+/// The bit positions of a single path in Bloom filters with particular [`Settings`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Key {
+    hashes: Vec<u32>,
+}
+
+impl Key {
+    /// Compute the key for `path` to use with filters created with `settings`.
+    pub fn new(path: &BStr, settings: &Settings) -> Self {
+        let sign_extend = settings.hash_version == 1;
+        let hash0 = murmur3_seeded(SEED0, path, sign_extend);
+        let hash1 = murmur3_seeded(SEED1, path, sign_extend);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|i| hash0.wrapping_add(i.wrapping_mul(hash1)))
+                .collect(),
+        }
+    }
+}
+
+/// A Bloom filter of a commit, as stored in a commit-graph file.
+#[derive(Debug, Clone, Copy)]
+pub struct FilterRef<'a> {
+    data: &'a [u8],
+    settings: Settings,
+}
+
+impl<'a> FilterRef<'a> {
+    pub(crate) fn new(data: &'a [u8], settings: Settings) -> Self {
+        FilterRef { data, settings }
+    }
+
+    /// Return the raw bytes of the filter.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Return the settings with which the filter was computed.
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Return `true` if `key` may be contained in this filter, or `false` if it certainly isn't.
+    ///
+    /// Note that `key` must have been computed with [our settings](Self::settings()).
+    pub fn contains(&self, key: &Key) -> bool {
+        contains(self.data, key)
+    }
+
+    /// Return `true` if `path` may have been changed, or `false` if it certainly wasn't.
+    ///
+    /// All leading directories of `path` are checked as well, to reduce the chance of false positives.
+    pub fn maybe_contains_path(&self, path: &BStr) -> bool {
+        let path = path.trim_end_with(|c| c == '/');
+        if path.is_empty() {
+            return true;
+        }
+        std::iter::once(path.len())
+            .chain(path.rfind_iter("/"))
+            .all(|end| self.contains(&Key::new(path[..end].as_bstr(), &self.settings)))
+    }
+
+    /// Turn this instance into a filter that owns its data.
+    pub fn into_owned(self) -> Filter {
+        Filter {
+            data: self.data.to_owned(),
+        }
+    }
+}
+
+/// An owned Bloom filter, for use when [writing](crate::file::write) commit-graph files.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Filter {
+    data: Vec<u8>,
+}
+
+impl Filter {
+    /// Create a filter from all paths a commit changed compared to its first parent, with `settings`.
+    ///
+    /// `paths` are the paths of changed files, symlinks and submodules, but not the trees that contain them. Their leading
+    /// directories are added automatically.
+    /// If there are more than [`Settings::max_changed_paths`], the filter will match every path.
+    pub fn from_changed_paths<'a>(paths: impl IntoIterator<Item = &'a BStr>, settings: &Settings) -> Self {
+        let mut num_changes = 0;
+        let mut all_paths = std::collections::BTreeSet::new();
+        for path in paths {
+            num_changes += 1;
+            if num_changes > settings.max_changed_paths {
+                return Self::too_large();
+            }
+            all_paths.insert(path);
+            all_paths.extend(path.rfind_iter("/").map(|end| path[..end].as_bstr()));
+        }
+
+        let num_bits = all_paths.len() as u64 * u64::from(settings.bits_per_entry);
+        let len = ((num_bits + BITS_PER_WORD - 1) / BITS_PER_WORD).max(1);
+        let mut data = vec![0; len as usize];
+        for path in all_paths {
+            for hash in Key::new(path, settings).hashes {
+                let (byte, mask) = bit_position(hash, len);
+                data[byte] |= mask;
+            }
+        }
+        Filter { data }
+    }
+
+    /// Create a filter that matches every path, used for commits that changed too many paths.
+    pub fn too_large() -> Self {
+        Filter { data: vec![0xff] }
+    }
+
+    /// Create a filter from the raw bytes of a filter, as obtained by [`FilterRef::data()`].
+    pub fn from_data(data: Vec<u8>) -> Self {
+        Filter { data }
+    }
+
+    /// Return the raw bytes of the filter.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Return `true` if `key` may be contained in this filter, or `false` if it certainly isn't.
+    pub fn contains(&self, key: &Key) -> bool {
+        contains(&self.data, key)
+    }
+}
+
+fn contains(data: &[u8], key: &Key) -> bool {
+    if data.is_empty() {
+        return true;
+    }
+    key.hashes.iter().all(|hash| {
+        let (byte, mask) = bit_position(*hash, data.len() as u64);
+        data[byte] & mask != 0
+    })
+}
+
+fn bit_position(hash: u32, len: u64) -> (usize, u8) {
+    let bit = u64::from(hash) % (len * BITS_PER_WORD);
+    ((bit / BITS_PER_WORD) as usize, 1 << (bit % BITS_PER_WORD))
+}
+
+/// The 32 bit murmur3 hash as used by `git`, which interprets bytes as signed in its first version if `sign_extend` is set.
+fn murmur3_seeded(mut seed: u32, data: &[u8], sign_extend: bool) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let byte = |b: u8| if sign_extend { b as i8 as u32 } else { u32::from(b) };
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = byte(chunk[0]) | byte(chunk[1]) << 8 | byte(chunk[2]) << 16 | byte(chunk[3]) << 24;
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        seed ^= k;
+        seed = seed.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0;
+        for (shift, b) in tail.iter().enumerate().rev() {
+            k ^= byte(*b) << (shift * 8);
+        }
+        seed ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
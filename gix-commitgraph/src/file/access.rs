@@ -4,7 +4,8 @@ use std::{
 };
 
 use crate::{
-    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
+    bloom,
+    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, GENERATION_DATA_OVERFLOW_MASK},
     File,
 };
 
@@ -15,6 +16,11 @@ impl File {
         self.base_graph_count
     }
 
+    /// Return the settings of the changed-path Bloom filters stored in this file, or `None` if it has no usable filters.
+    pub fn bloom_filter_settings(&self) -> Option<bloom::Settings> {
+        self.bloom_filter_data.as_ref().map(|(_, settings)| *settings)
+    }
+
     /// Returns the commit data for the commit located at the given lexicographical position.
     ///
     /// `pos` must range from 0 to `self.num_commits()`.
@@ -26,6 +32,11 @@ impl File {
         Commit::new(self, pos)
     }
 
+    /// Return `true` if this file stores corrected commit dates, also known as generation numbers v2.
+    pub fn has_generation_data(&self) -> bool {
+        self.generation_data_offset.is_some()
+    }
+
     /// The kind of hash used in this File.
     ///
     /// Note that it is always conforming to the hash used in the owning repository.
@@ -127,6 +138,45 @@ impl File {
         &self.data[start..][..entry_size]
     }
 
+    /// Returns the offset of the corrected commit date relative to the commit timestamp of the commit at `pos`,
+    /// if this file has a Generation Data (GDA2) chunk.
+    pub(crate) fn corrected_commit_date_offset(&self, pos: file::Position) -> Option<u64> {
+        let pos: usize = pos
+            .0
+            .try_into()
+            .expect("an architecture able to hold 32 bits of integer");
+        let start = self.generation_data_offset? + pos * 4;
+        let offset = u32::from_be_bytes(self.data[start..][..4].try_into().unwrap());
+        if offset & GENERATION_DATA_OVERFLOW_MASK == 0 {
+            return Some(offset.into());
+        }
+        let overflow_index = (offset & !GENERATION_DATA_OVERFLOW_MASK) as usize;
+        self.data[self.generation_data_overflow_range.clone()?]
+            .chunks_exact(8)
+            .nth(overflow_index)
+            .map(|bytes| u64::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Returns the changed-path Bloom filter of the commit at `pos`, if this file has Bloom filter index (BIDX) and
+    /// data (BDAT) chunks and a valid filter was computed for the commit.
+    pub(crate) fn bloom_filter_at(&self, pos: file::Position) -> Option<bloom::FilterRef<'_>> {
+        let (data_range, settings) = self.bloom_filter_data.clone()?;
+        let pos: usize = pos
+            .0
+            .try_into()
+            .expect("an architecture able to hold 32 bits of integer");
+        let read_end = |pos: usize| {
+            let start = self.bloom_filter_index_offset? + pos * 4;
+            Some(u32::from_be_bytes(self.data[start..][..4].try_into().unwrap()) as usize)
+        };
+        let start = if pos == 0 { 0 } else { read_end(pos - 1)? };
+        let end = read_end(pos)?;
+        if start >= end || end > data_range.len() {
+            return None;
+        }
+        Some(bloom::FilterRef::new(&self.data[data_range][start..end], settings))
+    }
+
     /// Returns the byte slice for this file's entire Extra Edge List (EDGE) chunk.
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
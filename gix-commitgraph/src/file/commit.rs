@@ -50,9 +50,6 @@ impl<'a> Commit<'a> {
             root_tree_id: gix_hash::oid::from_bytes_unchecked(&bytes[..file.hash_len]),
             parent1: ParentEdge::from_raw(read_u32(&bytes[file.hash_len..][..4])),
             parent2: ParentEdge::from_raw(read_u32(&bytes[file.hash_len + 4..][..4])),
-            // TODO: Add support for corrected commit date offset overflow.
-            //      See https://github.com/git/git/commit/e8b63005c48696a26f976f5f9b0ccaf1983e439d and
-            //          https://github.com/git/git/commit/f90fca638e99a031dce8e3aca72427b2f9b4bb38 for more details and hints at a test.
             generation: read_u32(&bytes[file.hash_len + 8..][..4]) >> 2,
             commit_timestamp: u64::from_be_bytes(bytes[file.hash_len + 8..][..8].try_into().unwrap())
                 & 0x0003_ffff_ffff,
@@ -66,6 +63,27 @@ impl<'a> Commit<'a> {
         self.commit_timestamp
     }
 
+    /// Returns the corrected commit date of this commit, also known as generation number v2, if the owning file
+    /// stores generation data.
+    ///
+    /// It's the larger of the committer timestamp and one more than the largest corrected commit date of all parents.
+    pub fn corrected_commit_date(&self) -> Option<u64> {
+        Some(self.commit_timestamp + self.file.corrected_commit_date_offset(self.pos)?)
+    }
+
+    /// Returns the changed-path Bloom filter of this commit, if the owning file has one for it.
+    pub fn bloom_filter(&self) -> Option<crate::bloom::FilterRef<'a>> {
+        self.file.bloom_filter_at(self.pos)
+    }
+
+    /// Return `Some(false)` if this commit certainly didn't change `path` compared to its first parent, or `Some(true)` if it may
+    /// have, according to its changed-path [Bloom filter](Self::bloom_filter()).
+    ///
+    /// Return `None` if there is no Bloom filter for this commit, so it must be assumed that `path` may have changed.
+    pub fn maybe_contains_path(&self, path: &bstr::BStr) -> Option<bool> {
+        self.bloom_filter().map(|filter| filter.maybe_contains_path(path))
+    }
+
     /// Returns the generation number of this commit.
     ///
     /// Commits without parents have generation number 1. Commits with parents have a generation
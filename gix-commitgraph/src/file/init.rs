@@ -2,9 +2,12 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use crate::{
+    bloom,
     file::{
-        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID, HEADER_LEN,
+        OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
     File,
 };
@@ -184,6 +187,76 @@ impl File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        let generation_data_offset = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+
+                let expected_size = 4 * commit_data_count as usize;
+                if chunk_size != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: GENERATION_DATA_CHUNK_ID,
+                        msg: format!("expected chunk length {expected_size}, got {chunk_size}"),
+                    });
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+
+        let generation_data_overflow_range = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_OVERFLOW_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                if chunk_size % 8 != 0 {
+                    return Err(Error::InvalidChunkSize {
+                        id: GENERATION_DATA_OVERFLOW_CHUNK_ID,
+                        msg: format!("chunk size {chunk_size} is not a multiple of 8"),
+                    });
+                }
+                Ok(chunk_range)
+            })
+            .ok()
+            .transpose()?;
+
+        let bloom_filter_index_offset = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+
+                let expected_size = 4 * commit_data_count as usize;
+                if chunk_size != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_INDEX_CHUNK_ID,
+                        msg: format!("expected chunk length {expected_size}, got {chunk_size}"),
+                    });
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+
+        let bloom_filter_data = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                if chunk_size < BLOOM_FILTER_DATA_HEADER_LEN {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_DATA_CHUNK_ID,
+                        msg: format!("chunk size {chunk_size} is too small to hold the {BLOOM_FILTER_DATA_HEADER_LEN} bytes header"),
+                    });
+                }
+                let header = &data[chunk_range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                let read_u32 = |ofs: usize| u32::from_be_bytes(header[ofs..][..4].try_into().unwrap());
+                let settings = bloom::Settings {
+                    hash_version: read_u32(0),
+                    num_hashes: read_u32(4),
+                    bits_per_entry: read_u32(8),
+                    ..Default::default()
+                };
+                Ok((chunk_range.start + BLOOM_FILTER_DATA_HEADER_LEN..chunk_range.end, settings))
+            })
+            .ok()
+            .transpose()?
+            // Filters with unknown hash versions can't be queried, and filters are useless without their index.
+            .filter(|(_, settings)| matches!(settings.hash_version, 1 | 2) && bloom_filter_index_offset.is_some());
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Trailer(format!(
@@ -220,10 +293,14 @@ impl File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filter_index_offset,
+            bloom_filter_data,
             commit_data_offset,
             data,
             extra_edges_list_range,
             fan,
+            generation_data_offset,
+            generation_data_overflow_range,
             oid_lookup_offset,
             path,
             hash_len: object_hash.len_in_bytes(),
@@ -0,0 +1,445 @@
+//! Write commit-graph files, either standalone or as new layer on top of an existing [graph](crate::Graph).
+use std::io::Write;
+
+use gix_hash::ObjectId;
+
+use crate::{
+    bloom,
+    file::{
+        BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN, GENERATION_DATA_CHUNK_ID,
+        GENERATION_DATA_OVERFLOW_CHUNK_ID, GENERATION_DATA_OVERFLOW_MASK, HEADER_LEN, LAST_EXTENDED_EDGE_MASK,
+        NO_PARENT, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    File, Graph, Position, GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+mod error {
+    use crate::MAX_COMMITS;
+
+    /// The error returned by [`File::write_to()`][crate::File::write_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] gix_hash::io::Error),
+        #[error(
+            "Commit {id} has parent {parent_id} which is neither among the commits to write nor in the base graph"
+        )]
+        MissingParent {
+            id: gix_hash::ObjectId,
+            parent_id: gix_hash::ObjectId,
+        },
+        #[error("Commit-graph would contain {0} commits altogether, but only {MAX_COMMITS} commits are allowed")]
+        TooManyCommits(u64),
+        #[error("Commit-graph can be written on top of at most 255 base graphs, got {0}")]
+        TooManyBaseGraphs(usize),
+        #[error("Base graph uses hash {actual:?}, but the commit-graph is written with hash {expected:?}")]
+        HashMismatch {
+            actual: gix_hash::Kind,
+            expected: gix_hash::Kind,
+        },
+    }
+}
+pub use error::Error;
+
+/// The largest offset of a corrected commit date that fits into the generation data chunk without overflow.
+const GENERATION_DATA_OFFSET_MAX: u64 = (1 << 31) - 1;
+
+/// A commit to write into a commit-graph file, typically obtained by decoding a commit object.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Commit {
+    /// The id of the commit itself.
+    pub id: ObjectId,
+    /// The id of the commit's root tree.
+    pub tree: ObjectId,
+    /// The ids of all parents of the commit, in order.
+    pub parents: Vec<ObjectId>,
+    /// The committer timestamp in seconds since 1970-01-01 00:00:00 UTC.
+    ///
+    /// Note that like in `git`, only the lower 34 bits of it are stored in the file.
+    pub committer_timestamp: u64,
+    /// The changed-path Bloom filter of the commit, computed with the [settings](Options::changed_paths) of the file to write.
+    ///
+    /// If `None`, an empty filter is written which tells readers that the commit may have changed any path.
+    pub changed_paths: Option<bloom::Filter>,
+}
+
+/// Options for use in [`File::write_to()`].
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    /// The kind of hash to use in the file, which has to match the hash of the commits and of the base graph.
+    pub object_hash: gix_hash::Kind,
+    /// If `true`, corrected commit dates, also known as generation numbers v2, will be written like `git` does by default.
+    ///
+    /// Note that they are only written if all base graphs have them as well.
+    pub corrected_commit_dates: bool,
+    /// If set, changed-path Bloom filters of all commits will be written, which must have been computed with these settings.
+    pub changed_paths: Option<bloom::Settings>,
+}
+
+/// The result of [`File::write_to()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksum of the written file, which is also used in its file name if it's part of a split commit-graph chain.
+    pub checksum: ObjectId,
+    /// The number of commits stored in the file, not counting the commits in its base graphs.
+    pub num_commits: u32,
+    /// `true` if corrected commit dates were written.
+    pub has_generation_data: bool,
+    /// `true` if changed-path Bloom filters were written.
+    pub has_bloom_filters: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Generation {
+    level: u32,
+    corrected_commit_date: u64,
+}
+
+/// Writing
+impl File {
+    /// Write a commit-graph file with all `commits` to `out`, whose order doesn't matter, and return the checksum of the file.
+    ///
+    /// If `base` is set, the file becomes a new layer on top of it, and all `commits` that are already in `base` are skipped.
+    /// All parents of `commits` must either be contained in `commits` or in `base`.
+    /// Note that the caller is responsible for placing the file accordingly, and for updating the `commit-graph-chain` file
+    /// if `base` is set.
+    pub fn write_to(
+        commits: impl IntoIterator<Item = Commit>,
+        base: Option<&Graph>,
+        out: &mut dyn std::io::Write,
+        Options {
+            object_hash,
+            corrected_commit_dates,
+            changed_paths,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        let base_files = base.map_or(&[][..], Graph::files);
+        if base_files.len() > usize::from(u8::MAX) {
+            return Err(Error::TooManyBaseGraphs(base_files.len()));
+        }
+        if let Some(file) = base_files.iter().find(|f| f.object_hash() != object_hash) {
+            return Err(Error::HashMismatch {
+                actual: file.object_hash(),
+                expected: object_hash,
+            });
+        }
+
+        let mut commits: Vec<_> = commits
+            .into_iter()
+            .filter(|c| base.map_or(true, |base| base.lookup(c.id).is_none()))
+            .collect();
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+
+        let num_base_commits = base.map_or(0, Graph::num_commits);
+        let num_commits_total = u64::from(num_base_commits) + commits.len() as u64;
+        if num_commits_total > u64::from(MAX_COMMITS) {
+            return Err(Error::TooManyCommits(num_commits_total));
+        }
+        let num_commits = commits.len() as u32;
+
+        let parents = commits
+            .iter()
+            .map(|commit| {
+                commit
+                    .parents
+                    .iter()
+                    .map(|parent_id| {
+                        commits
+                            .binary_search_by(|c| c.id.cmp(parent_id))
+                            .ok()
+                            .map(|idx| Position(num_base_commits + idx as u32))
+                            .or_else(|| base.and_then(|base| base.lookup(parent_id)))
+                            .ok_or(Error::MissingParent {
+                                id: commit.id,
+                                parent_id: *parent_id,
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let generations = compute_generations(&commits, &parents, base, num_base_commits);
+
+        let has_generation_data = corrected_commit_dates && base_files.iter().all(File::has_generation_data);
+        let num_generation_data_overflows = if has_generation_data {
+            commits
+                .iter()
+                .zip(&generations)
+                .filter(|(c, g)| g.corrected_commit_date - c.committer_timestamp > GENERATION_DATA_OFFSET_MAX)
+                .count()
+        } else {
+            0
+        };
+        let num_extra_edges: usize = parents.iter().filter(|p| p.len() > 2).map(|p| p.len() - 1).sum();
+
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = gix_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (commits.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (commits.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        if has_generation_data {
+            cf.plan_chunk(GENERATION_DATA_CHUNK_ID, (commits.len() * 4) as u64);
+            if num_generation_data_overflows != 0 {
+                cf.plan_chunk(
+                    GENERATION_DATA_OVERFLOW_CHUNK_ID,
+                    (num_generation_data_overflows * 8) as u64,
+                );
+            }
+        }
+        if num_extra_edges != 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+        }
+        if changed_paths.is_some() {
+            cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, (commits.len() * 4) as u64);
+            let filters_len: usize = commits
+                .iter()
+                .filter_map(|c| c.changed_paths.as_ref())
+                .map(|f| f.data().len())
+                .sum();
+            cf.plan_chunk(
+                BLOOM_FILTER_DATA_CHUNK_ID,
+                (BLOOM_FILTER_DATA_HEADER_LEN + filters_len) as u64,
+            );
+        }
+        if !base_files.is_empty() {
+            cf.plan_chunk(BASE_GRAPHS_LIST_CHUNK_ID, (base_files.len() * hash_len) as u64);
+        }
+
+        let mut out = gix_hash::io::Write::new(out, object_hash);
+        let bytes_written = write_header(
+            &mut out,
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            base_files.len() as u8,
+            object_hash,
+        )
+        .map_err(gix_hash::io::Error::from)?;
+
+        let mut chunk_write = cf
+            .into_write(&mut out, bytes_written)
+            .map_err(gix_hash::io::Error::from)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => write_fanout(&commits, &mut chunk_write),
+                OID_LOOKUP_CHUNK_ID => commits.iter().try_for_each(|c| chunk_write.write_all(c.id.as_slice())),
+                COMMIT_DATA_CHUNK_ID => write_commit_data(&commits, &parents, &generations, &mut chunk_write),
+                GENERATION_DATA_CHUNK_ID => write_generation_data(&commits, &generations, &mut chunk_write),
+                GENERATION_DATA_OVERFLOW_CHUNK_ID => {
+                    write_generation_data_overflow(&commits, &generations, &mut chunk_write)
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => write_extra_edges(&parents, &mut chunk_write),
+                BLOOM_FILTER_INDEX_CHUNK_ID => write_bloom_filter_index(&commits, &mut chunk_write),
+                BLOOM_FILTER_DATA_CHUNK_ID => write_bloom_filter_data(
+                    &commits,
+                    changed_paths.expect("only planned with settings"),
+                    &mut chunk_write,
+                ),
+                BASE_GRAPHS_LIST_CHUNK_ID => base_files
+                    .iter()
+                    .try_for_each(|f| chunk_write.write_all(f.checksum().as_bytes())),
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+            .map_err(gix_hash::io::Error::from)?;
+        }
+
+        let checksum = out.hash.try_finalize().map_err(gix_hash::io::Error::from)?;
+        out.inner
+            .write_all(checksum.as_slice())
+            .map_err(gix_hash::io::Error::from)?;
+
+        Ok(Outcome {
+            checksum,
+            num_commits,
+            has_generation_data,
+            has_bloom_filters: changed_paths.is_some(),
+        })
+    }
+}
+
+fn write_header(
+    out: &mut dyn std::io::Write,
+    num_chunks: u8,
+    num_base_graphs: u8,
+    object_hash: gix_hash::Kind,
+) -> std::io::Result<usize> {
+    out.write_all(SIGNATURE)?;
+    out.write_all(&[1 /* version */, object_hash as u8, num_chunks, num_base_graphs])?;
+    Ok(HEADER_LEN)
+}
+
+/// Compute generation numbers for all `commits`, whose `parents` are given as graph positions, which refer to `base`
+/// if they are smaller than `num_base_commits`.
+fn compute_generations(
+    commits: &[Commit],
+    parents: &[Vec<Position>],
+    base: Option<&Graph>,
+    num_base_commits: u32,
+) -> Vec<Generation> {
+    let mut out: Vec<Option<Generation>> = vec![None; commits.len()];
+    let mut stack = Vec::new();
+    for start in 0..commits.len() {
+        if out[start].is_some() {
+            continue;
+        }
+        stack.push(start);
+        while let Some(&idx) = stack.last() {
+            if out[idx].is_some() {
+                stack.pop();
+                continue;
+            }
+            let mut max_parent_level = 0;
+            let mut max_parent_corrected_commit_date = None;
+            let mut has_unknown_parents = false;
+            for &pos in &parents[idx] {
+                let parent = match pos.0.checked_sub(num_base_commits) {
+                    Some(parent_idx) => match out[parent_idx as usize] {
+                        Some(generation) => generation,
+                        None => {
+                            stack.push(parent_idx as usize);
+                            has_unknown_parents = true;
+                            continue;
+                        }
+                    },
+                    None => {
+                        let commit = base.expect("positions below base commits are in base").commit_at(pos);
+                        Generation {
+                            level: commit.generation(),
+                            corrected_commit_date: commit
+                                .corrected_commit_date()
+                                .unwrap_or(commit.committer_timestamp()),
+                        }
+                    }
+                };
+                max_parent_level = max_parent_level.max(parent.level);
+                max_parent_corrected_commit_date =
+                    max_parent_corrected_commit_date.max(Some(parent.corrected_commit_date));
+            }
+            if has_unknown_parents {
+                continue;
+            }
+            stack.pop();
+
+            let committer_timestamp = commits[idx].committer_timestamp;
+            out[idx] = Some(Generation {
+                level: (max_parent_level + 1).min(GENERATION_NUMBER_MAX),
+                corrected_commit_date: max_parent_corrected_commit_date
+                    .map_or(committer_timestamp, |date| committer_timestamp.max(date + 1)),
+            });
+        }
+    }
+    out.into_iter()
+        .map(|generation| generation.expect("all generations computed"))
+        .collect()
+}
+
+fn write_fanout(commits: &[Commit], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    let mut iter = commits.iter().map(|c| c.id.first_byte()).peekable();
+    let mut count = 0u32;
+    for byte in 0..=u8::MAX {
+        while iter.next_if(|first_byte| *first_byte == byte).is_some() {
+            count += 1;
+        }
+        out.write_all(&count.to_be_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_commit_data(
+    commits: &[Commit],
+    parents: &[Vec<Position>],
+    generations: &[Generation],
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let mut num_extra_edges = 0u32;
+    for ((commit, parents), generation) in commits.iter().zip(parents).zip(generations) {
+        out.write_all(commit.tree.as_slice())?;
+        let parent1 = parents.first().map_or(NO_PARENT, |p| p.0);
+        let parent2 = match parents.len() {
+            0 | 1 => NO_PARENT,
+            2 => parents[1].0,
+            num_parents => {
+                let edge_index = num_extra_edges | EXTENDED_EDGES_MASK;
+                num_extra_edges += num_parents as u32 - 1;
+                edge_index
+            }
+        };
+        out.write_all(&parent1.to_be_bytes())?;
+        out.write_all(&parent2.to_be_bytes())?;
+        let timestamp = commit.committer_timestamp;
+        out.write_all(&((generation.level << 2) | ((timestamp >> 32) as u32 & 0x3)).to_be_bytes())?;
+        out.write_all(&(timestamp as u32).to_be_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_generation_data(
+    commits: &[Commit],
+    generations: &[Generation],
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let mut num_overflows = 0;
+    for (commit, generation) in commits.iter().zip(generations) {
+        let offset = generation.corrected_commit_date - commit.committer_timestamp;
+        let offset = if offset > GENERATION_DATA_OFFSET_MAX {
+            num_overflows += 1;
+            (num_overflows - 1) | GENERATION_DATA_OVERFLOW_MASK
+        } else {
+            offset as u32
+        };
+        out.write_all(&offset.to_be_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_generation_data_overflow(
+    commits: &[Commit],
+    generations: &[Generation],
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    for (commit, generation) in commits.iter().zip(generations) {
+        let offset = generation.corrected_commit_date - commit.committer_timestamp;
+        if offset > GENERATION_DATA_OFFSET_MAX {
+            out.write_all(&offset.to_be_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+fn write_extra_edges(parents: &[Vec<Position>], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    for parents in parents.iter().filter(|p| p.len() > 2) {
+        let (last, rest) = parents[1..].split_last().expect("more than two parents");
+        for pos in rest {
+            out.write_all(&pos.0.to_be_bytes())?;
+        }
+        out.write_all(&(last.0 | LAST_EXTENDED_EDGE_MASK).to_be_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_bloom_filter_index(commits: &[Commit], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    let mut end = 0u32;
+    for commit in commits {
+        end += commit.changed_paths.as_ref().map_or(0, |f| f.data().len() as u32);
+        out.write_all(&end.to_be_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_bloom_filter_data(
+    commits: &[Commit],
+    settings: bloom::Settings,
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    for value in [settings.hash_version, settings.num_hashes, settings.bits_per_entry] {
+        out.write_all(&value.to_be_bytes())?;
+    }
+    for filter in commits.iter().filter_map(|c| c.changed_paths.as_ref()) {
+        out.write_all(filter.data())?;
+    }
+    Ok(())
+}
@@ -102,6 +102,11 @@ impl Graph {
 
         Ok(Self { files })
     }
+
+    /// Turn this graph back into the files it consists of, with the files of base graphs first.
+    pub fn into_files(self) -> Vec<File> {
+        self.files
+    }
 }
 
 impl TryFrom<&Path> for Graph {
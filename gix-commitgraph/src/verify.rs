@@ -34,6 +34,12 @@ pub enum Error<E: std::error::Error + 'static> {
         err: file::verify::Error<std::convert::Infallible>,
         path: PathBuf,
     },
+    #[error("Commit {id}'s corrected commit date should be at least {expected} but is {actual}")]
+    CorrectedCommitDate {
+        actual: u64,
+        expected: u64,
+        id: gix_hash::ObjectId,
+    },
     #[error("Commit {id}'s generation should be {expected} but is {actual}")]
     Generation {
         actual: u32,
@@ -130,6 +136,7 @@ impl Graph {
             let file_stats = file
                 .traverse(|commit| {
                     let mut max_parent_generation = 0u32;
+                    let mut min_corrected_commit_date = commit.committer_timestamp();
                     for parent_pos in commit.iter_parents() {
                         let parent_pos = parent_pos.map_err(Error::Commit)?;
                         if parent_pos >= next_file_start_pos {
@@ -141,6 +148,9 @@ impl Graph {
                         }
                         let parent = self.commit_at(parent_pos);
                         max_parent_generation = max(max_parent_generation, parent.generation());
+                        if let Some(parent_date) = parent.corrected_commit_date() {
+                            min_corrected_commit_date = max(min_corrected_commit_date, parent_date + 1);
+                        }
                     }
 
                     // If the max parent generation is GENERATION_NUMBER_MAX, then this commit's
@@ -154,6 +164,16 @@ impl Graph {
                         });
                     }
 
+                    if let Some(actual) = commit.corrected_commit_date() {
+                        if actual < min_corrected_commit_date {
+                            return Err(Error::CorrectedCommitDate {
+                                actual,
+                                expected: min_corrected_commit_date,
+                                id: commit.id().into(),
+                            });
+                        }
+                    }
+
                     processor(commit).map_err(Error::Processor)?;
 
                     Ok(())
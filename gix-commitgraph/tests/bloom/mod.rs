@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_commitgraph::{bloom, file::write, File, Graph};
+use gix_testtools::scripted_fixture_read_only;
+
+/// Return the graph along with the paths changed by each commit, as determined by `git`.
+fn graph_and_changed_paths() -> gix_testtools::Result<(Graph, HashMap<gix_hash::ObjectId, Vec<BString>>)> {
+    let dir = scripted_fixture_read_only("changed_paths.sh")?;
+    let graph = Graph::from_info_dir(&dir.join(".git").join("objects").join("info"))?;
+    let mut changed_paths = HashMap::new();
+    for block in std::fs::read(dir.join("changed-paths"))?.split_str("\n\n") {
+        let mut lines = block.lines();
+        let Some(id) = lines.next() else { continue };
+        changed_paths.insert(
+            gix_hash::ObjectId::from_hex(id)?,
+            lines.map(|line| line.as_bstr().to_owned()).collect(),
+        );
+    }
+    Ok((graph, changed_paths))
+}
+
+#[test]
+fn filters_are_computed_like_git_does() -> gix_testtools::Result {
+    let (graph, changed_paths) = graph_and_changed_paths()?;
+    let file = &graph.files()[0];
+    let settings = file.bloom_filter_settings().expect("git wrote changed paths");
+    assert_eq!(settings, bloom::Settings::default(), "git writes the default settings");
+    assert_eq!(changed_paths.len(), 6);
+
+    for (id, paths) in &changed_paths {
+        let commit = graph.commit_by_id(id).expect("all commits are in the graph");
+        let expected = commit.bloom_filter().expect("all commits have filters").into_owned();
+        let actual = bloom::Filter::from_changed_paths(paths.iter().map(AsRef::<BStr>::as_ref), &settings);
+        assert_eq!(actual, expected, "{id}: {paths:?}");
+        if paths.len() > 512 {
+            assert_eq!(actual, bloom::Filter::too_large());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn maybe_contains_path() -> gix_testtools::Result {
+    let (graph, changed_paths) = graph_and_changed_paths()?;
+    for (id, paths) in &changed_paths {
+        let commit = graph.commit_by_id(id).expect("present");
+        for path in paths {
+            assert_eq!(
+                commit.maybe_contains_path(path.as_ref()),
+                Some(true),
+                "no false negatives"
+            );
+        }
+        if paths.len() <= 512 {
+            for path in ["a", "dir/sub/c", "dir/b", "ü", "many/1"] {
+                if !paths.iter().any(|p| p == path) {
+                    assert_eq!(
+                        commit.maybe_contains_path(path.into()),
+                        Some(false),
+                        "{id}: {path} wasn't changed"
+                    );
+                }
+            }
+        } else {
+            assert_eq!(
+                commit.maybe_contains_path("a".into()),
+                Some(true),
+                "commits with too many changes may contain everything"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn rewriting_git_written_filters_yields_the_same_bytes() -> gix_testtools::Result {
+    let (graph, _) = graph_and_changed_paths()?;
+    let file: &File = &graph.files()[0];
+    let commits = file.iter_commits().map(|c| write::Commit {
+        id: c.id().into(),
+        tree: c.root_tree_id().into(),
+        parents: c
+            .iter_parents()
+            .map(|pos| graph.id_at(pos.expect("valid")).into())
+            .collect(),
+        committer_timestamp: c.committer_timestamp(),
+        changed_paths: c.bloom_filter().map(gix_commitgraph::bloom::FilterRef::into_owned),
+    });
+    let mut buf = Vec::new();
+    let outcome = File::write_to(
+        commits,
+        None,
+        &mut buf,
+        write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            corrected_commit_dates: file.has_generation_data(),
+            changed_paths: file.bloom_filter_settings(),
+        },
+    )?;
+    assert!(outcome.has_bloom_filters);
+    assert_eq!(buf, std::fs::read(file.path())?);
+    Ok(())
+}
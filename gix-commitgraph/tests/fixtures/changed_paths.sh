@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config core.quotePath false
+
+mkdir -p dir/sub
+echo a > a
+echo b > dir/b
+echo c > dir/sub/c
+git add . && git commit -q -m initial
+
+echo a2 > a
+git commit -q -am "change a"
+
+echo c2 > dir/sub/c
+git commit -q -am "change dir/sub/c"
+
+echo u > ü
+git add ü && git commit -q -m "add non-ascii path"
+
+mkdir many
+for i in $(seq 513); do echo $i > many/$i; done
+git add many && git commit -q -m "change too many paths"
+
+git commit -q --allow-empty -m "change nothing"
+
+git commit-graph write --no-progress --reachable --changed-paths
+
+for commit in $(git rev-list HEAD); do
+  echo "$commit"
+  git diff-tree --no-commit-id --name-only -r --root "$commit"
+  echo
+done > changed-paths
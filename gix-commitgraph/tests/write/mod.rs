@@ -0,0 +1,194 @@
+use gix_commitgraph::{file::write, File, Graph};
+use gix_testtools::scripted_fixture_read_only;
+
+fn graph_at(script: &str) -> Graph {
+    let info_dir = scripted_fixture_read_only(script)
+        .expect("script succeeds all the time")
+        .join(".git")
+        .join("objects")
+        .join("info");
+    Graph::from_info_dir(&info_dir).expect("graph present and valid")
+}
+
+/// Extract the commits stored in `file`, which must be part of `graph`.
+fn commits_of(graph: &Graph, file: &File) -> Vec<write::Commit> {
+    file.iter_commits()
+        .map(|c| write::Commit {
+            id: c.id().into(),
+            tree: c.root_tree_id().into(),
+            parents: c
+                .iter_parents()
+                .map(|pos| graph.id_at(pos.expect("valid parent")).into())
+                .collect(),
+            committer_timestamp: c.committer_timestamp(),
+            changed_paths: c.bloom_filter().map(gix_commitgraph::bloom::FilterRef::into_owned),
+        })
+        .collect()
+}
+
+fn write_to_vec(
+    commits: Vec<write::Commit>,
+    base: Option<&Graph>,
+    corrected_commit_dates: bool,
+    changed_paths: Option<gix_commitgraph::bloom::Settings>,
+) -> (Vec<u8>, write::Outcome) {
+    let mut buf = Vec::new();
+    let outcome = File::write_to(
+        commits,
+        base,
+        &mut buf,
+        write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            corrected_commit_dates,
+            changed_paths,
+        },
+    )
+    .expect("valid input");
+    (buf, outcome)
+}
+
+#[test]
+fn rewriting_git_written_files_yields_the_same_bytes() {
+    for script in [
+        "single_commit.sh",
+        "single_parent.sh",
+        "two_parents.sh",
+        "octopus_merges.sh",
+    ] {
+        let graph = graph_at(script);
+        let file = &graph.files()[0];
+        assert!(
+            file.has_generation_data(),
+            "{script}: git writes generation data by default"
+        );
+        let mut commits = commits_of(&graph, file);
+        commits.reverse();
+
+        let (actual, outcome) = write_to_vec(commits, None, file.has_generation_data(), None);
+        assert_eq!(
+            actual,
+            std::fs::read(file.path()).expect("readable"),
+            "{script}: we write exactly what git writes"
+        );
+        assert_eq!(outcome.checksum, file.checksum(), "{script}");
+        assert_eq!(outcome.num_commits, file.num_commits(), "{script}");
+    }
+}
+
+#[test]
+fn new_layers_of_split_chains_are_written_like_git_does() {
+    let graph = graph_at("split_chain.sh");
+    assert_eq!(graph.files().len(), 3, "three layers, one commit each");
+    let top_commits = commits_of(&graph, &graph.files()[2]);
+    let mut files = graph.into_files();
+    let top = files.pop().expect("three files");
+    let base = Graph::new(files).expect("valid");
+    assert!(top.has_generation_data());
+
+    let (actual, outcome) = write_to_vec(top_commits, Some(&base), top.has_generation_data(), None);
+    assert_eq!(actual, std::fs::read(top.path()).expect("readable"));
+    assert_eq!(outcome.checksum, top.checksum());
+    assert_eq!(outcome.num_commits, 1);
+}
+
+#[test]
+fn commits_in_base_are_skipped() {
+    let graph = graph_at("split_chain.sh");
+    let all_commits: Vec<_> = graph.files().iter().flat_map(|file| commits_of(&graph, file)).collect();
+    let (_, outcome) = write_to_vec(all_commits, Some(&graph), true, None);
+    assert_eq!(outcome.num_commits, 0, "everything is already in the base graph");
+}
+
+#[test]
+fn missing_parents_are_an_error() {
+    let graph = graph_at("split_chain.sh");
+    let err = File::write_to(
+        commits_of(&graph, &graph.files()[2]),
+        None,
+        &mut Vec::new(),
+        write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            corrected_commit_dates: true,
+            changed_paths: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, write::Error::MissingParent { .. }));
+}
+
+#[test]
+fn generations_extra_edges_and_overflowing_corrected_commit_dates() -> gix_testtools::Result {
+    fn id(byte: u8) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from([byte; 20])
+    }
+    let future = 1 << 33;
+    let commits = vec![
+        write::Commit {
+            id: id(4),
+            tree: id(100),
+            parents: vec![id(1), id(2), id(3)],
+            committer_timestamp: 10,
+            changed_paths: None,
+        },
+        write::Commit {
+            id: id(1),
+            tree: id(100),
+            parents: vec![],
+            committer_timestamp: future,
+            changed_paths: None,
+        },
+        write::Commit {
+            id: id(2),
+            tree: id(100),
+            parents: vec![id(1)],
+            committer_timestamp: 5,
+            changed_paths: None,
+        },
+        write::Commit {
+            id: id(3),
+            tree: id(100),
+            parents: vec![],
+            committer_timestamp: 7,
+            changed_paths: None,
+        },
+    ];
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("commit-graph");
+    let (buf, outcome) = write_to_vec(commits, None, true, None);
+    assert!(outcome.has_generation_data);
+    std::fs::write(&path, buf)?;
+
+    let graph = Graph::from_file(&path)?;
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+
+    let octopus = graph.commit_by_id(id(4)).expect("present");
+    assert_eq!(
+        octopus
+            .iter_parents()
+            .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+            .collect::<Result<Vec<_>, _>>()?,
+        [id(1), id(2), id(3)],
+        "extra edges are stored in order"
+    );
+    assert_eq!(octopus.generation(), 3);
+    assert_eq!(octopus.corrected_commit_date(), Some(future + 2));
+    assert_eq!(octopus.committer_timestamp(), 10);
+
+    let child = graph.commit_by_id(id(2)).expect("present");
+    assert_eq!(child.generation(), 2);
+    assert_eq!(
+        child.corrected_commit_date(),
+        Some(future + 1),
+        "the offset to the commit time overflows and is stored separately"
+    );
+    assert_eq!(
+        graph.commit_by_id(id(1)).expect("present").corrected_commit_date(),
+        Some(future)
+    );
+    assert_eq!(
+        graph.commit_by_id(id(3)).expect("present").corrected_commit_date(),
+        Some(7)
+    );
+    Ok(())
+}
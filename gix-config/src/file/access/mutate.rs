@@ -304,13 +304,7 @@ impl<'event> File<'event> {
             .ok()?
             .rev()
             .find(|id| self.sections.get(id).is_some_and(|section| filter(section.meta())))?;
-        self.section_order.remove(
-            self.section_order
-                .iter()
-                .position(|v| *v == id)
-                .expect("known section id"),
-        );
-        self.sections.remove(&id)
+        self.remove_section_by_id(id)
     }
 
     /// Adds the provided `section` to the config, returning a mutable reference to it for immediate editing.
@@ -71,6 +71,10 @@ mod remove_section_filter {
             "it's OK to try again"
         );
         assert_eq!(file.remove_section_filter("core", Some("name".into()), |_| true), None);
+        assert!(
+            file.raw_value("core.a").is_err(),
+            "lookups don't see the removed sections anymore"
+        );
 
         file.section_mut_or_create_new("core", None).expect("creation succeeds");
         file.section_mut_or_create_new("core", Some("name".into()))
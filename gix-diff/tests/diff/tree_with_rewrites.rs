@@ -107,8 +107,8 @@ fn empty_to_new_tree_without_rename_tracking() -> crate::Result {
     {
         let (lhs, rhs, mut cache, odb) = repo_with_trees(None, "c1 - initial")?;
         let err = gix_diff::tree_with_rewrites(
-            TreeRefIter::from_bytes(&lhs),
-            TreeRefIter::from_bytes(&rhs),
+            TreeRefIter::from_bytes(&lhs, gix_hash::Kind::Sha1),
+            TreeRefIter::from_bytes(&rhs, gix_hash::Kind::Sha1),
             &mut cache,
             &mut Default::default(),
             &odb,
@@ -1840,8 +1840,8 @@ mod util {
         let (from, to, mut cache, odb) = repo_with_trees(lhs, rhs)?;
         let mut out = Vec::new();
         let rewrites_info = gix_diff::tree_with_rewrites(
-            TreeRefIter::from_bytes(&from),
-            TreeRefIter::from_bytes(&to),
+            TreeRefIter::from_bytes(&from, gix_hash::Kind::Sha1),
+            TreeRefIter::from_bytes(&to, gix_hash::Kind::Sha1),
             &mut cache,
             &mut Default::default(),
             &odb,
@@ -0,0 +1,74 @@
+use bstr::{BStr, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{Entry, FetchHead, NOT_FOR_MERGE};
+
+/// The error returned by [`FetchHead::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number} is missing the tab-separated merge marker or description: {line:?}")]
+    MissingField { line_number: usize, line: bstr::BString },
+    #[error("Line {line_number} doesn't start with a valid object id: {line:?}")]
+    InvalidId { line_number: usize, line: bstr::BString },
+    #[error("Line {line_number} has the unknown merge marker {marker:?}")]
+    InvalidMarker { line_number: usize, marker: bstr::BString },
+}
+
+/// Parsing
+impl FetchHead {
+    /// Parse all lines of a `FETCH_HEAD` file in `data`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let entries = data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(idx, line)| Entry::from_line(line.as_bstr(), idx + 1))
+            .collect::<Result<_, _>>()?;
+        Ok(FetchHead { entries })
+    }
+}
+
+/// Access
+impl FetchHead {
+    /// Return the id that `FETCH_HEAD` refers to when used as revision, which is the id of the first entry.
+    pub fn id(&self) -> Option<ObjectId> {
+        self.entries.first().map(|entry| entry.id)
+    }
+
+    /// Return all entries that are meant to be merged, like `git pull` would do it.
+    pub fn merge_heads(&self) -> impl Iterator<Item = &Entry> + '_ {
+        self.entries.iter().filter(|entry| !entry.not_for_merge)
+    }
+}
+
+impl Entry {
+    fn from_line(line: &BStr, line_number: usize) -> Result<Self, Error> {
+        let mut fields = line.splitn_str(3, "\t");
+        let (Some(hex), Some(marker), Some(description)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(Error::MissingField {
+                line_number,
+                line: line.into(),
+            });
+        };
+        let id = ObjectId::from_hex(hex).map_err(|_| Error::InvalidId {
+            line_number,
+            line: line.into(),
+        })?;
+        let not_for_merge = match marker {
+            b"" => false,
+            _ if marker == NOT_FOR_MERGE.as_bytes() => true,
+            _ => {
+                return Err(Error::InvalidMarker {
+                    line_number,
+                    marker: marker.into(),
+                })
+            }
+        };
+        Ok(Entry {
+            id,
+            not_for_merge,
+            description: description.into(),
+        })
+    }
+}
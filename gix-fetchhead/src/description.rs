@@ -0,0 +1,154 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_hash::ObjectId;
+
+use crate::{Description, Entry};
+
+/// The kind of ref that was fetched, as it's spelled out in the description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// The `HEAD` of the remote, which isn't mentioned in the description at all.
+    Head,
+    /// A ref in `refs/heads/`.
+    Branch,
+    /// A ref in `refs/tags/`.
+    Tag,
+    /// A ref in `refs/remotes/`.
+    RemoteTrackingBranch,
+    /// Any other ref or an object id, whose full name is used.
+    Other,
+}
+
+impl Kind {
+    /// The word used in descriptions for this kind, which is empty for [`Head`](Kind::Head) and [`Other`](Kind::Other).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Head | Kind::Other => "",
+            Kind::Branch => "branch",
+            Kind::Tag => "tag",
+            Kind::RemoteTrackingBranch => "remote-tracking branch",
+        }
+    }
+}
+
+const SEPARATOR: &str = "' of ";
+
+const PREFIXES: [(&str, Kind); 3] = [
+    ("refs/heads/", Kind::Branch),
+    ("refs/tags/", Kind::Tag),
+    ("refs/remotes/", Kind::RemoteTrackingBranch),
+];
+
+/// Lifecycle
+impl Entry {
+    /// Create a new entry for the remote ref or object id named `remote`, which was fetched from `url` and pointed to `id`.
+    ///
+    /// The description is composed just like `git` does it, and `url` is expected to be free of credentials.
+    pub fn new(id: ObjectId, not_for_merge: bool, remote: &BStr, url: &BStr) -> Self {
+        Entry {
+            id,
+            not_for_merge,
+            description: Description::from_remote(remote, url).to_bstring(),
+        }
+    }
+
+    /// Parse our description into its parts, or return `None` if it isn't in the format that `git` writes.
+    pub fn parse_description(&self) -> Option<Description<'_>> {
+        Description::from_bytes(self.description.as_ref())
+    }
+}
+
+impl<'a> Description<'a> {
+    /// Describe the ref or object id named `remote` as fetched from `url`.
+    pub fn from_remote(remote: &'a BStr, url: &'a BStr) -> Self {
+        let (kind, name) = if remote == "HEAD" {
+            (Kind::Head, "".into())
+        } else {
+            PREFIXES
+                .iter()
+                .find_map(|(prefix, kind)| {
+                    remote
+                        .strip_prefix(prefix.as_bytes())
+                        .map(|name| (*kind, name.as_bstr()))
+                })
+                .unwrap_or((Kind::Other, remote))
+        };
+        Description {
+            kind,
+            name,
+            url: shorten_url(url),
+        }
+    }
+
+    /// Parse `description` as written by `git`, or return `None` if it isn't in the expected format.
+    pub fn from_bytes(description: &'a BStr) -> Option<Self> {
+        let quoted = [Kind::Branch, Kind::Tag, Kind::RemoteTrackingBranch, Kind::Other]
+            .into_iter()
+            .find_map(|kind| {
+                let rest = match kind {
+                    Kind::Other => description.as_bytes(),
+                    _ => description.strip_prefix(kind.as_str().as_bytes())?.strip_prefix(b" ")?,
+                };
+                Some((kind, rest.strip_prefix(b"'")?))
+            });
+        let Some((kind, rest)) = quoted else {
+            return Some(Description {
+                kind: Kind::Head,
+                name: "".into(),
+                url: description,
+            });
+        };
+        let end = rest.find(SEPARATOR)?;
+        Some(Description {
+            kind,
+            name: rest[..end].as_bstr(),
+            url: rest[end + SEPARATOR.len()..].as_bstr(),
+        })
+    }
+
+    /// Return the full name of the fetched ref, which is the object id itself if it was fetched by id.
+    pub fn to_full_name(&self) -> BString {
+        match self.kind {
+            Kind::Head => "HEAD".into(),
+            Kind::Other => self.name.to_owned(),
+            kind => {
+                let prefix = PREFIXES
+                    .iter()
+                    .find_map(|(prefix, k)| (*k == kind).then_some(*prefix))
+                    .expect("all prefixed kinds are known");
+                let mut name = BString::from(prefix);
+                name.push_str(self.name);
+                name
+            }
+        }
+    }
+
+    /// Serialize this description like `git` does.
+    pub fn to_bstring(&self) -> BString {
+        let mut out = BString::default();
+        if self.kind != Kind::Head {
+            if self.kind != Kind::Other {
+                out.push_str(self.kind.as_str());
+                out.push_byte(b' ');
+            }
+            out.push_byte(b'\'');
+            out.push_str(self.name);
+            out.push_str(SEPARATOR);
+        }
+        out.push_str(self.url);
+        out
+    }
+}
+
+/// Remove trailing slashes and the `.git` suffix from `url`, like `git` does.
+fn shorten_url(url: &BStr) -> &BStr {
+    let mut url = url.as_bytes();
+    while let Some(stripped) = url.strip_suffix(b"/") {
+        url = stripped;
+    }
+    if url.len() > 5 {
+        if let Some(stripped) = url.strip_suffix(b".git") {
+            url = stripped;
+        }
+    }
+    url.as_bstr()
+}
@@ -1,2 +1,51 @@
-#![deny(rust_2018_idioms)]
+//! Read and write `.git/FETCH_HEAD`, which records the tips of the most recent fetch.
+//!
+//! Each line holds the id of a fetched ref, whether it is meant to be merged by `git pull`, and a description of where it came from,
+//! like `branch 'main' of https://example.com/repo`. When used as revision, `FETCH_HEAD` refers to the id of the first line.
+//!
+//! * [`FetchHead`] parses and writes entire files.
+//! * [`Entry::new()`] creates entries with the descriptions `git` would write.
+//! * [`Description`] parses descriptions back into their parts.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The marker that `git` writes for entries that shouldn't be merged.
+pub const NOT_FOR_MERGE: &str = "not-for-merge";
+
+/// The contents of a `FETCH_HEAD` file.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct FetchHead {
+    /// All entries in the order they are written, with the entries to merge first if written by `git`.
+    pub entries: Vec<Entry>,
+}
+
+/// A single line of a `FETCH_HEAD` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The id the fetched ref pointed to, which may be an annotated tag.
+    pub id: ObjectId,
+    /// If `true`, this entry was fetched as a side effect, and shouldn't be merged by `git pull`.
+    pub not_for_merge: bool,
+    /// A human-readable description of the fetched ref and the remote it was fetched from, like `branch 'main' of ../remote`.
+    pub description: BString,
+}
+
+/// The parts of an [entry description](Entry::description).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Description<'a> {
+    /// The kind of the fetched ref.
+    pub kind: description::Kind,
+    /// The name of the fetched ref, shortened if it is a branch, a tag or a remote-tracking branch, and empty for `HEAD`.
+    pub name: &'a bstr::BStr,
+    /// The URL of the remote, without credentials and without the `.git` suffix.
+    pub url: &'a bstr::BStr,
+}
+
+///
+pub mod decode;
+///
+pub mod description;
+mod write;
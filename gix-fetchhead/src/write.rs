@@ -0,0 +1,36 @@
+use std::io::Write;
+
+use crate::{Entry, FetchHead, NOT_FOR_MERGE};
+
+/// Serialization
+impl FetchHead {
+    /// Write all entries to `out` in the format `git` uses, one line per entry.
+    pub fn write_to(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        for entry in &self.entries {
+            entry.write_to(out)?;
+        }
+        Ok(())
+    }
+
+    /// Return all entries in the format `git` uses.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).expect("writing to memory can't fail");
+        buf
+    }
+}
+
+/// Serialization
+impl Entry {
+    /// Write this entry to `out` as a single line.
+    pub fn write_to(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        write!(
+            out,
+            "{}\t{}\t",
+            self.id,
+            if self.not_for_merge { NOT_FOR_MERGE } else { "" }
+        )?;
+        out.write_all(&self.description)?;
+        out.write_all(b"\n")
+    }
+}
@@ -0,0 +1,120 @@
+use gix_fetchhead::{description::Kind, Entry, FetchHead};
+
+pub use gix_testtools::Result;
+
+fn fixture(name: &str) -> Result<(Vec<u8>, FetchHead)> {
+    let data = std::fs::read(gix_testtools::scripted_fixture_read_only("make_fetch_heads.sh")?.join(name))?;
+    let fetch_head = FetchHead::from_bytes(&data)?;
+    Ok((data, fetch_head))
+}
+
+#[test]
+fn round_trip() -> Result {
+    for name in ["configured", "explicit", "head-by-url"] {
+        let (data, fetch_head) = fixture(name)?;
+        assert!(!fetch_head.entries.is_empty());
+        assert_eq!(fetch_head.to_bytes(), data, "{name}: writing reproduces what git wrote");
+    }
+    Ok(())
+}
+
+#[test]
+fn entries_are_described_like_git_does() -> Result {
+    for (name, remotes, url) in [
+        (
+            "configured",
+            &[
+                "refs/heads/feature",
+                "refs/heads/main",
+                "refs/tags/light",
+                "refs/tags/v1",
+            ][..],
+            "../remote/",
+        ),
+        (
+            "explicit",
+            &["refs/heads/main", "refs/heads/feature", "refs/pull/1/head"],
+            "../remote/",
+        ),
+        ("head-by-url", &["HEAD"], "../remote/.git"),
+    ] {
+        let (_, fetch_head) = fixture(name)?;
+        assert_eq!(fetch_head.entries.len(), remotes.len(), "{name}");
+        for (entry, remote) in fetch_head.entries.iter().zip(remotes) {
+            let expected = Entry::new(entry.id, entry.not_for_merge, (*remote).into(), url.into());
+            assert_eq!(entry, &expected, "{name}");
+
+            let description = entry.parse_description().expect("valid");
+            assert_eq!(
+                description.to_full_name(),
+                *remote,
+                "{name}: parsing reverses the description"
+            );
+            assert_eq!(description.to_bstring(), entry.description);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_heads_and_id() -> Result {
+    let (_, fetch_head) = fixture("configured")?;
+    assert_eq!(
+        fetch_head.merge_heads().count(),
+        0,
+        "nothing is merged if the first refspec is a pattern and the branch has no merge configuration"
+    );
+    assert_eq!(fetch_head.id(), Some(fetch_head.entries[0].id), "the first line counts");
+    assert_ne!(
+        fetch_head.entries[3].id, fetch_head.entries[2].id,
+        "annotated tags are recorded with the id of the tag object"
+    );
+
+    let (_, fetch_head) = fixture("explicit")?;
+    assert_eq!(
+        fetch_head
+            .merge_heads()
+            .map(|entry| entry.parse_description().expect("valid").kind)
+            .collect::<Vec<_>>(),
+        [Kind::Branch, Kind::Branch, Kind::Other],
+        "everything given on the command-line is merged"
+    );
+    Ok(())
+}
+
+#[test]
+fn descriptions() {
+    for (remote, url, expected) in [
+        ("HEAD", "https://example.com/repo.git/", "https://example.com/repo"),
+        ("refs/heads/main", "../a.git", "branch 'main' of ../a"),
+        ("refs/tags/v1.0", "a.git", "tag 'v1.0' of a.git"),
+        (
+            "refs/remotes/origin/main",
+            "/path/to/repo//",
+            "remote-tracking branch 'origin/main' of /path/to/repo",
+        ),
+        ("refs/notes/commits", "repo", "'refs/notes/commits' of repo"),
+    ] {
+        let entry = Entry::new(gix_hash::Kind::Sha1.null(), false, remote.into(), url.into());
+        assert_eq!(entry.description, expected);
+        assert_eq!(entry.parse_description().expect("valid").to_full_name(), remote);
+    }
+}
+
+#[test]
+fn invalid() {
+    for (input, expected) in [
+        (
+            "703335bff1e89f4ba17005a84e706b2d2a3490ba\tbranch 'main' of x\n",
+            "missing",
+        ),
+        ("70333\t\tbranch 'main' of x\n", "valid object id"),
+        (
+            "703335bff1e89f4ba17005a84e706b2d2a3490ba\tmerge\tbranch 'main' of x\n",
+            "unknown merge marker",
+        ),
+    ] {
+        let err = FetchHead::from_bytes(input.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains(expected), "{input:?}: {err}");
+    }
+}
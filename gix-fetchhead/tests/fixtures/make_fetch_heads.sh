@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git checkout -q -b main
+  git commit -q --allow-empty -m "init"
+  git branch feature
+  git tag -a v1 -m "annotated"
+  git tag light
+  git update-ref refs/pull/1/head HEAD
+)
+
+git init -q local
+(cd local
+  git remote add origin ../remote/
+
+  git fetch -q origin
+  cp .git/FETCH_HEAD ../configured
+
+  git fetch -q origin main feature refs/pull/1/head
+  cp .git/FETCH_HEAD ../explicit
+
+  git fetch -q ../remote/.git HEAD
+  cp .git/FETCH_HEAD ../head-by-url
+)
@@ -0,0 +1,27 @@
+use bstr::BStr;
+
+use crate::driver::apply::Context;
+
+/// The error returned by [`Builtin`] implementations.
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A filter driver that runs in-process, and which is used instead of the programs of the [`Driver`](crate::Driver) of the
+/// same name.
+///
+/// Built-in drivers are registered in [`Options::builtin_drivers`](crate::pipeline::Options::builtin_drivers) and are selected
+/// by the `filter` attribute just like external drivers, but they don't need to be configured to be used.
+pub trait Builtin: Send + Sync {
+    /// The name by which the `filter` attribute refers to this driver, like `lfs`.
+    fn name(&self) -> &BStr;
+
+    /// Turn the worktree content in `src` into content suitable for storage in `git` and write it to `out`, which is empty.
+    ///
+    /// `ctx` provides information about the entry being processed.
+    fn clean(&self, src: &mut dyn std::io::Read, ctx: Context<'_, '_>, out: &mut Vec<u8>) -> Result<(), Error>;
+
+    /// Turn `src` as stored in `git` into content for the working tree and write it to `out`, which is empty,
+    /// returning `true` if `out` was written, or `false` if `src` should be used as is.
+    ///
+    /// `ctx` provides information about the entry being processed.
+    fn smudge(&self, src: &[u8], ctx: Context<'_, '_>, out: &mut Vec<u8>) -> Result<bool, Error>;
+}
@@ -36,6 +36,11 @@ pub mod to_git {
         Driver(#[from] crate::driver::apply::Error),
         #[error(transparent)]
         Configuration(#[from] super::configuration::Error),
+        #[error("The built-in '{name}' filter failed to clean the file")]
+        Builtin {
+            name: bstr::BString,
+            source: crate::driver::builtin::Error,
+        },
         #[error("Copy of driver process output to memory failed")]
         ReadProcessOutputToBuffer(#[from] std::io::Error),
         #[error("Could not allocate buffer")]
@@ -59,6 +64,11 @@ pub mod to_worktree {
         Driver(#[from] crate::driver::apply::Error),
         #[error(transparent)]
         Configuration(#[from] super::configuration::Error),
+        #[error("The built-in '{name}' filter failed to smudge the file")]
+        Builtin {
+            name: bstr::BString,
+            source: crate::driver::builtin::Error,
+        },
     }
 }
 
@@ -81,6 +91,7 @@ impl Pipeline {
         let bstr_rela_path = gix_path::to_unix_separators_on_windows(gix_path::into_bstr(rela_path));
         let Configuration {
             driver,
+            builtin,
             digest,
             _attr_digest: _,
             encoding,
@@ -88,6 +99,7 @@ impl Pipeline {
         } = Configuration::at_path(
             bstr_rela_path.as_ref(),
             &self.options.drivers,
+            &self.options.builtin_drivers,
             &mut self.attrs,
             attributes,
             self.options.eol_config,
@@ -106,7 +118,20 @@ impl Pipeline {
             },
         )?;
 
-        if let Some(driver) = driver {
+        if let Some(builtin) = builtin {
+            self.bufs.clear();
+            builtin
+                .clean(
+                    &mut src,
+                    self.context.with_path(bstr_rela_path.as_ref()),
+                    &mut self.bufs.src,
+                )
+                .map_err(|source| to_git::Error::Builtin {
+                    name: builtin.name().to_owned(),
+                    source,
+                })?;
+            in_src_buffer = true;
+        } else if let Some(driver) = driver {
             if let Some(mut read) = self.processes.apply(
                 driver,
                 &mut src,
@@ -183,6 +208,7 @@ impl Pipeline {
     ) -> Result<ToWorktreeOutcome<'input, '_>, to_worktree::Error> {
         let Configuration {
             driver,
+            builtin,
             digest,
             _attr_digest: _,
             encoding,
@@ -190,6 +216,7 @@ impl Pipeline {
         } = Configuration::at_path(
             rela_path,
             &self.options.drivers,
+            &self.options.builtin_drivers,
             &mut self.attrs,
             attributes,
             self.options.eol_config,
@@ -212,7 +239,18 @@ impl Pipeline {
             bufs.swap();
         }
 
-        if let Some(driver) = driver {
+        if let Some(builtin) = builtin {
+            let (src, dest) = bufs.src_and_dest();
+            if builtin
+                .smudge(src, self.context.with_path(rela_path), dest)
+                .map_err(|source| to_worktree::Error::Builtin {
+                    name: builtin.name().to_owned(),
+                    source,
+                })?
+            {
+                bufs.swap();
+            }
+        } else if let Some(driver) = driver {
             let (mut src, _dest) = bufs.src_and_dest();
             if let Some(maybe_delayed) = self.processes.apply_delayed(
                 driver,
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use bstr::BString;
 
 use crate::{driver, eol, Driver, Pipeline};
@@ -21,6 +23,8 @@ pub enum CrlfRoundTripCheck {
 pub struct Options {
     /// Available (external) driver programs to invoke if attributes for path configure them.
     pub drivers: Vec<Driver>,
+    /// Drivers that run in-process, which take precedence over [external drivers](Self::drivers) of the same name.
+    pub builtin_drivers: Vec<Arc<dyn driver::Builtin>>,
     /// Global options to configure end-of-line conversions, to worktree or to git.
     pub eol_config: eol::Configuration,
     /// How to perform round-trip checks during end-of-line conversions to git.
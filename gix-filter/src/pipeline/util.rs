@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::{path::Path, sync::Arc};
 
 use bstr::BStr;
 use gix_attributes::StateRef;
@@ -13,6 +13,8 @@ use crate::{
 
 pub(crate) struct Configuration<'a> {
     pub(crate) driver: Option<&'a Driver>,
+    /// A built-in driver to use instead of `driver`.
+    pub(crate) builtin: Option<&'a dyn driver::Builtin>,
     /// What attributes say about CRLF handling.
     pub(crate) _attr_digest: Option<eol::AttributesDigest>,
     /// The final digest that includes configuration values
@@ -26,6 +28,7 @@ impl<'driver> Configuration<'driver> {
     pub(crate) fn at_path(
         rela_path: &BStr,
         drivers: &'driver [Driver],
+        builtins: &'driver [Arc<dyn driver::Builtin>],
         attrs: &mut gix_attributes::search::Outcome,
         attributes: &mut dyn FnMut(&BStr, &mut gix_attributes::search::Outcome),
         config: eol::Configuration,
@@ -38,6 +41,17 @@ impl<'driver> Configuration<'driver> {
             }
         }
 
+        fn extract_builtin<'a>(
+            builtins: &'a [Arc<dyn driver::Builtin>],
+            attr: &gix_attributes::search::Match<'_>,
+        ) -> Option<&'a dyn driver::Builtin> {
+            if let StateRef::Value(name) = attr.assignment.state {
+                builtins.iter().find(|b| b.name() == name.as_bstr()).map(AsRef::as_ref)
+            } else {
+                None
+            }
+        }
+
         fn extract_encoding(
             attr: &gix_attributes::search::Match<'_>,
         ) -> Result<Option<&'static encoding_rs::Encoding>, configuration::Error> {
@@ -97,6 +111,7 @@ impl<'driver> Configuration<'driver> {
         let attrs: SmallVec<[_; crate::pipeline::ATTRS.len()]> = attrs.iter_selected().collect();
         let apply_ident_filter = attrs[1].assignment.state.is_set();
         let driver = extract_driver(drivers, &attrs[2]);
+        let builtin = extract_builtin(builtins, &attrs[2]);
         let encoding = extract_encoding(&attrs[5])?;
 
         let mut digest = extract_crlf(&attrs[4]);
@@ -128,6 +143,7 @@ impl<'driver> Configuration<'driver> {
 
         Ok(Configuration {
             driver,
+            builtin,
             _attr_digest: attr_digest,
             digest: digest.expect("always set by now"),
             encoding,
@@ -1,6 +1,10 @@
-use bstr::ByteSlice;
+use std::{io::Read, path::Path, sync::Arc};
+
+use bstr::{BStr, ByteSlice};
 use gix_attributes::glob::pattern::Case;
-use gix_filter::eol;
+use gix_filter::{driver, eol, pipeline::CrlfRoundTripCheck};
+
+use crate::driver::apply::driver_with_process;
 
 mod convert_to_git;
 mod convert_to_worktree;
@@ -22,6 +26,89 @@ fn default() -> crate::Result {
     Ok(())
 }
 
+/// A built-in driver that removes or adds a leading arrow.
+struct Arrow;
+
+impl driver::Builtin for Arrow {
+    fn name(&self) -> &BStr {
+        "arrow".into()
+    }
+
+    fn clean(
+        &self,
+        src: &mut dyn Read,
+        _ctx: driver::apply::Context<'_, '_>,
+        out: &mut Vec<u8>,
+    ) -> Result<(), driver::builtin::Error> {
+        src.read_to_end(out)?;
+        if out.starts_with("➡".as_bytes()) {
+            out.drain(.."➡".len());
+        }
+        Ok(())
+    }
+
+    fn smudge(
+        &self,
+        src: &[u8],
+        ctx: driver::apply::Context<'_, '_>,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, driver::builtin::Error> {
+        if ctx.rela_path == "unchanged.txt" {
+            return Ok(false);
+        }
+        out.extend_from_slice("➡".as_bytes());
+        out.extend_from_slice(src);
+        Ok(true)
+    }
+}
+
+#[test]
+fn builtin_drivers_take_precedence_over_programs() -> crate::Result {
+    let (mut cache, mut pipe) = pipeline("driver-only", || {
+        (
+            vec![driver_with_process()],
+            Vec::new(),
+            CrlfRoundTripCheck::Skip,
+            Default::default(),
+        )
+    })?;
+    pipe.options_mut().builtin_drivers.push(Arc::new(Arrow));
+    let mut attributes = |path: &BStr, attrs: &mut gix_attributes::search::Outcome| {
+        cache
+            .at_entry(path, None, &gix_object::find::Never)
+            .expect("cannot fail")
+            .matching_attributes(attrs);
+    };
+
+    let out = pipe.convert_to_worktree(
+        b"a",
+        "any.txt".into(),
+        &mut attributes,
+        gix_filter::driver::apply::Delay::Forbid,
+    )?;
+    assert_eq!(
+        out.as_bytes().expect("built-ins produce buffers").as_bstr(),
+        "➡a",
+        "the built-in was used instead of the process"
+    );
+    drop(out);
+
+    let out = pipe.convert_to_worktree(
+        b"a",
+        "unchanged.txt".into(),
+        &mut attributes,
+        gix_filter::driver::apply::Delay::Forbid,
+    )?;
+    assert!(!out.is_changed(), "built-ins may leave the input untouched");
+    drop(out);
+
+    let out = pipe.convert_to_git("➡a".as_bytes(), Path::new("any.txt"), &mut attributes, &mut |_| {
+        Ok(None)
+    })?;
+    assert_eq!(out.as_bytes().expect("built-ins produce buffers").as_bstr(), "a");
+    Ok(())
+}
+
 fn attribute_cache(name: &str) -> gix_testtools::Result<gix_worktree::Stack> {
     let dir = gix_testtools::scripted_fixture_read_only("pipeline_repos.sh")?.join(name);
     Ok(gix_worktree::Stack::new(
@@ -61,6 +148,7 @@ fn pipeline(
         Default::default(),
         gix_filter::pipeline::Options {
             drivers,
+            builtin_drivers: Vec::new(),
             eol_config,
             encodings_with_roundtrip_check,
             crlf_roundtrip_check,
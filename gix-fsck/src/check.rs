@@ -0,0 +1,360 @@
+//! A full check of all objects in an object database, similar to `git fsck --full`.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_hashtable::{HashMap, HashSet};
+use gix_object::{tree::EntryKind, Kind};
+
+use crate::{message, validate};
+
+mod error {
+    /// The error returned by [`check()`](crate::check()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Interrupted")]
+        Interrupted,
+    }
+}
+pub use error::Error;
+
+/// The progress ids used in [`check()`](crate::check()).
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// The amount of objects that were read, hashed and validated.
+    CheckedObjects,
+    /// The amount of objects found to be reachable from the starting points.
+    TraversedObjects,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::CheckedObjects => *b"FKCO",
+            ProgressId::TraversedObjects => *b"FKTO",
+        }
+    }
+}
+
+/// Options for use in [`check()`](crate::check()).
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// The severity of each message produced when validating objects.
+    pub severities: message::Severities,
+    /// Objects that are known to be broken, and which thus aren't validated.
+    ///
+    /// They are still hashed, and they still participate in the connectivity check.
+    pub skip_list: HashSet<ObjectId>,
+    /// The commits at the boundary of a shallow repository, whose parents are expected to be missing.
+    pub shallow_commits: HashSet<ObjectId>,
+}
+
+/// A problem found by [`check()`](crate::check()).
+#[derive(Debug)]
+pub enum Problem {
+    /// The object is listed in the database, but couldn't be read.
+    Unreadable {
+        /// The id of the object.
+        id: ObjectId,
+        /// The reason for the failure.
+        source: gix_object::find::Error,
+    },
+    /// The object data doesn't hash to the id it is stored under.
+    HashMismatch {
+        /// The id the object is stored under.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: Kind,
+        /// The id the object actually has.
+        actual: ObjectId,
+    },
+    /// The object is malformed.
+    Message(message::Message),
+    /// An object is referenced, but doesn't exist.
+    Missing {
+        /// The id of the missing object.
+        id: ObjectId,
+        /// The kind the object is expected to have, or `None` if the object is a starting point.
+        kind: Option<Kind>,
+        /// The object referencing the missing object, or `None` if the object is a starting point or
+        /// couldn't be found despite being listed.
+        referenced_by: Option<ObjectId>,
+    },
+    /// An object is referenced as an object of another kind than it actually is.
+    KindMismatch {
+        /// The id of the referenced object.
+        id: ObjectId,
+        /// The kind the referencing object expects.
+        expected: Kind,
+        /// The actual kind of the object.
+        actual: Kind,
+        /// The object holding the reference.
+        referenced_by: ObjectId,
+    },
+}
+
+impl Problem {
+    /// Return `true` if this problem makes the object database invalid, which is everything but messages
+    /// that are configured to be [warnings](message::Severity::Warn).
+    pub fn is_error(&self) -> bool {
+        match self {
+            Problem::Message(msg) => msg.severity == message::Severity::Error,
+            _ => true,
+        }
+    }
+}
+
+/// The result of [`check()`](crate::check()).
+#[derive(Debug)]
+pub struct Outcome {
+    /// The amount of objects that were checked.
+    pub num_objects: usize,
+    /// All problems, in the order they were found.
+    pub problems: Vec<Problem>,
+    /// All objects that can't be reached from any of the starting points, sorted by id.
+    pub unreachable: Vec<(ObjectId, Kind)>,
+    /// All unreachable objects that also aren't referenced by any other object, sorted by id.
+    ///
+    /// These are the tips of unreachable history, and they are a subset of `unreachable`.
+    pub dangling: Vec<(ObjectId, Kind)>,
+}
+
+impl Outcome {
+    /// Return `true` if there is at least one [error](Problem::is_error()).
+    pub fn has_errors(&self) -> bool {
+        self.problems.iter().any(Problem::is_error)
+    }
+}
+
+pub(super) mod function {
+    use super::*;
+
+    #[derive(Default)]
+    struct State {
+        /// The kind of the object, or `None` if it couldn't be read or is corrupt.
+        kind: Option<Kind>,
+        /// `true` if any other object references this one.
+        referenced: bool,
+        /// `true` if the object can be reached from any of the starting points.
+        reachable: bool,
+    }
+
+    /// Check all `objects`, which are expected to be everything contained in `db`, and find those that can't be reached
+    /// when traversing the graph from `tips`, like references and their logs.
+    ///
+    /// ### Algorithm
+    ///
+    /// First, each object is read from `db`, re-hashed to assure its content matches its id and then validated
+    /// according to its kind, producing [messages](message::Message) configured in `options`.
+    /// References to objects that aren't contained in `objects` are reported as missing.
+    /// Then all objects are traversed starting from `tips` to find those that are unreachable, and
+    /// references to objects of an unexpected kind are reported.
+    ///
+    /// `progress` receives information about both phases, and `should_interrupt` is checked for each object.
+    pub fn check(
+        objects: impl IntoIterator<Item = ObjectId>,
+        db: &dyn gix_object::Find,
+        tips: impl IntoIterator<Item = ObjectId>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        Options {
+            severities,
+            skip_list,
+            shallow_commits,
+        }: &Options,
+    ) -> Result<Outcome, Error> {
+        let mut ids: Vec<_> = objects.into_iter().collect();
+        ids.sort();
+        ids.dedup();
+        let mut state: HashMap<ObjectId, State> = ids.iter().map(|id| (*id, State::default())).collect();
+
+        let mut problems = Vec::new();
+        let mut messages = Vec::new();
+        let mut edges = Vec::new();
+        let mut buf = Vec::new();
+        let start = std::time::Instant::now();
+        let mut checked = progress.add_child_with_id("checking objects".into(), ProgressId::CheckedObjects.into());
+        checked.init(Some(ids.len()), gix_features::progress::count("objects"));
+        for id in &ids {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            checked.inc();
+            let data = match db.try_find(id, &mut buf) {
+                Ok(Some(data)) => data,
+                Ok(None) => {
+                    problems.push(Problem::Missing {
+                        id: *id,
+                        kind: None,
+                        referenced_by: None,
+                    });
+                    continue;
+                }
+                Err(err) => {
+                    problems.push(Problem::Unreadable { id: *id, source: err });
+                    continue;
+                }
+            };
+            let kind = data.kind;
+            match gix_object::compute_hash(id.kind(), kind, data.data) {
+                Ok(actual) if actual == *id => {}
+                Ok(actual) => {
+                    problems.push(Problem::HashMismatch { id: *id, kind, actual });
+                    continue;
+                }
+                Err(err) => {
+                    problems.push(Problem::Unreadable {
+                        id: *id,
+                        source: err.into(),
+                    });
+                    continue;
+                }
+            }
+            state.get_mut(id).expect("present").kind = Some(kind);
+            if !skip_list.contains(id) {
+                validate::object(id, kind, data.data, severities, &mut messages);
+                problems.extend(messages.drain(..).map(Problem::Message));
+            }
+
+            references(id, kind, data.data, shallow_commits, &mut edges);
+            for (target, target_kind) in edges.drain(..) {
+                match state.get_mut(&target) {
+                    Some(target) => target.referenced = true,
+                    None => problems.push(Problem::Missing {
+                        id: target,
+                        kind: Some(target_kind),
+                        referenced_by: Some(*id),
+                    }),
+                }
+            }
+        }
+        checked.show_throughput(start);
+
+        let start = std::time::Instant::now();
+        let mut traversed = progress.add_child_with_id("traversing graph".into(), ProgressId::TraversedObjects.into());
+        traversed.init(None, gix_features::progress::count("objects"));
+        let mut stack: Vec<(ObjectId, Option<(Kind, ObjectId)>)> = tips.into_iter().map(|id| (id, None)).collect();
+        while let Some((id, referenced_by)) = stack.pop() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let Some(object) = state.get_mut(&id) else {
+                // Missing objects that are referenced by others were already reported.
+                if referenced_by.is_none() {
+                    problems.push(Problem::Missing {
+                        id,
+                        kind: None,
+                        referenced_by: None,
+                    });
+                }
+                continue;
+            };
+            if object.reachable {
+                continue;
+            }
+            object.reachable = true;
+            traversed.inc();
+            let Some(kind) = object.kind else { continue };
+            if let Some((expected, referenced_by)) = referenced_by.filter(|(expected, _)| *expected != kind) {
+                problems.push(Problem::KindMismatch {
+                    id,
+                    expected,
+                    actual: kind,
+                    referenced_by,
+                });
+            }
+            if kind == Kind::Blob {
+                continue;
+            }
+            let Ok(Some(data)) = db.try_find(&id, &mut buf) else {
+                continue;
+            };
+            references(&id, kind, data.data, shallow_commits, &mut edges);
+            stack.extend(
+                edges
+                    .drain(..)
+                    .map(|(target, target_kind)| (target, Some((target_kind, id)))),
+            );
+        }
+        traversed.show_throughput(start);
+
+        let mut unreachable = Vec::new();
+        let mut dangling = Vec::new();
+        for id in ids {
+            let object = &state[&id];
+            let Some(kind) = object.kind.filter(|_| !object.reachable) else {
+                continue;
+            };
+            unreachable.push((id, kind));
+            if !object.referenced {
+                dangling.push((id, kind));
+            }
+        }
+
+        Ok(Outcome {
+            num_objects: state.len(),
+            problems,
+            unreachable,
+            dangling,
+        })
+    }
+
+    /// Put all objects referenced by the object `id` of `kind` with `data` into `out`, along with the kind they are
+    /// expected to have.
+    ///
+    /// Submodules are skipped, just like the parents of `shallow_commits`, as these are expected to be missing.
+    /// Decoding errors stop the extraction silently as they are already reported as part of the validation.
+    fn references(
+        id: &ObjectId,
+        kind: Kind,
+        data: &[u8],
+        shallow_commits: &HashSet<ObjectId>,
+        out: &mut Vec<(ObjectId, Kind)>,
+    ) {
+        match kind {
+            Kind::Commit => {
+                use gix_object::commit::ref_iter::Token;
+                for token in gix_object::CommitRefIter::from_bytes(data) {
+                    match token {
+                        Ok(Token::Tree { id }) => out.push((id, Kind::Tree)),
+                        Ok(Token::Parent { id: parent }) => {
+                            if !shallow_commits.contains(id) {
+                                out.push((parent, Kind::Commit));
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            Kind::Tree => {
+                for entry in gix_object::TreeRefIter::from_bytes(data, id.kind()) {
+                    let Ok(entry) = entry else { break };
+                    let kind = match entry.mode.kind() {
+                        EntryKind::Tree => Kind::Tree,
+                        EntryKind::Commit => continue,
+                        EntryKind::Blob | EntryKind::BlobExecutable | EntryKind::Link => Kind::Blob,
+                    };
+                    out.push((entry.oid.to_owned(), kind));
+                }
+            }
+            Kind::Tag => {
+                use gix_object::tag::ref_iter::Token;
+                let mut target = None;
+                for token in gix_object::TagRefIter::from_bytes(data) {
+                    match token {
+                        Ok(Token::Target { id }) => target = Some(id),
+                        Ok(Token::TargetKind(kind)) => {
+                            out.extend(target.take().map(|id| (id, kind)));
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            Kind::Blob => {}
+        }
+    }
+}
@@ -7,6 +7,14 @@ use gix_hash::ObjectId;
 use gix_hashtable::HashSet;
 use gix_object::{tree::EntryKind, Exists, FindExt, Kind};
 
+pub mod check;
+pub use check::function::check;
+pub mod lost_found;
+pub mod message;
+pub mod skip_list;
+pub mod tips;
+pub mod validate;
+
 /// Perform a connectivity check.
 pub struct Connectivity<T, F>
 where
@@ -0,0 +1,48 @@
+//! Write dangling objects into the `lost-found` directory, like `git fsck --lost-found`.
+use std::path::Path;
+
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+mod error {
+    /// The error returned by [`write()`](super::write()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Find(#[from] gix_object::find::existing_object::Error),
+        #[error("Could not write '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+pub use error::Error;
+
+/// Write a file for each of the `dangling` objects into the `lost-found` directory within `git_dir`, reading
+/// blobs from `db`.
+///
+/// Commits are placed into `lost-found/commit/<id>`, and all other objects into `lost-found/other/<id>`.
+/// Blob files contain the blob itself, all other files contain the id of the object so they can be inspected.
+/// Return the amount of written files.
+pub fn write(git_dir: &Path, db: &dyn gix_object::Find, dangling: &[(ObjectId, Kind)]) -> Result<usize, Error> {
+    let mut buf = Vec::new();
+    for (id, kind) in dangling {
+        let dir = git_dir
+            .join("lost-found")
+            .join(if *kind == Kind::Commit { "commit" } else { "other" });
+        let path = dir.join(id.to_string());
+        let data = if *kind == Kind::Blob {
+            gix_object::FindExt::find_blob(db, id, &mut buf)?.data
+        } else {
+            buf.clear();
+            buf.extend_from_slice(format!("{id}\n").as_bytes());
+            &buf
+        };
+        std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(&path, data))
+            .map_err(|source| Error::Io { source, path })?;
+    }
+    Ok(dangling.len())
+}
@@ -0,0 +1,221 @@
+//! Identifiers and severities of the messages emitted when validating objects, compatible with those used by `git fsck`.
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+/// How severe a [message](Id) is.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+    /// The message is not reported at all.
+    Ignore,
+    /// The message is reported, but doesn't mark the object as broken.
+    Warn,
+    /// The message is reported, and the object is considered broken.
+    Error,
+}
+
+impl Severity {
+    /// Parse `name` as used as value for `fsck.<msg-id>`, i.e. `error`, `warn` or `ignore`.
+    pub fn from_name(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"error" => Severity::Error,
+            b"warn" => Severity::Warn,
+            b"ignore" => Severity::Ignore,
+            _ => return None,
+        })
+    }
+
+    /// Return the name of this severity as used in `git fsck` output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Ignore => "ignore",
+            Severity::Warn => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+macro_rules! ids {
+    ($($(#[$doc:meta])* $variant:ident => $name:literal, $severity:ident;)*) => {
+        /// The identifier of a problem found when validating an object, named like its `git fsck` counterpart.
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub enum Id {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl Id {
+            /// All known message ids.
+            pub const ALL: &'static [Id] = &[$(Id::$variant,)*];
+
+            /// Return the camel-cased name of the message id, as used in `git fsck` output and in `fsck.<msg-id>` configuration.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Id::$variant => $name,)*
+                }
+            }
+
+            /// Return the severity the message has if it is not configured otherwise.
+            pub fn default_severity(&self) -> Severity {
+                match self {
+                    $(Id::$variant => Severity::$severity,)*
+                }
+            }
+        }
+    };
+}
+
+ids! {
+    /// A header line contains a NUL byte.
+    NulInHeader => "nulInHeader", Error;
+    /// The headers of the object aren't terminated by a newline.
+    UnterminatedHeader => "unterminatedHeader", Error;
+    /// The date of an identity can't be parsed.
+    BadDate => "badDate", Error;
+    /// The date of an identity doesn't fit into a 64 bit integer.
+    BadDateOverflow => "badDateOverflow", Error;
+    /// The email of an identity is malformed.
+    BadEmail => "badEmail", Error;
+    /// The name of an identity is malformed.
+    BadName => "badName", Error;
+    /// The `object` line of a tag doesn't contain a valid hash.
+    BadObjectSha1 => "badObjectSha1", Error;
+    /// A `parent` line of a commit doesn't contain a valid hash.
+    BadParentSha1 => "badParentSha1", Error;
+    /// The time zone of an identity is malformed.
+    BadTimezone => "badTimezone", Error;
+    /// A tree can't be parsed.
+    BadTree => "badTree", Error;
+    /// The `tree` line of a commit doesn't contain a valid hash.
+    BadTreeSha1 => "badTreeSha1", Error;
+    /// The `type` line of a tag doesn't name a known object type.
+    BadType => "badType", Error;
+    /// A tree contains the same name more than once.
+    DuplicateEntries => "duplicateEntries", Error;
+    /// A commit lacks the `author` line.
+    MissingAuthor => "missingAuthor", Error;
+    /// A commit lacks the `committer` line.
+    MissingCommitter => "missingCommitter", Error;
+    /// An identity lacks the email.
+    MissingEmail => "missingEmail", Error;
+    /// An identity starts with the email.
+    MissingNameBeforeEmail => "missingNameBeforeEmail", Error;
+    /// A tag lacks the `object` line.
+    MissingObject => "missingObject", Error;
+    /// An identity lacks the space between email and date.
+    MissingSpaceBeforeDate => "missingSpaceBeforeDate", Error;
+    /// An identity lacks the space between name and email.
+    MissingSpaceBeforeEmail => "missingSpaceBeforeEmail", Error;
+    /// A tag ends right after the `tag` field.
+    MissingTag => "missingTag", Error;
+    /// A tag lacks the `tag` line.
+    MissingTagEntry => "missingTagEntry", Error;
+    /// A commit lacks the `tree` line.
+    MissingTree => "missingTree", Error;
+    /// A tag ends right after the `type` field.
+    MissingType => "missingType", Error;
+    /// A tag lacks the `type` line.
+    MissingTypeEntry => "missingTypeEntry", Error;
+    /// A commit has more than one `author` line.
+    MultipleAuthors => "multipleAuthors", Error;
+    /// The entries of a tree aren't sorted.
+    TreeNotSorted => "treeNotSorted", Error;
+    /// The date of an identity has leading zeroes.
+    ZeroPaddedDate => "zeroPaddedDate", Error;
+    /// A tree entry has an empty name.
+    EmptyName => "emptyName", Warn;
+    /// A tree entry name contains a slash.
+    FullPathname => "fullPathname", Warn;
+    /// A tree entry is named `.`.
+    HasDot => "hasDot", Warn;
+    /// A tree entry is named `..`.
+    HasDotdot => "hasDotdot", Warn;
+    /// A tree entry is named `.git`, or something that file systems may consider equivalent.
+    HasDotgit => "hasDotgit", Warn;
+    /// A tree entry points to the null hash.
+    NullSha1 => "nullSha1", Warn;
+    /// A tree entry mode has leading zeroes.
+    ZeroPaddedFilemode => "zeroPaddedFilemode", Warn;
+    /// A commit contains a NUL byte.
+    NulInCommit => "nulInCommit", Warn;
+    /// A tree entry has a mode that git doesn't produce.
+    BadFilemode => "badFilemode", Warn;
+    /// The name of a tag isn't a valid reference name.
+    BadTagName => "badTagName", Warn;
+    /// A tag lacks the `tagger` line.
+    MissingTaggerEntry => "missingTaggerEntry", Warn;
+    /// A tag has additional headers after the `tagger` line.
+    ExtraHeaderEntry => "extraHeaderEntry", Warn;
+}
+
+impl Id {
+    /// Parse the camel-cased `name` of a message id, ignoring case just like `git` does.
+    pub fn from_name(name: &[u8]) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|id| id.as_str().as_bytes().eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    /// Return `true` if messages with this id can't be configured to be anything but an [error](Severity::Error)
+    /// as the object can't be processed any further.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Id::NulInHeader | Id::UnterminatedHeader)
+    }
+}
+
+/// A mapping of message ids to their configured severity, for use with `fsck.<msg-id>`.
+#[derive(Debug, Default, Clone)]
+pub struct Severities {
+    overrides: BTreeMap<Id, Severity>,
+}
+
+impl Severities {
+    /// Configure `id` to have `severity`.
+    ///
+    /// Note that [fatal](Id::is_fatal()) ids can't be downgraded, which is why `false` is returned in that case.
+    pub fn set(&mut self, id: Id, severity: Severity) -> bool {
+        if id.is_fatal() && severity != Severity::Error {
+            return false;
+        }
+        self.overrides.insert(id, severity);
+        true
+    }
+
+    /// Return the severity of `id`.
+    pub fn get(&self, id: Id) -> Severity {
+        self.overrides
+            .get(&id)
+            .copied()
+            .unwrap_or_else(|| id.default_severity())
+    }
+}
+
+/// A message produced when validating an object.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Message {
+    /// The id of the object the message is about.
+    pub object: ObjectId,
+    /// The kind of object the message is about.
+    pub kind: Kind,
+    /// The message identifier.
+    pub id: Id,
+    /// The severity of the message, which is never [`Severity::Ignore`].
+    pub severity: Severity,
+    /// A human-readable description of the problem.
+    pub text: &'static str,
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} in {} {}: {}: {}",
+            self.severity.as_str(),
+            self.kind,
+            self.object,
+            self.id.as_str(),
+            self.text
+        )
+    }
+}
@@ -0,0 +1,37 @@
+//! Parse the list of objects known to be broken, as configured with `fsck.skipList`.
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+
+mod error {
+    use bstr::BString;
+
+    /// The error returned by [`parse()`](super::parse()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Invalid object name in line {line_number}: {line:?}")]
+        InvalidObjectName { line_number: usize, line: BString },
+    }
+}
+pub use error::Error;
+
+/// Parse `data` as skip-list, with one full hexadecimal object id per line.
+///
+/// Everything after `#` is a comment, and empty lines are ignored.
+/// Objects in the skip-list aren't validated, but they are still considered for connectivity.
+pub fn parse(data: &[u8]) -> Result<HashSet<ObjectId>, Error> {
+    let mut out = HashSet::default();
+    for (line_number, line) in data.lines().enumerate() {
+        let line = line.find_byte(b'#').map_or(line, |pos| &line[..pos]).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let id = ObjectId::from_hex(line).map_err(|_| Error::InvalidObjectName {
+            line_number: line_number + 1,
+            line: BString::from(line),
+        })?;
+        out.insert(id);
+    }
+    Ok(out)
+}
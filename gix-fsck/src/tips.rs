@@ -0,0 +1,48 @@
+//! Obtain the starting points of a full connectivity check from references and their logs.
+use gix_hash::ObjectId;
+use gix_ref::{store, Target};
+
+mod error {
+    /// The error returned by [`from_refs()`](super::from_refs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindHead(#[from] gix_ref::store::find::Error),
+        #[error(transparent)]
+        IterRefs(#[from] gix_ref::store::iter::Error),
+        #[error(transparent)]
+        Reflog(#[from] gix_ref::store::log::Error),
+    }
+}
+pub use error::Error;
+
+/// Return all objects directly pointed to by `HEAD` and all references in `store`, along with all previous and current
+/// values recorded in their reference logs.
+///
+/// These are the objects that are considered reachable by `git fsck` if no starting points are given.
+/// The result is sorted and free of duplicates, and never contains the null hash.
+pub fn from_refs(store: &store::Handle) -> Result<Vec<ObjectId>, Error> {
+    let mut refs = Vec::new();
+    if let Some(head) = store.try_find("HEAD")? {
+        refs.push(head);
+    }
+    let platform = store.iter()?;
+    for reference in platform.all()? {
+        refs.push(reference?);
+    }
+
+    let mut out = Vec::new();
+    for reference in refs {
+        if let Target::Object(id) = reference.target {
+            out.push(id);
+        }
+        if let Some(log) = store.reflog(reference.name.as_ref())? {
+            out.extend(log.into_iter().flat_map(|line| [line.previous_oid, line.new_oid]));
+        }
+    }
+    out.retain(|id| !id.is_null());
+    out.sort();
+    out.dedup();
+    Ok(out)
+}
@@ -0,0 +1,390 @@
+//! Validate the raw encoding of objects like `git fsck` does, which is stricter than what is needed to decode them.
+use bstr::ByteSlice;
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+use crate::message::{Id, Message, Severities, Severity};
+
+/// Validate the `data` of the object `id` of `kind` and push all [messages](Message) that aren't
+/// [ignored](Severity::Ignore) in `severities` into `out`.
+///
+/// Validation stops at the first message that is an [error](Severity::Error), as the remainder of
+/// the object can't be trusted anymore.
+/// Blobs are never validated.
+pub fn object(id: &gix_hash::oid, kind: Kind, data: &[u8], severities: &Severities, out: &mut Vec<Message>) {
+    let mut report = Report {
+        object: id.to_owned(),
+        kind,
+        severities,
+        out,
+    };
+    let hash_kind = id.kind();
+    let _ = match kind {
+        Kind::Commit => commit(data, hash_kind, &mut report),
+        Kind::Tag => tag(data, hash_kind, &mut report),
+        Kind::Tree => tree(data, hash_kind, &mut report),
+        Kind::Blob => Ok(()),
+    };
+}
+
+/// Returned by [`Report::report()`] if validation of an object has to stop.
+struct Abort;
+
+struct Report<'a> {
+    object: ObjectId,
+    kind: Kind,
+    severities: &'a Severities,
+    out: &'a mut Vec<Message>,
+}
+
+impl Report<'_> {
+    /// Record `id` with `text`, and abort if it's an error.
+    fn report(&mut self, id: Id, text: &'static str) -> Result<(), Abort> {
+        let severity = self.severities.get(id);
+        if severity == Severity::Ignore {
+            return Ok(());
+        }
+        self.out.push(Message {
+            object: self.object,
+            kind: self.kind,
+            id,
+            severity,
+            text,
+        });
+        if severity == Severity::Error {
+            Err(Abort)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn verify_headers(data: &[u8], report: &mut Report<'_>) -> Result<(), Abort> {
+    for (pos, byte) in data.iter().enumerate() {
+        match byte {
+            0 => return report.report(Id::NulInHeader, "unterminated header: NUL before end of headers"),
+            b'\n' if data.get(pos + 1) == Some(&b'\n') => return Ok(()),
+            _ => {}
+        }
+    }
+    // Not having a body is fine, but the last header still needs its newline.
+    if data.last() == Some(&b'\n') {
+        return Ok(());
+    }
+    report.report(Id::UnterminatedHeader, "unterminated header")
+}
+
+/// Parse a hexadecimal hash of `hash_kind` followed by a newline at the beginning of `data`, and return the remainder
+/// after the newline.
+fn hex_line(data: &[u8], hash_kind: gix_hash::Kind) -> Option<&[u8]> {
+    let hex_len = hash_kind.len_in_hex();
+    let hex = data.get(..hex_len)?;
+    if !hex.iter().all(u8::is_ascii_hexdigit) || data.get(hex_len) != Some(&b'\n') {
+        return None;
+    }
+    Some(&data[hex_len + 1..])
+}
+
+fn commit(data: &[u8], hash_kind: gix_hash::Kind, report: &mut Report<'_>) -> Result<(), Abort> {
+    verify_headers(data, report)?;
+    let Some(mut buf) = data.strip_prefix(b"tree ") else {
+        return report.report(Id::MissingTree, "invalid format - expected 'tree' line");
+    };
+    buf = match hex_line(buf, hash_kind) {
+        Some(rest) => rest,
+        None => {
+            report.report(Id::BadTreeSha1, "invalid 'tree' line format - bad sha1")?;
+            skip_line(buf)
+        }
+    };
+    while let Some(parent) = buf.strip_prefix(b"parent ") {
+        buf = match hex_line(parent, hash_kind) {
+            Some(rest) => rest,
+            None => {
+                report.report(Id::BadParentSha1, "invalid 'parent' line format - bad sha1")?;
+                skip_line(parent)
+            }
+        };
+    }
+    let mut num_authors = 0;
+    while let Some(author) = buf.strip_prefix(b"author ") {
+        num_authors += 1;
+        buf = ident(author, report)?;
+    }
+    match num_authors {
+        0 => report.report(Id::MissingAuthor, "invalid format - expected 'author' line")?,
+        1 => {}
+        _ => report.report(Id::MultipleAuthors, "invalid format - multiple 'author' lines")?,
+    }
+    let Some(committer) = buf.strip_prefix(b"committer ") else {
+        return report.report(Id::MissingCommitter, "invalid format - expected 'committer' line");
+    };
+    ident(committer, report)?;
+    if data.contains(&0) {
+        report.report(Id::NulInCommit, "NUL byte in the commit object body")?;
+    }
+    Ok(())
+}
+
+fn tag(data: &[u8], hash_kind: gix_hash::Kind, report: &mut Report<'_>) -> Result<(), Abort> {
+    verify_headers(data, report)?;
+    let Some(mut buf) = data.strip_prefix(b"object ") else {
+        return report.report(Id::MissingObject, "invalid format - expected 'object' line");
+    };
+    buf = match hex_line(buf, hash_kind) {
+        Some(rest) => rest,
+        None => {
+            report.report(Id::BadObjectSha1, "invalid 'object' line format - bad sha1")?;
+            skip_line(buf)
+        }
+    };
+
+    let Some(kind) = buf.strip_prefix(b"type ") else {
+        return report.report(Id::MissingTypeEntry, "invalid format - expected 'type' line");
+    };
+    let Some(eol) = kind.find_byte(b'\n') else {
+        return report.report(Id::MissingType, "invalid format - unexpected end after 'type' line");
+    };
+    if Kind::from_bytes(&kind[..eol]).is_err() {
+        report.report(Id::BadType, "invalid 'type' value")?;
+    }
+    buf = &kind[eol + 1..];
+
+    let Some(name) = buf.strip_prefix(b"tag ") else {
+        return report.report(Id::MissingTagEntry, "invalid format - expected 'tag' line");
+    };
+    let Some(eol) = name.find_byte(b'\n') else {
+        return report.report(Id::MissingTag, "invalid format - unexpected end after 'type' line");
+    };
+    let mut ref_name = b"refs/tags/".to_vec();
+    ref_name.extend_from_slice(&name[..eol]);
+    if gix_validate::reference::name(ref_name.as_bstr()).is_err() {
+        report.report(Id::BadTagName, "invalid 'tag' name")?;
+    }
+    buf = &name[eol + 1..];
+
+    match buf.strip_prefix(b"tagger ") {
+        Some(tagger) => buf = ident(tagger, report)?,
+        // Early tags don't have a tagger.
+        None => report.report(Id::MissingTaggerEntry, "invalid format - expected 'tagger' line")?,
+    }
+    if !buf.is_empty() && !buf.starts_with(b"\n") {
+        report.report(Id::ExtraHeaderEntry, "invalid format - extra header(s) after 'tagger'")?;
+    }
+    Ok(())
+}
+
+fn skip_line(data: &[u8]) -> &[u8] {
+    data.find_byte(b'\n').map_or(&[], |eol| &data[eol + 1..])
+}
+
+/// Validate the identity in the line at the beginning of `data`, and return the remainder of `data` after the line.
+fn ident<'a>(data: &'a [u8], report: &mut Report<'_>) -> Result<&'a [u8], Abort> {
+    let (line, rest) = match data.find_byte(b'\n') {
+        Some(eol) => (&data[..eol], &data[eol + 1..]),
+        None => (data, &[][..]),
+    };
+    if line.first() == Some(&b'<') {
+        report.report(
+            Id::MissingNameBeforeEmail,
+            "invalid author/committer line - missing space before email",
+        )?;
+        return Ok(rest);
+    }
+    let Some(email_start) = line.iter().position(|b| matches!(b, b'<' | b'>')) else {
+        report.report(Id::MissingEmail, "invalid author/committer line - missing email")?;
+        return Ok(rest);
+    };
+    if line[email_start] == b'>' {
+        report.report(Id::BadName, "invalid author/committer line - bad name")?;
+        return Ok(rest);
+    }
+    if email_start == 0 || line[email_start - 1] != b' ' {
+        report.report(
+            Id::MissingSpaceBeforeEmail,
+            "invalid author/committer line - missing space before email",
+        )?;
+        return Ok(rest);
+    }
+    let email = &line[email_start + 1..];
+    let Some(email_end) = email
+        .iter()
+        .position(|b| matches!(b, b'<' | b'>'))
+        .filter(|pos| email[*pos] == b'>')
+    else {
+        report.report(Id::BadEmail, "invalid author/committer line - bad email")?;
+        return Ok(rest);
+    };
+    let Some(date) = email[email_end + 1..].strip_prefix(b" ") else {
+        report.report(
+            Id::MissingSpaceBeforeDate,
+            "invalid author/committer line - missing space before date",
+        )?;
+        return Ok(rest);
+    };
+    let num_digits = date.iter().take_while(|b| b.is_ascii_digit()).count();
+    if num_digits == 0 {
+        report.report(Id::BadDate, "invalid author/committer line - bad date")?;
+        return Ok(rest);
+    }
+    if date[0] == b'0' && date.get(1) != Some(&b' ') {
+        report.report(Id::ZeroPaddedDate, "invalid author/committer line - zero-padded date")?;
+        return Ok(rest);
+    }
+    if date[..num_digits]
+        .to_str()
+        .ok()
+        .and_then(|d| d.parse::<i64>().ok())
+        .is_none()
+    {
+        report.report(
+            Id::BadDateOverflow,
+            "invalid author/committer line - date causes integer overflow",
+        )?;
+        return Ok(rest);
+    }
+    let Some(tz) = date[num_digits..].strip_prefix(b" ") else {
+        report.report(Id::BadDate, "invalid author/committer line - bad date")?;
+        return Ok(rest);
+    };
+    if tz.len() != 5 || !matches!(tz[0], b'+' | b'-') || !tz[1..].iter().all(u8::is_ascii_digit) {
+        report.report(Id::BadTimezone, "invalid author/committer line - bad time zone")?;
+    }
+    Ok(rest)
+}
+
+/// The bits of a mode that determine the type of the entry.
+const S_IFMT: u32 = 0o170000;
+/// The type of a directory entry.
+const S_IFDIR: u32 = 0o040000;
+
+fn tree(data: &[u8], hash_kind: gix_hash::Kind, report: &mut Report<'_>) -> Result<(), Abort> {
+    let hash_len = hash_kind.len_in_bytes();
+    let (mut has_null_hash, mut has_full_path, mut has_empty_name, mut has_dot, mut has_dotdot, mut has_dotgit) =
+        (false, false, false, false, false, false);
+    let (mut has_zero_pad, mut has_bad_modes, mut has_duplicates, mut not_sorted) = (false, false, false, false);
+    let mut previous: Option<(u32, &[u8])> = None;
+    let mut buf = data;
+    while !buf.is_empty() {
+        let Some((RawEntry { mode, name, oid }, rest)) = tree_entry(buf, hash_len) else {
+            return report.report(Id::BadTree, "cannot be parsed as a tree");
+        };
+        buf = rest;
+
+        has_null_hash |= oid.iter().all(|b| *b == 0);
+        has_full_path |= name.contains(&b'/');
+        has_empty_name |= name.is_empty();
+        has_dot |= name == b".";
+        has_dotdot |= name == b"..";
+        has_dotgit |= matches!(
+            gix_validate::path::component(
+                name.as_bstr(),
+                None,
+                gix_validate::path::component::Options {
+                    protect_windows: false,
+                    protect_hfs: true,
+                    protect_ntfs: true,
+                },
+            ),
+            Err(gix_validate::path::component::Error::DotGitDir)
+        );
+        has_zero_pad |= mode.first() == Some(&b'0');
+        let mode = mode
+            .iter()
+            .fold(0u32, |mode, digit| (mode << 3) | u32::from(digit - b'0'));
+        match mode {
+            0o100755 | 0o100644 | 0o120000 | 0o040000 | 0o160000 => {}
+            // Allowed unless in `strict` mode, which isn't supported.
+            0o100664 => {}
+            _ => has_bad_modes = true,
+        }
+
+        if let Some((previous_mode, previous_name)) = previous {
+            match verify_ordered(previous_mode, previous_name, mode, name) {
+                Ordering::Ordered => {}
+                Ordering::Unordered => not_sorted = true,
+                Ordering::Duplicate => has_duplicates = true,
+            }
+        }
+        previous = Some((mode, name));
+    }
+
+    for (is_set, id, text) in [
+        (has_null_hash, Id::NullSha1, "contains entries pointing to null sha1"),
+        (has_full_path, Id::FullPathname, "contains full pathnames"),
+        (has_empty_name, Id::EmptyName, "contains empty pathname"),
+        (has_dot, Id::HasDot, "contains '.'"),
+        (has_dotdot, Id::HasDotdot, "contains '..'"),
+        (has_dotgit, Id::HasDotgit, "contains '.git'"),
+        (has_zero_pad, Id::ZeroPaddedFilemode, "contains zero-padded file modes"),
+        (has_bad_modes, Id::BadFilemode, "contains bad file modes"),
+        (has_duplicates, Id::DuplicateEntries, "contains duplicate file entries"),
+        (not_sorted, Id::TreeNotSorted, "not properly sorted"),
+    ] {
+        if is_set {
+            report.report(id, text)?;
+        }
+    }
+    Ok(())
+}
+
+/// A tree entry whose mode isn't interpreted yet.
+struct RawEntry<'a> {
+    mode: &'a [u8],
+    name: &'a [u8],
+    oid: &'a [u8],
+}
+
+/// Parse a single tree entry at the beginning of `data`, and return it along with the remaining data.
+fn tree_entry(data: &[u8], hash_len: usize) -> Option<(RawEntry<'_>, &[u8])> {
+    let space = data.find_byte(b' ')?;
+    let mode = &data[..space];
+    if mode.is_empty() || !mode.iter().all(|b| matches!(b, b'0'..=b'7')) {
+        return None;
+    }
+    let data = &data[space + 1..];
+    let nul = data.find_byte(0)?;
+    let name = &data[..nul];
+    let data = &data[nul + 1..];
+    if data.len() < hash_len {
+        return None;
+    }
+    Some((
+        RawEntry {
+            mode,
+            name,
+            oid: &data[..hash_len],
+        },
+        &data[hash_len..],
+    ))
+}
+
+enum Ordering {
+    Ordered,
+    Unordered,
+    Duplicate,
+}
+
+/// Check that the entry `(mode, name)` may follow `(previous_mode, previous_name)`, knowing that trees sort as if their
+/// name had a trailing slash.
+fn verify_ordered(previous_mode: u32, previous_name: &[u8], mode: u32, name: &[u8]) -> Ordering {
+    let len = previous_name.len().min(name.len());
+    match previous_name[..len].cmp(&name[..len]) {
+        std::cmp::Ordering::Less => return Ordering::Ordered,
+        std::cmp::Ordering::Greater => return Ordering::Unordered,
+        std::cmp::Ordering::Equal => {}
+    }
+    let next_byte = |mode: u32, name: &[u8]| match name.get(len) {
+        Some(byte) => Some(*byte),
+        None if mode & S_IFMT == S_IFDIR => Some(b'/'),
+        None => None,
+    };
+    if previous_name.len() == name.len() {
+        return Ordering::Duplicate;
+    }
+    if next_byte(previous_mode, previous_name) < next_byte(mode, name) {
+        Ordering::Ordered
+    } else {
+        Ordering::Unordered
+    }
+}
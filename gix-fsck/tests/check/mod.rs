@@ -0,0 +1,167 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_fsck::{
+    check::{Options, Outcome, Problem},
+    message::{Id, Severity},
+};
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+fn fixture() -> PathBuf {
+    gix_testtools::scripted_fixture_read_only("make_fsck_repo.sh").expect("fixture path")
+}
+
+fn id(name: &str) -> ObjectId {
+    let hex = std::fs::read_to_string(fixture().join(format!("{name}.id"))).expect("id file exists");
+    crate::hex_to_id(hex.trim())
+}
+
+fn odb() -> gix_odb::Handle {
+    gix_odb::at(fixture().join("broken/.git/objects")).expect("valid odb")
+}
+
+fn run(options: &Options) -> Outcome {
+    let db = odb();
+    let objects = db
+        .iter()
+        .expect("index can be loaded")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("all objects can be listed");
+    let store = gix_ref::Store::at(
+        fixture().join("broken/.git"),
+        gix_ref::store::Backend::Files,
+        Default::default(),
+    )
+    .expect("git dir exists")
+    .to_handle();
+    let tips = gix_fsck::tips::from_refs(&store).expect("refs and reflogs are valid");
+    gix_fsck::check(
+        objects,
+        &db,
+        tips,
+        &mut gix_features::progress::Discard,
+        &AtomicBool::default(),
+        options,
+    )
+    .expect("not interrupted")
+}
+
+fn messages(out: &Outcome) -> Vec<(ObjectId, Id, Severity)> {
+    out.problems
+        .iter()
+        .filter_map(|problem| match problem {
+            Problem::Message(msg) => Some((msg.object, msg.id, msg.severity)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn finds_malformed_and_corrupt_objects() {
+    let out = run(&Options::default());
+    let mut messages = messages(&out);
+    messages.sort();
+    let mut expected = vec![
+        (id("bad-ident-commit"), Id::BadName, Severity::Error),
+        (id("unsorted-tree"), Id::TreeNotSorted, Severity::Error),
+        (id("no-tagger-tag"), Id::MissingTaggerEntry, Severity::Warn),
+    ];
+    expected.sort();
+    assert_eq!(messages, expected);
+
+    let mismatches: Vec<_> = out
+        .problems
+        .iter()
+        .filter_map(|problem| match problem {
+            Problem::HashMismatch { id, kind, .. } => Some((*id, *kind)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(mismatches, [(id("hash-mismatch"), Kind::Blob)]);
+    assert_eq!(
+        out.problems.len(),
+        4,
+        "nothing is missing, and there are no other problems: {:?}",
+        out.problems
+    );
+    assert!(out.has_errors());
+}
+
+#[test]
+fn reports_unreachable_and_dangling_objects() {
+    let out = run(&Options::default());
+    assert!(
+        out.unreachable.iter().all(|(oid, _)| *oid != id("reflog-commit")),
+        "objects in the reflog are reachable"
+    );
+    assert!(
+        out.unreachable.iter().all(|(oid, _)| *oid != id("hash-mismatch")),
+        "corrupt objects are neither reachable nor unreachable"
+    );
+    for (expected, kind) in [
+        (id("dangling-commit"), Kind::Commit),
+        (id("unreachable-tree"), Kind::Tree),
+        (id("unreachable-blob"), Kind::Blob),
+    ] {
+        assert!(out.unreachable.contains(&(expected, kind)));
+    }
+    assert!(out.dangling.contains(&(id("dangling-commit"), Kind::Commit)));
+    assert!(
+        !out.dangling.iter().any(|(_, kind)| *kind == Kind::Tree),
+        "the unreachable tree is referenced by the dangling commit"
+    );
+    assert_eq!(
+        out.dangling.len(),
+        2,
+        "the dangling commit and the blob that was used to corrupt another object"
+    );
+}
+
+#[test]
+fn skip_list_and_severities_affect_messages() -> Result<(), Box<dyn std::error::Error>> {
+    let mut options = Options {
+        skip_list: gix_fsck::skip_list::parse(
+            format!("# known to be broken\n{}  # bad committer\n\n", id("bad-ident-commit")).as_bytes(),
+        )?,
+        ..Default::default()
+    };
+    options.severities.set(Id::TreeNotSorted, Severity::Ignore);
+    options.severities.set(Id::MissingTaggerEntry, Severity::Error);
+    assert!(
+        !options.severities.set(Id::NulInHeader, Severity::Warn),
+        "fatal messages can't be downgraded"
+    );
+
+    let out = run(&options);
+    assert_eq!(
+        messages(&out),
+        [(id("no-tagger-tag"), Id::MissingTaggerEntry, Severity::Error)]
+    );
+    Ok(())
+}
+
+#[test]
+fn lost_found_receives_dangling_objects() -> Result<(), Box<dyn std::error::Error>> {
+    let out = run(&Options::default());
+    let git_dir = gix_testtools::tempfile::TempDir::new()?;
+    let num_written = gix_fsck::lost_found::write(git_dir.path(), &odb(), &out.dangling)?;
+    assert_eq!(num_written, 2);
+
+    let commit = id("dangling-commit");
+    assert_eq!(
+        std::fs::read_to_string(git_dir.path().join("lost-found/commit").join(commit.to_string()))?,
+        format!("{commit}\n"),
+        "non-blobs are referred to by id"
+    );
+    let (blob, _) = out
+        .dangling
+        .iter()
+        .find(|(_, kind)| *kind == Kind::Blob)
+        .expect("a dangling blob");
+    assert_eq!(
+        std::fs::read(git_dir.path().join("lost-found/other").join(blob.to_string()))?,
+        b"replacement\n",
+        "blobs are written as is"
+    );
+    Ok(())
+}
@@ -0,0 +1,52 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function hex_to_binary() {
+  printf "$(echo "$1" | sed 's/../\\x&/g')"
+}
+
+git init -q broken
+(cd broken
+  echo a > a
+  git add a && git commit -q -m "c1"
+  echo b > b
+  git add b && git commit -q -m "c2"
+
+  # a commit that is only reachable through the reflog of the current branch and HEAD
+  echo c > c
+  git add c && git commit -q -m "c3"
+  git rev-parse HEAD > ../reflog-commit.id
+  git reset -q --hard HEAD~1
+
+  # a dangling commit, along with its unreachable tree and blob
+  dangling_blob=$(echo dangling | git hash-object -w --stdin)
+  dangling_tree=$(printf '100644 blob %s\tdangling\n' "$dangling_blob" | git mktree)
+  git commit-tree -m "dangling" "$dangling_tree" > ../dangling-commit.id
+  echo "$dangling_tree" > ../unreachable-tree.id
+  echo "$dangling_blob" > ../unreachable-blob.id
+
+  # a reachable commit whose committer lacks the opening angle bracket of the email
+  tree=$(git rev-parse HEAD^{tree})
+  printf 'tree %s\nauthor a <a@example.com> 1000000000 +0000\ncommitter c c@example.com> 1000000000 +0000\n\nbad ident\n' "$tree" \
+    | git hash-object -t commit --literally -w --stdin > ../bad-ident-commit.id
+  git update-ref refs/heads/bad-ident "$(cat ../bad-ident-commit.id)"
+
+  # a reachable tree whose entries are not sorted, referenced by a commit
+  blob=$(git rev-parse HEAD:a)
+  { printf '100644 b\0'; hex_to_binary "$blob"; printf '100644 a\0'; hex_to_binary "$blob"; } \
+    | git hash-object -t tree --literally -w --stdin > ../unsorted-tree.id
+  git update-ref refs/heads/unsorted "$(git commit-tree -m "unsorted" "$(cat ../unsorted-tree.id)")"
+
+  # an old-style tag without tagger
+  printf 'object %s\ntype commit\ntag old\n\nno tagger\n' "$(git rev-parse HEAD)" \
+    | git hash-object -t tag --literally -w --stdin > ../no-tagger-tag.id
+  git update-ref refs/tags/old "$(cat ../no-tagger-tag.id)"
+
+  # a loose object whose content doesn't match its id
+  corrupt=$(echo corrupt | git hash-object -w --stdin)
+  replacement=$(echo replacement | git hash-object -w --stdin)
+  corrupt_path=.git/objects/${corrupt:0:2}/${corrupt:2}
+  chmod u+w "$corrupt_path"
+  cp .git/objects/${replacement:0:2}/${replacement:2} "$corrupt_path"
+  echo "$corrupt" > ../hash-mismatch.id
+)
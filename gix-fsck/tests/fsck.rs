@@ -4,4 +4,6 @@ pub fn hex_to_id(hex: &str) -> ObjectId {
     ObjectId::from_hex(hex.as_bytes()).expect("40 bytes hex")
 }
 
+mod check;
 mod connectivity;
+mod validate;
@@ -0,0 +1,124 @@
+use gix_fsck::message::{Id, Severities};
+use gix_object::Kind;
+
+fn ids(kind: Kind, data: &[u8]) -> Vec<Id> {
+    let mut out = Vec::new();
+    gix_fsck::validate::object(
+        gix_hash::Kind::Sha1.null().as_ref(),
+        kind,
+        data,
+        &Severities::default(),
+        &mut out,
+    );
+    out.into_iter().map(|msg| msg.id).collect()
+}
+
+fn commit_with_committer(committer: &str) -> Vec<u8> {
+    format!(
+        "tree {tree}\nauthor a <a@example.com> 1 +0000\ncommitter {committer}\n\nmessage\n",
+        tree = gix_hash::Kind::Sha1.null()
+    )
+    .into_bytes()
+}
+
+fn tree(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (mode, name) in entries {
+        out.extend_from_slice(format!("{mode} {name}\0").as_bytes());
+        out.extend_from_slice(&[1; 20]);
+    }
+    out
+}
+
+#[test]
+fn commit_identities() {
+    for (committer, expected) in [
+        ("c <c@example.com> 1 +0000", None),
+        ("<c@example.com> 1 +0000", Some(Id::MissingNameBeforeEmail)),
+        ("c 1 +0000", Some(Id::MissingEmail)),
+        ("c> 1 +0000", Some(Id::BadName)),
+        ("c<c@example.com> 1 +0000", Some(Id::MissingSpaceBeforeEmail)),
+        ("c <c<@example.com> 1 +0000", Some(Id::BadEmail)),
+        ("c <c@example.com>1 +0000", Some(Id::MissingSpaceBeforeDate)),
+        ("c <c@example.com> x +0000", Some(Id::BadDate)),
+        ("c <c@example.com> 01 +0000", Some(Id::ZeroPaddedDate)),
+        (
+            "c <c@example.com> 99999999999999999999 +0000",
+            Some(Id::BadDateOverflow),
+        ),
+        ("c <c@example.com> 1 0000", Some(Id::BadTimezone)),
+        ("c <c@example.com> 1 +00000", Some(Id::BadTimezone)),
+    ] {
+        assert_eq!(
+            ids(Kind::Commit, &commit_with_committer(committer)),
+            Vec::from_iter(expected),
+            "{committer}"
+        );
+    }
+}
+
+#[test]
+fn commit_headers() {
+    assert_eq!(
+        ids(Kind::Commit, b"author a <a@example.com> 1 +0000\n"),
+        [Id::MissingTree]
+    );
+    assert_eq!(ids(Kind::Commit, b"tree 123\n"), [Id::BadTreeSha1]);
+    assert_eq!(ids(Kind::Commit, b"tree"), [Id::UnterminatedHeader]);
+    assert_eq!(ids(Kind::Commit, b"tree \0\n"), [Id::NulInHeader]);
+}
+
+#[test]
+fn tag_headers() {
+    let object = gix_hash::Kind::Sha1.null();
+    assert_eq!(
+        ids(
+            Kind::Tag,
+            format!("object {object}\ntype commit\ntag v1\ntagger t <t@example.com> 1 +0000\n\nmessage\n").as_bytes()
+        ),
+        []
+    );
+    assert_eq!(
+        ids(Kind::Tag, format!("object {object}\ntype car\ntag v1\n").as_bytes()),
+        [Id::BadType]
+    );
+    assert_eq!(
+        ids(
+            Kind::Tag,
+            format!("object {object}\ntype commit\ntag v..1\n").as_bytes()
+        ),
+        [Id::BadTagName, Id::MissingTaggerEntry],
+        "warnings don't stop the validation"
+    );
+    assert_eq!(
+        ids(
+            Kind::Tag,
+            format!("object {object}\ntype commit\ntag v1\ntagger t <t@example.com> 1 +0000\nextra\n").as_bytes()
+        ),
+        [Id::ExtraHeaderEntry]
+    );
+}
+
+#[test]
+fn tree_entries() {
+    assert_eq!(ids(Kind::Tree, &tree(&[("100644", "a.b"), ("40000", "a")])), []);
+    assert_eq!(
+        ids(Kind::Tree, &tree(&[("40000", "a"), ("100644", "a.b")])),
+        [Id::TreeNotSorted],
+        "trees sort as if their name had a trailing slash"
+    );
+    assert_eq!(
+        ids(Kind::Tree, &tree(&[("100644", "a"), ("40000", "a")])),
+        [Id::DuplicateEntries]
+    );
+    assert_eq!(
+        ids(
+            Kind::Tree,
+            &tree(&[("100644", ".GIT"), ("100755", "a/b"), ("100600", "c")])
+        ),
+        [Id::FullPathname, Id::HasDotgit, Id::BadFilemode]
+    );
+    assert_eq!(ids(Kind::Tree, &tree(&[("040000", "a")])), [Id::ZeroPaddedFilemode]);
+    assert_eq!(ids(Kind::Tree, b"100644 a\0\x01"), [Id::BadTree]);
+    assert_eq!(ids(Kind::Blob, b"anything goes"), []);
+}
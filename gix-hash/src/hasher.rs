@@ -10,28 +10,42 @@ pub(super) mod _impl {
     use crate::hasher::Error;
     use sha1_checked::{CollisionResult, Digest};
 
-    /// An implementation of the Sha1 hash, which can be used once.
+    /// An implementation of the Sha1 or Sha256 hash, which can be used once.
     ///
     /// We use [`sha1_checked`] to implement the same collision detection
-    /// algorithm as Git.
+    /// algorithm as Git for Sha1, and [`sha2`] for Sha256.
     #[derive(Clone)]
-    pub struct Hasher(sha1_checked::Sha1);
+    pub struct Hasher(Inner);
+
+    #[derive(Clone)]
+    #[allow(clippy::large_enum_variant)]
+    enum Inner {
+        Sha1(sha1_checked::Sha1),
+        Sha256(sha2::Sha256),
+    }
 
     impl Hasher {
         /// Let's not provide a public default implementation to force people to go through [`hasher()`].
-        fn default() -> Self {
+        fn sha1() -> Self {
             // This matches the configuration used by Git, which only uses
             // the collision detection to bail out, rather than computing
             // alternate “safe hashes” for inputs where a collision attack
             // was detected.
-            Self(sha1_checked::Builder::default().safe_hash(false).build())
+            Self(Inner::Sha1(sha1_checked::Builder::default().safe_hash(false).build()))
+        }
+
+        fn sha256() -> Self {
+            Self(Inner::Sha256(sha2::Sha256::new()))
         }
     }
 
     impl Hasher {
         /// Digest the given `bytes`.
         pub fn update(&mut self, bytes: &[u8]) {
-            self.0.update(bytes);
+            match &mut self.0 {
+                Inner::Sha1(h) => h.update(bytes),
+                Inner::Sha256(h) => h.update(bytes),
+            }
         }
 
         /// Finalize the hash and produce an object ID.
@@ -39,13 +53,17 @@ pub(super) mod _impl {
         /// Returns [`Error`] if a collision attack is detected.
         #[inline]
         pub fn try_finalize(self) -> Result<crate::ObjectId, Error> {
-            match self.0.try_finalize() {
+            let hasher = match self.0 {
+                Inner::Sha1(hasher) => hasher,
+                Inner::Sha256(hasher) => return Ok(crate::ObjectId::Sha256(hasher.finalize().into())),
+            };
+            match hasher.try_finalize() {
                 CollisionResult::Ok(digest) => Ok(crate::ObjectId::Sha1(digest.into())),
                 CollisionResult::Mitigated(_) => {
                     // SAFETY: `CollisionResult::Mitigated` is only
                     // returned when `safe_hash()` is on. `Hasher`’s field
                     // is private, and we only construct it in the
-                    // `Default` instance, which turns `safe_hash()` off.
+                    // `sha1()` instance, which turns `safe_hash()` off.
                     //
                     // As of Rust 1.84.1, the compiler can’t figure out
                     // this function cannot panic without this.
@@ -65,7 +83,8 @@ pub(super) mod _impl {
     #[inline]
     pub fn hasher(kind: crate::Kind) -> Hasher {
         match kind {
-            crate::Kind::Sha1 => Hasher::default(),
+            crate::Kind::Sha1 => Hasher::sha1(),
+            crate::Kind::Sha256 => Hasher::sha256(),
         }
     }
 }
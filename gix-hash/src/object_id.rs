@@ -4,7 +4,7 @@ use std::{
     ops::Deref,
 };
 
-use crate::{borrowed::oid, Kind, SIZE_OF_SHA1_DIGEST};
+use crate::{borrowed::oid, Kind, SIZE_OF_SHA1_DIGEST, SIZE_OF_SHA256_DIGEST};
 
 /// An owned hash identifying objects, most commonly `Sha1`
 #[derive(PartialEq, Eq, Ord, PartialOrd, Clone, Copy)]
@@ -12,6 +12,8 @@ use crate::{borrowed::oid, Kind, SIZE_OF_SHA1_DIGEST};
 pub enum ObjectId {
     /// A SHA 1 hash digest
     Sha1([u8; SIZE_OF_SHA1_DIGEST]),
+    /// A SHA 256 hash digest
+    Sha256([u8; SIZE_OF_SHA256_DIGEST]),
 }
 
 // False positive: https://github.com/rust-lang/rust-clippy/issues/2627
@@ -45,28 +47,35 @@ pub mod decode {
 
     /// Hash decoding
     impl ObjectId {
-        /// Create an instance from a `buffer` of 40 bytes encoded with hexadecimal notation.
+        /// Create an instance from a `buffer` of 40 or 64 bytes encoded with hexadecimal notation.
         ///
         /// Such a buffer can be obtained using [`oid::write_hex_to(buffer)`][super::oid::write_hex_to()]
         pub fn from_hex(buffer: &[u8]) -> Result<ObjectId, Error> {
             match buffer.len() {
-                40 => Ok({
-                    ObjectId::Sha1({
-                        let mut buf = [0; 20];
-                        faster_hex::hex_decode(buffer, &mut buf).map_err(|err| match err {
-                            faster_hex::Error::InvalidChar | faster_hex::Error::Overflow => Error::Invalid,
-                            faster_hex::Error::InvalidLength(_) => {
-                                unreachable!("BUG: This is already checked")
-                            }
-                        })?;
-                        buf
-                    })
-                }),
+                40 => Ok(ObjectId::Sha1({
+                    let mut buf = [0; 20];
+                    decode_hex(buffer, &mut buf)?;
+                    buf
+                })),
+                64 => Ok(ObjectId::Sha256({
+                    let mut buf = [0; 32];
+                    decode_hex(buffer, &mut buf)?;
+                    buf
+                })),
                 len => Err(Error::InvalidHexEncodingLength(len)),
             }
         }
     }
 
+    fn decode_hex(buffer: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        faster_hex::hex_decode(buffer, out).map_err(|err| match err {
+            faster_hex::Error::InvalidChar | faster_hex::Error::Overflow => Error::Invalid,
+            faster_hex::Error::InvalidLength(_) => {
+                unreachable!("BUG: This is already checked")
+            }
+        })
+    }
+
     impl FromStr for ObjectId {
         type Err = Error;
 
@@ -83,6 +92,7 @@ impl ObjectId {
     pub fn kind(&self) -> Kind {
         match self {
             ObjectId::Sha1(_) => Kind::Sha1,
+            ObjectId::Sha256(_) => Kind::Sha256,
         }
     }
     /// Return the raw byte slice representing this hash.
@@ -90,6 +100,7 @@ impl ObjectId {
     pub fn as_slice(&self) -> &[u8] {
         match self {
             Self::Sha1(b) => b.as_ref(),
+            Self::Sha256(b) => b.as_ref(),
         }
     }
     /// Return the raw mutable byte slice representing this hash.
@@ -97,6 +108,7 @@ impl ObjectId {
     pub fn as_mut_slice(&mut self) -> &mut [u8] {
         match self {
             Self::Sha1(b) => b.as_mut(),
+            Self::Sha256(b) => b.as_mut(),
         }
     }
 
@@ -107,6 +119,9 @@ impl ObjectId {
             Kind::Sha1 => {
                 ObjectId::Sha1(*b"\xe6\x9d\xe2\x9b\xb2\xd1\xd6\x43\x4b\x8b\x29\xae\x77\x5a\xd8\xc2\xe4\x8c\x53\x91")
             }
+            Kind::Sha256 => ObjectId::Sha256(
+                *b"\x47\x3a\x0f\x4c\x3b\xe8\xa9\x36\x81\xa2\x67\xe3\xb1\xe9\xa7\xdc\xda\x11\x85\x43\x6f\xe1\x41\xf7\x74\x91\x20\xa3\x03\x72\x18\x13",
+            ),
         }
     }
 
@@ -117,6 +132,9 @@ impl ObjectId {
             Kind::Sha1 => {
                 ObjectId::Sha1(*b"\x4b\x82\x5d\xc6\x42\xcb\x6e\xb9\xa0\x60\xe5\x4b\xf8\xd6\x92\x88\xfb\xee\x49\x04")
             }
+            Kind::Sha256 => ObjectId::Sha256(
+                *b"\x6e\xf1\x9b\x41\x22\x5c\x53\x69\xf1\xc1\x04\xd4\x5d\x8d\x85\xef\xa9\xb0\x57\xb5\x3b\x14\xb4\xb9\xb9\x39\xdd\x74\xde\xcc\x53\x21",
+            ),
         }
     }
 
@@ -126,6 +144,7 @@ impl ObjectId {
     pub const fn null(kind: Kind) -> ObjectId {
         match kind {
             Kind::Sha1 => Self::null_sha1(),
+            Kind::Sha256 => Self::null_sha256(),
         }
     }
 
@@ -135,6 +154,7 @@ impl ObjectId {
     pub fn is_null(&self) -> bool {
         match self {
             ObjectId::Sha1(digest) => &digest[..] == oid::null_sha1().as_bytes(),
+            ObjectId::Sha256(digest) => &digest[..] == oid::null_sha256().as_bytes(),
         }
     }
 
@@ -159,6 +179,7 @@ impl ObjectId {
     pub fn from_bytes_or_panic(bytes: &[u8]) -> Self {
         match bytes.len() {
             20 => Self::Sha1(bytes.try_into().expect("prior length validation")),
+            32 => Self::Sha256(bytes.try_into().expect("prior length validation")),
             other => panic!("BUG: unsupported hash len: {other}"),
         }
     }
@@ -189,10 +210,20 @@ impl ObjectId {
     }
 }
 
+/// Sha256 hash specific methods
+impl ObjectId {
+    /// Returns an Digest representing a Sha256 with whose memory is zeroed.
+    #[inline]
+    pub(crate) const fn null_sha256() -> ObjectId {
+        ObjectId::Sha256([0u8; 32])
+    }
+}
+
 impl std::fmt::Debug for ObjectId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ObjectId::Sha1(_hash) => f.write_str("Sha1(")?,
+            ObjectId::Sha256(_hash) => f.write_str("Sha256(")?,
         }
         for b in self.as_bytes() {
             write!(f, "{b:02x}")?;
@@ -207,10 +238,17 @@ impl From<[u8; SIZE_OF_SHA1_DIGEST]> for ObjectId {
     }
 }
 
+impl From<[u8; SIZE_OF_SHA256_DIGEST]> for ObjectId {
+    fn from(v: [u8; 32]) -> Self {
+        ObjectId::Sha256(v)
+    }
+}
+
 impl From<&oid> for ObjectId {
     fn from(v: &oid) -> Self {
         match v.kind() {
             Kind::Sha1 => ObjectId::from_20_bytes(v.as_bytes()),
+            Kind::Sha256 => ObjectId::Sha256(v.as_bytes().try_into().expect("no bug in hash detection")),
         }
     }
 }
@@ -17,7 +17,7 @@ fn size_of_hasher() {
 fn size_of_try_finalize_return_type() {
     assert_eq!(
         std::mem::size_of::<Result<ObjectId, gix_hash::hasher::Error>>(),
-        21,
+        34,
         "The size of the return value is just 1 byte larger than just returning the object hash itself"
     );
 }
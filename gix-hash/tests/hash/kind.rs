@@ -1,3 +1,5 @@
+use gix_hash::Kind;
+
 mod from_hex_len {
     use gix_hash::Kind;
 
@@ -9,8 +11,24 @@ mod from_hex_len {
         assert_eq!(Kind::from_hex_len(40), Some(Kind::Sha1));
     }
 
+    #[test]
+    fn some_sha256() {
+        assert_eq!(Kind::from_hex_len(41), Some(Kind::Sha256));
+        assert_eq!(Kind::from_hex_len(64), Some(Kind::Sha256));
+    }
+
     #[test]
     fn none_if_there_is_no_fit() {
         assert_eq!(Kind::from_hex_len(65), None);
     }
 }
+
+#[test]
+fn from_str_and_display_round_trip() {
+    for kind in [Kind::Sha1, Kind::Sha256] {
+        assert_eq!(kind.to_string().parse::<Kind>(), Ok(kind));
+    }
+    assert_eq!("sha256".parse::<Kind>(), Ok(Kind::Sha256));
+    assert_eq!(Kind::Sha256.len_in_bytes(), 32);
+    assert_eq!(Kind::try_from(2u8), Ok(Kind::Sha256));
+}
@@ -12,6 +12,12 @@ mod from_hex {
         fn twenty_hex_chars_uppercase() {
             assert!(ObjectId::from_hex(b"1234567890ABCDEFAAAAAAAAAAAAAAAAAAAAAAAA").is_ok());
         }
+
+        #[test]
+        fn thirty_two_hex_chars() {
+            let id = ObjectId::from_hex(b"1234567890abcdefaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+            assert_eq!(id.kind(), gix_hash::Kind::Sha256);
+        }
     }
 
     mod invalid {
@@ -120,3 +126,42 @@ mod sha1 {
         assert_eq!(digest, expected);
     }
 }
+
+mod sha256 {
+    use std::str::FromStr as _;
+
+    use gix_hash::{hasher, Kind, ObjectId};
+
+    fn hash_contents(s: &[u8]) -> ObjectId {
+        let mut hasher = hasher(Kind::Sha256);
+        hasher.update(s);
+        hasher.try_finalize().expect("no collision detection for sha256")
+    }
+
+    #[test]
+    fn empty_blob() {
+        assert_eq!(ObjectId::empty_blob(Kind::Sha256), hash_contents(b"blob 0\0"));
+    }
+
+    #[test]
+    fn empty_tree() {
+        assert_eq!(ObjectId::empty_tree(Kind::Sha256), hash_contents(b"tree 0\0"));
+    }
+
+    #[test]
+    fn null_and_round_trip() {
+        let null = Kind::Sha256.null();
+        assert!(null.is_null());
+        assert_eq!(null.kind(), Kind::Sha256);
+
+        let id = hash_contents(b"abc");
+        assert_eq!(
+            id.to_string(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "the test vector of FIPS 180-2"
+        );
+        assert_eq!(ObjectId::from_str(&id.to_string()).unwrap(), id);
+        assert_eq!(id.as_ref().to_owned(), id);
+        assert_eq!(ObjectId::try_from(id.as_slice()).unwrap(), id);
+    }
+}
@@ -553,6 +553,11 @@ impl State {
     pub fn tree(&self) -> Option<&extension::Tree> {
         self.tree.as_ref()
     }
+    /// Access the `tree` extension mutably, for instance to [invalidate](extension::Tree::invalidate_path()) paths
+    /// whose entries were changed.
+    pub fn tree_mut(&mut self) -> Option<&mut extension::Tree> {
+        self.tree.as_mut()
+    }
     /// Remove the `tree` extension.
     pub fn remove_tree(&mut self) -> Option<extension::Tree> {
         self.tree.take()
@@ -0,0 +1,38 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::extension::Tree;
+
+impl Tree {
+    /// Mark this tree and all trees leading to `path` as invalid, which must be done whenever the entry at `path` is
+    /// added, changed or removed.
+    ///
+    /// `path` is relative to this tree, and if it names a subtree, the subtree is removed entirely as it doesn't
+    /// necessarily exist anymore.
+    pub fn invalidate_path(&mut self, path: &BStr) {
+        let mut tree = self;
+        let mut path = path;
+        loop {
+            tree.num_entries = None;
+            match path.find_byte(b'/') {
+                Some(pos) => {
+                    let (name, rest) = (&path[..pos], &path[pos + 1..]);
+                    match tree.children.iter_mut().find(|child| child.name.as_slice() == name) {
+                        Some(child) if !rest.is_empty() => {
+                            tree = child;
+                            path = rest.as_bstr();
+                        }
+                        Some(child) => {
+                            child.num_entries = None;
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+                None => {
+                    tree.children.retain(|child| child.name.as_slice() != path.as_bytes());
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -11,6 +11,8 @@ pub use decode::decode;
 
 mod write;
 
+mod invalidate;
+
 #[cfg(test)]
 mod tests {
     use gix_testtools::size_ok;
@@ -18,7 +20,7 @@ mod tests {
     #[test]
     fn size_of_tree() {
         let actual = std::mem::size_of::<crate::extension::Tree>();
-        let expected = 88;
+        let expected = 104;
         assert!(
             size_ok(actual, expected),
             "the size of this structure should not change unexpectedly: {actual} <~ {expected}"
@@ -0,0 +1,168 @@
+use bstr::{BString, ByteSlice};
+use smallvec::SmallVec;
+
+use crate::{
+    entry::{Flags, Stage},
+    extension, Entry, PathStorageRef, State,
+};
+
+/// The error returned by [`State::write_tree()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The entry at '{path}' has unresolved conflicts and can't be written to a tree")]
+    Unmerged { path: BString },
+    #[error("The entry at '{path}' has mode {mode:o} which can't be stored in a tree")]
+    InvalidMode { path: BString, mode: u32 },
+    #[error(transparent)]
+    Hash(#[from] gix_hash::hasher::Error),
+    #[error(transparent)]
+    Encode(#[from] std::io::Error),
+    #[error(transparent)]
+    WriteObject(#[from] gix_object::write::Error),
+}
+
+impl State {
+    /// Write all entries of this index as trees into `objects`, and return the id of the root tree, similar to `git write-tree`.
+    ///
+    /// Trees whose sections are still valid in the [`tree` extension](Self::tree()) are reused as long as they exist
+    /// in `objects`, so only trees containing changes have to be written.
+    /// Afterwards, the `tree` extension is created or updated to represent all written trees.
+    ///
+    /// Entries marked with [`Flags::INTENT_TO_ADD`] or [`Flags::REMOVE`] are skipped, and the trees containing them
+    /// remain invalid in the `tree` extension.
+    /// Entries with unresolved conflicts cause an error.
+    pub fn write_tree(
+        &mut self,
+        objects: impl gix_object::Write + gix_object::Exists,
+    ) -> Result<gix_hash::ObjectId, Error> {
+        let _span = gix_features::trace::coarse!("gix_index::State::write_tree()");
+        let mut root = self.tree.take().unwrap_or_else(|| extension::Tree {
+            name: SmallVec::new(),
+            id: gix_hash::ObjectId::null(self.object_hash),
+            num_entries: None,
+            children: Vec::new(),
+        });
+        let mut buf = Vec::new();
+        let res = write_recursive(
+            &self.entries,
+            &self.path_backing,
+            0,
+            &mut root,
+            self.object_hash,
+            &objects,
+            &mut buf,
+        );
+        let id = root.id;
+        self.tree = res.is_ok().then_some(root);
+        res.map(|_| id)
+    }
+}
+
+/// Write the tree of `node`, which contains all entries at the beginning of `entries` whose path is at least
+/// `prefix_len` bytes long and shares its prefix with the first entry.
+///
+/// Return the amount of consumed entries, and `true` if the tree ended up being empty.
+fn write_recursive(
+    entries: &[Entry],
+    path_backing: &PathStorageRef,
+    prefix_len: usize,
+    node: &mut extension::Tree,
+    object_hash: gix_hash::Kind,
+    objects: &(impl gix_object::Write + gix_object::Exists),
+    buf: &mut Vec<u8>,
+) -> Result<(usize, bool), Error> {
+    let prefix = match entries.first() {
+        Some(entry) => &entry.path_in(path_backing)[..prefix_len],
+        None => &[][..],
+    };
+    let in_tree = |entry: &Entry| entry.path_in(path_backing).starts_with(prefix);
+
+    if let Some(num_entries) = node.num_entries.map(|n| n as usize) {
+        let is_within_bounds =
+            num_entries <= entries.len() && entries.get(num_entries).map_or(true, |entry| !in_tree(entry));
+        if is_within_bounds && num_entries > 0 && objects.exists(&node.id) {
+            return Ok((num_entries, false));
+        }
+    }
+
+    let mut previous_children = std::mem::take(&mut node.children);
+    let mut tree = gix_object::Tree::empty();
+    let mut is_valid = true;
+    let mut idx = 0;
+    while let Some(entry) = entries.get(idx).filter(|entry| in_tree(entry)) {
+        let path = entry.path_in(path_backing);
+        let relative_path = &path[prefix_len..];
+        match relative_path.find_byte(b'/') {
+            Some(pos) if pos + 1 < relative_path.len() => {
+                let name = &relative_path[..pos];
+                let mut child = match previous_children.iter().position(|child| child.name.as_slice() == name) {
+                    Some(child_idx) => previous_children.swap_remove(child_idx),
+                    None => extension::Tree {
+                        name: name.as_bytes().into(),
+                        id: gix_hash::ObjectId::null(object_hash),
+                        num_entries: None,
+                        children: Vec::new(),
+                    },
+                };
+                let (consumed, is_empty) = write_recursive(
+                    &entries[idx..],
+                    path_backing,
+                    prefix_len + pos + 1,
+                    &mut child,
+                    object_hash,
+                    objects,
+                    buf,
+                )?;
+                idx += consumed;
+                is_valid &= child.num_entries.is_some();
+                if !is_empty {
+                    tree.entries.push(gix_object::tree::Entry {
+                        mode: gix_object::tree::EntryKind::Tree.into(),
+                        filename: name.into(),
+                        oid: child.id,
+                    });
+                    node.children.push(child);
+                }
+            }
+            _ => {
+                idx += 1;
+                if entry.flags.intersects(Flags::INTENT_TO_ADD | Flags::REMOVE) {
+                    is_valid = false;
+                    continue;
+                }
+                if entry.stage() != Stage::Unconflicted {
+                    return Err(Error::Unmerged { path: path.into() });
+                }
+                let mode = entry.mode.to_tree_entry_mode().ok_or_else(|| Error::InvalidMode {
+                    path: path.into(),
+                    mode: entry.mode.bits(),
+                })?;
+                let filename = relative_path.strip_suffix(b"/").unwrap_or(relative_path);
+                tree.entries.push(gix_object::tree::Entry {
+                    mode,
+                    filename: filename.into(),
+                    oid: entry.id,
+                });
+            }
+        }
+    }
+
+    node.children.sort_by(|a, b| a.name.cmp(&b.name));
+    node.num_entries = is_valid.then_some(idx as u32);
+    let is_empty = tree.entries.is_empty();
+    if is_empty && prefix_len != 0 {
+        return Ok((idx, true));
+    }
+
+    tree.entries.sort();
+    buf.clear();
+    gix_object::WriteTo::write_to(&tree, &mut *buf)?;
+    let id = gix_object::compute_hash(object_hash, gix_object::Kind::Tree, buf)?;
+    node.id = if objects.exists(&id) {
+        id
+    } else {
+        objects.write_buf(gix_object::Kind::Tree, buf)?
+    };
+    Ok((idx, is_empty))
+}
@@ -323,3 +323,33 @@ fn check_prefix(index: &gix_index::State, prefix: &str, expected: &[&str]) {
         "{prefix:?}"
     );
 }
+
+#[test]
+fn tree_invalidate_path() {
+    let mut file = Fixture::Generated("v2_deeper_tree").open();
+    let tree = file.tree_mut().expect("tree extension is present");
+    let names = |tree: &gix_index::extension::Tree| {
+        tree.children
+            .iter()
+            .map(|child| child.name.as_bstr().to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(tree), ["d", "sub"]);
+    assert!(tree.num_entries.is_some());
+
+    tree.invalidate_path("d/nested/1".into());
+    assert_eq!(tree.num_entries, None);
+    let d = &tree.children[0];
+    assert_eq!(d.num_entries, None);
+    assert_eq!(
+        d.children[0].num_entries, None,
+        "all trees leading to the path are invalid"
+    );
+    assert!(tree.children[1].num_entries.is_some(), "other trees remain valid");
+
+    tree.invalidate_path("sub".into());
+    assert_eq!(names(tree), ["d"], "trees are removed entirely if they are replaced");
+
+    tree.invalidate_path("does-not-exist/file".into());
+    assert_eq!(names(tree), ["d"]);
+}
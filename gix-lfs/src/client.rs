@@ -0,0 +1,359 @@
+use std::{
+    borrow::Cow,
+    io::{BufRead, Read, Write},
+};
+
+use base64::Engine;
+use gix_transport::client::http::{self, GetResponse, Http, PostBodyDataKind, PostResponse};
+
+use crate::{store, Pointer, Store};
+
+/// The media type of requests to and responses from the batch API.
+pub const MEDIA_TYPE: &str = "application/vnd.git-lfs+json";
+
+/// A function to obtain credentials for the LFS server, and to store or erase them depending on their validity.
+pub type AuthenticateFn =
+    dyn FnMut(gix_credentials::helper::Action) -> gix_credentials::protocol::Result + Send + 'static;
+
+/// The types of the [batch API](https://github.com/git-lfs/git-lfs/blob/main/docs/api/batch.md).
+pub mod batch {
+    use std::collections::BTreeMap;
+
+    use crate::Pointer;
+
+    /// What to do with the objects of a batch request.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Operation {
+        /// Obtain objects from the server.
+        Download,
+        /// Send objects to the server.
+        Upload,
+    }
+
+    /// A request for actions to perform on objects.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Request {
+        /// What to do with the objects.
+        pub operation: Operation,
+        /// The transfer adapters supported by the client, in order of preference.
+        pub transfers: Vec<String>,
+        /// The reference the objects belong to, if known.
+        #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+        pub reference: Option<Reference>,
+        /// The objects to perform the operation on.
+        pub objects: Vec<Object>,
+        /// The hash algorithm used for object ids.
+        pub hash_algo: String,
+    }
+
+    /// A reference name in a [`Request`].
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Reference {
+        /// The full name of the reference.
+        pub name: String,
+    }
+
+    /// An object as identified in a [`Request`].
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Object {
+        /// The hexadecimal SHA256 hash of the object.
+        pub oid: String,
+        /// The size of the object in bytes.
+        pub size: u64,
+    }
+
+    /// The server's answer to a [`Request`].
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Response {
+        /// The transfer adapter chosen by the server, or `basic` if unset.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub transfer: Option<String>,
+        /// What to do with each of the requested objects.
+        pub objects: Vec<ObjectResponse>,
+    }
+
+    /// The actions to perform on an object, or the reason why that isn't possible.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct ObjectResponse {
+        /// The hexadecimal SHA256 hash of the object.
+        pub oid: String,
+        /// The size of the object in bytes.
+        pub size: u64,
+        /// If `true`, the actions don't need additional authentication.
+        #[serde(default)]
+        pub authenticated: bool,
+        /// The actions to perform, which are empty if there is nothing to do.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub actions: Option<Actions>,
+        /// The reason why the operation can't be performed on this object.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub error: Option<ObjectError>,
+    }
+
+    /// The actions to perform for an object.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Actions {
+        /// How to download the object.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub download: Option<Action>,
+        /// How to upload the object.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub upload: Option<Action>,
+        /// How to confirm an upload.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub verify: Option<Action>,
+    }
+
+    /// A request to perform for an object.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Action {
+        /// The URL to send the request to.
+        pub href: String,
+        /// Additional headers to send with the request.
+        #[serde(default)]
+        pub header: BTreeMap<String, String>,
+    }
+
+    /// The reason an operation can't be performed on an object.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct ObjectError {
+        /// An HTTP-like status code, like 404 if the object doesn't exist.
+        pub code: u32,
+        /// A description of the problem.
+        pub message: String,
+    }
+
+    impl Request {
+        /// Create a request to perform `operation` on the objects of `pointers` using the `basic` transfer adapter.
+        pub fn new<'a>(operation: Operation, pointers: impl IntoIterator<Item = &'a Pointer>) -> Self {
+            Request {
+                operation,
+                transfers: vec!["basic".into()],
+                reference: None,
+                objects: pointers
+                    .into_iter()
+                    .map(|pointer| Object {
+                        oid: pointer.oid.to_string(),
+                        size: pointer.size,
+                    })
+                    .collect(),
+                hash_algo: "sha256".into(),
+            }
+        }
+    }
+}
+
+/// The error returned by the [`Client`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Http(#[from] http::Error),
+    #[error("Failed to communicate with the LFS server")]
+    Io(#[from] std::io::Error),
+    #[error("Could not serialize the request or parse the response")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Credentials(#[from] gix_credentials::protocol::Error),
+    #[error("Credentials provided for \"{url}\" were not accepted by the LFS server")]
+    InvalidCredentials { url: String, source: std::io::Error },
+    #[error("No credentials were returned for \"{url}\"")]
+    EmptyCredentials { url: String },
+    #[error("Will not send credentials in clear text over http")]
+    AuthenticationRefused,
+    #[error("The server can't provide object {oid}: {message} ({code})")]
+    Object { oid: String, code: u32, message: String },
+    #[error("The server chose the unsupported transfer adapter {transfer:?}")]
+    UnsupportedTransfer { transfer: String },
+    #[error("The server didn't say how to download object {oid}")]
+    MissingAction { oid: String },
+    #[error(transparent)]
+    Store(#[from] store::Error),
+}
+
+/// A client for the LFS batch API, which uses `http` to talk to an LFS server at its endpoint URL.
+///
+/// If the server asks for authentication, credentials are obtained through the authentication function, if set.
+pub struct Client<H: Http> {
+    http: H,
+    endpoint: String,
+    identity: Option<gix_sec::identity::Account>,
+    authenticate: Option<Box<AuthenticateFn>>,
+}
+
+/// Lifecycle
+impl<H: Http> Client<H> {
+    /// Create a new instance to talk to the LFS server at `endpoint`, like `https://example.com/repo.git/info/lfs`, via `http`.
+    pub fn new(http: H, endpoint: impl Into<String>) -> Self {
+        Client {
+            http,
+            endpoint: endpoint.into(),
+            identity: None,
+            authenticate: None,
+        }
+    }
+
+    /// Use `authenticate` to obtain credentials if the server requires authentication.
+    ///
+    /// It's typically implemented with [`gix_credentials::builtin()`] or with the credential helpers of a repository.
+    pub fn with_authentication(
+        mut self,
+        authenticate: impl FnMut(gix_credentials::helper::Action) -> gix_credentials::protocol::Result + Send + 'static,
+    ) -> Self {
+        self.authenticate = Some(Box::new(authenticate));
+        self
+    }
+
+    /// Use `identity` for authentication right away.
+    pub fn with_identity(mut self, identity: gix_sec::identity::Account) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+}
+
+/// Access
+impl<H: Http> Client<H> {
+    /// Return the URL of the LFS server.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Return the http implementation, for example to configure it.
+    pub fn http_mut(&mut self) -> &mut H {
+        &mut self.http
+    }
+}
+
+/// Operations
+impl<H: Http> Client<H> {
+    /// Send `request` to the batch API of the server and return its response.
+    ///
+    /// If the server asks for authentication and an [authentication function](Self::with_authentication()) is set,
+    /// credentials are obtained and the request is repeated with them.
+    /// Credentials are stored if they are accepted, and erased otherwise.
+    #[allow(clippy::result_large_err)]
+    pub fn batch(&mut self, request: &batch::Request) -> Result<batch::Response, Error> {
+        let body = serde_json::to_vec(request)?;
+        match self.post_batch(&body) {
+            Err(Error::Io(err))
+                if err.kind() == std::io::ErrorKind::PermissionDenied && self.authenticate.is_some() =>
+            {
+                let authenticate = self.authenticate.as_mut().expect("checked");
+                let url = self.endpoint.clone();
+                let gix_credentials::protocol::Outcome { identity, next } =
+                    authenticate(gix_credentials::helper::Action::get_for_url(url.clone()))?
+                        .ok_or_else(|| Error::EmptyCredentials { url: url.clone() })?;
+                self.identity = Some(identity);
+                let res = self.post_batch(&body);
+                let authenticate = self.authenticate.as_mut().expect("checked");
+                match res {
+                    Ok(response) => {
+                        authenticate(next.store())?;
+                        Ok(response)
+                    }
+                    Err(Error::Io(err)) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+                        authenticate(next.erase())?;
+                        Err(Error::InvalidCredentials { url, source: err })
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            res => res,
+        }
+    }
+
+    /// Download the objects of all `pointers` that aren't in `store` yet, and write them into it.
+    ///
+    /// The operation fails if any of the objects can't be downloaded or doesn't match its pointer.
+    #[allow(clippy::result_large_err)]
+    pub fn download<'a>(
+        &mut self,
+        pointers: impl IntoIterator<Item = &'a Pointer>,
+        store: &Store,
+    ) -> Result<(), Error> {
+        let missing: Vec<_> = pointers.into_iter().filter(|p| !store.contains(p)).collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let response = self.batch(&batch::Request::new(
+            batch::Operation::Download,
+            missing.iter().copied(),
+        ))?;
+        if let Some(transfer) = response.transfer.filter(|transfer| transfer != "basic") {
+            return Err(Error::UnsupportedTransfer { transfer });
+        }
+        for pointer in missing {
+            let oid = pointer.oid.to_string();
+            let object = response
+                .objects
+                .iter()
+                .find(|object| object.oid == oid)
+                .ok_or_else(|| Error::MissingAction { oid: oid.clone() })?;
+            if let Some(err) = &object.error {
+                return Err(Error::Object {
+                    oid,
+                    code: err.code,
+                    message: err.message.clone(),
+                });
+            }
+            let action = object
+                .actions
+                .as_ref()
+                .and_then(|actions| actions.download.as_ref())
+                .ok_or(Error::MissingAction { oid })?;
+            let headers: Vec<_> = action
+                .header
+                .iter()
+                .map(|(name, value)| format!("{name}: {value}"))
+                .collect();
+            let GetResponse { headers, mut body } = self.http.get(&action.href, &action.href, &headers)?;
+            drain(headers)?;
+            store.write_verified(pointer, &mut body)?;
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn post_batch(&mut self, request: &[u8]) -> Result<batch::Response, Error> {
+        let url = format!("{}/objects/batch", self.endpoint.trim_end_matches('/'));
+        let mut headers = vec![
+            Cow::Borrowed(concat!("User-Agent: git/oxide-", env!("CARGO_PKG_VERSION"))),
+            Cow::Owned(format!("Accept: {MEDIA_TYPE}")),
+            Cow::Owned(format!("Content-Type: {MEDIA_TYPE}")),
+        ];
+        if let Some(gix_sec::identity::Account { username, password }) = &self.identity {
+            if url.starts_with("http://") {
+                return Err(Error::AuthenticationRefused);
+            }
+            headers.push(Cow::Owned(format!(
+                "Authorization: Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"))
+            )));
+        }
+        let PostResponse {
+            mut post_body,
+            headers,
+            mut body,
+        } = self.http.post(
+            &url,
+            &self.endpoint,
+            &headers,
+            PostBodyDataKind::BoundedAndFitsIntoMemory,
+        )?;
+        post_body.write_all(request)?;
+        drop(post_body);
+        drain(headers)?;
+        let mut buf = Vec::new();
+        body.read_to_end(&mut buf)?;
+        Ok(serde_json::from_slice(&buf)?)
+    }
+}
+
+/// Read all `headers`, which fails if the server responded with an error status.
+fn drain(headers: impl BufRead) -> std::io::Result<()> {
+    for line in headers.lines() {
+        line?;
+    }
+    Ok(())
+}
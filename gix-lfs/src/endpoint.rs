@@ -0,0 +1,32 @@
+/// Derive the endpoint of the LFS server from the `url` of a remote, like `git-lfs` does.
+///
+/// This is `<url>.git/info/lfs` for `http(s)` URLs, with `ssh` URLs being turned into `https` URLs for the same host and path.
+/// Return `None` if the URL doesn't have an LFS server, like local paths.
+pub fn from_url(url: &gix_url::Url) -> Option<String> {
+    let mut url = url.clone();
+    match url.scheme {
+        gix_url::Scheme::Http | gix_url::Scheme::Https => {}
+        gix_url::Scheme::Ssh => {
+            url.scheme = gix_url::Scheme::Https;
+            url.port = None;
+            url = url.serialize_alternate_form(false);
+            if !url.path.starts_with(b"/") {
+                url.path.insert(0, b'/');
+            }
+        }
+        _ => return None,
+    }
+    url.host()?;
+    url.set_user(None);
+    url.set_password(None);
+    while url.path.len() > 1 && url.path.ends_with(b"/") {
+        url.path.pop();
+    }
+    let has_git_suffix = url.path.ends_with(b".git");
+    let mut endpoint = url.to_bstring().to_string();
+    if !has_git_suffix {
+        endpoint.push_str(".git");
+    }
+    endpoint.push_str("/info/lfs");
+    Some(endpoint)
+}
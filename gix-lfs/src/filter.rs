@@ -0,0 +1,99 @@
+use std::io::Read;
+
+use bstr::BStr;
+use gix_filter::driver::{apply::Context, builtin};
+use gix_hash::ObjectId;
+
+use crate::{pointer, store, Filter, Pointer, Store};
+
+/// A function to download the object of a pointer into the given store, called when smudging a pointer whose object is missing.
+pub type DownloadFn = dyn Fn(&Pointer, &Store) -> Result<(), builtin::Error> + Send + Sync;
+
+/// The error produced by the [`Filter`] when cleaning or smudging files.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Object {oid} isn't present in the LFS store and can't be downloaded")]
+    ObjectMissing { oid: ObjectId },
+    #[error("Failed to download object {oid}")]
+    Download { oid: ObjectId, source: builtin::Error },
+    #[error("Pointer to object {oid} uses extensions, which aren't supported")]
+    Extensions { oid: ObjectId },
+    #[error(transparent)]
+    Store(#[from] store::Error),
+    #[error("Could not read file content")]
+    Io(#[from] std::io::Error),
+}
+
+/// Lifecycle
+impl Filter {
+    /// Create a new instance which keeps objects in `store`.
+    pub fn new(store: Store) -> Self {
+        Filter { store, download: None }
+    }
+
+    /// Use `download` to obtain objects that are missing in the store.
+    pub fn with_download(
+        mut self,
+        download: impl Fn(&Pointer, &Store) -> Result<(), builtin::Error> + Send + Sync + 'static,
+    ) -> Self {
+        self.download = Some(Box::new(download));
+        self
+    }
+}
+
+/// Access
+impl Filter {
+    /// Return the store holding the objects.
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+}
+
+impl gix_filter::driver::Builtin for Filter {
+    fn name(&self) -> &BStr {
+        crate::FILTER_NAME.into()
+    }
+
+    /// Write the content of `src` into the store, and turn it into a pointer. Pointers and empty files are left as they are,
+    /// just like `git-lfs` does it.
+    fn clean(&self, src: &mut dyn Read, _ctx: Context<'_, '_>, out: &mut Vec<u8>) -> Result<(), builtin::Error> {
+        src.take(pointer::MAX_SIZE as u64 + 1)
+            .read_to_end(out)
+            .map_err(Error::from)?;
+        if out.is_empty() || Pointer::from_bytes(out).is_ok() {
+            return Ok(());
+        }
+        let pointer = self.store.write(&mut out.as_slice().chain(src)).map_err(Error::from)?;
+        out.clear();
+        pointer.write_to(out).map_err(Error::from)?;
+        Ok(())
+    }
+
+    /// Replace a pointer in `src` with its object, downloading it if needed. Everything else is left as is.
+    fn smudge(&self, src: &[u8], _ctx: Context<'_, '_>, out: &mut Vec<u8>) -> Result<bool, builtin::Error> {
+        let Ok(pointer) = Pointer::from_bytes(src) else {
+            return Ok(false);
+        };
+        if !pointer.extensions.is_empty() {
+            return Err(Error::Extensions { oid: pointer.oid }.into());
+        }
+        if !self.store.contains(&pointer) {
+            let download = self
+                .download
+                .as_ref()
+                .ok_or(Error::ObjectMissing { oid: pointer.oid })?;
+            download(&pointer, &self.store).map_err(|source| Error::Download {
+                oid: pointer.oid,
+                source,
+            })?;
+        }
+        let mut object = self
+            .store
+            .open(&pointer)
+            .map_err(Error::from)?
+            .ok_or(Error::ObjectMissing { oid: pointer.oid })?;
+        object.read_to_end(out).map_err(Error::from)?;
+        Ok(true)
+    }
+}
@@ -1,2 +1,68 @@
-#![deny(rust_2018_idioms)]
+//! Handle [git large file support](https://github.com/git-lfs/git-lfs/blob/main/docs/spec.md) without requiring `git-lfs`.
+//!
+//! Files tracked by LFS are stored in git as small [pointers](Pointer) that refer to their content by its SHA256 hash,
+//! while the content itself lives in a [local store](Store) below `.git/lfs/objects` and on an LFS server.
+//!
+//! * [`Pointer`] parses and writes pointer files.
+//! * [`Store`] keeps LFS objects locally, in the same layout that `git-lfs` uses.
+//! * [`Filter`] is a built-in `lfs` filter driver for [`gix_filter::Pipeline`], which turns files into pointers and back.
+//! * With the `http-client` feature, [`client::Client`] talks to the LFS batch API to download objects.
+//!
+//! ## Feature Flags
+#![cfg_attr(
+    all(doc, feature = "document-features"),
+    doc = ::document_features::document_features!()
+)]
+#![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg, doc_auto_cfg))]
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+use gix_hash::ObjectId;
+
+/// The name of the filter driver that LFS files are configured with, as in `*.bin filter=lfs`.
+pub const FILTER_NAME: &str = "lfs";
+
+/// A pointer file, which is stored in git in place of the content of a file tracked by LFS.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pointer {
+    /// The SHA256 hash of the content.
+    pub oid: ObjectId,
+    /// The size of the content in bytes.
+    pub size: u64,
+    /// Extensions that were applied to the content, ordered by priority.
+    pub extensions: Vec<pointer::Extension>,
+}
+
+/// A store for LFS objects on disk, typically at `.git/lfs/objects`.
+///
+/// Objects are stored as `ab/cd/abcd…` files named after their SHA256 hash, just like `git-lfs` does.
+#[derive(Debug, Clone)]
+pub struct Store {
+    path: PathBuf,
+}
+
+/// A built-in `lfs` filter driver, which uses a [`Store`] to smudge pointers into files and to clean files into pointers.
+///
+/// Objects that aren't in the store yet are obtained using the [download function](Filter::with_download()), if set.
+pub struct Filter {
+    store: Store,
+    download: Option<Box<filter::DownloadFn>>,
+}
+
+///
+pub mod pointer;
+
+///
+pub mod store;
+
+///
+pub mod filter;
+
+///
+pub mod endpoint;
+
+///
+#[cfg(feature = "http-client")]
+pub mod client;
@@ -0,0 +1,169 @@
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::Pointer;
+
+/// The version line value of pointers written by us.
+pub const VERSION: &str = "https://git-lfs.github.com/spec/v1";
+/// The version line value of pointers written by pre-release versions of `git-lfs`, which we can still read.
+const VERSION_LEGACY: &str = "https://hawser.github.com/spec/v1";
+
+/// Files larger than this amount of bytes are never pointers.
+pub const MAX_SIZE: usize = 1024;
+
+/// An extension that was applied to the content of a file before it was stored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Extension {
+    /// The name of the extension.
+    pub name: BString,
+    /// The SHA256 hash of the content before the extension was applied.
+    pub oid: ObjectId,
+    /// The position of the extension in the chain of extensions, from 0 to 9.
+    pub priority: u8,
+}
+
+///
+pub mod decode {
+    use bstr::BString;
+
+    /// The error returned by [`Pointer::from_bytes()`](crate::Pointer::from_bytes()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Pointers can't be larger than {} bytes, got {size}", super::MAX_SIZE)]
+        TooLarge { size: usize },
+        #[error("Pointer must end with a newline")]
+        MissingNewline,
+        #[error("Line {line:?} isn't a 'key value' pair")]
+        InvalidLine { line: BString },
+        #[error("Unknown pointer version '{version}'")]
+        UnknownVersion { version: BString },
+        #[error("The first line must declare the version")]
+        MissingVersion,
+        #[error("Key '{key}' is unknown")]
+        UnknownKey { key: BString },
+        #[error("Key '{key}' must come after '{previous}'")]
+        UnsortedKey { key: BString, previous: BString },
+        #[error("Hash {value:?} must be a hexadecimal SHA256 hash prefixed with 'sha256:'")]
+        InvalidOid { value: BString },
+        #[error("Size {value:?} isn't a valid number")]
+        InvalidSize { value: BString },
+        #[error("Required key '{key}' is missing")]
+        MissingKey { key: &'static str },
+    }
+}
+
+/// Serialization
+impl Pointer {
+    /// Parse `data` as pointer file.
+    ///
+    /// Note that only well-formed pointers are accepted, to avoid mistaking regular files for pointers.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, decode::Error> {
+        use decode::Error;
+        if data.len() > MAX_SIZE {
+            return Err(Error::TooLarge { size: data.len() });
+        }
+        let data = data.strip_suffix(b"\n").ok_or(Error::MissingNewline)?;
+        let mut lines = data.split_str("\n").map(|line| {
+            line.split_once_str(" ").ok_or_else(|| Error::InvalidLine {
+                line: line.as_bstr().into(),
+            })
+        });
+
+        let (key, version) = lines.next().ok_or(Error::MissingVersion)??;
+        if key != b"version" {
+            return Err(Error::MissingVersion);
+        }
+        if version != VERSION.as_bytes() && version != VERSION_LEGACY.as_bytes() {
+            return Err(Error::UnknownVersion {
+                version: version.as_bstr().into(),
+            });
+        }
+
+        let (mut oid, mut size, mut extensions) = (None, None, Vec::new());
+        let mut previous: Option<&[u8]> = None;
+        for line in lines {
+            let (key, value) = line?;
+            if let Some(previous) = previous.filter(|previous| *previous >= key) {
+                return Err(Error::UnsortedKey {
+                    key: key.as_bstr().into(),
+                    previous: previous.as_bstr().into(),
+                });
+            }
+            previous = Some(key);
+            match key {
+                b"oid" => oid = Some(parse_oid(value)?),
+                b"size" => {
+                    size = Some(
+                        value
+                            .to_str()
+                            .ok()
+                            .filter(|value| value.bytes().all(|b| b.is_ascii_digit()))
+                            .and_then(|value| value.parse().ok())
+                            .ok_or_else(|| Error::InvalidSize {
+                                value: value.as_bstr().into(),
+                            })?,
+                    );
+                }
+                _ => {
+                    let extension = key
+                        .strip_prefix(b"ext-")
+                        .and_then(|rest| match rest {
+                            [priority @ b'0'..=b'9', b'-', name @ ..] if !name.is_empty() => {
+                                Some((*priority - b'0', name))
+                            }
+                            _ => None,
+                        })
+                        .ok_or_else(|| Error::UnknownKey {
+                            key: key.as_bstr().into(),
+                        })?;
+                    extensions.push(Extension {
+                        name: extension.1.into(),
+                        oid: parse_oid(value)?,
+                        priority: extension.0,
+                    });
+                }
+            }
+        }
+
+        Ok(Pointer {
+            oid: oid.ok_or(Error::MissingKey { key: "oid" })?,
+            size: size.ok_or(Error::MissingKey { key: "size" })?,
+            extensions,
+        })
+    }
+
+    /// Write this pointer to `out` in its canonical form.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        writeln!(out, "version {VERSION}")?;
+        let mut extensions: Vec<_> = self.extensions.iter().collect();
+        extensions.sort_by_key(|extension| extension.priority);
+        for extension in extensions {
+            out.write_all(format!("ext-{}-", extension.priority).as_bytes())?;
+            out.write_all(&extension.name)?;
+            writeln!(out, " sha256:{}", extension.oid)?;
+        }
+        writeln!(out, "oid sha256:{}", self.oid)?;
+        writeln!(out, "size {}", self.size)
+    }
+
+    /// Return this pointer in its canonical form.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).expect("writing to memory can't fail");
+        buf
+    }
+}
+
+fn parse_oid(value: &[u8]) -> Result<ObjectId, decode::Error> {
+    value
+        .strip_prefix(b"sha256:")
+        .filter(|hex| {
+            hex.len() == gix_hash::Kind::Sha256.len_in_hex()
+                && hex.iter().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        })
+        .and_then(|hex| ObjectId::from_hex(hex).ok())
+        .ok_or_else(|| decode::Error::InvalidOid {
+            value: value.as_bstr().into(),
+        })
+}
@@ -0,0 +1,137 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use gix_hash::ObjectId;
+
+use crate::{Pointer, Store};
+
+/// The error returned by [`Store`] operations.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not {message} '{}'", path.display())]
+    Io {
+        source: std::io::Error,
+        message: &'static str,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    Hash(#[from] gix_hash::hasher::Error),
+    #[error(
+        "Object should have hash {expected} and size {expected_size}, but had hash {actual} and size {actual_size}"
+    )]
+    Mismatch {
+        expected: ObjectId,
+        expected_size: u64,
+        actual: ObjectId,
+        actual_size: u64,
+    },
+    #[error("Could not move object into place at '{}'", target.display())]
+    Persist {
+        source: tempfile::PersistError,
+        target: PathBuf,
+    },
+}
+
+/// Lifecycle
+impl Store {
+    /// Create a store at `path`, which is typically `.git/lfs/objects`. It will be created when objects are written.
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Store { path: path.into() }
+    }
+}
+
+/// Access
+impl Store {
+    /// Return the directory containing all objects.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return the path to the object with `oid`, which may not exist yet.
+    pub fn object_path(&self, oid: &gix_hash::oid) -> PathBuf {
+        let hex = oid.to_hex().to_string();
+        let mut path = self.path.join(&hex[..2]);
+        path.push(&hex[2..4]);
+        path.push(hex);
+        path
+    }
+
+    /// Return `true` if the object of `pointer` is present.
+    ///
+    /// Note that objects of unexpected size are considered missing.
+    pub fn contains(&self, pointer: &Pointer) -> bool {
+        std::fs::metadata(self.object_path(&pointer.oid)).is_ok_and(|md| md.is_file() && md.len() == pointer.size)
+    }
+
+    /// Open the object of `pointer` for reading, or return `None` if it isn't present.
+    pub fn open(&self, pointer: &Pointer) -> Result<Option<std::fs::File>, Error> {
+        if !self.contains(pointer) {
+            return Ok(None);
+        }
+        let path = self.object_path(&pointer.oid);
+        std::fs::File::open(&path).map(Some).map_err(|source| Error::Io {
+            source,
+            message: "open object at",
+            path,
+        })
+    }
+}
+
+/// Writing
+impl Store {
+    /// Write all of `data` into the store, and return the pointer to it.
+    pub fn write(&self, data: &mut dyn Read) -> Result<Pointer, Error> {
+        let (oid, size, file) = self.write_tempfile(data)?;
+        let pointer = Pointer {
+            oid,
+            size,
+            extensions: Vec::new(),
+        };
+        self.persist(&pointer, file)?;
+        Ok(pointer)
+    }
+
+    /// Write all of `data` into the store if it matches the hash and size of `pointer`, as needed when downloading objects.
+    pub fn write_verified(&self, pointer: &Pointer, data: &mut dyn Read) -> Result<(), Error> {
+        let (oid, size, file) = self.write_tempfile(data)?;
+        if oid != pointer.oid || size != pointer.size {
+            return Err(Error::Mismatch {
+                expected: pointer.oid,
+                expected_size: pointer.size,
+                actual: oid,
+                actual_size: size,
+            });
+        }
+        self.persist(pointer, file)
+    }
+
+    fn write_tempfile(&self, data: &mut dyn Read) -> Result<(ObjectId, u64, tempfile::NamedTempFile), Error> {
+        let io_err = |message| {
+            let path = self.path.clone();
+            move |source| Error::Io { source, message, path }
+        };
+        std::fs::create_dir_all(&self.path).map_err(io_err("create directory"))?;
+        let mut out = gix_hash::io::Write::new(
+            tempfile::NamedTempFile::new_in(&self.path).map_err(io_err("create temporary file in"))?,
+            gix_hash::Kind::Sha256,
+        );
+        let size = std::io::copy(data, &mut out).map_err(io_err("write temporary file in"))?;
+        Ok((out.hash.try_finalize()?, size, out.inner))
+    }
+
+    fn persist(&self, pointer: &Pointer, file: tempfile::NamedTempFile) -> Result<(), Error> {
+        let target = self.object_path(&pointer.oid);
+        let dir = target.parent().expect("objects are in a directory");
+        std::fs::create_dir_all(dir).map_err(|source| Error::Io {
+            source,
+            message: "create directory",
+            path: dir.to_owned(),
+        })?;
+        file.persist(&target)
+            .map_err(|source| Error::Persist { source, target })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,375 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, Cursor, Read},
+    sync::{Arc, Mutex},
+};
+
+use gix_lfs::{
+    client::{self, batch, Client},
+    Store,
+};
+use gix_transport::client::http;
+
+use crate::pointer_for;
+
+const ENDPOINT: &str = "https://example.com/repo.git/info/lfs";
+/// `user:pass` for basic authentication.
+const AUTHORIZATION: &str = "Authorization: Basic dXNlcjpwYXNz";
+
+/// A stand-in for an LFS server, which answers batch requests for its objects and serves their content.
+#[derive(Default)]
+struct Server {
+    objects: BTreeMap<String, Vec<u8>>,
+    /// If set, batch requests must carry this header.
+    authorization: Option<&'static str>,
+    /// All requests as `(method, url, headers)`.
+    requests: Vec<(&'static str, String, Vec<String>)>,
+}
+
+impl Server {
+    fn with_objects<'a>(objects: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        Server {
+            objects: objects
+                .into_iter()
+                .map(|content| (pointer_for(content).oid.to_string(), content.to_vec()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn batch(&self, request: &[u8]) -> Vec<u8> {
+        let request: batch::Request = serde_json::from_slice(request).expect("valid request");
+        assert_eq!(request.operation, batch::Operation::Download);
+        let response = batch::Response {
+            transfer: Some("basic".into()),
+            objects: request
+                .objects
+                .into_iter()
+                .map(|object| match self.objects.get(&object.oid) {
+                    Some(_) => batch::ObjectResponse {
+                        actions: Some(batch::Actions {
+                            download: Some(batch::Action {
+                                href: format!("https://storage.example.com/{}", object.oid),
+                                header: [("Authorization".to_string(), "RemoteAuth token".to_string())].into(),
+                            }),
+                            upload: None,
+                            verify: None,
+                        }),
+                        oid: object.oid,
+                        size: object.size,
+                        authenticated: true,
+                        error: None,
+                    },
+                    None => batch::ObjectResponse {
+                        oid: object.oid,
+                        size: object.size,
+                        authenticated: false,
+                        actions: None,
+                        error: Some(batch::ObjectError {
+                            code: 404,
+                            message: "Object does not exist".into(),
+                        }),
+                    },
+                })
+                .collect(),
+        };
+        serde_json::to_vec(&response).expect("serializable")
+    }
+}
+
+/// An implementation of [`http::Http`] that talks to a [`Server`] in memory.
+#[derive(Clone, Default)]
+struct StandIn(Arc<Mutex<Server>>);
+
+impl StandIn {
+    fn new(server: Server) -> Self {
+        StandIn(Arc::new(Mutex::new(server)))
+    }
+
+    fn requests(&self) -> Vec<(&'static str, String, Vec<String>)> {
+        std::mem::take(&mut self.0.lock().unwrap().requests)
+    }
+}
+
+/// The headers or body of a response, which may fail like a real response with an error status.
+enum Response {
+    Ready(Cursor<Vec<u8>>),
+    Status(std::io::ErrorKind),
+    /// The response to a batch request, which is computed once the request body was written.
+    Batch {
+        server: Arc<Mutex<Server>>,
+        request: Arc<Mutex<Vec<u8>>>,
+    },
+}
+
+impl Read for Response {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.fill_buf()?.read(buf)?;
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for Response {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if let Response::Batch { server, request } = self {
+            let body = server.lock().unwrap().batch(&request.lock().unwrap());
+            *self = Response::Ready(Cursor::new(body));
+        }
+        match self {
+            Response::Ready(cursor) => cursor.fill_buf(),
+            Response::Status(kind) => Err(std::io::Error::new(*kind, "error status")),
+            Response::Batch { .. } => unreachable!("replaced above"),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Response::Ready(cursor) = self {
+            cursor.consume(amt);
+        }
+    }
+}
+
+struct RequestBody(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for RequestBody {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn record(
+    server: &mut Server,
+    method: &'static str,
+    url: &str,
+    headers: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Vec<String> {
+    let headers: Vec<_> = headers.into_iter().map(|h| h.as_ref().to_owned()).collect();
+    server.requests.push((method, url.to_owned(), headers.clone()));
+    headers
+}
+
+impl http::Http for StandIn {
+    type Headers = Response;
+    type ResponseBody = Response;
+    type PostBody = RequestBody;
+
+    fn get(
+        &mut self,
+        url: &str,
+        _base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<http::GetResponse<Self::Headers, Self::ResponseBody>, http::Error> {
+        let mut server = self.0.lock().unwrap();
+        record(&mut server, "GET", url, headers);
+        let content = url
+            .strip_prefix("https://storage.example.com/")
+            .and_then(|oid| server.objects.get(oid));
+        Ok(match content {
+            Some(content) => http::GetResponse {
+                headers: Response::Ready(Cursor::new(Vec::new())),
+                body: Response::Ready(Cursor::new(content.clone())),
+            },
+            None => http::GetResponse {
+                headers: Response::Status(std::io::ErrorKind::Other),
+                body: Response::Status(std::io::ErrorKind::Other),
+            },
+        })
+    }
+
+    fn post(
+        &mut self,
+        url: &str,
+        _base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+        _body: http::PostBodyDataKind,
+    ) -> Result<http::PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
+        let mut server = self.0.lock().unwrap();
+        let headers = record(&mut server, "POST", url, headers);
+        assert_eq!(url, format!("{ENDPOINT}/objects/batch"));
+        let request = Arc::new(Mutex::new(Vec::new()));
+        let authorized = server
+            .authorization
+            .map_or(true, |expected| headers.iter().any(|h| h == expected));
+        Ok(http::PostResponse {
+            post_body: RequestBody(request.clone()),
+            headers: if authorized {
+                Response::Ready(Cursor::new(Vec::new()))
+            } else {
+                Response::Status(std::io::ErrorKind::PermissionDenied)
+            },
+            body: Response::Batch {
+                server: self.0.clone(),
+                request,
+            },
+        })
+    }
+
+    fn configure(
+        &mut self,
+        _config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+}
+
+#[test]
+fn download() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    let contents: [&[u8]; 2] = [b"first", b"second"];
+    let http = StandIn::new(Server::with_objects(contents));
+    let mut client = Client::new(http.clone(), ENDPOINT);
+    let pointers: Vec<_> = contents.iter().map(|content| pointer_for(content)).collect();
+
+    client.download(&pointers, &store)?;
+    for (pointer, content) in pointers.iter().zip(contents) {
+        assert_eq!(std::fs::read(store.object_path(&pointer.oid))?, content);
+    }
+    let requests = http.requests();
+    assert_eq!(
+        requests.iter().map(|(method, _, _)| *method).collect::<Vec<_>>(),
+        ["POST", "GET", "GET"],
+        "one batch request for all objects"
+    );
+    assert!(requests[0]
+        .2
+        .iter()
+        .any(|h| h == "Accept: application/vnd.git-lfs+json"));
+    assert!(
+        requests[1].2.iter().any(|h| h == "Authorization: RemoteAuth token"),
+        "headers of actions are passed on"
+    );
+
+    client.download(&pointers, &store)?;
+    assert!(http.requests().is_empty(), "objects that are present aren't downloaded");
+    Ok(())
+}
+
+#[test]
+fn download_failures() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    let http = StandIn::new(Server::with_objects([b"present".as_slice()]));
+    let mut client = Client::new(http.clone(), ENDPOINT);
+
+    let missing = pointer_for(b"missing");
+    assert!(matches!(
+        client.download([&missing], &store),
+        Err(client::Error::Object { code: 404, oid, .. }) if oid == missing.oid.to_string()
+    ));
+
+    let present = pointer_for(b"present");
+    http.0
+        .lock()
+        .unwrap()
+        .objects
+        .insert(present.oid.to_string(), b"corrupt".to_vec());
+    assert!(matches!(
+        client.download([&present], &store),
+        Err(client::Error::Store(gix_lfs::store::Error::Mismatch { .. }))
+    ));
+    assert!(!store.contains(&present));
+    Ok(())
+}
+
+type Actions = Arc<Mutex<Vec<&'static str>>>;
+
+fn client_with_credentials(http: StandIn, password: &'static str) -> (Client<StandIn>, Actions) {
+    let actions = Actions::default();
+    let client = Client::new(http, ENDPOINT).with_authentication({
+        let actions = actions.clone();
+        #[allow(clippy::result_large_err)]
+        move |action| {
+            Ok(match action {
+                gix_credentials::helper::Action::Get(ctx) => {
+                    assert_eq!(ctx.url.as_ref().map(ToString::to_string).as_deref(), Some(ENDPOINT));
+                    actions.lock().unwrap().push("get");
+                    Some(gix_credentials::protocol::Outcome {
+                        identity: gix_sec::identity::Account {
+                            username: "user".into(),
+                            password: password.into(),
+                        },
+                        next: ctx.into(),
+                    })
+                }
+                gix_credentials::helper::Action::Store(_) => {
+                    actions.lock().unwrap().push("store");
+                    None
+                }
+                gix_credentials::helper::Action::Erase(_) => {
+                    actions.lock().unwrap().push("erase");
+                    None
+                }
+            })
+        }
+    });
+    (client, actions)
+}
+
+#[test]
+fn authentication() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    let content: &[u8] = b"secret";
+    let pointer = pointer_for(content);
+    let server = || Server {
+        authorization: Some(AUTHORIZATION),
+        ..Server::with_objects([content])
+    };
+
+    let http = StandIn::new(server());
+    assert!(
+        matches!(
+            Client::new(http, ENDPOINT).download([&pointer], &store),
+            Err(client::Error::Io(err)) if err.kind() == std::io::ErrorKind::PermissionDenied
+        ),
+        "without a way to authenticate, the error is passed on"
+    );
+
+    let (mut client, actions) = client_with_credentials(StandIn::new(server()), "wrong");
+    assert!(matches!(
+        client.download([&pointer], &store),
+        Err(client::Error::InvalidCredentials { url, .. }) if url == ENDPOINT
+    ));
+    assert_eq!(
+        *actions.lock().unwrap(),
+        ["get", "erase"],
+        "rejected credentials are erased"
+    );
+
+    let http = StandIn::new(server());
+    let (mut client, actions) = client_with_credentials(http.clone(), "pass");
+    client.download([&pointer], &store)?;
+    assert!(store.contains(&pointer));
+    assert_eq!(
+        *actions.lock().unwrap(),
+        ["get", "store"],
+        "accepted credentials are stored"
+    );
+    assert!(
+        http.requests()[1].2.iter().any(|h| h == AUTHORIZATION),
+        "the request is repeated with credentials"
+    );
+    Ok(())
+}
+
+#[test]
+fn requests_have_the_expected_shape() -> crate::Result {
+    let request = batch::Request::new(batch::Operation::Download, [&pointer_for(b"a")]);
+    assert_eq!(
+        serde_json::to_value(&request)?,
+        serde_json::json!({
+            "operation": "download",
+            "transfers": ["basic"],
+            "objects": [{"oid": "ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb", "size": 1}],
+            "hash_algo": "sha256",
+        })
+    );
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use bstr::ByteSlice;
+use gix_filter::driver::{apply::Context, Builtin};
+use gix_lfs::{filter, pointer, Filter, Pointer, Store};
+
+use crate::pointer_for;
+
+fn ctx() -> Context<'static, 'static> {
+    Context {
+        rela_path: "file.bin".into(),
+        ref_name: None,
+        treeish: None,
+        blob: None,
+    }
+}
+
+#[test]
+fn clean_and_smudge() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let filter = Filter::new(Store::at(tmp.path()));
+    assert_eq!(filter.name(), "lfs");
+
+    let content = vec![b'x'; pointer::MAX_SIZE * 3];
+    let mut out = Vec::new();
+    filter.clean(&mut content.as_slice(), ctx(), &mut out)?;
+    let pointer = Pointer::from_bytes(&out)?;
+    assert_eq!(pointer, pointer_for(&content));
+    assert!(filter.store().contains(&pointer), "the content was stored");
+
+    let mut cleaned_again = Vec::new();
+    filter.clean(&mut out.as_slice(), ctx(), &mut cleaned_again)?;
+    assert_eq!(cleaned_again, out, "pointers are left as they are");
+
+    let mut smudged = Vec::new();
+    assert!(filter.smudge(&out, ctx(), &mut smudged)?);
+    assert_eq!(smudged, content);
+
+    let mut empty = Vec::new();
+    filter.clean(&mut [].as_slice(), ctx(), &mut empty)?;
+    assert!(empty.is_empty(), "empty files aren't turned into pointers");
+    assert!(
+        !filter.smudge(b"not a pointer\n", ctx(), &mut Vec::new())?,
+        "everything but pointers is left as is"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_objects_are_downloaded() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let pointer = pointer_for(b"remote content");
+    let filter = Filter::new(Store::at(tmp.path()));
+    let err = filter
+        .smudge(&pointer.to_bytes(), ctx(), &mut Vec::new())
+        .expect_err("not present and no way to download it");
+    assert!(matches!(
+        err.downcast_ref::<filter::Error>(),
+        Some(filter::Error::ObjectMissing { oid }) if *oid == pointer.oid
+    ));
+
+    let calls = Arc::new(AtomicUsize::default());
+    let filter = Filter::new(Store::at(tmp.path())).with_download({
+        let calls = calls.clone();
+        move |pointer, store| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            store.write_verified(pointer, &mut b"remote content".as_slice())?;
+            Ok(())
+        }
+    });
+    for _ in 0..2 {
+        let mut out = Vec::new();
+        assert!(filter.smudge(&pointer.to_bytes(), ctx(), &mut out)?);
+        assert_eq!(out.as_bstr(), "remote content");
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 1, "objects are only downloaded once");
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use bstr::ByteSlice;
+use gix_lfs::{pointer, Pointer};
+
+use crate::hex_to_id;
+
+const SPEC_EXAMPLE: &str = "version https://git-lfs.github.com/spec/v1
+oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+size 12345
+";
+
+#[test]
+fn round_trip() -> crate::Result {
+    let pointer = Pointer::from_bytes(SPEC_EXAMPLE.as_bytes())?;
+    assert_eq!(
+        pointer,
+        Pointer {
+            oid: hex_to_id("4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393"),
+            size: 12345,
+            extensions: Vec::new(),
+        }
+    );
+    assert_eq!(pointer.to_bytes().as_bstr(), SPEC_EXAMPLE);
+
+    let legacy = SPEC_EXAMPLE.replace("git-lfs.github.com", "hawser.github.com");
+    assert_eq!(
+        Pointer::from_bytes(legacy.as_bytes())?,
+        pointer,
+        "pointers written by pre-release versions can be read, but are written in the current version"
+    );
+    Ok(())
+}
+
+#[test]
+fn extensions() -> crate::Result {
+    let input = "version https://git-lfs.github.com/spec/v1
+ext-0-foo sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+ext-1-bar sha256:eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee
+oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+size 12345
+";
+    let pointer = Pointer::from_bytes(input.as_bytes())?;
+    assert_eq!(
+        pointer.extensions,
+        [
+            pointer::Extension {
+                name: "foo".into(),
+                oid: hex_to_id("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+                priority: 0,
+            },
+            pointer::Extension {
+                name: "bar".into(),
+                oid: hex_to_id("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee"),
+                priority: 1,
+            }
+        ]
+    );
+    assert_eq!(pointer.to_bytes().as_bstr(), input);
+    Ok(())
+}
+
+#[test]
+fn invalid() {
+    let oid = "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393";
+    for (input, expected) in [
+        (SPEC_EXAMPLE.trim_end().to_owned(), "Pointer must end with a newline"),
+        (
+            format!("oid sha256:{oid}\nsize 1\n"),
+            "The first line must declare the version",
+        ),
+        (
+            format!("version https://example.com/v2\noid sha256:{oid}\nsize 1\n"),
+            "Unknown pointer version 'https://example.com/v2'",
+        ),
+        (
+            format!("version https://git-lfs.github.com/spec/v1\nsize 1\noid sha256:{oid}\n"),
+            "Key 'oid' must come after 'size'",
+        ),
+        (
+            format!("version https://git-lfs.github.com/spec/v1\nauthor me\noid sha256:{oid}\nsize 1\n"),
+            "Key 'author' is unknown",
+        ),
+        (
+            format!(
+                "version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize 1\n",
+                oid.to_uppercase()
+            ),
+            "must be a hexadecimal SHA256 hash",
+        ),
+        (
+            format!("version https://git-lfs.github.com/spec/v1\noid sha256:{oid}\nsize -1\n"),
+            "Size \"-1\" isn't a valid number",
+        ),
+        (
+            "version https://git-lfs.github.com/spec/v1\nsize 1\n".into(),
+            "Required key 'oid' is missing",
+        ),
+        (SPEC_EXAMPLE.repeat(20), "Pointers can't be larger than 1024 bytes"),
+        ("version\n".into(), "isn't a 'key value' pair"),
+    ] {
+        let err = Pointer::from_bytes(input.as_bytes()).expect_err("invalid");
+        assert!(
+            err.to_string().contains(expected),
+            "{input:?}: expected '{expected}' in '{err}'"
+        );
+    }
+}
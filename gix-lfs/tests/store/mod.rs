@@ -0,0 +1,51 @@
+use gix_lfs::{store, Store};
+
+use crate::{hex_to_id, pointer_for};
+
+#[test]
+fn write_and_read() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path().join("lfs").join("objects"));
+    let pointer = store.write(&mut b"hello world".as_slice())?;
+    assert_eq!(
+        pointer.oid,
+        hex_to_id("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+    );
+    assert_eq!(pointer.size, 11);
+    assert_eq!(
+        store.object_path(&pointer.oid),
+        store
+            .path()
+            .join("b9")
+            .join("4d")
+            .join("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"),
+        "the layout is the same as the one of git-lfs"
+    );
+    assert!(store.contains(&pointer));
+    assert_eq!(
+        std::io::read_to_string(store.open(&pointer)?.expect("present"))?,
+        "hello world"
+    );
+
+    let mut wrong_size = pointer.clone();
+    wrong_size.size += 1;
+    assert!(!store.contains(&wrong_size), "objects must have the expected size");
+    assert!(store.open(&wrong_size)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn write_verified() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path());
+    let pointer = pointer_for(b"content");
+    assert!(matches!(
+        store.write_verified(&pointer, &mut b"other content".as_slice()),
+        Err(store::Error::Mismatch { expected, actual_size: 13, .. }) if expected == pointer.oid
+    ));
+    assert!(!store.contains(&pointer), "nothing is stored on mismatch");
+
+    store.write_verified(&pointer, &mut b"content".as_slice())?;
+    assert!(store.contains(&pointer));
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use bstr::{BString, ByteSlice};
+
+/// Return `ours` followed by `theirs`, separated by an empty line, or just one of them if the other one is empty.
+///
+/// This is used when appending to a note and by the [`Union`](crate::merge::Strategy::Union) merge strategy.
+pub fn concatenate(ours: &[u8], theirs: &[u8]) -> BString {
+    if theirs.is_empty() {
+        return ours.into();
+    }
+    if ours.is_empty() {
+        return theirs.into();
+    }
+    let mut out = BString::from(ours.strip_suffix(b"\n").unwrap_or(ours));
+    out.extend_from_slice(b"\n\n");
+    out.extend_from_slice(theirs);
+    out
+}
+
+/// Return all non-empty lines of `ours` and `theirs`, sorted and without duplicates.
+///
+/// This is used by the [`CatSortUniq`](crate::merge::Strategy::CatSortUniq) merge strategy.
+pub fn cat_sort_uniq(ours: &[u8], theirs: &[u8]) -> BString {
+    let mut lines: Vec<_> = ours
+        .lines()
+        .chain(theirs.lines())
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.sort();
+    lines.dedup();
+    let mut out = BString::default();
+    for line in lines {
+        out.extend_from_slice(line);
+        out.push(b'\n');
+    }
+    out
+}
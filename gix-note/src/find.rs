@@ -0,0 +1,48 @@
+/// The error returned by [`find()`](crate::find()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindTree(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    DecodeTree(#[from] gix_object::decode::Error),
+}
+
+pub(crate) mod function {
+    use gix_hash::{oid, ObjectId};
+    use gix_object::FindExt;
+
+    use super::Error;
+
+    /// Return the id of the note blob for `object` in the notes tree `tree`, or `None` if it has no note,
+    /// looking up `tree` and its subtrees in `objects`.
+    ///
+    /// Only the subtrees on the way to the note are read, no matter how many notes there are.
+    pub fn find(tree: &oid, object: &oid, objects: &impl gix_object::Find) -> Result<Option<ObjectId>, Error> {
+        let hex = object.to_hex().to_string();
+        let mut consumed = 0;
+        let mut tree = tree.to_owned();
+        let mut buf = Vec::new();
+        loop {
+            let remaining = &hex[consumed..];
+            let mut subtree = None;
+            for entry in objects.find_tree_iter(&tree, &mut buf)? {
+                let entry = entry?;
+                if !entry.mode.is_tree() {
+                    if entry.filename == remaining {
+                        return Ok(Some(entry.oid.to_owned()));
+                    }
+                } else if remaining.len() > 2 && entry.filename == &remaining[..2] {
+                    subtree = Some(entry.oid.to_owned());
+                }
+            }
+            match subtree {
+                Some(id) => {
+                    tree = id;
+                    consumed += 2;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
@@ -1,2 +1,54 @@
-#![deny(rust_2018_idioms)]
+//! Read, write and merge [git notes](https://git-scm.com/docs/git-notes), which associate content with objects without
+//! changing them.
+//!
+//! Notes are stored as blobs in a tree that is referenced by commits, typically at [`refs/notes/commits`](DEFAULT_REF).
+//! The note of an object is stored under the hexadecimal id of the object, which may be split into directories named
+//! after its leading bytes, like `ab/cdef…`. This *fanout* keeps trees small once there are many notes,
+//! and is adjusted automatically when [writing](Notes::write_to()).
+//!
+//! * [find()] looks up a single note without reading the whole tree.
+//! * [`Notes`] holds all notes of a tree in memory to edit them.
+//! * [merge()] performs a three-way merge of notes trees, resolving conflicts according to a [`merge::Strategy`].
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+use std::collections::BTreeMap;
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The reference holding notes if nothing else is configured.
+pub const DEFAULT_REF: &str = "refs/notes/commits";
+
+/// All notes of a notes tree, held in memory for editing.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Notes {
+    /// The id of the note blob for each annotated object.
+    pub by_object: BTreeMap<ObjectId, ObjectId>,
+    /// Entries of the notes tree that aren't notes, which are retained when writing.
+    pub other: Vec<Entry>,
+}
+
+/// An entry of a notes tree that isn't a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The slash-separated path of the entry relative to the root of the notes tree.
+    pub path: BString,
+    /// The kind of the entry.
+    pub mode: gix_object::tree::EntryMode,
+    /// The id of the object the entry points to.
+    pub id: ObjectId,
+}
+
+///
+pub mod combine;
+///
+pub mod find;
+pub use find::function::find;
+///
+pub mod merge;
+pub use merge::function::merge;
+///
+pub mod read;
+///
+pub mod write;
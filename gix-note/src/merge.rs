@@ -0,0 +1,156 @@
+use gix_hash::ObjectId;
+
+use crate::Notes;
+
+/// How to resolve notes of the same object that were changed differently on both sides.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Don't resolve the conflict, but report it so it can be resolved by hand.
+    #[default]
+    Manual,
+    /// Use our note.
+    Ours,
+    /// Use their note.
+    Theirs,
+    /// Use both notes, ours followed by theirs, as [concatenated](crate::combine::concatenate()).
+    Union,
+    /// Use the sorted and unique lines of both notes, as [combined](crate::combine::cat_sort_uniq()).
+    CatSortUniq,
+}
+
+impl Strategy {
+    /// Parse the strategy from its `name` as used in the `notes.mergeStrategy` configuration, like `cat_sort_uniq`.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"manual" => Strategy::Manual,
+            b"ours" => Strategy::Ours,
+            b"theirs" => Strategy::Theirs,
+            b"union" => Strategy::Union,
+            b"cat_sort_uniq" => Strategy::CatSortUniq,
+            _ => return None,
+        })
+    }
+
+    /// Return the name of the strategy as used in the `notes.mergeStrategy` configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Strategy::Manual => "manual",
+            Strategy::Ours => "ours",
+            Strategy::Theirs => "theirs",
+            Strategy::Union => "union",
+            Strategy::CatSortUniq => "cat_sort_uniq",
+        }
+    }
+}
+
+/// The note of an object that was changed differently on both sides and couldn't be resolved with [`Strategy::Manual`].
+///
+/// A side that is `None` has no note for the object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The annotated object.
+    pub object: ObjectId,
+    /// The note in the merge-base.
+    pub base: Option<ObjectId>,
+    /// Our note, which is the one in the merged notes.
+    pub ours: Option<ObjectId>,
+    /// Their note.
+    pub theirs: Option<ObjectId>,
+}
+
+/// The result of [`merge()`](crate::merge()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The merged notes.
+    pub notes: Notes,
+    /// All conflicts that weren't resolved, which can only happen with [`Strategy::Manual`].
+    pub conflicts: Vec<Conflict>,
+}
+
+/// The error returned by [`merge()`](crate::merge()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindBlob(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    WriteBlob(#[from] gix_object::write::Error),
+}
+
+pub(crate) mod function {
+    use std::collections::BTreeSet;
+
+    use gix_hash::ObjectId;
+    use gix_object::FindExt;
+
+    use super::{Conflict, Error, Outcome, Strategy};
+    use crate::{combine, Notes};
+
+    /// Merge the notes of `ours` and `theirs`, which both derive from `base`, and resolve notes that were changed
+    /// differently on both sides according to `strategy`, with note blobs read from and written to `objects`.
+    ///
+    /// Entries that aren't notes are taken from `ours`.
+    pub fn merge(
+        base: &Notes,
+        ours: &Notes,
+        theirs: &Notes,
+        strategy: Strategy,
+        objects: &(impl gix_object::Find + gix_object::Write),
+    ) -> Result<Outcome, Error> {
+        let mut notes = ours.clone();
+        let mut conflicts = Vec::new();
+        let mut buf = Vec::new();
+        let all_objects: BTreeSet<_> = base
+            .by_object
+            .keys()
+            .chain(ours.by_object.keys())
+            .chain(theirs.by_object.keys())
+            .copied()
+            .collect();
+        for object in all_objects {
+            let base = base.by_object.get(&object).copied();
+            let our_note = ours.by_object.get(&object).copied();
+            let their_note = theirs.by_object.get(&object).copied();
+            let merged = if our_note == their_note || their_note == base {
+                continue;
+            } else if our_note == base {
+                their_note
+            } else {
+                match strategy {
+                    Strategy::Manual => {
+                        conflicts.push(Conflict {
+                            object,
+                            base,
+                            ours: our_note,
+                            theirs: their_note,
+                        });
+                        continue;
+                    }
+                    Strategy::Ours => continue,
+                    Strategy::Theirs => their_note,
+                    Strategy::Union | Strategy::CatSortUniq => {
+                        let ours = content(our_note, objects, &mut buf)?;
+                        let theirs = content(their_note, objects, &mut buf)?;
+                        let content = match strategy {
+                            Strategy::Union => combine::concatenate(&ours, &theirs),
+                            _ => combine::cat_sort_uniq(&ours, &theirs),
+                        };
+                        Some(objects.write_buf(gix_object::Kind::Blob, &content)?)
+                    }
+                }
+            };
+            match merged {
+                Some(note) => notes.by_object.insert(object, note),
+                None => notes.by_object.remove(&object),
+            };
+        }
+        Ok(Outcome { notes, conflicts })
+    }
+
+    fn content(note: Option<ObjectId>, objects: &impl gix_object::Find, buf: &mut Vec<u8>) -> Result<Vec<u8>, Error> {
+        Ok(match note {
+            Some(id) => objects.find_blob(&id, buf)?.data.to_owned(),
+            None => Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_hash::{oid, ObjectId};
+use gix_object::FindExt;
+
+use crate::{Entry, Notes};
+
+/// The error returned by [`Notes::from_tree()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindTree(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    DecodeTree(#[from] gix_object::decode::Error),
+}
+
+impl Notes {
+    /// Read all notes from the notes tree `tree` and its subtrees, which are looked up in `objects`.
+    ///
+    /// Entries whose path is the hexadecimal id of an object, possibly split into directories with two hexadecimal
+    /// characters each, are notes. All other entries are kept in [`other`](Notes::other).
+    pub fn from_tree(tree: &oid, objects: &impl gix_object::Find) -> Result<Self, Error> {
+        let mut out = Notes::default();
+        let hex_len = tree.kind().len_in_hex();
+        let mut buf = Vec::new();
+        let mut queue = vec![(tree.to_owned(), BString::default(), String::new())];
+        while let Some((tree, path, hex)) = queue.pop() {
+            for entry in objects.find_tree_iter(&tree, &mut buf)? {
+                let entry = entry?;
+                let name = entry.filename;
+                let full_hex = is_lower_hex(name).then(|| format!("{hex}{name}"));
+                match full_hex {
+                    Some(full_hex) if entry.mode.is_tree() && name.len() == 2 && full_hex.len() < hex_len => {
+                        queue.push((entry.oid.to_owned(), join(path.as_ref(), name), full_hex));
+                        continue;
+                    }
+                    Some(full_hex) if !entry.mode.is_tree() && full_hex.len() == hex_len => {
+                        let object = ObjectId::from_hex(full_hex.as_bytes()).expect("valid hex of the right length");
+                        out.by_object.insert(object, entry.oid.to_owned());
+                        continue;
+                    }
+                    _ => {}
+                }
+                out.other.push(Entry {
+                    path: join(path.as_ref(), name),
+                    mode: entry.mode,
+                    id: entry.oid.to_owned(),
+                });
+            }
+        }
+        out.other.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(out)
+    }
+}
+
+fn is_lower_hex(name: &BStr) -> bool {
+    !name.is_empty() && name.iter().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn join(path: &BStr, name: &BStr) -> BString {
+    let mut out = path.to_owned();
+    if !out.is_empty() {
+        out.push_byte(b'/');
+    }
+    out.push_str(name.as_bytes());
+    out
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+use gix_object::tree::{self, EntryKind};
+
+use crate::Notes;
+
+/// The error returned by [`Notes::write_to()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    WriteTree(#[from] gix_object::write::Error),
+}
+
+impl Notes {
+    /// Write all notes and other entries as notes tree into `objects`, and return the id of the tree.
+    ///
+    /// Notes are placed into subtrees named after the leading bytes of the annotated object whenever there are many of
+    /// them, using the same heuristic as git so both produce the same trees.
+    pub fn write_to(&self, objects: &impl gix_object::Write) -> Result<ObjectId, Error> {
+        let mut root = Node::default();
+        let notes: Vec<_> = self.by_object.iter().map(|(object, note)| (*object, *note)).collect();
+        root.add_notes(&notes, 0);
+        for entry in &self.other {
+            let mut node = &mut root;
+            let mut components = entry.path.split_str("/").peekable();
+            while let Some(name) = components.next() {
+                if components.peek().is_none() {
+                    node.entries.push(tree::Entry {
+                        mode: entry.mode,
+                        filename: name.into(),
+                        oid: entry.id,
+                    });
+                } else {
+                    node = node.children.entry(name.into()).or_default();
+                }
+            }
+        }
+        root.write_to(objects)
+    }
+}
+
+/// A tree to be written, with subtrees that still have to be written.
+#[derive(Default)]
+struct Node {
+    entries: Vec<tree::Entry>,
+    children: BTreeMap<BString, Node>,
+}
+
+impl Node {
+    /// Add `notes`, sorted by object and sharing the same `depth` leading bytes, to this node.
+    fn add_notes(&mut self, notes: &[(ObjectId, ObjectId)], depth: usize) {
+        if needs_fanout(notes, depth) {
+            let mut rest = notes;
+            while let Some((first, _)) = rest.first() {
+                let byte = first.as_bytes()[depth];
+                let end = rest
+                    .iter()
+                    .position(|(object, _)| object.as_bytes()[depth] != byte)
+                    .unwrap_or(rest.len());
+                let (group, remaining) = rest.split_at(end);
+                self.children
+                    .entry(format!("{byte:02x}").into())
+                    .or_default()
+                    .add_notes(group, depth + 1);
+                rest = remaining;
+            }
+        } else {
+            self.entries.extend(notes.iter().map(|(object, note)| tree::Entry {
+                mode: EntryKind::Blob.into(),
+                filename: object.to_hex().to_string()[depth * 2..].into(),
+                oid: *note,
+            }));
+        }
+    }
+
+    fn write_to(self, objects: &impl gix_object::Write) -> Result<ObjectId, Error> {
+        let mut entries = self.entries;
+        for (name, child) in self.children {
+            entries.push(tree::Entry {
+                mode: EntryKind::Tree.into(),
+                filename: name,
+                oid: child.write_to(objects)?,
+            });
+        }
+        entries.sort();
+        Ok(objects.write(&gix_object::Tree { entries })?)
+    }
+}
+
+/// Return `true` if `notes` that share `depth` leading bytes should be placed into subtrees for the next byte.
+///
+/// Like git, this is the case if each possible value of the upper half of the next byte is shared by at least two notes.
+fn needs_fanout(notes: &[(ObjectId, ObjectId)], depth: usize) -> bool {
+    let Some((object, _)) = notes.first() else {
+        return false;
+    };
+    if depth + 1 >= object.as_bytes().len() {
+        return false;
+    }
+    let mut counts = [0usize; 16];
+    for (object, _) in notes {
+        counts[usize::from(object.as_bytes()[depth] >> 4)] += 1;
+    }
+    counts.iter().all(|count| *count >= 2)
+}
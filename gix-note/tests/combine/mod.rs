@@ -0,0 +1,15 @@
+use gix_note::combine::{cat_sort_uniq, concatenate};
+
+#[test]
+fn concatenate_separates_with_empty_line() {
+    assert_eq!(concatenate(b"ours\n", b"theirs\n"), "ours\n\ntheirs\n");
+    assert_eq!(concatenate(b"ours", b"theirs\n"), "ours\n\ntheirs\n");
+    assert_eq!(concatenate(b"", b"theirs\n"), "theirs\n", "empty notes are ignored");
+    assert_eq!(concatenate(b"ours\n", b""), "ours\n");
+}
+
+#[test]
+fn cat_sort_uniq_keeps_unique_non_empty_lines() {
+    assert_eq!(cat_sort_uniq(b"b\n\na\n", b"c\na\n"), "a\nb\nc\n");
+    assert_eq!(cat_sort_uniq(b"", b"\n"), "");
+}
@@ -0,0 +1,53 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q fanout
+(cd fanout
+  git commit -q --allow-empty -m "annotated"
+  git notes add -m "a note" HEAD
+  for i in $(seq 200); do
+    blob=$(echo "$i" | git hash-object -w --stdin)
+    git notes add -m "note $i" "$blob"
+  done
+
+  {
+    echo "annotated $(git rev-parse HEAD)"
+    echo "blob-1 $(echo 1 | git hash-object --stdin)"
+    echo "notes $(git rev-parse refs/notes/commits^{tree})"
+  } >baseline
+)
+
+git init -q merge
+(cd merge
+  for i in 1 2 3 4; do
+    git commit -q --allow-empty -m "c$i"
+    git tag "c$i"
+  done
+
+  git notes add -m "base 1" c1
+  git notes add -m "base 2" c2
+  git notes add -m "base 3" c3
+  git update-ref refs/notes/theirs refs/notes/commits
+  echo "base $(git rev-parse refs/notes/commits^{tree})" >baseline
+
+  git notes add -f -m "ours 1" -m "shared" c1
+  git notes remove c2
+  git notes add -m "ours 4" c4
+
+  git notes --ref theirs add -f -m "theirs 1" -m "shared" c1
+  git notes --ref theirs add -f -m "theirs 2" c2
+  git notes --ref theirs add -f -m "theirs 3" c3
+  git notes --ref theirs add -m "theirs 4" c4
+
+  for strategy in ours theirs union cat_sort_uniq; do
+    git update-ref refs/notes/result-$strategy refs/notes/commits
+    git notes --ref result-$strategy merge -q -s $strategy theirs
+  done
+
+  for name in c1 c2 c3 c4; do
+    echo "$name $(git rev-parse $name)"
+  done >>baseline
+  for name in commits theirs result-ours result-theirs result-union result-cat_sort_uniq; do
+    echo "$name $(git rev-parse refs/notes/$name^{tree})"
+  done >>baseline
+)
@@ -0,0 +1,71 @@
+use gix_note::{merge::Strategy, Notes};
+
+use crate::fixture;
+
+#[test]
+fn strategies_match_git() -> crate::Result {
+    let (_root, odb, ids) = fixture("merge")?;
+    let read = |name: &str| Notes::from_tree(&ids[name], &odb);
+    let (base, ours, theirs) = (read("base")?, read("commits")?, read("theirs")?);
+    for strategy in [Strategy::Ours, Strategy::Theirs, Strategy::Union, Strategy::CatSortUniq] {
+        let outcome = gix_note::merge(&base, &ours, &theirs, strategy, &odb)?;
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(
+            outcome.notes.write_to(&odb)?,
+            ids[&format!("result-{}", strategy.as_str())],
+            "{strategy:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn manual_reports_conflicts_and_keeps_ours() -> crate::Result {
+    let (_root, odb, ids) = fixture("merge")?;
+    let read = |name: &str| Notes::from_tree(&ids[name], &odb);
+    let (base, ours, theirs) = (read("base")?, read("commits")?, read("theirs")?);
+    let outcome = gix_note::merge(&base, &ours, &theirs, Strategy::Manual, &odb)?;
+    assert_eq!(
+        outcome.conflicts.iter().map(|c| c.object).collect::<Vec<_>>(),
+        {
+            let mut expected = vec![ids["c1"], ids["c2"], ids["c4"]];
+            expected.sort();
+            expected
+        },
+        "c3 only changed on their side"
+    );
+    let c2 = outcome
+        .conflicts
+        .iter()
+        .find(|c| c.object == ids["c2"])
+        .expect("present");
+    assert_eq!(c2.base, base.by_object.get(&ids["c2"]).copied());
+    assert_eq!(c2.ours, None, "removed on our side");
+    assert_eq!(c2.theirs, theirs.by_object.get(&ids["c2"]).copied());
+
+    assert_eq!(
+        outcome.notes.by_object.get(&ids["c3"]),
+        theirs.by_object.get(&ids["c3"])
+    );
+    for object in ["c1", "c2", "c4"] {
+        assert_eq!(
+            outcome.notes.by_object.get(&ids[object]),
+            ours.by_object.get(&ids[object])
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn strategy_names() {
+    for strategy in [
+        Strategy::Manual,
+        Strategy::Ours,
+        Strategy::Theirs,
+        Strategy::Union,
+        Strategy::CatSortUniq,
+    ] {
+        assert_eq!(Strategy::from_bytes(strategy.as_str().as_bytes()), Some(strategy));
+    }
+    assert_eq!(Strategy::from_bytes(b"resolve"), None);
+}
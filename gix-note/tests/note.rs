@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+
+pub use gix_testtools::Result;
+
+mod combine;
+mod merge;
+mod tree;
+
+/// Return the repository `name` of the fixture, along with its object database and the ids in its baseline by name.
+fn fixture(name: &str) -> Result<(PathBuf, gix_odb::Handle, HashMap<String, ObjectId>)> {
+    let root = gix_testtools::scripted_fixture_read_only("make_notes.sh")?.join(name);
+    let odb = gix_odb::at(root.join(".git").join("objects"))?;
+    let baseline = baseline(&root)?;
+    Ok((root, odb, baseline))
+}
+
+fn baseline(root: &Path) -> Result<HashMap<String, ObjectId>> {
+    std::fs::read_to_string(root.join("baseline"))?
+        .lines()
+        .map(|line| {
+            let (name, hex) = line.split_once(' ').expect("name and id");
+            Ok((name.to_owned(), ObjectId::from_hex(hex.as_bytes())?))
+        })
+        .collect()
+}
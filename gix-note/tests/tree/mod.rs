@@ -0,0 +1,71 @@
+use gix_note::Notes;
+
+use crate::fixture;
+
+#[test]
+fn find_with_fanout() -> crate::Result {
+    let (_root, odb, ids) = fixture("fanout")?;
+    let notes = ids["notes"];
+    for object in [ids["annotated"], ids["blob-1"]] {
+        assert!(
+            gix_note::find(&notes, &object, &odb)?.is_some(),
+            "{object} has a note, whether it's in a subtree or not"
+        );
+    }
+    assert_eq!(gix_note::find(&notes, &notes, &odb)?, None);
+    Ok(())
+}
+
+#[test]
+fn read_and_write_with_fanout_like_git() -> crate::Result {
+    let (_root, odb, ids) = fixture("fanout")?;
+    let notes = Notes::from_tree(&ids["notes"], &odb)?;
+    assert_eq!(notes.by_object.len(), 201);
+    assert!(notes.other.is_empty());
+    assert_eq!(
+        gix_note::find(&ids["notes"], &ids["blob-1"], &odb)?,
+        notes.by_object.get(&ids["blob-1"]).copied()
+    );
+    assert_eq!(
+        notes.write_to(&odb)?,
+        ids["notes"],
+        "the fanout is chosen just like git does"
+    );
+
+    let mut few = notes.clone();
+    few.by_object
+        .retain(|object, _| object == &ids["annotated"] || object == &ids["blob-1"]);
+    let tree = few.write_to(&odb)?;
+    assert_eq!(Notes::from_tree(&tree, &odb)?, few);
+    let mut buf = Vec::new();
+    let tree = gix_object::FindExt::find_tree(&odb, &tree, &mut buf)?;
+    assert!(
+        tree.entries.iter().all(|entry| !entry.mode.is_tree()),
+        "few notes don't need subtrees"
+    );
+    Ok(())
+}
+
+#[test]
+fn other_entries_are_retained() -> crate::Result {
+    let (_root, odb, ids) = fixture("merge")?;
+    let mut notes = Notes::from_tree(&ids["base"], &odb)?;
+    for (path, kind, id) in [
+        ("README", gix_object::tree::EntryKind::Blob, ids["c1"]),
+        ("ab/file", gix_object::tree::EntryKind::Blob, ids["c1"]),
+        ("dir", gix_object::tree::EntryKind::Tree, ids["base"]),
+    ] {
+        notes.other.push(gix_note::Entry {
+            path: path.into(),
+            mode: kind.into(),
+            id,
+        });
+    }
+    let tree = notes.write_to(&odb)?;
+    assert_eq!(
+        Notes::from_tree(&tree, &odb)?,
+        notes,
+        "directories that aren't part of the fanout are kept as they are"
+    );
+    Ok(())
+}
@@ -4,7 +4,7 @@ use crate::{fixture_name, hex_to_id};
 
 #[test]
 fn empty() -> crate::Result {
-    let tree_ref = TreeRef::from_bytes(&[])?;
+    let tree_ref = TreeRef::from_bytes(&[], gix_hash::Kind::Sha1)?;
     assert_eq!(
         tree_ref,
         TreeRef { entries: vec![] },
@@ -24,7 +24,7 @@ fn empty() -> crate::Result {
 #[test]
 fn everything() -> crate::Result {
     let fixture = fixture_name("tree", "everything.tree");
-    let tree_ref = TreeRef::from_bytes(&fixture)?;
+    let tree_ref = TreeRef::from_bytes(&fixture, gix_hash::Kind::Sha1)?;
     assert_eq!(
         tree_ref,
         TreeRef {
@@ -64,14 +64,18 @@ fn everything() -> crate::Result {
 fn invalid() {
     let fixture = fixture_name("tree", "definitely-special.tree");
     let partial_tree = &fixture[..fixture.len() / 2];
-    let err = TreeRef::from_bytes(partial_tree).unwrap_err().to_string();
+    let err = TreeRef::from_bytes(partial_tree, gix_hash::Kind::Sha1)
+        .unwrap_err()
+        .to_string();
     if cfg!(feature = "verbose-object-parsing-errors") {
         assert!(err.starts_with("object parsing failed at `100644"), "{err}");
     } else {
         assert_eq!(err, "object parsing failed");
     }
     assert_eq!(
-        TreeRefIter::from_bytes(partial_tree).take_while(Result::is_ok).count(),
+        TreeRefIter::from_bytes(partial_tree, gix_hash::Kind::Sha1)
+            .take_while(Result::is_ok)
+            .count(),
         9,
         "we can decode about half of it before failing"
     );
@@ -79,7 +83,10 @@ fn invalid() {
 
 #[test]
 fn fuzzed() {
-    assert!(gix_object::TreeRef::from_bytes(b"2").is_err(), "fail, but don't crash");
+    assert!(
+        gix_object::TreeRef::from_bytes(b"2", gix_hash::Kind::Sha1).is_err(),
+        "fail, but don't crash"
+    );
 }
 
 #[test]
@@ -94,10 +101,12 @@ fn special_trees() -> crate::Result {
         ("special-5", 17),
     ] {
         let fixture = fixture_name("tree", &format!("{name}.tree"));
-        let actual = TreeRef::from_bytes(&fixture)?;
+        let actual = TreeRef::from_bytes(&fixture, gix_hash::Kind::Sha1)?;
         assert_eq!(actual.entries.len(), expected_entry_count, "{name}");
         assert_eq!(
-            TreeRefIter::from_bytes(&fixture).map(Result::unwrap).count(),
+            TreeRefIter::from_bytes(&fixture, gix_hash::Kind::Sha1)
+                .map(Result::unwrap)
+                .count(),
             expected_entry_count,
             "{name}"
         );
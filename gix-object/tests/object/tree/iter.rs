@@ -9,13 +9,17 @@ use crate::{fixture_name, hex_to_id};
 
 #[test]
 fn empty() {
-    assert_eq!(TreeRefIter::from_bytes(&[]).count(), 0, "empty trees are definitely ok");
+    assert_eq!(
+        TreeRefIter::from_bytes(&[], gix_hash::Kind::Sha1).count(),
+        0,
+        "empty trees are definitely ok"
+    );
 }
 
 #[test]
 fn error_handling() {
     let data = fixture_name("tree", "everything.tree");
-    let iter = TreeRefIter::from_bytes(&data[..data.len() / 2]);
+    let iter = TreeRefIter::from_bytes(&data[..data.len() / 2], gix_hash::Kind::Sha1);
     let entries = iter.collect::<Vec<_>>();
     assert!(
         entries.last().expect("at least one token").is_err(),
@@ -26,14 +30,14 @@ fn error_handling() {
 #[test]
 fn offset_to_next_entry() {
     let buf = fixture_name("tree", "everything.tree");
-    let mut iter = TreeRefIter::from_bytes(&buf);
+    let mut iter = TreeRefIter::from_bytes(&buf, gix_hash::Kind::Sha1);
     assert_eq!(iter.offset_to_next_entry(&buf), 0, "first entry is always at 0");
     iter.next();
 
     let actual = iter.offset_to_next_entry(&buf);
     assert_eq!(actual, 31, "now the offset increases");
     assert_eq!(
-        TreeRefIter::from_bytes(&buf[actual..])
+        TreeRefIter::from_bytes(&buf[actual..], gix_hash::Kind::Sha1)
             .next()
             .map(|e| e.unwrap().filename),
         iter.next().map(|e| e.unwrap().filename),
@@ -44,7 +48,8 @@ fn offset_to_next_entry() {
 #[test]
 fn everything() -> crate::Result {
     assert_eq!(
-        TreeRefIter::from_bytes(&fixture_name("tree", "everything.tree")).collect::<Result<Vec<_>, _>>()?,
+        TreeRefIter::from_bytes(&fixture_name("tree", "everything.tree"), gix_hash::Kind::Sha1)
+            .collect::<Result<Vec<_>, _>>()?,
         vec![
             EntryRef {
                 mode: tree::EntryKind::BlobExecutable.into(),
@@ -146,3 +151,55 @@ mod lookup_entry {
         }
     }
 }
+
+#[test]
+fn sha256_entries() -> crate::Result {
+    use gix_object::WriteTo;
+
+    let blob = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha256);
+    let tree = gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha256);
+    let mut buf = Vec::new();
+    gix_object::Tree {
+        entries: vec![
+            tree::Entry {
+                mode: tree::EntryKind::Tree.into(),
+                filename: "dir".into(),
+                oid: tree,
+            },
+            tree::Entry {
+                mode: tree::EntryKind::Blob.into(),
+                filename: "file".into(),
+                oid: blob,
+            },
+        ],
+    }
+    .write_to(&mut buf)?;
+
+    assert_eq!(
+        TreeRefIter::from_bytes(&buf, gix_hash::Kind::Sha256).collect::<Result<Vec<_>, _>>()?,
+        vec![
+            EntryRef {
+                mode: tree::EntryKind::Tree.into(),
+                filename: b"dir".as_bstr(),
+                oid: &tree
+            },
+            EntryRef {
+                mode: tree::EntryKind::Blob.into(),
+                filename: b"file".as_bstr(),
+                oid: &blob
+            },
+        ]
+    );
+    assert_eq!(
+        gix_object::TreeRef::from_bytes(&buf, gix_hash::Kind::Sha256)?
+            .entries
+            .len(),
+        2,
+        "trees can be decoded at once as well"
+    );
+    assert!(
+        TreeRefIter::from_bytes(&buf, gix_hash::Kind::Sha1).any(|e| e.is_err()),
+        "the hash kind must match or else the entries can't be parsed"
+    );
+    Ok(())
+}
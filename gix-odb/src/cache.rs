@@ -17,6 +17,12 @@ pub type ObjectCache = dyn gix_pack::cache::Object + Send + 'static;
 /// A constructor for boxed object caches.
 pub type NewObjectCacheFn = dyn Fn() -> Box<ObjectCache> + Send + Sync + 'static;
 
+/// A function to obtain all given objects which are missing locally, typically by fetching them from a promisor remote.
+///
+/// Once it returns successfully, the objects are expected to be present in the underlying object database.
+pub type FetchMissingFn =
+    dyn Fn(&[gix_hash::ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync + 'static;
+
 impl Cache<crate::store::Handle<Rc<crate::Store>>> {
     /// Convert this cache's handle into one that keeps its store in an arc. This creates an entirely new store,
     /// so should be done early to avoid unnecessary work (and mappings).
@@ -28,6 +34,7 @@ impl Cache<crate::store::Handle<Rc<crate::Store>>> {
             new_object_cache: self.new_object_cache,
             pack_cache: self.pack_cache,
             object_cache: self.object_cache,
+            fetch_missing: self.fetch_missing,
         })
     }
 }
@@ -89,6 +96,57 @@ impl<S> Cache<S> {
         self.object_cache = None;
         self.new_object_cache = None;
     }
+    /// Set the function to call with objects that couldn't be found, to obtain them before trying once more.
+    ///
+    /// This is how objects omitted by partial clones are fetched on demand, and it's only used when objects are
+    /// looked up, not when checking for their existence.
+    /// If `fetch` fails, the object is considered missing, just like when it doesn't provide the object.
+    /// Note that the underlying store must be able to pick up the newly added objects, which it does unless its
+    /// [refresh mode](crate::store::RefreshMode::Never) prevents it.
+    pub fn set_fetch_missing(
+        &mut self,
+        fetch: impl Fn(&[gix_hash::ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'static,
+    ) {
+        self.fetch_missing = Some(Arc::new(fetch));
+    }
+    /// Remove the function to fetch missing objects, so missing objects remain missing.
+    pub fn unset_fetch_missing(&mut self) {
+        self.fetch_missing = None;
+    }
+    /// Return true if missing objects are fetched on demand.
+    pub fn has_fetch_missing(&self) -> bool {
+        self.fetch_missing.is_some()
+    }
+}
+
+/// Fetching missing objects
+impl<S> Cache<S>
+where
+    S: gix_pack::Find,
+{
+    /// Obtain all objects in `ids` that don't exist locally in a single batch, if a function to
+    /// [fetch missing objects](Self::set_fetch_missing()) is set.
+    ///
+    /// Return the amount of objects that were missing, which is `0` if there is no way to fetch them.
+    /// This is useful to avoid fetching objects one by one when it's known which objects will be needed, like before a checkout.
+    pub fn fetch_missing(
+        &self,
+        ids: impl IntoIterator<Item = gix_hash::ObjectId>,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(fetch) = self.fetch_missing.as_ref() else {
+            return Ok(0);
+        };
+        let mut missing: Vec<_> = ids.into_iter().filter(|id| !self.inner.contains(id)).collect();
+        missing.sort();
+        missing.dedup();
+        if !missing.is_empty() {
+            fetch(&missing)?;
+        }
+        Ok(missing.len())
+    }
 }
 
 impl<S> From<S> for Cache<S>
@@ -102,6 +160,7 @@ where
             new_pack_cache: None,
             object_cache: None,
             new_object_cache: None,
+            fetch_missing: None,
         }
     }
 }
@@ -114,6 +173,7 @@ impl<S: Clone> Clone for Cache<S> {
             new_object_cache: self.new_object_cache.clone(),
             pack_cache: self.new_pack_cache.as_ref().map(|create| RefCell::new(create())),
             object_cache: self.new_object_cache.as_ref().map(|create| RefCell::new(create())),
+            fetch_missing: self.fetch_missing.clone(),
         }
     }
 }
@@ -139,7 +199,21 @@ mod impls {
     use gix_object::{Data, Kind};
     use gix_pack::cache::Object;
 
-    use crate::{find::Header, pack::data::entry::Location, Cache};
+    use crate::{cache::FetchMissingFn, find::Header, pack::data::entry::Location, Cache};
+
+    /// Use `fetch` to obtain the missing object `id`, and return `true` if that succeeded.
+    ///
+    /// Just like in `git`, failing to fetch an object, for instance because the remote doesn't have it either,
+    /// means it's missing.
+    fn fetch_one(fetch: &FetchMissingFn, id: &oid) -> bool {
+        match fetch(&[id.to_owned()]) {
+            Ok(()) => true,
+            Err(_err) => {
+                gix_features::trace::warn!("Could not fetch missing object {id}: {_err}");
+                false
+            }
+        }
+    }
 
     impl<S> gix_object::Write for Cache<S>
     where
@@ -178,7 +252,10 @@ mod impls {
         S: crate::Header,
     {
         fn try_header(&self, id: &oid) -> Result<Option<Header>, gix_object::find::Error> {
-            self.inner.try_header(id)
+            match (self.inner.try_header(id)?, self.fetch_missing.as_ref()) {
+                (None, Some(fetch)) if fetch_one(fetch.as_ref(), id) => self.inner.try_header(id),
+                (header, _) => Ok(header),
+            }
         }
     }
 
@@ -187,7 +264,10 @@ mod impls {
         S: gix_object::FindHeader,
     {
         fn try_header(&self, id: &oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
-            self.inner.try_header(id)
+            match (self.inner.try_header(id)?, self.fetch_missing.as_ref()) {
+                (None, Some(fetch)) if fetch_one(fetch.as_ref(), id) => self.inner.try_header(id),
+                (header, _) => Ok(header),
+            }
         }
     }
 
@@ -218,10 +298,26 @@ mod impls {
         ) -> Result<Option<(Data<'a>, Option<gix_pack::data::entry::Location>)>, gix_object::find::Error> {
             if let Some(mut obj_cache) = self.object_cache.as_ref().map(RefCell::borrow_mut) {
                 if let Some(kind) = obj_cache.get(&id.as_ref().to_owned(), buffer) {
-                    return Ok(Some((Data::new(kind, buffer), None)));
+                    return Ok(Some((Data::new(kind, id.kind(), buffer), None)));
                 }
             }
-            let possibly_obj = self.inner.try_find_cached(id.as_ref(), buffer, pack_cache)?;
+            let possibly_obj = match self.fetch_missing.as_ref() {
+                None => self.inner.try_find_cached(id.as_ref(), buffer, pack_cache)?,
+                Some(fetch) => {
+                    // Objects are always decoded into `buffer`, so only their kind is kept to be able to look again.
+                    match self
+                        .inner
+                        .try_find_cached(id.as_ref(), buffer, pack_cache)?
+                        .map(|(obj, location)| (obj.kind, location))
+                    {
+                        Some((kind, location)) => Some((Data::new(kind, id.kind(), buffer), location)),
+                        None if fetch_one(fetch.as_ref(), id) => {
+                            self.inner.try_find_cached(id.as_ref(), buffer, pack_cache)?
+                        }
+                        None => None,
+                    }
+                }
+            };
             if let (Some(mut obj_cache), Some((obj, _location))) =
                 (self.object_cache.as_ref().map(RefCell::borrow_mut), &possibly_obj)
             {
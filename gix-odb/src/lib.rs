@@ -44,6 +44,7 @@ pub struct Cache<S> {
     new_object_cache: Option<Arc<cache::NewObjectCacheFn>>,
     pack_cache: Option<RefCell<Box<cache::PackCache>>>,
     object_cache: Option<RefCell<Box<cache::ObjectCache>>>,
+    fetch_missing: Option<Arc<cache::FetchMissingFn>>,
 }
 
 ///
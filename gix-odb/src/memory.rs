@@ -149,6 +149,7 @@ where
                 buffer.extend_from_slice(data);
                 return Ok(Some(Data {
                     kind: *kind,
+                    hash_kind: id.kind(),
                     data: &*buffer,
                 }));
             }
@@ -62,7 +62,7 @@ impl Store {
             .max_depth(1)
             .follow_links(false)
             .into_iter(),
-            hash_hex_len: prefix.as_oid().kind().len_in_hex(),
+            hash_hex_len: self.object_hash.len_in_hex(),
         };
         let mut candidate = None;
         for oid in single_directory_iter {
@@ -261,6 +261,10 @@ impl Store {
                 .expect("BUG: here the size is already confirmed to fit into memory"),
             0,
         );
-        Ok(gix_object::Data { kind, data: buf })
+        Ok(gix_object::Data {
+            kind,
+            hash_kind: self.object_hash,
+            data: buf,
+        })
     }
 }
@@ -26,3 +26,46 @@ fn pack_object() {
     can_find(&db, "4dac9989f96bc5b5b1263b582c08f0c5f0b58542"); // pack a2bf
     can_find(&db, "dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043
 }
+
+#[test]
+fn missing_objects_are_fetched_only_on_lookup_and_stay_missing_if_fetch_fails() -> crate::Result {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use gix_object::Exists;
+    use gix_odb::Header;
+
+    let mut db = db();
+    let calls = Arc::new(AtomicUsize::default());
+    db.set_fetch_missing({
+        let calls = calls.clone();
+        move |_ids| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err("the remote doesn't have it".into())
+        }
+    });
+
+    let missing = hex_to_id("0000000000000000000000000000000000000001");
+    assert!(!db.exists(&missing));
+    assert_eq!(calls.load(Ordering::SeqCst), 0, "existence checks never fetch");
+
+    can_find(&db, "37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        0,
+        "objects that are present aren't fetched"
+    );
+
+    let mut buf = Vec::new();
+    assert!(gix_object::Find::try_find(&db, &missing, &mut buf)?.is_none());
+    assert_eq!(calls.load(Ordering::SeqCst), 1, "lookups of missing objects fetch them");
+    assert!(db.try_header(&missing)?.is_none());
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        2,
+        "objects that couldn't be fetched are missing"
+    );
+    Ok(())
+}
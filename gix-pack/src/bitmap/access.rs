@@ -0,0 +1,138 @@
+use gix_bitmap::ewah;
+
+use crate::bitmap::{Bitmap, Entry, File, Index, Objects, Version};
+
+/// Access methods
+impl File {
+    /// The version of the bitmap file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// The path of the opened bitmap file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+    /// The [flags](crate::bitmap::flags) stored in the header.
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+    /// The checksum of the pack or multi-pack index this bitmap belongs to.
+    pub fn checksum(&self) -> gix_hash::ObjectId {
+        self.checksum
+    }
+    /// The bitmaps of all selected commits, in the order they are stored in the file.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+    /// Return the bitmap that has all objects of `kind` set.
+    pub fn objects_of_kind(&self, kind: gix_object::Kind) -> &ewah::Vec {
+        match kind {
+            gix_object::Kind::Commit => &self.commits,
+            gix_object::Kind::Tree => &self.trees,
+            gix_object::Kind::Blob => &self.blobs,
+            gix_object::Kind::Tag => &self.tags,
+        }
+    }
+    /// Return the name-hash of the object at `index` in the list of sorted object ids, or `None` if there is no
+    /// name-hash cache or if `index` is out of bounds.
+    ///
+    /// Commits and tags always have a name-hash of `0`, and trees and blobs have the [`name_hash()`](crate::bitmap::name_hash())
+    /// of the first path they were seen at.
+    pub fn name_hash_at_index(&self, index: u32) -> Option<u32> {
+        let start = self.name_hashes.as_ref()?.start + index as usize * 4;
+        (start + 4 <= self.name_hashes.as_ref()?.end).then(|| crate::read_u32(&self.data[start..][..4]))
+    }
+}
+
+/// Access methods
+impl Index {
+    /// Return the underlying bitmap file.
+    pub fn bitmap_file(&self) -> &File {
+        &self.bitmap
+    }
+    /// Return the amount of objects, which is also the amount of bits in each bitmap.
+    pub fn num_objects(&self) -> u32 {
+        self.index_by_bit.len() as u32
+    }
+    /// Return the bit that represents `id`, or `None` if it's not contained in the pack or multi-pack index.
+    pub fn bit_of(&self, id: &gix_hash::oid) -> Option<u32> {
+        let index = match &self.objects {
+            Objects::Pack(index) => index.lookup(id),
+            Objects::MultiPack(index) => index.lookup(id),
+        }?;
+        Some(self.bit_by_index[index as usize])
+    }
+    /// Return the id of the object represented by `bit`.
+    ///
+    /// # Panics
+    ///
+    /// If `bit` is out of bounds.
+    pub fn id_at_bit(&self, bit: u32) -> &gix_hash::oid {
+        let index = self.index_by_bit[bit as usize];
+        match &self.objects {
+            Objects::Pack(pack_index) => pack_index.oid_at_index(index),
+            Objects::MultiPack(multi_index) => multi_index.oid_at_index(index),
+        }
+    }
+    /// Return the kind of the object represented by `bit`, or `None` if it's out of bounds or if the bitmap doesn't know it.
+    pub fn kind_at_bit(&self, bit: u32) -> Option<gix_object::Kind> {
+        use gix_object::Kind::*;
+        [Commit, Tree, Blob, Tag]
+            .into_iter()
+            .zip(&self.kinds)
+            .find_map(|(kind, objects)| objects.contains(bit).then_some(kind))
+    }
+    /// Return the name-hash of the object represented by `bit`, if the bitmap file has a name-hash cache.
+    pub fn name_hash_at_bit(&self, bit: u32) -> Option<u32> {
+        self.bitmap.name_hash_at_index(*self.index_by_bit.get(bit as usize)?)
+    }
+    /// Return `true` if the object represented by `bit` is a commit with a bitmap.
+    pub fn has_bitmap_at_bit(&self, bit: u32) -> bool {
+        self.entry_by_bit.contains_key(&bit)
+    }
+
+    /// Return the bitmap of all objects reachable from the commit represented by `bit`, or `None` if the commit has no bitmap.
+    pub fn bitmap_at_bit(&self, bit: u32) -> Option<Bitmap> {
+        let entry_index = *self.entry_by_bit.get(&bit)?;
+        Some(self.resolve(entry_index, &mut Default::default()))
+    }
+
+    /// Resolve the bitmap of the entry at `entry_index`, applying all XOR operations it depends on and caching
+    /// intermediate results in `cache`.
+    pub(crate) fn resolve(&self, entry_index: usize, cache: &mut std::collections::HashMap<usize, Bitmap>) -> Bitmap {
+        let entries = &self.bitmap.entries;
+        let mut chain = vec![entry_index];
+        let mut bitmap = loop {
+            let entry_index = *chain.last().expect("at least one entry");
+            if let Some(bitmap) = cache.get(&entry_index) {
+                chain.pop();
+                break bitmap.clone();
+            }
+            let entry = &entries[entry_index];
+            if entry.xor_offset == 0 {
+                chain.pop();
+                let bitmap = Bitmap::from_ewah(&entry.bitmap);
+                cache.insert(entry_index, bitmap.clone());
+                break bitmap;
+            }
+            chain.push(entry_index - usize::from(entry.xor_offset));
+        };
+        while let Some(entry_index) = chain.pop() {
+            let mut xored = Bitmap::from_ewah(&entries[entry_index].bitmap);
+            xored.xor_with(&bitmap);
+            cache.insert(entry_index, xored.clone());
+            bitmap = xored;
+        }
+        bitmap
+    }
+}
+
+impl std::fmt::Debug for Index {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Index")
+            .field("path", &self.bitmap.path)
+            .field("num_objects", &self.num_objects())
+            .field("num_bitmaps", &self.bitmap.entries.len())
+            .finish_non_exhaustive()
+    }
+}
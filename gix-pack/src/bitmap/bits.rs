@@ -0,0 +1,97 @@
+use gix_bitmap::ewah;
+
+use crate::bitmap::Bitmap;
+
+impl Bitmap {
+    /// Create a bitmap that can hold `num_bits` without reallocation, with all bits unset.
+    pub fn with_capacity(num_bits: usize) -> Self {
+        Bitmap {
+            words: vec![0; (num_bits + 63) / 64],
+        }
+    }
+
+    /// Decompress `bitmap`.
+    pub fn from_ewah(bitmap: &ewah::Vec) -> Self {
+        Bitmap {
+            words: bitmap.to_words(),
+        }
+    }
+
+    /// Compress this instance into an EWAH bitmap.
+    pub fn to_ewah(&self) -> ewah::Vec {
+        ewah::encode(&self.words)
+    }
+
+    /// Return `true` if the bit at `pos` is set.
+    pub fn contains(&self, pos: u32) -> bool {
+        let pos = pos as usize;
+        self.words
+            .get(pos / 64)
+            .is_some_and(|word| word & (1 << (pos % 64)) != 0)
+    }
+
+    /// Set the bit at `pos`, and return `true` if it wasn't set before.
+    pub fn insert(&mut self, pos: u32) -> bool {
+        let pos = pos as usize;
+        if self.words.len() <= pos / 64 {
+            self.words.resize(pos / 64 + 1, 0);
+        }
+        let word = &mut self.words[pos / 64];
+        let mask = 1 << (pos % 64);
+        let is_new = *word & mask == 0;
+        *word |= mask;
+        is_new
+    }
+
+    /// Set all bits that are set in `other`.
+    pub fn union_with(&mut self, other: &Bitmap) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// Unset all bits that are set in `other`.
+    pub fn difference_with(&mut self, other: &Bitmap) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= !other;
+        }
+    }
+
+    /// Flip all bits that are set in `other`.
+    pub fn xor_with(&mut self, other: &Bitmap) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+
+    /// Return the amount of set bits.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Return `true` if no bit is set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Iterate over the positions of all set bits, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_index, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros();
+                word &= word - 1;
+                Some(word_index as u32 * 64 + bit)
+            })
+        })
+    }
+}
@@ -0,0 +1,254 @@
+use std::path::{Path, PathBuf};
+
+use gix_bitmap::ewah;
+
+use crate::bitmap::{flags, Bitmap, Entry, File, Index, Objects, Version};
+
+mod error {
+    /// The error returned by [File::at()][super::File::at()] and the constructors of [`Index`](super::Index).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open bitmap file at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+        #[error("Unsupported bitmap file version: {version}")]
+        UnsupportedVersion { version: u16 },
+        #[error("Bitmaps of pseudo-merges aren't supported")]
+        UnsupportedPseudoMerges,
+        #[error("Could not decode the bitmap of {kind}")]
+        Ewah {
+            kind: &'static str,
+            source: gix_bitmap::ewah::decode::Error,
+        },
+        #[error("The bitmap at '{path}' belongs to a pack or multi-pack index with checksum {expected}, but got one with checksum {actual}")]
+        ChecksumMismatch {
+            path: std::path::PathBuf,
+            expected: gix_hash::ObjectId,
+            actual: gix_hash::ObjectId,
+        },
+        #[error(
+            "The bitmap of a commit referred to object at index {index}, but there are only {num_objects} objects"
+        )]
+        CommitOutOfBounds { index: u32, num_objects: u32 },
+        #[error("The multi-pack index at '{path}' has no reverse index, which is needed to use its bitmap")]
+        MissingReverseIndex { path: std::path::PathBuf },
+        #[error(transparent)]
+        OpenIndex(#[from] crate::index::init::Error),
+        #[error(transparent)]
+        OpenMultiIndex(#[from] crate::multi_index::init::Error),
+    }
+}
+pub use error::Error;
+
+pub(crate) const SIGNATURE: &[u8] = b"BITM";
+
+/// Instantiation
+impl File {
+    /// Open the bitmap file at `path`, which refers to objects hashed with `object_hash`.
+    pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<File, Error> {
+        let path = path.as_ref();
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        let hash_len = object_hash.len_in_bytes();
+        let corrupt = |message| Error::Corrupt { message };
+
+        let header_len = SIGNATURE.len() + 2 /* version */ + 2 /* flags */ + 4 /* entries */ + hash_len;
+        if data.len() < header_len + hash_len {
+            return Err(corrupt("The file is too short to contain a header and trailer"));
+        }
+        let (header, mut body) = data.split_at(header_len);
+        let body_end = body.len() - hash_len;
+        body = &body[..body_end];
+        if &header[..4] != SIGNATURE {
+            return Err(corrupt("The file doesn't start with the bitmap signature"));
+        }
+        let version = u16::from_be_bytes([header[4], header[5]]);
+        let version = match version {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let flags = u16::from_be_bytes([header[6], header[7]]);
+        if flags & flags::PSEUDO_MERGES != 0 {
+            return Err(Error::UnsupportedPseudoMerges);
+        }
+        let num_entries = crate::read_u32(&header[8..12]);
+        let checksum = gix_hash::ObjectId::from_bytes_or_panic(&header[12..]);
+
+        let mut decode = |kind| {
+            let (bitmap, rest) = ewah::decode(body).map_err(|source| Error::Ewah { kind, source })?;
+            body = rest;
+            Ok::<_, Error>(bitmap)
+        };
+        let commits = decode("commits")?;
+        let trees = decode("trees")?;
+        let blobs = decode("blobs")?;
+        let tags = decode("tags")?;
+
+        let mut entries = Vec::with_capacity(num_entries as usize);
+        for entry_index in 0..num_entries as usize {
+            if body.len() < 6 {
+                return Err(corrupt("Unexpected end of file when reading the bitmap of a commit"));
+            }
+            let (commit_index, xor_offset, flags) = (crate::read_u32(&body[..4]), body[4], body[5]);
+            if usize::from(xor_offset) > entry_index {
+                return Err(corrupt("The bitmap of a commit refers to a bitmap that doesn't exist"));
+            }
+            let (bitmap, rest) = ewah::decode(&body[6..]).map_err(|source| Error::Ewah {
+                kind: "a commit",
+                source,
+            })?;
+            body = rest;
+            entries.push(Entry {
+                commit_index,
+                xor_offset,
+                flags,
+                bitmap,
+            });
+        }
+
+        if flags & flags::LOOKUP_TABLE != 0 {
+            let table_len = num_entries as usize * (4 + 8 + 4);
+            if body.len() < table_len {
+                return Err(corrupt("The lookup table is truncated"));
+            }
+            body = &body[table_len..];
+        }
+        let name_hashes = if flags & flags::HASH_CACHE != 0 {
+            if body.len() % 4 != 0 {
+                return Err(corrupt("The name-hash cache doesn't have a multiple of 4 bytes"));
+            }
+            let start = body_end + header_len - body.len();
+            Some(start..start + body.len())
+        } else {
+            None
+        };
+
+        Ok(File {
+            data,
+            path: path.to_owned(),
+            version,
+            flags,
+            checksum,
+            commits,
+            trees,
+            blobs,
+            tags,
+            entries,
+            name_hashes,
+        })
+    }
+}
+
+/// Instantiation
+impl Index {
+    /// Combine the `bitmap` with the `index` of the pack it belongs to.
+    pub fn from_pack(bitmap: File, index: crate::index::File) -> Result<Self, Error> {
+        let pack_checksum = index.pack_checksum();
+        if pack_checksum != bitmap.checksum {
+            return Err(Error::ChecksumMismatch {
+                path: bitmap.path,
+                expected: bitmap.checksum,
+                actual: pack_checksum,
+            });
+        }
+        let mut index_by_bit: Vec<u32> = (0..index.num_objects()).collect();
+        index_by_bit.sort_by_key(|entry_index| index.pack_offset_at_index(*entry_index));
+        Self::new(bitmap, Objects::Pack(index), index_by_bit)
+    }
+
+    /// Combine the `bitmap` with the multi-pack `index` it belongs to, which must have a reverse index.
+    pub fn from_multi_index(bitmap: File, index: crate::multi_index::File) -> Result<Self, Error> {
+        let checksum = index.checksum();
+        if checksum != bitmap.checksum {
+            return Err(Error::ChecksumMismatch {
+                path: bitmap.path,
+                expected: bitmap.checksum,
+                actual: checksum,
+            });
+        }
+        let index_by_bit = index
+            .pseudo_pack_order()
+            .ok_or_else(|| Error::MissingReverseIndex {
+                path: index.path().to_owned(),
+            })?
+            .collect();
+        Self::new(bitmap, Objects::MultiPack(index), index_by_bit)
+    }
+
+    /// Find the bitmap to use in the `objects_dir` of a repository whose objects are hashed with `object_hash`,
+    /// or return `None` if there is none.
+    ///
+    /// Like `git`, the bitmap of the multi-pack index is preferred, and otherwise the bitmap of the first pack
+    /// with a bitmap is used.
+    pub fn discover(objects_dir: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<Option<Self>, Error> {
+        let pack_dir = objects_dir.as_ref().join("pack");
+        let multi_index_path = pack_dir.join("multi-pack-index");
+        if multi_index_path.is_file() {
+            let index = crate::multi_index::File::at(&multi_index_path)?;
+            let bitmap_path = pack_dir.join(format!("multi-pack-index-{}.bitmap", index.checksum()));
+            if bitmap_path.is_file() {
+                return Self::from_multi_index(File::at(bitmap_path, object_hash)?, index).map(Some);
+            }
+        }
+
+        let mut bitmap_paths: Vec<PathBuf> = match std::fs::read_dir(&pack_dir) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension().is_some_and(|ext| ext == "bitmap")
+                        && path
+                            .file_name()
+                            .and_then(|name| name.to_str())
+                            .is_some_and(|name| name.starts_with("pack-"))
+                })
+                .collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(Error::Io { source, path: pack_dir }),
+        };
+        bitmap_paths.sort();
+        for bitmap_path in bitmap_paths {
+            let index_path = bitmap_path.with_extension("idx");
+            if !index_path.is_file() {
+                continue;
+            }
+            let index = crate::index::File::at(index_path, object_hash)?;
+            return Self::from_pack(File::at(bitmap_path, object_hash)?, index).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn new(bitmap: File, objects: Objects, index_by_bit: Vec<u32>) -> Result<Self, Error> {
+        let num_objects = index_by_bit.len() as u32;
+        let mut bit_by_index = vec![0; index_by_bit.len()];
+        for (bit, index) in index_by_bit.iter().enumerate() {
+            bit_by_index[*index as usize] = bit as u32;
+        }
+        let mut entry_by_bit = std::collections::HashMap::with_capacity(bitmap.entries.len());
+        for (entry_index, entry) in bitmap.entries.iter().enumerate() {
+            let bit = bit_by_index
+                .get(entry.commit_index as usize)
+                .ok_or(Error::CommitOutOfBounds {
+                    index: entry.commit_index,
+                    num_objects,
+                })?;
+            entry_by_bit.insert(*bit, entry_index);
+        }
+        let kinds = [&bitmap.commits, &bitmap.trees, &bitmap.blobs, &bitmap.tags].map(Bitmap::from_ewah);
+        Ok(Index {
+            bitmap,
+            objects,
+            index_by_bit,
+            bit_by_index,
+            entry_by_bit,
+            kinds,
+        })
+    }
+}
@@ -0,0 +1,105 @@
+//! Reachability bitmaps as stored in `.bitmap` files next to a pack or a multi-pack index.
+//!
+//! For selected commits, a bitmap has one bit set for each object reachable from it, where each bit refers to an object
+//! in the order it appears in the pack, or the multi-pack index's pseudo-pack. This allows to know all objects reachable
+//! from these commits without traversing a single tree.
+//! Additional bitmaps indicate the kind of each object, and an optional cache provides a hash of the path of each tree
+//! and blob, which is useful to find good delta bases.
+use gix_bitmap::ewah;
+use memmap2::Mmap;
+
+/// Known bitmap file versions.
+#[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    #[default]
+    V1 = 1,
+}
+
+/// The flags stored in the header of bitmap files.
+pub mod flags {
+    /// All objects reachable from the selected commits are contained in the pack.
+    pub const FULL_DAG: u16 = 0x1;
+    /// The file contains the name-hash of each object in the pack.
+    pub const HASH_CACHE: u16 = 0x4;
+    /// The file contains a table to find the bitmap of each selected commit without reading all bitmaps.
+    pub const LOOKUP_TABLE: u16 = 0x10;
+    /// The file contains bitmaps for multiple commits at once.
+    pub const PSEUDO_MERGES: u16 = 0x20;
+}
+
+/// The bitmap of a selected commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The position of the commit in the list of sorted object ids of the pack index or multi-pack index.
+    pub commit_index: u32,
+    /// If not `0`, the `bitmap` must be combined using XOR with the bitmap of the entry this many entries before this one.
+    pub xor_offset: u8,
+    /// Flags to hint at how the bitmap was produced, which are not relevant when reading it.
+    pub flags: u8,
+    /// The possibly XORed bitmap of all objects reachable from the commit.
+    pub bitmap: ewah::Vec,
+}
+
+/// A representation of a `.bitmap` file, belonging to a pack or multi-pack index.
+pub struct File {
+    data: Mmap,
+    path: std::path::PathBuf,
+    version: Version,
+    flags: u16,
+    checksum: gix_hash::ObjectId,
+    /// The kinds of objects, in the order of [`gix_object::Kind`] variants.
+    commits: ewah::Vec,
+    trees: ewah::Vec,
+    blobs: ewah::Vec,
+    tags: ewah::Vec,
+    entries: Vec<Entry>,
+    name_hashes: Option<std::ops::Range<usize>>,
+}
+
+/// A [bitmap file](File) along with the pack index or multi-pack index it belongs to, which allows to map objects to
+/// their bit and back, and to learn which objects are reachable from a set of tips.
+pub struct Index {
+    bitmap: File,
+    objects: Objects,
+    /// Maps the position of a bit to the position of its object in the list of sorted object ids.
+    index_by_bit: Vec<u32>,
+    /// Maps the position of an object in the list of sorted object ids to its bit.
+    bit_by_index: Vec<u32>,
+    /// Maps the bit of selected commits to their entry in the bitmap file.
+    entry_by_bit: std::collections::HashMap<u32, usize>,
+    /// The uncompressed bitmaps for commits, trees, blobs and tags.
+    kinds: [Bitmap; 4],
+}
+
+enum Objects {
+    Pack(crate::index::File),
+    MultiPack(crate::multi_index::File),
+}
+
+/// An uncompressed set of bits, with one bit for each object of a pack or multi-pack index.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    words: Vec<u64>,
+}
+
+/// Compute the hash of `path` like `git` does to find similar objects, which is a hint for finding good delta bases.
+///
+/// It's mostly affected by the last characters of `path`, so blobs with the same file name or extension tend to have
+/// similar hashes.
+pub fn name_hash(path: &[u8]) -> u32 {
+    path.iter()
+        .filter(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        .fold(0u32, |hash, b| (hash >> 2).wrapping_add(u32::from(*b) << 24))
+}
+
+mod access;
+mod bits;
+///
+pub mod init;
+///
+pub mod reachable;
+pub use reachable::Reachable;
+///
+pub mod write;
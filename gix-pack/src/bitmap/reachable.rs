@@ -0,0 +1,143 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::ObjectId;
+use gix_object::{FindExt, Kind};
+
+use crate::bitmap::{Bitmap, Index};
+
+mod error {
+    /// The error returned by [`Index::reachable()`](crate::bitmap::Index::reachable()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindExisting(#[from] gix_object::find::existing::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error("Operation interrupted")]
+        Interrupted,
+    }
+}
+pub use error::Error;
+
+/// A set of objects reachable from a set of tips, as computed by [`Index::reachable()`].
+#[derive(Debug, Default, Clone)]
+pub struct Reachable {
+    /// The objects contained in the pack or multi-pack index the bitmap belongs to.
+    pub bitmap: Bitmap,
+    /// The objects that aren't contained in the pack or multi-pack index, like loose objects, in the order they were found.
+    pub extended: Vec<ObjectId>,
+    /// The same as `extended`, for fast lookup.
+    extended_set: HashSet<ObjectId>,
+    /// The amount of objects that had to be decoded as they weren't covered by the bitmaps of commits.
+    pub decoded_objects: usize,
+}
+
+impl Reachable {
+    /// Return the total amount of objects.
+    pub fn len(&self) -> usize {
+        self.bitmap.count() + self.extended.len()
+    }
+
+    /// Return `true` if there is no object.
+    pub fn is_empty(&self) -> bool {
+        self.bitmap.is_empty() && self.extended.is_empty()
+    }
+
+    /// Remove all objects that are also contained in `other`.
+    pub fn remove(&mut self, other: &Reachable) {
+        self.bitmap.difference_with(&other.bitmap);
+        self.extended.retain(|id| !other.extended_set.contains(id));
+        self.extended_set.retain(|id| !other.extended_set.contains(id));
+    }
+
+    fn insert_extended(&mut self, id: ObjectId) -> bool {
+        let is_new = self.extended_set.insert(id);
+        if is_new {
+            self.extended.push(id);
+        }
+        is_new
+    }
+}
+
+impl Index {
+    /// Find all objects reachable from `tips` using the bitmaps of commits, and traverse the commits and trees that
+    /// don't have a bitmap by looking them up in `objects`.
+    ///
+    /// If `exclude` is set, objects contained in it aren't traversed, as everything reachable from them is assumed
+    /// to be contained in it as well. This is useful to quickly compute the objects a client doesn't have yet, which is
+    /// the result of this call with its [contained objects removed](Reachable::remove()).
+    ///
+    /// `should_interrupt` is checked for each object that has to be traversed.
+    pub fn reachable(
+        &self,
+        tips: impl IntoIterator<Item = ObjectId>,
+        exclude: Option<&Reachable>,
+        objects: &dyn gix_object::Find,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Reachable, Error> {
+        let mut out = Reachable {
+            bitmap: Bitmap::with_capacity(self.num_objects() as usize),
+            ..Default::default()
+        };
+        let mut resolved = HashMap::new();
+        let mut buf = Vec::new();
+        let mut stack: Vec<(ObjectId, Option<Kind>)> = tips.into_iter().map(|id| (id, None)).collect();
+        while let Some((id, kind)) = stack.pop() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            match self.bit_of(&id) {
+                Some(bit) => {
+                    if out.bitmap.contains(bit) || exclude.is_some_and(|exclude| exclude.bitmap.contains(bit)) {
+                        continue;
+                    }
+                    if let Some(entry_index) = self.entry_by_bit.get(&bit) {
+                        out.bitmap.union_with(&self.resolve(*entry_index, &mut resolved));
+                        continue;
+                    }
+                    out.bitmap.insert(bit);
+                }
+                None => {
+                    if exclude.is_some_and(|exclude| exclude.extended_set.contains(&id)) || !out.insert_extended(id) {
+                        continue;
+                    }
+                }
+            }
+            if kind == Some(Kind::Blob) {
+                continue;
+            }
+
+            let object = objects.find(&id, &mut buf)?;
+            out.decoded_objects += 1;
+            match object.kind {
+                Kind::Commit => {
+                    let mut commit = gix_object::CommitRefIter::from_bytes(object.data);
+                    stack.push((commit.tree_id()?, Some(Kind::Tree)));
+                    for token in commit {
+                        match token? {
+                            gix_object::commit::ref_iter::Token::Parent { id } => stack.push((id, Some(Kind::Commit))),
+                            _ => break,
+                        }
+                    }
+                }
+                Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes(object.data, object.hash_kind) {
+                        let entry = entry?;
+                        if entry.mode.is_commit() {
+                            continue;
+                        }
+                        let kind = if entry.mode.is_tree() { Kind::Tree } else { Kind::Blob };
+                        stack.push((entry.oid.to_owned(), Some(kind)));
+                    }
+                }
+                Kind::Tag => stack.push((gix_object::TagRefIter::from_bytes(object.data).target_id()?, None)),
+                Kind::Blob => {}
+            }
+        }
+        Ok(out)
+    }
+}
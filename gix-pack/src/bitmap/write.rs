@@ -0,0 +1,222 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_hash::ObjectId;
+use gix_object::{bstr::BString, FindExt, Kind};
+
+use crate::bitmap::{flags, name_hash, Bitmap, File, Version};
+
+mod error {
+    /// The error returned by [`File::write_to()`](crate::bitmap::File::write_to()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] gix_hash::io::Error),
+        #[error(transparent)]
+        FindExisting(#[from] gix_object::find::existing::Error),
+        #[error(transparent)]
+        Find(#[from] gix_object::find::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+        #[error("Object {id} is reachable from a selected commit, but isn't contained in the pack")]
+        NotInPack { id: gix_hash::ObjectId },
+        #[error("Selected object {id} is a {kind}, but only commits can have bitmaps")]
+        NotACommit {
+            id: gix_hash::ObjectId,
+            kind: gix_object::Kind,
+        },
+        #[error("Operation interrupted")]
+        Interrupted,
+    }
+}
+pub use error::Error;
+
+/// Options for use in [`File::write_to()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The kind of hash used for all objects.
+    pub object_hash: gix_hash::Kind,
+    /// If `true`, store the [name-hash](crate::bitmap::name_hash()) of the first path each tree and blob was seen at,
+    /// which helps to find good delta bases when packing objects of the pack again.
+    pub name_hash_cache: bool,
+}
+
+/// The result of [`File::write_to()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Outcome {
+    /// The checksum over all bytes of the bitmap file, which is stored at its end.
+    pub checksum: ObjectId,
+    /// The amount of commits that received a bitmap.
+    pub num_bitmaps: usize,
+}
+
+/// Writing
+impl File {
+    /// Write a bitmap file for the pack described by `index` to `out`, with a bitmap for each of the `commits`,
+    /// looking up objects in `objects`.
+    ///
+    /// All objects reachable from `commits` must be contained in the pack, and the file must be stored next to the pack
+    /// with the `.bitmap` extension to be picked up.
+    /// `should_interrupt` is checked for each object that is traversed.
+    pub fn write_to(
+        index: &crate::index::File,
+        commits: impl IntoIterator<Item = ObjectId>,
+        objects: &dyn gix_object::FindObjectOrHeader,
+        out: &mut dyn std::io::Write,
+        should_interrupt: &AtomicBool,
+        Options {
+            object_hash,
+            name_hash_cache,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        let num_objects = index.num_objects();
+        let mut index_by_bit: Vec<u32> = (0..num_objects).collect();
+        index_by_bit.sort_by_key(|entry_index| index.pack_offset_at_index(*entry_index));
+        let mut bit_by_index = vec![0; index_by_bit.len()];
+        for (bit, index) in index_by_bit.iter().enumerate() {
+            bit_by_index[*index as usize] = bit as u32;
+        }
+        let bit_of = |id: &gix_hash::oid| index.lookup(id).map(|index| bit_by_index[index as usize]);
+
+        let mut kinds: [Bitmap; 4] = Default::default();
+        for (bit, entry_index) in index_by_bit.iter().enumerate() {
+            let id = index.oid_at_index(*entry_index);
+            let header = objects
+                .try_header(id)?
+                .ok_or_else(|| gix_object::find::existing::Error::NotFound { oid: id.to_owned() })?;
+            let kind_index = match header.kind {
+                Kind::Commit => 0,
+                Kind::Tree => 1,
+                Kind::Blob => 2,
+                Kind::Tag => 3,
+            };
+            kinds[kind_index].insert(bit as u32);
+        }
+
+        let mut buf = Vec::new();
+        let mut selected = Vec::new();
+        for id in commits {
+            let bit = bit_of(&id).ok_or(Error::NotInPack { id })?;
+            let object = objects.find(&id, &mut buf)?;
+            if object.kind != Kind::Commit {
+                return Err(Error::NotACommit { id, kind: object.kind });
+            }
+            let commit_time = gix_object::CommitRefIter::from_bytes(object.data)
+                .committer()?
+                .time
+                .seconds;
+            selected.push((commit_time, bit));
+        }
+        // Ancestors tend to be older, and computing their bitmaps first allows to reuse them for their descendants.
+        selected.sort();
+        selected.dedup_by_key(|(_, bit)| *bit);
+
+        let mut name_hashes = vec![0u32; num_objects as usize];
+        let mut bitmaps = HashMap::<u32, Bitmap>::new();
+        let mut stack = Vec::<(ObjectId, Kind, Option<BString>)>::new();
+        for (_, commit_bit) in &selected {
+            let mut reachable = Bitmap::with_capacity(num_objects as usize);
+            stack.push((
+                index.oid_at_index(index_by_bit[*commit_bit as usize]).to_owned(),
+                Kind::Commit,
+                None,
+            ));
+            while let Some((id, kind, path)) = stack.pop() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                let bit = bit_of(&id).ok_or(Error::NotInPack { id })?;
+                if reachable.contains(bit) {
+                    continue;
+                }
+                if let Some(bitmap) = bitmaps.get(&bit) {
+                    reachable.union_with(bitmap);
+                    continue;
+                }
+                reachable.insert(bit);
+                if let Some(path) = path.as_ref().filter(|_| name_hashes[bit as usize] == 0) {
+                    name_hashes[bit as usize] = name_hash(path);
+                }
+                if kind == Kind::Blob {
+                    continue;
+                }
+
+                let object = objects.find(&id, &mut buf)?;
+                match object.kind {
+                    Kind::Commit => {
+                        let mut commit = gix_object::CommitRefIter::from_bytes(object.data);
+                        stack.push((commit.tree_id()?, Kind::Tree, Some(BString::default())));
+                        for token in commit {
+                            match token? {
+                                gix_object::commit::ref_iter::Token::Parent { id } => {
+                                    stack.push((id, Kind::Commit, None));
+                                }
+                                _ => break,
+                            }
+                        }
+                    }
+                    Kind::Tree => {
+                        for entry in gix_object::TreeRefIter::from_bytes(object.data, object.hash_kind) {
+                            let entry = entry?;
+                            if entry.mode.is_commit() {
+                                continue;
+                            }
+                            let mut entry_path = path.clone().unwrap_or_default();
+                            if !entry_path.is_empty() {
+                                entry_path.push(b'/');
+                            }
+                            entry_path.extend_from_slice(entry.filename);
+                            let kind = if entry.mode.is_tree() { Kind::Tree } else { Kind::Blob };
+                            stack.push((entry.oid.to_owned(), kind, Some(entry_path)));
+                        }
+                    }
+                    Kind::Tag => stack.push((
+                        gix_object::TagRefIter::from_bytes(object.data).target_id()?,
+                        Kind::Tag,
+                        None,
+                    )),
+                    Kind::Blob => {}
+                }
+            }
+            bitmaps.insert(*commit_bit, reachable);
+        }
+
+        let mut out = gix_hash::io::Write::new(out, object_hash);
+        let flags = flags::FULL_DAG | if name_hash_cache { flags::HASH_CACHE } else { 0 };
+        let mut write = || -> std::io::Result<()> {
+            out.write_all(super::init::SIGNATURE)?;
+            out.write_all(&(Version::V1 as u16).to_be_bytes())?;
+            out.write_all(&flags.to_be_bytes())?;
+            out.write_all(&(selected.len() as u32).to_be_bytes())?;
+            out.write_all(index.pack_checksum().as_slice())?;
+            for kind in &kinds {
+                kind.to_ewah().write_to(&mut out)?;
+            }
+            for (_, commit_bit) in &selected {
+                out.write_all(&index_by_bit[*commit_bit as usize].to_be_bytes())?;
+                out.write_all(&[0 /* xor offset */, 0 /* flags */])?;
+                bitmaps[commit_bit].to_ewah().write_to(&mut out)?;
+            }
+            if name_hash_cache {
+                for bit in &bit_by_index {
+                    out.write_all(&name_hashes[*bit as usize].to_be_bytes())?;
+                }
+            }
+            Ok(())
+        };
+        write().map_err(gix_hash::io::Error::from)?;
+
+        let checksum = out.hash.try_finalize().map_err(gix_hash::io::Error::from)?;
+        out.inner
+            .write_all(checksum.as_slice())
+            .map_err(gix_hash::io::Error::from)?;
+        Ok(Outcome {
+            checksum,
+            num_bitmaps: selected.len(),
+        })
+    }
+}
@@ -57,6 +57,7 @@ impl crate::Bundle {
                 (
                     gix_object::Data {
                         kind: r.kind,
+                        hash_kind: self.index.object_hash(),
                         data: out.as_slice(),
                     },
                     crate::data::entry::Location {
@@ -254,7 +254,7 @@ mod tests {
             }
 
             let actual = std::mem::size_of::<[Item<EntryWithDefault>; 7_500_000]>();
-            let expected = 840_000_000;
+            let expected = 960_000_000;
             assert!(
                 size_ok(actual, expected),
                 "we don't want these to grow unnoticed: {actual} <~ {expected}"
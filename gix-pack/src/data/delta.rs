@@ -68,3 +68,8 @@ pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) {
     assert_eq!(i, data.len());
     assert_eq!(target.len(), 0);
 }
+
+#[cfg(feature = "generate")]
+mod encode;
+#[cfg(feature = "generate")]
+pub use encode::Index;
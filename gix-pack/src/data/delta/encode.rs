@@ -0,0 +1,218 @@
+/// The amount of bytes of a base object that are indexed together to find copyable regions in a target object.
+const BLOCK_SIZE: usize = 16;
+/// The maximum amount of base offsets with the same block hash to consider, to bound the time spent on repetitive data.
+const MAX_CANDIDATES: usize = 64;
+/// The maximum amount of bytes a single insert instruction can hold.
+const MAX_INSERT: usize = 0x7f;
+/// The maximum amount of bytes a single copy instruction is made to copy, for compatibility with older decoders.
+const MAX_COPY: usize = 0x10000;
+
+/// An index over the blocks of a base object, to quickly produce deltas against it.
+///
+/// Building it is relatively costly, which is why it should be kept for as long as the base is used to delta against.
+pub struct Index {
+    base: Vec<u8>,
+    /// `(block-hash, base-offset)`, sorted.
+    blocks: Vec<(u32, u32)>,
+}
+
+impl Index {
+    /// Index `base`, or return `None` if it is too large to be copied from by delta instructions.
+    pub fn new(base: Vec<u8>) -> Option<Self> {
+        u32::try_from(base.len()).ok()?;
+        let mut blocks: Vec<_> = base
+            .chunks_exact(BLOCK_SIZE)
+            .enumerate()
+            .map(|(block_index, block)| (block_hash(block), (block_index * BLOCK_SIZE) as u32))
+            .collect();
+        blocks.sort_unstable();
+        let mut previous = None;
+        let mut run = 0;
+        blocks.retain(|(hash, _)| {
+            if previous == Some(*hash) {
+                run += 1;
+            } else {
+                previous = Some(*hash);
+                run = 1;
+            }
+            run <= MAX_CANDIDATES
+        });
+        Some(Index { base, blocks })
+    }
+
+    /// Produce delta instructions that turn our base into `target`, or return `None` if these would be larger
+    /// than `max_size` bytes.
+    pub fn encode(&self, target: &[u8], max_size: usize) -> Option<Vec<u8>> {
+        let mut out = Vec::with_capacity(max_size.min(target.len() / 2 + 16));
+        encode_header_size(self.base.len(), &mut out);
+        encode_header_size(target.len(), &mut out);
+
+        let mut insert_start = 0;
+        let mut pos = 0;
+        while pos + BLOCK_SIZE <= target.len() {
+            if out.len() + (pos - insert_start) > max_size {
+                return None;
+            }
+            let hash = block_hash(&target[pos..][..BLOCK_SIZE]);
+            let first = self.blocks.partition_point(|(block_hash, _)| *block_hash < hash);
+            let (mut ofs, mut len) = (0, 0);
+            for (_, candidate) in self.blocks[first..]
+                .iter()
+                .take_while(|(block_hash, _)| *block_hash == hash)
+            {
+                let candidate = *candidate as usize;
+                let candidate_len = common_prefix_len(&self.base[candidate..], &target[pos..]);
+                if candidate_len > len {
+                    (ofs, len) = (candidate, candidate_len);
+                }
+            }
+            if len < BLOCK_SIZE {
+                pos += 1;
+                continue;
+            }
+
+            let mut start = pos;
+            while start > insert_start && ofs > 0 && self.base[ofs - 1] == target[start - 1] {
+                start -= 1;
+                ofs -= 1;
+                len += 1;
+            }
+            encode_insert(&target[insert_start..start], &mut out);
+            encode_copy(ofs, len, &mut out);
+            pos = start + len;
+            insert_start = pos;
+        }
+        encode_insert(&target[insert_start..], &mut out);
+        (out.len() <= max_size).then_some(out)
+    }
+}
+
+fn block_hash(block: &[u8]) -> u32 {
+    let lo = u64::from_le_bytes(block[..8].try_into().expect("block has 16 bytes"));
+    let hi = u64::from_le_bytes(block[8..BLOCK_SIZE].try_into().expect("block has 16 bytes"));
+    ((lo.wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(31) ^ hi).wrapping_mul(0xc2b2_ae3d_27d4_eb4f) >> 32) as u32
+}
+
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// The inverse of [`decode_header_size()`](super::decode_header_size()).
+fn encode_header_size(mut size: usize, out: &mut Vec<u8>) {
+    loop {
+        let mut byte = (size & 0x7f) as u8;
+        size >>= 7;
+        if size != 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if size == 0 {
+            break;
+        }
+    }
+}
+
+fn encode_insert(data: &[u8], out: &mut Vec<u8>) {
+    for chunk in data.chunks(MAX_INSERT) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn encode_copy(mut ofs: usize, mut len: usize, out: &mut Vec<u8>) {
+    while len != 0 {
+        let size = len.min(MAX_COPY);
+        let cmd_pos = out.len();
+        let mut cmd = 0b1000_0000;
+        out.push(cmd);
+        for byte_index in 0..4 {
+            let byte = (ofs >> (byte_index * 8)) as u8;
+            if byte != 0 {
+                cmd |= 1 << byte_index;
+                out.push(byte);
+            }
+        }
+        let encoded_size = if size == MAX_COPY { 0 } else { size };
+        for byte_index in 0..3 {
+            let byte = (encoded_size >> (byte_index * 8)) as u8;
+            if byte != 0 {
+                cmd |= 0b0001_0000 << byte_index;
+                out.push(byte);
+            }
+        }
+        out[cmd_pos] = cmd;
+        ofs += size;
+        len -= size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::delta::{apply, decode_header_size};
+
+    fn roundtrip(base: &[u8], target: &[u8]) -> usize {
+        let delta = Index::new(base.to_vec())
+            .expect("small enough")
+            .encode(target, usize::MAX)
+            .expect("no size limit");
+        let (base_size, consumed) = decode_header_size(&delta);
+        assert_eq!(base_size as usize, base.len());
+        let (target_size, consumed_2) = decode_header_size(&delta[consumed..]);
+        assert_eq!(target_size as usize, target.len());
+
+        let mut actual = vec![0; target.len()];
+        apply(base, &mut actual, &delta[consumed + consumed_2..]);
+        assert_eq!(actual, target, "applying the delta to the base reproduces the target");
+        delta.len()
+    }
+
+    fn lines(range: std::ops::Range<usize>) -> Vec<u8> {
+        range.flat_map(|line| format!("line {line}\n").into_bytes()).collect()
+    }
+
+    #[test]
+    fn similar_objects_produce_small_deltas() {
+        let base = lines(0..10_000);
+        let mut target = lines(0..3_000);
+        target.extend_from_slice(b"something new\n");
+        target.extend(lines(3_100..10_000));
+        target.extend(lines(20_000..20_010));
+
+        let delta_size = roundtrip(&base, &target);
+        assert!(
+            delta_size < 300,
+            "copies are used for everything but the new lines, got {delta_size} bytes"
+        );
+    }
+
+    #[test]
+    fn copies_larger_than_a_single_instruction_are_split() {
+        let base = lines(0..50_000);
+        assert!(base.len() > 4 * MAX_COPY);
+        let delta_size = roundtrip(&base, &base);
+        assert!(
+            delta_size < 100,
+            "only a few copy instructions are needed, got {delta_size}"
+        );
+    }
+
+    #[test]
+    fn unrelated_and_tiny_objects() {
+        roundtrip(b"", b"");
+        roundtrip(b"", b"hello");
+        roundtrip(b"hello", b"");
+        roundtrip(b"short base", b"short target");
+        let unrelated: Vec<u8> = (0..1000u32).map(|n| (n * 7 % 251) as u8).collect();
+        roundtrip(&lines(0..100), &unrelated);
+    }
+
+    #[test]
+    fn encoding_stops_once_max_size_is_exceeded() {
+        let base = lines(0..100);
+        let target = lines(1_000..1_100);
+        let index = Index::new(base).expect("small enough");
+        assert_eq!(index.encode(&target, 100), None);
+        assert!(index.encode(&target, usize::MAX).is_some());
+    }
+}
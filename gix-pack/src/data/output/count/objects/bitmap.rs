@@ -0,0 +1,72 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_hash::ObjectId;
+
+use super::types::{Error, Outcome};
+use crate::data::output;
+
+/// Generate [`Count`][output::Count]s for all objects reachable from `wants` but not from `haves`, using the reachability
+/// `bitmap` to avoid traversing trees and commits whenever possible.
+///
+/// This is what's needed to produce a pack for a fetch or push, where `wants` are the tips to send and `haves` are the
+/// commits the other side is known to have. Note that unlike with [`objects()`][super::objects()], `haves` are expected
+/// to be complete, i.e. the other side has all objects reachable from them.
+///
+/// * `db` - the object store to use for accessing objects that aren't covered by bitmaps.
+/// * `bitmap` - the bitmap of the pack or multi-pack index containing most of the objects in `db`.
+/// * `objects`
+///   * count the amount of objects we encounter
+/// * `should_interrupt`
+///  * A flag that is set to true if the operation should stop
+///
+/// Counts are returned in the order the objects appear in the pack or multi-pack index, followed by the objects that
+/// aren't contained in it.
+pub fn objects_with_bitmap(
+    db: &dyn crate::Find,
+    bitmap: &crate::bitmap::Index,
+    wants: impl IntoIterator<Item = ObjectId>,
+    haves: impl IntoIterator<Item = ObjectId>,
+    objects: &dyn gix_features::progress::Count,
+    should_interrupt: &AtomicBool,
+) -> Result<(Vec<output::Count>, Outcome), Error> {
+    let find = AsObjectFind(db);
+    let haves = bitmap.reachable(haves, None, &find, should_interrupt)?;
+    let wants: Vec<_> = wants.into_iter().collect();
+    let mut reachable = bitmap.reachable(wants.iter().copied(), Some(&haves), &find, should_interrupt)?;
+    reachable.remove(&haves);
+
+    let mut buf = Vec::new();
+    let mut out = Vec::with_capacity(reachable.len());
+    let counter = objects.counter();
+    for id in reachable
+        .bitmap
+        .iter()
+        .map(|bit| bitmap.id_at_bit(bit).to_owned())
+        .chain(reachable.extended.iter().copied())
+    {
+        let location = db.location_by_oid(&id, &mut buf);
+        out.push(output::Count::from_data(id, location));
+        counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    let outcome = Outcome {
+        input_objects: wants.len(),
+        expanded_objects: out.len().saturating_sub(wants.len()),
+        decoded_objects: haves.decoded_objects + reachable.decoded_objects,
+        total_objects: out.len(),
+    };
+    Ok((out, outcome))
+}
+
+/// Allow object lookups through a pack-aware database, for use where only object data is needed.
+struct AsObjectFind<'a>(&'a dyn crate::Find);
+
+impl gix_object::Find for AsObjectFind<'_> {
+    fn try_find<'a>(
+        &self,
+        id: &gix_hash::oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        Ok(self.0.try_find(id, buffer)?.map(|(data, _location)| data))
+    }
+}
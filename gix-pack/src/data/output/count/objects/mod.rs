@@ -13,6 +13,9 @@ pub use types::{Error, ObjectExpansion, Options, Outcome};
 
 mod tree;
 
+mod bitmap;
+pub use bitmap::objects_with_bitmap;
+
 /// Generate [`Count`][output::Count]s from input `objects` with object expansion based on [`options`][Options]
 /// to learn which objects would would constitute a pack. This step is required to know exactly how many objects would
 /// be in a pack while keeping data around to avoid minimize object database access.
@@ -204,7 +207,7 @@ mod expand {
                                     push_obj_count_unique(
                                         &mut out, seen_objs, &tree_id, location, objects, stats, true,
                                     );
-                                    gix_object::TreeRefIter::from_bytes(obj.data)
+                                    gix_object::TreeRefIter::from_bytes(obj.data, obj.hash_kind)
                                 };
 
                                 let objects_ref = if parent_commit_ids.is_empty() {
@@ -220,6 +223,7 @@ mod expand {
                                     out = objects.dissolve(stats);
                                     &traverse_delegate.non_trees
                                 } else {
+                                    changes_delegate.clear();
                                     for commit_id in &parent_commit_ids {
                                         let parent_tree_id = {
                                             let (parent_commit_obj, location) = db.find(commit_id, buf2)?;
@@ -242,10 +246,12 @@ mod expand {
                                                 stats,
                                                 true,
                                             );
-                                            gix_object::TreeRefIter::from_bytes(parent_tree_obj.data)
+                                            gix_object::TreeRefIter::from_bytes(
+                                                parent_tree_obj.data,
+                                                parent_tree_obj.hash_kind,
+                                            )
                                         };
 
-                                        changes_delegate.clear();
                                         let objects = CountingObjects::new(db);
                                         gix_diff::tree(
                                             parent_tree,
@@ -279,7 +285,7 @@ mod expand {
                                 {
                                     let objects = ExpandedCountingObjects::new(db, out, objects);
                                     gix_traverse::tree::breadthfirst(
-                                        gix_object::TreeRefIter::from_bytes(obj.0.data),
+                                        gix_object::TreeRefIter::from_bytes(obj.0.data, obj.0.hash_kind),
                                         &mut tree_traversal_state,
                                         &objects,
                                         &mut traverse_delegate,
@@ -91,6 +91,8 @@ pub enum Error {
     TreeTraverse(gix_traverse::tree::breadthfirst::Error),
     #[error(transparent)]
     TreeChanges(gix_diff::tree::Error),
+    #[error(transparent)]
+    Bitmap(#[from] crate::bitmap::reachable::Error),
     #[error("Operation interrupted")]
     Interrupted,
 }
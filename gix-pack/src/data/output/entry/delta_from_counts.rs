@@ -0,0 +1,352 @@
+pub(crate) mod function {
+    use std::{
+        collections::VecDeque,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use gix_features::parallel;
+
+    use super::{reduce, Error, Hint, Options, Outcome};
+    use crate::data::{delta, output, output::entry::Kind};
+
+    /// Objects smaller than this are neither delta-compressed nor used as delta base, as it's not worth the effort.
+    const MIN_DELTA_OBJECT_SIZE: u64 = 50;
+
+    /// Given a known list of object `counts`, find objects that are similar to each other and store them as deltas
+    /// against each other, and compress all remaining objects as base objects.
+    ///
+    /// Unlike with [`iter_from_counts()`][crate::data::output::entry::iter_from_counts()], all objects are decoded,
+    /// and existing deltas aren't reused, which is more expensive but produces much smaller packs.
+    ///
+    /// * `counts`
+    ///   * A list of previously counted objects to add to the pack. No object is expected to be duplicated.
+    /// * `hint(count) -> Hint`
+    ///   * Provide additional information about each of the `counts` to find good delta bases or to prevent
+    ///     an object from being delta-compressed.
+    /// * `db`
+    ///   * the object database to obtain the objects from.
+    /// * `objects`
+    ///   * count the amount of objects that were processed.
+    /// * `should_interrupt`
+    ///   * A flag that is set to true if the operation should stop.
+    /// * `options`
+    ///   * more configuration
+    ///
+    /// Objects are sorted by kind, name-hash and size, and each object is tried against the [`window`](Options::window)
+    /// objects preceding it to find the smallest delta, while assuring that delta chains don't get longer
+    /// than [`depth`](Options::depth).
+    ///
+    /// _Returns_ all entries in the order they should be written, which is the order of `counts` with the
+    /// exception of delta bases, which are placed right before the first entry that needs them.
+    /// Delta entries are of kind [`DeltaRef`][Kind::DeltaRef] and refer to the index of their base in the returned list,
+    /// making them suitable for producing `OFS_DELTA` entries.
+    pub fn delta_from_counts<Find>(
+        counts: &[output::Count],
+        mut hint: impl FnMut(&output::Count) -> Hint,
+        db: Find,
+        objects: &dyn gix_features::progress::Count,
+        should_interrupt: &AtomicBool,
+        Options {
+            window,
+            depth,
+            thread_limit,
+        }: Options,
+    ) -> Result<(Vec<output::Entry>, Outcome), Error>
+    where
+        Find: crate::Find + gix_object::FindHeader + Send + Clone,
+    {
+        let mut candidates = Vec::new();
+        let mut plain = Vec::new();
+        for (object_index, count) in counts.iter().enumerate() {
+            let Hint { name_hash, no_delta } = hint(count);
+            match db.try_header(&count.id).map_err(Error::Find)? {
+                Some(header) if !no_delta && window != 0 && header.size >= MIN_DELTA_OBJECT_SIZE => {
+                    candidates.push((header.kind, name_hash, header.size, object_index));
+                }
+                _ => plain.push(object_index),
+            }
+        }
+        // Similar objects of the same kind tend to have the same name, and newer versions of files tend to be larger.
+        candidates.sort_by(|lhs, rhs| {
+            lhs.0
+                .cmp(&rhs.0)
+                .then(lhs.1.cmp(&rhs.1))
+                .then(rhs.2.cmp(&lhs.2))
+                .then(lhs.3.cmp(&rhs.3))
+        });
+
+        let num_threads = parallel::num_threads(thread_limit);
+        let mut tasks = Vec::new();
+        let segment_len = ((candidates.len() + num_threads - 1) / num_threads).max(1);
+        let mut remaining = candidates.as_slice();
+        while !remaining.is_empty() {
+            // Don't separate objects with the same name from each other.
+            let mut end = segment_len.min(remaining.len());
+            while end < remaining.len()
+                && (remaining[end].0, remaining[end].1) == (remaining[end - 1].0, remaining[end - 1].1)
+            {
+                end += 1;
+            }
+            tasks.push(Task {
+                objects: remaining[..end].iter().map(|candidate| candidate.3).collect(),
+                find_deltas: true,
+            });
+            remaining = &remaining[end..];
+        }
+        tasks.extend(plain.chunks(1000).map(|objects| Task {
+            objects: objects.to_vec(),
+            find_deltas: false,
+        }));
+
+        let objects = objects.counter();
+        let num_tasks = tasks.len();
+        let (mut entries, outcome) = parallel::in_parallel_if(
+            || num_tasks > 1,
+            tasks.into_iter(),
+            thread_limit,
+            |_| Vec::new(),
+            move |Task {
+                      objects: task,
+                      find_deltas,
+                  },
+                  buf| {
+                let mut out = Vec::with_capacity(task.len());
+                let mut outcome = Outcome::default();
+                let mut slots = VecDeque::<Slot>::with_capacity(window);
+                for object_index in task {
+                    if should_interrupt.load(Ordering::Relaxed) {
+                        return Err(Error::Interrupted);
+                    }
+                    let count = &counts[object_index];
+                    let Some((obj, _location)) = db.try_find(&count.id, buf).map_err(Error::Find)? else {
+                        outcome.missing_objects += 1;
+                        out.push((object_index, output::Entry::invalid()));
+                        continue;
+                    };
+
+                    let mut best: Option<(usize, Vec<u8>, usize)> = None;
+                    if find_deltas {
+                        for slot in slots.iter().rev().filter(|slot| slot.kind == obj.kind) {
+                            let Some(base) = slot.index.as_ref() else {
+                                continue;
+                            };
+                            // Prefer shallow bases, and only accept deltas that are smaller than what we have.
+                            let (max_size, ref_depth) = match &best {
+                                Some((_, delta, delta_depth)) => (delta.len().saturating_sub(1), *delta_depth),
+                                None => ((obj.data.len() / 2).saturating_sub(20), 1),
+                            };
+                            let max_size = max_size * (depth - slot.depth) / (depth - ref_depth + 1);
+                            if max_size == 0
+                                || obj.data.len().saturating_sub(slot.size) >= max_size
+                                || obj.data.len() < slot.size / 32
+                            {
+                                continue;
+                            }
+                            if let Some(delta) = base.encode(obj.data, max_size) {
+                                best = Some((slot.object_index, delta, slot.depth + 1));
+                            }
+                        }
+                    }
+
+                    let (entry, entry_depth) = match best {
+                        Some((base_object_index, delta, delta_depth)) => {
+                            outcome.delta_objects += 1;
+                            (
+                                output::Entry::from_delta(count, base_object_index, &delta)?,
+                                delta_depth,
+                            )
+                        }
+                        None => {
+                            outcome.base_objects += 1;
+                            (output::Entry::from_data(count, &obj)?, 0)
+                        }
+                    };
+                    out.push((object_index, entry));
+                    objects.fetch_add(1, Ordering::Relaxed);
+
+                    if find_deltas {
+                        if slots.len() == window {
+                            slots.pop_front();
+                        }
+                        slots.push_back(Slot {
+                            object_index,
+                            kind: obj.kind,
+                            size: obj.data.len(),
+                            depth: entry_depth,
+                            index: (entry_depth < depth)
+                                .then(|| delta::Index::new(obj.data.to_vec()))
+                                .flatten(),
+                        });
+                    }
+                }
+                Ok((out, outcome))
+            },
+            reduce::Entries::new(counts.len()),
+        )?;
+
+        // Place bases before the deltas that refer to them, and let these refer to the position of their base.
+        let mut out = Vec::with_capacity(entries.len());
+        let mut position_by_object_index = vec![None; entries.len()];
+        let mut stack = Vec::new();
+        for object_index in 0..entries.len() {
+            let mut next = Some(object_index);
+            while let Some(current) = next.filter(|idx| position_by_object_index[*idx].is_none()) {
+                stack.push(current);
+                next = match entries[current].as_ref().expect("not yet taken").kind {
+                    Kind::DeltaRef { object_index } => Some(object_index),
+                    _ => None,
+                };
+            }
+            while let Some(current) = stack.pop() {
+                let mut entry = entries[current].take().expect("every object is placed once");
+                if let Kind::DeltaRef { object_index } = &mut entry.kind {
+                    *object_index = position_by_object_index[*object_index].expect("bases are placed first");
+                }
+                position_by_object_index[current] = Some(out.len());
+                out.push(entry);
+            }
+        }
+        Ok((out, outcome))
+    }
+
+    /// A set of objects to turn into entries.
+    struct Task {
+        /// Indices into the counts to process.
+        objects: Vec<usize>,
+        /// If `true`, try to find deltas between the objects, which are expected to be sorted so similar objects are close.
+        find_deltas: bool,
+    }
+
+    /// An object in the delta window.
+    struct Slot {
+        object_index: usize,
+        kind: gix_object::Kind,
+        size: usize,
+        /// The length of the delta chain this object is at the end of, or 0 if it is a base object.
+        depth: usize,
+        /// The index to compute deltas against this object, if it is allowed to be a base.
+        index: Option<delta::Index>,
+    }
+}
+
+mod reduce {
+    use gix_features::parallel;
+
+    use super::{Error, Outcome};
+    use crate::data::output;
+
+    /// Collect entries at the position of their object in the input counts.
+    pub struct Entries {
+        entries: Vec<Option<output::Entry>>,
+        total: Outcome,
+    }
+
+    impl Entries {
+        pub fn new(num_objects: usize) -> Self {
+            Entries {
+                entries: vec![None; num_objects],
+                total: Outcome::default(),
+            }
+        }
+    }
+
+    impl parallel::Reduce for Entries {
+        type Input = Result<(Vec<(usize, output::Entry)>, Outcome), Error>;
+        type FeedProduce = ();
+        type Output = (Vec<Option<output::Entry>>, Outcome);
+        type Error = Error;
+
+        fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+            let (entries, outcome) = item?;
+            for (object_index, entry) in entries {
+                self.entries[object_index] = Some(entry);
+            }
+            self.total.aggregate(outcome);
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Self::Error> {
+            Ok((self.entries, self.total))
+        }
+    }
+}
+
+mod types {
+    use crate::data::output::entry;
+
+    /// Information about an object to help [`delta_from_counts()`][crate::data::output::entry::delta_from_counts()]
+    /// to find good delta bases.
+    #[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Hint {
+        /// The [name-hash](crate::bitmap::name_hash()) of the path at which the object was seen, or 0 if unknown,
+        /// like for commits.
+        pub name_hash: u32,
+        /// If `true`, the object must be stored as base object and won't be used as base for other objects either.
+        /// This is the case if the `delta` attribute is unset for its path.
+        pub no_delta: bool,
+    }
+
+    /// Information gathered during the run of [`delta_from_counts()`][crate::data::output::entry::delta_from_counts()].
+    #[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Outcome {
+        /// The amount of objects that were stored as delta against another object.
+        pub delta_objects: usize,
+        /// The amount of objects that were stored as base objects.
+        pub base_objects: usize,
+        /// The amount of objects that could not be located despite them being mentioned in the counts.
+        pub missing_objects: usize,
+    }
+
+    impl Outcome {
+        pub(in crate::data::output::entry) fn aggregate(
+            &mut self,
+            Outcome {
+                delta_objects,
+                base_objects,
+                missing_objects,
+            }: Self,
+        ) {
+            self.delta_objects += delta_objects;
+            self.base_objects += base_objects;
+            self.missing_objects += missing_objects;
+        }
+    }
+
+    /// Configuration options for [`delta_from_counts()`][crate::data::output::entry::delta_from_counts()].
+    #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Options {
+        /// The amount of similar objects to try as delta base for each object, like `pack.window`.
+        /// If 0, no deltas are produced.
+        pub window: usize,
+        /// The maximum length of delta chains, like `pack.depth`. If 0, no deltas are produced.
+        pub depth: usize,
+        /// The amount of threads to use at most when searching deltas. If `None`, all logical cores are used.
+        pub thread_limit: Option<usize>,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                window: 10,
+                depth: 50,
+                thread_limit: None,
+            }
+        }
+    }
+
+    /// The error returned by [`delta_from_counts()`][crate::data::output::entry::delta_from_counts()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Find(gix_object::find::Error),
+        #[error(transparent)]
+        NewEntry(#[from] entry::Error),
+        #[error("Operation interrupted")]
+        Interrupted,
+    }
+}
+pub use types::{Error, Hint, Options, Outcome};
@@ -83,7 +83,7 @@ mod function {
         // Write header
         let mut out = Count::new(std::io::BufWriter::with_capacity(
             8 * 4096,
-            gix_hash::io::Write::new(out, kind.hash()),
+            gix_hash::io::Write::new(out, pack_hash.kind()),
         ));
         out.write_all(V2_SIGNATURE)?;
         out.write_all(&(kind as u32).to_be_bytes())?;
@@ -142,7 +142,7 @@ mod function {
         progress.inc();
         progress.show_throughput_with(
             start,
-            (bytes_written_without_trailer + 20) as usize,
+            (bytes_written_without_trailer + pack_hash.kind().len_in_bytes() as u64) as usize,
             progress::bytes().expect("unit always set"),
             progress::MessageLevel::Success,
         );
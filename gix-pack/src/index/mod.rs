@@ -79,6 +79,8 @@ macro_rules! izip {
 use memmap2::Mmap;
 
 /// The version of an index file
+///
+/// V3, which could store ids of multiple hash kinds, isn't supported as `git` doesn't write it either.
 #[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
@@ -89,7 +91,9 @@ pub enum Version {
 }
 
 impl Version {
-    /// The kind of hash to produce to be compatible to this kind of index
+    /// The kind of hash to produce to be compatible to this kind of index by default.
+    ///
+    /// Note that V2 indices are also used for `Sha256` repositories, as the kind of hash isn't stored in the file itself.
     pub fn hash(&self) -> gix_hash::Kind {
         match self {
             Version::V1 | Version::V2 => gix_hash::Kind::Sha1,
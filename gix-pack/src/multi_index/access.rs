@@ -129,6 +129,20 @@ impl File {
         (pack_index, pack_offset)
     }
 
+    /// Return the entry index of each object in the order of the pseudo-pack that reachability bitmaps refer to,
+    /// or `None` if the file has no reverse index.
+    ///
+    /// The pseudo-pack contains the objects of all packs in the order of their packs, with the preferred pack first,
+    /// and by their offset within each pack.
+    pub fn pseudo_pack_order(&self) -> Option<impl ExactSizeIterator<Item = EntryIndex> + '_> {
+        let ofs = self.reverse_index_ofs?;
+        Some(
+            self.data[ofs..][..self.num_objects as usize * 4]
+                .chunks_exact(4)
+                .map(crate::read_u32),
+        )
+    }
+
     /// Return an iterator over all entries within this file.
     pub fn iter(&self) -> impl Iterator<Item = Entry> + '_ {
         (0..self.num_objects).map(move |idx| {
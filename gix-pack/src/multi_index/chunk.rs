@@ -272,3 +272,16 @@ pub mod large_offsets {
         8 * large_offsets as u64
     }
 }
+
+/// Information about the reverse index, which stores the order of objects in the pseudo-pack used by reachability bitmaps.
+pub mod reverse_index {
+    use std::ops::Range;
+
+    /// The id uniquely identifying the reverse index.
+    pub const ID: gix_chunk::Id = *b"RIDX";
+
+    /// Returns true if the `offset` range has one entry for each of `num_objects`.
+    pub fn is_valid(offset: &Range<usize>, num_objects: u32) -> bool {
+        offset.end - offset.start == num_objects as usize * 4
+    }
+}
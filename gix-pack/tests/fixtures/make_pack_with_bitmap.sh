@@ -0,0 +1,69 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_files() {
+  local base_dir=${1:?directory to write them into}
+  local num_files=${2:?amount of files to write}
+  local nonce=${3:?something to make files more unique}
+
+  mkdir -p "$base_dir"
+  for file_id in $(seq -w "$num_files"); do
+    seq "$file_id" > "$base_dir/$file_id"
+    echo "$nonce" >> "$base_dir/$file_id"
+  done
+}
+
+dirs=(. a b c a/a a/b a/c a/a/a)
+
+function commit_rounds() {
+  local from=${1:?first round}
+  local to=${2:?last round}
+  for round in $(seq "$from" "$to"); do
+    dir_index=$(( round % ${#dirs[@]} ))
+    num_files=$(( (round + 1) * 3 ))
+    write_files "${dirs[$dir_index]}" $num_files "$round"
+    git add .
+    git commit -qm "$round $num_files"
+  done
+}
+
+# Write all object ids reachable from the first argument, but not from the second one if given, sorted.
+function objects() {
+  local tip=${1:?tip to start at}
+  local exclude=${2:-}
+  if [ -n "$exclude" ]; then
+    comm -23 <(git rev-list --objects "$tip" | cut -d' ' -f1 | sort) <(git rev-list --objects "$exclude" | cut -d' ' -f1 | sort)
+  else
+    git rev-list --objects "$tip" | cut -d' ' -f1 | sort
+  fi
+}
+
+git init -q single
+(cd single
+  commit_rounds 1 10
+  git tag -m "an annotated tag" annotated
+  git repack -adbq
+
+  # The newest commit is loose, and thus not covered by the bitmap
+  commit_rounds 11 11
+
+  git rev-list --objects main annotated > all.paths
+  objects main > main.objects
+  objects annotated > annotated.objects
+  objects main main~4 > main-without-main~4.objects
+)
+
+git init -q multi
+(cd multi
+  commit_rounds 1 6
+  git repack -dq
+  commit_rounds 7 10
+  git tag -m "an annotated tag" annotated
+  git repack -dq
+  git multi-pack-index write --bitmap
+
+  git rev-list --objects main annotated > all.paths
+  objects main > main.objects
+  objects annotated > annotated.objects
+  objects main main~4 > main-without-main~4.objects
+)
@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+echo base > base
+git add base
+git commit -qm "base"
+
+git checkout -qb side
+echo b > b
+git add b
+git commit -qm "add b"
+
+git checkout -q main
+echo c > c
+git add c
+git commit -qm "add c"
+
+git merge -q --no-ff --no-commit side
+echo evil > evil
+git add evil
+git commit -qm "merge side with an evil change"
+
+# The merge commit and its tree, along with all blobs that are new compared to at least one of its parents.
+for spec in HEAD 'HEAD^{tree}' HEAD:b HEAD:c HEAD:evil; do
+  git rev-parse "$spec"
+done | sort > merge.objects
+git rev-parse HEAD > merge.commit
@@ -0,0 +1,365 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use gix_hash::ObjectId;
+use gix_pack::bitmap;
+
+fn repo(name: &str) -> crate::Result<PathBuf> {
+    Ok(crate::scripted_fixture_read_only("make_pack_with_bitmap.sh")?.join(name))
+}
+
+fn odb(repo: &Path) -> crate::Result<gix_odb::HandleArc> {
+    let store = gix_odb::Store::at_opts(
+        repo.join(".git").join("objects"),
+        &mut None.into_iter(),
+        gix_odb::store::init::Options::default(),
+    )?;
+    let mut cache = Arc::new(store).to_cache_arc();
+    cache.prevent_pack_unload();
+    Ok(cache)
+}
+
+fn bitmap_index(repo: &Path) -> crate::Result<bitmap::Index> {
+    Ok(
+        bitmap::Index::discover(repo.join(".git").join("objects"), gix_hash::Kind::Sha1)?
+            .expect("fixture has a bitmap"),
+    )
+}
+
+fn expected_objects(repo: &Path, name: &str) -> crate::Result<BTreeSet<ObjectId>> {
+    Ok(std::fs::read_to_string(repo.join(format!("{name}.objects")))?
+        .lines()
+        .map(crate::hex_to_id)
+        .collect())
+}
+
+fn rev_parse(repo: &Path, spec: &str) -> crate::Result<ObjectId> {
+    let out = std::process::Command::new("git")
+        .args(["rev-parse", spec])
+        .current_dir(repo)
+        .output()?;
+    assert!(out.status.success(), "{spec} can be parsed");
+    Ok(crate::hex_to_id(std::str::from_utf8(&out.stdout)?.trim()))
+}
+
+fn reachable_ids(index: &bitmap::Index, reachable: &bitmap::Reachable) -> BTreeSet<ObjectId> {
+    reachable
+        .bitmap
+        .iter()
+        .map(|bit| index.id_at_bit(bit).to_owned())
+        .chain(reachable.extended.iter().copied())
+        .collect()
+}
+
+#[test]
+fn name_hash() {
+    assert_eq!(bitmap::name_hash(b""), 0);
+    assert_eq!(bitmap::name_hash(b"a"), 0x6100_0000);
+    assert_eq!(bitmap::name_hash(b"ab"), 0x7a40_0000);
+    assert_eq!(
+        bitmap::name_hash(b"a/b"),
+        bitmap::name_hash(b"a /b\n"),
+        "whitespace is ignored"
+    );
+}
+
+mod file {
+    use gix_pack::bitmap;
+
+    use super::{bitmap_index, repo};
+
+    #[test]
+    fn pack_bitmap_metadata() -> crate::Result {
+        let index = bitmap_index(&repo("single")?)?;
+        let file = index.bitmap_file();
+        assert_eq!(file.version(), bitmap::Version::V1);
+        assert_eq!(
+            file.flags(),
+            bitmap::flags::FULL_DAG | bitmap::flags::HASH_CACHE,
+            "git writes the name-hash cache by default"
+        );
+        assert_eq!(
+            file.entries().len(),
+            10,
+            "one bitmap for each of the commits in the pack"
+        );
+        assert!(
+            file.entries().iter().any(|entry| entry.xor_offset != 0),
+            "some bitmaps are stored as difference to others, so XOR chains are exercised"
+        );
+        assert!(file
+            .path()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("pack-")));
+        assert_eq!(index.num_objects(), 230);
+        Ok(())
+    }
+
+    #[test]
+    fn multi_pack_index_bitmap_metadata() -> crate::Result {
+        let index = bitmap_index(&repo("multi")?)?;
+        let file = index.bitmap_file();
+        assert_eq!(file.version(), bitmap::Version::V1);
+        assert!(file
+            .path()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("multi-pack-index-")));
+        assert_eq!(index.num_objects(), 230);
+        Ok(())
+    }
+
+    #[test]
+    fn kinds_and_name_hashes() -> crate::Result {
+        for (name, has_name_hashes) in [("single", true), ("multi", false)] {
+            let repo = repo(name)?;
+            let index = bitmap_index(&repo)?;
+            let mut num_checked = 0;
+            for line in std::fs::read_to_string(repo.join("all.paths"))?.lines() {
+                let (hex, path) = line.split_once(' ').unwrap_or((line, ""));
+                let Some(bit) = index.bit_of(&crate::hex_to_id(hex)) else {
+                    continue;
+                };
+                num_checked += 1;
+                let kind = index.kind_at_bit(bit).expect("every object has a kind");
+                if kind == gix_object::Kind::Tag {
+                    // The name of tags depends on how they were found, and isn't always the name of their reference.
+                    continue;
+                }
+                let expected_hash = if path.is_empty() {
+                    assert!(
+                        matches!(kind, gix_object::Kind::Commit | gix_object::Kind::Tree),
+                        "only commits and root trees have no path"
+                    );
+                    0
+                } else {
+                    assert!(matches!(kind, gix_object::Kind::Tree | gix_object::Kind::Blob));
+                    bitmap::name_hash(path.as_bytes())
+                };
+                assert_eq!(
+                    index.name_hash_at_bit(bit),
+                    Some(if has_name_hashes { expected_hash } else { 0 }),
+                    "{name}: {path}: git doesn't know the names of objects when writing bitmaps for multi-pack indices"
+                );
+            }
+            assert_eq!(num_checked, 230, "{name}: every object in the pack was checked");
+        }
+        Ok(())
+    }
+}
+
+mod reachable {
+    use std::sync::atomic::AtomicBool;
+
+    use super::{bitmap_index, expected_objects, odb, reachable_ids, repo, rev_parse};
+
+    #[test]
+    fn from_tips_with_and_without_bitmaps() -> crate::Result {
+        for name in ["single", "multi"] {
+            let repo = repo(name)?;
+            let index = bitmap_index(&repo)?;
+            let odb = odb(&repo)?;
+            for tip in ["main", "annotated"] {
+                let reachable = index.reachable(Some(rev_parse(&repo, tip)?), None, &odb, &AtomicBool::default())?;
+                assert_eq!(
+                    reachable_ids(&index, &reachable),
+                    expected_objects(&repo, tip)?,
+                    "{name}: {tip}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn loose_objects_are_traversed_and_listed_separately() -> crate::Result {
+        let repo = repo("single")?;
+        let index = bitmap_index(&repo)?;
+        let reachable = index.reachable(
+            Some(rev_parse(&repo, "main")?),
+            None,
+            &odb(&repo)?,
+            &AtomicBool::default(),
+        )?;
+        assert_eq!(reachable.len(), 268);
+        assert_eq!(
+            reachable.extended.len(),
+            268 - 229,
+            "the loose commit, its tree and blobs aren't in the pack"
+        );
+        assert_eq!(
+            reachable.decoded_objects, 3,
+            "only the loose commit, its root tree and the changed tree have to be decoded, everything else is in bitmaps"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn without_the_objects_of_another_set() -> crate::Result {
+        for name in ["single", "multi"] {
+            let repo = repo(name)?;
+            let index = bitmap_index(&repo)?;
+            let odb = odb(&repo)?;
+            let should_interrupt = AtomicBool::default();
+            let haves = index.reachable(Some(rev_parse(&repo, "main~4")?), None, &odb, &should_interrupt)?;
+            let mut wants = index.reachable(Some(rev_parse(&repo, "main")?), Some(&haves), &odb, &should_interrupt)?;
+            wants.remove(&haves);
+            assert_eq!(
+                reachable_ids(&index, &wants),
+                expected_objects(&repo, "main-without-main~4")?,
+                "{name}"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn count_objects_with_bitmap() -> crate::Result {
+    for name in ["single", "multi"] {
+        let repo = repo(name)?;
+        let index = bitmap_index(&repo)?;
+        let odb = odb(&repo)?;
+        let (counts, outcome) = gix_pack::data::output::count::objects_with_bitmap(
+            &odb,
+            &index,
+            Some(rev_parse(&repo, "main")?),
+            Some(rev_parse(&repo, "main~4")?),
+            &gix_features::progress::Discard,
+            &AtomicBool::default(),
+        )?;
+        let expected = expected_objects(&repo, "main-without-main~4")?;
+        assert_eq!(counts.iter().map(|count| count.id).collect::<BTreeSet<_>>(), expected);
+        assert_eq!(counts.len(), expected.len(), "there are no duplicates");
+        assert_eq!(outcome.input_objects, 1);
+        assert_eq!(outcome.total_objects, expected.len());
+        assert!(
+            counts
+                .iter()
+                .filter(|count| index.bit_of(&count.id).is_some())
+                .all(|count| count.entry_pack_location.as_ref().is_some()),
+            "{name}: packed objects have their location looked up so they can be copied"
+        );
+    }
+    Ok(())
+}
+
+mod write {
+    use std::sync::atomic::AtomicBool;
+
+    use gix_pack::bitmap;
+
+    use super::{expected_objects, odb, reachable_ids, rev_parse};
+
+    #[test]
+    fn roundtrip_and_validation_by_git() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable_standalone("make_pack_with_bitmap.sh")?;
+        let repo = tmp.path().join("single");
+        let pack_dir = repo.join(".git/objects/pack");
+        let bitmap_path = std::fs::read_dir(&pack_dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "bitmap"))
+            .expect("fixture has a pack bitmap");
+        let original = bitmap::File::at(&bitmap_path, gix_hash::Kind::Sha1)?;
+        std::fs::remove_file(&bitmap_path)?;
+
+        let index = gix_pack::index::File::at(bitmap_path.with_extension("idx"), gix_hash::Kind::Sha1)?;
+        let odb = odb(&repo)?;
+        let commits = ["main~1", "main~3", "main~7"]
+            .into_iter()
+            .map(|spec| rev_parse(&repo, spec))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut buf = Vec::new();
+        let outcome = bitmap::File::write_to(
+            &index,
+            commits.iter().copied(),
+            &odb,
+            &mut buf,
+            &AtomicBool::default(),
+            bitmap::write::Options {
+                object_hash: gix_hash::Kind::Sha1,
+                name_hash_cache: true,
+            },
+        )?;
+        assert_eq!(outcome.num_bitmaps, 3);
+        assert_eq!(outcome.checksum.as_slice(), &buf[buf.len() - 20..]);
+        std::fs::write(&bitmap_path, &buf)?;
+
+        let written = bitmap::File::at(&bitmap_path, gix_hash::Kind::Sha1)?;
+        for kind in [
+            gix_object::Kind::Commit,
+            gix_object::Kind::Tree,
+            gix_object::Kind::Blob,
+            gix_object::Kind::Tag,
+        ] {
+            assert_eq!(
+                written.objects_of_kind(kind).to_words(),
+                original.objects_of_kind(kind).to_words(),
+                "{kind}"
+            );
+        }
+        let tag = rev_parse(&repo, "annotated")?;
+        for entry_index in (0..index.num_objects()).filter(|idx| index.oid_at_index(*idx) != tag) {
+            assert_eq!(
+                written.name_hash_at_index(entry_index),
+                original.name_hash_at_index(entry_index),
+                "the first path an object is seen at is the same as the one git uses"
+            );
+        }
+        assert_eq!(
+            written.name_hash_at_index(index.lookup(tag).expect("tag is packed")),
+            Some(0),
+            "git uses the name of the reference to the tag, which we don't know"
+        );
+
+        let written = bitmap::Index::from_pack(written, index)?;
+        for spec in ["main~1", "main~3", "main~7"] {
+            let bit = written
+                .bit_of(&rev_parse(&repo, spec)?)
+                .expect("selected commits are in the pack");
+            assert!(written.has_bitmap_at_bit(bit));
+        }
+        let reachable = written.reachable(Some(rev_parse(&repo, "annotated")?), None, &odb, &AtomicBool::default())?;
+        assert_eq!(
+            reachable_ids(&written, &reachable),
+            expected_objects(&repo, "annotated")?
+        );
+
+        for spec in ["main~1", "main~3", "main~7"] {
+            let status = gix_testtools::run_git(&repo, &["rev-list", "--test-bitmap", spec])?;
+            assert!(status.success(), "git considers the bitmap of {spec} valid");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn objects_must_be_in_the_pack() -> crate::Result {
+        let repo = super::repo("single")?;
+        let index_path = std::fs::read_dir(repo.join(".git/objects/pack"))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "idx"))
+            .expect("fixture has a pack");
+        let index = gix_pack::index::File::at(index_path, gix_hash::Kind::Sha1)?;
+        let loose_commit = rev_parse(&repo, "main")?;
+        let err = bitmap::File::write_to(
+            &index,
+            Some(loose_commit),
+            &odb(&repo)?,
+            &mut Vec::new(),
+            &AtomicBool::default(),
+            bitmap::write::Options {
+                object_hash: gix_hash::Kind::Sha1,
+                name_hash_cache: false,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, bitmap::write::Error::NotInPack { id } if id == loose_commit));
+        Ok(())
+    }
+}
@@ -320,6 +320,158 @@ fn traversals() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn delta_compression() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+    let commits = gix_traverse::commit::Simple::new(Some(head), db.clone())
+        .map(|c| c.map(|c| c.id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (counts, _) = output::count::objects(
+        db.clone(),
+        Box::new(commits.into_iter().map(Ok)),
+        &progress::Discard,
+        &AtomicBool::new(false),
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(counts.len(), 864, "all objects reachable from head");
+
+    let compressed_size = |entries: &[output::Entry]| entries.iter().map(|e| e.compressed_data.len()).sum::<usize>();
+    let is_blob = |count: &output::Count| {
+        let mut buf = Vec::new();
+        db.find(&count.id, &mut buf).expect("present").0.kind == gix_object::Kind::Blob
+    };
+    let delta_from_counts = |hint: &dyn Fn(&output::Count) -> entry::delta_from_counts::Hint,
+                             options: entry::delta_from_counts::Options|
+     -> crate::Result<_> {
+        let (entries, outcome) = output::entry::delta_from_counts(
+            &counts,
+            hint,
+            db.clone(),
+            &progress::Discard,
+            &AtomicBool::new(false),
+            options,
+        )?;
+        assert_eq!(entries.len(), counts.len(), "every object is represented");
+        assert_eq!(outcome.missing_objects, 0);
+        assert_eq!(outcome.delta_objects + outcome.base_objects, counts.len());
+        for (position, entry) in entries.iter().enumerate() {
+            if let entry::Kind::DeltaRef { object_index } = entry.kind {
+                assert!(object_index < position, "bases are always written before their deltas");
+            }
+        }
+        Ok((entries, outcome))
+    };
+
+    let (base_entries, outcome) = delta_from_counts(
+        &|_| Default::default(),
+        entry::delta_from_counts::Options {
+            window: 0,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.delta_objects, 0, "a window of 0 disables delta compression");
+
+    for thread_limit in [Some(1), None] {
+        let (entries, outcome) = delta_from_counts(
+            &|_| Default::default(),
+            entry::delta_from_counts::Options {
+                thread_limit,
+                ..Default::default()
+            },
+        )?;
+        assert!(outcome.delta_objects > 500, "most objects are similar to each other");
+        assert!(
+            compressed_size(&entries) * 2 < compressed_size(&base_entries),
+            "deltas make the pack much smaller"
+        );
+        write_and_verify(db.clone(), entries, head, None)?;
+    }
+
+    let (entries, _) = delta_from_counts(
+        &|_| Default::default(),
+        entry::delta_from_counts::Options {
+            depth: 1,
+            thread_limit: Some(1),
+            ..Default::default()
+        },
+    )?;
+    for entry in &entries {
+        if let entry::Kind::DeltaRef { object_index } = entry.kind {
+            assert!(
+                matches!(entries[object_index].kind, entry::Kind::Base(_)),
+                "delta chains are limited in length"
+            );
+        }
+    }
+    write_and_verify(db.clone(), entries, head, None)?;
+
+    let (entries, outcome) = delta_from_counts(
+        &|count| entry::delta_from_counts::Hint {
+            name_hash: 0,
+            no_delta: is_blob(count),
+        },
+        Default::default(),
+    )?;
+    assert!(
+        outcome.delta_objects > 0,
+        "trees and commits are still delta-compressed"
+    );
+    let base_ids: std::collections::HashSet<_> = entries
+        .iter()
+        .filter_map(|entry| match entry.kind {
+            entry::Kind::DeltaRef { object_index } => Some(entries[object_index].id),
+            _ => None,
+        })
+        .collect();
+    for entry in &entries {
+        let count = output::Count::from_data(entry.id, None);
+        if is_blob(&count) {
+            assert!(
+                matches!(entry.kind, entry::Kind::Base(_)),
+                "objects without delta are stored as is"
+            );
+            assert!(!base_ids.contains(&entry.id), "…and aren't used as base either");
+        }
+    }
+    write_and_verify(db, entries, head, None)?;
+    Ok(())
+}
+
+#[test]
+fn tree_additions_of_merge_commits_are_compared_to_all_parents() -> crate::Result {
+    let repo = crate::scripted_fixture_read_only("make_pack_with_merge.sh")?;
+    let mut db = gix_odb::at(repo.join(".git").join("objects"))?.into_arc()?;
+    db.prevent_pack_unload();
+    let expected: Vec<_> = std::fs::read_to_string(repo.join("merge.objects"))?
+        .lines()
+        .map(hex_to_id)
+        .collect();
+    let head = hex_to_id(std::fs::read_to_string(repo.join("merge.commit"))?.trim());
+
+    let (counts, _) = output::count::objects(
+        db,
+        Box::new(std::iter::once(Ok(head))),
+        &progress::Discard,
+        &AtomicBool::new(false),
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+            ..Default::default()
+        },
+    )?;
+    let actual: Vec<_> = counts.into_iter().map(|count| count.id).collect();
+    for id in expected {
+        assert!(
+            actual.contains(&id),
+            "{id} is missing: objects that are new compared to the first parent aren't lost when comparing to the second one"
+        );
+    }
+    Ok(())
+}
+
 #[test]
 fn empty_pack_is_allowed() {
     assert_eq!(
@@ -6,7 +6,7 @@ use gix_testtools::size_ok;
 #[test]
 fn size_of_entry() {
     let actual = std::mem::size_of::<output::Entry>();
-    let expected = 80;
+    let expected = 112;
     assert!(
         size_ok(actual, expected),
         "The size of the structure shouldn't change unexpectedly: {actual} <~ {expected}"
@@ -16,7 +16,7 @@ fn size_of_entry() {
 #[test]
 fn size_of_count() {
     let actual = std::mem::size_of::<output::Count>();
-    let expected = 56;
+    let expected = 72;
     assert!(
         size_ok(actual, expected),
         "The size of the structure shouldn't change unexpectedly: {actual} <~ {expected}"
@@ -0,0 +1,281 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// How the bundles in a [`List`] relate to each other, and which of them a client should download.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// All bundles are needed to obtain the complete data, as they build upon each other.
+    #[default]
+    All,
+    /// Each bundle contains the complete data, and any one of them is sufficient.
+    Any,
+}
+
+/// The heuristic the server suggests to decide which bundles of a [`List`] to download.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Heuristic {
+    /// Bundles have a [creation token](Bundle::creation_token), and those with a higher token build upon those with a lower one.
+    CreationToken,
+}
+
+/// A single bundle that is available for download, as advertised in a [`List`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bundle {
+    /// The identifier of the bundle, which is unique within the list it's contained in.
+    pub id: BString,
+    /// The location at which the bundle can be downloaded.
+    pub uri: BString,
+    /// A number that increases with each newly created bundle, for use with [`Heuristic::CreationToken`].
+    pub creation_token: Option<u64>,
+    /// The filter specification that was used to create the bundle, if it's a bundle for partial clones.
+    pub filter: Option<BString>,
+}
+
+/// A list of bundles that the server suggests to download before fetching, as obtained by the `bundle-uri` command.
+///
+/// It's transmitted as a list of `bundle.*` configuration keys and values, which is why it's also used to represent
+/// the bundle list configured on the server side.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct List {
+    /// How the bundles relate to each other.
+    pub mode: Mode,
+    /// The heuristic to use to decide which bundles to download, if the server provides one.
+    pub heuristic: Option<Heuristic>,
+    /// The bundles in the order in which they were advertised.
+    pub bundles: Vec<Bundle>,
+}
+
+///
+pub mod parse {
+    use bstr::BString;
+
+    /// The error returned by [List::from_lines()](super::List::from_lines()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Expected a line of the form 'key=value', got {line:?}")]
+        MissingSeparator { line: BString },
+        #[error("Only version 1 of bundle lists is supported, got {version:?}")]
+        UnsupportedVersion { version: BString },
+        #[error("Unknown bundle list mode {mode:?}")]
+        UnknownMode { mode: BString },
+        #[error("The creation token {value:?} of bundle {id:?} isn't a number")]
+        InvalidCreationToken { id: BString, value: BString },
+        #[error("Bundle {id:?} doesn't have a URI")]
+        MissingUri { id: BString },
+    }
+}
+
+impl List {
+    /// Parse a list from `lines` of the form `bundle.<key>=<value>`, as sent in response to the `bundle-uri` command.
+    ///
+    /// Keys that aren't known are ignored so that future extensions don't affect older clients, just like in `git`.
+    /// Bundles are ordered by their first appearance.
+    pub fn from_lines(lines: impl IntoIterator<Item = impl AsRef<BStr>>) -> Result<Self, parse::Error> {
+        use parse::Error;
+
+        let mut list = List::default();
+        let mut uris = Vec::<Option<BString>>::new();
+        for line in lines {
+            let line = line.as_ref().trim_end_with(|c| c == '\n');
+            let (key, value) = line
+                .split_once_str("=")
+                .ok_or_else(|| Error::MissingSeparator { line: line.into() })?;
+            let Some(key) = strip_prefix_ignore_case(key, b"bundle.") else {
+                continue;
+            };
+            match key.rsplit_once_str(".") {
+                None => {
+                    if key.eq_ignore_ascii_case(b"version") {
+                        if value != b"1" {
+                            return Err(Error::UnsupportedVersion { version: value.into() });
+                        }
+                    } else if key.eq_ignore_ascii_case(b"mode") {
+                        list.mode = match value {
+                            b"all" => Mode::All,
+                            b"any" => Mode::Any,
+                            _ => return Err(Error::UnknownMode { mode: value.into() }),
+                        };
+                    } else if key.eq_ignore_ascii_case(b"heuristic") {
+                        list.heuristic = (value == b"creationToken").then_some(Heuristic::CreationToken);
+                    }
+                }
+                Some((id, key)) => {
+                    let idx = match list.bundles.iter().position(|b| b.id == id) {
+                        Some(idx) => idx,
+                        None => {
+                            list.bundles.push(Bundle {
+                                id: id.into(),
+                                uri: BString::default(),
+                                creation_token: None,
+                                filter: None,
+                            });
+                            uris.push(None);
+                            list.bundles.len() - 1
+                        }
+                    };
+                    let bundle = &mut list.bundles[idx];
+                    if key.eq_ignore_ascii_case(b"uri") {
+                        uris[idx] = Some(value.into());
+                    } else if key.eq_ignore_ascii_case(b"creationToken") {
+                        bundle.creation_token = Some(
+                            value
+                                .to_str()
+                                .ok()
+                                .and_then(|value| value.parse().ok())
+                                .ok_or_else(|| Error::InvalidCreationToken {
+                                    id: id.into(),
+                                    value: value.into(),
+                                })?,
+                        );
+                    } else if key.eq_ignore_ascii_case(b"filter") {
+                        bundle.filter = Some(value.into());
+                    }
+                }
+            }
+        }
+        for (bundle, uri) in list.bundles.iter_mut().zip(uris) {
+            bundle.uri = uri.ok_or_else(|| Error::MissingUri { id: bundle.id.clone() })?;
+        }
+        Ok(list)
+    }
+
+    /// Produce the lines of the form `bundle.<key>=<value>` that represent this list, which can be [parsed](Self::from_lines()) again.
+    pub fn to_lines(&self) -> Vec<BString> {
+        let mut out = vec![
+            BString::from("bundle.version=1"),
+            format!(
+                "bundle.mode={}",
+                match self.mode {
+                    Mode::All => "all",
+                    Mode::Any => "any",
+                }
+            )
+            .into(),
+        ];
+        if let Some(Heuristic::CreationToken) = self.heuristic {
+            out.push("bundle.heuristic=creationToken".into());
+        }
+        for bundle in &self.bundles {
+            let line = |key: &str, value: &[u8]| {
+                let mut line = BString::from(format!("bundle.{}.{key}=", bundle.id));
+                line.extend_from_slice(value);
+                line
+            };
+            out.push(line("uri", &bundle.uri));
+            if let Some(token) = bundle.creation_token {
+                out.push(line("creationToken", token.to_string().as_bytes()));
+            }
+            if let Some(filter) = &bundle.filter {
+                out.push(line("filter", filter));
+            }
+        }
+        out
+    }
+
+    /// Return the bundles in the order in which they should be downloaded and applied.
+    ///
+    /// With [`Heuristic::CreationToken`], these are sorted by ascending creation token, so each bundle can build upon the previous ones.
+    /// Otherwise the advertised order is retained.
+    pub fn bundles_in_download_order(&self) -> Vec<&Bundle> {
+        let mut out: Vec<_> = self.bundles.iter().collect();
+        if self.heuristic == Some(Heuristic::CreationToken) {
+            out.sort_by_key(|bundle| bundle.creation_token.unwrap_or_default());
+        }
+        out
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    (value.len() >= prefix.len() && value[..prefix.len()].eq_ignore_ascii_case(prefix)).then(|| &value[prefix.len()..])
+}
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+mod error {
+    /// The error returned by [`bundle_uri()`][crate::bundle_uri()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The server doesn't support the 'bundle-uri' command")]
+        Unsupported,
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Transport(#[from] gix_transport::client::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_transport::packetline::decode::Error),
+        #[error(transparent)]
+        Parse(#[from] super::parse::Error),
+        #[error(transparent)]
+        ArgumentValidation(#[from] crate::command::validate_argument_prefixes::Error),
+    }
+
+    impl gix_transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Io(err) => err.is_spurious(),
+                Error::Transport(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use error::Error;
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub(crate) mod function {
+    use std::borrow::Cow;
+
+    use bstr::BString;
+    use gix_transport::client::{Capabilities, Transport, TransportV2Ext};
+    use maybe_async::maybe_async;
+
+    use super::{Error, List};
+    use crate::Command;
+
+    /// Invoke the `bundle-uri` V2 command on `transport`, which requires a prior handshake that yielded server `capabilities`,
+    /// and return the list of bundles the server suggests to download before fetching.
+    ///
+    /// `user_agent` is sent along with the command, just like in `ls-refs` and `fetch`.
+    /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    ///
+    /// Note that the list may be empty, and that the URIs in it aren't validated in any way.
+    #[maybe_async]
+    pub async fn bundle_uri(
+        mut transport: impl Transport,
+        capabilities: &Capabilities,
+        user_agent: (&'static str, Option<Cow<'static, str>>),
+        trace: bool,
+    ) -> Result<List, Error> {
+        let _span = gix_features::trace::detail!("gix_protocol::bundle_uri()");
+        if capabilities.capability(Command::BundleUri.as_str()).is_none() {
+            return Err(Error::Unsupported);
+        }
+        let bundle_uri = Command::BundleUri;
+        let features = vec![user_agent];
+        bundle_uri.validate_argument_prefixes(gix_transport::Protocol::V2, capabilities, &[], &features)?;
+        let mut reader = transport
+            .invoke(
+                bundle_uri.as_str(),
+                features.into_iter(),
+                None::<std::iter::Empty<BString>>,
+                trace,
+            )
+            .await?;
+        let mut lines = Vec::new();
+        while let Some(line) = reader
+            .readline()
+            .await
+            .transpose()?
+            .transpose()?
+            .and_then(|line| line.as_bstr())
+        {
+            lines.push(line.to_owned());
+        }
+        Ok(List::from_lines(lines)?)
+    }
+}
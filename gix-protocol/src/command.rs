@@ -12,12 +12,15 @@ impl Command {
         match self {
             Command::LsRefs => "ls-refs",
             Command::Fetch => "fetch",
+            Command::BundleUri => "bundle-uri",
         }
     }
 }
 
 #[cfg(any(test, feature = "async-client", feature = "blocking-client"))]
 mod with_io {
+    use std::borrow::Cow;
+
     use bstr::{BString, ByteSlice};
     use gix_transport::client::Capabilities;
 
@@ -28,6 +31,7 @@ mod with_io {
         fn all_argument_prefixes(&self) -> &'static [&'static str] {
             match self {
                 Command::LsRefs => &["symrefs", "peel", "ref-prefix ", "unborn"],
+                Command::BundleUri => &[],
                 Command::Fetch => &[
                     "want ", // hex oid
                     "have ", // hex oid
@@ -58,7 +62,7 @@ mod with_io {
 
         fn all_features(&self, version: gix_transport::Protocol) -> &'static [&'static str] {
             match self {
-                Command::LsRefs => &[],
+                Command::LsRefs | Command::BundleUri => &[],
                 Command::Fetch => match version {
                     gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => &[
                         "multi_ack",
@@ -99,25 +103,38 @@ mod with_io {
                     .iter()
                     .map(|s| s.as_bytes().as_bstr().to_owned())
                     .chain(
-                        [
-                            "sideband-all",
-                            /* "packfile-uris" */ // packfile-uris must be configurable and can't just be used. Some servers advertise it and reject it later.
-                        ]
-                        .iter()
-                        .filter(|f| features.iter().any(|(sf, _)| sf == *f))
-                        .map(|f| f.as_bytes().as_bstr().to_owned()),
+                        // packfile-uris must be configurable and can't just be used, see `Arguments::packfile_uris()`.
+                        ["sideband-all"]
+                            .iter()
+                            .filter(|f| features.iter().any(|(sf, _)| sf == *f))
+                            .map(|f| f.as_bytes().as_bstr().to_owned()),
                     )
                     .collect(),
                 Command::LsRefs => vec![b"symrefs".as_bstr().to_owned(), b"peel".as_bstr().to_owned()],
+                Command::BundleUri => Vec::new(),
             }
         }
 
         /// Turns on all modern features for V1 and all supported features for V2, returning them as a vector of features.
         /// Note that this is the basis for any fetch operation as these features fulfil basic requirements and reasonably up-to-date servers.
+        ///
+        /// If the server advertises an `object-format` other than SHA1, it's echoed back as servers assume SHA1 otherwise.
         pub fn default_features(
             &self,
             version: gix_transport::Protocol,
             server_capabilities: &Capabilities,
+        ) -> Vec<Feature> {
+            let mut features = self.default_features_inner(version, server_capabilities);
+            if matches!(self, Command::Fetch | Command::LsRefs) {
+                features.extend(object_format(server_capabilities));
+            }
+            features
+        }
+
+        fn default_features_inner(
+            &self,
+            version: gix_transport::Protocol,
+            server_capabilities: &Capabilities,
         ) -> Vec<Feature> {
             match self {
                 Command::Fetch => match version {
@@ -155,7 +172,7 @@ mod with_io {
                             .collect()
                     }
                 },
-                Command::LsRefs => vec![],
+                Command::LsRefs | Command::BundleUri => vec![],
             }
         }
         /// Return an error if the given `arguments` and `features` don't match what's statically known.
@@ -208,7 +225,7 @@ mod with_io {
                             continue;
                         }
                         match *feature {
-                            "agent" => {}
+                            "agent" | "object-format" => {}
                             _ => {
                                 return Err(Error::UnsupportedCapability {
                                     command: self.as_str(),
@@ -223,6 +240,12 @@ mod with_io {
         }
     }
 
+    /// The `object-format` feature with the hash the server uses, if it advertised one that isn't the default.
+    fn object_format(server_capabilities: &Capabilities) -> Option<Feature> {
+        let format = server_capabilities.capability("object-format")?.value()?;
+        (format != "sha1").then(|| ("object-format", Some(Cow::Owned(format.to_str_lossy().into_owned()))))
+    }
+
     ///
     pub mod validate_argument_prefixes {
         use bstr::BString;
@@ -19,6 +19,7 @@ pub struct Arguments {
     deepen_relative: bool,
     ref_in_want: bool,
     supports_include_tag: bool,
+    packfile_uris: bool,
 
     features_for_first_want: Option<Vec<String>>,
     #[cfg(any(feature = "async-client", feature = "blocking-client"))]
@@ -81,6 +82,12 @@ impl Arguments {
     pub fn can_use_include_tag(&self) -> bool {
         self.supports_include_tag
     }
+    /// Return true if the 'packfile-uris' capability is supported, which is only the case in protocol V2.
+    ///
+    /// This allows the server to offload parts of the pack to static storage, from which they have to be downloaded separately.
+    pub fn can_use_packfile_uris(&self) -> bool {
+        self.packfile_uris
+    }
     /// Return true if we will use a stateless mode of operation, which can be decided in conjunction with `transport_is_stateless`.
     ///
     /// * we are always stateless if the transport is stateless, i.e. doesn't support multiple interactions with a single connection.
@@ -162,6 +169,17 @@ impl Arguments {
             self.prefixed("filter ", spec);
         }
     }
+    /// Allow the server to send parts of the pack as separate packs to be downloaded from URIs with one of the given `protocols`,
+    /// like `https`, which are listed in the `packfile-uris` section of the response.
+    ///
+    /// This must be configured explicitly as the client has to be prepared to download these packs.
+    pub fn packfile_uris(&mut self, protocols: &[impl AsRef<str>]) {
+        debug_assert!(self.packfile_uris, "'packfile-uris' feature required");
+        if self.packfile_uris && !protocols.is_empty() {
+            let protocols = protocols.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+            self.prefixed("packfile-uris ", protocols.join(","));
+        }
+    }
     /// Permanently allow the server to include tags that point to commits or objects it would return.
     ///
     /// Needs to only be called once.
@@ -207,6 +225,7 @@ impl Arguments {
         let filter = has("filter");
         let shallow = has("shallow");
         let ref_in_want = has("ref-in-want");
+        let packfile_uris = has("packfile-uris");
         let mut deepen_since = shallow;
         let mut deepen_not = shallow;
         let mut deepen_relative = shallow;
@@ -246,6 +265,7 @@ impl Arguments {
             deepen_not,
             deepen_relative,
             ref_in_want,
+            packfile_uris,
             deepen_since,
             features_for_first_want,
             trace,
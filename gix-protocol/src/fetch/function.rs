@@ -45,6 +45,8 @@ pub async fn fetch<P, T, E>(
         shallow,
         tags,
         reject_shallow_remote,
+        filter,
+        packfile_uri_protocols,
     }: Options<'_>,
 ) -> Result<Option<Outcome>, Error>
 where
@@ -78,6 +80,19 @@ where
         }
         arguments.use_include_tag();
     }
+    if let Some(filter) = filter {
+        if !arguments.can_use_filter() {
+            return Err(Error::MissingServerFeature {
+                feature: "filter",
+                description:
+                    "partial clones and fetches need the server to omit objects, otherwise all objects are sent",
+            });
+        }
+        arguments.filter(&filter.to_string());
+    }
+    if !packfile_uri_protocols.is_empty() && arguments.can_use_packfile_uris() {
+        arguments.packfile_uris(&packfile_uri_protocols);
+    }
     let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, shallow, &shallow_file)?;
 
     let negotiate_span = gix_trace::detail!(
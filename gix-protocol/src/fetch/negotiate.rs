@@ -7,7 +7,7 @@
 //! 3. [`one_round()`] is called for each negotiation round, providing information if the negotiation is done.
 use gix_date::SecondsSinceUnixEpoch;
 use gix_negotiate::Flags;
-use gix_ref::file::ReferenceExt;
+use gix_ref::store::ReferenceExt;
 use std::borrow::Cow;
 
 use crate::fetch::{refmap, RefMap, Shallow, Tags};
@@ -27,7 +27,7 @@ pub enum Error {
     #[error(transparent)]
     IO(#[from] std::io::Error),
     #[error(transparent)]
-    InitRefIter(#[from] gix_ref::file::iter::loose_then_packed::Error),
+    InitRefIter(#[from] gix_ref::store::iter::Error),
     #[error(transparent)]
     PeelToId(#[from] gix_ref::peel::to_id::Error),
     #[error(transparent)]
@@ -119,7 +119,7 @@ pub struct Round {
 #[allow(clippy::too_many_arguments)]
 pub fn mark_complete_and_common_ref<Out, F, E>(
     objects: &(impl gix_object::Find + gix_object::FindHeader + gix_object::Exists),
-    refs: &gix_ref::file::Store,
+    refs: &gix_ref::store::Handle,
     alternates: impl FnOnce() -> Result<Out, E>,
     negotiator: &mut dyn gix_negotiate::Negotiator,
     graph: &mut gix_negotiate::Graph<'_, '_>,
@@ -129,7 +129,7 @@ pub fn mark_complete_and_common_ref<Out, F, E>(
 ) -> Result<Action, Error>
 where
     E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
-    Out: Iterator<Item = (gix_ref::file::Store, F)>,
+    Out: Iterator<Item = (gix_ref::store::Handle, F)>,
     F: gix_object::Find,
 {
     let _span = gix_trace::detail!("mark_complete_and_common_ref", mappings = ref_map.mappings.len());
@@ -363,7 +363,7 @@ fn mark_recent_complete_commits(
 }
 
 fn mark_all_refs_in_repo(
-    store: &gix_ref::file::Store,
+    store: &gix_ref::store::Handle,
     objects: &impl gix_object::Find,
     graph: &mut gix_negotiate::Graph<'_, '_>,
     queue: &mut Queue,
@@ -165,6 +165,7 @@ fn extract_object_format(outcome: &crate::handshake::Outcome) -> Result<gix_hash
             })?;
             match object_format {
                 "sha1" => gix_hash::Kind::Sha1,
+                "sha256" => gix_hash::Kind::Sha256,
                 unknown => return Err(Error::UnknownObjectFormat { format: unknown.into() }),
             }
         } else {
@@ -72,6 +72,16 @@ pub struct WantedRef {
     pub path: BString,
 }
 
+/// A packfile-uri line received from the server, naming a pack that has to be downloaded in addition to the one sent in the response.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackfileUri {
+    /// The hash of the pack to download, as seen in its trailer and in the name of its `.pack` file.
+    pub hash: gix_hash::ObjectId,
+    /// The location at which the pack can be downloaded.
+    pub uri: BString,
+}
+
 /// Parse a `ShallowUpdate` from a `line` as received to the server.
 pub fn shallow_update_from_line(line: &str) -> Result<ShallowUpdate, Error> {
     match line.trim_end().split_once(' ') {
@@ -139,6 +149,20 @@ impl WantedRef {
     }
 }
 
+impl PackfileUri {
+    /// Parse a `PackfileUri` from a `line` as received from the server.
+    pub fn from_line(line: &str) -> Result<PackfileUri, Error> {
+        match line.trim_end().split_once(' ') {
+            Some((hash, uri)) => {
+                let hash = gix_hash::ObjectId::from_hex(hash.as_bytes())
+                    .map_err(|_| Error::UnknownLineType { line: line.to_owned() })?;
+                Ok(PackfileUri { hash, uri: uri.into() })
+            }
+            None => Err(Error::UnknownLineType { line: line.to_owned() }),
+        }
+    }
+}
+
 impl Response {
     /// Return true if the response has a pack which can be read next.
     pub fn has_pack(&self) -> bool {
@@ -198,6 +222,14 @@ impl Response {
     pub fn wanted_refs(&self) -> &[WantedRef] {
         &self.wanted_refs
     }
+
+    /// Return all packfile-uris [parsed previously][Response::from_line_reader()].
+    ///
+    /// These name packs which contain objects that were left out of the pack in this response, and which
+    /// have to be downloaded for the fetch to be complete.
+    pub fn packfile_uris(&self) -> &[PackfileUri] {
+        &self.packfile_uris
+    }
 }
 
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
@@ -1,4 +1,4 @@
-use crate::fetch::response::{Acknowledgement, ShallowUpdate, WantedRef};
+use crate::fetch::response::{Acknowledgement, PackfileUri, ShallowUpdate, WantedRef};
 use std::path::PathBuf;
 
 /// Options for use in [`fetch()`](`crate::fetch()`)
@@ -15,6 +15,14 @@ pub struct Options<'a> {
     /// If `true`, if we fetch from a remote that only offers shallow clones, the operation will fail with an error
     /// instead of writing the shallow boundary to the shallow file.
     pub reject_shallow_remote: bool,
+    /// If set, ask the remote to omit objects as described by the filter, which requires the `filter` capability.
+    pub filter: Option<Filter>,
+    /// If not empty, allow the remote to send parts of the pack as separate packs to be downloaded from URIs using one of these
+    /// protocols, like `https`. This is ignored if the remote doesn't support the `packfile-uris` capability.
+    ///
+    /// These packs are listed in the [`Response::packfile_uris()`] of the outcome, and the fetch is only complete
+    /// once they have been downloaded as well.
+    pub packfile_uri_protocols: Vec<String>,
 }
 
 /// For use in [`RefMap::new()`] and [`fetch`](crate::fetch()).
@@ -47,14 +55,14 @@ mod with_fetch {
         Objects: gix_object::Find + gix_object::FindHeader + gix_object::Exists,
         Alternates: FnOnce() -> Result<AlternatesOut, AlternatesErr>,
         AlternatesErr: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
-        AlternatesOut: Iterator<Item = (gix_ref::file::Store, Find)>,
+        AlternatesOut: Iterator<Item = (gix_ref::store::Handle, Find)>,
         Find: gix_object::Find,
     {
         /// Access to the object database.
         /// *Note* that the `exists()` calls must not trigger a refresh of the ODB packs as plenty of them might fail, i.e. find on object.
         pub objects: &'a Objects,
         /// Access to the git references database.
-        pub refs: &'a gix_ref::file::Store,
+        pub refs: &'a gix_ref::store::Handle,
         /// A function that returns an iterator over `(refs, objects)` for each alternate repository, to assure all known objects are added also according to their tips.
         pub alternates: Alternates,
         /// The implementation that performs the negotiation later, i.e. prepare wants and haves.
@@ -214,12 +222,77 @@ impl Tags {
     }
 }
 
+/// A filter to reduce the objects sent by the server, turning a clone or fetch into a *partial* one.
+///
+/// Objects omitted this way are expected to be fetched on demand from the remote, which is then called a *promisor* remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Filter {
+    /// Don't send any blobs, as in `blob:none`.
+    BlobNone,
+    /// Only send blobs smaller than the given amount of bytes, as in `blob:limit=<n>[kmg]`.
+    BlobLimit(u64),
+    /// Don't send trees or blobs whose depth from the root tree is greater or equal to the given depth, as in `tree:<depth>`.
+    ///
+    /// A depth of `0` thus only sends commits and tags, which is also known as *treeless* clone.
+    TreeDepth(u64),
+}
+
+impl Filter {
+    /// Parse a filter `spec` like `blob:none`, `blob:limit=1k` or `tree:0`, or return `None` if it isn't supported.
+    pub fn from_bytes(spec: &bstr::BStr) -> Option<Self> {
+        use bstr::ByteSlice;
+        if spec == "blob:none" {
+            return Some(Filter::BlobNone);
+        }
+        fn number(input: &[u8]) -> Option<u64> {
+            input.to_str().ok()?.parse().ok()
+        }
+        if let Some(depth) = spec.strip_prefix(b"tree:") {
+            return number(depth).map(Filter::TreeDepth);
+        }
+        let limit = spec.strip_prefix(b"blob:limit=")?;
+        let (limit, factor) = match limit.last()? {
+            b'k' | b'K' => (&limit[..limit.len() - 1], 1024),
+            b'm' | b'M' => (&limit[..limit.len() - 1], 1024 * 1024),
+            b'g' | b'G' => (&limit[..limit.len() - 1], 1024 * 1024 * 1024),
+            _ => (limit, 1),
+        };
+        Some(Filter::BlobLimit(number(limit)?.checked_mul(factor)?))
+    }
+
+    /// Return `true` if a blob of `size` bytes should be sent.
+    ///
+    /// Note that tree filters never allow blobs, which is only correct for depths of `0` and `1`.
+    pub fn allows_blob(&self, size: u64) -> bool {
+        match self {
+            Filter::BlobNone | Filter::TreeDepth(_) => false,
+            Filter::BlobLimit(limit) => size < *limit,
+        }
+    }
+
+    /// Return `true` if trees should be sent at all.
+    pub fn allows_trees(&self) -> bool {
+        !matches!(self, Filter::TreeDepth(0))
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::BlobNone => f.write_str("blob:none"),
+            Filter::BlobLimit(limit) => write!(f, "blob:limit={limit}"),
+            Filter::TreeDepth(depth) => write!(f, "tree:{depth}"),
+        }
+    }
+}
+
 /// A representation of a complete fetch response
 #[derive(Debug, Clone)]
 pub struct Response {
     pub(crate) acks: Vec<Acknowledgement>,
     pub(crate) shallows: Vec<ShallowUpdate>,
     pub(crate) wanted_refs: Vec<WantedRef>,
+    pub(crate) packfile_uris: Vec<PackfileUri>,
     pub(crate) has_pack: bool,
 }
 
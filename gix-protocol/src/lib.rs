@@ -1,4 +1,4 @@
-//! An abstraction over [fetching][fetch()] a pack from the server.
+//! An abstraction over [fetching][fetch()] a pack from the server, and [pushing](push()) one to it.
 //!
 //! Generally, there is the following order of operations.
 //!
@@ -7,8 +7,12 @@
 //! * execute a [`Command`]
 //!     - [list references](ls_refs())
 //!          - create a mapping between [refspecs and references](fetch::RefMap)
+//!     - optionally [list bundles](bundle_uri()) to download before fetching
 //!     - [receive a pack](fetch())
 //!
+//! Pushing works similarly, but uses the [`push::handshake()`] to obtain the remote references and then
+//! [sends a pack](push()) along with reference updates.
+//!
 //! ## Feature Flags
 #![cfg_attr(
     all(doc, feature = "document-features"),
@@ -30,6 +34,8 @@ pub enum Command {
     LsRefs,
     /// Fetch a pack.
     Fetch,
+    /// List bundles to download before fetching.
+    BundleUri,
 }
 pub mod command;
 
@@ -56,6 +62,24 @@ pub use remote_progress::RemoteProgress;
 #[cfg(all(feature = "blocking-client", feature = "async-client"))]
 compile_error!("Cannot set both 'blocking-client' and 'async-client' features as they are mutually exclusive");
 
+///
+pub mod push;
+#[cfg(feature = "blocking-client")]
+pub use push::function::push;
+
+#[cfg(feature = "upload-pack")]
+pub mod upload_pack;
+#[cfg(feature = "upload-pack")]
+pub use upload_pack::function::upload_pack;
+
+#[cfg(feature = "receive-pack")]
+pub mod receive_pack;
+#[cfg(feature = "receive-pack")]
+pub use receive_pack::function::receive_pack;
+
+#[cfg(any(feature = "upload-pack", feature = "receive-pack"))]
+mod server;
+
 ///
 pub mod handshake;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
@@ -67,5 +91,10 @@ pub mod ls_refs;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use ls_refs::function::ls_refs;
 
+///
+pub mod bundle_uri;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use bundle_uri::function::bundle_uri;
+
 mod util;
 pub use util::*;
@@ -0,0 +1,112 @@
+use bstr::{BString, ByteVec};
+
+use crate::push::Update;
+
+/// The arguments to send to `git-receive-pack`, consisting of the reference updates to perform and the capabilities
+/// we want to use.
+#[derive(Debug, Clone)]
+pub struct Arguments {
+    updates: Vec<Update>,
+    features: Vec<BString>,
+
+    report_status: bool,
+    supports_atomic: bool,
+    supports_delete_refs: bool,
+}
+
+impl Arguments {
+    /// Create a new instance to use the `capabilities` of the remote as obtained during the handshake,
+    /// and with `user_agent` as value for the `agent` capability if the server supports it.
+    ///
+    /// We will request a status report if the server supports it, and ask it to stay quiet as we don't read progress
+    /// information from side-bands.
+    /// An `object-format` other than SHA1 is echoed back, as the server would assume SHA1 otherwise.
+    pub fn new(capabilities: &gix_transport::client::Capabilities, user_agent: Option<&str>) -> Self {
+        let mut features = Vec::new();
+        let report_status = capabilities.contains("report-status");
+        if report_status {
+            features.push("report-status".into());
+        }
+        if capabilities.contains("quiet") {
+            features.push("quiet".into());
+        }
+        if let Some(format) = capabilities
+            .capability("object-format")
+            .and_then(|c| c.value())
+            .filter(|format| *format != "sha1")
+        {
+            features.push(format!("object-format={format}").into());
+        }
+        if let Some(agent) = user_agent.filter(|_| capabilities.contains("agent")) {
+            features.push(format!("agent={agent}").into());
+        }
+        Arguments {
+            updates: Vec::new(),
+            features,
+            report_status,
+            supports_atomic: capabilities.contains("atomic"),
+            supports_delete_refs: capabilities.contains("delete-refs"),
+        }
+    }
+
+    /// Return `true` if there is no update to send.
+    ///
+    /// In that case, no request should be sent as there is nothing to do.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Return `true` if the server supports the `atomic` capability, so either all or none of the updates are applied.
+    pub fn can_use_atomic(&self) -> bool {
+        self.supports_atomic
+    }
+
+    /// Request all updates to be applied atomically, which must be supported as indicated by [`can_use_atomic()`](Self::can_use_atomic()).
+    pub fn use_atomic(&mut self) {
+        if !self.features.iter().any(|f| f == "atomic") {
+            self.features.push("atomic".into());
+        }
+    }
+
+    /// Return `true` if the server allows references to be deleted.
+    pub fn can_delete_refs(&self) -> bool {
+        self.supports_delete_refs
+    }
+
+    /// Return `true` if the server will send a [status report](crate::push::response::Report) after receiving our updates.
+    pub fn expects_report(&self) -> bool {
+        self.report_status
+    }
+
+    /// Return `true` if at least one of our updates isn't a deletion, which means a pack has to be sent along with the updates.
+    ///
+    /// Note that the pack may be empty if the remote already has all objects.
+    pub fn needs_pack(&self) -> bool {
+        self.updates.iter().any(|u| !u.is_delete())
+    }
+
+    /// Add `update` to the list of updates to send.
+    pub fn update(&mut self, update: Update) {
+        self.updates.push(update);
+    }
+
+    /// Return all updates added so far.
+    pub fn updates(&self) -> &[Update] {
+        &self.updates
+    }
+
+    /// Return all lines to send to the server as packet lines, each terminated with a newline.
+    ///
+    /// The first line carries the requested capabilities, and a flush packet is expected to follow the last line.
+    pub fn lines(&self) -> impl Iterator<Item = BString> + '_ {
+        self.updates.iter().enumerate().map(|(idx, update)| {
+            let mut line = update.to_line();
+            if idx == 0 {
+                line.push(0);
+                line.push_str(bstr::join(" ", &self.features));
+            }
+            line.push(b'\n');
+            line
+        })
+    }
+}
@@ -0,0 +1,24 @@
+/// The error returned by [`push()`](crate::push()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Client(#[from] crate::transport::client::Error),
+    #[error("Failed to send reference updates")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to write the pack to send")]
+    WritePack(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("Could not decode the status report of the remote")]
+    Report(#[from] crate::push::response::Error),
+}
+
+impl crate::transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Client(err) => err.is_spurious(),
+            Error::Io(err) => err.is_spurious(),
+            Error::Report(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::Write;
+
+use gix_transport::client::{MessageKind, Transport, WriteMode};
+
+use crate::push::{response::Report, Arguments, Error};
+
+/// Send all reference updates in `arguments` through `transport`, which must have performed a
+/// [handshake](crate::push::handshake()) with `git-receive-pack` before.
+///
+/// `write_pack(out)` is called to write the pack containing all objects needed by the remote to perform the updates,
+/// and is only called if [`Arguments::needs_pack()`] is `true`. It writes verbatim into the underlying channel.
+/// If `trace` is `true`, all packetlines sent will be passed to the facilities of the `gix-trace` crate.
+///
+/// Return the status report of the remote if the `report-status` capability was supported, or `None` otherwise or
+/// if there was no update to send.
+///
+/// As opposed to a full `git push`, this operation does *not*…
+///
+/// * …compute which objects to send
+/// * …check if updates would be fast-forwards
+/// * …update local tracking references
+///
+/// **Note that the interaction will never be ended**, leaving it up to the caller to do that, maybe
+/// with the help of [`SendFlushOnDrop`](crate::SendFlushOnDrop) which can wrap `transport`.
+pub fn push<T, E>(
+    mut transport: T,
+    arguments: &Arguments,
+    write_pack: impl FnOnce(&mut dyn Write) -> Result<(), E>,
+    trace: bool,
+) -> Result<Option<Report>, Error>
+where
+    T: Transport,
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let _span = gix_features::trace::coarse!("gix_protocol::push()", updates = arguments.updates().len());
+    if arguments.is_empty() {
+        return Ok(None);
+    }
+
+    let mut request = transport.request(WriteMode::Binary, MessageKind::Flush, trace)?;
+    for line in arguments.lines() {
+        request.write_all(&line)?;
+    }
+    request.write_message(MessageKind::Flush)?;
+
+    let (mut out, mut reader) = request.into_parts();
+    if arguments.needs_pack() {
+        write_pack(&mut out).map_err(|err| Error::WritePack(err.into()))?;
+    }
+    out.flush()?;
+    // Dropping the writer finishes the request, which is required by some transports before the response can be read.
+    drop(out);
+
+    if !arguments.expects_report() {
+        return Ok(None);
+    }
+    reader.reset(gix_transport::Protocol::V1);
+    Ok(Some(Report::from_line_reader(&mut reader)?))
+}
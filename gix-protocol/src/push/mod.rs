@@ -0,0 +1,70 @@
+//! A module providing low-level primitives to send objects and reference updates to a `git-receive-pack` server.
+//! Note that the typesystem isn't used to assure they are performed in the right order, the caller has to follow
+//! some parts of the protocol itself.
+//!
+//! ### Order for sending a pack
+//!
+//! * [handshake](handshake()) - this yields the references on the remote as `git-receive-pack` always speaks
+//!   protocol V0 or V1.
+//! * create [`Arguments`] from the handshake capabilities and add one [`Update`] per reference to change
+//! * [send the updates and the pack](crate::push()) and receive the [status report](response::Report).
+//!
+//! Note that this flow doesn't involve computing which objects to send, nor does it update local tracking references.
+
+// Note: for ease of use, this is tested in `gix` itself, with the request and response formats being tested here.
+use bstr::{BString, ByteVec};
+
+mod arguments;
+pub use arguments::Arguments;
+
+///
+pub mod response;
+
+#[cfg(feature = "blocking-client")]
+mod error;
+#[cfg(feature = "blocking-client")]
+pub use error::Error;
+
+#[cfg(feature = "blocking-client")]
+pub(crate) mod function;
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+#[cfg(feature = "handshake")]
+mod handshake;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+#[cfg(feature = "handshake")]
+pub use handshake::receive_pack as handshake;
+
+/// A request to change a single reference on the remote, as sent to `git-receive-pack`.
+///
+/// `old` is the value the remote is expected to have for `name`, and the update is rejected by the remote if that doesn't match.
+/// This is what makes `--force-with-lease` work - it's safe as the comparison is performed atomically on the remote.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Update {
+    /// The full name of the reference to update on the remote, like `refs/heads/main`.
+    pub name: BString,
+    /// The value of the reference we expect on the remote, or the null-hash if it must not exist yet.
+    pub old: gix_hash::ObjectId,
+    /// The new value of the reference on the remote, or the null-hash to delete it.
+    pub new: gix_hash::ObjectId,
+}
+
+impl Update {
+    /// Return `true` if this update creates `name` on the remote.
+    pub fn is_create(&self) -> bool {
+        self.old.is_null() && !self.new.is_null()
+    }
+
+    /// Return `true` if this update deletes `name` on the remote.
+    pub fn is_delete(&self) -> bool {
+        self.new.is_null()
+    }
+
+    /// Produce the line to send for this update, without trailing newline.
+    pub fn to_line(&self) -> BString {
+        let mut out = BString::from(format!("{} {} ", self.old, self.new));
+        out.push_str(&self.name);
+        out
+    }
+}
@@ -0,0 +1,123 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// The error returned when parsing a [`Report`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to read from line reader")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    PacketlineDecode(#[from] gix_transport::packetline::decode::Error),
+    #[error("The status report did not start with an 'unpack' line, got {line:?}")]
+    MissingUnpackStatus { line: Option<BString> },
+    #[error("Encountered an unknown line in the status report: {line:?}")]
+    UnknownLineType { line: BString },
+}
+
+impl gix_transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Io(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
+
+/// The status of a single reference update as reported by the remote.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RefStatus {
+    /// The reference at `name` was updated successfully.
+    Ok {
+        /// The full name of the updated reference.
+        name: BString,
+    },
+    /// The update of the reference at `name` was rejected.
+    Rejected {
+        /// The full name of the reference that wasn't updated.
+        name: BString,
+        /// The reason for the rejection as given by the remote, like `non-fast-forward` or `atomic push failed`.
+        reason: BString,
+    },
+}
+
+impl RefStatus {
+    /// Return the full name of the reference this status is about.
+    pub fn name(&self) -> &BStr {
+        match self {
+            RefStatus::Ok { name } | RefStatus::Rejected { name, .. } => name.as_ref(),
+        }
+    }
+
+    /// Return `true` if the reference was updated.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, RefStatus::Ok { .. })
+    }
+}
+
+/// The status report sent by `git-receive-pack` if the `report-status` capability was requested.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    /// If `Some`, the pack we sent couldn't be unpacked by the remote, with the error message given here.
+    ///
+    /// Typically all reference updates are rejected in that case.
+    pub unpack_error: Option<BString>,
+    /// The status for each reference update, in the order they were sent.
+    pub refs: Vec<RefStatus>,
+}
+
+impl Report {
+    /// Parse a status report from `lines`, without trailing newlines and up to but not including the flush packet.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a BStr>) -> Result<Self, Error> {
+        let mut lines = lines.into_iter().map(|line| line.trim_end().as_bstr());
+        let unpack_error = match lines.next() {
+            Some(line) => match line.strip_prefix(b"unpack ") {
+                Some(b"ok") => None,
+                Some(error) => Some(error.into()),
+                None => {
+                    return Err(Error::MissingUnpackStatus {
+                        line: Some(line.to_owned()),
+                    })
+                }
+            },
+            None => return Err(Error::MissingUnpackStatus { line: None }),
+        };
+
+        let mut refs = Vec::new();
+        for line in lines {
+            let status = if let Some(name) = line.strip_prefix(b"ok ") {
+                RefStatus::Ok { name: name.into() }
+            } else if let Some(rest) = line.strip_prefix(b"ng ") {
+                let (name, reason) = rest.split_once_str(" ").unwrap_or((rest, b""));
+                RefStatus::Rejected {
+                    name: name.into(),
+                    reason: reason.into(),
+                }
+            } else {
+                return Err(Error::UnknownLineType { line: line.to_owned() });
+            };
+            refs.push(status);
+        }
+        Ok(Report { unpack_error, refs })
+    }
+
+    /// Read all packet lines from `reader` up to the next flush packet and parse them as status report.
+    #[cfg(feature = "blocking-client")]
+    pub fn from_line_reader(reader: &mut dyn gix_transport::client::ReadlineBufRead) -> Result<Self, Error> {
+        let mut lines = Vec::new();
+        while let Some(line) = reader.readline() {
+            let line = line??;
+            match line.as_bstr() {
+                Some(line) => lines.push(line.to_owned()),
+                None => break,
+            }
+        }
+        Self::from_lines(lines.iter().map(AsRef::as_ref))
+    }
+
+    /// Return `true` if the remote could unpack our objects and applied all reference updates.
+    pub fn is_success(&self) -> bool {
+        self.unpack_error.is_none() && self.refs.iter().all(RefStatus::is_ok)
+    }
+}
@@ -0,0 +1,84 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::push::Update;
+
+/// The error returned by [`Arguments::parse_line()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not parse object id in line {line:?}")]
+    ObjectId {
+        line: BString,
+        source: gix_hash::decode::Error,
+    },
+    #[error("The line {line:?} is not a valid reference update")]
+    InvalidUpdate { line: BString },
+}
+
+/// The arguments sent by the client to push, consisting of reference updates and the capabilities it wants to use.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Arguments {
+    /// The reference updates to perform, in order.
+    pub updates: Vec<Update>,
+    /// The commits that are shallow in the client repository, which are only sent by shallow clients.
+    pub shallow: Vec<ObjectId>,
+    /// All capabilities sent along with the first update, like `report-status` or `atomic`.
+    pub features: Vec<BString>,
+}
+
+impl Arguments {
+    /// Parse a single `line` as sent by the client and add it to our state.
+    ///
+    /// Note that capabilities after the first update are split into individual features.
+    pub fn parse_line(&mut self, line: &BStr) -> Result<(), Error> {
+        let line = line.strip_suffix(b"\n").unwrap_or(line).as_bstr();
+        let parse_id = |hex: &[u8]| {
+            ObjectId::from_hex(hex).map_err(|err| Error::ObjectId {
+                line: line.to_owned(),
+                source: err,
+            })
+        };
+        if let Some(hex) = line.strip_prefix(b"shallow ") {
+            self.shallow.push(parse_id(hex)?);
+            return Ok(());
+        }
+
+        let (update, features) = match line.split_once_str(b"\0") {
+            Some((update, features)) => (update, Some(features)),
+            None => (line.as_bytes(), None),
+        };
+        let mut tokens = update.splitn(3, |b| *b == b' ');
+        let (Some(old), Some(new), Some(name)) = (tokens.next(), tokens.next(), tokens.next()) else {
+            return Err(Error::InvalidUpdate { line: line.to_owned() });
+        };
+        if name.is_empty() {
+            return Err(Error::InvalidUpdate { line: line.to_owned() });
+        }
+        self.updates.push(Update {
+            name: name.into(),
+            old: parse_id(old)?,
+            new: parse_id(new)?,
+        });
+        if self.updates.len() == 1 {
+            self.features.extend(
+                features
+                    .into_iter()
+                    .flat_map(|f| f.split_str(" "))
+                    .filter(|f| !f.is_empty())
+                    .map(Into::into),
+            );
+        }
+        Ok(())
+    }
+
+    /// Return `true` if the client requested the `feature`, like `atomic`.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+
+    /// Return `true` if at least one of the updates isn't a deletion, which means the client will send a pack.
+    pub fn expects_pack(&self) -> bool {
+        self.updates.iter().any(|u| !u.is_delete())
+    }
+}
@@ -0,0 +1,24 @@
+/// The error returned by [`receive_pack()`](crate::receive_pack()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    PacketlineDecode(#[from] gix_packetline::decode::Error),
+    #[error(transparent)]
+    Arguments(#[from] super::arguments::Error),
+    #[error("The client disconnected unexpectedly")]
+    UnexpectedEof,
+    #[error("Could not create the quarantine directory at '{}'", path.display())]
+    CreateQuarantine {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not move '{}' from quarantine into the object database at '{}'", source_path.display(), destination.display())]
+    MigrateQuarantine {
+        source_path: std::path::PathBuf,
+        destination: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
@@ -0,0 +1,464 @@
+use std::{
+    cell::Cell,
+    io::{BufReader, Read, Write},
+    path::Path,
+    sync::atomic::AtomicBool,
+};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_packetline::{encode, PacketLineRef, StreamingPeekableIter};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use super::{
+    quarantine::{Objects, Quarantine},
+    Arguments, Error, Hooks, Options, Outcome,
+};
+use crate::{
+    handshake::Ref,
+    push::{
+        response::{RefStatus, Report},
+        Update,
+    },
+    server::{object_format, write_ref_line},
+};
+
+/// The capabilities we advertise, without those that depend on `options`.
+const CAPABILITIES: &[&str] = &[
+    "report-status",
+    "delete-refs",
+    "side-band-64k",
+    "quiet",
+    "atomic",
+    "ofs-delta",
+];
+
+/// The largest amount of data to send in a single `side-band-64k` packet, one byte less than the largest data line.
+const BAND_DATA_LEN: usize = 65515;
+
+/// Serve a client connected through `input` and `output` as `git-receive-pack` would, advertising `refs` and accepting a pack
+/// along with reference updates to apply to `store`.
+///
+/// `db` is the object database whose directory is `objects_dir`, and which is used to complete thin packs and for connectivity checks.
+/// The pack is received into a quarantine directory within `objects_dir`, and only moved into the `pack` directory after all
+/// updates were checked and accepted by `hooks`. See the [module documentation](super) for the order of operations.
+///
+/// All updates are applied in a single transaction if the client requested the `atomic` capability, or one by one otherwise.
+/// Note that updates with an `old` value that doesn't match the current value of the reference are rejected, which makes it safe for
+/// clients to push concurrently.
+///
+/// Errors that are specific to updates are only reported to the client, so an error is returned only if the client couldn't be
+/// served at all. Before returning an error that isn't caused by IO, an attempt is made to let the client know by sending an `ERR` line.
+#[allow(clippy::too_many_arguments)]
+pub fn receive_pack<Find>(
+    db: Find,
+    refs: &[Ref],
+    store: &gix_ref::store::Handle,
+    objects_dir: &Path,
+    input: impl Read,
+    mut output: impl Write,
+    hooks: &mut dyn Hooks,
+    options: Options,
+    should_interrupt: &AtomicBool,
+) -> Result<Outcome, Error>
+where
+    Find: gix_object::Find + gix_object::Exists,
+{
+    let _span = gix_trace::coarse!("gix_protocol::receive_pack()", protocol = ?options.protocol);
+    let res = serve(
+        db,
+        refs,
+        store,
+        objects_dir,
+        input,
+        &mut output,
+        hooks,
+        &options,
+        should_interrupt,
+    );
+    if let Err(err) = &res {
+        if !matches!(err, Error::Io(_)) {
+            encode::error_to_write(err.to_string().as_bytes(), &mut output).ok();
+            output.flush().ok();
+        }
+    }
+    res
+}
+
+#[allow(clippy::too_many_arguments)]
+fn serve<Find>(
+    db: Find,
+    refs: &[Ref],
+    store: &gix_ref::store::Handle,
+    objects_dir: &Path,
+    input: impl Read,
+    out: &mut impl Write,
+    hooks: &mut dyn Hooks,
+    options: &Options,
+    should_interrupt: &AtomicBool,
+) -> Result<Outcome, Error>
+where
+    Find: gix_object::Find + gix_object::Exists,
+{
+    advertise(refs, options, out)?;
+    out.flush()?;
+
+    let mut input = StreamingPeekableIter::new(input, &[PacketLineRef::Flush], options.trace);
+    let mut args = Arguments::default();
+    loop {
+        match input.read_line() {
+            None => break,
+            Some(Err(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return if args.updates.is_empty() {
+                    Ok(Outcome::default())
+                } else {
+                    Err(Error::UnexpectedEof)
+                }
+            }
+            Some(line) => {
+                if let Some(line) = line??.as_bstr() {
+                    args.parse_line(line)?;
+                }
+            }
+        }
+    }
+    if args.updates.is_empty() {
+        return Ok(Outcome::default());
+    }
+
+    let mut outcome = Outcome {
+        updates: args.updates.clone(),
+        report: None,
+        objects: 0,
+    };
+    // `None` for updates that are still accepted, or the reason for their rejection.
+    let mut rejections: Vec<Option<BString>> = vec![None; args.updates.len()];
+    let mut quarantine = Quarantine::new(objects_dir)?;
+    let mut unpack_error = None;
+    if args.expects_pack() {
+        let mut pack = BufReader::new(input.into_inner());
+        match quarantine.receive(&mut pack, &db, options.object_hash, should_interrupt) {
+            Ok(num_objects) => outcome.objects = num_objects,
+            Err(err) => {
+                unpack_error = Some(BString::from(err.to_string()));
+                rejections.fill(Some("unpacker error".into()));
+            }
+        }
+    }
+
+    let objects = quarantine.objects(&db);
+    for (update, rejection) in args.updates.iter().zip(rejections.iter_mut()) {
+        if rejection.is_some() {
+            continue;
+        }
+        *rejection = if !is_valid_name(update) {
+            Some("funny refname".into())
+        } else if !update.is_delete() && !is_connected(&objects, update.new) {
+            Some("missing necessary objects".into())
+        } else {
+            None
+        };
+    }
+
+    let use_sideband = args.has_feature("side-band-64k");
+    let accepted = accepted_updates(&args.updates, &rejections);
+    if !accepted.is_empty() {
+        if let Err(message) = hooks.pre_receive(&accepted, &objects) {
+            if use_sideband {
+                write_progress(message.as_ref(), out)?;
+            }
+            let reason = hook_rejection("pre-receive hook declined", message.as_ref());
+            rejections
+                .iter_mut()
+                .filter(|r| r.is_none())
+                .for_each(|r| *r = Some(reason.clone()));
+        }
+    }
+    for (update, rejection) in args.updates.iter().zip(rejections.iter_mut()) {
+        if rejection.is_none() {
+            if let Err(message) = hooks.update(update, &objects) {
+                if use_sideband {
+                    write_progress(message.as_ref(), out)?;
+                }
+                *rejection = Some(hook_rejection("hook declined", message.as_ref()));
+            }
+        }
+    }
+    drop(objects);
+
+    let atomic = args.has_feature("atomic");
+    if atomic && rejections.iter().any(Option::is_some) {
+        rejections
+            .iter_mut()
+            .filter(|r| r.is_none())
+            .for_each(|r| *r = Some("atomic push failure".into()));
+    }
+
+    let keep_path = if rejections.iter().any(Option::is_none) {
+        quarantine.migrate(objects_dir)?
+    } else {
+        None
+    };
+    apply_updates(store, &args.updates, &mut rejections, atomic, options);
+    if let Some(keep_path) = keep_path {
+        std::fs::remove_file(keep_path)?;
+    }
+
+    let applied = accepted_updates(&args.updates, &rejections);
+    let report = Report {
+        unpack_error,
+        refs: args
+            .updates
+            .iter()
+            .zip(rejections)
+            .map(|(update, rejection)| match rejection {
+                None => RefStatus::Ok {
+                    name: update.name.clone(),
+                },
+                Some(reason) => RefStatus::Rejected {
+                    name: update.name.clone(),
+                    reason,
+                },
+            })
+            .collect(),
+    };
+    if args.has_feature("report-status") {
+        write_report(&report, use_sideband, out)?;
+    }
+    out.flush()?;
+
+    hooks.post_receive(&applied);
+    outcome.report = Some(report);
+    Ok(outcome)
+}
+
+fn accepted_updates(updates: &[Update], rejections: &[Option<BString>]) -> Vec<Update> {
+    updates
+        .iter()
+        .zip(rejections)
+        .filter(|(_update, rejection)| rejection.is_none())
+        .map(|(update, _rejection)| update.clone())
+        .collect()
+}
+
+/// Apply all `updates` that weren't rejected yet, in one transaction if `atomic` is set, and record failures in `rejections`.
+fn apply_updates(
+    store: &gix_ref::store::Handle,
+    updates: &[Update],
+    rejections: &mut [Option<BString>],
+    atomic: bool,
+    options: &Options,
+) {
+    let edits: Vec<_> = updates
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| rejections[*idx].is_none())
+        .map(|(idx, update)| (idx, to_edit(update)))
+        .collect();
+    let committer = options.committer.as_ref().map(gix_actor::Signature::to_ref);
+    let apply = |edits: Vec<RefEdit>| {
+        store
+            .transaction()
+            .prepare(
+                edits,
+                gix_lock::acquire::Fail::Immediately,
+                gix_lock::acquire::Fail::Immediately,
+            )
+            .map_err(|err| err.to_string())
+            .and_then(|transaction| transaction.commit(committer).map_err(|err| err.to_string()))
+    };
+    if atomic {
+        let (indices, edits): (Vec<_>, Vec<_>) = edits.into_iter().unzip();
+        if edits.is_empty() {
+            return;
+        }
+        if let Err(_err) = apply(edits) {
+            gix_trace::debug!("atomic reference transaction failed: {}", _err);
+            for idx in indices {
+                rejections[idx] = Some("failed to update refs".into());
+            }
+        }
+    } else {
+        for (idx, edit) in edits {
+            if let Err(_err) = apply(vec![edit]) {
+                gix_trace::debug!("reference transaction for {} failed: {}", updates[idx].name, _err);
+                rejections[idx] = Some("failed to update ref".into());
+            }
+        }
+    }
+}
+
+fn to_edit(update: &Update) -> RefEdit {
+    let expected = |if_null: PreviousValue| {
+        if update.old.is_null() {
+            if_null
+        } else {
+            PreviousValue::MustExistAndMatch(Target::Object(update.old))
+        }
+    };
+    RefEdit {
+        change: if update.is_delete() {
+            Change::Delete {
+                expected: expected(PreviousValue::Any),
+                log: RefLog::AndReference,
+            }
+        } else {
+            Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: "push".into(),
+                },
+                expected: expected(PreviousValue::MustNotExist),
+                new: Target::Object(update.new),
+            }
+        },
+        name: FullName::try_from(update.name.clone()).expect("validated before"),
+        deref: false,
+    }
+}
+
+/// Only allow valid names of references that aren't pseudo-refs, like `HEAD`.
+fn is_valid_name(update: &Update) -> bool {
+    update.name.starts_with(b"refs/") && FullName::try_from(update.name.clone()).is_ok()
+}
+
+/// Return `true` if `tip` and all objects reachable from it are present, assuming that all objects that aren't
+/// in quarantine are connected already.
+fn is_connected<Find>(objects: &Objects<'_, Find>, tip: ObjectId) -> bool
+where
+    Find: gix_object::Find + gix_object::Exists,
+{
+    use gix_object::Exists;
+    let missing = Cell::new(false);
+    let mut connectivity = gix_fsck::Connectivity::new(objects, |_id, _kind| missing.set(true));
+    let mut seen = gix_hashtable::HashSet::default();
+    let mut queue = vec![tip];
+    let mut buf = Vec::new();
+    while let Some(id) = queue.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        if !objects.is_quarantined(&id) {
+            if objects.exists(&id) {
+                continue;
+            }
+            return false;
+        }
+        let Ok(obj) = objects.find(&id, &mut buf) else {
+            return false;
+        };
+        match obj.kind {
+            gix_object::Kind::Commit => {
+                queue.extend(gix_object::CommitRefIter::from_bytes(obj.data).parent_ids());
+                if connectivity.check_commit(&id).is_err() {
+                    return false;
+                }
+            }
+            gix_object::Kind::Tag => match gix_object::TagRefIter::from_bytes(obj.data).target_id() {
+                Ok(target) => queue.push(target),
+                Err(_) => return false,
+            },
+            gix_object::Kind::Tree => {
+                for entry in gix_object::TreeRefIter::from_bytes(obj.data, obj.hash_kind) {
+                    let Ok(entry) = entry else {
+                        return false;
+                    };
+                    // Submodule commits are not part of this repository.
+                    if !entry.mode.is_commit() {
+                        queue.push(entry.oid.to_owned());
+                    }
+                }
+            }
+            gix_object::Kind::Blob => {}
+        }
+    }
+    !missing.get()
+}
+
+/// Write all `refs` along with our capabilities, as `git-receive-pack` only speaks `V0` or `V1`.
+fn advertise(refs: &[Ref], options: &Options, out: &mut impl Write) -> std::io::Result<()> {
+    if options.protocol == gix_transport::Protocol::V1 {
+        encode::text_to_write(b"version 1", &mut *out)?;
+    }
+    let mut capabilities: BString = CAPABILITIES.join(" ").into();
+    capabilities.push_str(format!(" object-format={}", object_format(options.object_hash)));
+    if let Some(agent) = &options.agent {
+        capabilities.push_str(format!(" agent={agent}"));
+    }
+
+    let mut capabilities = Some(capabilities);
+    for r in refs {
+        if let (name, Some(target), _peeled) = r.unpack() {
+            write_ref_line(target, name, &mut capabilities, out)?;
+        }
+    }
+    if capabilities.is_some() {
+        write_ref_line(
+            &options.object_hash.null(),
+            "capabilities^{}".into(),
+            &mut capabilities,
+            out,
+        )?;
+    }
+    encode::flush_to_write(out)?;
+    Ok(())
+}
+
+/// Return `declined` along with the `message` of a hook, on a single line to fit into the status report.
+fn hook_rejection(declined: &str, message: &BStr) -> BString {
+    let lines: Vec<_> = message
+        .lines()
+        .map(ByteSlice::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return declined.into();
+    }
+    let mut reason = BString::from(format!("{declined}: "));
+    reason.push_str(lines.join(&b"; "[..]));
+    reason
+}
+
+/// Send the `message` of a hook to the client through the progress channel of the side-band, as `git` does with the output of hooks.
+fn write_progress(message: &BStr, out: &mut impl Write) -> std::io::Result<()> {
+    let mut message = message.to_owned();
+    if !message.ends_with(b"\n") {
+        message.push(b'\n');
+    }
+    for chunk in message.chunks(BAND_DATA_LEN) {
+        encode::band_to_write(gix_packetline::Channel::Progress, chunk, &mut *out)?;
+    }
+    Ok(())
+}
+
+/// Send `report` to the client, within the data channel of a side-band if `use_sideband` is set.
+fn write_report(report: &Report, use_sideband: bool, out: &mut impl Write) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut line = |text: BString| encode::data_to_write(&text, &mut buf).map(|_| ());
+    match &report.unpack_error {
+        None => line("unpack ok\n".into())?,
+        Some(err) => line(format!("unpack {err}\n").into())?,
+    }
+    for status in &report.refs {
+        match status {
+            RefStatus::Ok { name } => line(format!("ok {name}\n").into())?,
+            RefStatus::Rejected { name, reason } => line(format!("ng {name} {reason}\n").into())?,
+        }
+    }
+    encode::flush_to_write(&mut buf)?;
+
+    if use_sideband {
+        for chunk in buf.chunks(BAND_DATA_LEN) {
+            encode::band_to_write(gix_packetline::Channel::Data, chunk, &mut *out)?;
+        }
+        encode::flush_to_write(out)?;
+    } else {
+        out.write_all(&buf)?;
+    }
+    Ok(())
+}
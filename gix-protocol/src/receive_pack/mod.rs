@@ -0,0 +1,94 @@
+//! The server side of pushes, receiving packs and reference updates from clients similar to `git-receive-pack`.
+//!
+//! Receiving works with any object database and a [reference store](gix_ref::store::Handle), where the client connection is
+//! represented by a pair of [`Read`](std::io::Read) and [`Write`](std::io::Write) implementations.
+//!
+//! Use [`receive_pack()`](crate::receive_pack()) to serve a single push, which is performed in the following order:
+//!
+//! * advertise all references and our capabilities
+//! * read the reference updates of the client and the pack that follows them
+//! * write the pack into a *quarantine* directory so its objects aren't visible to other readers of the object database yet
+//! * check that all objects needed by the updated references are present
+//! * call [`Hooks::pre_receive()`] and [`Hooks::update()`] for each reference
+//! * move the pack from quarantine into the object database and apply all reference updates
+//! * send the status report to the client and call [`Hooks::post_receive()`]
+
+use bstr::BString;
+
+///
+pub mod arguments;
+pub use arguments::Arguments;
+
+mod error;
+pub use error::Error;
+
+pub(crate) mod function;
+mod quarantine;
+
+use crate::push::Update;
+
+/// Callbacks to validate pushes before they are applied, and to act on them afterwards, similar to the hooks of the same name in `git`.
+///
+/// All objects received from the client can be accessed through `objects`, even though they are still quarantined.
+/// Each method accepts all updates by default.
+pub trait Hooks {
+    /// Called once with all `updates` that passed the connectivity check, before any of them is applied.
+    ///
+    /// Return an error with a message for the client to reject all updates.
+    /// The message becomes part of the reason for each rejection, and is sent through the side-band as well if the client uses one.
+    fn pre_receive(&mut self, _updates: &[Update], _objects: &dyn gix_object::Find) -> Result<(), BString> {
+        Ok(())
+    }
+
+    /// Called for each `update` that was accepted by [`pre_receive()`](Self::pre_receive()), before any of them is applied.
+    ///
+    /// Return an error with a message for the client to reject only this update, which is passed on like the one of
+    /// [`pre_receive()`](Self::pre_receive()).
+    fn update(&mut self, _update: &Update, _objects: &dyn gix_object::Find) -> Result<(), BString> {
+        Ok(())
+    }
+
+    /// Called once with all `updates` that were applied, after the client received the status report.
+    fn post_receive(&mut self, _updates: &[Update]) {}
+}
+
+/// Accept all updates.
+impl Hooks for () {}
+
+/// Options for use in [`receive_pack()`](crate::receive_pack()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The protocol version to speak, as requested by the client. Note that `V2` isn't supported for pushes and is treated like `V0`.
+    pub protocol: gix_transport::Protocol,
+    /// The kind of hash used by the object database and thus in all references.
+    pub object_hash: gix_hash::Kind,
+    /// The value of the `agent` capability, like `git/oxide-0.1.0`.
+    pub agent: Option<String>,
+    /// The identity to use in the reflog of updated references, which is required if reflogs are written.
+    pub committer: Option<gix_actor::Signature>,
+    /// If `true`, all packetlines that are received or sent will be traced.
+    pub trace: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            protocol: gix_transport::Protocol::V0,
+            object_hash: gix_hash::Kind::Sha1,
+            agent: None,
+            committer: None,
+            trace: false,
+        }
+    }
+}
+
+/// The outcome of [`receive_pack()`](crate::receive_pack()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The reference updates requested by the client, in order.
+    pub updates: Vec<Update>,
+    /// The status of each of the `updates` as sent to the client, or `None` if the client disconnected without sending updates.
+    pub report: Option<crate::push::response::Report>,
+    /// The amount of objects in the pack that was received, or 0 if no pack was sent.
+    pub objects: u32,
+}
@@ -0,0 +1,153 @@
+use std::{
+    cell::RefCell,
+    io::BufRead,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_features::zlib;
+
+use super::Error;
+
+/// A directory next to the object database to receive a pack into, keeping its objects invisible to other readers
+/// until it's [migrated](Quarantine::migrate()). It's removed with all its contents when dropped.
+pub(super) struct Quarantine {
+    dir: PathBuf,
+    /// The received pack, if it had any objects.
+    pack: Option<(gix_pack::Bundle, gix_pack::bundle::write::Outcome)>,
+}
+
+impl Quarantine {
+    /// Create a new directory to receive objects in `objects_dir`, the directory of the object database.
+    pub fn new(objects_dir: &Path) -> Result<Self, Error> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let dir = objects_dir.join(format!("incoming-{}-{nanos:08x}", std::process::id()));
+        std::fs::create_dir(&dir).map_err(|err| Error::CreateQuarantine {
+            path: dir.clone(),
+            source: err,
+        })?;
+        Ok(Quarantine { dir, pack: None })
+    }
+
+    /// Receive the pack in `input` and write it along with an index, using `db` to complete thin packs.
+    /// Return the amount of objects in the pack.
+    pub fn receive(
+        &mut self,
+        input: &mut dyn BufRead,
+        db: impl gix_object::Find,
+        object_hash: gix_hash::Kind,
+        should_interrupt: &AtomicBool,
+    ) -> Result<u32, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let outcome = gix_pack::Bundle::write_to_directory(
+            input,
+            Some(&self.dir),
+            &mut gix_features::progress::Discard,
+            should_interrupt,
+            Some(db),
+            gix_pack::bundle::write::Options {
+                object_hash,
+                ..Default::default()
+            },
+        )?;
+        let num_objects = outcome.index.num_objects;
+        if num_objects != 0 {
+            if let Some(bundle) = outcome.to_bundle().transpose()? {
+                self.pack = Some((bundle, outcome));
+            }
+        }
+        Ok(num_objects)
+    }
+
+    /// Return a view on all objects in `db` and those in quarantine.
+    pub fn objects<'a, Find>(&'a self, db: &'a Find) -> Objects<'a, Find> {
+        Objects {
+            db,
+            pack: self.pack.as_ref().map(|(bundle, _)| bundle),
+            inflate: Default::default(),
+        }
+    }
+
+    /// Move the received pack into the `pack` directory of `objects_dir`, with its index being moved last so
+    /// readers don't see it before it's complete.
+    ///
+    /// Return the path to the `.keep` file which protects the pack until it's referenced, and which should be removed
+    /// once references were updated.
+    pub fn migrate(mut self, objects_dir: &Path) -> Result<Option<PathBuf>, Error> {
+        let Some((bundle, outcome)) = self.pack.take() else {
+            return Ok(None);
+        };
+        // The bundle maps the files we are about to move, which can't be renamed while they are in use on Windows.
+        drop(bundle);
+        let pack_dir = objects_dir.join("pack");
+        let mut keep_path = None;
+        for (source_path, is_keep) in [
+            (outcome.keep_path, true),
+            (outcome.data_path, false),
+            (outcome.index_path, false),
+        ] {
+            let Some(source_path) = source_path else {
+                continue;
+            };
+            let destination = pack_dir.join(source_path.file_name().expect("pack files have names"));
+            std::fs::rename(&source_path, &destination).map_err(|err| Error::MigrateQuarantine {
+                source_path,
+                destination: destination.clone(),
+                source: err,
+            })?;
+            if is_keep {
+                keep_path = Some(destination);
+            }
+        }
+        Ok(keep_path)
+    }
+}
+
+impl Drop for Quarantine {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+/// Objects in quarantine, falling back to the object database for everything else.
+pub(super) struct Objects<'a, Find> {
+    db: &'a Find,
+    pack: Option<&'a gix_pack::Bundle>,
+    inflate: RefCell<zlib::Inflate>,
+}
+
+impl<Find> Objects<'_, Find> {
+    /// Return `true` if `id` was received from the client.
+    pub fn is_quarantined(&self, id: &gix_hash::oid) -> bool {
+        self.pack.is_some_and(|pack| pack.index.lookup(id).is_some())
+    }
+}
+
+impl<Find> gix_object::Find for Objects<'_, Find>
+where
+    Find: gix_object::Find,
+{
+    fn try_find<'a>(
+        &self,
+        id: &gix_hash::oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        match self.pack.filter(|_| self.is_quarantined(id)) {
+            Some(pack) => pack
+                .find(id, buffer, &mut self.inflate.borrow_mut(), &mut gix_pack::cache::Never)
+                .map(|obj| obj.map(|(data, _location)| data))
+                .map_err(|err| Box::new(err) as _),
+            None => self.db.try_find(id, buffer),
+        }
+    }
+}
+
+impl<Find> gix_object::Exists for Objects<'_, Find>
+where
+    Find: gix_object::Exists,
+{
+    fn exists(&self, id: &gix_hash::oid) -> bool {
+        self.is_quarantined(id) || self.db.exists(id)
+    }
+}
@@ -0,0 +1,32 @@
+//! Utilities shared by the server-side implementations of `git-upload-pack` and `git-receive-pack`.
+
+use std::io::Write;
+
+use bstr::{BStr, BString, ByteVec};
+use gix_packetline::encode;
+
+/// Write a single reference line of a `V0` or `V1` advertisement, with `capabilities` appended if this is the first line.
+pub(crate) fn write_ref_line(
+    id: &gix_hash::oid,
+    name: &BStr,
+    capabilities: &mut Option<BString>,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    let mut line = BString::from(id.to_hex().to_string());
+    line.push(b' ');
+    line.push_str(name);
+    if let Some(capabilities) = capabilities.take() {
+        line.push(0);
+        line.push_str(capabilities);
+    }
+    line.push(b'\n');
+    encode::data_to_write(&line, out).map(|_| ())
+}
+
+/// The name of `kind` as used in the `object-format` capability.
+pub(crate) fn object_format(kind: gix_hash::Kind) -> &'static str {
+    match kind {
+        gix_hash::Kind::Sha1 => "sha1",
+        gix_hash::Kind::Sha256 => "sha256",
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::Write;
+
+use bstr::{BString, ByteVec};
+use gix_packetline::encode;
+
+use super::Options;
+use crate::{
+    handshake::Ref,
+    server::{object_format, write_ref_line},
+};
+
+/// The capabilities we advertise in `V0` and `V1`, without those that depend on the repository or `options`.
+const V1_CAPABILITIES: &[&str] = &[
+    "multi_ack",
+    "thin-pack",
+    "side-band",
+    "side-band-64k",
+    "ofs-delta",
+    "shallow",
+    "deepen-relative",
+    "no-progress",
+    "include-tag",
+    "multi_ack_detailed",
+    "no-done",
+    "filter",
+];
+
+/// Write the `V2` capability advertisement.
+pub(super) fn v2(options: &Options, out: &mut impl Write) -> std::io::Result<()> {
+    encode::text_to_write(b"version 2", &mut *out)?;
+    if let Some(agent) = &options.agent {
+        encode::text_to_write(format!("agent={agent}").as_bytes(), &mut *out)?;
+    }
+    encode::text_to_write(b"ls-refs=unborn", &mut *out)?;
+    encode::text_to_write(b"fetch=shallow filter", &mut *out)?;
+    if options.bundle_uri.is_some() {
+        encode::text_to_write(b"bundle-uri", &mut *out)?;
+    }
+    encode::text_to_write(
+        format!("object-format={}", object_format(options.object_hash)).as_bytes(),
+        &mut *out,
+    )?;
+    encode::flush_to_write(out)?;
+    Ok(())
+}
+
+/// Write all `refs` along with our capabilities as `V0` or `V1` advertisement.
+pub(super) fn v1(refs: &[Ref], options: &Options, out: &mut impl Write) -> std::io::Result<()> {
+    if options.protocol == gix_transport::Protocol::V1 {
+        encode::text_to_write(b"version 1", &mut *out)?;
+    }
+    let mut capabilities: BString = V1_CAPABILITIES.join(" ").into();
+    for r in refs {
+        if let Ref::Symbolic {
+            full_ref_name, target, ..
+        } = r
+        {
+            capabilities.push_str(format!(" symref={full_ref_name}:{target}"));
+        }
+    }
+    capabilities.push_str(format!(" object-format={}", object_format(options.object_hash)));
+    if let Some(agent) = &options.agent {
+        capabilities.push_str(format!(" agent={agent}"));
+    }
+
+    let mut capabilities = Some(capabilities);
+    for r in refs {
+        let (name, target, peeled) = r.unpack();
+        let Some(target) = target else {
+            continue;
+        };
+        write_ref_line(target, name, &mut capabilities, out)?;
+        if let Some(peeled) = peeled {
+            let mut name = name.to_owned();
+            name.push_str("^{}");
+            write_ref_line(peeled, name.as_ref(), &mut capabilities, out)?;
+        }
+    }
+    if capabilities.is_some() {
+        write_ref_line(
+            &options.object_hash.null(),
+            "capabilities^{}".into(),
+            &mut capabilities,
+            out,
+        )?;
+    }
+    encode::flush_to_write(out)?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+
+pub use crate::fetch::Filter;
+
+/// The error returned by [`Arguments::parse_line()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not parse object id in line {line:?}")]
+    ObjectId {
+        line: BString,
+        source: gix_hash::decode::Error,
+    },
+    #[error("The depth in line {line:?} must be a positive integer")]
+    InvalidDepth { line: BString },
+    #[error("The filter {spec:?} isn't supported")]
+    UnsupportedFilter { spec: BString },
+    #[error("The line {line:?} is unknown")]
+    UnknownLine { line: BString },
+}
+
+/// The arguments sent by the client to request a pack, for use in both `V1` and `V2`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Arguments {
+    /// The objects the client wants.
+    pub wants: Vec<ObjectId>,
+    /// The objects the client has, which may or may not be known to us.
+    pub haves: Vec<ObjectId>,
+    /// The commits that are shallow in the client repository.
+    pub shallow: Vec<ObjectId>,
+    /// The amount of commits to send, counted from each of the `wants`, or from the client's `shallow` commits
+    /// if the `deepen-relative` feature is set.
+    pub deepen: Option<u32>,
+    /// The filter to apply to the objects to send.
+    pub filter: Option<Filter>,
+    /// If `true`, the client has no more `haves` to send and expects a pack.
+    pub done: bool,
+    /// All flags like `ofs-delta` or `no-progress`, which in `V1` are the capabilities sent along with the first `want`.
+    pub features: Vec<BString>,
+}
+
+impl Arguments {
+    /// Parse a single `line` as sent by the client and add it to our state.
+    ///
+    /// Note that `V1` capabilities on the first `want` line are split into individual features.
+    pub fn parse_line(&mut self, line: &BStr) -> Result<(), Error> {
+        let line = line.trim_end().as_bstr();
+        let (keyword, value) = match line.split_once_str(" ") {
+            Some((keyword, value)) => (keyword.as_bstr(), Some(value.as_bstr())),
+            None => (line, None),
+        };
+        let parse_id = |hex: &[u8]| {
+            ObjectId::from_hex(hex).map_err(|err| Error::ObjectId {
+                line: line.to_owned(),
+                source: err,
+            })
+        };
+        match (keyword.as_bytes(), value) {
+            (b"want", Some(value)) => {
+                let (hex, features) = match value.split_once_str(" ") {
+                    Some((hex, features)) => (hex, Some(features)),
+                    None => (value.as_bytes(), None),
+                };
+                self.wants.push(parse_id(hex)?);
+                self.features.extend(
+                    features
+                        .into_iter()
+                        .flat_map(|f| f.split_str(" "))
+                        .filter(|f| !f.is_empty())
+                        .map(Into::into),
+                );
+            }
+            (b"have", Some(hex)) => self.haves.push(parse_id(hex)?),
+            (b"shallow", Some(hex)) => self.shallow.push(parse_id(hex)?),
+            (b"deepen", Some(depth)) => {
+                self.deepen = Some(
+                    depth
+                        .to_str()
+                        .ok()
+                        .and_then(|d| d.parse().ok())
+                        .filter(|d| *d > 0)
+                        .ok_or_else(|| Error::InvalidDepth { line: line.to_owned() })?,
+                );
+            }
+            (b"filter", Some(spec)) => {
+                self.filter = Some(
+                    Filter::from_bytes(spec)
+                        .filter(|filter| !matches!(filter, Filter::TreeDepth(depth) if *depth > 0))
+                        .ok_or_else(|| Error::UnsupportedFilter { spec: spec.to_owned() })?,
+                );
+            }
+            (b"done", None) => self.done = true,
+            (
+                b"thin-pack" | b"no-progress" | b"include-tag" | b"ofs-delta" | b"wait-for-done" | b"deepen-relative",
+                None,
+            ) => self.features.push(keyword.to_owned()),
+            _ => return Err(Error::UnknownLine { line: line.to_owned() }),
+        }
+        Ok(())
+    }
+
+    /// Return `true` if the client requested the `feature`, like `include-tag`.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+}
@@ -0,0 +1,40 @@
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The error returned by [`upload_pack()`](crate::upload_pack()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    PacketlineDecode(#[from] gix_packetline::decode::Error),
+    #[error(transparent)]
+    Arguments(#[from] super::arguments::Error),
+    #[error("The client sent an empty request")]
+    MissingCommand,
+    #[error("The command {command:?} is unknown")]
+    UnknownCommand { command: BString },
+    #[error("The line {line:?} is unknown in the context of the '{command}' command")]
+    UnknownArgument { command: &'static str, line: BString },
+    #[error("not our ref {id}")]
+    NotOurRef { id: ObjectId },
+    #[error("The object {id} was expected to be a commit")]
+    NotACommit { id: ObjectId },
+    #[error("The client disconnected unexpectedly")]
+    UnexpectedEof,
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::existing::Error),
+    #[error(transparent)]
+    FindHeader(#[from] gix_object::find::Error),
+    #[error(transparent)]
+    DecodeObject(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    MergeBase(#[from] gix_revision::merge_base::Error),
+    #[error(transparent)]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error("Interrupted")]
+    Interrupted,
+}
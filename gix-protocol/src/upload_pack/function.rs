@@ -0,0 +1,461 @@
+use std::{
+    io::{Read, Write},
+    sync::atomic::AtomicBool,
+};
+
+use bstr::{BString, ByteSlice, ByteVec};
+use gix_hash::ObjectId;
+use gix_packetline::{encode, PacketLineRef, StreamingPeekableIter};
+
+use super::{advertise, negotiate::Negotiation, pack, Arguments, Error, Options, Outcome};
+use crate::handshake::Ref;
+
+/// The largest amount of pack data to send in a single `side-band-64k` packet, one byte less than the largest data line.
+const LARGE_BAND_DATA_LEN: usize = 65515;
+/// The largest amount of pack data to send in a single `side-band` packet.
+const SMALL_BAND_DATA_LEN: usize = 995;
+
+/// Serve a client connected through `input` and `output` as `git-upload-pack` would, providing packs from `db` and advertising `refs`.
+///
+/// The `refs` are advertised in order, which is why `HEAD` should come first. Peeled references are advertised with their peeled
+/// object, and symbolic references are used to advertise their target.
+/// Only objects that exist in `db` may be requested by the client, and negotiation is performed in full to send only what's needed.
+///
+/// Before returning an error that isn't caused by IO, an attempt is made to let the client know by sending an `ERR` line.
+/// Note that the connection isn't closed by this function.
+pub fn upload_pack<Find>(
+    db: Find,
+    refs: &[Ref],
+    input: impl Read,
+    mut output: impl Write,
+    options: Options,
+    should_interrupt: &AtomicBool,
+) -> Result<Outcome, Error>
+where
+    Find: gix_pack::Find + gix_object::Find + gix_object::FindHeader + Clone + Send + 'static,
+{
+    let _span = gix_trace::coarse!("gix_protocol::upload_pack()", protocol = ?options.protocol);
+    let mut input = StreamingPeekableIter::new(input, &[PacketLineRef::Flush], options.trace);
+    let res = match options.protocol {
+        gix_transport::Protocol::V2 => serve_v2(db, refs, &mut input, &mut output, &options, should_interrupt),
+        gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => {
+            serve_v1(db, refs, &mut input, &mut output, &options, should_interrupt)
+        }
+    };
+    if let Err(err) = &res {
+        if !matches!(err, Error::Io(_)) {
+            encode::error_to_write(err.to_string().as_bytes(), &mut output).ok();
+            output.flush().ok();
+        }
+    }
+    res
+}
+
+fn serve_v2<Find>(
+    db: Find,
+    refs: &[Ref],
+    input: &mut StreamingPeekableIter<impl Read>,
+    out: &mut impl Write,
+    options: &Options,
+    should_interrupt: &AtomicBool,
+) -> Result<Outcome, Error>
+where
+    Find: gix_pack::Find + gix_object::Find + gix_object::FindHeader + Clone + Send + 'static,
+{
+    let mut outcome = Outcome::default();
+    advertise::v2(options, out)?;
+    out.flush()?;
+    loop {
+        let mut command = None;
+        let mut arguments = None::<Vec<BString>>;
+        loop {
+            match next_line(input)? {
+                Line::Eof if command.is_none() && arguments.is_none() => return Ok(outcome),
+                Line::Eof => return Err(Error::UnexpectedEof),
+                Line::Flush => break,
+                Line::Delimiter => arguments = Some(Vec::new()),
+                Line::Data(line) => match arguments.as_mut() {
+                    Some(arguments) => arguments.push(line),
+                    None => {
+                        if let Some(name) = line.strip_prefix(b"command=") {
+                            command = Some(name.trim_end().as_bstr().to_owned());
+                        }
+                    }
+                },
+            }
+        }
+        let arguments = arguments.unwrap_or_default();
+        match command.as_ref().map(|c| c.as_bytes()) {
+            None if arguments.is_empty() => return Ok(outcome),
+            None => return Err(Error::MissingCommand),
+            Some(b"ls-refs") => {
+                ls_refs(refs, &arguments, out)?;
+                outcome.ls_refs += 1;
+            }
+            Some(b"bundle-uri") if options.bundle_uri.is_some() => {
+                bundle_uri(options.bundle_uri.as_ref().expect("checked"), out)?;
+                outcome.bundle_uri += 1;
+            }
+            Some(b"fetch") => {
+                if let Some(objects) = fetch_v2(db.clone(), refs, &arguments, options, out, should_interrupt)? {
+                    outcome.packs += 1;
+                    outcome.objects += objects;
+                }
+            }
+            Some(_) => {
+                return Err(Error::UnknownCommand {
+                    command: command.expect("set"),
+                })
+            }
+        }
+        out.flush()?;
+    }
+}
+
+fn ls_refs(refs: &[Ref], arguments: &[BString], out: &mut impl Write) -> Result<(), Error> {
+    let (mut symrefs, mut peel, mut unborn, mut prefixes) = (false, false, false, Vec::new());
+    for argument in arguments {
+        match argument.trim_end() {
+            b"symrefs" => symrefs = true,
+            b"peel" => peel = true,
+            b"unborn" => unborn = true,
+            line => match line.strip_prefix(b"ref-prefix ") {
+                Some(prefix) => prefixes.push(prefix),
+                None => {
+                    return Err(Error::UnknownArgument {
+                        command: "ls-refs",
+                        line: line.into(),
+                    })
+                }
+            },
+        }
+    }
+
+    for r in refs {
+        let (name, target, peeled) = r.unpack();
+        if !prefixes.is_empty() && !prefixes.iter().any(|prefix| name.starts_with(prefix)) {
+            continue;
+        }
+        let mut line = match target {
+            Some(target) => BString::from(target.to_hex().to_string()),
+            None if unborn => "unborn".into(),
+            None => continue,
+        };
+        line.push(b' ');
+        line.push_str(name);
+        if symrefs {
+            if let Ref::Symbolic { target, .. } | Ref::Unborn { target, .. } = r {
+                line.push_str(b" symref-target:");
+                line.push_str(target);
+            }
+        }
+        if let Some(peeled) = peeled.filter(|_| peel) {
+            line.push_str(format!(" peeled:{peeled}"));
+        }
+        line.push(b'\n');
+        encode::data_to_write(&line, &mut *out)?;
+    }
+    encode::flush_to_write(out)?;
+    Ok(())
+}
+
+fn bundle_uri(list: &crate::bundle_uri::List, out: &mut impl Write) -> Result<(), Error> {
+    for mut line in list.to_lines() {
+        line.push(b'\n');
+        encode::data_to_write(&line, &mut *out)?;
+    }
+    encode::flush_to_write(out)?;
+    Ok(())
+}
+
+/// Answer a single `fetch` command, and return the amount of objects sent if a pack was sent.
+fn fetch_v2<Find>(
+    db: Find,
+    refs: &[Ref],
+    lines: &[BString],
+    options: &Options,
+    out: &mut impl Write,
+    should_interrupt: &AtomicBool,
+) -> Result<Option<usize>, Error>
+where
+    Find: gix_pack::Find + gix_object::Find + gix_object::FindHeader + Clone + Send + 'static,
+{
+    let mut args = Arguments::default();
+    for line in lines {
+        args.parse_line(line.as_ref())?;
+    }
+    assure_wants_exist(&db, &args.wants)?;
+
+    let mut negotiation = Negotiation::new(&db, &args.wants)?;
+    let mut acks = Vec::new();
+    for have in &args.haves {
+        if negotiation.add_have(&db, *have)? {
+            acks.push(*have);
+        }
+    }
+    if !args.done {
+        encode::data_to_write(b"acknowledgments\n", &mut *out)?;
+        if acks.is_empty() {
+            encode::data_to_write(b"NAK\n", &mut *out)?;
+        }
+        for id in &acks {
+            encode::data_to_write(format!("ACK {id}\n").as_bytes(), &mut *out)?;
+        }
+        if !negotiation.is_ready()? {
+            encode::flush_to_write(out)?;
+            return Ok(None);
+        }
+        encode::data_to_write(b"ready\n", &mut *out)?;
+        encode::delim_to_write(&mut *out)?;
+    }
+
+    let plan = pack::plan(&db, &args, &negotiation.common, options.bitmap.is_some())?;
+    drop(negotiation);
+    if args.deepen.is_some() {
+        encode::data_to_write(b"shallow-info\n", &mut *out)?;
+        write_shallow_info(&plan.shallow_info, out)?;
+        encode::delim_to_write(&mut *out)?;
+    }
+    encode::data_to_write(b"packfile\n", &mut *out)?;
+    let objects = pack::write(
+        db,
+        refs,
+        plan,
+        options.bitmap.as_deref(),
+        &args,
+        options.object_hash,
+        &mut pack::SidebandWriter::new(&mut *out, Some(LARGE_BAND_DATA_LEN)),
+        should_interrupt,
+    )?;
+    encode::flush_to_write(out)?;
+    Ok(Some(objects))
+}
+
+fn serve_v1<Find>(
+    db: Find,
+    refs: &[Ref],
+    input: &mut StreamingPeekableIter<impl Read>,
+    out: &mut impl Write,
+    options: &Options,
+    should_interrupt: &AtomicBool,
+) -> Result<Outcome, Error>
+where
+    Find: gix_pack::Find + gix_object::Find + gix_object::FindHeader + Clone + Send + 'static,
+{
+    let mut outcome = Outcome::default();
+    advertise::v1(refs, options, out)?;
+    out.flush()?;
+
+    let mut args = Arguments::default();
+    loop {
+        match next_line(input)? {
+            Line::Eof if args.wants.is_empty() => return Ok(outcome),
+            Line::Eof => return Err(Error::UnexpectedEof),
+            Line::Flush => break,
+            Line::Delimiter => {
+                return Err(Error::UnknownArgument {
+                    command: "upload-pack",
+                    line: "0001".into(),
+                })
+            }
+            Line::Data(line) => args.parse_line(line.as_ref())?,
+        }
+    }
+    if args.wants.is_empty() {
+        return Ok(outcome);
+    }
+    assure_wants_exist(&db, &args.wants)?;
+
+    if args.deepen.is_some() {
+        let shallow_info = pack::plan(&db, &args, &[], false)?.shallow_info;
+        write_shallow_info(&shallow_info, out)?;
+        encode::flush_to_write(&mut *out)?;
+        out.flush()?;
+    }
+
+    let mut negotiation = Negotiation::new(&db, &args.wants)?;
+    negotiate_v1(&db, &mut negotiation, &args, input, out)?;
+
+    let max_data_len = if args.has_feature("side-band-64k") {
+        Some(LARGE_BAND_DATA_LEN)
+    } else if args.has_feature("side-band") {
+        Some(SMALL_BAND_DATA_LEN)
+    } else {
+        None
+    };
+    let plan = pack::plan(&db, &args, &negotiation.common, options.bitmap.is_some())?;
+    drop(negotiation);
+    outcome.objects = pack::write(
+        db,
+        refs,
+        plan,
+        options.bitmap.as_deref(),
+        &args,
+        options.object_hash,
+        &mut pack::SidebandWriter::new(&mut *out, max_data_len),
+        should_interrupt,
+    )?;
+    outcome.packs = 1;
+    if max_data_len.is_some() {
+        encode::flush_to_write(&mut *out)?;
+    }
+    out.flush()?;
+    Ok(outcome)
+}
+
+/// Receive `have` lines until the client is `done` and send acknowledgements, similar to what `git` does.
+fn negotiate_v1(
+    db: &dyn gix_object::FindHeader,
+    negotiation: &mut Negotiation<'_>,
+    args: &Arguments,
+    input: &mut StreamingPeekableIter<impl Read>,
+    out: &mut impl Write,
+) -> Result<(), Error> {
+    #[derive(PartialEq, Eq, PartialOrd)]
+    enum MultiAck {
+        None,
+        Basic,
+        Detailed,
+    }
+    let multi_ack = if args.has_feature("multi_ack_detailed") {
+        MultiAck::Detailed
+    } else if args.has_feature("multi_ack") {
+        MultiAck::Basic
+    } else {
+        MultiAck::None
+    };
+    let no_done = args.has_feature("no-done");
+    let (mut got_common, mut got_other, mut sent_ready) = (false, false, false);
+    let mut last_common = None::<ObjectId>;
+    let ack = |id: &ObjectId, suffix: &str, out: &mut dyn Write| {
+        encode::data_to_write(format!("ACK {id}{suffix}\n").as_bytes(), out).map(|_| ())
+    };
+    loop {
+        match next_line(input)? {
+            Line::Eof => return Err(Error::UnexpectedEof),
+            Line::Delimiter => {
+                return Err(Error::UnknownArgument {
+                    command: "upload-pack",
+                    line: "0001".into(),
+                })
+            }
+            Line::Flush => {
+                if let Some(last_common) = last_common
+                    .as_ref()
+                    .filter(|_| multi_ack == MultiAck::Detailed && got_common && !got_other)
+                {
+                    if negotiation.is_ready()? {
+                        sent_ready = true;
+                        ack(last_common, " ready", out)?;
+                    }
+                }
+                if negotiation.common.is_empty() || multi_ack > MultiAck::None {
+                    encode::data_to_write(b"NAK\n", &mut *out)?;
+                }
+                if let Some(last_common) = last_common.as_ref().filter(|_| no_done && sent_ready) {
+                    ack(last_common, "", out)?;
+                    out.flush()?;
+                    return Ok(());
+                }
+                out.flush()?;
+                got_common = false;
+                got_other = false;
+            }
+            Line::Data(line) => {
+                let line = line.trim_end();
+                if line == b"done" {
+                    match last_common.as_ref() {
+                        Some(last_common) if !negotiation.common.is_empty() => {
+                            if multi_ack > MultiAck::None {
+                                ack(last_common, "", out)?;
+                            }
+                        }
+                        _ => {
+                            encode::data_to_write(b"NAK\n", &mut *out)?;
+                        }
+                    }
+                    out.flush()?;
+                    return Ok(());
+                }
+                let Some(hex) = line.strip_prefix(b"have ") else {
+                    return Err(Error::UnknownArgument {
+                        command: "upload-pack",
+                        line: line.into(),
+                    });
+                };
+                let id = ObjectId::from_hex(hex).map_err(|err| super::arguments::Error::ObjectId {
+                    line: line.into(),
+                    source: err,
+                })?;
+                if negotiation.add_have(db, id)? {
+                    got_common = true;
+                    last_common = Some(id);
+                    match multi_ack {
+                        MultiAck::Detailed => ack(&id, " common", out)?,
+                        MultiAck::Basic => ack(&id, " continue", out)?,
+                        MultiAck::None if negotiation.common.len() == 1 => ack(&id, "", out)?,
+                        MultiAck::None => {}
+                    }
+                } else {
+                    got_other = true;
+                    if multi_ack > MultiAck::None && negotiation.is_ready()? {
+                        if multi_ack == MultiAck::Detailed {
+                            sent_ready = true;
+                            ack(&id, " ready", out)?;
+                        } else {
+                            ack(&id, " continue", out)?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn write_shallow_info(info: &pack::ShallowInfo, out: &mut impl Write) -> std::io::Result<()> {
+    for id in &info.shallow {
+        encode::data_to_write(format!("shallow {id}\n").as_bytes(), &mut *out)?;
+    }
+    for id in &info.unshallow {
+        encode::data_to_write(format!("unshallow {id}\n").as_bytes(), &mut *out)?;
+    }
+    Ok(())
+}
+
+fn assure_wants_exist(db: &dyn gix_object::FindHeader, wants: &[ObjectId]) -> Result<(), Error> {
+    for want in wants {
+        if db.try_header(want)?.is_none() {
+            return Err(Error::NotOurRef { id: *want });
+        }
+    }
+    Ok(())
+}
+
+enum Line {
+    Data(BString),
+    Delimiter,
+    Flush,
+    Eof,
+}
+
+/// Read the next line from `input`, transparently continuing after flush packets, and turning the end of the stream into [`Line::Eof`].
+fn next_line(input: &mut StreamingPeekableIter<impl Read>) -> Result<Line, Error> {
+    Ok(match input.read_line() {
+        None => {
+            let line = if input.stopped_at() == Some(PacketLineRef::Flush) {
+                Line::Flush
+            } else {
+                Line::Eof
+            };
+            input.reset();
+            line
+        }
+        Some(Err(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => Line::Eof,
+        Some(line) => match line?? {
+            PacketLineRef::Data(data) => Line::Data(data.into()),
+            PacketLineRef::Delimiter => Line::Delimiter,
+            PacketLineRef::Flush => Line::Flush,
+            PacketLineRef::ResponseEnd => Line::Eof,
+        },
+    })
+}
@@ -0,0 +1,64 @@
+//! The server side of fetches and clones, serving packs to clients similar to `git-upload-pack`.
+//!
+//! Serving works with any object database and a list of [references](crate::handshake::Ref) to advertise,
+//! where the client connection is represented by a pair of [`Read`](std::io::Read) and [`Write`](std::io::Write)
+//! implementations, like `stdin` and `stdout` of a process spawned by `ssh`.
+//!
+//! Use [`upload_pack()`](crate::upload_pack()) to serve a client until it disconnects.
+
+///
+pub mod arguments;
+pub use arguments::{Arguments, Filter};
+
+mod error;
+pub use error::Error;
+
+mod advertise;
+pub(crate) mod function;
+mod negotiate;
+mod pack;
+
+/// Options for use in [`upload_pack()`](crate::upload_pack()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The protocol version to speak, as requested by the client, typically through the `GIT_PROTOCOL` environment variable.
+    pub protocol: gix_transport::Protocol,
+    /// The kind of hash used by the object database and thus in all references.
+    pub object_hash: gix_hash::Kind,
+    /// The value of the `agent` capability, like `git/oxide-0.1.0`.
+    pub agent: Option<String>,
+    /// If `true`, all packetlines that are received or sent will be traced.
+    pub trace: bool,
+    /// The reachability bitmap of the object database, to learn which objects to send without traversing commits and trees.
+    ///
+    /// It's ignored for shallow clients and when deepening.
+    pub bitmap: Option<std::sync::Arc<gix_pack::bitmap::Index>>,
+    /// If set, the `bundle-uri` capability is advertised in `V2` and this list is sent in response to the `bundle-uri` command.
+    pub bundle_uri: Option<crate::bundle_uri::List>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            protocol: gix_transport::Protocol::V2,
+            object_hash: gix_hash::Kind::Sha1,
+            agent: None,
+            trace: false,
+            bitmap: None,
+            bundle_uri: None,
+        }
+    }
+}
+
+/// The outcome of [`upload_pack()`](crate::upload_pack()).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of `ls-refs` commands that were answered, which is always 0 in `V1`.
+    pub ls_refs: usize,
+    /// The amount of `bundle-uri` commands that were answered, which is always 0 in `V1`.
+    pub bundle_uri: usize,
+    /// The amount of packs that were sent.
+    pub packs: usize,
+    /// The total amount of objects in all packs that were sent.
+    pub objects: usize,
+}
@@ -0,0 +1,90 @@
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_revision::merge_base;
+use gix_revwalk::{graph, Graph};
+
+use super::Error;
+
+/// The state of a negotiation, tracking the objects the client has in common with us.
+pub(super) struct Negotiation<'find> {
+    /// The wants of the client, peeled to commits. Non-commits don't take part in the negotiation.
+    want_commits: Vec<ObjectId>,
+    /// The commits the client has that we have as well.
+    pub common: Vec<ObjectId>,
+    /// The wants that are known to have a common commit as ancestor.
+    ready_wants: gix_hashtable::HashSet,
+    graph: Graph<'find, 'static, graph::Commit<merge_base::Flags>>,
+}
+
+impl<'find> Negotiation<'find> {
+    pub fn new(db: &'find impl gix_object::Find, wants: &[ObjectId]) -> Result<Self, Error> {
+        let mut buf = Vec::new();
+        let mut want_commits = Vec::new();
+        for want in wants {
+            if let Some(commit) = peel_to_commit(db, *want, &mut buf)? {
+                want_commits.push(commit);
+            }
+        }
+        Ok(Negotiation {
+            want_commits,
+            common: Vec::new(),
+            ready_wants: Default::default(),
+            graph: Graph::new(db, None),
+        })
+    }
+
+    /// Process the client's `have` and return `true` if it is a commit we have as well.
+    pub fn add_have(&mut self, db: &dyn gix_object::FindHeader, have: ObjectId) -> Result<bool, Error> {
+        if self.common.contains(&have) {
+            return Ok(true);
+        }
+        let is_common = db
+            .try_header(&have)
+            .map_err(Error::FindHeader)?
+            .is_some_and(|header| header.kind == gix_object::Kind::Commit);
+        if is_common {
+            self.common.push(have);
+        }
+        Ok(is_common)
+    }
+
+    /// Return `true` if each of the wants has a common commit as ancestor, so the client doesn't have to send more `haves`.
+    pub fn is_ready(&mut self) -> Result<bool, Error> {
+        if self.common.is_empty() {
+            return Ok(false);
+        }
+        for want in &self.want_commits {
+            if self.ready_wants.contains(want) {
+                continue;
+            }
+            let mut has_common_ancestor = false;
+            for common in &self.common {
+                if merge_base(*want, &[*common], &mut self.graph)?.is_some_and(|bases| bases.contains(common)) {
+                    has_common_ancestor = true;
+                    break;
+                }
+            }
+            if !has_common_ancestor {
+                return Ok(false);
+            }
+            self.ready_wants.insert(*want);
+        }
+        Ok(true)
+    }
+}
+
+/// Follow tags starting at `id` until a non-tag object is found, and return it if it's a commit.
+pub(super) fn peel_to_commit(
+    db: &dyn gix_object::Find,
+    mut id: ObjectId,
+    buf: &mut Vec<u8>,
+) -> Result<Option<ObjectId>, Error> {
+    loop {
+        let obj = db.find(&id, buf)?;
+        match obj.kind {
+            gix_object::Kind::Commit => return Ok(Some(id)),
+            gix_object::Kind::Tag => id = gix_object::TagRefIter::from_bytes(obj.data).target_id()?,
+            gix_object::Kind::Tree | gix_object::Kind::Blob => return Ok(None),
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use std::{collections::VecDeque, io::Write, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_pack::data::output;
+
+use super::{negotiate::peel_to_commit, Arguments, Error};
+use crate::handshake::Ref;
+
+/// Changes to the shallow boundary of the client, as implied by [`Arguments::deepen`].
+#[derive(Default)]
+pub(super) struct ShallowInfo {
+    /// Commits that will be the new shallow boundary in the client repository.
+    pub shallow: Vec<ObjectId>,
+    /// Commits of the client's shallow boundary whose parents will be sent.
+    pub unshallow: Vec<ObjectId>,
+}
+
+/// The commits and other objects to put into the pack, along with shallow information.
+pub(super) struct Plan {
+    /// The commits to send, in the order we encountered them.
+    commits: Vec<ObjectId>,
+    /// Tags that were asked for directly.
+    tags: Vec<ObjectId>,
+    /// Trees and blobs that were asked for directly.
+    non_commits: Vec<ObjectId>,
+    /// If `true`, all commits are expanded into the entire tree they refer to.
+    full_trees: bool,
+    /// If set, no commits were walked as all objects reachable from the wants, but not from these common commits,
+    /// will be found using a reachability bitmap.
+    bitmap_haves: Option<Vec<ObjectId>>,
+    pub shallow_info: ShallowInfo,
+}
+
+/// Figure out which commits to send based on the client `args` and the commits `common` with it.
+///
+/// If `has_bitmap` is `true`, walking commits is skipped if it's not needed to learn about shallow boundaries, so
+/// objects can be counted with the bitmap.
+pub(super) fn plan(
+    db: &dyn gix_object::Find,
+    args: &Arguments,
+    common: &[ObjectId],
+    has_bitmap: bool,
+) -> Result<Plan, Error> {
+    let mut buf = Vec::new();
+    let mut tags = Vec::new();
+    let mut non_commits = Vec::new();
+    let mut tips = Vec::new();
+    for want in &args.wants {
+        match db.find(want, &mut buf)?.kind {
+            gix_object::Kind::Tag => {
+                tags.push(*want);
+                match peel_to_commit(db, *want, &mut buf)? {
+                    Some(commit) => tips.push(commit),
+                    None => non_commits.push(*want),
+                }
+            }
+            gix_object::Kind::Commit => tips.push(*want),
+            gix_object::Kind::Tree | gix_object::Kind::Blob => non_commits.push(*want),
+        }
+    }
+
+    if has_bitmap && args.deepen.is_none() && args.shallow.is_empty() {
+        return Ok(Plan {
+            commits: Vec::new(),
+            tags,
+            non_commits,
+            full_trees: false,
+            bitmap_haves: Some(common.to_vec()),
+            shallow_info: ShallowInfo::default(),
+        });
+    }
+
+    let client_shallow: gix_hashtable::HashSet = args.shallow.iter().copied().collect();
+    // Everything reachable from common commits is known to the client, up to its shallow boundary.
+    let mut client_has = gix_hashtable::HashSet::default();
+    let mut queue: VecDeque<_> = common.iter().copied().collect();
+    while let Some(id) = queue.pop_front() {
+        if !client_has.insert(id) || client_shallow.contains(&id) {
+            continue;
+        }
+        queue.extend(parents(db, &id, &mut buf)?);
+    }
+
+    // Commits are either walked until we reach what the client has, or up to the given depth.
+    // Clients may send `deepen-relative` just because it was advertised, so without shallow commits it's ignored.
+    let mut queue = VecDeque::<(ObjectId, Option<u32>)>::new();
+    if args.deepen.is_some() && args.has_feature("deepen-relative") && !args.shallow.is_empty() {
+        queue.extend(args.shallow.iter().map(|id| (*id, Some(0))));
+        queue.extend(tips.into_iter().map(|id| (id, None)));
+    } else {
+        queue.extend(tips.into_iter().map(|id| (id, args.deepen.map(|_| 1))));
+    }
+
+    let mut shallow_info = ShallowInfo::default();
+    let mut commits = Vec::new();
+    let mut seen = gix_hashtable::HashSet::default();
+    while let Some((id, depth)) = queue.pop_front() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let is_client_shallow = client_shallow.contains(&id);
+        match depth.zip(args.deepen) {
+            Some((depth, max_depth)) => {
+                let parents = parents(db, &id, &mut buf)?;
+                if !client_has.contains(&id) {
+                    commits.push(id);
+                }
+                if depth >= max_depth {
+                    if !parents.is_empty() && !is_client_shallow {
+                        shallow_info.shallow.push(id);
+                    }
+                    continue;
+                }
+                if is_client_shallow && !parents.is_empty() {
+                    shallow_info.unshallow.push(id);
+                }
+                queue.extend(parents.into_iter().map(|id| (id, Some(depth + 1))));
+            }
+            None => {
+                if client_has.contains(&id) {
+                    continue;
+                }
+                commits.push(id);
+                if !is_client_shallow {
+                    queue.extend(parents(db, &id, &mut buf)?.into_iter().map(|id| (id, None)));
+                }
+            }
+        }
+    }
+
+    Ok(Plan {
+        commits,
+        tags,
+        non_commits,
+        full_trees: args.deepen.is_some(),
+        bitmap_haves: None,
+        shallow_info,
+    })
+}
+
+fn parents(db: &dyn gix_object::Find, id: &gix_hash::oid, buf: &mut Vec<u8>) -> Result<Vec<ObjectId>, Error> {
+    Ok(db
+        .find_commit_iter(id, buf)
+        .map_err(|err| match err {
+            gix_object::find::existing_iter::Error::Find(err) => Error::FindHeader(err),
+            gix_object::find::existing_iter::Error::NotFound { oid } => {
+                Error::FindObject(gix_object::find::existing::Error::NotFound { oid })
+            }
+            gix_object::find::existing_iter::Error::ObjectKind { .. } => Error::NotACommit { id: id.to_owned() },
+        })?
+        .parent_ids()
+        .collect())
+}
+
+/// Count all objects of `plan` and write them as pack into `out`, returning the amount of objects written.
+/// `bitmap` is used for counting if `plan` was made for it.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn write<Find>(
+    db: Find,
+    refs: &[Ref],
+    plan: Plan,
+    bitmap: Option<&gix_pack::bitmap::Index>,
+    args: &Arguments,
+    object_hash: gix_hash::Kind,
+    out: &mut dyn Write,
+    should_interrupt: &AtomicBool,
+) -> Result<usize, Error>
+where
+    Find: gix_pack::Find + gix_object::FindHeader + Send + Clone + 'static,
+{
+    use output::count::objects::ObjectExpansion;
+    let progress = gix_features::progress::Discard;
+    let count = |ids: Vec<ObjectId>, expansion: ObjectExpansion| {
+        output::count::objects_unthreaded(
+            &db,
+            &mut ids.into_iter().map(Ok),
+            &progress,
+            should_interrupt,
+            expansion,
+        )
+        .map(|(counts, _outcome)| counts)
+    };
+
+    let mut counts = match bitmap.zip(plan.bitmap_haves) {
+        Some((bitmap, haves)) => {
+            output::count::objects_with_bitmap(
+                &db,
+                bitmap,
+                args.wants.iter().copied(),
+                haves,
+                &progress,
+                should_interrupt,
+            )?
+            .0
+        }
+        None => {
+            let mut counts = count(
+                plan.commits,
+                if plan.full_trees {
+                    ObjectExpansion::TreeContents
+                } else {
+                    ObjectExpansion::TreeAdditionsComparedToAncestor
+                },
+            )?;
+            counts.extend(count(plan.non_commits, ObjectExpansion::TreeContents)?);
+            counts.extend(count(plan.tags, ObjectExpansion::AsIs)?);
+            counts
+        }
+    };
+
+    let mut seen = gix_hashtable::HashSet::default();
+    counts.retain(|c| seen.insert(c.id));
+    if args.has_feature("include-tag") {
+        let tags: Vec<_> = refs
+            .iter()
+            .filter_map(|r| match r {
+                Ref::Peeled { tag, object, .. }
+                | Ref::Symbolic {
+                    tag: Some(tag), object, ..
+                } => (seen.contains(object) && !seen.contains(tag)).then_some(*tag),
+                _ => None,
+            })
+            .collect();
+        counts.extend(
+            count(tags, ObjectExpansion::AsIs)?
+                .into_iter()
+                .filter(|c| seen.insert(c.id)),
+        );
+    }
+
+    if let Some(filter) = args.filter {
+        let mut filtered = Vec::with_capacity(counts.len());
+        for c in counts {
+            let header =
+                db.try_header(&c.id)?
+                    .ok_or(Error::FindObject(gix_object::find::existing::Error::NotFound {
+                        oid: c.id,
+                    }))?;
+            let keep = match header.kind {
+                gix_object::Kind::Blob => filter.allows_blob(header.size),
+                gix_object::Kind::Tree => filter.allows_trees(),
+                gix_object::Kind::Commit | gix_object::Kind::Tag => true,
+            };
+            // Objects that are wanted explicitly are always sent, which is what promisors rely on to fetch missing objects.
+            if keep || args.wants.contains(&c.id) {
+                filtered.push(c);
+            }
+        }
+        counts = filtered;
+    }
+
+    let num_objects = counts.len();
+    let entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+        counts,
+        db,
+        Box::new(progress),
+        output::entry::iter_from_counts::Options {
+            thread_limit: None,
+            mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+            allow_thin_pack: false,
+            chunk_size: 1000,
+            version: Default::default(),
+        },
+    ));
+    for written in output::bytes::FromEntriesIter::new(
+        entries,
+        out,
+        num_objects as u32,
+        gix_pack::data::Version::V2,
+        object_hash,
+    ) {
+        written?;
+        if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+    }
+    Ok(num_objects)
+}
+
+/// A writer to send all data on the data channel of a side-band, or as is if no side-band is used.
+pub(super) struct SidebandWriter<W> {
+    inner: W,
+    /// The maximum amount of bytes per band, or `None` to write everything as is.
+    max_data_len: Option<usize>,
+}
+
+impl<W: Write> SidebandWriter<W> {
+    pub fn new(inner: W, max_data_len: Option<usize>) -> Self {
+        SidebandWriter { inner, max_data_len }
+    }
+}
+
+impl<W: Write> Write for SidebandWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.max_data_len {
+            Some(max_data_len) => {
+                for chunk in buf.chunks(max_data_len) {
+                    gix_packetline::encode::band_to_write(gix_packetline::Channel::Data, chunk, &mut self.inner)?;
+                }
+                Ok(buf.len())
+            }
+            None => self.inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
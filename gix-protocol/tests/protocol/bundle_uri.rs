@@ -0,0 +1,102 @@
+mod list {
+    use gix_protocol::bundle_uri::{parse, Bundle, Heuristic, List, Mode};
+
+    #[test]
+    fn from_lines_with_creation_tokens() -> crate::Result {
+        let list = List::from_lines([
+            "bundle.version=1\n",
+            "bundle.mode=all",
+            "bundle.heuristic=creationToken",
+            "bundle.daily.uri=https://cdn.example.com/daily.bundle",
+            "bundle.daily.creationToken=2",
+            "bundle.base.URI=https://cdn.example.com/base.bundle",
+            "bundle.base.filter=blob:none",
+            "bundle.base.creationToken=1",
+            "bundle.daily.unknown-key=is ignored",
+            "unrelated.key=is ignored",
+        ])?;
+        assert_eq!(list.mode, Mode::All);
+        assert_eq!(list.heuristic, Some(Heuristic::CreationToken));
+        assert_eq!(
+            list.bundles,
+            [
+                Bundle {
+                    id: "daily".into(),
+                    uri: "https://cdn.example.com/daily.bundle".into(),
+                    creation_token: Some(2),
+                    filter: None,
+                },
+                Bundle {
+                    id: "base".into(),
+                    uri: "https://cdn.example.com/base.bundle".into(),
+                    creation_token: Some(1),
+                    filter: Some("blob:none".into()),
+                }
+            ],
+            "keys are case-insensitive, and bundles retain the order in which they were seen"
+        );
+        assert_eq!(
+            list.bundles_in_download_order()
+                .into_iter()
+                .map(|b| b.id.to_string())
+                .collect::<Vec<_>>(),
+            ["base", "daily"],
+            "bundles with lower creation tokens come first as the others build on them"
+        );
+        assert_eq!(
+            List::from_lines(list.to_lines())?,
+            list,
+            "lines produced from a list parse into the same list"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_lines_without_heuristic_retains_order() -> crate::Result {
+        let list = List::from_lines([
+            "bundle.mode=any",
+            "bundle.b.uri=file:///b.bundle",
+            "bundle.b.creationToken=2",
+            "bundle.a.uri=file:///a.bundle",
+            "bundle.a.creationToken=1",
+        ])?;
+        assert_eq!(list.mode, Mode::Any);
+        assert_eq!(list.heuristic, None);
+        assert_eq!(
+            list.bundles_in_download_order()
+                .into_iter()
+                .map(|b| b.id.to_string())
+                .collect::<Vec<_>>(),
+            ["b", "a"]
+        );
+        assert!(
+            List::from_lines(None::<&str>)?.bundles.is_empty(),
+            "empty lists are valid"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_lines_errors() {
+        assert!(matches!(
+            List::from_lines(["bundle.version"]),
+            Err(parse::Error::MissingSeparator { .. })
+        ));
+        assert!(matches!(
+            List::from_lines(["bundle.version=2"]),
+            Err(parse::Error::UnsupportedVersion { .. })
+        ));
+        assert!(matches!(
+            List::from_lines(["bundle.mode=some"]),
+            Err(parse::Error::UnknownMode { .. })
+        ));
+        assert!(matches!(
+            List::from_lines(["bundle.a.uri=file:///a.bundle", "bundle.a.creationToken=first"]),
+            Err(parse::Error::InvalidCreationToken { .. })
+        ));
+        assert!(matches!(
+            List::from_lines(["bundle.a.filter=blob:none"]),
+            Err(parse::Error::MissingUri { .. })
+        ));
+    }
+}
@@ -44,6 +44,25 @@ mod v1 {
                     "we don't enforce no-progress"
                 );
             }
+
+            #[test]
+            fn object_format_is_echoed_unless_it_is_sha1() {
+                assert_eq!(
+                    Command::Fetch.default_features(
+                        gix_transport::Protocol::V1,
+                        &capabilities("thin-pack object-format=sha256")
+                    ),
+                    &[("thin-pack", None), ("object-format", Some("sha256".into()))]
+                );
+                assert_eq!(
+                    Command::Fetch.default_features(
+                        gix_transport::Protocol::V1,
+                        &capabilities("thin-pack object-format=sha1")
+                    ),
+                    &[("thin-pack", None)],
+                    "servers assume SHA1 if nothing is sent"
+                );
+            }
         }
     }
 }
@@ -93,7 +112,7 @@ mod v2 {
                         .iter()
                         .map(|s| s.as_bytes().as_bstr().to_owned())
                         .collect::<Vec<_>>(),
-                    "packfile-uris isn't really supported that well and we don't support it either yet"
+                    "packfile-uris has to be requested with the protocols to use, see `Arguments::packfile_uris()`"
                 );
             }
         }
@@ -114,6 +133,22 @@ mod v2 {
                     &[]
                 );
             }
+
+            #[test]
+            fn object_format_is_echoed_unless_it_is_sha1() {
+                let capabilities = gix_transport::client::Capabilities::from_lines(
+                    "version 2\nls-refs=unborn\nobject-format=sha256".into(),
+                )
+                .expect("valid input for V2 capabilities");
+                let features = Command::LsRefs.default_features(gix_transport::Protocol::V2, &capabilities);
+                assert_eq!(features, &[("object-format", Some("sha256".into()))]);
+                assert!(
+                    Command::LsRefs
+                        .validate_argument_prefixes(gix_transport::Protocol::V2, &capabilities, &[], &features)
+                        .is_ok(),
+                    "the feature doesn't have to be listed as value of the command"
+                );
+            }
         }
 
         mod validate {
@@ -5,8 +5,10 @@ pub fn fixture_bytes(path: &str) -> Vec<u8> {
         .expect("fixture to be present and readable")
 }
 
+mod bundle_uri;
 mod command;
 pub mod fetch;
 mod handshake;
+mod push;
 pub use fetch::_impl::{fetch, FetchConnection};
 pub mod remote_progress;
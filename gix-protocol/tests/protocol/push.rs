@@ -0,0 +1,144 @@
+fn capabilities(input: &str) -> gix_transport::client::Capabilities {
+    gix_transport::client::Capabilities::from_bytes(format!("\0{input}").as_bytes())
+        .expect("valid input capabilities")
+        .0
+}
+
+fn id(hex: &str) -> gix_hash::ObjectId {
+    gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+const RECEIVE_PACK_CAPABILITIES: &str =
+    "report-status report-status-v2 delete-refs side-band-64k quiet atomic ofs-delta object-format=sha1 agent=git/2.39.5";
+
+mod arguments {
+    use bstr::ByteSlice;
+    use gix_protocol::push::{Arguments, Update};
+
+    use super::{capabilities, id, RECEIVE_PACK_CAPABILITIES};
+
+    #[test]
+    fn capabilities_go_into_the_first_line_only() {
+        let mut args = Arguments::new(&capabilities(RECEIVE_PACK_CAPABILITIES), Some("git/gix"));
+        assert!(args.is_empty());
+        assert!(args.can_use_atomic());
+        assert!(args.can_delete_refs());
+        assert!(args.expects_report());
+        args.use_atomic();
+        args.update(Update {
+            name: "refs/heads/main".into(),
+            old: id("0000000000000000000000000000000000000000"),
+            new: id("1111111111111111111111111111111111111111"),
+        });
+        args.update(Update {
+            name: "refs/heads/gone".into(),
+            old: id("2222222222222222222222222222222222222222"),
+            new: id("0000000000000000000000000000000000000000"),
+        });
+        assert!(args.needs_pack());
+        let lines: Vec<_> = args.lines().collect();
+        assert_eq!(
+            lines.iter().map(|l| l.as_bstr()).collect::<Vec<_>>(),
+            [
+                "0000000000000000000000000000000000000000 1111111111111111111111111111111111111111 refs/heads/main\0report-status quiet agent=git/gix atomic\n",
+                "2222222222222222222222222222222222222222 0000000000000000000000000000000000000000 refs/heads/gone\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn object_format_is_echoed_unless_it_is_sha1() {
+        let mut args = Arguments::new(&capabilities("report-status object-format=sha256"), None);
+        let (old, new) = (
+            gix_hash::Kind::Sha256.null(),
+            gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha256),
+        );
+        args.update(Update {
+            name: "refs/heads/main".into(),
+            old,
+            new,
+        });
+        assert_eq!(
+            args.lines().next().expect("one line"),
+            format!("{old} {new} refs/heads/main\0report-status object-format=sha256\n")
+        );
+    }
+
+    #[test]
+    fn deletions_only_need_no_pack_and_unsupported_capabilities_are_not_requested() {
+        let mut args = Arguments::new(&capabilities("delete-refs"), Some("git/gix"));
+        assert!(!args.expects_report());
+        assert!(!args.can_use_atomic());
+        args.update(Update {
+            name: "refs/tags/v1".into(),
+            old: id("2222222222222222222222222222222222222222"),
+            new: id("0000000000000000000000000000000000000000"),
+        });
+        assert!(args.updates()[0].is_delete());
+        assert!(!args.needs_pack());
+        assert_eq!(
+            args.lines().next().expect("one line"),
+            "2222222222222222222222222222222222222222 0000000000000000000000000000000000000000 refs/tags/v1\0\n"
+        );
+    }
+}
+
+mod report {
+    use bstr::BStr;
+    use gix_protocol::push::response::{Error, RefStatus, Report};
+
+    fn parse(lines: &[&str]) -> Result<Report, Error> {
+        Report::from_lines(lines.iter().map(BStr::new))
+    }
+
+    #[test]
+    fn success_and_rejections() -> crate::Result {
+        let report = parse(&[
+            "unpack ok\n",
+            "ok refs/heads/main\n",
+            "ng refs/heads/other non-fast-forward\n",
+            "ng refs/heads/x pre-receive hook declined",
+        ])?;
+        assert_eq!(report.unpack_error, None);
+        assert_eq!(
+            report.refs,
+            [
+                RefStatus::Ok {
+                    name: "refs/heads/main".into()
+                },
+                RefStatus::Rejected {
+                    name: "refs/heads/other".into(),
+                    reason: "non-fast-forward".into()
+                },
+                RefStatus::Rejected {
+                    name: "refs/heads/x".into(),
+                    reason: "pre-receive hook declined".into()
+                }
+            ]
+        );
+        assert!(!report.is_success());
+        assert_eq!(report.refs[1].name(), "refs/heads/other");
+        Ok(())
+    }
+
+    #[test]
+    fn unpack_failure() -> crate::Result {
+        let report = parse(&["unpack index-pack abnormal exit", "ng refs/heads/main unpacker error"])?;
+        assert_eq!(report.unpack_error.as_ref().expect("set"), "index-pack abnormal exit");
+        assert!(!report.is_success());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(matches!(parse(&[]), Err(Error::MissingUnpackStatus { line: None })));
+        assert!(matches!(
+            parse(&["ok refs/heads/main"]),
+            Err(Error::MissingUnpackStatus { line: Some(_) })
+        ));
+        assert!(matches!(
+            parse(&["unpack ok", "what refs/heads/main"]),
+            Err(Error::UnknownLineType { .. })
+        ));
+    }
+}
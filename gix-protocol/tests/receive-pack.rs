@@ -0,0 +1,74 @@
+fn id(hex: &str) -> gix_hash::ObjectId {
+    gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+mod arguments {
+    use bstr::ByteSlice;
+    use gix_protocol::{
+        push::Update,
+        receive_pack::{arguments, Arguments},
+    };
+
+    use super::id;
+
+    fn parse(lines: &[&str]) -> Result<Arguments, arguments::Error> {
+        let mut args = Arguments::default();
+        for line in lines {
+            args.parse_line(line.as_bytes().as_bstr())?;
+        }
+        Ok(args)
+    }
+
+    #[test]
+    fn capabilities_on_the_first_update_become_features() -> Result<(), arguments::Error> {
+        let args = parse(&[
+            "shallow 3333333333333333333333333333333333333333\n",
+            "0000000000000000000000000000000000000000 1111111111111111111111111111111111111111 refs/heads/new\0report-status atomic agent=git/2.39.5\n",
+            "1111111111111111111111111111111111111111 0000000000000000000000000000000000000000 refs/heads/old\n",
+        ])?;
+        assert_eq!(
+            args.updates,
+            [
+                Update {
+                    name: "refs/heads/new".into(),
+                    old: gix_hash::Kind::Sha1.null(),
+                    new: id("1111111111111111111111111111111111111111"),
+                },
+                Update {
+                    name: "refs/heads/old".into(),
+                    old: id("1111111111111111111111111111111111111111"),
+                    new: gix_hash::Kind::Sha1.null(),
+                },
+            ]
+        );
+        assert_eq!(args.shallow, [id("3333333333333333333333333333333333333333")]);
+        assert!(args.has_feature("report-status"));
+        assert!(args.has_feature("atomic"));
+        assert!(args.has_feature("agent=git/2.39.5"));
+        assert!(args.expects_pack());
+        Ok(())
+    }
+
+    #[test]
+    fn deletions_only_need_no_pack() -> Result<(), arguments::Error> {
+        let args = parse(&[
+            "1111111111111111111111111111111111111111 0000000000000000000000000000000000000000 refs/heads/main",
+        ])?;
+        assert!(args.features.is_empty());
+        assert!(!args.expects_pack());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_lines() {
+        for line in [
+            "1234 1111111111111111111111111111111111111111 refs/heads/main",
+            "1111111111111111111111111111111111111111 1111111111111111111111111111111111111111",
+            "1111111111111111111111111111111111111111 1111111111111111111111111111111111111111 ",
+            "shallow 1234",
+            "done",
+        ] {
+            assert!(parse(&[line]).is_err(), "{line:?} should fail");
+        }
+    }
+}
@@ -0,0 +1,123 @@
+fn id(hex: &str) -> gix_hash::ObjectId {
+    gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+mod arguments {
+    use bstr::ByteSlice;
+    use gix_protocol::upload_pack::{arguments, Arguments, Filter};
+
+    use super::id;
+
+    fn parse(lines: &[&str]) -> Result<Arguments, arguments::Error> {
+        let mut args = Arguments::default();
+        for line in lines {
+            args.parse_line(line.as_bytes().as_bstr())?;
+        }
+        Ok(args)
+    }
+
+    #[test]
+    fn v1_capabilities_on_the_first_want_become_features() -> Result<(), arguments::Error> {
+        let args = parse(&[
+            "want 1111111111111111111111111111111111111111 multi_ack_detailed side-band-64k ofs-delta agent=git/2.39.5\n",
+            "want 2222222222222222222222222222222222222222\n",
+            "shallow 3333333333333333333333333333333333333333\n",
+            "deepen 2\n",
+            "have 4444444444444444444444444444444444444444\n",
+            "done\n",
+        ])?;
+        assert_eq!(
+            args.wants,
+            [
+                id("1111111111111111111111111111111111111111"),
+                id("2222222222222222222222222222222222222222")
+            ]
+        );
+        assert_eq!(args.shallow, [id("3333333333333333333333333333333333333333")]);
+        assert_eq!(args.haves, [id("4444444444444444444444444444444444444444")]);
+        assert_eq!(args.deepen, Some(2));
+        assert!(args.done);
+        assert!(args.has_feature("side-band-64k"));
+        assert!(args.has_feature("agent=git/2.39.5"));
+        assert!(!args.has_feature("include-tag"));
+        Ok(())
+    }
+
+    #[test]
+    fn v2_flags_become_features() -> Result<(), arguments::Error> {
+        let args = parse(&[
+            "thin-pack",
+            "ofs-delta",
+            "include-tag",
+            "deepen-relative",
+            "want 1111111111111111111111111111111111111111",
+            "filter blob:none",
+        ])?;
+        assert!(args.has_feature("include-tag"));
+        assert!(args.has_feature("deepen-relative"));
+        assert_eq!(args.filter, Some(Filter::BlobNone));
+        assert!(!args.done);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_lines() {
+        for line in [
+            "want 1234",
+            "deepen 0",
+            "deepen -1",
+            "filter tree:1",
+            "deepen-since 1234",
+            "have",
+        ] {
+            assert!(parse(&[line]).is_err(), "{line:?} should fail");
+        }
+    }
+
+    #[test]
+    fn blob_limit_filter() {
+        for (spec, limit) in [
+            ("blob:limit=0", 0),
+            ("blob:limit=100", 100),
+            ("blob:limit=1k", 1024),
+            ("blob:limit=2m", 2 * 1024 * 1024),
+            ("blob:limit=1G", 1024 * 1024 * 1024),
+        ] {
+            assert_eq!(
+                Filter::from_bytes(spec.into()),
+                Some(Filter::BlobLimit(limit)),
+                "{spec}"
+            );
+        }
+        assert_eq!(Filter::from_bytes("blob:limit=".into()), None);
+        assert_eq!(Filter::from_bytes("blob:limit=k".into()), None);
+
+        let filter = Filter::BlobLimit(10);
+        assert!(filter.allows_blob(9));
+        assert!(!filter.allows_blob(10));
+        assert!(!Filter::BlobNone.allows_blob(0));
+    }
+
+    #[test]
+    fn tree_depth_filter() {
+        assert_eq!(Filter::from_bytes("tree:0".into()), Some(Filter::TreeDepth(0)));
+        assert_eq!(Filter::from_bytes("tree:2".into()), Some(Filter::TreeDepth(2)));
+        assert_eq!(Filter::from_bytes("tree:".into()), None);
+        assert!(!Filter::TreeDepth(0).allows_trees());
+        assert!(Filter::TreeDepth(1).allows_trees());
+        assert!(Filter::BlobNone.allows_trees());
+    }
+
+    #[test]
+    fn filter_display_roundtrips() {
+        for spec in ["blob:none", "blob:limit=1024", "tree:0"] {
+            let filter = Filter::from_bytes(spec.into()).expect("valid");
+            assert_eq!(filter.to_string(), spec);
+        }
+        assert_eq!(
+            Filter::from_bytes("blob:limit=1k".into()).expect("valid").to_string(),
+            "blob:limit=1024",
+            "sizes are normalized to bytes"
+        );
+    }
+}
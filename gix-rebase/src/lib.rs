@@ -1,2 +1,18 @@
-#![deny(rust_2018_idioms)]
+//! Read and write the state of a rebase as kept by git in the `rebase-merge` directory, and parse, write and rearrange
+//! the todo lists that drive it.
+//!
+//! This crate doesn't replay commits itself, which requires merging trees and updating the index and the worktree.
+//! That is the job of `gix`, which drives rebases based on the [`State`] provided here, so that rebases started by
+//! git can be continued and vice versa.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+/// The name of the directory within the git directory that holds the [`State`] of a rebase in progress.
+pub const STATE_DIR: &str = "rebase-merge";
+
+///
+pub mod todo;
+
+///
+pub mod state;
+pub use state::State;
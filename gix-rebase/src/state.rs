@@ -0,0 +1,290 @@
+use std::path::{Path, PathBuf};
+
+use bstr::{BString, ByteSlice};
+use gix_hash::{ObjectId, Prefix};
+
+use crate::todo;
+
+/// The error returned by [`State::from_dir()`] and [`State::write_to_dir()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read or write '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("'{}' doesn't contain a valid object id", path.display())]
+    ObjectId {
+        path: PathBuf,
+        source: gix_hash::decode::Error,
+    },
+    #[error("'{}' doesn't contain a valid commit id", path.display())]
+    Prefix {
+        path: PathBuf,
+        source: gix_hash::prefix::from_hex::Error,
+    },
+    #[error("'{}' doesn't contain a valid reference name", path.display())]
+    ReferenceName {
+        path: PathBuf,
+        source: gix_ref::name::Error,
+    },
+    #[error("Could not parse the todo list at '{}'", path.display())]
+    Todo { path: PathBuf, source: todo::Error },
+    #[error("'{}' isn't a valid author script", path.display())]
+    AuthorScript { path: PathBuf },
+}
+
+/// The state of a rebase in progress, as stored in the [`rebase-merge`](crate::STATE_DIR) directory by git.
+///
+/// Files that aren't represented here, like the ones holding options of the rebase, are kept as they are when writing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    /// The branch that is rebased, and that is updated when the rebase finishes, or `None` if `HEAD` was detached.
+    ///
+    /// Stored in `head-name`.
+    pub head_name: Option<gix_ref::FullName>,
+    /// The commit the first commit is replayed onto, stored in `onto`.
+    pub onto: ObjectId,
+    /// The commit `HEAD` pointed to before the rebase started, stored in `orig-head`.
+    pub orig_head: ObjectId,
+    /// The commands that are yet to be executed, stored in `git-rebase-todo`.
+    pub todo: todo::List,
+    /// The commands that were executed already, with the last one being the one the rebase stopped at, stored in `done`.
+    pub done: todo::List,
+    /// If `true`, the rebase was started by the user with their own todo list, stored as presence of `interactive`.
+    pub interactive: bool,
+    /// The commit that couldn't be replayed cleanly or that was replayed before stopping, stored in `stopped-sha`.
+    pub stopped_at: Option<Prefix>,
+    /// The commit that was created right before stopping to let the user amend it, stored in `amend`.
+    pub amend: Option<ObjectId>,
+    /// The message to use for the commit that is to be created after the user resolved conflicts, stored in `message`.
+    pub message: Option<BString>,
+    /// The author to use for the commit that is to be created after the user resolved conflicts, stored in `author-script`.
+    pub author: Option<gix_actor::Signature>,
+}
+
+impl State {
+    /// Create the state for a new rebase of `head_name` onto `onto`, with `HEAD` at `orig_head`, executing `todo`.
+    pub fn new(
+        head_name: Option<gix_ref::FullName>,
+        onto: ObjectId,
+        orig_head: ObjectId,
+        todo: todo::List,
+        interactive: bool,
+    ) -> Self {
+        State {
+            head_name,
+            onto,
+            orig_head,
+            todo,
+            done: Default::default(),
+            interactive,
+            stopped_at: None,
+            amend: None,
+            message: None,
+            author: None,
+        }
+    }
+
+    /// Clear all information about why and where the rebase stopped.
+    pub fn clear_stop(&mut self) {
+        self.stopped_at = None;
+        self.amend = None;
+        self.message = None;
+        self.author = None;
+    }
+}
+
+/// The content of `head-name` if `HEAD` was detached.
+const DETACHED_HEAD: &[u8] = b"detached HEAD";
+
+/// Reading and writing
+impl State {
+    /// Read the state from the `rebase-merge` directory at `dir`, or return `None` if it doesn't exist as no rebase is in progress.
+    pub fn from_dir(dir: &Path) -> Result<Option<Self>, Error> {
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+        let head_name = {
+            let (path, name) = read_trimmed(dir, "head-name")?;
+            if name == DETACHED_HEAD {
+                None
+            } else {
+                Some(gix_ref::FullName::try_from(name).map_err(|source| Error::ReferenceName { path, source })?)
+            }
+        };
+        let stopped_at = read_trimmed_if_present(dir, "stopped-sha")?
+            .map(|(path, hex)| {
+                hex.to_str()
+                    .map_err(|_| gix_hash::prefix::from_hex::Error::Invalid)
+                    .and_then(Prefix::from_hex)
+                    .map_err(|source| Error::Prefix { path, source })
+            })
+            .transpose()?;
+        let author = read_if_present(dir, "author-script")?
+            .map(|(path, script)| parse_author_script(&script).ok_or(Error::AuthorScript { path }))
+            .transpose()?;
+        Ok(Some(State {
+            head_name,
+            onto: read_id(dir, "onto")?,
+            orig_head: read_id(dir, "orig-head")?,
+            todo: read_todo(dir, "git-rebase-todo")?,
+            done: read_todo(dir, "done")?,
+            interactive: dir.join("interactive").is_file(),
+            stopped_at,
+            amend: read_trimmed_if_present(dir, "amend")?
+                .map(|(path, hex)| ObjectId::from_hex(&hex).map_err(|source| Error::ObjectId { path, source }))
+                .transpose()?,
+            message: read_if_present(dir, "message")?.map(|(_, message)| message),
+            author,
+        }))
+    }
+
+    /// Write this state into the `rebase-merge` directory at `dir`, creating it if needed, in a way that git understands.
+    ///
+    /// Files of optional information that isn't set are removed.
+    pub fn write_to_dir(&self, dir: &Path) -> Result<(), Error> {
+        std::fs::create_dir_all(dir).map_err(|source| Error::Io {
+            path: dir.to_owned(),
+            source,
+        })?;
+        let head_name = match &self.head_name {
+            Some(name) => name.as_bstr().as_bytes(),
+            None => DETACHED_HEAD,
+        };
+        write(dir, "head-name", Some(with_newline(head_name)))?;
+        write(dir, "onto", Some(with_newline(self.onto.to_string())))?;
+        write(dir, "orig-head", Some(with_newline(self.orig_head.to_string())))?;
+        write(dir, "git-rebase-todo", Some(self.todo.to_bstring().into()))?;
+        write(dir, "done", Some(self.done.to_bstring().into()))?;
+        write(dir, "msgnum", Some(with_newline(self.done.lines.len().to_string())))?;
+        write(
+            dir,
+            "end",
+            Some(with_newline(
+                (self.done.lines.len() + self.todo.lines.len()).to_string(),
+            )),
+        )?;
+        write(dir, "interactive", self.interactive.then(Vec::new))?;
+        write(
+            dir,
+            "stopped-sha",
+            self.stopped_at.map(|prefix| with_newline(prefix.to_string())),
+        )?;
+        write(dir, "amend", self.amend.map(|id| with_newline(id.to_string())))?;
+        write(dir, "message", self.message.clone().map(Into::into))?;
+        write(
+            dir,
+            "author-script",
+            self.author.as_ref().map(|author| author_script(author.to_ref())),
+        )?;
+        Ok(())
+    }
+}
+
+/// Produce the content of an `author-script` file that sets the name, email and date of `author` as shell variables.
+fn author_script(author: gix_actor::SignatureRef<'_>) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (name, value) in [
+        ("GIT_AUTHOR_NAME", author.name.to_owned()),
+        ("GIT_AUTHOR_EMAIL", author.email.to_owned()),
+        ("GIT_AUTHOR_DATE", format!("@{}", author.time.to_bstring()).into()),
+    ] {
+        out.extend_from_slice(name.as_bytes());
+        out.push(b'=');
+        out.extend_from_slice(&gix_quote::single(value.as_ref()));
+        out.push(b'\n');
+    }
+    out
+}
+
+/// Parse an `author-script` as written by git and by [`author_script()`], or return `None` if it's malformed.
+fn parse_author_script(script: &[u8]) -> Option<gix_actor::Signature> {
+    let (mut name, mut email, mut date) = (None, None, None);
+    for line in script.lines().filter(|line| !line.trim().is_empty()) {
+        let (key, value) = line.split_once_str("=")?;
+        let value = unquote(value)?;
+        match key {
+            b"GIT_AUTHOR_NAME" => name = Some(value),
+            b"GIT_AUTHOR_EMAIL" => email = Some(value),
+            b"GIT_AUTHOR_DATE" => date = Some(value),
+            _ => return None,
+        }
+    }
+    let date = date?;
+    let date = date.to_str().ok()?;
+    Some(gix_actor::Signature {
+        name: name?,
+        email: email?,
+        time: gix_date::parse(date.strip_prefix('@').unwrap_or(date), None).ok()?,
+    })
+}
+
+/// Undo the single-quoting of a value in an `author-script`, which may also contain `'\''` and `'\!'` to escape
+/// single quotes and exclamation marks.
+fn unquote(mut value: &[u8]) -> Option<BString> {
+    let mut out = BString::default();
+    value = value.strip_prefix(b"'")?;
+    loop {
+        let end = value.find_byte(b'\'')?;
+        out.extend_from_slice(&value[..end]);
+        value = &value[end + 1..];
+        match value {
+            [] => return Some(out),
+            [b'\\', escaped @ (b'\'' | b'!'), b'\'', rest @ ..] => {
+                out.push(*escaped);
+                value = rest;
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn with_newline(value: impl AsRef<[u8]>) -> Vec<u8> {
+    let mut out = value.as_ref().to_vec();
+    out.push(b'\n');
+    out
+}
+
+/// Write `content` to the file `name` in `dir`, or remove that file if `content` is `None`.
+fn write(dir: &Path, name: &str, content: Option<Vec<u8>>) -> Result<(), Error> {
+    let path = dir.join(name);
+    let res = match content {
+        Some(content) => std::fs::write(&path, content),
+        None => match std::fs::remove_file(&path) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        },
+    };
+    res.map_err(|source| Error::Io { path, source })
+}
+
+fn read_if_present(dir: &Path, name: &str) -> Result<Option<(PathBuf, BString)>, Error> {
+    let path = dir.join(name);
+    match std::fs::read(&path) {
+        Ok(content) => Ok(Some((path, content.into()))),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(Error::Io { path, source }),
+    }
+}
+
+fn read_trimmed_if_present(dir: &Path, name: &str) -> Result<Option<(PathBuf, BString)>, Error> {
+    Ok(read_if_present(dir, name)?.map(|(path, content)| (path, content.trim().into())))
+}
+
+fn read_trimmed(dir: &Path, name: &str) -> Result<(PathBuf, BString), Error> {
+    read_trimmed_if_present(dir, name)?.ok_or_else(|| Error::Io {
+        path: dir.join(name),
+        source: std::io::ErrorKind::NotFound.into(),
+    })
+}
+
+fn read_id(dir: &Path, name: &str) -> Result<ObjectId, Error> {
+    let (path, hex) = read_trimmed(dir, name)?;
+    ObjectId::from_hex(&hex).map_err(|source| Error::ObjectId { path, source })
+}
+
+fn read_todo(dir: &Path, name: &str) -> Result<todo::List, Error> {
+    match read_if_present(dir, name)? {
+        Some((path, content)) => todo::List::from_bytes(&content).map_err(|source| Error::Todo { path, source }),
+        None => Ok(Default::default()),
+    }
+}
@@ -0,0 +1,409 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::Prefix;
+use gix_sequencer::todo::{parse, parse::split_word};
+
+/// The error returned by [`List::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number}: unknown command '{command}'")]
+    UnknownCommand { line_number: usize, command: BString },
+    #[error("Line {line_number}: '{command}' needs an argument")]
+    MissingArgument { line_number: usize, command: &'static str },
+    #[error("Line {line_number}: '{command}' doesn't accept arguments")]
+    UnexpectedArgument { line_number: usize, command: &'static str },
+    #[error("Line {line_number}: '{commit}' isn't a valid commit id")]
+    InvalidCommit {
+        line_number: usize,
+        commit: BString,
+        source: gix_hash::prefix::from_hex::Error,
+    },
+}
+
+/// Determine which message a [`Command::Fixup`] leaves on the commit it amends.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FixupMessage {
+    /// Keep the message of the amended commit, like `fixup` does.
+    #[default]
+    Keep,
+    /// Use the message of the fixup commit instead, like `fixup -C` does.
+    Replace,
+    /// Use the message of the fixup commit and let the user edit it, like `fixup -c` does.
+    Edit,
+}
+
+/// A command in a todo list along with its arguments, as understood by `git rebase --interactive`.
+///
+/// Commits are identified by a [`Prefix`], which may be abbreviated in todo lists written by users.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Command {
+    /// Replay `commit`.
+    Pick {
+        /// The commit to replay.
+        commit: Prefix,
+    },
+    /// Replay `commit` and edit its message.
+    Reword {
+        /// The commit to replay.
+        commit: Prefix,
+    },
+    /// Replay `commit` and stop to allow amending it.
+    Edit {
+        /// The commit to replay.
+        commit: Prefix,
+    },
+    /// Meld the changes of `commit` into the previous commit, and append its message to the message of the previous commit.
+    Squash {
+        /// The commit to meld into the previous one.
+        commit: Prefix,
+    },
+    /// Meld the changes of `commit` into the previous commit, with `message` determining the resulting message.
+    Fixup {
+        /// The commit to meld into the previous one.
+        commit: Prefix,
+        /// Which message to keep.
+        message: FixupMessage,
+    },
+    /// Don't replay `commit`.
+    Drop {
+        /// The commit to skip.
+        commit: Prefix,
+    },
+    /// Run `command` with the shell, and stop if it fails.
+    Exec {
+        /// The command line to run.
+        command: BString,
+    },
+    /// Stop the rebase to allow the user to make changes, and continue with the next command later.
+    Break,
+    /// Label the current `HEAD` with `name`.
+    Label {
+        /// The name of the label.
+        name: BString,
+    },
+    /// Reset `HEAD` to the label `name`.
+    Reset {
+        /// The name of the label.
+        name: BString,
+    },
+    /// Create a merge commit, with the `arguments` kept as they are.
+    Merge {
+        /// Everything after the command name, like `-C <commit> <label>`.
+        arguments: BString,
+    },
+    /// Update the reference `name` to the current `HEAD` when the rebase finishes.
+    UpdateRef {
+        /// The name of the reference to update.
+        name: BString,
+    },
+    /// Do nothing.
+    Noop,
+}
+
+impl Command {
+    /// Return the name of the command as it's written in todo lists.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Pick { .. } => "pick",
+            Command::Reword { .. } => "reword",
+            Command::Edit { .. } => "edit",
+            Command::Squash { .. } => "squash",
+            Command::Fixup { .. } => "fixup",
+            Command::Drop { .. } => "drop",
+            Command::Exec { .. } => "exec",
+            Command::Break => "break",
+            Command::Label { .. } => "label",
+            Command::Reset { .. } => "reset",
+            Command::Merge { .. } => "merge",
+            Command::UpdateRef { .. } => "update-ref",
+            Command::Noop => "noop",
+        }
+    }
+
+    /// Return the commit this command operates on, if it is one of the commands that replays or skips a commit.
+    pub fn commit(&self) -> Option<&Prefix> {
+        match self {
+            Command::Pick { commit }
+            | Command::Reword { commit }
+            | Command::Edit { commit }
+            | Command::Squash { commit }
+            | Command::Fixup { commit, .. }
+            | Command::Drop { commit } => Some(commit),
+            Command::Exec { .. }
+            | Command::Break
+            | Command::Label { .. }
+            | Command::Reset { .. }
+            | Command::Merge { .. }
+            | Command::UpdateRef { .. }
+            | Command::Noop => None,
+        }
+    }
+}
+
+/// A single line of a todo list.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Line {
+    /// The command to execute.
+    pub command: Command,
+    /// The text following the commit of commands that take one, which typically is the subject of the commit.
+    ///
+    /// It's only informational and ignored when executing the command, except for [autosquashing](List::autosquash()).
+    pub subject: BString,
+}
+
+/// A list of commands that drive a rebase, as stored in the `git-rebase-todo` and `done` files.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct List {
+    /// The lines of the todo list, in order of execution.
+    pub lines: Vec<Line>,
+}
+
+impl From<Vec<Line>> for List {
+    fn from(lines: Vec<Line>) -> Self {
+        List { lines }
+    }
+}
+
+/// Parsing
+impl List {
+    /// Parse a todo list from `data` as written by git or by the user, ignoring empty lines and comments.
+    ///
+    /// Commands may be abbreviated to their first letter, and commits may be abbreviated as well, but must be hexadecimal.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut lines = Vec::new();
+        for (line_number, line) in parse::lines(data) {
+            let (name, rest) = split_word(line);
+            let with_commit = |name, to_command: fn(Prefix) -> Command| {
+                parse_commit(line_number, name, rest).map(|(commit, subject)| (to_command(commit), subject))
+            };
+            let (command, subject) = match name.as_bytes() {
+                b"pick" | b"p" => with_commit("pick", |commit| Command::Pick { commit })?,
+                b"reword" | b"r" => with_commit("reword", |commit| Command::Reword { commit })?,
+                b"edit" | b"e" => with_commit("edit", |commit| Command::Edit { commit })?,
+                b"squash" | b"s" => with_commit("squash", |commit| Command::Squash { commit })?,
+                b"drop" | b"d" => with_commit("drop", |commit| Command::Drop { commit })?,
+                b"fixup" | b"f" => {
+                    let (flag, after_flag) = split_word(rest);
+                    let (message, rest) = match flag.as_bytes() {
+                        b"-C" => (FixupMessage::Replace, after_flag),
+                        b"-c" => (FixupMessage::Edit, after_flag),
+                        _ => (FixupMessage::Keep, rest),
+                    };
+                    let (commit, subject) = parse_commit(line_number, "fixup", rest)?;
+                    (Command::Fixup { commit, message }, subject)
+                }
+                b"exec" | b"x" => (
+                    Command::Exec {
+                        command: parse_argument(line_number, "exec", rest)?,
+                    },
+                    Default::default(),
+                ),
+                b"label" | b"l" => (
+                    Command::Label {
+                        name: parse_argument(line_number, "label", rest)?,
+                    },
+                    Default::default(),
+                ),
+                b"reset" | b"t" => (
+                    Command::Reset {
+                        name: parse_argument(line_number, "reset", rest)?,
+                    },
+                    Default::default(),
+                ),
+                b"merge" | b"m" => (
+                    Command::Merge {
+                        arguments: parse_argument(line_number, "merge", rest)?,
+                    },
+                    Default::default(),
+                ),
+                b"update-ref" | b"u" => (
+                    Command::UpdateRef {
+                        name: parse_argument(line_number, "update-ref", rest)?,
+                    },
+                    Default::default(),
+                ),
+                b"break" | b"b" => {
+                    expect_no_argument(line_number, "break", rest)?;
+                    (Command::Break, Default::default())
+                }
+                b"noop" => {
+                    expect_no_argument(line_number, "noop", rest)?;
+                    (Command::Noop, Default::default())
+                }
+                _ => {
+                    return Err(Error::UnknownCommand {
+                        line_number,
+                        command: name.to_owned(),
+                    })
+                }
+            };
+            lines.push(Line {
+                command,
+                subject: subject.to_owned(),
+            });
+        }
+        Ok(List { lines })
+    }
+}
+
+/// Serialization
+impl List {
+    /// Write all lines of this list to `out` in the format understood by git.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        for line in &self.lines {
+            line.write_to(out)?;
+        }
+        Ok(())
+    }
+
+    /// Return the serialized form of this list, as it would be written by [`write_to()`](Self::write_to()).
+    pub fn to_bstring(&self) -> BString {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).expect("writing to a vec never fails");
+        buf.into()
+    }
+}
+
+impl Line {
+    /// Write this line to `out` in the format understood by git, including the trailing newline.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        out.write_all(self.command.name().as_bytes())?;
+        match &self.command {
+            Command::Fixup { commit, message } => {
+                match message {
+                    FixupMessage::Keep => {}
+                    FixupMessage::Replace => out.write_all(b" -C")?,
+                    FixupMessage::Edit => out.write_all(b" -c")?,
+                }
+                write!(out, " {commit}")?;
+            }
+            Command::Pick { commit }
+            | Command::Reword { commit }
+            | Command::Edit { commit }
+            | Command::Squash { commit }
+            | Command::Drop { commit } => write!(out, " {commit}")?,
+            Command::Exec { command: argument }
+            | Command::Label { name: argument }
+            | Command::Reset { name: argument }
+            | Command::Merge { arguments: argument }
+            | Command::UpdateRef { name: argument } => {
+                out.write_all(b" ")?;
+                out.write_all(argument)?;
+            }
+            Command::Break | Command::Noop => {}
+        }
+        if self.command.commit().is_some() && !self.subject.is_empty() {
+            out.write_all(b" ")?;
+            out.write_all(&self.subject)?;
+        }
+        out.write_all(b"\n")
+    }
+}
+
+/// Rearranging
+impl List {
+    /// Move commits whose subject starts with `fixup! `, `squash! ` or `amend! ` right after the commit they refer to,
+    /// and turn them into `fixup`, `squash` or `fixup -C` commands respectively, like `git rebase --autosquash` does.
+    ///
+    /// The commit is referred to by the remainder of the subject, which is matched against the [subjects](Line::subject)
+    /// of all previous commits, first exactly, then as hexadecimal prefix of their commit id, and finally as prefix of
+    /// their subject. Commits that refer to no previous commit are left alone.
+    pub fn autosquash(&mut self) {
+        let mut followers = vec![Vec::new(); self.lines.len()];
+        let mut root_of: Vec<Option<usize>> = vec![None; self.lines.len()];
+        for idx in 0..self.lines.len() {
+            let line = &self.lines[idx];
+            if line.command.commit().is_none() {
+                continue;
+            }
+            let Some((message, target)) = fixup_target(line.subject.as_ref()) else {
+                continue;
+            };
+            let candidates = || {
+                self.lines[..idx]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| line.command.commit().is_some())
+            };
+            let Some(target_idx) = candidates()
+                .find(|(_, line)| line.subject == target)
+                .or_else(|| {
+                    let target = target.to_str().ok().filter(|target| {
+                        target.len() >= Prefix::MIN_HEX_LEN && target.bytes().all(|b| b.is_ascii_hexdigit())
+                    })?;
+                    candidates().find(|(_, line)| {
+                        line.command
+                            .commit()
+                            .is_some_and(|commit| commit.to_string().starts_with(target))
+                    })
+                })
+                .or_else(|| candidates().find(|(_, line)| line.subject.starts_with(target)))
+                .map(|(idx, _)| idx)
+            else {
+                continue;
+            };
+
+            let commit = *line.command.commit().expect("checked above");
+            self.lines[idx].command = match message {
+                Some(message) => Command::Fixup { commit, message },
+                None => Command::Squash { commit },
+            };
+            let root = root_of[target_idx].unwrap_or(target_idx);
+            root_of[idx] = Some(root);
+            followers[root].push(idx);
+        }
+
+        let mut lines: Vec<_> = std::mem::take(&mut self.lines).into_iter().map(Some).collect();
+        for idx in 0..lines.len() {
+            if root_of[idx].is_some() {
+                continue;
+            }
+            self.lines.extend(lines[idx].take());
+            for follower in &followers[idx] {
+                self.lines.extend(lines[*follower].take());
+            }
+        }
+    }
+}
+
+/// Return the message a fixup with `subject` would leave, `None` for a squash, along with the subject of the commit
+/// it refers to.
+fn fixup_target(subject: &BStr) -> Option<(Option<FixupMessage>, &BStr)> {
+    let (message, target) = if let Some(target) = subject.strip_prefix(b"fixup! ") {
+        (Some(FixupMessage::Keep), target)
+    } else if let Some(target) = subject.strip_prefix(b"squash! ") {
+        (None, target)
+    } else if let Some(target) = subject.strip_prefix(b"amend! ") {
+        (Some(FixupMessage::Replace), target)
+    } else {
+        return None;
+    };
+    let target = target.trim_start();
+    (!target.is_empty()).then(|| (message, target.as_bstr()))
+}
+
+fn parse_commit<'a>(line_number: usize, command: &'static str, rest: &'a BStr) -> Result<(Prefix, &'a BStr), Error> {
+    parse::commit(rest).map_err(|err| match err {
+        parse::Error::MissingCommit => Error::MissingArgument { line_number, command },
+        parse::Error::InvalidCommit { commit, source } => Error::InvalidCommit {
+            line_number,
+            commit,
+            source,
+        },
+    })
+}
+
+fn parse_argument(line_number: usize, command: &'static str, rest: &BStr) -> Result<BString, Error> {
+    if rest.is_empty() {
+        return Err(Error::MissingArgument { line_number, command });
+    }
+    Ok(rest.to_owned())
+}
+
+fn expect_no_argument(line_number: usize, command: &'static str, rest: &BStr) -> Result<(), Error> {
+    if rest.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::UnexpectedArgument { line_number, command })
+    }
+}
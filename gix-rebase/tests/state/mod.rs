@@ -0,0 +1,101 @@
+use gix_rebase::{todo, State};
+
+use crate::hex_to_id;
+
+fn state_dir() -> gix_testtools::tempfile::TempDir {
+    gix_testtools::tempfile::tempdir().expect("can create temp dir")
+}
+
+#[test]
+fn missing_directory_means_no_rebase_in_progress() -> crate::Result {
+    let tmp = state_dir();
+    assert_eq!(State::from_dir(&tmp.path().join("rebase-merge"))?, None);
+    Ok(())
+}
+
+#[test]
+fn read_as_written_by_git() -> crate::Result {
+    let tmp = state_dir();
+    let dir = tmp.path();
+    for (name, content) in [
+        ("head-name", "refs/heads/topic\n"),
+        ("onto", "1111111111111111111111111111111111111111\n"),
+        ("orig-head", "2222222222222222222222222222222222222222\n"),
+        (
+            "git-rebase-todo",
+            "pick 3333333333333333333333333333333333333333 add b\n\n# Rebase 1111111..2222222 onto 1111111 (2 commands)\n",
+        ),
+        ("done", "edit 4444444444444444444444444444444444444444 add a\n"),
+        ("interactive", ""),
+        ("stopped-sha", "4444444444444444444444444444444444444444\n"),
+        ("amend", "5555555555555555555555555555555555555555\n"),
+        ("message", "add a\n"),
+        (
+            "author-script",
+            "GIT_AUTHOR_NAME='O'\\''Brien'\nGIT_AUTHOR_EMAIL='o@example.com'\nGIT_AUTHOR_DATE='@1700000000 +0100'\n",
+        ),
+        ("quiet", ""),
+    ] {
+        std::fs::write(dir.join(name), content)?;
+    }
+
+    let state = State::from_dir(dir)?.expect("present");
+    assert_eq!(state.head_name.as_ref().expect("set").as_bstr(), "refs/heads/topic");
+    assert_eq!(state.onto, hex_to_id("1111111111111111111111111111111111111111"));
+    assert_eq!(state.orig_head, hex_to_id("2222222222222222222222222222222222222222"));
+    assert_eq!(state.todo.lines.len(), 1, "comments are ignored");
+    assert_eq!(state.done.lines[0].command.name(), "edit");
+    assert!(state.interactive);
+    assert_eq!(
+        state.stopped_at.expect("set").to_string(),
+        "4444444444444444444444444444444444444444"
+    );
+    assert_eq!(state.amend, Some(hex_to_id("5555555555555555555555555555555555555555")));
+    assert_eq!(state.message.as_ref().expect("set"), "add a\n");
+    let author = state.author.as_ref().expect("set");
+    assert_eq!(author.name, "O'Brien", "escaped quotes are unquoted");
+    assert_eq!(author.email, "o@example.com");
+    assert_eq!(author.time, gix_date::Time::new(1700000000, 3600));
+
+    state.write_to_dir(dir)?;
+    assert_eq!(State::from_dir(dir)?.expect("present"), state, "round-trips");
+    assert!(dir.join("quiet").is_file(), "unknown files are left alone");
+    assert_eq!(std::fs::read_to_string(dir.join("msgnum"))?, "1\n");
+    assert_eq!(std::fs::read_to_string(dir.join("end"))?, "2\n");
+    Ok(())
+}
+
+#[test]
+fn write_detached_and_clear_stop() -> crate::Result {
+    let tmp = state_dir();
+    let dir = tmp.path().join("rebase-merge");
+    let mut state = State::new(
+        None,
+        hex_to_id("1111111111111111111111111111111111111111"),
+        hex_to_id("2222222222222222222222222222222222222222"),
+        todo::List::from_bytes(b"pick 3333333 add b\nexec true\n")?,
+        false,
+    );
+    state.amend = Some(hex_to_id("5555555555555555555555555555555555555555"));
+    state.message = Some("message\n".into());
+    state.author = Some(gix_actor::Signature {
+        name: "name".into(),
+        email: "email".into(),
+        time: gix_date::Time::new(42, -7200),
+    });
+    state.write_to_dir(&dir)?;
+    assert_eq!(std::fs::read_to_string(dir.join("head-name"))?, "detached HEAD\n");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("author-script"))?,
+        "GIT_AUTHOR_NAME='name'\nGIT_AUTHOR_EMAIL='email'\nGIT_AUTHOR_DATE='@42 -0200'\n"
+    );
+    assert_eq!(State::from_dir(&dir)?.expect("present"), state);
+
+    state.clear_stop();
+    state.write_to_dir(&dir)?;
+    for name in ["interactive", "amend", "message", "author-script", "stopped-sha"] {
+        assert!(!dir.join(name).exists(), "{name} is removed if unset");
+    }
+    assert_eq!(State::from_dir(&dir)?.expect("present"), state);
+    Ok(())
+}
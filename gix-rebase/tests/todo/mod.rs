@@ -0,0 +1,155 @@
+use gix_rebase::todo::{Command, Error, FixupMessage, List};
+
+fn subjects_and_commands(list: &List) -> Vec<(&'static str, String)> {
+    list.lines
+        .iter()
+        .map(|line| (line.command.name(), line.subject.to_string()))
+        .collect()
+}
+
+#[test]
+fn parse_all_commands_with_abbreviations_and_comments() -> Result<(), Error> {
+    let list = List::from_bytes(
+        b"pick 1234567 first
+# a comment
+
+r 2345678 second
+e 3456789 third
+s 4567890 fourth
+f -C 5678901 fifth
+fixup -c 6789012 sixth
+f 7890123 seventh
+d 8901234 eighth
+x make test && echo done
+b
+label onto
+t onto
+merge -C 9012345 topic # Merge topic
+u refs/heads/topic
+noop
+",
+    )?;
+    assert_eq!(
+        list.lines.iter().map(|line| line.command.name()).collect::<Vec<_>>(),
+        [
+            "pick",
+            "reword",
+            "edit",
+            "squash",
+            "fixup",
+            "fixup",
+            "fixup",
+            "drop",
+            "exec",
+            "break",
+            "label",
+            "reset",
+            "merge",
+            "update-ref",
+            "noop"
+        ]
+    );
+    assert_eq!(list.lines[0].subject, "first");
+    assert_eq!(list.lines[0].command.commit().expect("pick").to_string(), "1234567");
+    assert!(matches!(
+        list.lines[4].command,
+        Command::Fixup {
+            message: FixupMessage::Replace,
+            ..
+        }
+    ));
+    assert!(matches!(
+        list.lines[5].command,
+        Command::Fixup {
+            message: FixupMessage::Edit,
+            ..
+        }
+    ));
+    assert!(matches!(
+        list.lines[6].command,
+        Command::Fixup {
+            message: FixupMessage::Keep,
+            ..
+        }
+    ));
+    assert_eq!(
+        list.lines[8].command,
+        Command::Exec {
+            command: "make test && echo done".into()
+        }
+    );
+    assert_eq!(
+        list.lines[12].command,
+        Command::Merge {
+            arguments: "-C 9012345 topic # Merge topic".into()
+        }
+    );
+    assert_eq!(List::from_bytes(&list.to_bstring())?, list, "serialization round-trips");
+    Ok(())
+}
+
+#[test]
+fn write_uses_full_command_names() -> Result<(), Error> {
+    let list = List::from_bytes(b"p 1234567 first\nf -c 2345678\nx true\nb\n")?;
+    assert_eq!(
+        list.to_bstring(),
+        "pick 1234567 first\nfixup -c 2345678\nexec true\nbreak\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn parse_errors() {
+    for (input, expected) in [
+        (
+            &b"pick 1234567\nfrobnicate 1234567"[..],
+            "Line 2: unknown command 'frobnicate'",
+        ),
+        (b"pick", "Line 1: 'pick' needs an argument"),
+        (b"exec", "Line 1: 'exec' needs an argument"),
+        (b"break now", "Line 1: 'break' doesn't accept arguments"),
+        (b"pick xyz", "Line 1: 'xyz' isn't a valid commit id"),
+    ] {
+        let err = List::from_bytes(input).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+}
+
+mod autosquash {
+    use gix_rebase::todo::List;
+
+    use crate::todo::subjects_and_commands;
+
+    #[test]
+    fn melds_follow_their_target_in_order() -> Result<(), gix_rebase::todo::Error> {
+        let mut list = List::from_bytes(
+            b"pick 1111111 add a
+pick 2222222 add b
+pick 3333333 fixup! add a
+pick 4444444 squash! add b
+pick 5555555 amend! add a
+pick 6666666 fixup! fixup! add a
+pick 7777777 fixup! 2222222
+pick 8888888 fixup! add
+pick 9999999 fixup! unknown
+",
+        )?;
+        list.autosquash();
+        assert_eq!(
+            subjects_and_commands(&list),
+            [
+                ("pick", "add a".into()),
+                ("fixup", "fixup! add a".into()),
+                ("fixup", "amend! add a".into()),
+                ("fixup", "fixup! fixup! add a".into()),
+                ("fixup", "fixup! add".into()),
+                ("pick", "add b".into()),
+                ("squash", "squash! add b".into()),
+                ("fixup", "fixup! 2222222".into()),
+                ("pick", "fixup! unknown".into()),
+            ],
+            "exact subjects match first, then hex prefixes of commits, then subject prefixes"
+        );
+        Ok(())
+    }
+}
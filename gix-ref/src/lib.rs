@@ -14,6 +14,10 @@
 //!     * one reference maps to a file on disk
 //!   * **packed**
 //!     * references are stored in a single human-readable file, along with their targets if they are symbolic.
+//! * **[reftable][reftable::Store]**
+//!   * references and their logs are stored in a stack of binary tables.
+//!
+//! The [`Store`] uses the one configured by `extensions.refStorage`, see [`store::Backend`].
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -29,7 +33,7 @@ use gix_object::bstr::{BStr, BString};
 
 #[path = "store/mod.rs"]
 mod store_impl;
-pub use store_impl::{file, packed};
+pub use store_impl::{file, packed, reftable};
 
 mod fullname;
 ///
@@ -57,6 +61,8 @@ pub mod store {
     ///
     pub mod init {
 
+        pub use super::general::init::Error;
+
         /// Options for use during [initialization](crate::file::Store::at).
         #[derive(Debug, Copy, Clone, Default)]
         pub struct Options {
@@ -84,18 +90,27 @@ pub mod store {
         Disable,
     }
 
+    /// The way references are stored, as configured by `extensions.refStorage`.
+    #[derive(Default, Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Clone, Copy)]
+    pub enum Backend {
+        /// Loose references in files along with a `packed-refs` file, the default.
+        #[default]
+        Files,
+        /// References and their logs in a stack of [reftables](crate::reftable::Store).
+        Reftable,
+    }
+
     /// A thread-local handle for interacting with a [`Store`][crate::Store] to find and iterate references.
     #[derive(Clone)]
-    #[allow(dead_code)]
-    pub(crate) struct Handle {
+    pub struct Handle {
         /// A way to access shared state with the requirement that interior mutability doesn't leak or is incorporated into error types
         /// if it could. The latter can't happen if references to said internal aren't ever returned.
         state: handle::State,
     }
 
-    #[allow(dead_code)]
     pub(crate) enum State {
         Loose { store: file::Store },
+        Reftable { store: reftable::Store },
     }
 
     pub(crate) mod general;
@@ -103,14 +118,14 @@ pub mod store {
     ///
     #[path = "general/handle/mod.rs"]
     mod handle;
-    use crate::file;
-    pub use handle::find;
+    use crate::{file, reftable};
+    pub use handle::{find, iter, log, transaction, ReferenceExt};
 }
 
-/// The git reference store.
-/// TODO: Figure out if handles are needed at all, which depends on the ref-table implementation.
-#[allow(dead_code)]
-pub(crate) struct Store {
+/// The git reference store, which is backed by either [files](file::Store) or [reftables](reftable::Store).
+///
+/// Use [`to_handle()`](Store::to_handle()) to interact with it.
+pub struct Store {
     inner: store::State,
 }
 
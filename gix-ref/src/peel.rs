@@ -19,17 +19,53 @@ pub mod to_id {
 pub mod to_object {
     use std::path::PathBuf;
 
-    use crate::file;
+    use crate::{file, store};
 
-    /// The error returned by [`file::ReferenceExt::follow_to_object_in_place_packed()`].
+    /// The error returned by [`file::ReferenceExt::follow_to_object_in_place_packed()`] and
+    /// [`store::ReferenceExt::follow_to_object_in_place_packed()`].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
         #[error("Could not follow a single level of a symbolic reference")]
         Follow(#[from] file::find::existing::Error),
+        #[error("Could not follow a single level of a symbolic reference")]
+        FollowInStore(#[from] store::find::existing::Error),
         #[error("Aborting due to reference cycle with first seen path being {start_absolute:?}")]
         Cycle { start_absolute: PathBuf },
         #[error("Refusing to follow more than {max_depth} levels of indirection")]
         DepthLimitExceeded { max_depth: usize },
     }
 }
+
+/// Peel the object `oid` that `reference` points to until the first non-tag object, and make it the target and
+/// peeled value of `reference`, using `objects` to access the tags.
+pub(crate) fn tags_in_place(
+    reference: &mut crate::Reference,
+    mut oid: gix_hash::ObjectId,
+    objects: &dyn gix_object::Find,
+) -> Result<gix_hash::ObjectId, to_id::Error> {
+    let mut buf = Vec::new();
+    let peeled_id = loop {
+        let gix_object::Data { kind, data, .. } =
+            objects
+                .try_find(&oid, &mut buf)?
+                .ok_or_else(|| to_id::Error::NotFound {
+                    oid,
+                    name: reference.name.0.clone(),
+                })?;
+        match kind {
+            gix_object::Kind::Tag => {
+                oid = gix_object::TagRefIter::from_bytes(data)
+                    .target_id()
+                    .map_err(|_err| to_id::Error::NotFound {
+                        oid,
+                        name: reference.name.0.clone(),
+                    })?;
+            }
+            _ => break oid,
+        }
+    };
+    reference.peeled = Some(peeled_id);
+    reference.target = crate::Target::Object(peeled_id);
+    Ok(peeled_id)
+}
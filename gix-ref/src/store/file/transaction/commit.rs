@@ -1,7 +1,7 @@
 use crate::{
-    store_impl::file::{transaction::PackedRefs, Transaction},
+    store_impl::file::{self, transaction::PackedRefs, Transaction},
     transaction::{Change, LogChange, RefEdit, RefLog},
-    Target,
+    FullName, Target,
 };
 
 impl Transaction<'_, '_> {
@@ -49,13 +49,21 @@ impl Transaction<'_, '_> {
                     };
                     if update_reflog {
                         let log_update = match new {
-                            Target::Symbolic(_) => {
+                            Target::Symbolic(new_referent) => {
                                 // Special HACK: no reflog for symref changes as there is no OID involved which the reflog needs.
                                 // Unless, the ref is new and we can obtain a peeled id
-                                // identified by the expectation of what could be there, as is the case when cloning.
+                                // identified by the expectation of what could be there, as is the case when cloning,
+                                // or the ref changes its referent, like `HEAD` does when switching branches, and we log
+                                // the ids of the previous and the new referent.
                                 match expected {
                                     PreviousValue::ExistingMustMatch(Target::Object(oid)) => {
-                                        Some((Some(gix_hash::ObjectId::null(oid.kind())), oid))
+                                        Some((Some(gix_hash::ObjectId::null(oid.kind())), *oid))
+                                    }
+                                    PreviousValue::MustExistAndMatch(Target::Symbolic(previous_referent))
+                                        if previous_referent != new_referent =>
+                                    {
+                                        referent_id(self.store, new_referent)
+                                            .map(|new_oid| (referent_id(self.store, previous_referent), new_oid))
                                     }
                                     _ => None,
                                 }
@@ -65,19 +73,25 @@ impl Transaction<'_, '_> {
                                     // Here, this means that the ref already existed, and that it will receive (even transitively)
                                     // the given value
                                     PreviousValue::MustExistAndMatch(Target::Object(oid)) => Some(oid.to_owned()),
+                                    // A symbolic ref is detached, like `HEAD` is when checking out a commit.
+                                    PreviousValue::MustExistAndMatch(Target::Symbolic(previous_referent))
+                                        if log.mode == RefLog::AndReference =>
+                                    {
+                                        referent_id(self.store, previous_referent)
+                                    }
                                     _ => None,
                                 }
                                 .or(change.leaf_referent_previous_oid);
-                                Some((previous, new_oid))
+                                Some((previous, *new_oid))
                             }
                         };
                         if let Some((previous, new_oid)) = log_update {
-                            let do_update = previous.as_ref() != Some(new_oid);
+                            let do_update = previous != Some(new_oid);
                             if do_update {
                                 self.store.reflog_create_or_append(
                                     change.update.name.as_ref(),
                                     previous,
-                                    new_oid,
+                                    &new_oid,
                                     committer,
                                     log.message.as_ref(),
                                     log.force_create_reflog,
@@ -176,6 +190,16 @@ impl Transaction<'_, '_> {
         Ok(updates.into_iter().map(|edit| edit.update).collect())
     }
 }
+
+/// Return the id the reference named `referent` points to, if it exists and isn't symbolic.
+fn referent_id(store: &file::Store, referent: &FullName) -> Option<gix_hash::ObjectId> {
+    store
+        .try_find(referent.as_ref())
+        .ok()
+        .flatten()
+        .and_then(|reference| reference.target.try_into_id().ok())
+}
+
 mod error {
     use gix_object::bstr::BString;
 
@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+
+use gix_hash::ObjectId;
+
+use crate::{
+    file, packed, peel,
+    store::{self, find::existing, handle::State},
+    Reference, Target,
+};
+
+pub trait Sealed {}
+impl Sealed for crate::Reference {}
+
+/// A trait to extend [Reference][crate::Reference] with functionality requiring a [store::Handle], independently
+/// of the backend that holds the references.
+pub trait ReferenceExt: Sealed {
+    /// A step towards obtaining forward or reverse iterators on reference logs.
+    fn log_iter<'a, 's>(&'a self, store: &'s store::Handle) -> store::log::Platform<'a, 's>;
+
+    /// Return `true` if a reflog exists for this reference, and `false` if it doesn't or if it couldn't be read.
+    fn log_exists(&self, store: &store::Handle) -> bool;
+
+    /// Follow all symbolic targets this reference might point to and peel the underlying object
+    /// to the end of the tag-chain, returning the first non-tag object the annotated tag points to,
+    /// using `objects` to access them and `store` to lookup symbolic references.
+    fn peel_to_id_in_place(
+        &mut self,
+        store: &store::Handle,
+        objects: &dyn gix_object::Find,
+    ) -> Result<ObjectId, peel::to_id::Error>;
+
+    /// Like [`ReferenceExt::peel_to_id_in_place()`], but with support for a known stable `packed` buffer
+    /// to use for resolving symbolic links, which is ignored if references are kept in reftables.
+    fn peel_to_id_in_place_packed(
+        &mut self,
+        store: &store::Handle,
+        objects: &dyn gix_object::Find,
+        packed: Option<&packed::Buffer>,
+    ) -> Result<ObjectId, peel::to_id::Error>;
+
+    /// Like [`ReferenceExt::follow()`], but follows all symbolic references while gracefully handling loops,
+    /// altering this instance in place.
+    fn follow_to_object_in_place_packed(
+        &mut self,
+        store: &store::Handle,
+        packed: Option<&packed::Buffer>,
+    ) -> Result<ObjectId, peel::to_object::Error>;
+
+    /// Follow this symbolic reference one level and return the ref it refers to.
+    ///
+    /// Returns `None` if this is not a symbolic reference, hence the leaf of the chain.
+    fn follow(&self, store: &store::Handle) -> Option<Result<Reference, existing::Error>>;
+}
+
+impl ReferenceExt for Reference {
+    fn log_iter<'a, 's>(&'a self, store: &'s store::Handle) -> store::log::Platform<'a, 's> {
+        store::log::Platform {
+            store,
+            name: self.name.as_ref(),
+            buf: Vec::new(),
+        }
+    }
+
+    fn log_exists(&self, store: &store::Handle) -> bool {
+        store.reflog_exists(self.name.as_ref()).unwrap_or(false)
+    }
+
+    fn peel_to_id_in_place(
+        &mut self,
+        store: &store::Handle,
+        objects: &dyn gix_object::Find,
+    ) -> Result<ObjectId, peel::to_id::Error> {
+        match &store.state {
+            State::Loose { store } => file::ReferenceExt::peel_to_id_in_place(self, store, objects),
+            State::Reftable { .. } => self.peel_to_id_in_place_packed(store, objects, None),
+        }
+    }
+
+    fn peel_to_id_in_place_packed(
+        &mut self,
+        store: &store::Handle,
+        objects: &dyn gix_object::Find,
+        packed: Option<&packed::Buffer>,
+    ) -> Result<ObjectId, peel::to_id::Error> {
+        match &store.state {
+            State::Loose { store } => file::ReferenceExt::peel_to_id_in_place_packed(self, store, objects, packed),
+            State::Reftable { .. } => match self.peeled {
+                Some(peeled) => {
+                    self.target = Target::Object(peeled);
+                    Ok(peeled)
+                }
+                None => {
+                    let oid = self.follow_to_object_in_place_packed(store, None)?;
+                    peel::tags_in_place(self, oid, objects)
+                }
+            },
+        }
+    }
+
+    fn follow_to_object_in_place_packed(
+        &mut self,
+        store: &store::Handle,
+        packed: Option<&packed::Buffer>,
+    ) -> Result<ObjectId, peel::to_object::Error> {
+        if let State::Loose { store } = &store.state {
+            return file::ReferenceExt::follow_to_object_in_place_packed(self, store, packed);
+        }
+        match self.target {
+            Target::Object(id) => Ok(id),
+            Target::Symbolic(_) => {
+                let mut seen = BTreeSet::new();
+                let cursor = &mut *self;
+                while let Some(next) = cursor.follow(store) {
+                    let next = next?;
+                    if seen.contains(&next.name) {
+                        return Err(peel::to_object::Error::Cycle {
+                            start_absolute: store.git_dir().join(cursor.name.to_path()),
+                        });
+                    }
+                    *cursor = next;
+                    seen.insert(cursor.name.clone());
+                    const MAX_REF_DEPTH: usize = 5;
+                    if seen.len() == MAX_REF_DEPTH {
+                        return Err(peel::to_object::Error::DepthLimitExceeded {
+                            max_depth: MAX_REF_DEPTH,
+                        });
+                    }
+                }
+                let oid = self.target.try_id().expect("peeled ref").to_owned();
+                Ok(oid)
+            }
+        }
+    }
+
+    fn follow(&self, store: &store::Handle) -> Option<Result<Reference, existing::Error>> {
+        match &self.target {
+            Target::Object(_) => None,
+            Target::Symbolic(full_name) => match store.try_find(full_name.as_ref()) {
+                Ok(Some(next)) => Some(Ok(next)),
+                Ok(None) => Some(Err(existing::Error::NotFound {
+                    name: full_name.to_path().to_owned(),
+                })),
+                Err(err) => Some(Err(existing::Error::Find(err))),
+            },
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use gix_path::RelativePath;
+
+use crate::{file, reftable, store, Reference};
+
+/// An intermediate structure to hold shared state alive long enough for iteration to happen.
+#[must_use = "Iterators should be obtained from this platform"]
+pub enum Platform<'s> {
+    /// The platform of a store with loose and packed references.
+    Loose(file::iter::Platform<'s>),
+    /// The platform of a store with reftables.
+    Reftable(reftable::iter::Platform<'s>),
+}
+
+/// An iterator over references of either store, sorted by their name.
+pub enum Iter<'a> {
+    /// Loose references along with packed ones.
+    Loose(Box<file::iter::LooseThenPacked<'a, 'a>>),
+    /// References in reftables.
+    Reftable(reftable::iter::Iter<'a>),
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<Reference, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Loose(iter) => iter.next().map(|res| res.map_err(Into::into)),
+            Iter::Reftable(iter) => iter.next().map(|res| res.map_err(Into::into)),
+        }
+    }
+}
+
+impl Platform<'_> {
+    /// Return an iterator over all references in `refs/`, sorted by their name.
+    pub fn all(&self) -> Result<Iter<'_>, Error> {
+        Ok(match self {
+            Platform::Loose(p) => Iter::Loose(Box::new(p.all()?)),
+            Platform::Reftable(p) => Iter::Reftable(p.all()?),
+        })
+    }
+
+    /// As [`all()`](Platform::all()), but filters by `prefix`, i.e. `refs/heads/` or `refs/heads/feature-`.
+    ///
+    /// Prefixes are relative paths with slash-separated components.
+    pub fn prefixed(&self, prefix: &RelativePath) -> Result<Iter<'_>, Error> {
+        Ok(match self {
+            Platform::Loose(p) => Iter::Loose(Box::new(p.prefixed(prefix)?)),
+            Platform::Reftable(p) => Iter::Reftable(p.prefixed(prefix)?),
+        })
+    }
+}
+
+impl store::Handle {
+    /// Return a platform to obtain iterators over all references, or prefixed ones, sorted by their name.
+    pub fn iter(&self) -> Result<Platform<'_>, Error> {
+        Ok(match &self.state {
+            store::handle::State::Loose { store } => Platform::Loose(store.iter()?),
+            store::handle::State::Reftable { store } => Platform::Reftable(store.iter()?),
+        })
+    }
+}
+
+mod error {
+    use crate::{file, packed, reftable};
+
+    /// The error returned by [`Handle::iter()`](crate::store::Handle::iter()), its [platform](super::Platform) and
+    /// [iterator](super::Iter).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The packed-refs file could not be opened")]
+        PackedOpen(#[from] packed::buffer::open::Error),
+        #[error("The loose references could not be traversed")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Loose(#[from] file::iter::loose_then_packed::Error),
+        #[error("The reftables could not be loaded")]
+        ReftableLoad(#[from] reftable::load::Error),
+        #[error(transparent)]
+        Reftable(#[from] reftable::iter::Error),
+    }
+}
+
+pub use error::Error;
@@ -0,0 +1,150 @@
+use crate::{file, log::Line, store, FullNameRef};
+
+/// A platform to store a buffer to hold ref log lines for iteration, independently of the backend.
+#[must_use = "Iterators should be obtained from this platform"]
+pub struct Platform<'a, 's> {
+    /// The store containing the reflogs
+    pub store: &'s store::Handle,
+    /// The full name of the reference whose reflog to retrieve.
+    pub name: &'a FullNameRef,
+    /// A reusable buffer for storing log lines.
+    pub buf: Vec<u8>,
+}
+
+impl Platform<'_, '_> {
+    /// Return an iterator over all log-lines, most recent to oldest, or `None` if there is no reflog.
+    pub fn rev(&mut self) -> Result<Option<Reverse<'_>>, Error> {
+        Ok(match &self.store.state {
+            store::handle::State::Loose { store } => {
+                self.buf.clear();
+                self.buf.resize(1024 * 4, 0);
+                store.reflog_iter_rev(self.name, &mut self.buf)?.map(Reverse::Loose)
+            }
+            store::handle::State::Reftable { store } => store
+                .reflog(self.name)?
+                .map(|lines| Reverse::Reftable(lines.into_iter().rev())),
+        })
+    }
+
+    /// Return an iterator over all log-lines, oldest to most recent, or `None` if there is no reflog.
+    ///
+    /// Reflogs in reftables are serialized into our buffer first so that they can be iterated just like reflog files.
+    pub fn all(&mut self) -> Result<Option<file::log::iter::Forward<'_>>, Error> {
+        self.buf.clear();
+        Ok(match &self.store.state {
+            store::handle::State::Loose { store } => store.reflog_iter(self.name, &mut self.buf)?,
+            store::handle::State::Reftable { store } => match store.reflog(self.name)? {
+                Some(lines) => {
+                    for line in lines {
+                        line.write_to(&mut self.buf)?;
+                    }
+                    Some(file::log::iter::forward(&self.buf))
+                }
+                None => None,
+            },
+        })
+    }
+}
+
+/// An iterator over reflog lines from most recent to oldest, obtained with [`Platform::rev()`].
+pub enum Reverse<'a> {
+    /// Lines read from the back of a reflog file.
+    Loose(file::log::iter::Reverse<'a, std::fs::File>),
+    /// Lines read from reftables.
+    Reftable(std::iter::Rev<std::vec::IntoIter<Line>>),
+}
+
+impl Iterator for Reverse<'_> {
+    type Item = Result<Line, file::log::iter::reverse::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Reverse::Loose(iter) => iter.next(),
+            Reverse::Reftable(iter) => iter.next().map(Ok),
+        }
+    }
+}
+
+impl store::Handle {
+    /// Returns true if a reflog exists for the given reference `name`.
+    pub fn reflog_exists<'a, Name, E>(&self, name: Name) -> Result<bool, Error>
+    where
+        Name: TryInto<&'a FullNameRef, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        self.reflog_exists_inner(name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?)
+    }
+
+    fn reflog_exists_inner(&self, name: &FullNameRef) -> Result<bool, Error> {
+        Ok(match &self.state {
+            store::handle::State::Loose { store } => store.reflog_exists(name)?,
+            store::handle::State::Reftable { store } => store.reflog_exists(name)?,
+        })
+    }
+
+    /// Return all reflog entries of the given fully qualified `name`, from oldest to most recent.
+    ///
+    /// Return `Ok(None)` if no reflog exists.
+    pub fn reflog<'a, Name, E>(&self, name: Name) -> Result<Option<Vec<Line>>, Error>
+    where
+        Name: TryInto<&'a FullNameRef, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        self.reflog_inner(name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?)
+    }
+
+    fn reflog_inner(&self, name: &FullNameRef) -> Result<Option<Vec<Line>>, Error> {
+        Ok(match &self.state {
+            store::handle::State::Loose { store } => {
+                let mut buf = Vec::new();
+                match store.reflog_iter(name, &mut buf)? {
+                    Some(lines) => Some(
+                        lines
+                            .map(|line| line.map(|line| line.to_owned()))
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    None => None,
+                }
+            }
+            store::handle::State::Reftable { store } => store.reflog(name)?,
+        })
+    }
+}
+
+mod error {
+    use std::convert::Infallible;
+
+    use crate::{file, reftable};
+
+    /// The error returned by [`Handle::reflog()`](crate::store::Handle::reflog()),
+    /// [`Handle::reflog_exists()`](crate::store::Handle::reflog_exists()) and the [log platform](super::Platform).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The reflog name or path is not a valid ref name")]
+        RefnameValidation(#[from] crate::name::Error),
+        #[error("The reflog file could not read")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Decode(#[from] file::log::iter::decode::Error),
+        #[error(transparent)]
+        Reftable(#[from] reftable::log::Error),
+    }
+
+    impl From<Infallible> for Error {
+        fn from(_: Infallible) -> Self {
+            unreachable!("this impl is needed to allow passing a known valid name as parameter")
+        }
+    }
+
+    impl From<file::log::Error> for Error {
+        fn from(err: file::log::Error) -> Self {
+            match err {
+                file::log::Error::Io(err) => Error::Io(err),
+                file::log::Error::RefnameValidation(err) => Error::RefnameValidation(err),
+            }
+        }
+    }
+}
+
+pub use error::Error;
@@ -1,19 +1,20 @@
-#![allow(dead_code)]
-use crate::{store, Namespace};
+use std::path::Path;
+
+use crate::{file, packed, store, store::Backend, Namespace};
 
 #[derive(Clone)]
 pub(crate) enum State {
     Loose { store: crate::file::Store },
+    Reftable { store: crate::reftable::Store },
 }
 
 impl crate::Store {
-    /// Return a new handle which sees all references if `namespace` is `None` or all read and write operations are limited
-    /// to the given `namespace` if `Some`.
+    /// Return a new handle which sees all references.
     pub fn to_handle(&self) -> store::Handle {
         Self::new_handle_inner(&self.inner, None)
     }
 
-    /// As above, but supports a namespace to be set
+    /// As above, but all read and write operations are limited to the given `namespace` if `Some`.
     pub fn to_handle_namespaced(&self, namespace: Option<Namespace>) -> store::Handle {
         Self::new_handle_inner(&self.inner, namespace)
     }
@@ -28,16 +29,102 @@ impl crate::Store {
                         store
                     },
                 },
+                store::State::Reftable { store } => store::handle::State::Reftable {
+                    store: {
+                        let mut store = store.clone();
+                        store.namespace = namespace;
+                        store
+                    },
+                },
             },
         }
     }
 }
 
+/// Access
+impl store::Handle {
+    /// Return the backend that holds the references.
+    pub fn backend(&self) -> Backend {
+        match &self.state {
+            State::Loose { .. } => Backend::Files,
+            State::Reftable { .. } => Backend::Reftable,
+        }
+    }
+
+    /// Return the `.git` directory of the store, which is the private directory of a worktree in case of linked worktrees.
+    pub fn git_dir(&self) -> &Path {
+        match &self.state {
+            State::Loose { store } => store.git_dir(),
+            State::Reftable { store } => store.git_dir(),
+        }
+    }
+
+    /// Return the store of loose and packed references, or `None` if references are kept in reftables.
+    ///
+    /// Use it for operations that are specific to the files backend.
+    pub fn as_file_store(&self) -> Option<&file::Store> {
+        match &self.state {
+            State::Loose { store } => Some(store),
+            State::Reftable { .. } => None,
+        }
+    }
+
+    /// Return the namespace that all reads and writes are limited to, if set.
+    pub fn namespace(&self) -> Option<&Namespace> {
+        match &self.state {
+            State::Loose { store } => store.namespace.as_ref(),
+            State::Reftable { store } => store.namespace.as_ref(),
+        }
+    }
+
+    /// Limit all reads and writes to `namespace` if `Some`, and return the previous namespace.
+    pub fn set_namespace(&mut self, namespace: Option<Namespace>) -> Option<Namespace> {
+        match &mut self.state {
+            State::Loose { store } => std::mem::replace(&mut store.namespace, namespace),
+            State::Reftable { store } => std::mem::replace(&mut store.namespace, namespace),
+        }
+    }
+
+    /// Return the way reflogs are written for reference edits.
+    pub fn write_reflog(&self) -> store::WriteReflog {
+        match &self.state {
+            State::Loose { store } => store.write_reflog,
+            State::Reftable { store } => store.write_reflog,
+        }
+    }
+
+    /// Set the way reflogs are written for reference edits to `mode`.
+    pub fn set_write_reflog(&mut self, mode: store::WriteReflog) {
+        match &mut self.state {
+            State::Loose { store } => store.write_reflog = mode,
+            State::Reftable { store } => store.write_reflog = mode,
+        }
+    }
+
+    /// Return a snapshot of the packed-refs file if it exists, loading or reloading it as needed.
+    ///
+    /// Always `None` when references are kept in reftables.
+    pub fn cached_packed_buffer(
+        &self,
+    ) -> Result<Option<file::packed::SharedBufferSnapshot>, packed::buffer::open::Error> {
+        match &self.state {
+            State::Loose { store } => store.cached_packed_buffer(),
+            State::Reftable { .. } => Ok(None),
+        }
+    }
+}
+
+mod ext;
+pub use ext::ReferenceExt;
+
 ///
 pub mod find;
 
-mod iter {
-    // impl store::Handle {
-    //     pub fn iter<'p, 's>(&'s self, packed: Option<&'p packed::Buffer>) -> std::io::Result<LooseThenPacked<'p, 's>> {
-    // }
-}
+///
+pub mod iter;
+
+///
+pub mod log;
+
+///
+pub mod transaction;
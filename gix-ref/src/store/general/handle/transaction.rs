@@ -0,0 +1,105 @@
+use crate::{file, reftable, store, transaction::RefEdit};
+
+/// A transaction on either store.
+#[derive(Debug)]
+pub enum Transaction<'s> {
+    /// A transaction on loose references, which only propagates deletions to packed references.
+    Loose(file::Transaction<'s, 's>),
+    /// A transaction on reftables.
+    Reftable(reftable::Transaction<'s>),
+}
+
+impl store::Handle {
+    /// Open a transaction with the given `edits`.
+    ///
+    /// The transaction inherits the namespace of the handle.
+    pub fn transaction(&self) -> Transaction<'_> {
+        match &self.state {
+            store::handle::State::Loose { store } => Transaction::Loose(store.transaction()),
+            store::handle::State::Reftable { store } => Transaction::Reftable(store.transaction()),
+        }
+    }
+}
+
+impl<'s> Transaction<'s> {
+    /// Configure the way packed refs are handled during the transaction, which only affects the
+    /// [files](crate::store::Backend::Files) backend.
+    pub fn packed_refs(self, packed_refs: file::transaction::PackedRefs<'s>) -> Self {
+        match self {
+            Transaction::Loose(t) => Transaction::Loose(t.packed_refs(packed_refs)),
+            Transaction::Reftable(t) => Transaction::Reftable(t),
+        }
+    }
+
+    /// Prepare for calling [`commit(…)`][Transaction::commit()] in a way that can be rolled back perfectly.
+    ///
+    /// `lock_fail_mode` determines how to fail if a lock cannot be obtained, which is the lock of each loose reference
+    /// or the lock of the table list, while `packed_refs_lock_fail_mode` applies to the lock of the packed-refs file.
+    pub fn prepare(
+        self,
+        edits: impl IntoIterator<Item = RefEdit>,
+        lock_fail_mode: gix_lock::acquire::Fail,
+        packed_refs_lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, prepare::Error> {
+        Ok(match self {
+            Transaction::Loose(t) => {
+                Transaction::Loose(t.prepare(edits, lock_fail_mode, packed_refs_lock_fail_mode)?)
+            }
+            Transaction::Reftable(t) => Transaction::Reftable(t.prepare(edits, lock_fail_mode)?),
+        })
+    }
+
+    /// Make all [prepared][Transaction::prepare()] permanent and return the performed edits which represent the current
+    /// state of the affected refs in the ref store in that instant.
+    ///
+    /// `committer` is used in the reflog and only if the reflog is actually written.
+    pub fn commit<'a>(
+        self,
+        committer: impl Into<Option<gix_actor::SignatureRef<'a>>>,
+    ) -> Result<Vec<RefEdit>, commit::Error> {
+        Ok(match self {
+            Transaction::Loose(t) => t.commit(committer)?,
+            Transaction::Reftable(t) => t.commit(committer)?,
+        })
+    }
+
+    /// Rollback all intermediate state and return the `RefEdits` as we know them thus far.
+    ///
+    /// A rollback happens automatically as this instance is dropped as well.
+    pub fn rollback(self) -> Vec<RefEdit> {
+        match self {
+            Transaction::Loose(t) => t.rollback(),
+            Transaction::Reftable(t) => t.rollback(),
+        }
+    }
+}
+
+///
+pub mod prepare {
+    use crate::{file, reftable};
+
+    /// The error returned by [`Transaction::prepare()`][super::Transaction::prepare()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Loose(#[from] file::transaction::prepare::Error),
+        #[error(transparent)]
+        Reftable(#[from] reftable::transaction::prepare::Error),
+    }
+}
+
+///
+pub mod commit {
+    use crate::{file, reftable};
+
+    /// The error returned by [`Transaction::commit()`][super::Transaction::commit()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Loose(#[from] file::transaction::commit::Error),
+        #[error(transparent)]
+        Reftable(#[from] reftable::transaction::commit::Error),
+    }
+}
@@ -1,33 +1,60 @@
 use std::path::PathBuf;
 
 mod error {
-    /// The error returned by [`crate::Store::at()`].
+    /// The error returned by [`crate::Store::at()`] and [`crate::Store::for_linked_worktree()`].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
         #[error("There was an error accessing the store's directory")]
         Io(#[from] std::io::Error),
+        #[error("Linked worktrees are not yet supported by the reftable backend")]
+        ReftableLinkedWorktree,
     }
 }
 
 pub use error::Error;
 
-use crate::file;
+use crate::{file, reftable, store::Backend};
 
-#[allow(dead_code)]
 impl crate::Store {
-    /// Create a new store at the given location, typically the `.git/` directory.
+    /// Create a new store at the given location, typically the `.git/` directory, which keeps references
+    /// in the given `backend`, as configured by `extensions.refStorage`.
     /// Use [`opts`](crate::store::init::Options) to adjust settings.
     ///
     /// Note that if [`precompose_unicode`](crate::store::init::Options::precompose_unicode) is set in the options,
     /// the `git_dir` is also expected to use precomposed unicode, or else some operations that strip prefixes will fail.
-    pub fn at(git_dir: PathBuf, opts: crate::store::init::Options) -> Result<Self, Error> {
-        // for now, just try to read the directory - later we will do that naturally as we have to figure out if it's a ref-table or not.
+    pub fn at(git_dir: PathBuf, backend: Backend, opts: crate::store::init::Options) -> Result<Self, Error> {
         std::fs::read_dir(&git_dir)?;
-        Ok(crate::Store {
-            inner: crate::store::State::Loose {
+        let inner = match backend {
+            Backend::Files => crate::store::State::Loose {
                 store: file::Store::at(git_dir, opts),
             },
-        })
+            Backend::Reftable => crate::store::State::Reftable {
+                store: reftable::Store::at(git_dir, opts),
+            },
+        };
+        Ok(crate::Store { inner })
+    }
+
+    /// Like [`at()`](crate::Store::at()), but for _linked_ work-trees which use `git_dir` as private ref store and `common_dir` for
+    /// shared references.
+    ///
+    /// Note that only the [files](Backend::Files) backend supports linked worktrees for now, as the per-worktree tables
+    /// that git keeps in `worktrees/<id>/reftable` aren't implemented. With the [reftable](Backend::Reftable) backend,
+    /// [`Error::ReftableLinkedWorktree`] is returned instead.
+    pub fn for_linked_worktree(
+        git_dir: PathBuf,
+        common_dir: PathBuf,
+        backend: Backend,
+        opts: crate::store::init::Options,
+    ) -> Result<Self, Error> {
+        std::fs::read_dir(&git_dir)?;
+        let inner = match backend {
+            Backend::Files => crate::store::State::Loose {
+                store: file::Store::for_linked_worktree(git_dir, common_dir, opts),
+            },
+            Backend::Reftable => return Err(Error::ReftableLinkedWorktree),
+        };
+        Ok(crate::Store { inner })
     }
 }
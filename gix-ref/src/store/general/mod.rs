@@ -1 +1 @@
-mod init;
+pub(crate) mod init;
@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+
+use gix_features::zlib;
+
+use super::{
+    decode::Error,
+    record::{block_type, Decoder, Record},
+};
+
+/// A single block of a table, with log blocks being decompressed.
+pub(crate) struct Block<'a> {
+    pub typ: u8,
+    /// The bytes of the block from its beginning, which for the first block of a table includes the file header.
+    data: Cow<'a, [u8]>,
+    /// The position of the first record in `data`.
+    pub records_start: usize,
+    /// The position of the restart table in `data`, which is also where records end.
+    restarts_start: usize,
+    restart_count: usize,
+    /// The amount of bytes the block takes in the table, including padding.
+    pub size_in_table: usize,
+}
+
+/// The position of the next record in a block, along with the key of the previous record for prefix decompression.
+#[derive(Clone)]
+pub(crate) struct Cursor {
+    pub pos: usize,
+    pub key: Vec<u8>,
+}
+
+impl Cursor {
+    pub fn at(pos: usize) -> Self {
+        Cursor { pos, key: Vec::new() }
+    }
+}
+
+fn u24(bytes: &[u8]) -> usize {
+    (usize::from(bytes[0]) << 16) | (usize::from(bytes[1]) << 8) | usize::from(bytes[2])
+}
+
+impl<'a> Block<'a> {
+    /// Decode the block at `offset` in `table`, whose blocks are aligned to `block_size` unless it's 0, and which
+    /// has readable blocks up to `end`.
+    /// `header_len` is the size of the file header which precedes the first block's data.
+    ///
+    /// Return `None` if there is no block at `offset`.
+    pub fn at(
+        table: &'a [u8],
+        offset: usize,
+        header_len: usize,
+        block_size: usize,
+        end: usize,
+    ) -> Result<Option<Self>, Error> {
+        let header_off = if offset == 0 { header_len } else { 0 };
+        let start = offset + header_off;
+        if start + 4 > end {
+            return Ok(None);
+        }
+        let typ = table[start];
+        if ![block_type::REF, block_type::LOG, block_type::OBJ, block_type::INDEX].contains(&typ) {
+            return Ok(None);
+        }
+        let len = u24(&table[start + 1..][..3]);
+        if len < header_off + 4 {
+            return Err(Error::Corrupt("block is too short"));
+        }
+        let (data, size_in_table) = if typ == block_type::LOG {
+            let mut data = Vec::with_capacity(len + 1);
+            data.extend_from_slice(&table[offset..start + 4]);
+            // One more byte than needed lets us detect if there is more data than the block should have.
+            data.resize(len + 1, 0);
+            let mut inflate = zlib::Inflate::default();
+            let (mut in_pos, mut out_pos) = (start + 4, header_off + 4);
+            loop {
+                let (status, consumed, written) = inflate.once(&table[in_pos..end], &mut data[out_pos..])?;
+                in_pos += consumed;
+                out_pos += written;
+                match status {
+                    zlib::Status::StreamEnd => break,
+                    _ if consumed == 0 && written == 0 => {
+                        return Err(Error::Corrupt("log block couldn't be decompressed"))
+                    }
+                    _ => continue,
+                }
+            }
+            if out_pos != len {
+                return Err(Error::Corrupt("log block has unexpected size"));
+            }
+            data.truncate(len);
+            (Cow::Owned(data), in_pos - offset)
+        } else {
+            let data = table
+                .get(offset..offset + len)
+                .filter(|_| offset + len <= end)
+                .ok_or(Error::Corrupt("block exceeds table"))?;
+            // Blocks are padded to the block size, unless the next block follows immediately.
+            let is_padded = block_size > len && table.get(offset + len).is_some_and(|b| *b == 0);
+            let size_in_table = if is_padded { block_size.min(end - offset) } else { len };
+            (Cow::Borrowed(data), size_in_table)
+        };
+        let restart_count = usize::from(u16::from_be_bytes([data[len - 2], data[len - 1]]));
+        let restarts_start = (len - 2)
+            .checked_sub(restart_count * 3)
+            .filter(|pos| *pos >= header_off + 4)
+            .ok_or(Error::Corrupt("restart table exceeds block"))?;
+        Ok(Some(Block {
+            typ,
+            data,
+            records_start: header_off + 4,
+            restarts_start,
+            restart_count,
+            size_in_table,
+        }))
+    }
+
+    /// Decode the record at `cursor` and advance it to the next record, or return `None` if there are no more records.
+    pub fn next(
+        &self,
+        cursor: &mut Cursor,
+        min_update_index: u64,
+        hash: gix_hash::Kind,
+    ) -> Result<Option<Record>, Error> {
+        if cursor.pos >= self.restarts_start {
+            return Ok(None);
+        }
+        let mut decoder = Decoder {
+            data: &self.data[..self.restarts_start],
+            pos: cursor.pos,
+        };
+        let value_type = decoder.key(&mut cursor.key)?;
+        let record = decoder.value(self.typ, &cursor.key, value_type, min_update_index, hash)?;
+        cursor.pos = decoder.pos;
+        Ok(Some(record))
+    }
+
+    /// Return the key of the first record, if there is one.
+    pub fn first_key(&self) -> Result<Option<Vec<u8>>, Error> {
+        self.restart_key(self.records_start)
+    }
+
+    fn restart_key(&self, pos: usize) -> Result<Option<Vec<u8>>, Error> {
+        if pos >= self.restarts_start {
+            return Ok(None);
+        }
+        let mut key = Vec::new();
+        Decoder {
+            data: &self.data[..self.restarts_start],
+            pos,
+        }
+        .key(&mut key)?;
+        Ok(Some(key))
+    }
+
+    fn restart(&self, idx: usize) -> usize {
+        u24(&self.data[self.restarts_start + idx * 3..][..3])
+    }
+
+    /// Return a cursor pointing to the first record whose key is equal to or greater than `key`, using a binary search
+    /// across all restart points.
+    pub fn seek(&self, key: &[u8], min_update_index: u64, hash: gix_hash::Kind) -> Result<Cursor, Error> {
+        let (mut lo, mut hi) = (0, self.restart_count);
+        // Find the first restart point whose key is greater than `key`, the record is past the restart point before it.
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.restart_key(self.restart(mid))? {
+                Some(restart_key) if restart_key.as_slice() > key => hi = mid,
+                _ => lo = mid + 1,
+            }
+        }
+        let mut cursor = Cursor::at(match lo {
+            0 => self.records_start,
+            idx => self.restart(idx - 1),
+        });
+        loop {
+            let previous = cursor.clone();
+            if self.next(&mut cursor, min_update_index, hash)?.is_none() || cursor.key.as_slice() >= key {
+                return Ok(previous);
+            }
+        }
+    }
+}
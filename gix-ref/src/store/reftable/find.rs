@@ -0,0 +1,169 @@
+use gix_object::bstr::BString;
+
+pub use error::Error;
+
+use super::{
+    record::{RefRecord, RefValue},
+    Stack,
+};
+use crate::{name::is_pseudo_ref, reftable, FullName, FullNameRef, PartialNameRef, Reference, Target};
+
+impl reftable::Store {
+    /// Find a single reference by the given `path` which is required to be a valid reference name.
+    ///
+    /// Returns `Ok(None)` if no such ref exists.
+    ///
+    /// ### Note
+    ///
+    /// The lookup algorithm follows the one in [the git documentation][git-lookup-docs], just like
+    /// [`file::Store::try_find()`](crate::file::Store::try_find()).
+    ///
+    /// [git-lookup-docs]: https://github.com/git/git/blob/5d5b1473453400224ebb126bf3947e0a3276bdf5/Documentation/revisions.txt#L34-L46
+    pub fn try_find<'a, Name, E>(&self, partial: Name) -> Result<Option<Reference>, Error>
+    where
+        Name: TryInto<&'a PartialNameRef, Error = E>,
+        Error: From<E>,
+    {
+        let partial = partial.try_into()?;
+        let stack = self.stack()?;
+        self.find_in_stack(&stack, partial)
+    }
+
+    pub(crate) fn find_in_stack(
+        &self,
+        stack: &Stack,
+        partial_name: &PartialNameRef,
+    ) -> Result<Option<Reference>, Error> {
+        let mut buf = BString::default();
+        for consider_pseudo_ref in [true, false] {
+            if !consider_pseudo_ref && !is_pseudo_ref(partial_name.as_bstr()) {
+                break;
+            }
+            for inbetween in &["", "tags", "heads", "remotes"] {
+                let full_name = partial_name.construct_full_name_ref(inbetween, &mut buf, consider_pseudo_ref);
+                if let Some(r) = self.find_full_name_in_stack(stack, full_name)? {
+                    return Ok(Some(r));
+                }
+                if consider_pseudo_ref && is_pseudo_ref(partial_name.as_bstr()) {
+                    break;
+                }
+            }
+        }
+        if partial_name.as_bstr() != "HEAD" {
+            let partial_name = partial_name.to_owned().join("HEAD".into()).expect("HEAD is valid name");
+            let full_name = partial_name
+                .as_ref()
+                .construct_full_name_ref("remotes", &mut buf, true /* consider-pseudo-ref */);
+            self.find_full_name_in_stack(stack, full_name)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Find the reference with the exact `name` in `stack`, handling namespaces.
+    pub(crate) fn find_full_name_in_stack(
+        &self,
+        stack: &Stack,
+        name: &FullNameRef,
+    ) -> Result<Option<Reference>, Error> {
+        let name = self.namespaced(name);
+        let Some(record) = stack.find(name.as_bstr())? else {
+            return Ok(None);
+        };
+        let mut r = to_reference(record)?;
+        if let Some(namespace) = &self.namespace {
+            r.strip_namespace(namespace);
+        }
+        Ok(Some(r))
+    }
+}
+
+/// Convert `record`, which must not be a deletion, into a reference.
+pub(crate) fn to_reference(record: RefRecord) -> Result<Reference, Error> {
+    let name = FullName::try_from(record.name.clone()).map_err(|err| Error::InvalidName {
+        source: err,
+        name: record.name.clone(),
+    })?;
+    let (target, peeled) = match record.value {
+        RefValue::Object(id) => (Target::Object(id), None),
+        RefValue::Peeled { target, peeled } => (Target::Object(target), Some(peeled)),
+        RefValue::Symbolic(target) => (
+            Target::Symbolic(FullName::try_from(target).map_err(|err| Error::InvalidName {
+                source: err,
+                name: record.name,
+            })?),
+            None,
+        ),
+        RefValue::Deletion => unreachable!("BUG: deletions are never turned into references"),
+    };
+    Ok(Reference { name, target, peeled })
+}
+
+///
+pub mod existing {
+    pub use error::Error;
+
+    use crate::{reftable, PartialNameRef, Reference};
+
+    impl reftable::Store {
+        /// Similar to [`reftable::Store::try_find()`] but a non-existing ref is treated as error.
+        pub fn find<'a, Name, E>(&self, partial: Name) -> Result<Reference, Error>
+        where
+            Name: TryInto<&'a PartialNameRef, Error = E>,
+            crate::name::Error: From<E>,
+        {
+            let path = partial
+                .try_into()
+                .map_err(|err| Error::Find(super::Error::RefnameValidation(err.into())))?;
+            match self.try_find(path) {
+                Ok(Some(r)) => Ok(r),
+                Ok(None) => Err(Error::NotFound {
+                    name: path.to_partial_path().to_owned(),
+                }),
+                Err(err) => Err(err.into()),
+            }
+        }
+    }
+
+    mod error {
+        use std::path::PathBuf;
+
+        /// The error returned by [reftable::Store::find()][crate::reftable::Store::find()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("An error occurred while trying to find a reference")]
+            Find(#[from] super::super::Error),
+            #[error("The ref partially named {name:?} could not be found")]
+            NotFound { name: PathBuf },
+        }
+    }
+}
+
+mod error {
+    use std::convert::Infallible;
+
+    use gix_object::bstr::BString;
+
+    use crate::reftable;
+
+    /// The error returned by [reftable::Store::try_find()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The ref name or path is not a valid ref name")]
+        RefnameValidation(#[from] crate::name::Error),
+        #[error("The reference {name:?} or its target is not a valid reference name")]
+        InvalidName { source: crate::name::Error, name: BString },
+        #[error(transparent)]
+        Load(#[from] reftable::load::Error),
+        #[error("A table could not be read")]
+        Decode(#[from] reftable::decode::Error),
+    }
+
+    impl From<Infallible> for Error {
+        fn from(_: Infallible) -> Self {
+            unreachable!("this impl is needed to allow passing a known valid partial path as parameter")
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use gix_object::bstr::{BString, ByteVec};
+use gix_path::RelativePath;
+
+use super::{
+    find::to_reference,
+    record::{block_type, Record},
+    stack::Merged,
+    Stack,
+};
+use crate::{reftable, Namespace, Reference};
+
+/// An intermediate structure to hold a snapshot of the stack of tables alive long enough for iteration to happen.
+#[must_use = "Iterators should be obtained from this platform"]
+pub struct Platform<'s> {
+    store: &'s reftable::Store,
+    stack: Stack,
+}
+
+/// An iterator over references of all tables of a stack, sorted by their name.
+pub struct Iter<'a> {
+    inner: Merged<'a>,
+    namespace: Option<&'a Namespace>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<Reference, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            Ok(Record::Ref(record)) => to_reference(record).map_err(Into::into).map(|mut r| {
+                if let Some(namespace) = self.namespace {
+                    r.strip_namespace(namespace);
+                }
+                r
+            }),
+            Ok(_) => unreachable!("ref sections only contain ref records"),
+            Err(err) => Err(err.into()),
+        })
+    }
+}
+
+impl Platform<'_> {
+    /// Return an iterator over all references in `refs/`, sorted by their name.
+    pub fn all(&self) -> Result<Iter<'_>, Error> {
+        self.prefixed_inner("refs/".into())
+    }
+
+    /// As [`all()`](Platform::all()), but filters by `prefix`, i.e. `refs/heads/` or `refs/heads/feature-`.
+    ///
+    /// Prefixes are relative paths with slash-separated components.
+    pub fn prefixed(&self, prefix: &RelativePath) -> Result<Iter<'_>, Error> {
+        self.prefixed_inner(prefix.as_ref().into())
+    }
+
+    fn prefixed_inner(&self, mut prefix: BString) -> Result<Iter<'_>, Error> {
+        if let Some(namespace) = &self.store.namespace {
+            prefix.insert_str(0, namespace.as_bstr());
+        }
+        Ok(Iter {
+            inner: self.stack.merged(block_type::REF, &prefix, &prefix, false)?,
+            namespace: self.store.namespace.as_ref(),
+        })
+    }
+}
+
+impl reftable::Store {
+    /// Return a platform to obtain iterators over all references, or prefixed ones, sorted by their name.
+    ///
+    /// The platform holds on to a snapshot of the stack of tables that is current at the time of the call.
+    pub fn iter(&self) -> Result<Platform<'_>, reftable::load::Error> {
+        Ok(Platform {
+            store: self,
+            stack: self.stack()?,
+        })
+    }
+}
+
+mod error {
+    use crate::reftable;
+
+    /// The error returned by [`Platform`](super::Platform) and its [iterator](super::Iter).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A table could not be read")]
+        Decode(#[from] reftable::decode::Error),
+        #[error(transparent)]
+        Reference(#[from] reftable::find::Error),
+    }
+}
+
+pub use error::Error;
@@ -0,0 +1,62 @@
+use crate::{log::Line, reftable, FullName, FullNameRef};
+
+impl reftable::Store {
+    /// Returns true if a reflog exists for the given reference `name`.
+    pub fn reflog_exists<'a, Name, E>(&self, name: Name) -> Result<bool, Error>
+    where
+        Name: TryInto<&'a FullNameRef, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        let name = self.namespaced(name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?);
+        Ok(!self.stack()?.logs(name.as_bstr())?.is_empty())
+    }
+
+    /// Return all reflog entries of the given fully qualified `name`, from oldest to most recent.
+    ///
+    /// Return `Ok(None)` if no reflog exists.
+    pub fn reflog<'a, Name, E>(&self, name: Name) -> Result<Option<Vec<Line>>, Error>
+    where
+        Name: TryInto<&'a FullNameRef, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        let name = self.namespaced(name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?);
+        let records = self.stack()?.logs(name.as_bstr())?;
+        if records.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(
+            records
+                .into_iter()
+                .rev()
+                .filter_map(|r| r.line)
+                // Like git, skip entries which only mark the existence of a reflog.
+                .filter(|line| !(line.previous_oid.is_null() && line.new_oid.is_null()))
+                .collect(),
+        ))
+    }
+
+    pub(crate) fn namespaced(&self, name: &FullNameRef) -> FullName {
+        match &self.namespace {
+            Some(namespace) => namespace.to_owned().into_namespaced_name(name),
+            None => name.to_owned(),
+        }
+    }
+}
+
+mod error {
+    use crate::reftable;
+
+    /// The error returned by [reftable::Store::reflog()] and [reftable::Store::reflog_exists()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The reflog name or path is not a valid ref name")]
+        RefnameValidation(#[from] crate::name::Error),
+        #[error(transparent)]
+        Load(#[from] reftable::load::Error),
+        #[error("A table could not be read")]
+        Decode(#[from] reftable::decode::Error),
+    }
+}
+
+pub use error::Error;
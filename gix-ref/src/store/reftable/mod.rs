@@ -0,0 +1,171 @@
+//! A store for references in the [reftable format](https://git-scm.com/docs/reftable).
+//!
+//! All references and their reflogs are kept in a stack of immutable tables in the `reftable` directory, with the
+//! `tables.list` file naming the tables that are currently part of the stack, oldest first.
+//! Newer tables shadow the records of older ones, which makes updates as cheap as writing a new table. To keep the stack
+//! small, tables are merged into one when committing transactions.
+use std::path::{Path, PathBuf};
+
+use gix_features::threading::{Mutable, OwnShared};
+
+use crate::{store::WriteReflog, Namespace};
+
+mod block;
+mod record;
+mod stack;
+mod table;
+mod writer;
+
+use stack::Stack;
+use table::Table;
+pub use transaction::Transaction;
+
+/// A store for references which keeps them in a stack of tables in the reftable format.
+#[derive(Clone)]
+pub struct Store {
+    /// The `.git` directory which contains the `reftable` directory.
+    git_dir: PathBuf,
+    /// The kind of hash to use when writing new tables.
+    object_hash: gix_hash::Kind,
+    /// The way to handle reflog edits
+    pub write_reflog: WriteReflog,
+    /// The namespace to use for edits and reads
+    pub namespace: Option<Namespace>,
+    /// The tables we have loaded most recently, which are reused if they are still part of the stack as they never change.
+    tables: OwnShared<Mutable<Vec<OwnShared<Table>>>>,
+}
+
+impl std::fmt::Debug for Store {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Store")
+            .field("git_dir", &self.git_dir)
+            .field("object_hash", &self.object_hash)
+            .field("write_reflog", &self.write_reflog)
+            .field("namespace", &self.namespace)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Initialization
+impl Store {
+    /// Create a new instance for the `.git` directory at `git_dir`, which contains the `reftable` directory.
+    /// Use `opts` to adjust settings.
+    ///
+    /// Note that nothing is read until references are accessed, and that the `reftable` directory will be created
+    /// on the first write if it doesn't exist yet.
+    pub fn at(git_dir: PathBuf, opts: crate::store::init::Options) -> Self {
+        Store {
+            git_dir,
+            object_hash: opts.object_hash,
+            write_reflog: opts.write_reflog,
+            namespace: None,
+            tables: Default::default(),
+        }
+    }
+}
+
+/// Access
+impl Store {
+    /// Return the `.git` directory which contains the `reftable` directory.
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// Return the directory containing all tables.
+    pub fn reftable_dir(&self) -> PathBuf {
+        self.git_dir.join("reftable")
+    }
+
+    fn tables_list_path(&self) -> PathBuf {
+        self.reftable_dir().join("tables.list")
+    }
+
+    /// Load the current stack of tables, reusing tables that we have seen before.
+    pub(crate) fn stack(&self) -> Result<Stack, load::Error> {
+        let list_path = self.tables_list_path();
+        let mut attempt = 0;
+        'retry: loop {
+            let names = match std::fs::read(&list_path) {
+                Ok(list) => list
+                    .split(|b| *b == b'\n')
+                    .filter(|line| !line.is_empty())
+                    .map(|name| String::from_utf8_lossy(name).into_owned())
+                    .collect::<Vec<_>>(),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                Err(err) => {
+                    return Err(load::Error::Io {
+                        source: err,
+                        path: list_path,
+                    })
+                }
+            };
+            let mut cache = gix_features::threading::lock(&self.tables);
+            let mut tables = Vec::with_capacity(names.len());
+            for name in names {
+                if let Some(table) = cache.iter().find(|t| t.name == name) {
+                    tables.push(table.clone());
+                    continue;
+                }
+                let path = self.reftable_dir().join(&name);
+                let data = match std::fs::read(&path) {
+                    Ok(data) => data,
+                    // The stack was compacted concurrently, and the new list has to be read to see the new tables.
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound && attempt < 3 => {
+                        attempt += 1;
+                        continue 'retry;
+                    }
+                    Err(err) => return Err(load::Error::Io { source: err, path }),
+                };
+                let table =
+                    Table::from_bytes(name.clone(), data).map_err(|err| load::Error::Decode { source: err, name })?;
+                tables.push(OwnShared::new(table));
+            }
+            cache.clone_from(&tables);
+            return Ok(Stack { tables });
+        }
+    }
+}
+
+///
+pub mod decode {
+    /// The error returned when decoding tables fails.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The table is corrupt: {0}")]
+        Corrupt(&'static str),
+        #[error("Table version {0} is unsupported")]
+        UnsupportedVersion(u8),
+        #[error("The hash with id {0:#x} is unsupported")]
+        UnsupportedHash(u32),
+        #[error("A log block could not be decompressed")]
+        Inflate(#[from] gix_features::zlib::inflate::Error),
+    }
+}
+
+///
+pub mod load {
+    use std::path::PathBuf;
+
+    /// The error returned when loading the stack of tables fails.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read {path:?}")]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("The table {name:?} could not be decoded")]
+        Decode { source: super::decode::Error, name: String },
+    }
+}
+
+///
+pub mod find;
+
+///
+pub mod iter;
+
+///
+pub mod log;
+
+///
+pub mod transaction;
@@ -0,0 +1,317 @@
+use gix_hash::ObjectId;
+use gix_object::bstr::{BString, ByteSlice};
+
+use super::decode::Error;
+use crate::log::Line;
+
+/// The type of a block, which is also the first byte of each block.
+pub(crate) mod block_type {
+    pub const REF: u8 = b'r';
+    pub const LOG: u8 = b'g';
+    pub const OBJ: u8 = b'o';
+    pub const INDEX: u8 = b'i';
+}
+
+/// The value of a ref record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RefValue {
+    /// The reference was deleted, shadowing records of the same name in older tables.
+    Deletion,
+    /// The reference points to an object.
+    Object(ObjectId),
+    /// The reference points to an annotated tag, which peels to the second object.
+    Peeled { target: ObjectId, peeled: ObjectId },
+    /// The reference points to another reference.
+    Symbolic(BString),
+}
+
+/// A reference as stored in a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RefRecord {
+    pub name: BString,
+    pub update_index: u64,
+    pub value: RefValue,
+}
+
+/// An entry of the reflog as stored in a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LogRecord {
+    pub name: BString,
+    pub update_index: u64,
+    /// The reflog line, or `None` if this record deletes the entry of the same name and update-index in older tables.
+    pub line: Option<Line>,
+}
+
+impl LogRecord {
+    /// Return the key by which log records are sorted, which places the most recent entries of each reference first.
+    pub fn key(&self) -> Vec<u8> {
+        log_key(self.name.as_ref(), self.update_index)
+    }
+}
+
+pub(crate) fn log_key(name: &[u8], update_index: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(name.len() + 9);
+    key.extend_from_slice(name);
+    key.push(0);
+    key.extend_from_slice(&(u64::MAX - update_index).to_be_bytes());
+    key
+}
+
+/// Any record, as decoded from a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Record {
+    Ref(RefRecord),
+    Log(LogRecord),
+    /// An index record with the key of the last record in the block at the given position.
+    Index {
+        last_key: BString,
+        position: u64,
+    },
+    /// Records of object blocks aren't used by us, so they are skipped.
+    Obj,
+}
+
+/// Decode a variable-length integer as used throughout the format, returning it along with the amount of bytes consumed.
+pub(crate) fn decode_varint(data: &[u8]) -> Option<(u64, usize)> {
+    let mut byte = *data.first()?;
+    let mut value = u64::from(byte & 0x7f);
+    let mut consumed = 1;
+    while byte & 0x80 != 0 {
+        byte = *data.get(consumed)?;
+        consumed += 1;
+        value = value.checked_add(1)?.checked_mul(128)? | u64::from(byte & 0x7f);
+    }
+    Some((value, consumed))
+}
+
+/// Encode `value` as variable-length integer and append it to `out`.
+pub(crate) fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    let mut buf = [0u8; 10];
+    let mut pos = buf.len() - 1;
+    buf[pos] = (value & 0x7f) as u8;
+    value >>= 7;
+    while value != 0 {
+        value -= 1;
+        pos -= 1;
+        buf[pos] = 0x80 | (value & 0x7f) as u8;
+        value >>= 7;
+    }
+    out.extend_from_slice(&buf[pos..]);
+}
+
+/// A cursor into the records of a block's data.
+pub(crate) struct Decoder<'a> {
+    pub data: &'a [u8],
+    pub pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn varint(&mut self) -> Result<u64, Error> {
+        let (value, consumed) = decode_varint(&self.data[self.pos..]).ok_or(Error::Corrupt("invalid varint"))?;
+        self.pos += consumed;
+        Ok(value)
+    }
+
+    pub fn usize(&mut self) -> Result<usize, Error> {
+        self.varint()?
+            .try_into()
+            .map_err(|_| Error::Corrupt("length out of bounds"))
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .data
+            .get(self.pos..)
+            .and_then(|d| d.get(..len))
+            .ok_or(Error::Corrupt("record exceeds its block"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn object_id(&mut self, hash: gix_hash::Kind) -> Result<ObjectId, Error> {
+        Ok(ObjectId::from_bytes_or_panic(self.bytes(hash.len_in_bytes())?))
+    }
+
+    /// Decode the key of the next record, using `key` as previous key for prefix compression, and return the value type.
+    pub fn key(&mut self, key: &mut Vec<u8>) -> Result<u8, Error> {
+        let prefix_len = self.usize()?;
+        let suffix_len_and_type = self.varint()?;
+        let suffix_len = usize::try_from(suffix_len_and_type >> 3).map_err(|_| Error::Corrupt("invalid suffix"))?;
+        if prefix_len > key.len() {
+            return Err(Error::Corrupt("key prefix exceeds previous key"));
+        }
+        key.truncate(prefix_len);
+        key.extend_from_slice(self.bytes(suffix_len)?);
+        Ok((suffix_len_and_type & 0x7) as u8)
+    }
+
+    /// Decode the record of a block of type `block_type` whose key is `key` and whose value type is `value_type`.
+    pub fn value(
+        &mut self,
+        block_type: u8,
+        key: &[u8],
+        value_type: u8,
+        min_update_index: u64,
+        hash: gix_hash::Kind,
+    ) -> Result<Record, Error> {
+        Ok(match block_type {
+            block_type::REF => {
+                let update_index = min_update_index
+                    .checked_add(self.varint()?)
+                    .ok_or(Error::Corrupt("update index out of bounds"))?;
+                let value = match value_type {
+                    0 => RefValue::Deletion,
+                    1 => RefValue::Object(self.object_id(hash)?),
+                    2 => RefValue::Peeled {
+                        target: self.object_id(hash)?,
+                        peeled: self.object_id(hash)?,
+                    },
+                    3 => {
+                        let len = self.usize()?;
+                        RefValue::Symbolic(self.bytes(len)?.into())
+                    }
+                    _ => return Err(Error::Corrupt("unknown ref value type")),
+                };
+                Record::Ref(RefRecord {
+                    name: key.into(),
+                    update_index,
+                    value,
+                })
+            }
+            block_type::LOG => {
+                let (name, update_index) = key
+                    .len()
+                    .checked_sub(9)
+                    .filter(|&pos| key[pos] == 0)
+                    .map(|pos| {
+                        let reversed = u64::from_be_bytes(key[pos + 1..].try_into().expect("8 bytes"));
+                        (&key[..pos], u64::MAX - reversed)
+                    })
+                    .ok_or(Error::Corrupt("invalid log key"))?;
+                let line = match value_type {
+                    0 => None,
+                    1 => Some(self.log_line(hash)?),
+                    _ => return Err(Error::Corrupt("unknown log value type")),
+                };
+                Record::Log(LogRecord {
+                    name: name.into(),
+                    update_index,
+                    line,
+                })
+            }
+            block_type::INDEX => Record::Index {
+                last_key: key.into(),
+                position: self.varint()?,
+            },
+            block_type::OBJ => {
+                let count = match value_type {
+                    0 => self.varint()?,
+                    count => count.into(),
+                };
+                for _ in 0..count {
+                    self.varint()?;
+                }
+                Record::Obj
+            }
+            _ => return Err(Error::Corrupt("unknown block type")),
+        })
+    }
+
+    fn log_line(&mut self, hash: gix_hash::Kind) -> Result<Line, Error> {
+        let previous_oid = self.object_id(hash)?;
+        let new_oid = self.object_id(hash)?;
+        let len = self.usize()?;
+        let name = self.bytes(len)?.into();
+        let len = self.usize()?;
+        let email = self.bytes(len)?.into();
+        let seconds = self.varint()?;
+        let offset_in_minutes = i16::from_be_bytes(self.bytes(2)?.try_into().expect("2 bytes"));
+        let len = self.usize()?;
+        let message = self.bytes(len)?;
+        Ok(Line {
+            previous_oid,
+            new_oid,
+            signature: gix_actor::Signature {
+                name,
+                email,
+                time: gix_actor::date::Time::new(
+                    seconds.try_into().map_err(|_| Error::Corrupt("time out of bounds"))?,
+                    i32::from(offset_in_minutes) * 60,
+                ),
+            },
+            message: message.strip_suffix(b"\n").unwrap_or(message).into(),
+        })
+    }
+}
+
+/// Encode the value of `record` without its key, as needed for blocks of the type of `record`.
+pub(crate) fn encode_value(record: &Record, min_update_index: u64, out: &mut Vec<u8>) {
+    match record {
+        Record::Ref(r) => {
+            encode_varint(r.update_index - min_update_index, out);
+            match &r.value {
+                RefValue::Deletion => {}
+                RefValue::Object(id) => out.extend_from_slice(id.as_bytes()),
+                RefValue::Peeled { target, peeled } => {
+                    out.extend_from_slice(target.as_bytes());
+                    out.extend_from_slice(peeled.as_bytes());
+                }
+                RefValue::Symbolic(target) => {
+                    encode_varint(target.len() as u64, out);
+                    out.extend_from_slice(target);
+                }
+            }
+        }
+        Record::Log(r) => {
+            if let Some(line) = &r.line {
+                out.extend_from_slice(line.previous_oid.as_bytes());
+                out.extend_from_slice(line.new_oid.as_bytes());
+                for field in [&line.signature.name, &line.signature.email] {
+                    encode_varint(field.len() as u64, out);
+                    out.extend_from_slice(field);
+                }
+                let time = line.signature.time;
+                encode_varint(time.seconds.max(0) as u64, out);
+                let offset_in_minutes = (time.offset / 60).clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+                out.extend_from_slice(&offset_in_minutes.to_be_bytes());
+                let message = line.message.trim_end_with(|c| c == '\n');
+                encode_varint(message.len() as u64 + 1, out);
+                out.extend_from_slice(message);
+                out.push(b'\n');
+            }
+        }
+        Record::Index { position, .. } => encode_varint(*position, out),
+        Record::Obj => unreachable!("BUG: we never write object records"),
+    }
+}
+
+/// Return the value type of `record` as stored next to its key.
+pub(crate) fn value_type(record: &Record) -> u8 {
+    match record {
+        Record::Ref(r) => match r.value {
+            RefValue::Deletion => 0,
+            RefValue::Object(_) => 1,
+            RefValue::Peeled { .. } => 2,
+            RefValue::Symbolic(_) => 3,
+        },
+        Record::Log(r) => u8::from(r.line.is_some()),
+        Record::Index { .. } | Record::Obj => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_varint, encode_varint};
+
+    #[test]
+    fn varint_round_trip() {
+        for value in [0, 1, 127, 128, 155, 16511, 16512, 946752000, u64::MAX] {
+            let mut buf = Vec::new();
+            encode_varint(value, &mut buf);
+            assert_eq!(decode_varint(&buf), Some((value, buf.len())), "{value}");
+        }
+        let mut buf = Vec::new();
+        encode_varint(155, &mut buf);
+        assert_eq!(buf, [0x80, 0x1b], "same encoding as git");
+    }
+}
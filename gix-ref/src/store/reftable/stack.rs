@@ -0,0 +1,120 @@
+use std::iter::Peekable;
+
+use gix_features::threading::OwnShared;
+
+use super::{
+    decode::Error,
+    record::{block_type, log_key, Record, RefRecord, RefValue},
+    table::{Records, Table},
+};
+
+/// The tables of a store at a particular point in time, oldest first.
+#[derive(Clone)]
+pub(crate) struct Stack {
+    pub tables: Vec<OwnShared<Table>>,
+}
+
+impl Stack {
+    /// The update-index to use for the next table.
+    pub fn next_update_index(&self) -> u64 {
+        self.tables.last().map_or(1, |t| t.max_update_index + 1)
+    }
+
+    /// Find the most recent record for the reference named `name`, or `None` if it doesn't exist or was deleted.
+    pub fn find(&self, name: &[u8]) -> Result<Option<RefRecord>, Error> {
+        for table in self.tables.iter().rev() {
+            if let Some(record) = table.find_ref(name)? {
+                return Ok((record.value != RefValue::Deletion).then_some(record));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return all log records of the reference named `name`, most recent first, without deleted ones.
+    pub fn logs(&self, name: &[u8]) -> Result<Vec<super::record::LogRecord>, Error> {
+        let mut prefix = name.to_vec();
+        prefix.push(0);
+        self.merged(block_type::LOG, &log_key(name, u64::MAX), &prefix, false)?
+            .map(|record| {
+                record.map(|r| match r {
+                    Record::Log(r) => r,
+                    _ => unreachable!("log sections only contain log records"),
+                })
+            })
+            .collect()
+    }
+
+    /// Merge the records of all tables in the section of type `typ` whose key starts with `prefix`, starting at
+    /// the first key that is equal to or greater than `start`.
+    /// Records of newer tables shadow those of older tables with the same key, and deletions are only returned
+    /// if `include_deletions` is `true`.
+    pub fn merged(&self, typ: u8, start: &[u8], prefix: &[u8], include_deletions: bool) -> Result<Merged<'_>, Error> {
+        Ok(Merged {
+            iters: self
+                .tables
+                .iter()
+                .map(|t| t.seek(typ, start).map(Iterator::peekable))
+                .collect::<Result<_, _>>()?,
+            prefix: prefix.to_owned(),
+            include_deletions,
+        })
+    }
+}
+
+fn key_and_deletion(record: &Record) -> (Vec<u8>, bool) {
+    match record {
+        Record::Ref(r) => (r.name.to_vec(), r.value == RefValue::Deletion),
+        Record::Log(r) => (r.key(), r.line.is_none()),
+        Record::Index { .. } | Record::Obj => unreachable!("only sections of refs and logs are merged"),
+    }
+}
+
+/// An iterator over the merged records of multiple tables, sorted by key.
+pub(crate) struct Merged<'a> {
+    /// Iterators for each table, oldest first.
+    iters: Vec<Peekable<Records<'a>>>,
+    prefix: Vec<u8>,
+    include_deletions: bool,
+}
+
+impl Iterator for Merged<'_> {
+    type Item = Result<Record, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // The smallest key wins, and for equal keys, the newest table wins.
+            let mut best: Option<(usize, Vec<u8>)> = None;
+            for (idx, iter) in self.iters.iter_mut().enumerate() {
+                match iter.peek() {
+                    None => continue,
+                    Some(Ok(record)) => {
+                        let (key, _) = key_and_deletion(record);
+                        if best.as_ref().map_or(true, |(_, best_key)| key <= *best_key) {
+                            best = Some((idx, key));
+                        }
+                    }
+                    Some(Err(_)) => return iter.next(),
+                }
+            }
+            let (idx, key) = best?;
+            let record = match self.iters[idx].next()? {
+                Ok(record) => record,
+                Err(err) => return Some(Err(err)),
+            };
+            for iter in &mut self.iters {
+                while iter
+                    .next_if(|r| r.as_ref().is_ok_and(|r| key_and_deletion(r).0 == key))
+                    .is_some()
+                {}
+            }
+            if !key.starts_with(&self.prefix) {
+                self.iters.clear();
+                return None;
+            }
+            if !self.include_deletions && key_and_deletion(&record).1 {
+                continue;
+            }
+            return Some(Ok(record));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use super::{
+    block::{Block, Cursor},
+    decode::Error,
+    record::{block_type, Record, RefRecord},
+};
+
+pub(crate) const MAGIC: &[u8; 4] = b"REFT";
+/// The hash id of SHA1 in version 2 of the format.
+pub(crate) const SHA1_ID: u32 = u32::from_be_bytes(*b"sha1");
+/// The hash id of SHA256 in version 2 of the format.
+pub(crate) const SHA256_ID: u32 = u32::from_be_bytes(*b"s256");
+
+/// Return the size of the file header for the given format `version`.
+pub(crate) fn header_len(version: u8) -> usize {
+    match version {
+        1 => 24,
+        _ => 28,
+    }
+}
+
+/// Return the size of the footer for the given format `version`.
+pub(crate) fn footer_len(version: u8) -> usize {
+    header_len(version) + 5 * 8 + 4
+}
+
+fn u64_at(data: &[u8], pos: usize) -> u64 {
+    u64::from_be_bytes(data[pos..][..8].try_into().expect("8 bytes"))
+}
+
+/// A single immutable table of a stack.
+pub(crate) struct Table {
+    /// The file name of the table in the `reftable` directory.
+    pub name: String,
+    data: Vec<u8>,
+    pub hash: gix_hash::Kind,
+    header_len: usize,
+    block_size: usize,
+    pub min_update_index: u64,
+    pub max_update_index: u64,
+    /// The position of the footer, which is where readable blocks end.
+    footer_start: usize,
+    ref_index_position: u64,
+    log_position: u64,
+    log_index_position: u64,
+    first_block_type: Option<u8>,
+}
+
+impl Table {
+    /// Parse `data` as table named `name`, validating its header and footer.
+    pub fn from_bytes(name: String, data: Vec<u8>) -> Result<Self, Error> {
+        if data.len() < 5 || &data[..4] != MAGIC {
+            return Err(Error::Corrupt("table doesn't start with the reftable magic"));
+        }
+        let version = data[4];
+        if !matches!(version, 1 | 2) {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let (header_len, footer_len) = (header_len(version), footer_len(version));
+        if data.len() < header_len + footer_len {
+            return Err(Error::Corrupt("table is too short"));
+        }
+        let footer_start = data.len() - footer_len;
+        let footer = &data[footer_start..];
+        if footer[..header_len] != data[..header_len] {
+            return Err(Error::Corrupt("footer doesn't repeat the table header"));
+        }
+        let (footer, crc) = footer.split_at(footer_len - 4);
+        if gix_features::hash::crc32(footer) != u32::from_be_bytes(crc.try_into().expect("4 bytes")) {
+            return Err(Error::Corrupt("footer checksum mismatch"));
+        }
+        let hash = match version {
+            1 => gix_hash::Kind::Sha1,
+            _ => match u32::from_be_bytes(data[24..28].try_into().expect("4 bytes")) {
+                SHA1_ID => gix_hash::Kind::Sha1,
+                SHA256_ID => gix_hash::Kind::Sha256,
+                id => return Err(Error::UnsupportedHash(id)),
+            },
+        };
+        let block_size = (usize::from(data[5]) << 16) | (usize::from(data[6]) << 8) | usize::from(data[7]);
+        let first_block_type = (header_len < footer_start).then(|| data[header_len]);
+        Ok(Table {
+            name,
+            hash,
+            header_len,
+            block_size,
+            min_update_index: u64_at(&data, 8),
+            max_update_index: u64_at(&data, 16),
+            footer_start,
+            ref_index_position: u64_at(footer, header_len),
+            log_position: u64_at(footer, header_len + 24),
+            log_index_position: u64_at(footer, header_len + 32),
+            first_block_type,
+            data,
+        })
+    }
+
+    /// The size of the table on disk.
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    fn block(&self, offset: u64) -> Result<Option<Block<'_>>, Error> {
+        let offset = usize::try_from(offset).map_err(|_| Error::Corrupt("block offset out of bounds"))?;
+        if offset >= self.footer_start {
+            return Ok(None);
+        }
+        Block::at(&self.data, offset, self.header_len, self.block_size, self.footer_start)
+    }
+
+    /// Return the position of the first block of the section of the given type along with the position of its index,
+    /// or `None` if there is no such section.
+    fn section(&self, typ: u8) -> Option<(u64, Option<u64>)> {
+        let non_zero = |pos: u64| (pos != 0).then_some(pos);
+        match typ {
+            block_type::REF => (self.first_block_type == Some(typ)).then(|| (0, non_zero(self.ref_index_position))),
+            block_type::LOG => match self.first_block_type {
+                Some(block_type::LOG) => Some(0),
+                _ => non_zero(self.log_position),
+            }
+            .map(|pos| (pos, non_zero(self.log_index_position))),
+            _ => None,
+        }
+    }
+
+    /// Return an iterator over all records of the section of type `typ`, starting at the first record whose key is
+    /// equal to or greater than `key`.
+    pub fn seek(&self, typ: u8, key: &[u8]) -> Result<Records<'_>, Error> {
+        let none = Records {
+            table: self,
+            typ,
+            block: None,
+            offset: 0,
+            cursor: Cursor::at(0),
+        };
+        let Some((mut offset, index)) = self.section(typ) else {
+            return Ok(none);
+        };
+        if let Some(mut index_offset) = index {
+            // Descend through all index levels, which point to the block whose last key is equal to or greater than `key`.
+            loop {
+                let Some(block) = self.block(index_offset)?.filter(|b| b.typ == block_type::INDEX) else {
+                    return Err(Error::Corrupt("index doesn't point to an index block"));
+                };
+                let mut cursor = block.seek(key, self.min_update_index, self.hash)?;
+                let Some(Record::Index { position, .. }) = block.next(&mut cursor, self.min_update_index, self.hash)?
+                else {
+                    return Ok(none);
+                };
+                match self.block(position)? {
+                    Some(b) if b.typ == block_type::INDEX => index_offset = position,
+                    Some(b) if b.typ == typ => {
+                        offset = position;
+                        break;
+                    }
+                    _ => return Err(Error::Corrupt("index points to a block of the wrong type")),
+                }
+            }
+        } else {
+            // Without index, skip all blocks which start with a key that is smaller than `key` if the next one does, too.
+            while let Some(block) = self.block(offset)? {
+                let next_offset = offset + block.size_in_table as u64;
+                match self.block(next_offset)? {
+                    Some(next) if next.typ == typ && next.first_key()?.is_some_and(|k| k.as_slice() <= key) => {
+                        offset = next_offset;
+                    }
+                    _ => break,
+                }
+            }
+        }
+        let Some(block) = self.block(offset)?.filter(|b| b.typ == typ) else {
+            return Ok(none);
+        };
+        let cursor = block.seek(key, self.min_update_index, self.hash)?;
+        Ok(Records {
+            table: self,
+            typ,
+            offset,
+            block: Some(block),
+            cursor,
+        })
+    }
+
+    /// Find the record of the reference named `name`.
+    pub fn find_ref(&self, name: &[u8]) -> Result<Option<RefRecord>, Error> {
+        Ok(match self.seek(block_type::REF, name)?.next().transpose()? {
+            Some(Record::Ref(r)) if r.name == name => Some(r),
+            _ => None,
+        })
+    }
+}
+
+/// An iterator over the records of one section of a table.
+pub(crate) struct Records<'a> {
+    table: &'a Table,
+    typ: u8,
+    block: Option<Block<'a>>,
+    offset: u64,
+    cursor: Cursor,
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<Record, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let block = self.block.as_ref()?;
+            match block.next(&mut self.cursor, self.table.min_update_index, self.table.hash) {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => {
+                    self.offset += block.size_in_table as u64;
+                    match self.table.block(self.offset) {
+                        Ok(Some(block)) if block.typ == self.typ => {
+                            self.cursor = Cursor::at(block.records_start);
+                            self.block = Some(block);
+                        }
+                        Ok(_) => {
+                            self.block = None;
+                        }
+                        Err(err) => {
+                            self.block = None;
+                            return Some(Err(err));
+                        }
+                    }
+                }
+                Err(err) => {
+                    self.block = None;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
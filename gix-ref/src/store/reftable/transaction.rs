@@ -0,0 +1,525 @@
+use std::io::Write;
+
+use gix_features::threading::OwnShared;
+use gix_hash::ObjectId;
+
+use super::{
+    record::{block_type, LogRecord, Record, RefRecord, RefValue},
+    writer::{Writer, DEFAULT_BLOCK_SIZE},
+    Stack, Table,
+};
+use crate::{
+    log::Line,
+    reftable,
+    store::WriteReflog,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefEditsExt, RefLog},
+    FullNameRef, Target,
+};
+
+/// A transaction on a reftable store, which writes all of its edits into a single new table.
+///
+/// It holds the lock on `tables.list` from the time it is prepared until it is committed or dropped.
+pub struct Transaction<'s> {
+    store: &'s reftable::Store,
+    lock: Option<gix_lock::File>,
+    stack: Option<Stack>,
+    updates: Option<Vec<Edit>>,
+}
+
+#[derive(Debug)]
+struct Edit {
+    update: RefEdit,
+    /// Set if this update is coming from a symbolic reference and used to make it appear like it is the one that is handled,
+    /// instead of the referent reference.
+    parent_index: Option<usize>,
+    /// For symbolic refs, this is the previous OID to put into the reflog instead of our own previous value. It's the
+    /// peeled value of the leaf referent.
+    leaf_referent_previous_oid: Option<ObjectId>,
+}
+
+impl std::borrow::Borrow<RefEdit> for Edit {
+    fn borrow(&self) -> &RefEdit {
+        &self.update
+    }
+}
+
+impl std::borrow::BorrowMut<RefEdit> for Edit {
+    fn borrow_mut(&mut self) -> &mut RefEdit {
+        &mut self.update
+    }
+}
+
+impl std::fmt::Debug for Transaction<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transaction")
+            .field("store", self.store)
+            .field("edits", &self.updates.as_ref().map(Vec::len))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Edits
+impl reftable::Store {
+    /// Open a transaction to apply edits to references and their reflogs atomically.
+    ///
+    /// The transaction inherits the parent namespace.
+    pub fn transaction(&self) -> Transaction<'_> {
+        Transaction {
+            store: self,
+            lock: None,
+            stack: None,
+            updates: None,
+        }
+    }
+}
+
+impl Transaction<'_> {
+    /// Prepare for calling [`commit(…)`][Transaction::commit()] by locking the stack of tables and validating all `edits`
+    /// against the current values of their references, failing according to `lock_fail_mode` if the lock cannot be obtained.
+    ///
+    /// If the operation succeeds, the transaction can be committed or dropped to cause a rollback automatically.
+    pub fn prepare(
+        mut self,
+        edits: impl IntoIterator<Item = RefEdit>,
+        lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, prepare::Error> {
+        use prepare::Error;
+        assert!(self.updates.is_none(), "BUG: Must not call prepare(…) multiple times");
+        let store = self.store;
+        let lock = gix_lock::File::acquire_to_update_resource(
+            store.tables_list_path(),
+            lock_fail_mode,
+            Some(store.git_dir.clone()),
+        )
+        .map_err(Error::LockAcquire)?;
+        // Only now that we hold the lock we know that the stack doesn't change anymore.
+        let stack = store.stack()?;
+
+        let mut updates: Vec<_> = edits
+            .into_iter()
+            .map(|update| Edit {
+                update,
+                parent_index: None,
+                leaf_referent_previous_oid: None,
+            })
+            .collect();
+        updates
+            .pre_process(
+                &mut |name| store.find_in_stack(&stack, name).ok().flatten().map(|r| r.target),
+                &mut |idx, update| Edit {
+                    update,
+                    parent_index: Some(idx),
+                    leaf_referent_previous_oid: None,
+                },
+            )
+            .map_err(Error::PreprocessingFailed)?;
+
+        for cid in 0..updates.len() {
+            let change = &mut updates[cid];
+            let existing_ref = store.find_full_name_in_stack(&stack, change.update.name.as_ref())?;
+            let full_name = &change.update.name.0;
+            match &mut change.update.change {
+                Change::Delete { expected, .. } => {
+                    match (&expected, &existing_ref) {
+                        (PreviousValue::MustNotExist, _) => {
+                            panic!("BUG: MustNotExist constraint makes no sense if references are to be deleted")
+                        }
+                        (PreviousValue::ExistingMustMatch(_) | PreviousValue::Any, None)
+                        | (PreviousValue::MustExist | PreviousValue::Any, Some(_)) => {}
+                        (PreviousValue::MustExist | PreviousValue::MustExistAndMatch(_), None) => {
+                            return Err(Error::DeleteReferenceMustExist {
+                                full_name: full_name.clone(),
+                            })
+                        }
+                        (
+                            PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
+                            Some(existing),
+                        ) => {
+                            if *previous != existing.target {
+                                return Err(Error::ReferenceOutOfDate {
+                                    full_name: full_name.clone(),
+                                    expected: previous.clone(),
+                                    actual: existing.target.clone(),
+                                });
+                            }
+                        }
+                    }
+                    // Keep the previous value for the caller and ourselves. Maybe they want to keep a log of sorts.
+                    if let Some(existing) = existing_ref {
+                        *expected = PreviousValue::MustExistAndMatch(existing.target);
+                    }
+                }
+                Change::Update { expected, new, .. } => {
+                    match (&expected, &existing_ref) {
+                        (PreviousValue::Any, _)
+                        | (PreviousValue::MustExist, Some(_))
+                        | (PreviousValue::MustNotExist | PreviousValue::ExistingMustMatch(_), None) => {}
+                        (PreviousValue::MustExist, None) => {
+                            let expected = Target::Object(store.object_hash.null());
+                            return Err(Error::MustExist {
+                                full_name: full_name.clone(),
+                                expected,
+                            });
+                        }
+                        (PreviousValue::MustNotExist, Some(existing)) => {
+                            if existing.target != *new {
+                                return Err(Error::MustNotExist {
+                                    full_name: full_name.clone(),
+                                    actual: existing.target.clone(),
+                                    new: new.clone(),
+                                });
+                            }
+                        }
+                        (
+                            PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
+                            Some(existing),
+                        ) => {
+                            if *previous != existing.target {
+                                return Err(Error::ReferenceOutOfDate {
+                                    full_name: full_name.clone(),
+                                    expected: previous.clone(),
+                                    actual: existing.target.clone(),
+                                });
+                            }
+                        }
+                        (PreviousValue::MustExistAndMatch(previous), None) => {
+                            return Err(Error::MustExist {
+                                full_name: full_name.clone(),
+                                expected: previous.clone(),
+                            });
+                        }
+                    }
+                    if let Some(existing) = existing_ref {
+                        *expected = PreviousValue::MustExistAndMatch(existing.target);
+                    }
+                }
+            }
+
+            // traverse parent chain from leaf/peeled ref and set the leaf previous oid accordingly
+            // to help with their reflog entries
+            if let (Some(crate::TargetRef::Object(oid)), Some(parent_idx)) =
+                (change.update.change.previous_value(), change.parent_index)
+            {
+                let oid = oid.to_owned();
+                let mut parent_idx_cursor = Some(parent_idx);
+                while let Some(parent) = parent_idx_cursor.take().map(|idx| &mut updates[idx]) {
+                    parent_idx_cursor = parent.parent_index;
+                    parent.leaf_referent_previous_oid = Some(oid);
+                }
+            }
+        }
+        self.lock = Some(lock);
+        self.stack = Some(stack);
+        self.updates = Some(updates);
+        Ok(self)
+    }
+
+    /// Rollback all intermediate state and return the `RefEdits` as we know them thus far.
+    ///
+    /// Note that they have been altered compared to what was initially provided as they have
+    /// been split and know about their current state on disk.
+    ///
+    /// # Note
+    ///
+    /// A rollback happens automatically as this instance is dropped as well.
+    pub fn rollback(self) -> Vec<RefEdit> {
+        self.updates
+            .map(|updates| updates.into_iter().map(|u| u.update).collect())
+            .unwrap_or_default()
+    }
+
+    /// Make all [prepared][Transaction::prepare()] permanent by writing them into a new table and return the performed edits
+    /// which represent the current state of the affected refs in the ref store in that instant.
+    /// Please note that the obtained edits may have been adjusted to contain more dependent edits or additional information.
+    ///
+    /// `committer` is used in the reflog and only if the reflog is actually written, which is why it is optional.
+    /// Unlike with the file store, transactions are atomic, so no change is visible if this fails.
+    ///
+    /// After writing the new table, the stack is compacted automatically if needed to keep the amount of tables logarithmic
+    /// in the amount of updates.
+    pub fn commit<'a>(
+        self,
+        committer: impl Into<Option<gix_actor::SignatureRef<'a>>>,
+    ) -> Result<Vec<RefEdit>, commit::Error> {
+        self.commit_inner(committer.into())
+    }
+
+    fn commit_inner(self, committer: Option<gix_actor::SignatureRef<'_>>) -> Result<Vec<RefEdit>, commit::Error> {
+        use commit::Error;
+        let updates = self.updates.expect("BUG: must call prepare before commit");
+        let (lock, stack) = (
+            self.lock.expect("present after prepare"),
+            self.stack.expect("present after prepare"),
+        );
+        let store = self.store;
+        let update_index = stack.next_update_index();
+
+        let mut refs = Vec::new();
+        let mut logs = Vec::new();
+        for change in &updates {
+            assert!(!change.update.deref, "Deref mode is turned into splits and turned off");
+            let name = store.namespaced(change.update.name.as_ref()).into_inner();
+            match &change.update.change {
+                Change::Update { log, new, expected } => {
+                    if log.mode == RefLog::AndReference {
+                        refs.push(RefRecord {
+                            name: name.clone(),
+                            update_index,
+                            value: match new {
+                                Target::Object(id) => RefValue::Object(*id),
+                                Target::Symbolic(target) => RefValue::Symbolic(target.as_bstr().to_owned()),
+                            },
+                        });
+                    }
+                    let log_update = match new {
+                        Target::Symbolic(_) => {
+                            // Special HACK: no reflog for symref changes as there is no OID involved which the reflog needs.
+                            // Unless, the ref is new and we can obtain a peeled id
+                            // identified by the expectation of what could be there, as is the case when cloning.
+                            match expected {
+                                PreviousValue::ExistingMustMatch(Target::Object(oid)) => {
+                                    Some((Some(gix_hash::ObjectId::null(oid.kind())), oid))
+                                }
+                                _ => None,
+                            }
+                        }
+                        Target::Object(new_oid) => {
+                            let previous = match expected {
+                                PreviousValue::MustExistAndMatch(Target::Object(oid)) => Some(oid.to_owned()),
+                                _ => None,
+                            }
+                            .or(change.leaf_referent_previous_oid);
+                            Some((previous, new_oid))
+                        }
+                    };
+                    let Some((previous, new_oid)) =
+                        log_update.filter(|(previous, new)| previous.as_ref() != Some(*new))
+                    else {
+                        continue;
+                    };
+                    if !should_write_reflog(store, &stack, change.update.name.as_ref(), log)? {
+                        continue;
+                    }
+                    let committer = committer.ok_or(Error::MissingCommitter)?;
+                    logs.push(LogRecord {
+                        name,
+                        update_index,
+                        line: Some(Line {
+                            previous_oid: previous.unwrap_or_else(|| new_oid.kind().null()),
+                            new_oid: *new_oid,
+                            signature: committer.to_owned(),
+                            message: log.message.clone(),
+                        }),
+                    });
+                }
+                Change::Delete { log: mode, .. } => {
+                    // Delete the reflog by shadowing all of its entries.
+                    for record in stack.logs(name.as_ref())? {
+                        logs.push(LogRecord { line: None, ..record });
+                    }
+                    if *mode == RefLog::AndReference {
+                        refs.push(RefRecord {
+                            name,
+                            update_index,
+                            value: RefValue::Deletion,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !refs.is_empty() || !logs.is_empty() {
+            refs.sort_by(|a, b| a.name.cmp(&b.name));
+            logs.sort_by_key(LogRecord::key);
+            let writer = Writer {
+                hash: store.object_hash,
+                block_size: DEFAULT_BLOCK_SIZE,
+                min_update_index: update_index,
+                max_update_index: update_index,
+            };
+            let table = write_table(store, &writer, &refs, &logs)?;
+            let mut tables = stack.tables;
+            tables.push(table);
+            let obsolete = compact(store, &mut tables)?;
+            commit_tables_list(lock, &tables)?;
+            for name in obsolete {
+                std::fs::remove_file(store.reftable_dir().join(name)).ok();
+            }
+        }
+        Ok(updates.into_iter().map(|edit| edit.update).collect())
+    }
+}
+
+fn should_write_reflog(
+    store: &reftable::Store,
+    stack: &Stack,
+    name: &FullNameRef,
+    log: &LogChange,
+) -> Result<bool, commit::Error> {
+    Ok(match store.write_reflog {
+        WriteReflog::Disable => false,
+        WriteReflog::Always => true,
+        WriteReflog::Normal => {
+            log.force_create_reflog
+                || name.as_bstr().starts_with(b"refs/heads/")
+                || name.as_bstr().starts_with(b"refs/remotes/")
+                || name.as_bstr().starts_with(b"refs/notes/")
+                || name.as_bstr().starts_with(b"refs/worktree/")
+                || name.as_bstr() == "HEAD"
+                || !stack.logs(store.namespaced(name).as_bstr())?.is_empty()
+        }
+    })
+}
+
+/// Write a new table with `refs` and `logs` into the `reftable` directory and return it.
+fn write_table(
+    store: &reftable::Store,
+    writer: &Writer,
+    refs: &[RefRecord],
+    logs: &[LogRecord],
+) -> Result<OwnShared<Table>, commit::Error> {
+    let data = writer.write(refs, logs);
+    let name = format!(
+        "0x{:012x}-0x{:012x}-{:08x}.ref",
+        writer.min_update_index,
+        writer.max_update_index,
+        random_suffix()
+    );
+    let path = store.reftable_dir().join(&name);
+    let mut file = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+    file.with_mut(|f| f.write_all(&data))?;
+    file.commit().map_err(|err| err.error)?;
+    let table = Table::from_bytes(name, data).expect("BUG: we can read what we just wrote");
+    Ok(OwnShared::new(table))
+}
+
+fn random_suffix() -> u32 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos()),
+    );
+    hasher.finish() as u32
+}
+
+/// Write the names of all `tables` into `tables.list` by committing `lock`.
+fn commit_tables_list(mut lock: gix_lock::File, tables: &[OwnShared<Table>]) -> Result<(), commit::Error> {
+    lock.with_mut(|f| {
+        for table in tables {
+            writeln!(f, "{}", table.name)?;
+        }
+        Ok(())
+    })?;
+    lock.commit().map_err(|err| err.error)?;
+    Ok(())
+}
+
+/// Merge tables of `tables` into one so that each table is at least twice as large as the sum of all newer tables,
+/// the same way as git does it, and return the names of the tables that are not part of the stack anymore.
+fn compact(store: &reftable::Store, tables: &mut Vec<OwnShared<Table>>) -> Result<Vec<String>, commit::Error> {
+    let Some((start, end)) = compaction_segment(&tables.iter().map(|t| t.size() as u64).collect::<Vec<_>>()) else {
+        return Ok(Vec::new());
+    };
+    let segment = Stack {
+        tables: tables[start..end].to_vec(),
+    };
+    // Deletions only need to be kept if there are older tables with records they shadow.
+    let include_deletions = start != 0;
+    let mut refs = Vec::new();
+    for record in segment.merged(block_type::REF, b"", b"", include_deletions)? {
+        if let Record::Ref(r) = record? {
+            refs.push(r);
+        }
+    }
+    let mut logs = Vec::new();
+    for record in segment.merged(block_type::LOG, b"", b"", include_deletions)? {
+        if let Record::Log(r) = record? {
+            logs.push(r);
+        }
+    }
+    let compacted: Vec<_> = if refs.is_empty() && logs.is_empty() {
+        Vec::new()
+    } else {
+        let writer = Writer {
+            hash: store.object_hash,
+            block_size: DEFAULT_BLOCK_SIZE,
+            min_update_index: segment.tables.first().expect("non-empty").min_update_index,
+            max_update_index: segment.tables.last().expect("non-empty").max_update_index,
+        };
+        vec![write_table(store, &writer, &refs, &logs)?]
+    };
+    Ok(tables.splice(start..end, compacted).map(|t| t.name.clone()).collect())
+}
+
+/// Given the `sizes` of all tables, oldest first, return the range of tables to merge, if any.
+fn compaction_segment(sizes: &[u64]) -> Option<(usize, usize)> {
+    const FACTOR: u64 = 2;
+    // Find the newest table that is less than `FACTOR` times larger than its successor.
+    let mut idx = (1..sizes.len()).rev().find(|&i| sizes[i - 1] < sizes[i] * FACTOR)?;
+    let end = idx + 1;
+    let mut bytes = sizes[idx];
+    // Then grow the segment towards older tables until one is large enough to remain.
+    while idx > 0 && sizes[idx - 1] < bytes * FACTOR {
+        idx -= 1;
+        bytes += sizes[idx];
+    }
+    Some((idx, end))
+}
+
+///
+pub mod prepare {
+    use gix_object::bstr::BString;
+
+    use crate::{reftable, Target};
+
+    /// The error returned by [`Transaction::prepare()`][super::Transaction::prepare()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The lock for the table list could not be obtained")]
+        LockAcquire(#[source] gix_lock::acquire::Error),
+        #[error(transparent)]
+        Load(#[from] reftable::load::Error),
+        #[error("Edit preprocessing failed with an error")]
+        PreprocessingFailed(#[source] std::io::Error),
+        #[error("A reference could not be read")]
+        Find(#[from] reftable::find::Error),
+        #[error("The reference {full_name:?} for deletion did not exist or could not be parsed")]
+        DeleteReferenceMustExist { full_name: BString },
+        #[error("Reference {full_name:?} was not supposed to exist when writing it with value {new:?}, but actual content was {actual:?}")]
+        MustNotExist {
+            full_name: BString,
+            actual: Target,
+            new: Target,
+        },
+        #[error("Reference {full_name:?} was supposed to exist with value {expected}, but didn't.")]
+        MustExist { full_name: BString, expected: Target },
+        #[error("The reference {full_name:?} should have content {expected}, actual content was {actual}")]
+        ReferenceOutOfDate {
+            full_name: BString,
+            expected: Target,
+            actual: Target,
+        },
+    }
+}
+
+///
+pub mod commit {
+    use crate::reftable;
+
+    /// The error returned by [`Transaction::commit()`][super::Transaction::commit()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Reflog could not be written as no committer information was provided")]
+        MissingCommitter,
+        #[error("A table could not be read")]
+        Decode(#[from] reftable::decode::Error),
+        #[error("A lock for a new table could not be obtained")]
+        LockAcquire(#[from] gix_lock::acquire::Error),
+        #[error("An IO error occurred while writing a table or the table list")]
+        Io(#[from] std::io::Error),
+    }
+}
@@ -0,0 +1,223 @@
+use std::io::Write;
+
+use gix_features::zlib;
+
+use super::{
+    record::{block_type, encode_value, encode_varint, value_type, LogRecord, Record, RefRecord},
+    table::{footer_len, header_len, MAGIC, SHA256_ID},
+};
+
+/// The amount of records after which a restart point is written, which is the same value that git uses.
+const RESTART_INTERVAL: usize = 16;
+/// The size of blocks if none is configured, which is the same value that git uses.
+pub(crate) const DEFAULT_BLOCK_SIZE: u32 = 4096;
+
+/// Produces tables from records which are already sorted by key.
+pub(crate) struct Writer {
+    pub hash: gix_hash::Kind,
+    pub block_size: u32,
+    pub min_update_index: u64,
+    pub max_update_index: u64,
+}
+
+/// A block being written.
+struct BlockWriter {
+    typ: u8,
+    /// The size of the file header if this is the first block of a table.
+    header_off: usize,
+    /// The encoded records.
+    records: Vec<u8>,
+    restarts: Vec<u32>,
+    last_key: Vec<u8>,
+    num_records: usize,
+}
+
+impl BlockWriter {
+    fn new(typ: u8, header_off: usize) -> Self {
+        BlockWriter {
+            typ,
+            header_off,
+            records: Vec::new(),
+            restarts: Vec::new(),
+            last_key: Vec::new(),
+            num_records: 0,
+        }
+    }
+
+    /// Return the size of the block if a record of `len` bytes and possibly a restart point were added.
+    fn size_with(&self, len: usize, is_restart: bool) -> usize {
+        self.header_off + 4 + self.records.len() + len + 3 * (self.restarts.len() + usize::from(is_restart)) + 2
+    }
+
+    /// Add `record` with `key` if it fits into `block_size`, or return `false` if the block is full.
+    /// Note that the first record of a block is always added to assure progress.
+    fn add(&mut self, key: &[u8], record: &Record, min_update_index: u64, block_size: usize) -> bool {
+        let is_restart = self.num_records % RESTART_INTERVAL == 0;
+        let prefix_len = if is_restart {
+            0
+        } else {
+            key.iter().zip(self.last_key.iter()).take_while(|(a, b)| a == b).count()
+        };
+        let mut buf = Vec::new();
+        encode_varint(prefix_len as u64, &mut buf);
+        encode_varint(
+            (((key.len() - prefix_len) as u64) << 3) | u64::from(value_type(record)),
+            &mut buf,
+        );
+        buf.extend_from_slice(&key[prefix_len..]);
+        encode_value(record, min_update_index, &mut buf);
+        if self.num_records > 0 && self.size_with(buf.len(), is_restart) > block_size {
+            return false;
+        }
+        if is_restart {
+            self.restarts.push((self.header_off + 4 + self.records.len()) as u32);
+        }
+        self.records.extend_from_slice(&buf);
+        self.last_key.clear();
+        self.last_key.extend_from_slice(key);
+        self.num_records += 1;
+        true
+    }
+
+    /// Append the finished block to `out`, compressing it if it's a log block.
+    fn finish(self, out: &mut Vec<u8>) {
+        let mut body = self.records;
+        for restart in &self.restarts {
+            body.extend_from_slice(&restart.to_be_bytes()[1..]);
+        }
+        body.extend_from_slice(&(self.restarts.len() as u16).to_be_bytes());
+        let len = self.header_off + 4 + body.len();
+        out.push(self.typ);
+        out.extend_from_slice(&(len as u32).to_be_bytes()[1..]);
+        if self.typ == block_type::LOG {
+            let mut deflate = zlib::stream::deflate::Write::new(out);
+            deflate
+                .write_all(&body)
+                .and_then(|_| deflate.flush())
+                .expect("writing to a vec never fails");
+        } else {
+            out.extend_from_slice(&body);
+        }
+    }
+}
+
+impl Writer {
+    /// Write a table containing all `refs` and `logs`, both of which must be sorted by their key.
+    pub fn write(&self, refs: &[RefRecord], logs: &[LogRecord]) -> Vec<u8> {
+        let version = match self.hash {
+            gix_hash::Kind::Sha1 => 1,
+            gix_hash::Kind::Sha256 => 2,
+        };
+        let mut header = Vec::with_capacity(header_len(version));
+        header.extend_from_slice(MAGIC);
+        header.push(version);
+        header.extend_from_slice(&self.block_size.to_be_bytes()[1..]);
+        header.extend_from_slice(&self.min_update_index.to_be_bytes());
+        header.extend_from_slice(&self.max_update_index.to_be_bytes());
+        if version == 2 {
+            header.extend_from_slice(&SHA256_ID.to_be_bytes());
+        }
+
+        let mut out = header.clone();
+        let ref_index_position = self.write_section(
+            block_type::REF,
+            refs.iter().map(|r| (r.name.to_vec(), Record::Ref(r.clone()))),
+            &mut out,
+        );
+        let logs_start = out.len();
+        let log_index_position = self.write_section(
+            block_type::LOG,
+            logs.iter().map(|r| (r.key(), Record::Log(r.clone()))),
+            &mut out,
+        );
+        // If the log section comes first, its position is implied.
+        let log_position = if out.len() == logs_start || logs_start == header.len() {
+            0
+        } else {
+            logs_start as u64
+        };
+
+        let footer_start = out.len();
+        out.extend_from_slice(&header);
+        for value in [
+            ref_index_position.unwrap_or(0),
+            0, /* object blocks */
+            0, /* object index */
+            log_position,
+            log_index_position.unwrap_or(0),
+        ] {
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        let crc = gix_features::hash::crc32(&out[footer_start..]);
+        out.extend_from_slice(&crc.to_be_bytes());
+        debug_assert_eq!(out.len() - footer_start, footer_len(version));
+        out
+    }
+
+    /// Write all `records` of a section of type `typ` as blocks into `out`, and return the position of the index
+    /// if one was written.
+    fn write_section(
+        &self,
+        typ: u8,
+        records: impl Iterator<Item = (Vec<u8>, Record)>,
+        out: &mut Vec<u8>,
+    ) -> Option<u64> {
+        let block_size = self.block_size as usize;
+        let header_len = header_len(out[4]);
+        let new_block = |out: &Vec<u8>| BlockWriter::new(typ, if out.len() == header_len { header_len } else { 0 });
+        let block_position = |out: &Vec<u8>| if out.len() == header_len { 0 } else { out.len() as u64 };
+
+        let mut index = Vec::new();
+        let mut block = new_block(out);
+        let mut position = block_position(out);
+        for (key, record) in records {
+            if !block.add(&key, &record, self.min_update_index, block_size) {
+                index.push((std::mem::take(&mut block.last_key), position));
+                block.finish(out);
+                block = new_block(out);
+                position = block_position(out);
+                assert!(
+                    block.add(&key, &record, self.min_update_index, block_size),
+                    "empty blocks take any record"
+                );
+            }
+        }
+        if block.num_records == 0 {
+            return None;
+        }
+        index.push((std::mem::take(&mut block.last_key), position));
+        block.finish(out);
+
+        // Like git, only write an index if there are enough blocks to make it worth it, possibly with multiple levels.
+        if index.len() < 4 {
+            return None;
+        }
+        loop {
+            let mut next_level = Vec::new();
+            let mut block = BlockWriter::new(block_type::INDEX, 0);
+            let mut position = out.len() as u64;
+            for (last_key, block_position) in index {
+                let record = Record::Index {
+                    last_key: last_key.clone().into(),
+                    position: block_position,
+                };
+                if !block.add(&last_key, &record, 0, block_size) {
+                    next_level.push((std::mem::take(&mut block.last_key), position));
+                    block.finish(out);
+                    block = BlockWriter::new(block_type::INDEX, 0);
+                    position = out.len() as u64;
+                    assert!(
+                        block.add(&last_key, &record, 0, block_size),
+                        "empty blocks take any record"
+                    );
+                }
+            }
+            next_level.push((std::mem::take(&mut block.last_key), position));
+            block.finish(out);
+            if next_level.len() == 1 {
+                return Some(position);
+            }
+            index = next_level;
+        }
+    }
+}
@@ -431,6 +431,69 @@ fn symbolic_reference_writes_reflog_if_previous_value_is_set() -> crate::Result
     Ok(())
 }
 
+#[test]
+fn symbolic_head_changing_its_referent_or_detaching_writes_reflog() -> crate::Result {
+    let (_keep, store) = empty_store()?;
+    let a = hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242");
+    let b = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    let update_head = |new: Target, message: &str| -> crate::Result {
+        store
+            .transaction()
+            .prepare(
+                Some(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: message.into(),
+                        },
+                        expected: PreviousValue::Any,
+                        new,
+                    },
+                    name: "HEAD".try_into()?,
+                    deref: false,
+                }),
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref())?;
+        Ok(())
+    };
+    for (name, id) in [("refs/heads/a", a), ("refs/heads/b", b)] {
+        store
+            .transaction()
+            .prepare(
+                Some(RefEdit {
+                    change: Change::Update {
+                        log: LogChange::default(),
+                        expected: PreviousValue::MustNotExist,
+                        new: Target::Object(id),
+                    },
+                    name: name.try_into()?,
+                    deref: false,
+                }),
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref())?;
+    }
+    update_head(Target::Symbolic("refs/heads/a".try_into()?), "initial")?;
+    assert!(
+        store.reflog_iter("HEAD", &mut Vec::new())?.is_none(),
+        "there was no previous referent, so nothing is logged"
+    );
+
+    update_head(Target::Symbolic("refs/heads/b".try_into()?), "switch to b")?;
+    update_head(Target::Symbolic("refs/heads/b".try_into()?), "no change")?;
+    update_head(Target::Object(a), "detach")?;
+    assert_eq!(
+        reflog_lines(&store, "HEAD")?,
+        vec![log_line(a, b, "switch to b"), log_line(b, a, "detach")],
+        "the ids of the previous and new referent are logged"
+    );
+    Ok(())
+}
+
 #[test]
 fn windows_device_name_is_illegal_with_enabled_windows_protections() -> crate::Result {
     let (_keep, mut store) = empty_store()?;
@@ -10,6 +10,7 @@ mod file;
 mod fullname;
 mod namespace;
 mod packed;
+mod reftable;
 mod reference;
 mod store;
 mod transaction;
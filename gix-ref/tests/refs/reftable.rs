@@ -0,0 +1,400 @@
+use gix_date::{time::Sign, Time};
+use gix_lock::acquire::Fail;
+use gix_object::bstr::ByteSlice;
+use gix_ref::{
+    reftable,
+    store::{Backend, ReferenceExt},
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::hex_to_id;
+
+fn committer() -> gix_actor::Signature {
+    gix_actor::Signature {
+        name: "committer".into(),
+        email: "committer@example.com".into(),
+        time: Time {
+            seconds: 1234,
+            offset: 1800,
+            sign: Sign::Plus,
+        },
+    }
+}
+
+fn empty_store() -> (gix_testtools::tempfile::TempDir, reftable::Store) {
+    let dir = gix_testtools::tempfile::TempDir::new().unwrap();
+    let store = reftable::Store::at(dir.path().into(), Default::default());
+    (dir, store)
+}
+
+fn update(name: &str, new: Target, expected: PreviousValue) -> RefEdit {
+    RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: format!("update {name}").into(),
+            },
+            expected,
+            new,
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    }
+}
+
+fn delete(name: &str) -> RefEdit {
+    RefEdit {
+        change: Change::Delete {
+            expected: PreviousValue::MustExist,
+            log: RefLog::AndReference,
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    }
+}
+
+/// Return a unique, non-null id for `n`.
+fn id(n: usize) -> gix_hash::ObjectId {
+    hex_to_id(&format!("1{n:039x}"))
+}
+
+fn commit(store: &reftable::Store, edits: impl IntoIterator<Item = RefEdit>) -> crate::Result<Vec<RefEdit>> {
+    Ok(store
+        .transaction()
+        .prepare(edits, Fail::Immediately)?
+        .commit(committer().to_ref())?)
+}
+
+fn tables(store: &reftable::Store) -> crate::Result<usize> {
+    Ok(std::fs::read(store.reftable_dir().join("tables.list"))?.lines().count())
+}
+
+#[test]
+fn read_table_written_by_git() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_reftable_repo.sh")?;
+    let git_dir = dir.join("reftable-clone").join(".git");
+    let handle = gix_ref::Store::at(git_dir.clone(), Backend::Reftable, Default::default())?.to_handle();
+
+    let head = handle.find("HEAD")?;
+    assert_eq!(head.target.try_name().expect("symbolic").as_bstr(), "refs/heads/main");
+    let main = handle.find("main")?;
+    assert_eq!(main.name.as_bstr(), "refs/heads/main");
+    assert_eq!(
+        handle.find("origin")?.target.try_name().expect("symbolic").as_bstr(),
+        "refs/remotes/origin/main",
+        "the lookup rules are the same as for files"
+    );
+    assert!(handle.try_find("does-not-exist")?.is_none());
+
+    let names: Vec<_> = handle
+        .iter()?
+        .all()?
+        .map(|r| r.map(|r| r.name.as_bstr().to_owned()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        names,
+        [
+            "refs/heads/main",
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/main"
+        ]
+    );
+    let names: Vec<_> = handle
+        .iter()?
+        .prefixed("refs/remotes/origin/m".try_into()?)?
+        .map(|r| r.map(|r| r.name.as_bstr().to_owned()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(names, ["refs/remotes/origin/main"]);
+
+    for name in ["HEAD", "refs/heads/main"] {
+        let log = handle.reflog(name)?.expect("reflog exists");
+        assert_eq!(log.len(), 1);
+        assert!(log[0].previous_oid.is_null());
+        assert_eq!(log[0].new_oid, main.target.id());
+        assert!(log[0].message.starts_with(b"clone: from "), "{:?}", log[0].message);
+        assert!(!log[0].message.ends_with(b"\n"), "the trailing newline is stripped");
+    }
+    assert!(handle.reflog_exists("refs/remotes/origin/HEAD")?);
+    assert!(!handle.reflog_exists("refs/heads/does-not-exist")?);
+
+    let mut head = head;
+    assert!(head.log_exists(&handle));
+    let mut log = head.log_iter(&handle);
+    let line = log.rev()?.expect("reflog exists").next().expect("one line")?;
+    assert_eq!(line.new_oid, main.target.id());
+    let line = log.all()?.expect("reflog exists").next().expect("one line")?;
+    assert_eq!(
+        line.new_oid(),
+        main.target.id(),
+        "lines are serialized so they can be read like reflog files"
+    );
+    assert_eq!(
+        head.follow_to_object_in_place_packed(&handle, None)?,
+        main.target.id(),
+        "symbolic references are followed through the tables"
+    );
+    assert_eq!(head.name.as_bstr(), "refs/heads/main");
+
+    let store = reftable::Store::at(git_dir, Default::default());
+    assert_eq!(store.find("main")?, main, "the store can be used directly as well");
+    Ok(())
+}
+
+#[test]
+fn updates_deletions_and_reflogs_across_many_tables() -> crate::Result {
+    let (_dir, store) = empty_store();
+    // Enough references to need multiple blocks and an index.
+    let edits = commit(
+        &store,
+        (0..2000).map(|n| {
+            update(
+                &format!("refs/heads/branch-{n:04}"),
+                Target::Object(id(n)),
+                PreviousValue::MustNotExist,
+            )
+        }),
+    )?;
+    assert_eq!(edits.len(), 2000);
+    assert_eq!(tables(&store)?, 1);
+
+    for n in [0, 1, 999, 1000, 1999] {
+        let r = store.find(format!("branch-{n:04}").as_str())?;
+        assert_eq!(r.target, Target::Object(id(n)));
+    }
+    assert_eq!(store.iter()?.all()?.count(), 2000);
+    assert_eq!(
+        store
+            .iter()?
+            .prefixed("refs/heads/branch-19".try_into()?)?
+            .collect::<Result<Vec<_>, _>>()?
+            .len(),
+        100
+    );
+
+    for round in 1..=20 {
+        commit(
+            &store,
+            Some(update(
+                "refs/heads/branch-0000",
+                Target::Object(id(10_000 + round)),
+                PreviousValue::MustExistAndMatch(Target::Object(id(if round == 1 { 0 } else { 10_000 + round - 1 }))),
+            )),
+        )?;
+    }
+    assert!(
+        tables(&store)? <= 3,
+        "tables are compacted automatically to keep the stack small"
+    );
+    assert_eq!(store.find("branch-0000")?.target, Target::Object(id(10_020)));
+    let log = store.reflog("refs/heads/branch-0000")?.expect("reflog exists");
+    assert_eq!(
+        log.len(),
+        21,
+        "one entry for creation and one for each update, oldest first"
+    );
+    assert_eq!(log[0].new_oid, id(0));
+    assert_eq!(log[20].previous_oid, id(10_019));
+    assert_eq!(log[20].new_oid, id(10_020));
+    assert_eq!(log[20].message, "update refs/heads/branch-0000");
+    assert_eq!(log[20].signature, committer());
+
+    let err = store
+        .transaction()
+        .prepare(
+            Some(update(
+                "refs/heads/branch-0000",
+                Target::Object(id(0)),
+                PreviousValue::MustExistAndMatch(Target::Object(id(0))),
+            )),
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert!(
+        matches!(err, reftable::transaction::prepare::Error::ReferenceOutOfDate { .. }),
+        "{err:?}"
+    );
+
+    commit(
+        &store,
+        [delete("refs/heads/branch-0000"), delete("refs/heads/branch-1999")],
+    )?;
+    assert!(store.try_find("branch-0000")?.is_none());
+    assert!(store.try_find("branch-1999")?.is_none());
+    assert!(
+        store.reflog("refs/heads/branch-0000")?.is_none(),
+        "reflogs are deleted with their ref"
+    );
+    assert_eq!(store.iter()?.all()?.count(), 1998);
+    Ok(())
+}
+
+#[test]
+fn symbolic_refs_and_reflog_rules() -> crate::Result {
+    let (_dir, store) = empty_store();
+    commit(
+        &store,
+        [
+            update(
+                "HEAD",
+                Target::Symbolic("refs/heads/main".try_into()?),
+                PreviousValue::Any,
+            ),
+            update("refs/heads/main", Target::Object(id(1)), PreviousValue::Any),
+            update("refs/tags/v1", Target::Object(id(1)), PreviousValue::Any),
+        ],
+    )?;
+    assert_eq!(
+        store.find("HEAD")?.target.try_name().expect("symbolic").as_bstr(),
+        "refs/heads/main"
+    );
+    assert!(store.reflog_exists("refs/heads/main")?);
+    assert!(
+        !store.reflog_exists("refs/tags/v1")?,
+        "tags don't get a reflog by default"
+    );
+
+    let mut edit = update("HEAD", Target::Object(id(2)), PreviousValue::Any);
+    edit.deref = true;
+    let edits = commit(&store, Some(edit))?;
+    assert_eq!(
+        edits.len(),
+        2,
+        "the update of HEAD was split to also update its referent"
+    );
+    assert_eq!(store.find("main")?.target, Target::Object(id(2)));
+    assert_eq!(
+        store.find("HEAD")?.target.try_name().expect("still symbolic").as_bstr(),
+        "refs/heads/main"
+    );
+    let head_log = store.reflog("HEAD")?.expect("HEAD has a reflog");
+    assert_eq!(head_log.len(), 1);
+    assert_eq!(
+        head_log[0].previous_oid,
+        id(1),
+        "the previous value is the one of the referent"
+    );
+    assert_eq!(head_log[0].new_oid, id(2));
+    Ok(())
+}
+
+#[test]
+fn namespaces_isolate_references() -> crate::Result {
+    let (_dir, mut store) = empty_store();
+    store.namespace = Some(gix_ref::namespace::expand("foo")?);
+    commit(
+        &store,
+        Some(update(
+            "refs/heads/main",
+            Target::Object(id(1)),
+            PreviousValue::MustNotExist,
+        )),
+    )?;
+    let r = store.find("main")?;
+    assert_eq!(r.name.as_bstr(), "refs/heads/main", "the namespace is stripped");
+    assert_eq!(store.iter()?.all()?.count(), 1);
+
+    store.namespace = None;
+    assert!(store.try_find("main")?.is_none());
+    assert_eq!(
+        store.find("refs/namespaces/foo/refs/heads/main")?.target,
+        Target::Object(id(1))
+    );
+    Ok(())
+}
+
+#[test]
+fn general_store_uses_the_configured_backend() -> crate::Result {
+    let (dir, store) = empty_store();
+    let files = gix_ref::Store::at(dir.path().into(), Backend::Files, Default::default())?.to_handle();
+    assert_eq!(files.backend(), Backend::Files);
+    assert!(files.as_file_store().is_some());
+
+    commit(
+        &store,
+        Some(update(
+            "refs/heads/main",
+            Target::Object(id(1)),
+            PreviousValue::MustNotExist,
+        )),
+    )?;
+    assert!(
+        files.try_find("main")?.is_none(),
+        "the backend isn't guessed from the presence of tables"
+    );
+
+    let handle = gix_ref::Store::at(dir.path().into(), Backend::Reftable, Default::default())?.to_handle();
+    assert_eq!(handle.backend(), Backend::Reftable);
+    assert!(handle.as_file_store().is_none());
+    assert!(handle.cached_packed_buffer()?.is_none());
+    handle
+        .transaction()
+        .prepare(
+            Some(update(
+                "refs/heads/main",
+                Target::Object(id(2)),
+                PreviousValue::MustExist,
+            )),
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert_eq!(handle.find("main")?.target, Target::Object(id(2)));
+    assert_eq!(handle.reflog("refs/heads/main")?.expect("present").len(), 2);
+    assert!(
+        !dir.path().join("refs").exists(),
+        "nothing was written into the loose refs directory"
+    );
+
+    assert!(
+        matches!(
+            gix_ref::Store::for_linked_worktree(
+                dir.path().into(),
+                dir.path().into(),
+                Backend::Reftable,
+                Default::default()
+            ),
+            Err(gix_ref::store::init::Error::ReftableLinkedWorktree)
+        ),
+        "linked worktrees are rejected explicitly"
+    );
+    Ok(())
+}
+
+#[test]
+fn sha256_tables_use_version_2() -> crate::Result {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let store = reftable::Store::at(
+        dir.path().into(),
+        gix_ref::store::init::Options {
+            object_hash: gix_hash::Kind::Sha256,
+            ..Default::default()
+        },
+    );
+    let id = gix_hash::ObjectId::from_hex(b"8aec4e4876f854f688d0ebfc8f37598f38e5fd6903cccc850ca36591175aeb60")?;
+    commit(
+        &store,
+        Some(update(
+            "refs/heads/main",
+            Target::Object(id),
+            PreviousValue::MustNotExist,
+        )),
+    )?;
+
+    let list = std::fs::read(store.reftable_dir().join("tables.list"))?;
+    let table = std::fs::read(
+        store
+            .reftable_dir()
+            .join(list.lines().next().expect("one table").to_str()?),
+    )?;
+    assert_eq!(table[4], 2, "the version");
+    assert_eq!(&table[24..28], b"s256", "the hash id");
+
+    assert_eq!(store.find("main")?.target, Target::Object(id));
+    let log = store.reflog("refs/heads/main")?.expect("reflog exists");
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].previous_oid, gix_hash::Kind::Sha256.null());
+    assert_eq!(log[0].new_oid, id);
+    Ok(())
+}
@@ -1,2 +1,21 @@
-#![deny(rust_2018_idioms)]
+//! Read and write the state of a sequence of commits to cherry-pick or revert, as kept by git in the `sequencer`
+//! directory, and produce the messages of the commits that result from it.
+//!
+//! This crate doesn't replay commits itself, which requires merging trees and updating the index and the worktree.
+//! That is the job of `gix`, which drives cherry-picks and reverts based on the [`State`] provided here, so that
+//! sequences started by git can be continued, skipped or aborted and vice versa.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+/// The name of the directory within the git directory that holds the [`State`] of a sequence in progress.
+pub const STATE_DIR: &str = "sequencer";
+
+///
+pub mod todo;
+
+///
+pub mod state;
+pub use state::{Options, State};
+
+///
+pub mod message;
@@ -0,0 +1,78 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The text that precedes the id of the picked commit in messages of commits picked with [`record_origin`](crate::Options::record_origin).
+pub const CHERRY_PICKED_PREFIX: &str = "(cherry picked from commit ";
+
+/// Return the message of the commit that picks the commit `id` with `message`, which is `message` itself unless
+/// `record_origin` is `true`, in which case a line noting `id` is appended, like `git cherry-pick -x` does.
+///
+/// The line is appended to the trailers of `message` if it has any, or as paragraph of its own otherwise.
+pub fn cherry_pick(message: &BStr, id: &gix_hash::oid, record_origin: bool) -> BString {
+    let mut out = message.to_owned();
+    if !record_origin {
+        return out;
+    }
+    if !out.is_empty() && !out.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+    if !has_trailers(out.as_ref()) {
+        out.push(b'\n');
+    }
+    out.push_str(CHERRY_PICKED_PREFIX);
+    out.push_str(id.to_string());
+    out.push_str(")\n");
+    out
+}
+
+/// Return the message of the commit that reverts the commit `id` with `message`, like `git revert` does.
+///
+/// `merge_parent` is the parent whose changes are kept if the reverted commit is a merge commit, and is mentioned in
+/// the message.
+pub fn revert(message: &BStr, id: &gix_hash::oid, merge_parent: Option<&gix_hash::oid>) -> BString {
+    let subject = gix_object::commit::MessageRef::from_bytes(message).summary();
+    let mut out = BString::from("Revert \"");
+    out.push_str(subject.as_ref());
+    out.push_str("\"\n\nThis reverts commit ");
+    out.push_str(id.to_string());
+    if let Some(parent) = merge_parent {
+        out.push_str(", reversing\nchanges made to ");
+        out.push_str(parent.to_string());
+    }
+    out.push_str(".\n");
+    out
+}
+
+/// Remove comment lines starting with `#`, trailing whitespace and consecutive, leading and trailing empty lines from
+/// `message`, like `git commit --cleanup=strip` does with messages prepared in `MERGE_MSG`.
+pub fn cleanup(message: &BStr) -> BString {
+    let mut out = BString::default();
+    let mut pending_empty_line = false;
+    for line in message.lines().filter(|line| !line.starts_with(b"#")) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            pending_empty_line = !out.is_empty();
+            continue;
+        }
+        if pending_empty_line {
+            out.push(b'\n');
+            pending_empty_line = false;
+        }
+        out.push_str(line);
+        out.push(b'\n');
+    }
+    out
+}
+
+/// Return `true` if the last paragraph of `message` consists of trailers only, with the subject not counting as paragraph.
+fn has_trailers(message: &BStr) -> bool {
+    let message = message.trim_end();
+    let Some(pos) = message.rfind(b"\n\n") else {
+        return false;
+    };
+    message[pos + 2..].lines().all(|line| {
+        line.starts_with(CHERRY_PICKED_PREFIX.as_bytes())
+            || line
+                .find_byte(b':')
+                .is_some_and(|colon| colon > 0 && line[..colon].iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-'))
+    })
+}
@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::todo;
+
+/// The error returned by [`State::from_dir()`] and [`State::write_to_dir()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read or write '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("'{}' doesn't contain a valid object id", path.display())]
+    ObjectId {
+        path: PathBuf,
+        source: gix_hash::decode::Error,
+    },
+    #[error("Could not parse the todo list at '{}'", path.display())]
+    Todo { path: PathBuf, source: todo::Error },
+    #[error("Could not parse the options at '{}'", path.display())]
+    ParseOptions {
+        path: PathBuf,
+        source: gix_config::file::init::Error,
+    },
+    #[error("The option '{key}' at '{}' has an invalid value", path.display())]
+    OptionValue {
+        path: PathBuf,
+        key: &'static str,
+        source: gix_config::value::Error,
+    },
+    #[error("The mainline {value} at '{}' must be 1 or larger", path.display())]
+    Mainline { path: PathBuf, value: i64 },
+}
+
+/// Options that affect how the commits of a sequence are picked or reverted, stored in the `opts` file.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The 1-based number of the parent of merge commits to pick or revert the changes against, like `-m <parent>` does.
+    ///
+    /// It's required for merge commits, and may only be `1` for other commits.
+    pub mainline: Option<usize>,
+    /// If `true`, append a line saying which commit was picked to the messages of picked commits, like `-x` does.
+    pub record_origin: bool,
+    /// If `true`, commits that become empty as their changes are present already are kept, instead of being dropped.
+    pub keep_redundant_commits: bool,
+}
+
+/// The state of a sequence of cherry-picks or reverts in progress, as stored in the [`sequencer`](crate::STATE_DIR)
+/// directory by git.
+///
+/// Files that aren't represented here are kept as they are when writing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    /// The commit `HEAD` pointed to before the sequence started, which it's reset to when aborting. Stored in `head`.
+    pub head: ObjectId,
+    /// The commits that are yet to be picked or reverted, with the first one being the current one, stored in `todo`.
+    pub todo: todo::List,
+    /// The options that apply to all commits, stored in `opts`.
+    pub options: Options,
+    /// The commit `HEAD` pointed to after the last commit was created by the sequence, stored in `abort-safety`.
+    ///
+    /// If `HEAD` points elsewhere, it was moved by the user, and aborting won't reset it.
+    pub abort_safety: Option<ObjectId>,
+}
+
+impl State {
+    /// Create the state for a new sequence that executes `todo` with `options`, with `HEAD` at `head`.
+    pub fn new(head: ObjectId, todo: todo::List, options: Options) -> Self {
+        State {
+            head,
+            todo,
+            options,
+            abort_safety: Some(head),
+        }
+    }
+}
+
+/// The name of the section in `opts` that holds all options.
+const OPTIONS_SECTION: &str = "options";
+
+/// Reading and writing
+impl State {
+    /// Read the state from the `sequencer` directory at `dir`, or return `None` if it doesn't exist as no sequence is in progress.
+    pub fn from_dir(dir: &Path) -> Result<Option<Self>, Error> {
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+        let head = {
+            let (path, hex) = read_trimmed_if_present(dir, "head")?.ok_or_else(|| Error::Io {
+                path: dir.join("head"),
+                source: std::io::ErrorKind::NotFound.into(),
+            })?;
+            ObjectId::from_hex(&hex).map_err(|source| Error::ObjectId { path, source })?
+        };
+        let todo = match read_if_present(dir, "todo")? {
+            Some((path, content)) => todo::List::from_bytes(&content).map_err(|source| Error::Todo { path, source })?,
+            None => Default::default(),
+        };
+        let options = match read_if_present(dir, "opts")? {
+            Some((path, content)) => parse_options(path, &content)?,
+            None => Default::default(),
+        };
+        let abort_safety = read_trimmed_if_present(dir, "abort-safety")?
+            .filter(|(_, hex)| !hex.is_empty())
+            .map(|(path, hex)| ObjectId::from_hex(&hex).map_err(|source| Error::ObjectId { path, source }))
+            .transpose()?;
+        Ok(Some(State {
+            head,
+            todo,
+            options,
+            abort_safety,
+        }))
+    }
+
+    /// Write this state into the `sequencer` directory at `dir`, creating it if needed, in a way that git understands.
+    ///
+    /// The `opts` file is only written if it doesn't exist yet, to retain options that git may have written and that
+    /// aren't represented here.
+    pub fn write_to_dir(&self, dir: &Path) -> Result<(), Error> {
+        std::fs::create_dir_all(dir).map_err(|source| Error::Io {
+            path: dir.to_owned(),
+            source,
+        })?;
+        write(dir, "head", Some(with_newline(self.head.to_string())))?;
+        write(dir, "todo", Some(self.todo.to_bstring().into()))?;
+        write(
+            dir,
+            "abort-safety",
+            self.abort_safety.map(|id| with_newline(id.to_string())),
+        )?;
+        if !dir.join("opts").is_file() {
+            write(dir, "opts", Some(options_to_bytes(&self.options)))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_options(path: PathBuf, content: &[u8]) -> Result<Options, Error> {
+    let config = match gix_config::File::from_bytes_no_includes(
+        content,
+        gix_config::file::Metadata::from(gix_config::Source::Local),
+        Default::default(),
+    ) {
+        Ok(config) => config,
+        Err(source) => return Err(Error::ParseOptions { path, source }),
+    };
+    let boolean = |key: &'static str| {
+        config
+            .boolean_by(OPTIONS_SECTION, None, key)
+            .transpose()
+            .map(Option::unwrap_or_default)
+            .map_err(|source| Error::OptionValue {
+                path: path.clone(),
+                key,
+                source,
+            })
+    };
+    let mainline = match config.integer_by(OPTIONS_SECTION, None, "mainline").transpose() {
+        Ok(None) => None,
+        Ok(Some(value)) => {
+            Some(
+                usize::try_from(value)
+                    .ok()
+                    .filter(|value| *value > 0)
+                    .ok_or_else(|| Error::Mainline {
+                        path: path.clone(),
+                        value,
+                    })?,
+            )
+        }
+        Err(source) => {
+            return Err(Error::OptionValue {
+                path,
+                key: "mainline",
+                source,
+            })
+        }
+    };
+    Ok(Options {
+        mainline,
+        record_origin: boolean("record-origin")?,
+        keep_redundant_commits: boolean("keep-redundant-commits")?,
+    })
+}
+
+/// Serialize `options` like git does, which only writes options that aren't set to their default.
+fn options_to_bytes(options: &Options) -> Vec<u8> {
+    let mut out = BString::from(format!("[{OPTIONS_SECTION}]\n"));
+    if options.keep_redundant_commits {
+        out.extend_from_slice(b"\tallow-empty = true\n\tkeep-redundant-commits = true\n");
+    }
+    if options.record_origin {
+        out.extend_from_slice(b"\trecord-origin = true\n");
+    }
+    if let Some(mainline) = options.mainline {
+        out.extend_from_slice(format!("\tmainline = {mainline}\n").as_bytes());
+    }
+    out.into()
+}
+
+fn with_newline(value: impl AsRef<[u8]>) -> Vec<u8> {
+    let mut out = value.as_ref().to_vec();
+    out.push(b'\n');
+    out
+}
+
+/// Write `content` to the file `name` in `dir`, or remove that file if `content` is `None`.
+fn write(dir: &Path, name: &str, content: Option<Vec<u8>>) -> Result<(), Error> {
+    let path = dir.join(name);
+    let res = match content {
+        Some(content) => std::fs::write(&path, content),
+        None => match std::fs::remove_file(&path) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        },
+    };
+    res.map_err(|source| Error::Io { path, source })
+}
+
+fn read_if_present(dir: &Path, name: &str) -> Result<Option<(PathBuf, BString)>, Error> {
+    let path = dir.join(name);
+    match std::fs::read(&path) {
+        Ok(content) => Ok(Some((path, content.into()))),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(Error::Io { path, source }),
+    }
+}
+
+fn read_trimmed_if_present(dir: &Path, name: &str) -> Result<Option<(PathBuf, BString)>, Error> {
+    Ok(read_if_present(dir, name)?.map(|(path, content)| (path, content.trim().into())))
+}
@@ -0,0 +1,191 @@
+use bstr::{BString, ByteSlice};
+use gix_hash::Prefix;
+
+/// The error returned by [`List::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number}: unknown action '{action}'")]
+    UnknownAction { line_number: usize, action: BString },
+    #[error("Line {line_number}: '{action}' needs a commit")]
+    MissingCommit { line_number: usize, action: &'static str },
+    #[error("Line {line_number}: '{commit}' isn't a valid commit id")]
+    InvalidCommit {
+        line_number: usize,
+        commit: BString,
+        source: gix_hash::prefix::from_hex::Error,
+    },
+}
+
+/// What to do with a commit of the sequence.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Apply the changes the commit introduced, like `git cherry-pick` does.
+    Pick,
+    /// Apply the inverse of the changes the commit introduced, like `git revert` does.
+    Revert,
+}
+
+impl Action {
+    /// Return the name of the action as it's written in todo lists.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Pick => "pick",
+            Action::Revert => "revert",
+        }
+    }
+}
+
+/// A single line of a todo list.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Line {
+    /// What to do with `commit`.
+    pub action: Action,
+    /// The commit to pick or revert, which may be abbreviated.
+    pub commit: Prefix,
+    /// The text following the commit, which typically is its subject.
+    ///
+    /// It's only informational and ignored when executing the action.
+    pub subject: BString,
+}
+
+/// A list of commits to pick or revert, as stored in the `todo` file of the sequencer.
+///
+/// The first line is the one currently being executed, or the one that stopped the sequence.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct List {
+    /// The lines of the todo list, in order of execution.
+    pub lines: Vec<Line>,
+}
+
+impl From<Vec<Line>> for List {
+    fn from(lines: Vec<Line>) -> Self {
+        List { lines }
+    }
+}
+
+/// Parsing
+impl List {
+    /// Parse a todo list from `data` as written by git, ignoring empty lines and comments.
+    ///
+    /// Actions may be abbreviated to their first letter, and commits may be abbreviated as well, but must be hexadecimal.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut lines = Vec::new();
+        for (line_number, line) in parse::lines(data) {
+            let (name, rest) = parse::split_word(line);
+            let action = match name.as_bytes() {
+                b"pick" | b"p" => Action::Pick,
+                b"revert" => Action::Revert,
+                _ => {
+                    return Err(Error::UnknownAction {
+                        line_number,
+                        action: name.to_owned(),
+                    })
+                }
+            };
+            let (commit, subject) = parse::commit(rest).map_err(|err| match err {
+                parse::Error::MissingCommit => Error::MissingCommit {
+                    line_number,
+                    action: action.name(),
+                },
+                parse::Error::InvalidCommit { commit, source } => Error::InvalidCommit {
+                    line_number,
+                    commit,
+                    source,
+                },
+            })?;
+            lines.push(Line {
+                action,
+                commit,
+                subject: subject.to_owned(),
+            });
+        }
+        Ok(List { lines })
+    }
+}
+
+/// Serialization
+impl List {
+    /// Write all lines of this list to `out` in the format understood by git.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        for line in &self.lines {
+            line.write_to(out)?;
+        }
+        Ok(())
+    }
+
+    /// Return the serialized form of this list, as it would be written by [`write_to()`](Self::write_to()).
+    pub fn to_bstring(&self) -> BString {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).expect("writing to a vec never fails");
+        buf.into()
+    }
+}
+
+impl Line {
+    /// Write this line to `out` in the format understood by git, including the trailing newline.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        write!(out, "{} {}", self.action.name(), self.commit)?;
+        if !self.subject.is_empty() {
+            out.write_all(b" ")?;
+            out.write_all(&self.subject)?;
+        }
+        out.write_all(b"\n")
+    }
+}
+
+/// Utilities to parse the lines of todo lists, which are shared by all kinds of todo lists.
+pub mod parse {
+    use bstr::{BStr, BString, ByteSlice};
+    use gix_hash::Prefix;
+
+    /// The error returned by [`commit()`].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A commit is needed")]
+        MissingCommit,
+        #[error("'{commit}' isn't a valid commit id")]
+        InvalidCommit {
+            commit: BString,
+            source: gix_hash::prefix::from_hex::Error,
+        },
+    }
+
+    /// The character that starts comment lines.
+    const COMMENT_CHAR: u8 = b'#';
+
+    /// Return all lines in `data` that aren't empty or comments, without surrounding whitespace and along with their
+    /// line number, starting at 1.
+    pub fn lines(data: &[u8]) -> impl Iterator<Item = (usize, &BStr)> {
+        data.lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line.trim().as_bstr()))
+            .filter(|(_, line)| !line.is_empty() && line[0] != COMMENT_CHAR)
+    }
+
+    /// Split `input` into the first word and the remainder, without the whitespace in between.
+    pub fn split_word(input: &BStr) -> (&BStr, &BStr) {
+        let input = input.trim_start();
+        let end = input.iter().position(u8::is_ascii_whitespace).unwrap_or(input.len());
+        (input[..end].as_bstr(), input[end..].trim_start().as_bstr())
+    }
+
+    /// Split `input` into a commit, which may be abbreviated but must be hexadecimal, and the remainder which typically
+    /// is the subject of the commit.
+    pub fn commit(input: &BStr) -> Result<(Prefix, &BStr), Error> {
+        let (commit, subject) = split_word(input);
+        if commit.is_empty() {
+            return Err(Error::MissingCommit);
+        }
+        let prefix = commit
+            .to_str()
+            .map_err(|_| gix_hash::prefix::from_hex::Error::Invalid)
+            .and_then(Prefix::from_hex)
+            .map_err(|source| Error::InvalidCommit {
+                commit: commit.to_owned(),
+                source,
+            })?;
+        Ok((prefix, subject))
+    }
+}
@@ -0,0 +1,53 @@
+use gix_sequencer::message;
+
+use crate::hex_to_id;
+
+const ID: &str = "1111111111111111111111111111111111111111";
+
+#[test]
+fn cherry_pick() {
+    let id = hex_to_id(ID);
+    assert_eq!(message::cherry_pick("subject\n".into(), &id, false), "subject\n");
+    assert_eq!(
+        message::cherry_pick("subject".into(), &id, true),
+        format!("subject\n\n(cherry picked from commit {ID})\n"),
+        "the origin gets a paragraph of its own"
+    );
+    assert_eq!(
+        message::cherry_pick("subject\n\nbody\n".into(), &id, true),
+        format!("subject\n\nbody\n\n(cherry picked from commit {ID})\n")
+    );
+    assert_eq!(
+        message::cherry_pick("subject\n\nbody\n\nSigned-off-by: name <email>\n".into(), &id, true),
+        format!("subject\n\nbody\n\nSigned-off-by: name <email>\n(cherry picked from commit {ID})\n"),
+        "existing trailers are extended"
+    );
+    assert_eq!(
+        message::cherry_pick("Key: subject looking like trailer\n".into(), &id, true),
+        format!("Key: subject looking like trailer\n\n(cherry picked from commit {ID})\n"),
+        "the subject never counts as trailer"
+    );
+}
+
+#[test]
+fn revert() {
+    let id = hex_to_id(ID);
+    assert_eq!(
+        message::revert("subject\nwrapped\n\nbody\n".into(), &id, None),
+        format!("Revert \"subject wrapped\"\n\nThis reverts commit {ID}.\n")
+    );
+    let parent = hex_to_id("2222222222222222222222222222222222222222");
+    assert_eq!(
+        message::revert("merge\n".into(), &id, Some(&parent)),
+        format!("Revert \"merge\"\n\nThis reverts commit {ID}, reversing\nchanges made to {parent}.\n")
+    );
+}
+
+#[test]
+fn cleanup() {
+    assert_eq!(
+        message::cleanup("\n\nsubject  \n\n\n\nbody\n# comment\n\n# Conflicts:\n#\tf\n".into()),
+        "subject\n\nbody\n"
+    );
+    assert_eq!(message::cleanup("# only comments\n".into()), "");
+}
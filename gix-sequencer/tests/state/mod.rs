@@ -0,0 +1,92 @@
+use gix_sequencer::{todo, Options, State};
+
+use crate::hex_to_id;
+
+#[test]
+fn missing_directory_means_no_sequence_in_progress() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    assert_eq!(State::from_dir(&tmp.path().join("sequencer"))?, None);
+    Ok(())
+}
+
+#[test]
+fn read_as_written_by_git() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let dir = tmp.path();
+    let opts = "[options]\n\tallow-empty = true\n\tkeep-redundant-commits = true\n\trecord-origin = true\n\tmainline = 2\n\tstrategy = ort\n";
+    for (name, content) in [
+        ("head", "1111111111111111111111111111111111111111\n"),
+        ("abort-safety", "2222222222222222222222222222222222222222\n"),
+        ("todo", "pick a358f57 two\nrevert fb39c26 three\n"),
+        ("opts", opts),
+    ] {
+        std::fs::write(dir.join(name), content)?;
+    }
+
+    let mut state = State::from_dir(dir)?.expect("present");
+    assert_eq!(state.head, hex_to_id("1111111111111111111111111111111111111111"));
+    assert_eq!(
+        state.abort_safety,
+        Some(hex_to_id("2222222222222222222222222222222222222222"))
+    );
+    assert_eq!(state.todo.lines.len(), 2);
+    assert_eq!(state.todo.lines[1].action, todo::Action::Revert);
+    assert_eq!(
+        state.options,
+        Options {
+            mainline: Some(2),
+            record_origin: true,
+            keep_redundant_commits: true,
+        }
+    );
+
+    state.todo.lines.remove(0);
+    state.options = Default::default();
+    state.write_to_dir(dir)?;
+    assert_eq!(std::fs::read_to_string(dir.join("todo"))?, "revert fb39c26 three\n");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("opts"))?,
+        opts,
+        "options written by git are retained"
+    );
+    Ok(())
+}
+
+#[test]
+fn write_and_read() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let dir = tmp.path().join("sequencer");
+    let mut state = State::new(
+        hex_to_id("1111111111111111111111111111111111111111"),
+        todo::List::from_bytes(b"pick 3333333 one\n")?,
+        Options {
+            mainline: Some(1),
+            record_origin: true,
+            keep_redundant_commits: false,
+        },
+    );
+    state.write_to_dir(&dir)?;
+    assert_eq!(state.abort_safety, Some(state.head), "starts out at the original head");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("opts"))?,
+        "[options]\n\trecord-origin = true\n\tmainline = 1\n"
+    );
+    assert_eq!(State::from_dir(&dir)?.expect("present"), state);
+
+    state.abort_safety = None;
+    state.write_to_dir(&dir)?;
+    assert!(!dir.join("abort-safety").exists());
+    assert_eq!(State::from_dir(&dir)?.expect("present"), state);
+    Ok(())
+}
+
+#[test]
+fn invalid_mainline() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let dir = tmp.path();
+    std::fs::write(dir.join("head"), "1111111111111111111111111111111111111111\n")?;
+    std::fs::write(dir.join("opts"), "[options]\n\tmainline = 0\n")?;
+    let err = State::from_dir(dir).unwrap_err();
+    assert!(matches!(err, gix_sequencer::state::Error::Mainline { value: 0, .. }));
+    Ok(())
+}
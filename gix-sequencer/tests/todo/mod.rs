@@ -0,0 +1,35 @@
+use gix_sequencer::todo::{Action, Error, List};
+
+#[test]
+fn parse_and_write() -> Result<(), Error> {
+    let list = List::from_bytes(b"pick 1234567 first\n\n# comment\np 2345678\nrevert 3456789 third one\n")?;
+    assert_eq!(
+        list.lines
+            .iter()
+            .map(|line| (line.action, line.commit.to_string(), line.subject.to_string()))
+            .collect::<Vec<_>>(),
+        [
+            (Action::Pick, "1234567".into(), "first".into()),
+            (Action::Pick, "2345678".into(), String::new()),
+            (Action::Revert, "3456789".into(), "third one".into()),
+        ]
+    );
+    assert_eq!(
+        list.to_bstring(),
+        "pick 1234567 first\npick 2345678\nrevert 3456789 third one\n"
+    );
+    assert_eq!(List::from_bytes(&list.to_bstring())?, list, "round-trips");
+    Ok(())
+}
+
+#[test]
+fn parse_errors() {
+    for (input, expected) in [
+        (&b"pick 1234567\nedit 1234567"[..], "Line 2: unknown action 'edit'"),
+        (b"revert", "Line 1: 'revert' needs a commit"),
+        (b"pick xyz", "Line 1: 'xyz' isn't a valid commit id"),
+    ] {
+        let err = List::from_bytes(input).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+}
@@ -0,0 +1,339 @@
+use gix::{bstr::BString, ObjectId};
+
+use crate::{blame, diff, log::Log, refs, status};
+
+/// A key press, independent of the terminal backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// Move the selection up by one line.
+    Up,
+    /// Move the selection down by one line.
+    Down,
+    /// Move the selection up by one page.
+    PageUp,
+    /// Move the selection down by one page.
+    PageDown,
+    /// Select the first line.
+    Home,
+    /// Select the last line, which traverses the entire history in the log.
+    End,
+    /// Open the selected line.
+    Enter,
+    /// Close the current view, or quit if it's the log.
+    Back,
+    /// Any other character.
+    Char(char),
+}
+
+/// The selected line of a list of lines, along with the first line that is visible.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    /// The index of the selected line.
+    pub selected: usize,
+    /// The index of the first visible line.
+    pub offset: usize,
+}
+
+impl Cursor {
+    fn move_to(&mut self, selected: usize, len: usize, height: usize) {
+        self.selected = selected.min(len.saturating_sub(1));
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+    }
+}
+
+/// What's shown on top of the log.
+pub enum View {
+    /// The commit with `id` along with its changes.
+    Diff {
+        /// The id of the commit.
+        id: ObjectId,
+        /// The lines to show.
+        lines: Vec<diff::Line>,
+        /// The selected line.
+        cursor: Cursor,
+    },
+    /// The file at `path` as seen in commit `id`, with each line annotated by the commit that introduced it.
+    Blame {
+        /// The id of the commit to start the blame at.
+        id: ObjectId,
+        /// The path of the blamed file.
+        path: BString,
+        /// The lines to show.
+        lines: Vec<blame::Line>,
+        /// The selected line.
+        cursor: Cursor,
+    },
+    /// All references that point to commits.
+    Refs {
+        /// The references to show.
+        refs: Vec<refs::Ref>,
+        /// The selected line.
+        cursor: Cursor,
+    },
+    /// All changes in the index and in the worktree.
+    Status {
+        /// The changes to show.
+        entries: Vec<status::Entry>,
+        /// The selected line.
+        cursor: Cursor,
+    },
+}
+
+impl View {
+    /// The name of the view as shown in the status line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            View::Diff { .. } => "diff",
+            View::Blame { .. } => "blame",
+            View::Refs { .. } => "refs",
+            View::Status { .. } => "status",
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            View::Diff { lines, .. } => lines.len(),
+            View::Blame { lines, .. } => lines.len(),
+            View::Refs { refs, .. } => refs.len(),
+            View::Status { entries, .. } => entries.len(),
+        }
+    }
+
+    fn cursor_mut(&mut self) -> &mut Cursor {
+        match self {
+            View::Diff { cursor, .. }
+            | View::Blame { cursor, .. }
+            | View::Refs { cursor, .. }
+            | View::Status { cursor, .. } => cursor,
+        }
+    }
+}
+
+/// The state of the entire application, with the log at the bottom of a stack of views.
+pub struct App {
+    pub(crate) repo: gix::Repository,
+    pub(crate) log: Log,
+    pub(crate) log_cursor: Cursor,
+    pub(crate) views: Vec<View>,
+    pub(crate) height: usize,
+    pub(crate) message: Option<String>,
+    quit: bool,
+}
+
+/// Lifecycle
+impl App {
+    /// Show the history of `tips` in `repo`, or the history of `HEAD` if `tips` is empty.
+    pub fn new(repo: gix::Repository, tips: Vec<ObjectId>) -> anyhow::Result<Self> {
+        let tips = if tips.is_empty() {
+            repo.head_id().ok().map(gix::Id::detach).into_iter().collect()
+        } else {
+            tips
+        };
+        Ok(App {
+            log: Log::new(&repo, tips)?,
+            repo,
+            log_cursor: Cursor::default(),
+            views: Vec::new(),
+            height: 0,
+            message: None,
+            quit: false,
+        })
+    }
+}
+
+/// Access
+impl App {
+    /// Return the log of commits at the bottom of all views.
+    pub fn log(&self) -> &Log {
+        &self.log
+    }
+
+    /// Return the view that is currently shown, or `None` if it's the log.
+    pub fn view(&self) -> Option<&View> {
+        self.views.last()
+    }
+
+    /// Return the commit that is selected in the current view, if there is one.
+    pub fn selected_commit(&self) -> Option<ObjectId> {
+        match self.views.last() {
+            None => self.log.rows().get(self.log_cursor.selected).map(|row| row.id),
+            Some(View::Diff { id, .. }) => Some(*id),
+            Some(View::Blame { lines, cursor, .. }) => lines.get(cursor.selected).map(|line| line.commit_id),
+            Some(View::Refs { refs, cursor }) => refs.get(cursor.selected).map(|r| r.id),
+            Some(View::Status { .. }) => None,
+        }
+    }
+
+    /// Return `true` if the application should exit.
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+}
+
+/// Navigation
+impl App {
+    /// Open the commit with `id` along with its changes.
+    pub fn open_diff(&mut self, id: ObjectId) -> anyhow::Result<()> {
+        let lines = diff::commit(&self.repo, id)?;
+        self.views.push(View::Diff {
+            id,
+            lines,
+            cursor: Cursor::default(),
+        });
+        Ok(())
+    }
+
+    /// Open the file at `path` as seen in the commit with `id`, with each line annotated by the commit that introduced it.
+    pub fn open_blame(&mut self, id: ObjectId, path: BString) -> anyhow::Result<()> {
+        let lines = blame::file(&self.repo, id, path.as_ref())?;
+        self.views.push(View::Blame {
+            id,
+            path,
+            lines,
+            cursor: Cursor::default(),
+        });
+        Ok(())
+    }
+
+    /// Open the list of all references.
+    pub fn open_refs(&mut self) -> anyhow::Result<()> {
+        let refs = refs::list(&self.repo)?;
+        self.views.push(View::Refs {
+            refs,
+            cursor: Cursor::default(),
+        });
+        Ok(())
+    }
+
+    /// Open the list of all changes in the index and in the worktree.
+    pub fn open_status(&mut self) -> anyhow::Result<()> {
+        let entries = status::entries(&self.repo)?;
+        self.views.push(View::Status {
+            entries,
+            cursor: Cursor::default(),
+        });
+        Ok(())
+    }
+
+    /// Replace the log with the history of `tip` and close all other views.
+    pub fn open_log(&mut self, tip: ObjectId) -> anyhow::Result<()> {
+        self.log = Log::new(&self.repo, Some(tip))?;
+        self.log_cursor = Cursor::default();
+        self.views.clear();
+        Ok(())
+    }
+
+    /// Handle `key`, and show errors in the status line instead of returning them.
+    pub fn handle_key(&mut self, key: Key) {
+        self.message = None;
+        if let Err(err) = self.handle_key_inner(key) {
+            self.message = Some(format!("{err:#}"));
+        }
+    }
+
+    fn handle_key_inner(&mut self, key: Key) -> anyhow::Result<()> {
+        let page = self.height.max(1);
+        match key {
+            Key::Up | Key::Char('k') => self.move_selection(|selected| selected.saturating_sub(1))?,
+            Key::Down | Key::Char('j') => self.move_selection(|selected| selected + 1)?,
+            Key::PageUp => self.move_selection(|selected| selected.saturating_sub(page))?,
+            Key::PageDown | Key::Char(' ') => self.move_selection(|selected| selected + page)?,
+            Key::Home | Key::Char('g') => self.move_selection(|_| 0)?,
+            Key::End | Key::Char('G') => self.move_selection(|_| usize::MAX)?,
+            Key::Enter => self.open_selected()?,
+            Key::Char('b') => self.blame_selected()?,
+            Key::Char('r') => self.open_refs()?,
+            Key::Char('s') => self.open_status()?,
+            Key::Char('l') => self.views.clear(),
+            Key::Back | Key::Char('q') => {
+                if self.views.pop().is_none() {
+                    self.quit = true;
+                }
+            }
+            Key::Char('Q') => self.quit = true,
+            Key::Char(_) => {}
+        }
+        Ok(())
+    }
+
+    fn move_selection(&mut self, selected: impl FnOnce(usize) -> usize) -> anyhow::Result<()> {
+        let height = self.height;
+        match self.views.last_mut() {
+            None => {
+                let selected = selected(self.log_cursor.selected);
+                self.log.fill(selected.saturating_add(1))?;
+                self.log_cursor.move_to(selected, self.log.rows().len(), height);
+            }
+            Some(view) => {
+                let len = view.len();
+                let cursor = view.cursor_mut();
+                let selected = selected(cursor.selected);
+                cursor.move_to(selected, len, height);
+            }
+        }
+        Ok(())
+    }
+
+    fn open_selected(&mut self) -> anyhow::Result<()> {
+        match self.views.last() {
+            None | Some(View::Blame { .. }) => {
+                if let Some(id) = self.selected_commit() {
+                    self.open_diff(id)?;
+                }
+            }
+            Some(View::Refs { .. }) => {
+                if let Some(id) = self.selected_commit() {
+                    self.open_log(id)?;
+                }
+            }
+            Some(View::Diff { .. }) => self.blame_selected()?,
+            Some(View::Status { .. }) => {}
+        }
+        Ok(())
+    }
+
+    fn blame_selected(&mut self) -> anyhow::Result<()> {
+        let Some(View::Diff { id, lines, cursor }) = self.views.last() else {
+            return Ok(());
+        };
+        let Some(path) = lines.get(cursor.selected).and_then(|line| line.path.clone()) else {
+            return Ok(());
+        };
+        let id = *id;
+        self.open_blame(id, path)
+    }
+
+    /// Make sure everything that is visible in a view of `height` lines is available, traversing the history as needed.
+    fn prepare(&mut self, height: usize) -> anyhow::Result<()> {
+        self.height = height;
+        match self.views.last_mut() {
+            None => {
+                self.log.fill(self.log_cursor.offset + height)?;
+                let selected = self.log_cursor.selected;
+                self.log_cursor.move_to(selected, self.log.rows().len(), height);
+            }
+            Some(view) => {
+                let len = view.len();
+                let cursor = view.cursor_mut();
+                let selected = cursor.selected;
+                cursor.move_to(selected, len, height);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rendering
+impl App {
+    /// Draw the current view into `terminal`, traversing only as much of the history as is needed to fill it.
+    pub fn draw<B: ratatui::backend::Backend>(&mut self, terminal: &mut ratatui::Terminal<B>) -> anyhow::Result<()> {
+        let height = terminal.size()?.height.saturating_sub(1);
+        self.prepare(height.into())?;
+        terminal.draw(|frame| crate::ui::draw(frame, self))?;
+        Ok(())
+    }
+}
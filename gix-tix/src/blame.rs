@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use gix::{
+    bstr::{BStr, BString},
+    ObjectId,
+};
+
+/// A single line of a blamed file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// The commit that introduced the line.
+    pub commit_id: ObjectId,
+    /// The name of the author of `commit_id`.
+    pub author: BString,
+    /// The 1-based line number in the blamed file.
+    pub line_number: u32,
+    /// The text of the line, without trailing newline.
+    pub text: BString,
+}
+
+/// Find the commit that introduced each line of the file at `path` as seen in the commit with `id`.
+///
+/// `path` is a slash-separated path relative to the root of the repository.
+pub fn file(repo: &gix::Repository, id: ObjectId, path: &BStr) -> anyhow::Result<Vec<Line>> {
+    let mut resource_cache = repo.diff_resource_cache_for_tree_diff()?;
+    let outcome = gix::blame::file(
+        &repo.objects,
+        id,
+        repo.commit_graph_if_enabled()?,
+        &mut resource_cache,
+        path,
+        Default::default(),
+    )?;
+
+    let mut authors = HashMap::<ObjectId, BString>::new();
+    let mut out = Vec::new();
+    for (entry, lines) in outcome.entries_with_lines() {
+        let author = match authors.get(&entry.commit_id) {
+            Some(author) => author.clone(),
+            None => {
+                let author: BString = repo.find_commit(entry.commit_id)?.author()?.name.to_owned();
+                authors.insert(entry.commit_id, author.clone());
+                author
+            }
+        };
+        for (line_number, mut text) in entry.range_in_blamed_file().zip(lines) {
+            if text.last() == Some(&b'\n') {
+                text.pop();
+            }
+            out.push(Line {
+                commit_id: entry.commit_id,
+                author: author.clone(),
+                line_number: line_number as u32 + 1,
+                text,
+            });
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,189 @@
+use gix::{
+    bstr::{BStr, BString, ByteSlice},
+    diff::blob::{
+        platform::prepare_diff::Operation,
+        unified_diff::{ConsumeHunk, ContextSize, NewlineSeparator},
+        UnifiedDiff,
+    },
+    object::tree::diff::ChangeDetached,
+    ObjectId,
+};
+
+/// The kind of a [`Line`], useful for highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Information about the commit itself, like its id, author or message.
+    Commit,
+    /// The line introducing the changes of a file.
+    File,
+    /// Additional information about a file, like its mode or whether it was renamed.
+    FileInfo,
+    /// The header of a hunk, like `@@ -1,2 +1,3 @@`.
+    Hunk,
+    /// A line that was added.
+    Addition,
+    /// A line that was removed.
+    Removal,
+    /// An unchanged line around added or removed lines.
+    Context,
+}
+
+/// A single line of the diff of a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// The kind of line.
+    pub kind: LineKind,
+    /// The text to display, without trailing newline.
+    pub text: BString,
+    /// The path of the file the line belongs to, if it isn't about the commit itself.
+    pub path: Option<BString>,
+}
+
+/// Show the commit with `id` like `git show` would, with the diff against its first parent as unified diff.
+pub fn commit(repo: &gix::Repository, id: ObjectId) -> anyhow::Result<Vec<Line>> {
+    let commit = repo.find_commit(id)?;
+    let author = commit.author()?;
+    let mut out = vec![
+        commit_line(format!("commit {id}")),
+        commit_line(format!("Author: {} <{}>", author.name, author.email)),
+        commit_line(format!(
+            "Date:   {}",
+            author.time.format(gix::date::time::format::DEFAULT)
+        )),
+        commit_line(""),
+    ];
+    out.extend(
+        commit
+            .message_raw_sloppy()
+            .trim_end()
+            .lines()
+            .map(|line| commit_line(format!("    {}", line.as_bstr()))),
+    );
+    out.push(commit_line(""));
+
+    let new_tree = commit.tree()?;
+    let old_tree = match commit.parent_ids().next() {
+        Some(parent) => parent.object()?.peel_to_tree()?,
+        None => repo.empty_tree(),
+    };
+    let mut resource_cache = repo.diff_resource_cache_for_tree_diff()?;
+    for change in repo.diff_tree_to_tree(&old_tree, &new_tree, None)? {
+        if change.entry_mode().is_tree() {
+            continue;
+        }
+        file_header(&change, &mut out);
+        resource_cache.set_resource_by_change(change.to_ref(), &repo.objects)?;
+        let prep = resource_cache.prepare_diff()?;
+        match prep.operation {
+            Operation::InternalDiff { algorithm } => {
+                let input = prep.interned_input();
+                let hunks = gix::diff::blob::diff(
+                    algorithm,
+                    &input,
+                    UnifiedDiff::new(
+                        &input,
+                        Hunks {
+                            path: change.location().to_owned(),
+                            lines: Vec::new(),
+                        },
+                        NewlineSeparator::AfterHeaderAndWhenNeeded("\n"),
+                        ContextSize::symmetrical(3),
+                    ),
+                )?;
+                out.extend(hunks);
+            }
+            Operation::SourceOrDestinationIsBinary => {
+                out.push(file_line(LineKind::FileInfo, "Binary files differ", change.location()));
+            }
+            Operation::ExternalCommand { .. } => unreachable!("we don't allow external diff commands"),
+        }
+        resource_cache.clear_resource_cache_keep_allocation();
+    }
+    Ok(out)
+}
+
+fn commit_line(text: impl Into<BString>) -> Line {
+    Line {
+        kind: LineKind::Commit,
+        text: text.into(),
+        path: None,
+    }
+}
+
+fn file_line(kind: LineKind, text: impl Into<BString>, path: &BStr) -> Line {
+    Line {
+        kind,
+        text: text.into(),
+        path: Some(path.to_owned()),
+    }
+}
+
+fn file_header(change: &ChangeDetached, out: &mut Vec<Line>) {
+    let location = change.location();
+    let source_location = change.source_location();
+    out.push(file_line(
+        LineKind::File,
+        format!("diff --git a/{source_location} b/{location}"),
+        location,
+    ));
+    let info = match change {
+        ChangeDetached::Addition { entry_mode, .. } => vec![format!("new file mode {:o}", entry_mode.value())],
+        ChangeDetached::Deletion { entry_mode, .. } => vec![format!("deleted file mode {:o}", entry_mode.value())],
+        ChangeDetached::Modification {
+            previous_entry_mode,
+            entry_mode,
+            ..
+        } if previous_entry_mode != entry_mode => vec![
+            format!("old mode {:o}", previous_entry_mode.value()),
+            format!("new mode {:o}", entry_mode.value()),
+        ],
+        ChangeDetached::Modification { .. } => Vec::new(),
+        ChangeDetached::Rewrite { copy, .. } => {
+            let verb = if *copy { "copy" } else { "rename" };
+            vec![
+                format!("{verb} from {source_location}"),
+                format!("{verb} to {location}"),
+            ]
+        }
+    };
+    out.extend(
+        info.into_iter()
+            .map(|text| file_line(LineKind::FileInfo, text, location)),
+    );
+}
+
+/// Collect the lines of all hunks of a single file.
+struct Hunks {
+    path: BString,
+    lines: Vec<Line>,
+}
+
+impl ConsumeHunk for Hunks {
+    type Out = Vec<Line>;
+
+    fn consume_hunk(
+        &mut self,
+        _before_hunk_start: u32,
+        _before_hunk_len: u32,
+        _after_hunk_start: u32,
+        _after_hunk_len: u32,
+        header: &str,
+        hunk: &[u8],
+    ) -> std::io::Result<()> {
+        self.lines
+            .push(file_line(LineKind::Hunk, header.trim_end(), self.path.as_ref()));
+        for line in hunk.lines() {
+            let kind = match line.first() {
+                Some(b'+') => LineKind::Addition,
+                Some(b'-') => LineKind::Removal,
+                _ => LineKind::Context,
+            };
+            self.lines.push(file_line(kind, line, self.path.as_ref()));
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Self::Out {
+        self.lines
+    }
+}
@@ -0,0 +1,110 @@
+use gix::{hash::oid, ObjectId};
+
+/// Assigns each line of history its own column and draws an ASCII graph with exactly one row per commit.
+///
+/// Commits must be added in topological order, i.e. each commit before its parents, which is what
+/// [`gix::traverse::commit::Topo`] provides. The glyphs of each row are:
+///
+/// * `*` - the commit itself.
+/// * `|` - a line of history that passes by.
+/// * `.` - a line of history that starts here as it leads to another parent of a merge commit.
+/// * `'` - a line of history that ends here as it joins the commit.
+/// * `+` - a line of history that is already known to lead to another parent of a merge commit.
+/// * `-` - connects the commit with the lines that start or end here.
+#[derive(Default, Debug, Clone)]
+pub struct Graph {
+    /// The commit each column leads to next, or `None` if the column is free.
+    columns: Vec<Option<ObjectId>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Commit,
+    Pass,
+    Start,
+    End,
+    Join,
+}
+
+impl Graph {
+    /// Add the commit with `id` and `parents` and return its row.
+    pub fn next_row(&mut self, id: &oid, parents: &[ObjectId]) -> String {
+        let column = match self.column_of(id).or_else(|| self.free_column()) {
+            Some(column) => column,
+            None => {
+                self.columns.push(None);
+                self.columns.len() - 1
+            }
+        };
+        let mut cells: Vec<_> = self
+            .columns
+            .iter()
+            .map(|c| if c.is_some() { Cell::Pass } else { Cell::Empty })
+            .collect();
+        cells[column] = Cell::Commit;
+
+        for (idx, slot) in self.columns.iter_mut().enumerate() {
+            if idx != column && slot.as_deref() == Some(id) {
+                *slot = None;
+                cells[idx] = Cell::End;
+            }
+        }
+        self.columns[column] = parents.first().copied();
+
+        for parent in parents.iter().skip(1) {
+            if let Some(idx) = self.column_of(parent) {
+                if cells[idx] == Cell::Pass {
+                    cells[idx] = Cell::Join;
+                }
+                continue;
+            }
+            let idx =
+                match (0..self.columns.len()).find(|idx| self.columns[*idx].is_none() && cells[*idx] == Cell::Empty) {
+                    Some(idx) => idx,
+                    None => {
+                        self.columns.push(None);
+                        cells.push(Cell::Empty);
+                        self.columns.len() - 1
+                    }
+                };
+            self.columns[idx] = Some(*parent);
+            cells[idx] = Cell::Start;
+        }
+        while self.columns.last().is_some_and(Option::is_none) {
+            self.columns.pop();
+        }
+        render(&cells, column)
+    }
+
+    fn column_of(&self, id: &oid) -> Option<usize> {
+        self.columns.iter().position(|c| c.as_deref() == Some(id))
+    }
+
+    fn free_column(&self) -> Option<usize> {
+        self.columns.iter().position(Option::is_none)
+    }
+}
+
+fn render(cells: &[Cell], column: usize) -> String {
+    let connected = |cell: &Cell| matches!(cell, Cell::Start | Cell::End | Cell::Join);
+    let first = cells.iter().position(connected).map_or(column, |idx| idx.min(column));
+    let last = cells.iter().rposition(connected).map_or(column, |idx| idx.max(column));
+
+    let mut out = String::with_capacity(cells.len() * 2);
+    for (idx, cell) in cells.iter().enumerate() {
+        let within = idx > first && idx < last;
+        out.push(match cell {
+            Cell::Commit => '*',
+            Cell::Pass => '|',
+            Cell::Start => '.',
+            Cell::End => '\'',
+            Cell::Join => '+',
+            Cell::Empty if within => '-',
+            Cell::Empty => ' ',
+        });
+        out.push(if idx >= first && idx < last { '-' } else { ' ' });
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
@@ -1,2 +1,29 @@
-#![deny(rust_2018_idioms)]
+//! A tool like `tig`, but minimal, fast and efficient.
+//!
+//! It shows the history of a repository in topological order along with an ASCII graph, and opens commits into their
+//! diff, files into their blame, and has views for references and the status of the worktree. The history is only
+//! traversed as far as it is visible, which makes it open instantly even in very large repositories.
+//!
+//! * [`app::App`] holds the state of all views and handles key presses independently of the terminal.
+//! * [`terminal::run()`] shows an [`App`](app::App) in the terminal.
+//! * The remaining modules produce the data shown in each view, and can be used on their own.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+///
+pub mod app;
+///
+pub mod blame;
+///
+pub mod diff;
+///
+pub mod graph;
+///
+pub mod log;
+///
+pub mod refs;
+///
+pub mod status;
+///
+pub mod terminal;
+mod ui;
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use gix::{
+    bstr::{BString, ByteSlice},
+    hash::oid,
+    traverse::commit::{topo, Topo},
+    ObjectId,
+};
+
+use crate::graph::Graph;
+
+type Walk = Topo<gix::OdbHandle, fn(&oid) -> bool>;
+
+/// A single commit in the [`Log`].
+#[derive(Debug, Clone)]
+pub struct Row {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The graph leading to the commit, as drawn by [`Graph`].
+    pub graph: String,
+    /// The name of the author.
+    pub author: BString,
+    /// The time at which the commit was authored.
+    pub time: gix::date::Time,
+    /// The first line of the commit message.
+    pub summary: BString,
+    /// The short names of all references pointing to the commit.
+    pub refs: Vec<BString>,
+}
+
+/// The history of a set of tips in topological order, which is only traversed as far as rows are requested.
+///
+/// This makes opening even very large repositories instant as only the visible part of the history is ever traversed.
+pub struct Log {
+    repo: gix::Repository,
+    walk: Option<Walk>,
+    graph: Graph,
+    rows: Vec<Row>,
+    refs_by_id: HashMap<ObjectId, Vec<BString>>,
+}
+
+/// Lifecycle
+impl Log {
+    /// Prepare traversing the history of `tips` in `repo`, without traversing anything yet.
+    pub fn new(repo: &gix::Repository, tips: impl IntoIterator<Item = ObjectId>) -> anyhow::Result<Self> {
+        let walk = topo::Builder::from_iters(repo.objects.clone(), tips, None::<Vec<ObjectId>>)
+            .with_commit_graph(repo.commit_graph_if_enabled()?)
+            .build()?;
+        Ok(Log {
+            repo: repo.clone(),
+            walk: Some(walk),
+            graph: Graph::default(),
+            rows: Vec::new(),
+            refs_by_id: refs_by_id(repo)?,
+        })
+    }
+}
+
+/// Access
+impl Log {
+    /// Return all rows traversed so far.
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Return `true` if the entire history was traversed.
+    pub fn is_complete(&self) -> bool {
+        self.walk.is_none()
+    }
+
+    /// Traverse the history until there are at least `count` rows, or until there is no more history.
+    pub fn fill(&mut self, count: usize) -> anyhow::Result<()> {
+        while self.rows.len() < count {
+            let Some(walk) = self.walk.as_mut() else { break };
+            let Some(info) = walk.next().transpose()? else {
+                self.walk = None;
+                break;
+            };
+            let commit = self.repo.find_commit(info.id)?;
+            let author = commit.author()?;
+            let (author, time) = (author.name.to_owned(), author.time);
+            let summary = commit
+                .message_raw_sloppy()
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or_default()
+                .into();
+            self.rows.push(Row {
+                id: info.id,
+                graph: self.graph.next_row(&info.id, &info.parent_ids),
+                author,
+                time,
+                summary,
+                refs: self.refs_by_id.get(&info.id).cloned().unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn refs_by_id(repo: &gix::Repository) -> anyhow::Result<HashMap<ObjectId, Vec<BString>>> {
+    let mut out = HashMap::<_, Vec<_>>::new();
+    for reference in repo.references()?.all()? {
+        let Ok(mut reference) = reference else { continue };
+        let Ok(id) = reference.peel_to_id_in_place() else {
+            continue;
+        };
+        out.entry(id.detach())
+            .or_default()
+            .push(reference.name().shorten().to_owned());
+    }
+    Ok(out)
+}
@@ -0,0 +1,75 @@
+#![deny(rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use anyhow::{bail, Context};
+use gix::bstr::BString;
+use gix_tix::app::App;
+
+const USAGE: &str = "\
+Usage: tix [<revision>...]
+       tix blame <path> [<revision>]
+       tix refs
+       tix status
+
+Keys: j/k or arrows to move, space/page-down and page-up to page, g/G for the first and last line,
+      enter to open, b to blame the file of a diff, r for refs, s for status, l for the log, q to go back.";
+
+fn main() -> anyhow::Result<()> {
+    let mut args: Vec<_> = std::env::args_os().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    let repo = gix::discover(".")?;
+    let command = args.first().and_then(|arg| arg.to_str()).map(ToOwned::to_owned);
+    let command = match command.as_deref() {
+        Some(command @ ("blame" | "refs" | "status")) => {
+            args.remove(0);
+            Some(command.to_owned())
+        }
+        _ => None,
+    };
+    let blame_path = match command.as_deref() {
+        Some("blame") => {
+            if args.is_empty() {
+                bail!("{USAGE}");
+            }
+            Some(gix::path::os_string_into_bstring(args.remove(0))?)
+        }
+        _ => None,
+    };
+
+    let tips = args
+        .into_iter()
+        .map(|spec| -> anyhow::Result<_> {
+            let spec = gix::path::os_string_into_bstring(spec)?;
+            Ok(repo
+                .rev_parse_single(spec.as_slice())?
+                .object()?
+                .peel_to_commit()
+                .with_context(|| format!("'{spec}' doesn't point to a commit"))?
+                .id)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut app = App::new(repo.clone(), tips.clone())?;
+    match (command.as_deref(), blame_path) {
+        (Some("blame"), Some(path)) => {
+            let id = match tips.first() {
+                Some(id) => *id,
+                None => repo.head_id()?.detach(),
+            };
+            app.open_blame(id, repo_relative(&repo, path)?)?;
+        }
+        (Some("refs"), _) => app.open_refs()?,
+        (Some("status"), _) => app.open_status()?,
+        _ => {}
+    }
+    gix_tix::terminal::run(&mut app)
+}
+
+/// Turn `path` relative to the current directory into a path relative to the root of the repository.
+fn repo_relative(repo: &gix::Repository, path: BString) -> anyhow::Result<BString> {
+    let prefix = repo.prefix()?.unwrap_or_else(|| std::path::Path::new(""));
+    let path = prefix.join(gix::path::from_bstr(path));
+    Ok(gix::path::to_unix_separators_on_windows(gix::path::into_bstr(path)).into_owned())
+}
@@ -0,0 +1,44 @@
+use gix::{bstr::BString, ObjectId};
+
+/// A reference that points to a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ref {
+    /// The full name of the reference, like `refs/heads/main`.
+    pub name: gix::refs::FullName,
+    /// The commit the reference ultimately points to, after peeling tags.
+    pub id: ObjectId,
+    /// The first line of the message of the commit.
+    pub summary: BString,
+}
+
+impl Ref {
+    /// Return `true` if this is the branch that `HEAD` points to.
+    pub fn is_head(&self, head: Option<&gix::refs::FullNameRef>) -> bool {
+        head.is_some_and(|head| head == self.name.as_ref())
+    }
+}
+
+/// Return all local branches, remote-tracking branches and tags of `repo` that point to commits, in this order.
+pub fn list(repo: &gix::Repository) -> anyhow::Result<Vec<Ref>> {
+    let mut out = Vec::new();
+    let platform = repo.references()?;
+    for references in [
+        platform.local_branches()?,
+        platform.remote_branches()?,
+        platform.tags()?,
+    ] {
+        for reference in references {
+            let Ok(mut reference) = reference else { continue };
+            let Ok(commit) = reference.peel_to_commit() else {
+                continue;
+            };
+            let summary = commit.message()?.summary().into_owned();
+            out.push(Ref {
+                name: reference.name().to_owned(),
+                id: commit.id,
+                summary,
+            });
+        }
+    }
+    Ok(out)
+}
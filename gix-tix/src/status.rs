@@ -0,0 +1,81 @@
+use gix::{
+    bstr::BString,
+    status::{index_worktree, plumbing::index_as_worktree_with_renames::Summary},
+};
+
+/// Where a change was observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// The change is between `HEAD` and the index, and would be committed.
+    Staged,
+    /// The change is between the index and the worktree, and includes untracked files.
+    Unstaged,
+}
+
+/// A changed path in the worktree or the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Where the change was observed.
+    pub stage: Stage,
+    /// A single character to indicate the kind of change, like `M` for modifications or `?` for untracked files.
+    pub status: char,
+    /// The slash-separated path relative to the root of the repository.
+    pub path: BString,
+}
+
+/// Return all staged changes followed by all unstaged ones, each sorted by path.
+pub fn entries(repo: &gix::Repository) -> anyhow::Result<Vec<Entry>> {
+    let mut out = Vec::new();
+    for item in repo
+        .status(gix::progress::Discard)?
+        .index_worktree_options_mut(|opts| {
+            opts.sorting = Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive);
+        })
+        .into_iter(None)?
+    {
+        let item = item?;
+        let (stage, status) = match &item {
+            gix::status::Item::TreeIndex(change) => (
+                Stage::Staged,
+                match change {
+                    gix::diff::index::Change::Addition { .. } => 'A',
+                    gix::diff::index::Change::Deletion { .. } => 'D',
+                    gix::diff::index::Change::Modification { .. } => 'M',
+                    gix::diff::index::Change::Rewrite { .. } => 'R',
+                },
+            ),
+            gix::status::Item::IndexWorktree(item) => {
+                let Some(summary) = item.summary() else { continue };
+                if matches!(
+                    item,
+                    index_worktree::Item::DirectoryContents {
+                        collapsed_directory_status: Some(_),
+                        ..
+                    }
+                ) {
+                    continue;
+                }
+                (
+                    Stage::Unstaged,
+                    match summary {
+                        Summary::Removed => 'D',
+                        Summary::Added => '?',
+                        Summary::Modified => 'M',
+                        Summary::TypeChange => 'T',
+                        Summary::Renamed => 'R',
+                        Summary::Copied => 'C',
+                        Summary::IntentToAdd => 'A',
+                        Summary::Conflict => 'U',
+                    },
+                )
+            }
+        };
+        out.push(Entry {
+            stage,
+            status,
+            path: item.location().to_owned(),
+        });
+    }
+    out.sort_by(|a, b| (a.stage == Stage::Unstaged, &a.path).cmp(&(b.stage == Stage::Unstaged, &b.path)));
+    Ok(out)
+}
@@ -0,0 +1,210 @@
+use gix::bstr::{BStr, ByteSlice};
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{
+    app::{App, Cursor, View},
+    diff, status,
+};
+
+/// Draw `app` into `frame`, with the current view on top of a single status line.
+pub(crate) fn draw(frame: &mut Frame<'_>, app: &App) {
+    let [main, status_line] = {
+        let areas = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(frame.size());
+        [areas[0], areas[1]]
+    };
+    let height = main.height as usize;
+
+    let (lines, position) = match app.views.last() {
+        None => {
+            let rows = app.log.rows();
+            let cursor = app.log_cursor;
+            let lines: Vec<_> = visible(rows, cursor, height)
+                .map(|(row, selected)| {
+                    let mut spans = vec![
+                        Span::styled(format!("{:<8} ", row.graph), Style::new().fg(Color::Magenta)),
+                        Span::styled(
+                            format!("{} ", row.id.to_hex_with_len(7)),
+                            Style::new().fg(Color::Yellow),
+                        ),
+                        Span::styled(
+                            format!("{} ", row.time.format(gix::date::time::format::SHORT)),
+                            Style::new().fg(Color::Blue),
+                        ),
+                        Span::styled(
+                            format!("{:<16} ", truncate(row.author.as_ref(), 16)),
+                            Style::new().fg(Color::Green),
+                        ),
+                    ];
+                    if !row.refs.is_empty() {
+                        let refs: Vec<_> = row.refs.iter().map(|name| name.to_str_lossy()).collect();
+                        spans.push(Span::styled(
+                            format!("[{}] ", refs.join(", ")),
+                            Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                        ));
+                    }
+                    spans.push(Span::raw(text(row.summary.as_ref())));
+                    highlight(Line::from(spans), selected)
+                })
+                .collect();
+            let total = if app.log.is_complete() {
+                rows.len().to_string()
+            } else {
+                format!("{}+", rows.len())
+            };
+            let position = if rows.is_empty() {
+                "[log] no commits".to_owned()
+            } else {
+                format!("[log] commit {} of {total}", cursor.selected + 1)
+            };
+            (lines, position)
+        }
+        Some(view) => {
+            let (lines, cursor, len) = match view {
+                View::Diff { lines, cursor, .. } => (
+                    visible(lines, *cursor, height)
+                        .map(|(line, selected)| {
+                            let style = match line.kind {
+                                diff::LineKind::Commit => Style::new(),
+                                diff::LineKind::File => Style::new().add_modifier(Modifier::BOLD),
+                                diff::LineKind::FileInfo => Style::new().fg(Color::Yellow),
+                                diff::LineKind::Hunk => Style::new().fg(Color::Magenta),
+                                diff::LineKind::Addition => Style::new().fg(Color::Green),
+                                diff::LineKind::Removal => Style::new().fg(Color::Red),
+                                diff::LineKind::Context => Style::new(),
+                            };
+                            highlight(Line::styled(text(line.text.as_ref()), style), selected)
+                        })
+                        .collect(),
+                    *cursor,
+                    lines.len(),
+                ),
+                View::Blame { lines, cursor, .. } => (
+                    visible(lines, *cursor, height)
+                        .map(|(line, selected)| {
+                            highlight(
+                                Line::from(vec![
+                                    Span::styled(
+                                        format!("{} ", line.commit_id.to_hex_with_len(7)),
+                                        Style::new().fg(Color::Yellow),
+                                    ),
+                                    Span::styled(
+                                        format!("{:<16} ", truncate(line.author.as_ref(), 16)),
+                                        Style::new().fg(Color::Green),
+                                    ),
+                                    Span::styled(format!("{:>5} ", line.line_number), Style::new().fg(Color::Blue)),
+                                    Span::raw(text(line.text.as_ref())),
+                                ]),
+                                selected,
+                            )
+                        })
+                        .collect(),
+                    *cursor,
+                    lines.len(),
+                ),
+                View::Refs { refs, cursor } => {
+                    let head = app.repo.head_name().ok().flatten();
+                    (
+                        visible(refs, *cursor, height)
+                            .map(|(r, selected)| {
+                                let marker = if r.is_head(head.as_ref().map(AsRef::as_ref)) {
+                                    "* "
+                                } else {
+                                    "  "
+                                };
+                                highlight(
+                                    Line::from(vec![
+                                        Span::raw(marker),
+                                        Span::styled(
+                                            format!("{:<32} ", r.name.shorten().to_str_lossy()),
+                                            Style::new().fg(Color::Cyan),
+                                        ),
+                                        Span::styled(
+                                            format!("{} ", r.id.to_hex_with_len(7)),
+                                            Style::new().fg(Color::Yellow),
+                                        ),
+                                        Span::raw(text(r.summary.as_ref())),
+                                    ]),
+                                    selected,
+                                )
+                            })
+                            .collect(),
+                        *cursor,
+                        refs.len(),
+                    )
+                }
+                View::Status { entries, cursor } => (
+                    visible(entries, *cursor, height)
+                        .map(|(entry, selected)| {
+                            let style = match entry.stage {
+                                status::Stage::Staged => Style::new().fg(Color::Green),
+                                status::Stage::Unstaged => Style::new().fg(Color::Red),
+                            };
+                            highlight(
+                                Line::from(vec![
+                                    Span::styled(format!("{} ", entry.status), style),
+                                    Span::raw(text(entry.path.as_ref())),
+                                ]),
+                                selected,
+                            )
+                        })
+                        .collect(),
+                    *cursor,
+                    entries.len(),
+                ),
+            };
+            let position = match len {
+                0 => format!("[{}] empty", view.name()),
+                len => format!("[{}] line {} of {len}", view.name(), cursor.selected + 1),
+            };
+            (lines, position)
+        }
+    };
+
+    frame.render_widget(Paragraph::new(lines), main);
+    let status = app.message.clone().unwrap_or(position);
+    frame.render_widget(
+        Paragraph::new(Line::styled(status, Style::new().add_modifier(Modifier::REVERSED))),
+        status_line,
+    );
+}
+
+/// Return the items of `items` that are visible with `cursor` in a view of `height`, along with whether they are selected.
+fn visible<T>(items: &[T], cursor: Cursor, height: usize) -> impl Iterator<Item = (&T, bool)> {
+    items
+        .iter()
+        .enumerate()
+        .skip(cursor.offset)
+        .take(height)
+        .map(move |(idx, item)| (item, idx == cursor.selected))
+}
+
+fn highlight(line: Line<'_>, selected: bool) -> Line<'_> {
+    if selected {
+        line.patch_style(Style::new().add_modifier(Modifier::REVERSED))
+    } else {
+        line
+    }
+}
+
+/// Turn `input` into displayable text, with tabs expanded and other control characters replaced.
+fn text(input: &BStr) -> String {
+    input
+        .to_str_lossy()
+        .chars()
+        .flat_map(|c| match c {
+            '\t' => vec![' '; 4],
+            c if c.is_control() => vec!['?'],
+            c => vec![c],
+        })
+        .collect()
+}
+
+fn truncate(input: &BStr, width: usize) -> String {
+    text(input).chars().take(width).collect()
+}
@@ -0,0 +1,130 @@
+use gix_tix::app::{App, Key, View};
+use ratatui::{backend::TestBackend, Terminal};
+
+use crate::id;
+
+fn app() -> crate::Result<(App, Terminal<TestBackend>)> {
+    let repo = crate::repo()?;
+    let app = App::new(repo, Vec::new())?;
+    Ok((app, Terminal::new(TestBackend::new(80, 4))?))
+}
+
+fn render(app: &mut App, terminal: &mut Terminal<TestBackend>) -> crate::Result<Vec<String>> {
+    app.draw(terminal)?;
+    let buffer = terminal.backend().buffer();
+    Ok((0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol())
+                .collect::<String>()
+                .trim_end()
+                .to_owned()
+        })
+        .collect())
+}
+
+#[test]
+fn log_only_traverses_what_is_visible() -> crate::Result {
+    let (mut app, mut terminal) = app()?;
+    let screen = render(&mut app, &mut terminal)?;
+    assert_eq!(app.log().rows().len(), 3, "one line is used for the status");
+    assert!(!app.log().is_complete());
+    assert_eq!(
+        screen,
+        [
+            "*-.      9894cf4 2000-01-01 author           [main, v1.0] Merge branch 'feature'",
+            "* |      2425019 2000-01-01 author           change file",
+            "| *      94467ba 2000-01-01 author           [feature] add feature",
+            "[log] commit 1 of 3+",
+        ]
+    );
+
+    app.handle_key(Key::End);
+    let screen = render(&mut app, &mut terminal)?;
+    assert!(app.log().is_complete());
+    assert_eq!(screen[2], "*-'      e985753 2000-01-01 author           initial");
+    assert_eq!(screen[3], "[log] commit 4 of 4");
+    Ok(())
+}
+
+#[test]
+fn commits_open_into_diffs_and_files_into_blames() -> crate::Result {
+    let (mut app, mut terminal) = app()?;
+    app.handle_key(Key::Down);
+    app.handle_key(Key::Enter);
+    assert_eq!(app.view().map(View::name), Some("diff"));
+    assert_eq!(app.selected_commit(), Some(id(&crate::repo()?, "main~1")?));
+
+    for _ in 0..6 {
+        app.handle_key(Key::Down);
+    }
+    let screen = render(&mut app, &mut terminal)?;
+    assert_eq!(screen[3], "[diff] line 7 of 12");
+
+    app.handle_key(Key::Char('b'));
+    assert_eq!(app.view().map(View::name), Some("blame"));
+    let screen = render(&mut app, &mut terminal)?;
+    assert_eq!(
+        screen,
+        [
+            "e985753 author               1 one",
+            "2425019 author               2 2",
+            "e985753 author               3 three",
+            "[blame] line 1 of 3",
+        ]
+    );
+
+    app.handle_key(Key::Enter);
+    assert_eq!(
+        app.view().map(View::name),
+        Some("diff"),
+        "blamed lines open their commit"
+    );
+    app.handle_key(Key::Back);
+    app.handle_key(Key::Back);
+    app.handle_key(Key::Back);
+    assert!(app.view().is_none());
+    assert!(!app.should_quit());
+    app.handle_key(Key::Char('q'));
+    assert!(app.should_quit(), "going back from the log quits");
+    Ok(())
+}
+
+#[test]
+fn refs_open_into_the_log() -> crate::Result {
+    let (mut app, mut terminal) = app()?;
+    app.handle_key(Key::Char('r'));
+    let screen = render(&mut app, &mut terminal)?;
+    assert_eq!(
+        screen,
+        [
+            "  feature                          94467ba add feature",
+            "* main                             9894cf4 Merge branch 'feature'",
+            "  v1.0                             9894cf4 Merge branch 'feature'",
+            "[refs] line 1 of 3",
+        ]
+    );
+
+    app.handle_key(Key::Enter);
+    assert!(app.view().is_none(), "the log replaces all views");
+    let screen = render(&mut app, &mut terminal)?;
+    assert_eq!(
+        screen,
+        [
+            "*        94467ba 2000-01-01 author           [feature] add feature",
+            "*        e985753 2000-01-01 author           initial",
+            "",
+            "[log] commit 1 of 2",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn status_shows_staged_and_unstaged_changes() -> crate::Result {
+    let (mut app, mut terminal) = app()?;
+    app.handle_key(Key::Char('s'));
+    let screen = render(&mut app, &mut terminal)?;
+    assert_eq!(screen, ["A staged", "M file", "? untracked", "[status] line 1 of 3"]);
+    Ok(())
+}
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+printf 'one\ntwo\nthree\n' > file
+git add file
+git commit -q -m "initial"
+
+git checkout -q -b feature
+echo "feature" > feature
+git add feature
+git commit -q -m "add feature"
+
+git checkout -q main
+printf 'one\n2\nthree\n' > file
+git commit -q -am "change file"
+
+git merge -q --no-ff -m "Merge branch 'feature'" feature
+git tag -m "the first release" v1.0
+
+echo "four" >> file
+echo "staged" > staged
+git add staged
+echo "untracked" > untracked
@@ -0,0 +1,46 @@
+use gix::ObjectId;
+use gix_tix::graph::Graph;
+
+fn id(n: u8) -> ObjectId {
+    ObjectId::from_bytes_or_panic(&[n; 20])
+}
+
+fn rows(commits: &[(u8, &[u8])]) -> Vec<String> {
+    let mut graph = Graph::default();
+    commits
+        .iter()
+        .map(|(commit, parents)| {
+            let parents: Vec<_> = parents.iter().copied().map(id).collect();
+            graph.next_row(&id(*commit), &parents)
+        })
+        .collect()
+}
+
+#[test]
+fn linear_history_is_a_single_column() {
+    assert_eq!(rows(&[(3, &[2]), (2, &[1]), (1, &[])]), ["*", "*", "*"]);
+}
+
+#[test]
+fn merges_open_a_column_which_ends_where_it_forks() {
+    assert_eq!(
+        rows(&[(4, &[2, 3]), (2, &[1]), (3, &[1]), (1, &[])]),
+        ["*-.", "* |", "| *", "*-'"]
+    );
+}
+
+#[test]
+fn unrelated_tips_get_their_own_column_and_all_columns_end_together() {
+    assert_eq!(
+        rows(&[(4, &[2, 1]), (3, &[1]), (2, &[1]), (1, &[])]),
+        ["*-.", "| | *", "* | |", "*-'-'"]
+    );
+}
+
+#[test]
+fn merge_parents_that_are_already_shown_are_joined() {
+    assert_eq!(
+        rows(&[(4, &[1]), (3, &[2, 1]), (2, &[1]), (1, &[])]),
+        ["*", "+-*", "| *", "*-'"]
+    );
+}
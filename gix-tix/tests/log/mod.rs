@@ -0,0 +1,37 @@
+use gix_tix::log::Log;
+
+use crate::id;
+
+#[test]
+fn history_is_traversed_only_as_far_as_needed() -> crate::Result {
+    let repo = crate::repo()?;
+    let mut log = Log::new(&repo, Some(id(&repo, "main")?))?;
+    assert!(log.rows().is_empty(), "nothing is traversed on creation");
+
+    log.fill(2)?;
+    assert_eq!(log.rows().len(), 2);
+    assert!(!log.is_complete());
+
+    log.fill(100)?;
+    assert!(log.is_complete());
+    let rows: Vec<_> = log
+        .rows()
+        .iter()
+        .map(|row| (row.graph.as_str(), row.summary.to_string(), row.refs.clone()))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (
+                "*-.",
+                "Merge branch 'feature'".into(),
+                vec!["main".into(), "v1.0".into()]
+            ),
+            ("* |", "change file".into(), vec![]),
+            ("| *", "add feature".into(), vec!["feature".into()]),
+            ("*-'", "initial".into(), vec![]),
+        ]
+    );
+    assert_eq!(log.rows()[0].author, "author");
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use gix::ObjectId;
+
+pub use gix_testtools::Result;
+
+pub fn repo() -> Result<gix::Repository> {
+    let dir = gix_testtools::scripted_fixture_read_only("make_history.sh")?;
+    Ok(gix::open_opts(dir, gix::open::Options::isolated())?)
+}
+
+pub fn id(repo: &gix::Repository, spec: &str) -> Result<ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+mod app;
+mod graph;
+mod log;
+mod views;
@@ -0,0 +1,109 @@
+use gix_tix::{diff::LineKind, status::Stage};
+
+use crate::id;
+
+#[test]
+fn diff_of_a_commit_shows_the_message_and_all_hunks() -> crate::Result {
+    let repo = crate::repo()?;
+    let commit = id(&repo, "main~1")?;
+    let lines = gix_tix::diff::commit(&repo, commit)?;
+    let lines: Vec<_> = lines.iter().map(|line| (line.kind, line.text.to_string())).collect();
+    assert_eq!(
+        lines,
+        [
+            (LineKind::Commit, format!("commit {commit}")),
+            (LineKind::Commit, "Author: author <author@example.com>".into()),
+            (LineKind::Commit, "Date:   Sat Jan 1 00:00:00 2000 +0000".into()),
+            (LineKind::Commit, "".into()),
+            (LineKind::Commit, "    change file".into()),
+            (LineKind::Commit, "".into()),
+            (LineKind::File, "diff --git a/file b/file".into()),
+            (LineKind::Hunk, "@@ -1,3 +1,3 @@".into()),
+            (LineKind::Context, " one".into()),
+            (LineKind::Removal, "-two".into()),
+            (LineKind::Addition, "+2".into()),
+            (LineKind::Context, " three".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn diff_of_a_root_commit_shows_added_files() -> crate::Result {
+    let repo = crate::repo()?;
+    let lines = gix_tix::diff::commit(&repo, id(&repo, "main~2")?)?;
+    let file_lines: Vec<_> = lines
+        .iter()
+        .filter(|line| line.path.is_some())
+        .map(|line| (line.kind, line.text.to_string()))
+        .collect();
+    assert_eq!(
+        file_lines,
+        [
+            (LineKind::File, "diff --git a/file b/file".into()),
+            (LineKind::FileInfo, "new file mode 100644".into()),
+            (LineKind::Hunk, "@@ -1,0 +1,3 @@".into()),
+            (LineKind::Addition, "+one".into()),
+            (LineKind::Addition, "+two".into()),
+            (LineKind::Addition, "+three".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn blame_attributes_each_line_to_a_commit() -> crate::Result {
+    let repo = crate::repo()?;
+    let lines = gix_tix::blame::file(&repo, id(&repo, "main")?, "file".into())?;
+    let lines: Vec<_> = lines
+        .iter()
+        .map(|line| (line.commit_id, line.line_number, line.text.to_string()))
+        .collect();
+    let (initial, change) = (id(&repo, "main~2")?, id(&repo, "main~1")?);
+    assert_eq!(
+        lines,
+        [
+            (initial, 1, "one".into()),
+            (change, 2, "2".into()),
+            (initial, 3, "three".into())
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn refs_are_listed_by_category_and_peeled_to_commits() -> crate::Result {
+    let repo = crate::repo()?;
+    let refs: Vec<_> = gix_tix::refs::list(&repo)?
+        .into_iter()
+        .map(|r| (r.name.as_bstr().to_string(), r.id, r.summary.to_string()))
+        .collect();
+    let (main, feature) = (id(&repo, "main")?, id(&repo, "feature")?);
+    assert_eq!(
+        refs,
+        [
+            ("refs/heads/feature".into(), feature, "add feature".into()),
+            ("refs/heads/main".into(), main, "Merge branch 'feature'".into()),
+            ("refs/tags/v1.0".into(), main, "Merge branch 'feature'".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn status_lists_staged_changes_first() -> crate::Result {
+    let repo = crate::repo()?;
+    let entries: Vec<_> = gix_tix::status::entries(&repo)?
+        .into_iter()
+        .map(|entry| (entry.stage, entry.status, entry.path.to_string()))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (Stage::Staged, 'A', "staged".into()),
+            (Stage::Unstaged, 'M', "file".into()),
+            (Stage::Unstaged, '?', "untracked".into()),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    error::Error,
+    fs,
+    io::{self, BufReader, Cursor, Read},
+    sync::{Arc, Mutex},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_packetline::Channel;
+
+use crate::{
+    client::{self, git, MessageKind, RequestWriter, SetServiceResponse, WriteMode},
+    Protocol, Service,
+};
+
+/// The amount of pack bytes to send per side-band line, which is the maximum data length minus the band byte.
+const MAX_BAND_DATA_LEN: usize = 65515;
+
+/// A transport that serves the references and the pack of a bundle file as if it was a remote repository.
+///
+/// It speaks protocol V1 with an in-process [`Server`], which advertises the references of the bundle and
+/// sends its pack once the client is done negotiating, no matter what the client says it has.
+/// Pushing into bundles isn't possible.
+pub struct Connection {
+    inner: git::Connection<Server, Server>,
+}
+
+/// The in-process counterpart of a [`Connection`], which answers requests with the references and the pack of a bundle.
+#[derive(Clone)]
+pub struct Server {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    bundle: gix_bundle::File,
+    /// Data the client is yet to read.
+    response: Cursor<Vec<u8>>,
+    /// The pack to send on the data channel once the client is done, until it is exhausted.
+    pack: Option<BufReader<fs::File>>,
+    /// Bytes written by the client that don't form a complete packet line yet.
+    request: Vec<u8>,
+    /// If `true`, the lines since the last flush contained the wants of the client, which aren't acknowledged.
+    in_wants: bool,
+}
+
+/// Open the bundle at `path` to serve it like a remote repository.
+///
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+pub fn connect(path: impl Into<BString>, trace: bool) -> Result<Connection, gix_bundle::file::Error> {
+    let path = path.into();
+    let bundle = gix_bundle::File::at(path.to_os_str_lossy().into_owned())?;
+    let server = Server {
+        state: Arc::new(Mutex::new(State {
+            response: Cursor::new(advertisement(&bundle.header)),
+            bundle,
+            pack: None,
+            request: Vec::new(),
+            in_wants: false,
+        })),
+    };
+    Ok(Connection {
+        inner: git::Connection::new_for_spawned_process(server.clone(), server, Protocol::V1, path, trace),
+    })
+}
+
+/// Produce the V1 reference advertisement for `header`, guessing the branch `HEAD` points to like `git` does.
+fn advertisement(header: &gix_bundle::Header) -> Vec<u8> {
+    let object_format = match header.object_hash {
+        gix_hash::Kind::Sha1 => "sha1",
+        gix_hash::Kind::Sha256 => "sha256",
+    };
+    let mut capabilities = format!("multi_ack_detailed side-band-64k include-tag object-format={object_format}");
+    if let Some(head) = header.refs.iter().find(|r| r.name == "HEAD") {
+        let branches = || {
+            header
+                .refs
+                .iter()
+                .filter(|r| r.id == head.id && r.name.starts_with(b"refs/heads/"))
+        };
+        if let Some(branch) = branches()
+            .find(|r| r.name == "refs/heads/main" || r.name == "refs/heads/master")
+            .or_else(|| branches().next())
+        {
+            capabilities.push_str(" symref=HEAD:");
+            capabilities.push_str(&branch.name.to_str_lossy());
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut lines = header
+        .refs
+        .iter()
+        .map(|r| (r.id.to_string(), r.name.as_bstr()))
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        lines.push((
+            header.object_hash.null().to_string(),
+            "capabilities^{}".as_bytes().as_bstr(),
+        ));
+    }
+    for (idx, (id, name)) in lines.into_iter().enumerate() {
+        let mut line = BString::from(format!("{id} "));
+        line.extend_from_slice(name);
+        if idx == 0 {
+            line.push(0);
+            line.extend_from_slice(capabilities.as_bytes());
+        }
+        line.push(b'\n');
+        gix_packetline::encode::data_to_write(&line, &mut out).expect("writing to a vec never fails");
+    }
+    gix_packetline::encode::flush_to_write(&mut out).expect("writing to a vec never fails");
+    out
+}
+
+impl State {
+    /// Handle all complete packet lines the client sent so far.
+    fn process_request(&mut self) -> io::Result<()> {
+        let mut consumed = 0;
+        while let Some(header) = self.request.get(consumed..consumed + 4) {
+            let len = std::str::from_utf8(header)
+                .ok()
+                .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid packet line header"))?;
+            match len {
+                0 => {
+                    if !std::mem::take(&mut self.in_wants) {
+                        self.respond(b"NAK\n")?;
+                    }
+                    consumed += 4;
+                }
+                1 | 2 => consumed += 4,
+                3 => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid packet line length")),
+                _ => {
+                    let Some(line) = self.request.get(consumed + 4..consumed + len) else {
+                        break;
+                    };
+                    let line = line.trim_end();
+                    if line.starts_with(b"want ") {
+                        self.in_wants = true;
+                    } else if line == b"done" {
+                        self.respond(b"NAK\n")?;
+                        self.pack = Some(self.bundle.pack()?);
+                    }
+                    consumed += len;
+                }
+            }
+        }
+        self.request.drain(..consumed);
+        Ok(())
+    }
+
+    /// Queue `line` to be read by the client, after everything that is still unread.
+    fn respond(&mut self, line: &[u8]) -> io::Result<()> {
+        let position = self.response.position() as usize;
+        let mut unread = self.response.get_ref()[position..].to_vec();
+        gix_packetline::encode::data_to_write(line, &mut unread)?;
+        self.response = Cursor::new(unread);
+        Ok(())
+    }
+}
+
+impl Read for Server {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "poisoned"))?;
+        loop {
+            let read = state.response.read(buf)?;
+            if read != 0 {
+                return Ok(read);
+            }
+            let Some(pack) = state.pack.as_mut() else {
+                return Ok(0);
+            };
+            let mut chunk = vec![0; MAX_BAND_DATA_LEN];
+            let pack_bytes = pack.read(&mut chunk)?;
+            let mut out = Vec::new();
+            if pack_bytes == 0 {
+                gix_packetline::encode::flush_to_write(&mut out)?;
+                state.pack = None;
+            } else {
+                gix_packetline::encode::band_to_write(Channel::Data, &chunk[..pack_bytes], &mut out)?;
+            }
+            state.response = Cursor::new(out);
+        }
+    }
+}
+
+impl io::Write for Server {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "poisoned"))?;
+        state.request.extend_from_slice(buf);
+        state.process_request()?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl client::TransportWithoutIO for Connection {
+    fn request(
+        &mut self,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        self.inner.request(write_mode, on_into_read, trace)
+    }
+
+    fn to_url(&self) -> Cow<'_, BStr> {
+        self.inner.to_url()
+    }
+
+    fn supported_protocol_versions(&self) -> &[Protocol] {
+        &[Protocol::V1]
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        true
+    }
+
+    fn configure(&mut self, _config: &dyn Any) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+}
+
+impl client::Transport for Connection {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if service != Service::UploadPack {
+            return Err(client::Error::Io(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("bundles can only be fetched from, but {service:?} was requested"),
+            )));
+        }
+        self.inner.handshake(service, extra_parameters)
+    }
+}
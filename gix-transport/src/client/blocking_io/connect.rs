@@ -7,6 +7,7 @@ pub(crate) mod function {
     ///
     /// This includes connections to
     /// [local repositories][crate::client::file::connect()],
+    /// [bundle files][crate::client::bundle::connect()],
     /// [repositories over ssh][crate::client::ssh::connect()],
     /// [git daemons][crate::client::git::connect()],
     /// and if compiled in connections to [git repositories over https][crate::client::http::connect()].
@@ -27,10 +28,17 @@ pub(crate) mod function {
                         scheme: url.scheme,
                     });
                 }
-                Box::new(
-                    crate::client::blocking_io::file::connect(url.path, options.version, options.trace)
-                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
-                )
+                if gix_bundle::is_bundle(bstr::ByteSlice::to_os_str_lossy(url.path.as_slice())) {
+                    Box::new(
+                        crate::client::blocking_io::bundle::connect(url.path, options.trace)
+                            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+                    )
+                } else {
+                    Box::new(
+                        crate::client::blocking_io::file::connect(url.path, options.version, options.trace)
+                            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+                    )
+                }
             }
             gix_url::Scheme::Ssh => Box::new({
                 crate::client::blocking_io::ssh::connect(url, options.version, options.ssh, options.trace)
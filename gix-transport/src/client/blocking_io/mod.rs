@@ -1,4 +1,6 @@
 ///
+pub mod bundle;
+///
 pub mod connect;
 
 ///
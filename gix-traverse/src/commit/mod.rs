@@ -11,6 +11,7 @@ use smallvec::SmallVec;
 pub struct Simple<Find, Predicate> {
     objects: Find,
     cache: Option<gix_commitgraph::Graph>,
+    changed_path: Option<gix_object::bstr::BString>,
     predicate: Predicate,
     state: simple::State,
     parents: Parents,
@@ -94,7 +94,7 @@ pub(super) struct State {
 mod init {
     use gix_date::SecondsSinceUnixEpoch;
     use gix_hash::{oid, ObjectId};
-    use gix_object::{CommitRefIter, FindExt};
+    use gix_object::{bstr::BString, CommitRefIter, FindExt};
     use std::cmp::Reverse;
     use Err as Oldest;
     use Ok as Newest;
@@ -185,6 +185,17 @@ mod init {
             self
         }
 
+        /// Only return commits that may have changed `path` compared to their first parent, as determined by the changed-path
+        /// Bloom filters of the [commit-graph](Self::commit_graph()), but keep traversing through all other commits.
+        ///
+        /// As Bloom filters may yield false positives and commits without a filter are always returned, it's up to the caller
+        /// to verify that returned commits actually changed `path`, for instance with a tree-diff.
+        /// Without a commit-graph, all commits are returned.
+        pub fn changed_path(mut self, path: Option<BString>) -> Self {
+            self.changed_path = path;
+            self
+        }
+
         fn queue_to_vecdeque(&mut self) {
             let state = &mut self.state;
             state.next.extend(
@@ -249,6 +260,7 @@ mod init {
             Self {
                 objects: find,
                 cache: None,
+                changed_path: None,
                 predicate,
                 state,
                 parents: Default::default(),
@@ -278,18 +290,38 @@ mod init {
         type Item = Result<Info, Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            if matches!(self.parents, Parents::First) {
-                self.next_by_topology()
-            } else {
-                match self.sorting {
-                    Sorting::BreadthFirst => self.next_by_topology(),
-                    Sorting::ByCommitTime(order) => self.next_by_commit_date(order, None),
-                    Sorting::ByCommitTimeCutoff { seconds, order } => self.next_by_commit_date(order, seconds.into()),
+            loop {
+                let item = if matches!(self.parents, Parents::First) {
+                    self.next_by_topology()
+                } else {
+                    match self.sorting {
+                        Sorting::BreadthFirst => self.next_by_topology(),
+                        Sorting::ByCommitTime(order) => self.next_by_commit_date(order, None),
+                        Sorting::ByCommitTimeCutoff { seconds, order } => {
+                            self.next_by_commit_date(order, seconds.into())
+                        }
+                    }
+                };
+                match item {
+                    Some(Ok(info)) if self.certainly_did_not_change_path(&info.id) => continue,
+                    item => return item,
                 }
             }
         }
     }
 
+    impl<Find, Predicate> Simple<Find, Predicate> {
+        fn certainly_did_not_change_path(&self, id: &oid) -> bool {
+            let (Some(path), Some(cache)) = (self.changed_path.as_ref(), self.cache.as_ref()) else {
+                return false;
+            };
+            cache
+                .commit_by_id(id)
+                .and_then(|commit| commit.maybe_contains_path(path.as_ref()))
+                == Some(false)
+        }
+    }
+
     impl Sorting {
         /// If not topo sort, provide the cutoff date if present.
         fn cutoff_time(&self) -> Option<SecondsSinceUnixEpoch> {
@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+mkdir dir
+echo a >a && echo b >dir/b
+git add . && git commit -q -m c1
+
+echo a2 >a
+git commit -q -am c2
+
+echo b2 >dir/b
+git commit -q -am c3
+
+git checkout -q -b branch1
+echo a3 >a
+git commit -q -am b1c1
+
+git checkout -q main
+echo b3 >dir/b
+git commit -q -am c4
+
+git merge branch1 -m m1b1 -q
+
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
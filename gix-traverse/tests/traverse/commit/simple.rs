@@ -539,3 +539,54 @@ mod adjusted_dates {
         [CommitTimeOrder::NewestFirst, CommitTimeOrder::OldestFirst]
     }
 }
+
+mod changed_path {
+    use gix_traverse::commit::Simple;
+
+    use crate::hex_to_id;
+
+    fn traverse(path: &str, use_commitgraph: bool) -> crate::Result<Vec<gix_hash::ObjectId>> {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_traversal_repo_with_changed_paths.sh")?;
+        let store = gix_odb::at(dir.join(".git").join("objects"))?;
+        let graph = use_commitgraph
+            .then(|| gix_commitgraph::at(store.store_ref().path().join("info")))
+            .transpose()?;
+        let tip = hex_to_id("9d267297c9f186aac00d397d39db3eabaa99414d");
+        Ok(Simple::new(Some(tip), &store)
+            .commit_graph(graph)
+            .changed_path(Some(path.into()))
+            .map(|res| res.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    #[test]
+    fn commits_that_did_not_change_the_path_are_skipped_with_bloom_filters() -> crate::Result {
+        let m1b1 = hex_to_id("9d267297c9f186aac00d397d39db3eabaa99414d");
+        let c4 = hex_to_id("240d7bccc3d12f3f95daf6afebd0d34a202e0eb5");
+        let b1c1 = hex_to_id("1f8a448222674f66d7787f55bc88179d58676373");
+        let c3 = hex_to_id("6c706914cc2eb0235620431755cc5543cca2a859");
+        let c2 = hex_to_id("1c412dbcabb5d52131e2d4f5c0cf11f77a164199");
+        let c1 = hex_to_id("7485b1bafeaff71f32550d87cf5f08064f8ebe82");
+
+        assert_eq!(traverse("a", true)?, [m1b1, b1c1, c2, c1]);
+        assert_eq!(traverse("dir/b", true)?, [c4, c3, c1]);
+        assert_eq!(
+            traverse("dir", true)?,
+            [c4, c3, c1],
+            "directories are in the filter as well"
+        );
+        assert_eq!(traverse("dir/", true)?, [c4, c3, c1], "trailing slashes are ignored");
+        assert!(
+            traverse("missing", true)?.is_empty(),
+            "paths that never existed are never changed"
+        );
+
+        let all = [m1b1, c4, b1c1, c3, c2, c1];
+        assert_eq!(
+            traverse("a", false)?,
+            all,
+            "without Bloom filters, it's unknown which commits changed the path"
+        );
+        Ok(())
+    }
+}
@@ -35,6 +35,23 @@ impl PrepareFetch {
         self
     }
 
+    /// Make this clone a partial one which omits objects as described by `filter`, like `blob:none` for a *blobless* clone.
+    ///
+    /// The remote will be configured as promisor remote, from which missing objects are fetched on demand.
+    pub fn with_filter(mut self, filter: Option<crate::remote::fetch::Filter>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// If `toggle` is `true`, initialize all submodules and clone them into `.git/modules` after checking out the main worktree,
+    /// and do the same for their submodules, like `git clone --recurse-submodules` does.
+    ///
+    /// This only has an effect with [`fetch_then_checkout()`](Self::fetch_then_checkout()), and needs the `status` feature.
+    pub fn with_recurse_submodules(mut self, toggle: bool) -> Self {
+        self.recurse_submodules = toggle;
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
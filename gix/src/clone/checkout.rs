@@ -35,6 +35,11 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[error("Could not fetch the objects missing in the partial clone before checking them out")]
+        FetchMissingObjects(#[source] Box<dyn std::error::Error + Send + Sync>),
+        #[cfg(all(feature = "status", feature = "blocking-network-client"))]
+        #[error("Could not initialize and check out submodules")]
+        UpdateSubmodules(#[source] Box<crate::submodule::update::Error>),
     }
 
     /// The progress ids used in [`PrepareCheckout::main_worktree()`].
@@ -118,6 +123,19 @@ pub mod main_worktree {
                 })?;
             let mut index = gix_index::File::from_state(index, repo.index_path());
 
+            // In partial clones, obtain all blobs at once instead of one at a time during checkout.
+            repo.objects
+                .fetch_missing(
+                    index
+                        .entries()
+                        .iter()
+                        .filter(|entry| {
+                            !entry.mode.is_submodule() && !entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE)
+                        })
+                        .map(|entry| entry.id),
+                )
+                .map_err(Error::FetchMissingObjects)?;
+
             let mut opts = repo.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
             opts.destination_is_initially_empty = true;
 
@@ -141,6 +159,19 @@ pub mod main_worktree {
             bytes.show_throughput(start);
 
             index.write(Default::default())?;
+            #[cfg(all(feature = "status", feature = "blocking-network-client"))]
+            if self.recurse_submodules {
+                let options = crate::submodule::update::Options {
+                    init: true,
+                    recursive: true,
+                    ..Default::default()
+                };
+                self.repo
+                    .as_mut()
+                    .expect("still present")
+                    .update_submodules(&options, should_interrupt)
+                    .map_err(|err| Error::UpdateSubmodules(Box::new(err)))?;
+            }
             Ok((self.repo.take().expect("still present").clone(), outcome))
         }
     }
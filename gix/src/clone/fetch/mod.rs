@@ -1,6 +1,7 @@
 use crate::bstr::BString;
 use crate::bstr::ByteSlice;
 use crate::clone::PrepareFetch;
+use crate::config::cache::util::ApplyLeniency;
 
 /// The error returned by [`PrepareFetch::fetch_only()`].
 #[derive(Debug, thiserror::Error)]
@@ -35,8 +36,15 @@ pub enum Error {
         source: gix_validate::reference::name::Error,
         head_ref_name: crate::bstr::BString,
     },
+    #[error("Could not obtain configuration to learn if bundles advertised by the remote should be used")]
+    BundleUriConfig(#[from] crate::config::boolean::Error),
     #[error("Failed to update HEAD with values from remote")]
     HeadUpdate(#[from] crate::reference::edit::Error),
+    #[error("The remote uses {remote:?} object ids, but the repository to clone into was created for {local:?} - set it in the create options")]
+    IncompatibleObjectHash {
+        local: gix_hash::Kind,
+        remote: gix_hash::Kind,
+    },
     #[error("The remote didn't have any ref that matched '{}'", wanted.as_ref().as_bstr())]
     RefNameMissing { wanted: gix_ref::PartialName },
     #[error("The remote has {} refs for '{}', try to use a specific name: {}", candidates.len(), wanted.as_ref().as_bstr(), candidates.iter().filter_map(|n| n.to_str().ok()).collect::<Vec<_>>().join(", "))]
@@ -58,6 +66,9 @@ impl PrepareFetch {
     ///
     /// Note that all data we created will be removed once this instance drops if the operation wasn't successful.
     ///
+    /// The object format of the remote isn't adopted, so remotes that don't use SHA1 can only be cloned into repositories
+    /// created with the matching [`object_hash`](crate::create::Options::object_hash).
+    ///
     /// ### Note for users of `async`
     ///
     /// Even though `async` is technically supported, it will still be blocking in nature as it uses a lot of non-async writes
@@ -111,6 +122,9 @@ impl PrepareFetch {
         } else {
             clone_fetch_tags = remote::fetch::Tags::All.into();
         }
+        if self.filter.is_some() {
+            remote = remote.with_filter(self.filter);
+        }
 
         let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
 
@@ -183,15 +197,28 @@ impl PrepareFetch {
             util::find_custom_refname(pending_pack.ref_map(), ref_name)?;
         }
         if pending_pack.ref_map().object_hash != repo.object_hash() {
-            unimplemented!("configure repository to expect a different object hash as advertised by the server")
+            return Err(Error::IncompatibleObjectHash {
+                local: repo.object_hash(),
+                remote: pending_pack.ref_map().object_hash,
+            });
         }
         let reflog_message = {
             let mut b = self.url.to_bstring();
             b.insert_str(0, "clone: from ");
             b
         };
+        let use_bundle_uri = repo
+            .config
+            .resolved
+            .boolean(crate::config::tree::Transfer::BUNDLE_URI)
+            .map(|val| crate::config::tree::Transfer::BUNDLE_URI.enrich_error(val))
+            .transpose()
+            .with_leniency(repo.config.lenient_config)?
+            .unwrap_or(false);
         let outcome = pending_pack
+            .with_bundle_uri(use_bundle_uri)
             .with_write_packed_refs_only(true)
+            .with_write_fetch_head(false)
             .with_reflog_message(RefLogMessage::Override {
                 message: reflog_message.clone(),
             })
@@ -200,6 +227,9 @@ impl PrepareFetch {
             .await?;
 
         util::append_config_to_repo_config(repo, config);
+        if self.filter.is_some() {
+            util::setup_partial_clone(repo, remote_name.as_ref())?;
+        }
         util::update_head(
             repo,
             &outcome.ref_map,
@@ -227,6 +257,7 @@ impl PrepareFetch {
             crate::clone::PrepareCheckout {
                 repo: repo.into(),
                 ref_name: self.ref_name.clone(),
+                recurse_submodules: self.recurse_submodules,
             },
             fetch_outcome,
         ))
@@ -58,6 +58,29 @@ pub fn append_config_to_repo_config(repo: &mut Repository, config: gix_config::F
     repo_config.append(config);
 }
 
+/// Mark the repository as partial clone whose missing objects are fetched from `remote_name` on demand, like `git` does.
+pub fn setup_partial_clone(repo: &mut Repository, remote_name: &BStr) -> Result<(), Error> {
+    use crate::config::tree::{Core, Extensions};
+    let mut config = repo.config_snapshot_mut();
+    let is_local = |meta: &gix_config::file::Metadata| meta.source == gix_config::Source::Local;
+    config
+        .section_mut_or_create_new_filter("core", None, is_local)
+        .expect("valid at compile time")
+        .set(
+            Core::REPOSITORY_FORMAT_VERSION.name.try_into().expect("valid"),
+            "1".into(),
+        );
+    config
+        .section_mut_or_create_new_filter("extensions", None, is_local)
+        .expect("valid at compile time")
+        .set(Extensions::PARTIAL_CLONE.name.try_into().expect("valid"), remote_name);
+    write_to_local_config(&config, WriteMode::Overwrite)?;
+    config.commit()?;
+    #[cfg(feature = "blocking-network-client")]
+    crate::repository::init::setup_fetch_missing(repo);
+    Ok(())
+}
+
 /// HEAD cannot be written by means of refspec by design, so we have to do it manually here. Also create the pointed-to ref
 /// if we have to, as it might not have been naturally included in the ref-specs.
 /// Lastly, use `ref_name` if it was provided instead, and let `HEAD` point to it.
@@ -34,9 +34,15 @@ pub struct PrepareFetch {
     /// How to handle shallow clones
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     shallow: remote::fetch::Shallow,
+    /// The filter to use for partial clones, if any.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    filter: Option<remote::fetch::Filter>,
     /// The name of the reference to fetch. If `None`, the reference pointed to by `HEAD` will be checked out.
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     ref_name: Option<gix_ref::PartialName>,
+    /// If `true`, submodules are initialized, cloned and checked out recursively after checking out the main worktree.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    recurse_submodules: bool,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -125,7 +131,9 @@ impl PrepareFetch {
             #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
+            filter: None,
             ref_name: None,
+            recurse_submodules: false,
         })
     }
 }
@@ -140,6 +148,9 @@ pub struct PrepareCheckout {
     pub(self) repo: Option<crate::Repository>,
     /// The name of the reference to check out. If `None`, the reference pointed to by `HEAD` will be checked out.
     pub(self) ref_name: Option<gix_ref::PartialName>,
+    /// If `true`, submodules are initialized, cloned and checked out recursively after checking out the main worktree.
+    #[cfg_attr(not(all(feature = "status", feature = "blocking-network-client")), allow(dead_code))]
+    pub(self) recurse_submodules: bool,
 }
 
 // This module encapsulates functionality that works with both feature toggles. Can be combined with `fetch`
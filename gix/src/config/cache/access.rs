@@ -8,7 +8,7 @@ use crate::{
     config::{
         boolean,
         cache::util::{ApplyLeniency, ApplyLeniencyDefaultValue},
-        tree::{Core, Key},
+        tree::{CommitGraph, Core, Key, Pack},
         Cache,
     },
     remote,
@@ -222,6 +222,25 @@ impl Cache {
         })
     }
 
+    pub(crate) fn may_use_bitmaps(&self) -> Result<bool, config::boolean::Error> {
+        const DEFAULT: bool = true;
+        self.resolved.boolean(&Pack::USE_BITMAPS).map_or(Ok(DEFAULT), |res| {
+            Pack::USE_BITMAPS
+                .enrich_error(res)
+                .with_lenient_default_value(self.lenient_config, DEFAULT)
+        })
+    }
+
+    pub(crate) fn commit_graph_generation_version(&self) -> Result<u64, config::unsigned_integer::Error> {
+        Ok(self
+            .resolved
+            .integer("commitGraph.generationVersion")
+            .map(|number| CommitGraph::GENERATION_VERSION.try_into_u64(number))
+            .transpose()
+            .with_leniency(self.lenient_config)?
+            .unwrap_or(2))
+    }
+
     /// Returns (file-timeout, pack-refs timeout)
     pub(crate) fn lock_timeout(
         &self,
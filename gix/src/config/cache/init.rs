@@ -26,6 +26,7 @@ impl Cache {
             lossy,
             is_bare,
             object_hash,
+            ref_storage: _,
             reflog: _,
             precompose_unicode: _,
             protect_windows: _,
@@ -303,8 +304,14 @@ impl crate::Repository {
     }
 
     fn apply_changed_values(&mut self) {
-        self.refs.write_reflog = util::reflog_or_default(self.config.reflog, self.workdir().is_some());
-        self.refs.namespace.clone_from(&self.config.refs_namespace);
+        self.refs
+            .set_write_reflog(util::reflog_or_default(self.config.reflog, self.workdir().is_some()));
+        self.refs.set_namespace(self.config.refs_namespace.clone());
+        // Submodule configuration overrides the `.gitmodules` file, so it has to be re-read with the new values.
+        #[cfg(feature = "attributes")]
+        {
+            self.modules = gix_fs::SharedFileSnapshotMut::new().into();
+        }
     }
 }
 
@@ -330,10 +337,16 @@ fn apply_environment_overrides(
             "core",
             None,
             git_prefix,
-            &[{
-                let key = &Core::WORKTREE;
-                (env(key), key.name)
-            }][..],
+            &[
+                {
+                    let key = &Core::WORKTREE;
+                    (env(key), key.name)
+                },
+                {
+                    let key = &Core::NOTES_REF;
+                    (env(key), key.name)
+                },
+            ][..],
         ),
         (
             "http",
@@ -24,6 +24,8 @@ pub(crate) mod root {
         pub const CHECKOUT: sections::Checkout = sections::Checkout;
         /// The `clone` section.
         pub const CLONE: sections::Clone = sections::Clone;
+        /// The `commitGraph` section.
+        pub const COMMIT_GRAPH: sections::CommitGraph = sections::CommitGraph;
         /// The `committer` section.
         pub const COMMITTER: sections::Committer = sections::Committer;
         /// The `core` section.
@@ -49,6 +51,9 @@ pub(crate) mod root {
         pub const MAILMAP: sections::Mailmap = sections::Mailmap;
         /// The `merge` section.
         pub const MERGE: sections::Merge = sections::Merge;
+        /// The `notes` section.
+        #[cfg(feature = "note")]
+        pub const NOTES: sections::Notes = sections::Notes;
         /// The `pack` section.
         pub const PACK: sections::Pack = sections::Pack;
         /// The `protocol` section.
@@ -64,6 +69,10 @@ pub(crate) mod root {
         /// The `status` section.
         #[cfg(feature = "status")]
         pub const STATUS: sections::Status = sections::Status;
+        /// The `transfer` section.
+        pub const TRANSFER: sections::Transfer = sections::Transfer;
+        /// The `uploadpack` section.
+        pub const UPLOAD_PACK: sections::UploadPack = sections::UploadPack;
         /// The `user` section.
         pub const USER: sections::User = sections::User;
         /// The `url` section.
@@ -76,6 +85,7 @@ pub(crate) mod root {
                 &Self::BRANCH,
                 &Self::CHECKOUT,
                 &Self::CLONE,
+                &Self::COMMIT_GRAPH,
                 &Self::COMMITTER,
                 &Self::CORE,
                 &Self::CREDENTIAL,
@@ -89,6 +99,8 @@ pub(crate) mod root {
                 &Self::INIT,
                 &Self::MAILMAP,
                 &Self::MERGE,
+                #[cfg(feature = "note")]
+                &Self::NOTES,
                 &Self::PACK,
                 &Self::PROTOCOL,
                 &Self::PUSH,
@@ -97,6 +109,8 @@ pub(crate) mod root {
                 &Self::SSH,
                 #[cfg(feature = "status")]
                 &Self::STATUS,
+                &Self::TRANSFER,
+                &Self::UPLOAD_PACK,
                 &Self::USER,
                 &Self::URL,
             ]
@@ -107,11 +121,13 @@ pub(crate) mod root {
 mod sections;
 pub use sections::{
     branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, push, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Mailmap,
-    Merge, Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
+    Branch, Checkout, Clone, CommitGraph, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init,
+    Mailmap, Merge, Pack, Protocol, Push, Remote, Safe, Ssh, Transfer, UploadPack, Url, User,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
+#[cfg(feature = "note")]
+pub use sections::{notes, Notes};
 #[cfg(feature = "status")]
 pub use sections::{status, Status};
 
@@ -0,0 +1,20 @@
+use crate::config::{
+    tree::{keys, CommitGraph, Key, Section},
+    Tree,
+};
+
+impl CommitGraph {
+    /// The `commitGraph.generationVersion` key.
+    pub const GENERATION_VERSION: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("generationVersion", &Tree::COMMIT_GRAPH);
+}
+
+impl Section for CommitGraph {
+    fn name(&self) -> &str {
+        "commitGraph"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::GENERATION_VERSION]
+    }
+}
@@ -77,6 +77,9 @@ impl Core {
         .with_environment_override("GIT_NO_REPLACE_OBJECTS");
     /// The `core.commitGraph` key.
     pub const COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("commitGraph", &config::Tree::CORE);
+    /// The `core.notesRef` key.
+    pub const NOTES_REF: keys::String =
+        keys::String::new_string("notesRef", &config::Tree::CORE).with_environment_override("GIT_NOTES_REF");
     /// The `core.safecrlf` key.
     #[cfg(feature = "attributes")]
     pub const SAFE_CRLF: SafeCrlf = SafeCrlf::new_with_validate("safecrlf", &config::Tree::CORE, validate::SafeCrlf);
@@ -128,6 +131,7 @@ impl Section for Core {
             &Self::SSH_COMMAND,
             &Self::USE_REPLACE_REFS,
             &Self::COMMIT_GRAPH,
+            &Self::NOTES_REF,
             #[cfg(feature = "attributes")]
             &Self::SAFE_CRLF,
             #[cfg(feature = "attributes")]
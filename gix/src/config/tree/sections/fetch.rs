@@ -14,6 +14,10 @@ impl Fetch {
     #[cfg(feature = "attributes")]
     pub const RECURSE_SUBMODULES: RecurseSubmodules =
         RecurseSubmodules::new_with_validate("recurseSubmodules", &config::Tree::FETCH, validate::RecurseSubmodules);
+    /// The `fetch.writeFetchHead` key.
+    pub const WRITE_FETCH_HEAD: keys::Boolean = keys::Boolean::new_boolean("writeFetchHead", &config::Tree::FETCH);
+    /// The `fetch.uriProtocols` key.
+    pub const URI_PROTOCOLS: keys::String = keys::String::new_string("uriProtocols", &config::Tree::FETCH);
 }
 
 impl Section for Fetch {
@@ -26,6 +30,8 @@ impl Section for Fetch {
             &Self::NEGOTIATION_ALGORITHM,
             #[cfg(feature = "attributes")]
             &Self::RECURSE_SUBMODULES,
+            &Self::WRITE_FETCH_HEAD,
+            &Self::URI_PROTOCOLS,
         ]
     }
 }
@@ -21,6 +21,11 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `commitGraph` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct CommitGraph;
+mod commit_graph;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -81,6 +86,13 @@ mod mailmap;
 pub struct Merge;
 mod merge;
 
+/// The `notes` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "note")]
+pub struct Notes;
+#[cfg(feature = "note")]
+pub mod notes;
+
 /// The `pack` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Pack;
@@ -118,6 +130,16 @@ pub struct Status;
 #[cfg(feature = "status")]
 pub mod status;
 
+/// The `transfer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Transfer;
+mod transfer;
+
+/// The `uploadpack` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct UploadPack;
+mod upload_pack;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
@@ -0,0 +1,55 @@
+use crate::config;
+use crate::config::tree::{keys, Key, Notes, Section};
+
+impl Notes {
+    /// The `notes.mergeStrategy` key, which may also be used as `notes.<name>.mergeStrategy` for the notes reference
+    /// `refs/notes/<name>`.
+    pub const MERGE_STRATEGY: MergeStrategy =
+        MergeStrategy::new_with_validate("mergeStrategy", &config::Tree::NOTES, validate::MergeStrategy)
+            .with_subsection_requirement(None);
+}
+
+impl Section for Notes {
+    fn name(&self) -> &str {
+        "notes"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::MERGE_STRATEGY]
+    }
+}
+
+/// The `notes.mergeStrategy` key.
+pub type MergeStrategy = keys::Any<validate::MergeStrategy>;
+
+mod merge_strategy {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::notes::MergeStrategy};
+
+    impl MergeStrategy {
+        /// Derive the strategy to merge notes with from `name`, like `cat_sort_uniq`.
+        pub fn try_into_strategy(
+            &'static self,
+            name: Cow<'_, BStr>,
+        ) -> Result<gix_note::merge::Strategy, config::key::GenericErrorWithValue> {
+            gix_note::merge::Strategy::from_bytes(name.as_ref())
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, name.into_owned()))
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Notes},
+    };
+
+    pub struct MergeStrategy;
+    impl keys::Validate for MergeStrategy {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Notes::MERGE_STRATEGY.try_into_strategy(value.into())?;
+            Ok(())
+        }
+    }
+}
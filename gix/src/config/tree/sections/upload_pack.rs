@@ -0,0 +1,20 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, UploadPack},
+};
+
+impl UploadPack {
+    /// The `uploadpack.advertiseBundleURIs` key.
+    pub const ADVERTISE_BUNDLE_URIS: keys::Boolean =
+        keys::Boolean::new_boolean("advertiseBundleURIs", &config::Tree::UPLOAD_PACK);
+}
+
+impl Section for UploadPack {
+    fn name(&self) -> &str {
+        "uploadpack"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::ADVERTISE_BUNDLE_URIS]
+    }
+}
@@ -117,6 +117,10 @@ pub struct Options {
     /// If set, use these filesystem capabilities to populate the respective git-config fields.
     /// If `None`, the directory will be probed.
     pub fs_capabilities: Option<gix_fs::Capabilities>,
+    /// The kind of hash to use for all objects of the new repository.
+    ///
+    /// If it's not the default of `Sha1`, `extensions.objectFormat` will be set accordingly.
+    pub object_hash: gix_hash::Kind,
 }
 
 /// Create a new `.git` repository of `kind` within the possibly non-existing `directory`
@@ -130,6 +134,7 @@ pub fn into(
     Options {
         fs_capabilities,
         destination_must_be_empty,
+        object_hash,
     }: Options,
 ) -> Result<gix_discover::repository::Path, Error> {
     let mut dot_git = directory.into();
@@ -209,7 +214,8 @@ pub fn into(
             let caps = fs_capabilities.unwrap_or_else(|| gix_fs::Capabilities::probe(&dot_git));
             let mut core = config.new_section("core", None).expect("valid section name");
 
-            core.push(key("repositoryformatversion"), Some("0".into()));
+            let format_version = if object_hash == gix_hash::Kind::Sha1 { "0" } else { "1" };
+            core.push(key("repositoryformatversion"), Some(format_version.into()));
             core.push(key("filemode"), Some(bool(caps.executable_bit).into()));
             core.push(key("bare"), Some(bool(bare).into()));
             core.push(key("logallrefupdates"), Some(bool(!bare).into()));
@@ -218,6 +224,13 @@ pub fn into(
             core.push(key("precomposeunicode"), Some(bool(caps.precompose_unicode).into()));
             caps
         };
+        if object_hash != gix_hash::Kind::Sha1 {
+            let mut extensions = config.new_section("extensions", None).expect("valid section name");
+            extensions.push(
+                key("objectformat"),
+                Some(object_hash.to_string().to_ascii_lowercase().as_str().into()),
+            );
+        }
         let mut cursor = PathCursor(&mut dot_git);
         let config_path = cursor.at("config");
         std::fs::write(config_path, config.to_bstring()).map_err(|err| Error::IoWrite {
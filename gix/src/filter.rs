@@ -128,6 +128,10 @@ impl<'repo> Pipeline<'repo> {
             encodings_with_roundtrip_check: encodings,
             crlf_roundtrip_check: safe_crlf,
             object_hash: repo.object_hash(),
+            #[cfg(feature = "lfs")]
+            builtin_drivers: vec![std::sync::Arc::new(repo.lfs_filter())],
+            #[cfg(not(feature = "lfs"))]
+            builtin_drivers: Vec::new(),
         })
     }
 
@@ -83,8 +83,8 @@ impl ThreadSafeRepository {
                         source: err,
                     })?;
             let mut repo = repo.to_thread_local();
-            let prev_write_reflog = repo.refs.write_reflog;
-            repo.refs.write_reflog = WriteReflog::Disable;
+            let prev_write_reflog = repo.refs.write_reflog();
+            repo.refs.set_write_reflog(WriteReflog::Disable);
             repo.edit_reference(RefEdit {
                 change: gix_ref::transaction::Change::Update {
                     log: Default::default(),
@@ -94,7 +94,7 @@ impl ThreadSafeRepository {
                 name: "HEAD".try_into().expect("valid"),
                 deref: false,
             })?;
-            repo.refs.write_reflog = prev_write_reflog;
+            repo.refs.set_write_reflog(prev_write_reflog);
         }
 
         Ok(repo)
@@ -0,0 +1,161 @@
+//! Materialise files tracked by [git large file support](https://git-lfs.com) without requiring `git-lfs`.
+//!
+//! All [filter pipelines](crate::filter::Pipeline), and thus checkouts and archives, use the [built-in `lfs` filter](Repository::lfs_filter())
+//! for files with the `filter=lfs` attribute. It keeps objects in the [LFS store](Repository::lfs_store()), and if one of the
+//! `blocking-http-transport-*` features is enabled, it downloads missing objects from the [LFS server](Repository::lfs_endpoint()).
+use crate::{bstr::ByteSlice, remote, Repository};
+
+pub use gix_lfs as plumbing;
+
+///
+pub mod endpoint {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::lfs_endpoint()](crate::Repository::lfs_endpoint()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The LFS url {url:?} configured in '{key}' isn't valid UTF-8")]
+        IllformedUtf8 { key: BString, url: BString },
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+    }
+}
+
+/// LFS
+impl Repository {
+    /// Return the store of LFS objects, which is at `lfs/objects` in the common git directory,
+    /// or in the `objects` directory of the path configured in `lfs.storage`.
+    pub fn lfs_store(&self) -> plumbing::Store {
+        let mut path = self.common_dir().join("lfs");
+        if let Some(storage) = self
+            .config
+            .resolved
+            .string_filter("lfs.storage", &mut self.filter_config_section())
+            .filter(|storage| !storage.is_empty())
+        {
+            path = self.common_dir().join(gix_path::from_bstr(storage));
+        }
+        plumbing::Store::at(path.join("objects"))
+    }
+
+    /// Return the URL of the LFS server, similar to how `git-lfs` determines it.
+    ///
+    /// It's taken from `lfs.url`, or `remote.<name>.lfsurl` of the default remote for fetching, or it's derived from
+    /// the fetch URL of the default remote. `None` is returned if there is no such remote, or if its URL can't be used
+    /// to derive an LFS server URL.
+    pub fn lfs_endpoint(&self) -> Result<Option<String>, endpoint::Error> {
+        let config = &self.config.resolved;
+        let to_string = |key: String, url: std::borrow::Cow<'_, crate::bstr::BStr>| {
+            url.to_str()
+                .map(ToOwned::to_owned)
+                .map_err(|_| endpoint::Error::IllformedUtf8 {
+                    key: key.into(),
+                    url: url.into_owned(),
+                })
+        };
+        if let Some(url) = config
+            .string_filter("lfs.url", &mut self.filter_config_section())
+            .filter(|url| !url.is_empty())
+        {
+            return to_string("lfs.url".into(), url).map(Some);
+        }
+        let Some(remote) = self.find_default_remote(remote::Direction::Fetch).transpose()? else {
+            return Ok(None);
+        };
+        if let Some((name, url)) = remote.name().and_then(remote::Name::as_symbol).and_then(|name| {
+            config
+                .string_filter_by("remote", Some(name.into()), "lfsurl", &mut self.filter_config_section())
+                .filter(|url| !url.is_empty())
+                .map(|url| (name, url))
+        }) {
+            return to_string(format!("remote.{name}.lfsurl"), url).map(Some);
+        }
+        Ok(remote
+            .url(remote::Direction::Fetch)
+            .and_then(plumbing::endpoint::from_url))
+    }
+
+    /// Return the built-in `lfs` filter, which keeps objects in the [LFS store](Self::lfs_store()).
+    ///
+    /// With one of the `blocking-http-transport-*` features enabled, missing objects are downloaded from the
+    /// [LFS server](Self::lfs_endpoint()) using this repository's HTTP configuration and credential helpers.
+    pub fn lfs_filter(&self) -> plumbing::Filter {
+        let filter = plumbing::Filter::new(self.lfs_store());
+        #[cfg(any(
+            feature = "blocking-http-transport-curl",
+            feature = "blocking-http-transport-reqwest"
+        ))]
+        let filter = download::attach::<gix_transport::client::http::Impl>(filter, self);
+        filter
+    }
+}
+
+///
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+pub mod download {
+    use std::sync::Mutex;
+
+    use gix_transport::client::http::Http;
+
+    use super::plumbing::{self, client::Client};
+    use crate::{remote, Repository};
+
+    /// The error returned when the [built-in `lfs` filter](crate::Repository::lfs_filter()) fails to download an object.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Endpoint(#[from] super::endpoint::Error),
+        #[error("Could not determine the URL of the LFS server as there is no remote to derive it from")]
+        MissingEndpoint,
+        #[error(transparent)]
+        Url(#[from] gix_url::parse::Error),
+        #[error(transparent)]
+        TransportOptions(#[from] crate::config::transport::Error),
+        #[error("Could not configure the HTTP client")]
+        Configure(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        CredentialHelpers(#[from] crate::config::credential_helpers::Error),
+        #[error(transparent)]
+        Client(#[from] plumbing::client::Error),
+    }
+
+    /// Make `filter` download missing objects with a client that is created from the configuration of `repo` on first use.
+    pub(super) fn attach<H>(filter: plumbing::Filter, repo: &Repository) -> plumbing::Filter
+    where
+        H: Http + Default + Send + 'static,
+    {
+        let repo = repo.clone().into_sync();
+        let client = Mutex::new(None::<Client<H>>);
+        filter.with_download(move |pointer, store| {
+            let mut client = client.lock().expect("no panic while holding the lock");
+            if client.is_none() {
+                *client = Some(connect(&repo.to_thread_local())?);
+            }
+            client
+                .as_mut()
+                .expect("just set")
+                .download(Some(pointer), store)
+                .map_err(Error::from)?;
+            Ok(())
+        })
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn connect<H: Http + Default>(repo: &Repository) -> Result<Client<H>, Error> {
+        let endpoint = repo.lfs_endpoint()?.ok_or(Error::MissingEndpoint)?;
+        let remote_name = repo.remote_default_name(remote::Direction::Fetch);
+        let mut http = H::default();
+        if let Some(options) = repo.transport_options(endpoint.as_str(), remote_name.as_deref())? {
+            http.configure(&*options).map_err(Error::Configure)?;
+        }
+        let (mut cascade, _action_with_normalized_url, prompt_opts) = repo
+            .config_snapshot()
+            .credential_helpers(gix_url::parse(endpoint.as_str().into())?)?;
+        Ok(Client::new(http, endpoint).with_authentication(move |action| cascade.invoke(action, prompt_opts.clone())))
+    }
+}
@@ -97,6 +97,7 @@ pub use gix_actor as actor;
 pub use gix_attributes as attrs;
 #[cfg(feature = "blame")]
 pub use gix_blame as blame;
+pub use gix_bundle as bundle;
 #[cfg(feature = "command")]
 pub use gix_command as command;
 pub use gix_commitgraph as commitgraph;
@@ -112,6 +113,7 @@ pub use gix_features::{
     progress::{Count, DynNestedProgress, NestedProgress, Progress},
     threading,
 };
+pub use gix_fetchhead as fetchhead;
 pub use gix_fs as fs;
 pub use gix_glob as glob;
 pub use gix_hash as hash;
@@ -157,7 +159,7 @@ mod attribute_stack;
 pub mod path;
 
 /// The standard type for a store to handle git references.
-pub type RefStore = gix_ref::file::Store;
+pub type RefStore = gix_ref::store::Handle;
 /// A handle for finding objects in an object database, abstracting away caches for thread-local use.
 pub type OdbHandle = gix_odb::memory::Proxy<gix_odb::Handle>;
 /// A handle for finding objects in an object database, abstracting away caches for moving across threads.
@@ -207,6 +209,26 @@ pub mod diff;
 #[cfg(feature = "merge")]
 pub mod merge;
 
+///
+#[cfg(feature = "stash")]
+pub mod stash;
+
+///
+#[cfg(feature = "rebase")]
+pub mod rebase;
+
+///
+#[cfg(feature = "sequencer")]
+pub mod sequencer;
+
+///
+#[cfg(feature = "note")]
+pub mod note;
+
+///
+#[cfg(feature = "lfs")]
+pub mod lfs;
+
 /// See [`ThreadSafeRepository::discover()`], but returns a [`Repository`] instead.
 ///
 /// # Note
@@ -0,0 +1,142 @@
+use gix_hash::ObjectId;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::Target;
+
+use crate::bstr::BStr;
+use crate::note::{plumbing::combine, Error, Notes, Platform};
+
+impl Platform<'_> {
+    /// Set the note of `object` to `message` and return the id of the note, similar to `git notes add -m <message>`.
+    ///
+    /// If `object` has a note already, it's only replaced if `overwrite` is `true`.
+    /// An empty `message` removes the note instead, just like git does, and `None` is returned.
+    pub fn add(&self, object: impl Into<ObjectId>, message: &BStr, overwrite: bool) -> Result<Option<ObjectId>, Error> {
+        let object = object.into();
+        let (previous, mut notes) = self.load()?;
+        if !overwrite && notes.by_object.contains_key(&object) {
+            return Err(Error::NoteExists { object });
+        }
+        if message.is_empty() {
+            notes.by_object.remove(&object);
+            self.commit(previous, &notes, "Notes removed by 'git notes add'")?;
+            return Ok(None);
+        }
+        let note = self.write_note(message)?;
+        notes.by_object.insert(object, note);
+        self.commit(previous, &notes, "Notes added by 'git notes add'")?;
+        Ok(Some(note))
+    }
+
+    /// Append `message` to the note of `object`, separated by an empty line, or add it as new note, and return the id of
+    /// the note, similar to `git notes append -m <message>`.
+    pub fn append(&self, object: impl Into<ObjectId>, message: &BStr) -> Result<ObjectId, Error> {
+        let object = object.into();
+        let (previous, mut notes) = self.load()?;
+        let note = match notes.by_object.get(&object) {
+            Some(existing) => {
+                let existing = self.repo.find_blob(*existing)?;
+                self.write_note(combine::concatenate(&existing.data, &with_newline(message)).as_ref())?
+            }
+            None => self.write_note(message)?,
+        };
+        notes.by_object.insert(object, note);
+        self.commit(previous, &notes, "Notes added by 'git notes append'")?;
+        Ok(note)
+    }
+
+    /// Copy the note of object `from` to object `to` and return the id of the note, similar to `git notes copy`.
+    ///
+    /// If `to` has a note already, it's only replaced if `overwrite` is `true`.
+    pub fn copy(&self, from: impl Into<ObjectId>, to: impl Into<ObjectId>, overwrite: bool) -> Result<ObjectId, Error> {
+        let (from, to) = (from.into(), to.into());
+        let (previous, mut notes) = self.load()?;
+        let note = *notes.by_object.get(&from).ok_or(Error::NoteMissing { object: from })?;
+        if !overwrite && notes.by_object.contains_key(&to) {
+            return Err(Error::NoteExists { object: to });
+        }
+        notes.by_object.insert(to, note);
+        self.commit(previous, &notes, "Notes added by 'git notes copy'")?;
+        Ok(note)
+    }
+
+    /// Remove the note of `object` and return `true`, or return `false` if it had no note, similar to `git notes remove`.
+    pub fn remove(&self, object: impl Into<ObjectId>) -> Result<bool, Error> {
+        let (previous, mut notes) = self.load()?;
+        if notes.by_object.remove(&object.into()).is_none() {
+            return Ok(false);
+        }
+        self.commit(previous, &notes, "Notes removed by 'git notes remove'")?;
+        Ok(true)
+    }
+
+    /// Return the current notes commit, if there is one, along with all notes.
+    fn load(&self) -> Result<(Option<ObjectId>, Notes), Error> {
+        Ok((self.commit_id()?, self.list()?))
+    }
+
+    fn write_note(&self, message: &BStr) -> Result<ObjectId, Error> {
+        Ok(self.repo.write_blob(with_newline(message))?.detach())
+    }
+
+    /// Write `notes` into a new commit with `message` on top of the notes commit `previous`, and point the notes
+    /// reference to it.
+    fn commit(&self, previous: Option<ObjectId>, notes: &Notes, message: &str) -> Result<ObjectId, Error> {
+        let tree = notes.write_to(&self.repo.objects)?;
+        self.write_commit(tree, previous.into_iter().collect(), message, previous)
+    }
+
+    /// Write a commit with `tree`, `parents` and `message`, and point the notes reference to it if it still points to
+    /// `previous`.
+    pub(super) fn write_commit(
+        &self,
+        tree: ObjectId,
+        parents: Vec<ObjectId>,
+        message: &str,
+        previous: Option<ObjectId>,
+    ) -> Result<ObjectId, Error> {
+        let repo = self.repo;
+        let author = repo.author().ok_or(Error::AuthorMissing)??;
+        let committer = repo.committer().ok_or(Error::CommitterMissing)??;
+        let commit = gix_object::Commit {
+            message: format!("{message}\n").into(),
+            tree,
+            author: author.into(),
+            committer: committer.into(),
+            encoding: None,
+            parents: parents.into(),
+            extra_headers: Default::default(),
+        };
+        let id = repo.write_object(&commit)?.detach();
+        self.set_reference(id, previous, message)?;
+        Ok(id)
+    }
+
+    /// Point the notes reference to `id` if it still points to `previous`, logging `message`.
+    pub(super) fn set_reference(&self, id: ObjectId, previous: Option<ObjectId>, message: &str) -> Result<(), Error> {
+        self.repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("notes: {message}").into(),
+                },
+                expected: match previous {
+                    Some(previous) => PreviousValue::MustExistAndMatch(Target::Object(previous)),
+                    None => PreviousValue::MustNotExist,
+                },
+                new: Target::Object(id),
+            },
+            name: self.name.clone(),
+            deref: false,
+        })?;
+        Ok(())
+    }
+}
+
+fn with_newline(message: &BStr) -> Vec<u8> {
+    let mut out = message.to_vec();
+    if !out.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+    out
+}
@@ -0,0 +1,105 @@
+use gix_hash::ObjectId;
+
+use crate::bstr::BStr;
+use crate::config::tree::Key;
+use crate::config::tree::Notes as NotesKeys;
+use crate::note::{commit_id, expand_name, plumbing, Error, Notes, Platform, Strategy};
+
+/// The result of [`Platform::merge()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// All of their notes are contained in ours already, and nothing was changed.
+    UpToDate,
+    /// Our notes were contained in theirs, so the notes reference now points to their notes `commit`.
+    FastForward {
+        /// The notes commit the notes reference now points to.
+        commit: ObjectId,
+    },
+    /// The notes were merged into a new merge `commit` that the notes reference now points to.
+    Merged {
+        /// The new notes commit, whose parents are our and their notes commit.
+        commit: ObjectId,
+    },
+    /// Notes were changed differently on both sides, which isn't resolved with [`Strategy::Manual`].
+    ///
+    /// The notes reference is left unchanged, and the merge can be retried with another strategy.
+    Conflicts {
+        /// The notes that couldn't be merged.
+        conflicts: Vec<plumbing::merge::Conflict>,
+    },
+}
+
+impl Platform<'_> {
+    /// Merge the notes in the notes reference `other`, expanded like [`Repository::notes_at()`](crate::Repository::notes_at()),
+    /// into ours, similar to `git notes merge`.
+    ///
+    /// Notes that changed differently on both sides are resolved with `strategy`, or with the strategy configured in
+    /// `notes.<name>.mergeStrategy` or `notes.mergeStrategy` if `None`, which defaults to [`Strategy::Manual`].
+    ///
+    /// ### Deviation
+    ///
+    /// Conflicts aren't checked out into `.git/NOTES_MERGE_WORKTREE` to be resolved by hand, but are
+    /// [returned](Outcome::Conflicts) without changing any notes.
+    pub fn merge(&self, other: &BStr, strategy: Option<Strategy>) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let other = expand_name(other)?;
+        let theirs = commit_id(repo, other.as_ref())?.ok_or_else(|| Error::NotFound { name: other.clone() })?;
+        let strategy = match strategy {
+            Some(strategy) => strategy,
+            None => self.configured_strategy()?,
+        };
+        let Some(ours) = self.commit_id()? else {
+            self.set_reference(theirs, None, "Fast-forward")?;
+            return Ok(Outcome::FastForward { commit: theirs });
+        };
+        let base = match repo.merge_base(ours, theirs) {
+            Ok(base) => Some(base.detach()),
+            Err(crate::repository::merge_base::Error::NotFound { .. }) => None,
+            Err(err) => return Err(err.into()),
+        };
+        if base == Some(theirs) {
+            return Ok(Outcome::UpToDate);
+        }
+        if base == Some(ours) {
+            self.set_reference(theirs, Some(ours), "Fast-forward")?;
+            return Ok(Outcome::FastForward { commit: theirs });
+        }
+
+        let read = |commit: Option<ObjectId>| -> Result<Notes, Error> {
+            Ok(match commit {
+                Some(commit) => Notes::from_tree(&repo.find_commit(commit)?.tree_id()?, &repo.objects)?,
+                None => Notes::default(),
+            })
+        };
+        let outcome = plumbing::merge(
+            &read(base)?,
+            &read(Some(ours))?,
+            &read(Some(theirs))?,
+            strategy,
+            &repo.objects,
+        )?;
+        if !outcome.conflicts.is_empty() {
+            return Ok(Outcome::Conflicts {
+                conflicts: outcome.conflicts,
+            });
+        }
+        let tree = outcome.notes.write_to(&repo.objects)?;
+        let message = format!("Merged notes from {} into {}", other.as_bstr(), self.name.as_bstr());
+        let commit = self.write_commit(tree, vec![ours, theirs], &message, Some(ours))?;
+        Ok(Outcome::Merged { commit })
+    }
+
+    /// Return the merge strategy configured for our notes reference, or for all notes.
+    fn configured_strategy(&self) -> Result<Strategy, Error> {
+        let config = &self.repo.config.resolved;
+        let key = &NotesKeys::MERGE_STRATEGY;
+        let short_name = self.name.as_bstr().strip_prefix(b"refs/notes/").map(Into::into);
+        let value = short_name
+            .and_then(|name: &BStr| config.string_by(key.section().name(), Some(name), key.name()))
+            .or_else(|| config.string_by(key.section().name(), None, key.name()));
+        Ok(match value {
+            Some(value) => key.try_into_strategy(value)?,
+            None => Strategy::default(),
+        })
+    }
+}
@@ -0,0 +1,161 @@
+use std::borrow::Cow;
+
+use gix_hash::ObjectId;
+use gix_ref::{FullName, FullNameRef};
+
+use crate::bstr::{BStr, BString};
+use crate::config::tree::Core;
+use crate::Repository;
+
+pub use gix_note as plumbing;
+pub use gix_note::{merge::Strategy, Notes};
+
+mod error {
+    /// The error returned by all operations of the [notes platform](crate::note::Platform).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The notes reference name '{name}' is invalid")]
+        InvalidName {
+            name: crate::bstr::BString,
+            source: gix_validate::reference::name::Error,
+        },
+        #[error("Object {object} already has a note")]
+        NoteExists { object: gix_hash::ObjectId },
+        #[error("Object {object} has no note")]
+        NoteMissing { object: gix_hash::ObjectId },
+        #[error("The notes reference '{name}' doesn't exist")]
+        NotFound { name: gix_ref::FullName },
+        #[error("Author identity is not configured")]
+        AuthorMissing,
+        #[error("Committer identity is not configured")]
+        CommitterMissing,
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        MergeStrategy(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::to_kind::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        FindNote(#[from] gix_note::find::Error),
+        #[error(transparent)]
+        ReadNotes(#[from] gix_note::read::Error),
+        #[error(transparent)]
+        WriteNotes(#[from] gix_note::write::Error),
+        #[error(transparent)]
+        MergeNotes(#[from] gix_note::merge::Error),
+        #[error(transparent)]
+        MergeBase(#[from] crate::repository::merge_base::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+pub use error::Error;
+
+/// A platform to read and edit the notes in a notes reference, similar to `git notes`.
+///
+/// Obtain it with [`Repository::notes()`] or [`Repository::notes_at()`].
+///
+/// Each edit creates a new commit in the notes reference, whose tree holds the notes by annotated object.
+#[derive(Clone)]
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) name: FullName,
+}
+
+/// Notes
+impl Repository {
+    /// Obtain a platform to read and edit the notes in the reference configured in `core.notesRef` or `GIT_NOTES_REF`,
+    /// or in [`refs/notes/commits`](plumbing::DEFAULT_REF) if unset.
+    pub fn notes(&self) -> Result<Platform<'_>, Error> {
+        let name = self
+            .config
+            .resolved
+            .string(Core::NOTES_REF)
+            .map_or_else(|| plumbing::DEFAULT_REF.into(), Cow::into_owned);
+        self.notes_at(name.as_ref())
+    }
+
+    /// Obtain a platform to read and edit the notes in the reference `name`, which is expanded like git does, so that
+    /// `ci` is `refs/notes/ci` and `notes/ci` is `refs/notes/ci` as well.
+    pub fn notes_at(&self, name: &BStr) -> Result<Platform<'_>, Error> {
+        Ok(Platform {
+            repo: self,
+            name: expand_name(name)?,
+        })
+    }
+}
+
+impl<'repo> Platform<'repo> {
+    /// Return the name of the reference holding the notes.
+    pub fn name(&self) -> &FullNameRef {
+        self.name.as_ref()
+    }
+
+    /// Return the id of the commit that holds the current notes, or `None` if there are no notes yet.
+    pub fn commit_id(&self) -> Result<Option<ObjectId>, Error> {
+        commit_id(self.repo, self.name.as_ref())
+    }
+
+    /// Return the note of `object`, or `None` if it has none.
+    pub fn find(&self, object: impl Into<ObjectId>) -> Result<Option<crate::Blob<'repo>>, Error> {
+        let Some(tree) = self.tree_id()? else {
+            return Ok(None);
+        };
+        Ok(match plumbing::find(&tree, &object.into(), &self.repo.objects)? {
+            Some(note) => Some(self.repo.find_blob(note)?),
+            None => None,
+        })
+    }
+
+    /// Return all notes, which are empty if there are no notes yet.
+    pub fn list(&self) -> Result<Notes, Error> {
+        Ok(match self.tree_id()? {
+            Some(tree) => Notes::from_tree(&tree, &self.repo.objects)?,
+            None => Notes::default(),
+        })
+    }
+
+    fn tree_id(&self) -> Result<Option<ObjectId>, Error> {
+        Ok(match self.commit_id()? {
+            Some(commit) => Some(self.repo.find_commit(commit)?.tree_id()?.detach()),
+            None => None,
+        })
+    }
+}
+
+/// Return the commit `name` points to, or `None` if it doesn't exist.
+fn commit_id(repo: &Repository, name: &FullNameRef) -> Result<Option<ObjectId>, Error> {
+    Ok(match repo.try_find_reference(name)? {
+        Some(mut reference) => Some(reference.peel_to_commit()?.id),
+        None => None,
+    })
+}
+
+/// Turn `name` into a full reference name below `refs/notes/`, like git does.
+fn expand_name(name: &BStr) -> Result<FullName, Error> {
+    let expanded: BString = if name.starts_with(b"refs/notes/") {
+        name.to_owned()
+    } else if name.starts_with(b"notes/") {
+        format!("refs/{name}").into()
+    } else {
+        format!("refs/notes/{name}").into()
+    };
+    FullName::try_from(expanded).map_err(|source| Error::InvalidName {
+        name: name.to_owned(),
+        source,
+    })
+}
+
+///
+pub mod edit;
+///
+pub mod merge;
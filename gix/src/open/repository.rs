@@ -223,12 +223,17 @@ impl ThreadSafeRepository {
                 precompose_unicode: repo_config.precompose_unicode,
                 prohibit_windows_device_names: repo_config.protect_windows,
             };
+            let backend = repo_config.ref_storage;
             match &common_dir {
-                Some(common_dir) => {
-                    crate::RefStore::for_linked_worktree(git_dir.to_owned(), common_dir.into(), ref_store_init_opts)
-                }
-                None => crate::RefStore::at(git_dir.to_owned(), ref_store_init_opts),
-            }
+                Some(common_dir) => gix_ref::Store::for_linked_worktree(
+                    git_dir.to_owned(),
+                    common_dir.into(),
+                    backend,
+                    ref_store_init_opts,
+                ),
+                None => gix_ref::Store::at(git_dir.to_owned(), backend, ref_store_init_opts),
+            }?
+            .to_handle()
         };
         let head = refs.find("HEAD").ok();
         let git_install_dir = crate::path::install_dir().ok();
@@ -413,8 +418,11 @@ impl ThreadSafeRepository {
             config.resolved = resolved.into();
         }
 
-        refs.write_reflog = config::cache::util::reflog_or_default(config.reflog, worktree_dir.is_some());
-        refs.namespace.clone_from(&config.refs_namespace);
+        refs.set_write_reflog(config::cache::util::reflog_or_default(
+            config.reflog,
+            worktree_dir.is_some(),
+        ));
+        refs.set_namespace(config.refs_namespace.clone());
         let prefix = replacement_objects_refs_prefix(&config.resolved, lenient_config, filter_config_section)?;
         let replacements = match prefix {
             Some(prefix) => {
@@ -0,0 +1,119 @@
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::Target;
+
+use crate::bstr::BString;
+use crate::rebase::{todo::Command, Error, Outcome, Platform};
+use crate::worktree::update;
+
+impl Platform<'_> {
+    /// Continue the rebase in progress after it stopped, similar to `git rebase --continue`.
+    ///
+    /// If it stopped due to conflicts, these must have been resolved and added to the index, which is then committed with
+    /// the message and author of the replayed commit, or with `message` if set. If the resolution leaves no changes,
+    /// the commit is dropped.
+    /// If it stopped to edit or reword a commit that `HEAD` still points to, that commit is amended with the changes in
+    /// the index, and with `message` if set.
+    pub fn resume(&self, message: Option<BString>) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let mut state = self.state()?.ok_or(Error::NotInProgress)?;
+        let mut index = gix_index::File::clone(&*repo.index_or_empty()?);
+        if update::is_unmerged(&index) {
+            return Err(Error::UnresolvedConflicts);
+        }
+        let head = repo.head_commit()?;
+        let head_id = head.id;
+        let head = head.decode()?.into_owned();
+        let stopped_command = state
+            .done
+            .lines
+            .last()
+            .map(|line| line.command.clone())
+            .filter(|command| command.commit().is_some());
+
+        if let Some(amend) = state.amend {
+            if amend == head_id {
+                let tree = repo.write_tree(&mut index)?.detach();
+                if tree != head.tree || message.is_some() {
+                    let message = message.unwrap_or(head.message);
+                    let new = self.write_commit(tree, head.parents.to_vec(), head.author, message.clone())?;
+                    self.set_head(new, "continue", message.as_ref())?;
+                }
+            }
+        } else if let Some(command) = stopped_command.filter(|_| state.stopped_at.is_some()) {
+            let tree = repo.write_tree(&mut index)?.detach();
+            if matches!(command, Command::Squash { .. } | Command::Fixup { .. }) {
+                let message = message.or(state.message.take()).unwrap_or(head.message);
+                let new = self.write_commit(tree, head.parents.to_vec(), head.author, message.clone())?;
+                self.set_head(new, "continue", message.as_ref())?;
+            } else if tree != head.tree {
+                let stopped = command.commit().expect("filtered above");
+                let stopped = repo.find_commit(self.peel_to_commit(stopped.to_string().as_str().into())?)?;
+                let stopped = stopped.decode()?;
+                let message = message
+                    .or(state.message.take())
+                    .unwrap_or_else(|| stopped.message.to_owned());
+                let author = state.author.take().unwrap_or_else(|| stopped.author.into());
+                let new = self.write_commit(tree, vec![head_id], author, message.clone())?;
+                self.set_head(new, "continue", message.as_ref())?;
+            }
+        }
+        state.clear_stop();
+        self.remove_rebase_head()?;
+        self.run(state)
+    }
+
+    /// Discard the changes of the commit the rebase stopped at along with all other changes to tracked files,
+    /// and continue with the next command, similar to `git rebase --skip`.
+    pub fn skip(&self) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let mut state = self.state()?.ok_or(Error::NotInProgress)?;
+        repo.checkout_tree(
+            repo.head_tree_id()?,
+            crate::repository::switch::Options {
+                force: true,
+                ..Default::default()
+            },
+        )?;
+        state.clear_stop();
+        self.remove_rebase_head()?;
+        self.run(state)
+    }
+
+    /// Stop the rebase in progress and return to the commit and branch it started from, discarding all changes to
+    /// tracked files, similar to `git rebase --abort`.
+    pub fn abort(&self) -> Result<(), Error> {
+        let repo = self.repo;
+        let state = self.state()?.ok_or(Error::NotInProgress)?;
+        repo.checkout_tree(
+            repo.find_commit(state.orig_head)?.tree_id()?,
+            crate::repository::switch::Options {
+                force: true,
+                ..Default::default()
+            },
+        )?;
+        let (new, message) = match state.head_name {
+            Some(name) => (
+                Target::Symbolic(name.clone()),
+                format!("rebase (abort): returning to {name}"),
+            ),
+            None => (
+                Target::Object(state.orig_head),
+                format!("rebase (abort): returning to {}", state.orig_head),
+            ),
+        };
+        repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.into(),
+                },
+                expected: PreviousValue::Any,
+                new,
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        self.remove_state()
+    }
+}
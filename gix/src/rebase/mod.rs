@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+
+use crate::bstr::BString;
+use crate::merge::tree::Conflict;
+use crate::Repository;
+
+pub use gix_rebase as plumbing;
+pub use gix_rebase::{todo, State};
+
+mod error {
+    use crate::bstr::BString;
+
+    /// The error returned by all operations of the [rebase platform](crate::rebase::Platform).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot rebase in a bare repository")]
+        BareRepository,
+        #[error("Cannot rebase before the initial commit")]
+        UnbornHead,
+        #[error("Cannot start a rebase while another operation is in progress")]
+        InProgress,
+        #[error("There is no rebase in progress")]
+        NotInProgress,
+        #[error("Cannot rebase with uncommitted changes in the index or the worktree")]
+        DirtyWorktree,
+        #[error("The index has unresolved conflicts that need to be resolved and added first")]
+        UnresolvedConflicts,
+        #[error("Cannot replay merge commit {id} as rebasing merges isn't supported")]
+        MergeCommit { id: gix_hash::ObjectId },
+        #[error("The todo command '{name}' isn't supported")]
+        UnsupportedCommand { name: &'static str },
+        #[error("Could not run '{command}'")]
+        SpawnCommand { command: BString, source: std::io::Error },
+        #[error("Committer identity is not configured")]
+        CommitterMissing,
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        State(#[from] gix_rebase::state::Error),
+        #[error(transparent)]
+        RevParse(#[from] crate::revision::spec::parse::single::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        PeelObject(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        HeadTree(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        HeadCommit(#[from] crate::reference::head_commit::Error),
+        #[error(transparent)]
+        IsDirty(#[from] crate::status::is_dirty::Error),
+        #[error(transparent)]
+        CommitGraph(#[from] crate::repository::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        MergeBase(#[from] gix_revision::merge_base::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkIter(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        MergeOptions(#[from] crate::repository::tree_merge_options::Error),
+        #[error(transparent)]
+        MergeTrees(#[from] crate::repository::merge_trees::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        WriteIndexTree(#[from] gix_index::write_tree::Error),
+        #[error(transparent)]
+        Checkout(#[from] crate::repository::switch::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        WriteCommit(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error("Could not remove the rebase state at '{}'", path.display())]
+        RemoveState {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+pub use error::Error;
+
+/// Options for use in [`Platform::start()`].
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// The commit to replay the commits onto, instead of the upstream commit, like `git rebase --onto <newbase>` does.
+    pub onto: Option<gix_hash::ObjectId>,
+    /// If `true`, rearrange the generated todo list so that `fixup!`, `squash!` and `amend!` commits are melded into
+    /// the commits they refer to, like `git rebase --autosquash` does.
+    ///
+    /// It's ignored if a [`todo`](Self::todo) list is given.
+    pub autosquash: bool,
+    /// The todo list to execute instead of the one generated from the commits between the upstream commit and `HEAD`,
+    /// similar to what the user would edit when running `git rebase --interactive`.
+    ///
+    /// Use [`Platform::todo()`] to obtain the generated list for editing.
+    pub todo: Option<todo::List>,
+}
+
+/// The reason a rebase stopped before all commands of the todo list were executed.
+///
+/// Use [`Platform::resume()`] to continue the rebase afterwards, [`Platform::skip()`] to skip the current commit,
+/// or [`Platform::abort()`] to return to the state before the rebase.
+#[derive(Debug, Clone)]
+pub enum Stop {
+    /// The changes of `commit` couldn't be applied cleanly, and the index and the worktree contain the conflicts
+    /// that have to be resolved and added to the index before resuming.
+    Conflicts {
+        /// The commit that was replayed.
+        commit: gix_hash::ObjectId,
+        /// The conflicts encountered when merging, resolved or not.
+        conflicts: Vec<Conflict>,
+    },
+    /// An `edit` command replayed a commit as `commit`, which `HEAD` points to and which can now be amended.
+    ///
+    /// Changes in the index are amended to it when resuming.
+    Edit {
+        /// The replayed commit.
+        commit: gix_hash::ObjectId,
+    },
+    /// A `reword` or `fixup -c` command replayed a commit as `commit`, which `HEAD` points to and whose message is
+    /// replaced by the one passed when resuming.
+    Reword {
+        /// The replayed commit.
+        commit: gix_hash::ObjectId,
+    },
+    /// A `break` command was executed.
+    Break,
+    /// An `exec` command failed.
+    Exec {
+        /// The command that was run.
+        command: BString,
+        /// The status the command exited with.
+        status: std::process::ExitStatus,
+    },
+}
+
+/// The outcome of the operations on the [rebase platform](Platform) that execute the todo list.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    /// All commands were executed, and the rebased branch, if any, was updated and checked out again.
+    Finished {
+        /// The commit `HEAD` points to after the rebase.
+        head: gix_hash::ObjectId,
+    },
+    /// The rebase stopped for the given reason, and its state is kept so that it can be resumed, by git as well.
+    Stopped(Stop),
+}
+
+/// A platform to replay commits onto another commit, similar to `git rebase`, and to continue or abort rebases in
+/// progress, including those started by git.
+///
+/// Obtain it with [`Repository::rebase()`].
+///
+/// The state of a rebase is kept in the [`rebase-merge`](plumbing::STATE_DIR) directory in the format used by git.
+/// Commits are replayed with cherry-pick semantics, i.e. by merging the changes between their parent and themselves into
+/// the tree of `HEAD`. This is a merge of trees with the parent of the replayed commit as merge-base, and not a
+/// [commit-level merge](Repository::merge_commits()) which would compute merge-bases on its own.
+///
+/// ### Deviation
+///
+/// * commits whose changes are already present upstream are only dropped if they become empty, and not detected
+///   upfront by comparing patches.
+/// * the todo commands `label`, `reset`, `merge` and `update-ref` aren't supported, and neither are merge commits, which
+///   fail with [`Error::MergeCommit`] as there is no single parent to use as merge-base.
+/// * messages aren't edited interactively. Instead, the rebase [stops](Stop::Reword) to let the caller provide them.
+#[derive(Clone, Copy)]
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+}
+
+/// Rebasing
+impl Repository {
+    /// Obtain a platform to start, continue or abort rebases.
+    pub fn rebase(&self) -> Platform<'_> {
+        Platform { repo: self }
+    }
+}
+
+impl Platform<'_> {
+    /// Return the state of the rebase in progress, or `None` if there is none.
+    pub fn state(&self) -> Result<Option<State>, Error> {
+        Ok(State::from_dir(&self.state_dir())?)
+    }
+
+    pub(super) fn state_dir(&self) -> PathBuf {
+        self.repo.path().join(plumbing::STATE_DIR)
+    }
+}
+
+mod control;
+mod replay;
+mod start;
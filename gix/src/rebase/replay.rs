@@ -0,0 +1,341 @@
+use gix_hash::ObjectId;
+use gix_rebase::todo::{Command, FixupMessage, Line};
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::Target;
+
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+use crate::merge::tree::{apply_index_entries::RemovalMode, Conflict, TreatAsUnresolved};
+use crate::prelude::ObjectIdExt;
+use crate::rebase::{Error, Outcome, Platform, State, Stop};
+
+/// The name of the reference that points to the commit the rebase stopped at.
+pub(super) const REBASE_HEAD: &str = "REBASE_HEAD";
+
+/// The result of replaying a single commit.
+enum Replayed {
+    /// `HEAD` points to the given commit, which is the previous `HEAD` if the replayed commit became empty.
+    Commit(ObjectId),
+    /// The changes couldn't be applied cleanly, and the index and the worktree contain the conflicts.
+    Conflicts {
+        conflicts: Vec<Conflict>,
+        message: BString,
+        author: gix_actor::Signature,
+    },
+}
+
+impl Platform<'_> {
+    /// Execute the commands of the todo list in `state` until it's empty or a command stops the rebase.
+    pub(super) fn run(&self, mut state: State) -> Result<Outcome, Error> {
+        let dir = self.state_dir();
+        while !state.todo.lines.is_empty() {
+            let line = state.todo.lines.remove(0);
+            state.done.lines.push(line.clone());
+            state.write_to_dir(&dir)?;
+            let stop = match self.execute(&mut state, &line) {
+                Ok(stop) => stop,
+                Err(err) => {
+                    // Put the command back so it's executed again once the cause of the error was addressed.
+                    state.done.lines.pop();
+                    state.todo.lines.insert(0, line);
+                    state.write_to_dir(&dir)?;
+                    return Err(err);
+                }
+            };
+            if let Some(stop) = stop {
+                state.write_to_dir(&dir)?;
+                return Ok(Outcome::Stopped(stop));
+            }
+        }
+        self.finish(&state)
+    }
+
+    fn execute(&self, state: &mut State, line: &Line) -> Result<Option<Stop>, Error> {
+        Ok(match &line.command {
+            Command::Pick { commit }
+            | Command::Reword { commit }
+            | Command::Edit { commit }
+            | Command::Squash { commit }
+            | Command::Fixup { commit, .. } => {
+                let id = self.peel_to_commit(commit.to_string().as_str().into())?;
+                match self.replay(&line.command, id)? {
+                    Replayed::Commit(new) => {
+                        let stop = match &line.command {
+                            Command::Edit { .. } => Stop::Edit { commit: new },
+                            Command::Reword { .. }
+                            | Command::Fixup {
+                                message: FixupMessage::Edit,
+                                ..
+                            } => Stop::Reword { commit: new },
+                            _ => return Ok(None),
+                        };
+                        state.stopped_at = Some(id.into());
+                        state.amend = Some(new);
+                        Some(stop)
+                    }
+                    Replayed::Conflicts {
+                        conflicts,
+                        message,
+                        author,
+                    } => {
+                        self.set_rebase_head(id)?;
+                        state.stopped_at = Some(id.into());
+                        state.message = Some(message);
+                        state.author = Some(author);
+                        Some(Stop::Conflicts { commit: id, conflicts })
+                    }
+                }
+            }
+            Command::Drop { .. } | Command::Noop => None,
+            Command::Break => Some(Stop::Break),
+            Command::Exec { command } => self.exec(command.as_ref())?,
+            Command::Label { .. } | Command::Reset { .. } | Command::Merge { .. } | Command::UpdateRef { .. } => {
+                return Err(Error::UnsupportedCommand {
+                    name: line.command.name(),
+                })
+            }
+        })
+    }
+
+    /// Replay the commit `id` onto `HEAD` as directed by `command`, by merging the changes between its parent and itself
+    /// into the tree of `HEAD`, and update the index and the worktree accordingly.
+    fn replay(&self, command: &Command, id: ObjectId) -> Result<Replayed, Error> {
+        let repo = self.repo;
+        let commit = repo.find_commit(id)?.decode()?.into_owned();
+        if commit.parents.len() > 1 {
+            return Err(Error::MergeCommit { id });
+        }
+        let head = repo.head_commit()?;
+        let head_id = head.id;
+        let head = head.decode()?.into_owned();
+        let is_meld = matches!(command, Command::Squash { .. } | Command::Fixup { .. });
+        let (message, author, parents) = if is_meld {
+            (
+                melded_message(command, head.message.as_ref(), commit.message.as_ref()),
+                head.author,
+                head.parents.to_vec(),
+            )
+        } else {
+            (commit.message.clone(), commit.author.clone(), vec![head_id])
+        };
+
+        if !is_meld && commit.parents.first() == Some(&head_id) {
+            repo.checkout_tree(commit.tree, Default::default())?;
+            self.set_head(id, command.name(), commit.message.as_ref())?;
+            return Ok(Replayed::Commit(id));
+        }
+
+        let base_tree = match commit.parents.first() {
+            Some(parent) => repo.find_commit(*parent)?.tree_id()?.detach(),
+            None => ObjectId::empty_tree(repo.object_hash()),
+        };
+        let other = format!(
+            "{} ({})",
+            id.attach(repo).shorten_or_id(),
+            summary(commit.message.as_ref())
+        );
+        let ancestor = format!("parent of {other}");
+        let mut merge = repo.merge_trees(
+            base_tree,
+            head.tree,
+            commit.tree,
+            gix_merge::blob::builtin_driver::text::Labels {
+                ancestor: Some(ancestor.as_str().into()),
+                current: Some("HEAD".into()),
+                other: Some(other.as_str().into()),
+            },
+            repo.tree_merge_options()?,
+        )?;
+        let tree = merge.tree.write()?.detach();
+        repo.checkout_tree(tree, Default::default())?;
+
+        let how = TreatAsUnresolved::default();
+        if merge.has_unresolved_conflicts(how) {
+            let mut index = repo.open_index()?;
+            merge.index_changed_after_applying_conflicts(&mut index, how, RemovalMode::Prune);
+            index.write(Default::default())?;
+            return Ok(Replayed::Conflicts {
+                conflicts: merge.conflicts,
+                message,
+                author,
+            });
+        }
+        if !is_meld && tree == head.tree && commit.tree != base_tree {
+            // All changes are present already, so the commit would be empty and is dropped.
+            return Ok(Replayed::Commit(head_id));
+        }
+        let new = self.write_commit(tree, parents, author, message.clone())?;
+        self.set_head(new, command.name(), message.as_ref())?;
+        Ok(Replayed::Commit(new))
+    }
+
+    /// Run `command` with the shell in the worktree, and stop if it fails.
+    fn exec(&self, command: &BStr) -> Result<Option<Stop>, Error> {
+        let workdir = self.repo.workdir().ok_or(Error::BareRepository)?;
+        let status =
+            std::process::Command::from(gix_command::prepare(gix_path::from_bstr(command).into_owned()).with_shell())
+                .current_dir(workdir)
+                .status()
+                .map_err(|source| Error::SpawnCommand {
+                    command: command.to_owned(),
+                    source,
+                })?;
+        Ok((!status.success()).then(|| Stop::Exec {
+            command: command.to_owned(),
+            status,
+        }))
+    }
+
+    /// Set the branch the rebase started on to `HEAD` and check it out again, and remove the state of the rebase.
+    fn finish(&self, state: &State) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let head = repo.head_id()?.detach();
+        if let Some(name) = &state.head_name {
+            let log = |message: String| LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: message.into(),
+            };
+            repo.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: log(format!("rebase (finish): {name} onto {}", state.onto)),
+                    expected: PreviousValue::Any,
+                    new: Target::Object(head),
+                },
+                name: name.clone(),
+                deref: false,
+            })?;
+            repo.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: log(format!("rebase (finish): returning to {name}")),
+                    expected: PreviousValue::Any,
+                    new: Target::Symbolic(name.clone()),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: false,
+            })?;
+        }
+        self.remove_state()?;
+        Ok(Outcome::Finished { head })
+    }
+
+    /// Write a new commit with the given properties, with the configured committer.
+    pub(super) fn write_commit(
+        &self,
+        tree: ObjectId,
+        parents: Vec<ObjectId>,
+        author: gix_actor::Signature,
+        message: BString,
+    ) -> Result<ObjectId, Error> {
+        let repo = self.repo;
+        let committer = repo.committer().ok_or(Error::CommitterMissing)??;
+        let commit = gix_object::Commit {
+            message,
+            tree,
+            author,
+            committer: committer.into(),
+            encoding: None,
+            parents: parents.into(),
+            extra_headers: Default::default(),
+        };
+        Ok(repo.write_object(&commit)?.detach())
+    }
+
+    /// Detach `HEAD` at `id`, and log the change as caused by `action` creating a commit with `message`.
+    pub(super) fn set_head(&self, id: ObjectId, action: &str, message: &BStr) -> Result<(), Error> {
+        self.repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("rebase ({action}): {}", summary(message)).into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(id),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(())
+    }
+
+    fn set_rebase_head(&self, id: ObjectId) -> Result<(), Error> {
+        self.repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                expected: PreviousValue::Any,
+                new: Target::Object(id),
+            },
+            name: REBASE_HEAD.try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(())
+    }
+
+    /// Remove the state directory along with `REBASE_HEAD`.
+    pub(super) fn remove_state(&self) -> Result<(), Error> {
+        let dir = self.state_dir();
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(source) => return Err(Error::RemoveState { path: dir, source }),
+        }
+        self.remove_rebase_head()
+    }
+
+    pub(super) fn remove_rebase_head(&self) -> Result<(), Error> {
+        let path = self.repo.path().join(REBASE_HEAD);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(source) => Err(Error::RemoveState { path, source }),
+        }
+    }
+}
+
+/// Return the first line of `message`.
+pub(super) fn summary(message: &BStr) -> BString {
+    gix_object::commit::MessageRef::from_bytes(message)
+        .summary()
+        .into_owned()
+}
+
+/// Produce the message of the commit that results from melding a commit with `melded` as message into a commit with the
+/// `previous` message, as directed by the squash or fixup `command`.
+///
+/// Subjects that start with `squash!`, `fixup!` or `amend!` are removed from `melded`, just like git would
+/// comment them out.
+pub(super) fn melded_message(command: &Command, previous: &BStr, melded: &BStr) -> BString {
+    let body = without_fixup_subject(melded).trim();
+    match command {
+        _ if body.is_empty() => previous.to_owned(),
+        Command::Fixup {
+            message: FixupMessage::Keep,
+            ..
+        } => previous.to_owned(),
+        Command::Fixup { .. } => {
+            let mut out = BString::from(body);
+            out.push(b'\n');
+            out
+        }
+        _ => {
+            let mut out = BString::from(previous.trim_end());
+            out.push_str("\n\n");
+            out.push_str(body);
+            out.push(b'\n');
+            out
+        }
+    }
+}
+
+fn without_fixup_subject(message: &BStr) -> &BStr {
+    let is_fixup = ["squash!", "fixup!", "amend!"]
+        .iter()
+        .any(|prefix| message.starts_with(prefix.as_bytes()));
+    if !is_fixup {
+        return message;
+    }
+    match message.find_byte(b'\n') {
+        Some(pos) => message[pos + 1..].as_bstr(),
+        None => "".into(),
+    }
+}
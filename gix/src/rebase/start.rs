@@ -0,0 +1,125 @@
+use gix_hash::ObjectId;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::Target;
+
+use crate::bstr::BStr;
+use crate::rebase::{replay::summary, todo, Error, Options, Outcome, Platform, State};
+
+impl Platform<'_> {
+    /// Generate the todo list that replays all commits reachable from `HEAD` but not from the commit that the revision
+    /// specification `upstream` resolves to, oldest first, like `git rebase --interactive <upstream>` would present it.
+    ///
+    /// Merge commits are left out, and if `autosquash` is `true`, the list is [rearranged](todo::List::autosquash()).
+    pub fn todo(&self, upstream: impl AsRef<BStr>, autosquash: bool) -> Result<todo::List, Error> {
+        let upstream = self.peel_to_commit(upstream.as_ref())?;
+        let head = self.repo.head()?.id().ok_or(Error::UnbornHead)?.detach();
+        self.todo_between(upstream, head, autosquash)
+    }
+
+    /// Replay all commits reachable from `HEAD` but not from the commit that the revision specification `upstream` resolves
+    /// to onto `upstream`, similar to `git rebase <upstream>`, and configured by `options`.
+    ///
+    /// `HEAD` is detached at the commit to replay onto, and each command of the todo list is executed in turn.
+    /// Once all commands are done, the branch that was checked out, if any, is set to the rebased commits and checked out again.
+    ///
+    /// The index and the worktree must not have changes, but untracked files are kept as long as they are not in the way.
+    pub fn start(&self, upstream: impl AsRef<BStr>, options: Options) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        if repo.workdir().is_none() {
+            return Err(Error::BareRepository);
+        }
+        if repo.state().is_some() || self.state_dir().exists() {
+            return Err(Error::InProgress);
+        }
+        let head = repo.head()?;
+        let orig_head = head.id().ok_or(Error::UnbornHead)?.detach();
+        let head_name = head.referent_name().map(ToOwned::to_owned);
+        if repo.is_dirty()? {
+            return Err(Error::DirtyWorktree);
+        }
+
+        let upstream_name = upstream.as_ref();
+        let upstream = self.peel_to_commit(upstream_name)?;
+        let onto = match options.onto {
+            Some(onto) => repo.find_object(onto)?.peel_to_commit()?.id,
+            None => upstream,
+        };
+        let interactive = options.todo.is_some();
+        let todo = match options.todo {
+            Some(todo) => todo,
+            None => self.todo_between(upstream, orig_head, options.autosquash)?,
+        };
+
+        repo.checkout_tree(repo.find_commit(onto)?.tree_id()?, Default::default())?;
+        let onto_name = match options.onto {
+            Some(_) => onto.to_string(),
+            None => upstream_name.to_string(),
+        };
+        repo.edit_references([
+            RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("rebase (start): checkout {onto_name}").into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Object(onto),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: false,
+            },
+            RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    expected: PreviousValue::Any,
+                    new: Target::Object(orig_head),
+                },
+                name: "ORIG_HEAD".try_into().expect("valid"),
+                deref: false,
+            },
+        ])?;
+
+        let state = State::new(head_name, onto, orig_head, todo, interactive);
+        state.write_to_dir(&self.state_dir())?;
+        self.run(state)
+    }
+
+    pub(super) fn peel_to_commit(&self, spec: &BStr) -> Result<ObjectId, Error> {
+        Ok(self.repo.rev_parse_single(spec)?.object()?.peel_to_commit()?.id)
+    }
+
+    fn todo_between(&self, upstream: ObjectId, head: ObjectId, autosquash: bool) -> Result<todo::List, Error> {
+        let repo = self.repo;
+        let commit_graph = repo.commit_graph_if_enabled()?;
+        let mut graph = repo.revision_graph(commit_graph.as_ref());
+        let merge_bases: Vec<ObjectId> = repo
+            .merge_bases_many_with_graph(head, &[upstream], &mut graph)?
+            .into_iter()
+            .map(crate::Id::detach)
+            .collect();
+
+        let mut lines = Vec::new();
+        for info in repo
+            .rev_walk(Some(head))
+            .selected(move |id| !merge_bases.iter().any(|base| *base == id))?
+        {
+            let info = info?;
+            if info.parent_ids.len() > 1 {
+                continue;
+            }
+            let commit = info.object()?;
+            lines.push(todo::Line {
+                command: todo::Command::Pick { commit: info.id.into() },
+                subject: summary(commit.message_raw_sloppy()),
+            });
+        }
+        lines.reverse();
+
+        let mut todo = todo::List::from(lines);
+        if autosquash {
+            todo.autosquash();
+        }
+        Ok(todo)
+    }
+}
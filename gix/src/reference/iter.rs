@@ -2,26 +2,26 @@
 #![allow(clippy::empty_docs)]
 
 use gix_path::RelativePath;
-use gix_ref::file::ReferenceExt;
+use gix_ref::store::ReferenceExt;
 
 /// A platform to create iterators over references.
 #[must_use = "Iterators should be obtained from this iterator platform"]
 pub struct Platform<'r> {
-    pub(crate) platform: gix_ref::file::iter::Platform<'r>,
+    pub(crate) platform: gix_ref::store::iter::Platform<'r>,
     /// The owning repository.
     pub repo: &'r crate::Repository,
 }
 
 /// An iterator over references, with or without filter.
 pub struct Iter<'r> {
-    inner: gix_ref::file::iter::LooseThenPacked<'r, 'r>,
+    inner: gix_ref::store::iter::Iter<'r>,
     peel_with_packed: Option<gix_ref::file::packed::SharedBufferSnapshot>,
     peel: bool,
     repo: &'r crate::Repository,
 }
 
 impl<'r> Iter<'r> {
-    fn new(repo: &'r crate::Repository, platform: gix_ref::file::iter::LooseThenPacked<'r, 'r>) -> Self {
+    fn new(repo: &'r crate::Repository, platform: gix_ref::store::iter::Iter<'r>) -> Self {
         Iter {
             inner: platform,
             peel_with_packed: None,
@@ -129,11 +129,11 @@ pub mod init {
     #[allow(missing_docs)]
     pub enum Error {
         #[error(transparent)]
-        Io(#[from] std::io::Error),
+        Iter(#[from] gix_ref::store::iter::Error),
         #[error(transparent)]
         RelativePath(#[from] gix_path::relative_path::Error),
     }
 }
 
 /// The error returned by [references()][crate::Repository::references()].
-pub type Error = gix_ref::packed::buffer::open::Error;
+pub type Error = gix_ref::store::iter::Error;
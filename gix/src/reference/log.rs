@@ -1,7 +1,7 @@
 //!
 #![allow(clippy::empty_docs)]
 use gix_object::commit::MessageRef;
-use gix_ref::file::ReferenceExt;
+use gix_ref::store::ReferenceExt;
 
 use crate::{
     bstr::{BStr, BString, ByteVec},
@@ -10,7 +10,7 @@ use crate::{
 
 impl Reference<'_> {
     /// Return a platform for obtaining iterators over reference logs.
-    pub fn log_iter(&self) -> gix_ref::file::log::iter::Platform<'_, '_> {
+    pub fn log_iter(&self) -> gix_ref::store::log::Platform<'_, '_> {
         self.inner.log_iter(&self.repo.refs)
     }
 
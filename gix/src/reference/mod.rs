@@ -1,7 +1,7 @@
 //!
 #![allow(clippy::empty_docs)]
 
-use gix_ref::file::ReferenceExt;
+use gix_ref::store::ReferenceExt;
 
 use crate::{Blob, Commit, Id, Object, Reference, Tag, Tree};
 
@@ -182,7 +182,7 @@ impl<'repo> Reference<'repo> {
     /// Follow this symbolic reference one level and return the ref it refers to.
     ///
     /// Returns `None` if this is not a symbolic reference, hence the leaf of the chain.
-    pub fn follow(&self) -> Option<Result<Reference<'repo>, gix_ref::file::find::existing::Error>> {
+    pub fn follow(&self) -> Option<Result<Reference<'repo>, gix_ref::store::find::existing::Error>> {
         self.inner.follow(&self.repo.refs).map(|res| {
             res.map(|r| Reference {
                 inner: r,
@@ -27,6 +27,18 @@ impl<'repo> Remote<'repo> {
         self.fetch_tags
     }
 
+    /// Return the filter to use when fetching, if this is the remote of a partial clone.
+    pub fn filter(&self) -> Option<remote::fetch::Filter> {
+        self.filter
+    }
+
+    /// Return `true` if this remote promises to provide objects that are missing locally, as it's the remote of a partial clone.
+    ///
+    /// Packs fetched from such a remote are marked with a `.promisor` file.
+    pub fn is_promisor(&self) -> bool {
+        self.promisor
+    }
+
     /// Return the url used for the given `direction` with rewrites from `url.<base>.insteadOf|pushInsteadOf`, unless the instance
     /// was created with one of the `_without_url_rewrite()` methods.
     /// For pushing, this is the `remote.<name>.pushUrl` or the `remote.<name>.url` used for fetching, and for fetching it's
@@ -33,6 +33,15 @@ impl Remote<'_> {
         self
     }
 
+    /// Ask the remote to omit objects as described by `filter` when fetching, or fetch all objects if `None`.
+    ///
+    /// Setting a filter makes this remote a *promisor*, which is expected to provide omitted objects on demand.
+    pub fn with_filter(mut self, filter: Option<remote::fetch::Filter>) -> Self {
+        self.promisor |= filter.is_some();
+        self.filter = filter;
+        self
+    }
+
     fn push_url_inner(
         mut self,
         push_url: gix_url::Url,
@@ -110,7 +110,8 @@ impl<'repo> Remote<'repo> {
         direction: crate::remote::Direction,
     ) -> Result<(gix_url::Url, gix_protocol::transport::Protocol), Error> {
         fn sanitize(mut url: gix_url::Url) -> Result<gix_url::Url, Error> {
-            if url.scheme == gix_url::Scheme::File {
+            // Bundles are served by the transport itself and aren't repositories.
+            if url.scheme == gix_url::Scheme::File && !gix_bundle::is_bundle(gix_path::from_byte_slice(&url.path)) {
                 let mut dir = gix_path::to_native_path_on_windows(Cow::Borrowed(url.path.as_ref()));
                 let kind = gix_discover::is_git(dir.as_ref())
                     .or_else(|_| {
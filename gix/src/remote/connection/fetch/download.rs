@@ -0,0 +1,307 @@
+//! Download bundles advertised by the `bundle-uri` command, and packs listed as `packfile-uris` in the response to a fetch.
+use std::{io::BufRead, sync::atomic::AtomicBool};
+
+use gix_features::progress::DynNestedProgress;
+use gix_protocol::{bundle_uri, fetch::response::PackfileUri};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::tree::Fetch,
+    Repository,
+};
+
+/// The error returned when downloading a bundle or a pack from a URI.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Url(#[from] gix_url::parse::Error),
+    #[error("Cannot download from {uri:?} as only file:// and, if enabled, http(s):// URIs are supported")]
+    UnsupportedScheme { uri: BString },
+    #[error("Refusing to download from {uri:?} as its protocol isn't listed in `fetch.uriProtocols`, which defaults to 'https'")]
+    UnlistedProtocol { uri: BString },
+    #[error("Refusing to download from {uri:?} sent by the remote as `protocol.allow` forbids it")]
+    ForbiddenScheme { uri: BString },
+    #[error(transparent)]
+    SchemePermission(#[from] crate::config::protocol::allow::Error),
+    #[error("Could not open {uri:?} for reading")]
+    Open { uri: BString, source: std::io::Error },
+    #[cfg(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    ))]
+    #[error(transparent)]
+    TransportOptions(#[from] crate::config::transport::Error),
+    #[cfg(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    ))]
+    #[error("Could not configure the HTTP client")]
+    Configure(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[cfg(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    ))]
+    #[error(transparent)]
+    Http(#[from] gix_transport::client::http::Error),
+    #[error("The bundle at {uri:?} couldn't be read")]
+    DecodeBundle {
+        uri: BString,
+        source: gix_bundle::decode::Error,
+    },
+    #[error("The bundle at {uri:?} uses {bundle} object ids, but the repository uses {local}")]
+    IncompatibleObjectHash {
+        uri: BString,
+        local: gix_hash::Kind,
+        bundle: gix_hash::Kind,
+    },
+    #[error("The bundle at {uri:?} requires commit(s) that aren't present: {}", ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    MissingBundlePrerequisites { uri: BString, ids: Vec<gix_hash::ObjectId> },
+    #[error("The bundle at {uri:?} lacks the object {id} its reference {name:?} points to")]
+    MissingBundleTip {
+        uri: BString,
+        name: BString,
+        id: gix_hash::ObjectId,
+    },
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error("The pack downloaded from {uri:?} has hash {actual}, but {expected} was expected")]
+    PackHashMismatch {
+        uri: BString,
+        expected: gix_hash::ObjectId,
+        actual: gix_hash::ObjectId,
+    },
+    #[error("Could not remove the rejected pack at \"{}\"", path.display())]
+    RemovePack {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+}
+
+/// Download the bundles in `list` in the order suggested by the server, and add their objects to the object database of `repo`
+/// using `options`. The tips of each bundle are recorded as references, with `refs/` replaced by `refs/bundles/`, for
+/// subsequent negotiations to build upon them.
+/// `remote_name` is used to configure HTTP downloads, and `progress` and `should_interrupt` are used while indexing packs.
+/// Bundles with a URI whose protocol isn't listed in `protocols` or not permitted by `protocol.allow` are skipped.
+///
+/// Return the amount of bundles that were added. Just like in `git`, failing to use a bundle isn't fatal,
+/// as the fetch that follows can obtain all objects anyway.
+pub(crate) fn bundles(
+    repo: &Repository,
+    remote_name: Option<&BStr>,
+    list: &bundle_uri::List,
+    protocols: &[String],
+    options: &gix_pack::bundle::write::Options,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> usize {
+    let mut added = 0;
+    for bundle in list.bundles_in_download_order() {
+        match add_bundle(
+            repo,
+            remote_name,
+            bundle.uri.as_ref(),
+            protocols,
+            options,
+            progress,
+            should_interrupt,
+        ) {
+            Ok(()) => {
+                added += 1;
+                if list.mode == bundle_uri::Mode::Any {
+                    break;
+                }
+            }
+            Err(_err) => {
+                gix_trace::warn!("Ignoring bundle {:?}: {_err}", bundle.id);
+            }
+        }
+    }
+    added
+}
+
+/// Download all packs listed in `uris` and add them to the object database of `repo` using `options`,
+/// after assuring they have the advertised hash.
+///
+/// `remote_name` is used to configure HTTP downloads, and `progress` and `should_interrupt` are used while indexing packs.
+/// It's an error if the protocol of any of the `uris` isn't listed in `protocols` or not permitted by `protocol.allow`.
+pub(crate) fn packs(
+    repo: &Repository,
+    remote_name: Option<&BStr>,
+    uris: &[PackfileUri],
+    protocols: &[String],
+    options: &gix_pack::bundle::write::Options,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<gix_pack::bundle::write::Outcome>, Error> {
+    let mut out = Vec::with_capacity(uris.len());
+    for PackfileUri { hash, uri } in uris {
+        let mut read = open(repo, remote_name, uri.as_ref(), protocols)?;
+        let outcome = write_pack(repo, &mut read, options, progress, should_interrupt)?;
+        if outcome.index.data_hash != *hash {
+            for path in [&outcome.data_path, &outcome.index_path, &outcome.keep_path]
+                .into_iter()
+                .flatten()
+            {
+                std::fs::remove_file(path).map_err(|source| Error::RemovePack {
+                    path: path.to_owned(),
+                    source,
+                })?;
+            }
+            return Err(Error::PackHashMismatch {
+                uri: uri.clone(),
+                expected: *hash,
+                actual: outcome.index.data_hash,
+            });
+        }
+        out.push(outcome);
+    }
+    Ok(out)
+}
+
+fn add_bundle(
+    repo: &Repository,
+    remote_name: Option<&BStr>,
+    uri: &BStr,
+    protocols: &[String],
+    options: &gix_pack::bundle::write::Options,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<(), Error> {
+    let mut read = open(repo, remote_name, uri, protocols)?;
+    let header = gix_bundle::Header::from_read(&mut read).map_err(|source| Error::DecodeBundle {
+        uri: uri.into(),
+        source,
+    })?;
+    if header.object_hash != repo.object_hash() {
+        return Err(Error::IncompatibleObjectHash {
+            uri: uri.into(),
+            local: repo.object_hash(),
+            bundle: header.object_hash,
+        });
+    }
+    let ids: Vec<_> = header
+        .prerequisites
+        .iter()
+        .map(|prerequisite| prerequisite.id)
+        .filter(|id| !repo.has_object(id))
+        .collect();
+    if !ids.is_empty() {
+        return Err(Error::MissingBundlePrerequisites { uri: uri.into(), ids });
+    }
+
+    let outcome = write_pack(repo, &mut read, options, progress, should_interrupt)?;
+    if let Some(path) = outcome.keep_path {
+        std::fs::remove_file(&path).map_err(|source| Error::RemovePack { path, source })?;
+    }
+    for r in &header.refs {
+        // This also makes the new pack visible to handles that never refresh, like the one used for negotiation.
+        if !repo.has_object(r.id) {
+            return Err(Error::MissingBundleTip {
+                uri: uri.into(),
+                name: r.name.clone(),
+                id: r.id,
+            });
+        }
+    }
+    for r in &header.refs {
+        let Some(name) = r.name.strip_prefix(b"refs/") else {
+            continue;
+        };
+        repo.reference(
+            format!("refs/bundles/{}", name.as_bstr()),
+            r.id,
+            gix_ref::transaction::PreviousValue::Any,
+            "fetched bundle",
+        )?;
+    }
+    Ok(())
+}
+
+fn write_pack(
+    repo: &Repository,
+    read: &mut dyn BufRead,
+    options: &gix_pack::bundle::write::Options,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<gix_pack::bundle::write::Outcome, Error> {
+    Ok(gix_pack::Bundle::write_to_directory(
+        read,
+        Some(&repo.objects.store_ref().path().join("pack")),
+        progress,
+        should_interrupt,
+        Some(Box::new(repo.objects.clone())),
+        options.clone(),
+    )?)
+}
+
+/// Return the protocols listed in `fetch.uriProtocols` if it is set.
+pub(crate) fn configured_protocols(repo: &Repository) -> Option<Vec<String>> {
+    repo.config.resolved.string(Fetch::URI_PROTOCOLS).map(|protocols| {
+        protocols
+            .to_str_lossy()
+            .split(',')
+            .map(str::trim)
+            .filter(|protocol| !protocol.is_empty())
+            .map(ToOwned::to_owned)
+            .collect()
+    })
+}
+
+/// Return the protocols that URIs sent by the remote may use, which is `https` unless configured otherwise.
+pub(crate) fn permitted_protocols(repo: &Repository) -> Vec<String> {
+    configured_protocols(repo).unwrap_or_else(|| vec!["https".into()])
+}
+
+/// Open `uri` for reading, using the HTTP configuration for `remote_name` if it's an HTTP URL.
+///
+/// As `uri` was sent by the remote, its protocol must be listed in `protocols`, and it must be permitted by `protocol.allow`
+/// for URLs that weren't provided by the user.
+#[cfg_attr(
+    not(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    )),
+    allow(unused_variables)
+)]
+fn open(
+    repo: &Repository,
+    remote_name: Option<&BStr>,
+    uri: &BStr,
+    protocols: &[String],
+) -> Result<Box<dyn BufRead>, Error> {
+    let url = gix_url::parse(uri)?;
+    if !protocols.iter().any(|protocol| protocol == url.scheme.as_str()) {
+        return Err(Error::UnlistedProtocol { uri: uri.into() });
+    }
+    if !repo.config.url_scheme()?.allow_from_remote(&url.scheme) {
+        return Err(Error::ForbiddenScheme { uri: uri.into() });
+    }
+    match url.scheme {
+        gix_url::Scheme::File => {
+            let file = std::fs::File::open(gix_path::from_byte_slice(&url.path)).map_err(|source| Error::Open {
+                uri: uri.into(),
+                source,
+            })?;
+            Ok(Box::new(std::io::BufReader::new(file)))
+        }
+        #[cfg(any(
+            feature = "blocking-http-transport-curl",
+            feature = "blocking-http-transport-reqwest"
+        ))]
+        gix_url::Scheme::Http | gix_url::Scheme::Https => {
+            use gix_transport::client::http::Http;
+
+            let mut http = gix_transport::client::http::Impl::default();
+            if let Some(options) = repo.transport_options(uri, remote_name)? {
+                http.configure(&*options).map_err(Error::Configure)?;
+            }
+            let uri = uri.to_str_lossy();
+            let response = http.get(&uri, &uri, std::iter::empty::<&str>())?;
+            Ok(Box::new(response.body))
+        }
+        _ => Err(Error::UnsupportedScheme { uri: uri.into() }),
+    }
+}
@@ -16,6 +16,12 @@ pub enum Error {
         local: gix_hash::Kind,
         remote: gix_hash::Kind,
     },
+    #[error("The bundle to fetch from requires commit(s) that aren't present: {}", ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    MissingBundlePrerequisites { ids: Vec<gix_hash::ObjectId> },
+    #[error(transparent)]
+    BundleUri(#[from] gix_protocol::bundle_uri::Error),
+    #[error(transparent)]
+    PackfileUri(#[from] super::download::Error),
     #[error(transparent)]
     LoadAlternates(#[from] gix_odb::store::load_index::Error),
     #[error(transparent)]
@@ -27,6 +33,20 @@ pub enum Error {
         path: std::path::PathBuf,
         source: std::io::Error,
     },
+    #[error("Failed to write .promisor file at \"{}\"", path.display())]
+    WritePackPromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not obtain configuration to learn if FETCH_HEAD should be written")]
+    WriteFetchHeadConfig(#[source] config::boolean::Error),
+    #[error("Failed to lock FETCH_HEAD for writing")]
+    LockFetchHead(#[source] gix_lock::acquire::Error),
+    #[error("Failed to write FETCH_HEAD file at \"{}\"", path.display())]
+    WriteFetchHead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
     #[error("None of the refspec(s) {} matched any of the {num_remote_refs} refs on the remote", refspecs.iter().map(|r| r.to_ref().instruction().to_bstring().to_string()).collect::<Vec<_>>().join(", "))]
     NoMapping {
         refspecs: Vec<gix_refspec::RefSpec>,
@@ -36,12 +56,16 @@ pub enum Error {
     RejectShallowRemoteConfig(#[from] config::boolean::Error),
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[cfg(all(feature = "attributes", feature = "blocking-network-client"))]
+    #[error(transparent)]
+    FetchSubmodules(#[from] crate::submodule::fetch::Error),
 }
 
 impl gix_protocol::transport::IsSpuriousError for Error {
     fn is_spurious(&self) -> bool {
         match self {
             Error::Fetch(err) => err.is_spurious(),
+            Error::BundleUri(err) => err.is_spurious(),
             Error::Client(err) => err.is_spurious(),
             _ => false,
         }
@@ -0,0 +1,103 @@
+use std::borrow::Cow;
+
+use gix_fetchhead::{Entry, FetchHead};
+use gix_protocol::fetch::refmap::{Mapping, Source, SpecIndex};
+
+use crate::{
+    bstr::{BStr, ByteSlice},
+    config::tree::Branch,
+    remote,
+    remote::fetch::{refs, RefMap},
+    Remote,
+};
+
+/// Compose the `FETCH_HEAD` file for all `mappings` of `ref_map` that were fetched from `remote`, with the entries to merge first.
+///
+/// Implied tags are only recorded if they are new to us, as `git` only follows tags it doesn't have yet.
+///
+/// Which entries are meant to be merged is determined like `git fetch` does it without refspecs on the command-line:
+///
+/// * if the current branch has `branch.<name>.merge` configured and its `branch.<name>.remote` is `remote`, the configured refs are merged.
+/// * if the current branch has no `branch.<name>.merge`, the first ref matched by the first refspec is merged unless it's a pattern.
+/// * if `remote` has no name as it was created from a URL, all refs that were asked for are merged, similar to refspecs on the command-line.
+pub(crate) fn compose(remote: &Remote<'_>, ref_map: &RefMap, updates: &refs::update::Outcome) -> FetchHead {
+    let repo = remote.repo;
+    let url = remote
+        .url(remote::Direction::Fetch)
+        .map(|url| {
+            let mut url = url.clone();
+            url.set_user(None);
+            url.set_password(None);
+            url.to_bstring()
+        })
+        .unwrap_or_default();
+
+    let merge_refs = repo.head_name().ok().flatten().and_then(|head| {
+        let short_name = head
+            .as_ref()
+            .category_and_short_name()
+            .and_then(|(category, short_name)| (category == gix_ref::Category::LocalBranch).then_some(short_name))?;
+        let merge_refs = repo
+            .config
+            .resolved
+            .strings_by("branch", Some(short_name), Branch::MERGE.name)
+            .unwrap_or_default();
+        let is_our_remote = remote.name().is_some_and(|name| {
+            repo.branch_remote_name(short_name, remote::Direction::Fetch)
+                .is_some_and(|branch_remote| branch_remote.as_bstr() == name.as_bstr())
+        });
+        (!merge_refs.is_empty()).then(|| {
+            merge_refs
+                .into_iter()
+                .filter(|_| is_our_remote)
+                .filter_map(|name| crate::config::tree::branch::Merge::try_into_fullrefname(name).ok())
+                .map(|name| name.as_bstr().to_owned())
+                .collect::<Vec<_>>()
+        })
+    });
+    let first_spec_is_pattern = remote
+        .refspecs(remote::Direction::Fetch)
+        .first()
+        .and_then(|spec| spec.to_ref().remote().map(|name| name.contains(&b'*')))
+        .unwrap_or(true);
+
+    let first_of_first_spec = ref_map
+        .mappings
+        .iter()
+        .position(|mapping| mapping.spec_index == SpecIndex::ExplicitInRemote(0))
+        .filter(|_| !first_spec_is_pattern);
+
+    let mut entries: Vec<_> = updates
+        .updates
+        .iter()
+        .zip(&ref_map.mappings)
+        .enumerate()
+        .filter(|(_, (update, mapping))| match mapping.spec_index {
+            SpecIndex::Implicit(_) => matches!(update.mode, refs::update::Mode::New),
+            SpecIndex::ExplicitInRemote(_) => {
+                !matches!(update.mode, refs::update::Mode::RejectedSourceObjectNotFound { .. })
+            }
+        })
+        .filter_map(|(idx, (_, mapping))| {
+            let (name, id) = remote_name_and_id(mapping)?;
+            let merge = match (&merge_refs, remote.name()) {
+                (_, None) => matches!(mapping.spec_index, SpecIndex::ExplicitInRemote(_)),
+                (Some(merge_refs), Some(_)) => merge_refs.iter().any(|merge_ref| merge_ref.as_bstr() == name.as_ref()),
+                (None, Some(_)) => first_of_first_spec == Some(idx),
+            };
+            Some(Entry::new(id, !merge, name.as_ref(), url.as_ref()))
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.not_for_merge);
+    FetchHead { entries }
+}
+
+fn remote_name_and_id(mapping: &Mapping) -> Option<(Cow<'_, BStr>, gix_hash::ObjectId)> {
+    match &mapping.remote {
+        Source::ObjectId(id) => Some((Cow::Owned(id.to_string().into()), *id)),
+        Source::Ref(r) => {
+            let (name, id, _peeled) = r.unpack();
+            Some((Cow::Borrowed(name), id?.to_owned()))
+        }
+    }
+}
@@ -76,6 +76,10 @@ pub struct Outcome {
     pub handshake: gix_protocol::handshake::Outcome,
     /// The status of the operation to indicate what happened.
     pub status: Status,
+    /// The outcome of fetching into submodules, if [recursing into submodules](Prepare::with_recurse_submodules()) was
+    /// enabled or configured.
+    #[cfg(all(feature = "attributes", feature = "blocking-network-client"))]
+    pub submodules: Vec<crate::submodule::fetch::Outcome>,
 }
 
 /// Additional types related to the outcome of a fetch operation.
@@ -149,7 +153,11 @@ where
             dry_run: DryRun::No,
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
+            write_fetch_head: None,
             shallow: Default::default(),
+            bundle_uri: false,
+            #[cfg(all(feature = "attributes", feature = "blocking-network-client"))]
+            recurse_submodules: None,
         })
     }
 }
@@ -165,6 +173,8 @@ where
 }
 
 mod config;
+pub mod download;
+mod fetch_head;
 mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
@@ -180,7 +190,11 @@ where
     dry_run: DryRun,
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
+    write_fetch_head: Option<bool>,
     shallow: remote::fetch::Shallow,
+    bundle_uri: bool,
+    #[cfg(all(feature = "attributes", feature = "blocking-network-client"))]
+    recurse_submodules: Option<crate::submodule::config::FetchRecurse>,
 }
 
 /// Builder
@@ -209,6 +223,13 @@ where
         self
     }
 
+    /// If enabled, record all fetched refs in `FETCH_HEAD` like `git fetch` does, overriding `fetch.writeFetchHead`
+    /// which is used otherwise and defaults to `true`.
+    pub fn with_write_fetch_head(mut self, enabled: bool) -> Self {
+        self.write_fetch_head = Some(enabled);
+        self
+    }
+
     /// Set the reflog message to use when updating refs after fetching a pack.
     pub fn with_reflog_message(mut self, reflog_message: RefLogMessage) -> Self {
         self.reflog_message = reflog_message.into();
@@ -222,4 +243,27 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// If enabled, ask the remote for bundles to download before fetching using the `bundle-uri` command, like `git clone` does
+    /// if `transfer.bundleURI` is set. This is disabled by default.
+    ///
+    /// The objects of the bundles are added to the object database, and their tips are recorded in `refs/bundles/*`, so that
+    /// only what's missing has to be fetched afterwards.
+    /// Nothing happens if the remote doesn't support the `bundle-uri` command, and bundles that can't be downloaded or used are
+    /// skipped.
+    pub fn with_bundle_uri(mut self, enabled: bool) -> Self {
+        self.bundle_uri = enabled;
+        self
+    }
+
+    /// Decide in which submodules to fetch once the fetch into this repository is complete, like
+    /// `git fetch --recurse-submodules=<mode>` does.
+    ///
+    /// If `None`, which is the default, submodules are only fetched into if `fetch.recurseSubmodules` is configured.
+    /// See [`Repository::fetch_submodules()`](crate::Repository::fetch_submodules()) for details.
+    #[cfg(all(feature = "attributes", feature = "blocking-network-client"))]
+    pub fn with_recurse_submodules(mut self, mode: Option<crate::submodule::config::FetchRecurse>) -> Self {
+        self.recurse_submodules = mode;
+        self
+    }
 }
@@ -5,7 +5,7 @@ use crate::{
     },
     remote,
     remote::{
-        connection::fetch::config,
+        connection::fetch::{config, download, fetch_head},
         fetch,
         fetch::{negotiate::Algorithm, outcome, refs, Error, Outcome, Prepare, RefLogMessage, Status},
     },
@@ -63,12 +63,41 @@ where
     /// Currently, the entire process of resolving a pack is blocking the executor. This can be fixed using the `blocking` crate, but it
     /// didn't seem worth the tradeoff of having more complex code.
     ///
+    /// ### Partial clones
+    ///
+    /// If the remote has a [filter](crate::Remote::filter()), the server is asked to omit the respective objects, which fails
+    /// if it doesn't support filters at all.
+    /// Packs received from [promisor remotes](crate::Remote::is_promisor()) are marked with a `.promisor` file next to them,
+    /// to indicate that objects they refer to may be missing locally.
+    ///
+    /// ### `FETCH_HEAD`
+    ///
+    /// Unless disabled with [`with_write_fetch_head()`](Self::with_write_fetch_head()), with `fetch.writeFetchHead`, or in dry-run mode,
+    /// all fetched refs are recorded in `FETCH_HEAD` just like `git fetch` does it, with the refs to merge determined by the configuration
+    /// of the current branch.
+    ///
+    /// ### Submodules
+    ///
+    /// If [enabled](Self::with_recurse_submodules()) or if `fetch.recurseSubmodules` is configured, populated submodules
+    /// are fetched into afterwards, with the outcome available in `Outcome::submodules`.
+    ///
+    /// ### Bundles and pack URIs
+    ///
+    /// If [enabled](Self::with_bundle_uri()), bundles advertised by the remote are downloaded and added before fetching.
+    /// If `fetch.uriProtocols` is set, the remote may leave out objects from the pack it sends and list packs to download
+    /// with any of these protocols instead, which is supported for `file://` and, if an HTTP client is enabled, `http(s)://`.
+    /// These packs are verified to have the advertised hash, and the fetch fails if any of them can't be obtained.
+    ///
+    /// As the URIs of bundles and packs are chosen by the remote, they are only used if their protocol is listed in
+    /// `fetch.uriProtocols`, or is `https` if unset, and if `protocol.allow` permits it for URLs not provided by the user.
+    /// Hence `file://` URIs are only used if `protocol.file.allow` is `always`.
+    ///
     /// ### Configuration
     ///
     /// - `gitoxide.userAgent` is read to obtain the application user agent for git servers and for HTTP servers as well.
     ///
     #[gix_protocol::maybe_async::maybe_async]
-    pub async fn receive<P>(mut self, progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
+    pub async fn receive<P>(mut self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
     where
         P: gix_features::progress::NestedProgress,
         P::SubProgress: 'static,
@@ -95,6 +124,62 @@ where
             });
         }
 
+        // Bundles are sent as a whole, and the commits they build upon must be present for the result to be usable.
+        if let Some(bundle) = con
+            .remote
+            .url(remote::Direction::Fetch)
+            .filter(|url| url.scheme == gix_url::Scheme::File)
+            .map(|url| gix_path::from_byte_slice(&url.path))
+            .filter(|path| gix_bundle::is_bundle(path))
+            .and_then(|path| gix_bundle::File::at(path).ok())
+        {
+            let ids: Vec<_> = bundle
+                .header
+                .prerequisites
+                .iter()
+                .map(|prerequisite| prerequisite.id)
+                .filter(|id| !repo.has_object(id))
+                .collect();
+            if !ids.is_empty() {
+                return Err(Error::MissingBundlePrerequisites { ids });
+            }
+        }
+
+        let write_pack_options = gix_pack::bundle::write::Options {
+            thread_limit: config::index_threads(repo)?,
+            index_version: config::pack_index_version(repo)?,
+            iteration_mode: gix_pack::data::input::Mode::Verify,
+            object_hash: con.remote.repo.object_hash(),
+        };
+        let remote_name = con.remote.name().map(crate::remote::Name::as_bstr);
+        if self.bundle_uri
+            && matches!(self.dry_run, fetch::DryRun::No)
+            && handshake.server_protocol_version == gix_protocol::transport::Protocol::V2
+            && handshake
+                .capabilities
+                .capability(gix_protocol::Command::BundleUri.as_str())
+                .is_some()
+        {
+            let list = gix_protocol::bundle_uri(
+                &mut con.transport.inner,
+                &handshake.capabilities,
+                repo.config.user_agent_tuple(),
+                con.trace,
+            )
+            .await?;
+            let mut bundle_progress = progress.add_child("bundles");
+            download::bundles(
+                repo,
+                remote_name,
+                &list,
+                &download::permitted_protocols(repo),
+                &write_pack_options,
+                &mut bundle_progress,
+                should_interrupt,
+            );
+        }
+        let mut packfile_uri_progress = progress.add_child("packfile-uris");
+
         let fetch_options = gix_protocol::fetch::Options {
             shallow_file: repo.shallow_file(),
             shallow: &self.shallow,
@@ -106,6 +191,8 @@ where
                 .map(|val| Clone::REJECT_SHALLOW.enrich_error(val))
                 .transpose()?
                 .unwrap_or(false),
+            filter: con.remote.filter,
+            packfile_uri_protocols: download::configured_protocols(repo).unwrap_or_default(),
         };
         let context = gix_protocol::fetch::Context {
             handshake: &mut handshake,
@@ -127,6 +214,8 @@ where
             let mut r = repo.clone();
             // assure that checking for unknown server refs doesn't trigger ODB refreshes.
             r.objects.refresh = RefreshMode::Never;
+            // objects missing in partial clones are skipped during negotiation instead of being fetched one by one.
+            r.objects.unset_fetch_missing();
             // we cache everything of importance in the graph and thus don't need an object cache.
             r.objects.unset_object_cache();
             r
@@ -146,12 +235,6 @@ where
             open_options: repo.options.clone(),
         };
 
-        let write_pack_options = gix_pack::bundle::write::Options {
-            thread_limit: config::index_threads(repo)?,
-            index_version: config::pack_index_version(repo)?,
-            iteration_mode: gix_pack::data::input::Mode::Verify,
-            object_hash: con.remote.repo.object_hash(),
-        };
         let mut write_pack_bundle = None;
 
         let res = gix_protocol::fetch(
@@ -168,7 +251,7 @@ where
                             let repo = repo.clone();
                             repo.objects
                         })),
-                        write_pack_options,
+                        write_pack_options.clone(),
                     )?;
                     may_read_to_end = true;
                     Some(res)
@@ -183,6 +266,10 @@ where
             fetch_options,
         )
         .await?;
+        let packfile_uris = res
+            .as_ref()
+            .map(|res| res.last_response.packfile_uris().to_vec())
+            .unwrap_or_default();
         let negotiate = res.map(|v| outcome::Negotiate {
             graph: graph.detach(),
             rounds: v.negotiate.rounds,
@@ -194,6 +281,32 @@ where
                 .ok();
         }
 
+        // The pack we received leaves out the objects in these packs, so the fetch is only complete once they are present as well.
+        let uri_packs = if matches!(self.dry_run, fetch::DryRun::No) {
+            download::packs(
+                repo,
+                remote_name,
+                &packfile_uris,
+                &download::permitted_protocols(repo),
+                &write_pack_options,
+                &mut packfile_uri_progress,
+                should_interrupt,
+            )?
+        } else {
+            Vec::new()
+        };
+
+        if con.remote.promisor {
+            if let Some(path) = write_pack_bundle
+                .as_ref()
+                .and_then(|bundle| bundle.data_path.as_ref())
+                .map(|path| path.with_extension("promisor"))
+            {
+                std::fs::write(&path, promisor_file_content(&self.ref_map))
+                    .map_err(|source| Error::WritePackPromisorFile { path, source })?;
+            }
+        }
+
         let update_refs = refs::update(
             repo,
             self.reflog_message
@@ -215,9 +328,65 @@ where
             }
         }
 
+        for path in uri_packs.into_iter().filter_map(|bundle| bundle.keep_path) {
+            std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+        }
+
+        let write_fetch_head = match self.write_fetch_head {
+            Some(enabled) => enabled,
+            None => repo
+                .config
+                .resolved
+                .boolean(Fetch::WRITE_FETCH_HEAD)
+                .map(|val| Fetch::WRITE_FETCH_HEAD.enrich_error(val))
+                .transpose()
+                .with_leniency(repo.config.lenient_config)
+                .map_err(Error::WriteFetchHeadConfig)?
+                .unwrap_or(true),
+        };
+        if write_fetch_head && matches!(self.dry_run, fetch::DryRun::No) {
+            let path = repo.git_dir().join("FETCH_HEAD");
+            let fetch_head = fetch_head::compose(con.remote, &self.ref_map, &update_refs);
+            let mut lock =
+                gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)
+                    .map_err(Error::LockFetchHead)?;
+            std::io::Write::write_all(&mut lock, &fetch_head.to_bytes())
+                .and_then(|_| lock.commit().map(|_| ()).map_err(|err| err.error))
+                .map_err(|source| Error::WriteFetchHead { path, source })?;
+        }
+
+        #[cfg(all(feature = "attributes", feature = "blocking-network-client"))]
+        let submodules = {
+            let is_configured = repo.config.resolved.boolean("fetch.recurseSubmodules").is_some();
+            if (self.recurse_submodules.is_some() || is_configured) && matches!(self.dry_run, fetch::DryRun::No) {
+                let commits = update_refs
+                    .edits
+                    .iter()
+                    .filter_map(|edit| match &edit.change {
+                        gix_ref::transaction::Change::Update {
+                            new: gix_ref::Target::Object(id),
+                            ..
+                        } => Some(*id),
+                        _ => None,
+                    })
+                    .collect();
+                repo.fetch_submodules(
+                    &crate::submodule::fetch::Options {
+                        recurse: self.recurse_submodules,
+                        commits,
+                    },
+                    should_interrupt,
+                )?
+            } else {
+                Vec::new()
+            }
+        };
+
         let out = Outcome {
             handshake,
             ref_map: std::mem::take(&mut self.ref_map),
+            #[cfg(all(feature = "attributes", feature = "blocking-network-client"))]
+            submodules,
             status: match write_pack_bundle {
                 Some(write_pack_bundle) => Status::Change {
                     write_pack_bundle,
@@ -235,9 +404,24 @@ where
     }
 }
 
+/// Produce the content of a `.promisor` file like `git` does, with one line per fetched ref and the object it points to.
+fn promisor_file_content(ref_map: &fetch::RefMap) -> Vec<u8> {
+    let mut out = Vec::new();
+    for mapping in &ref_map.mappings {
+        let Some(id) = mapping.remote.as_id().map(|id| id.to_string()) else {
+            continue;
+        };
+        out.extend_from_slice(id.as_bytes());
+        out.push(b' ');
+        out.extend_from_slice(mapping.remote.as_name().map_or(id.as_bytes(), AsRef::as_ref));
+        out.push(b'\n');
+    }
+    out
+}
+
 struct Negotiate<'a, 'b, 'c> {
     objects: &'a crate::OdbHandle,
-    refs: &'a gix_ref::file::Store,
+    refs: &'a gix_ref::store::Handle,
     graph: &'a mut gix_negotiate::Graph<'b, 'c>,
     alternates: Vec<PathBuf>,
     ref_map: &'a gix_protocol::fetch::RefMap,
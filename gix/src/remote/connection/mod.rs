@@ -26,3 +26,7 @@ pub mod ref_map;
 
 ///
 pub mod fetch;
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod push;
@@ -0,0 +1,24 @@
+/// The error returned by [`transmit()`](super::Prepare::transmit()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Push(#[from] gix_protocol::push::Error),
+    #[error("Could not open the object database for use in multiple threads")]
+    OpenObjectDatabase(#[from] std::io::Error),
+    #[error("Could not traverse the commits to send")]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error("Could not count the objects to send")]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error("Could not update the remote-tracking references")]
+    UpdateTrackingRefs(#[from] crate::reference::edit::Error),
+}
+
+impl gix_protocol::transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Push(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
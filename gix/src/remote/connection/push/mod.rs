@@ -0,0 +1,328 @@
+use gix_protocol::transport::client::Transport;
+
+use crate::{
+    bstr::BString,
+    remote::{Connection, Direction},
+    Progress,
+};
+
+mod error;
+pub use error::Error;
+
+mod resolve;
+mod send_pack;
+
+/// A lease to implement `--force-with-lease` semantics for the remote reference it refers to.
+///
+/// The update of the remote reference will only be sent if the remote reference currently has the expected value,
+/// in which case it is also allowed to not be a fast-forward. As the remote checks this expectation atomically as well,
+/// this is safe even in the presence of concurrent pushes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lease {
+    /// The full name of the reference on the remote, like `refs/heads/main`.
+    pub remote_ref: gix_ref::FullName,
+    /// The value the remote reference is expected to have, with the null-hash indicating that it is expected not to exist.
+    ///
+    /// If `None`, the value of the local remote-tracking branch for `remote_ref` is expected, and the lease fails
+    /// if there is no such branch.
+    pub expected: Option<gix_hash::ObjectId>,
+}
+
+/// For use in [`Connection::prepare_push()`].
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// The refspecs to use for determining what to push instead of the ones configured for the remote.
+    ///
+    /// If empty, and the remote has no push refspecs configured, `push.default` decides which branch to push,
+    /// similar to `git push` without further arguments.
+    pub refspecs: Vec<gix_refspec::RefSpec>,
+    /// The leases for remote references, to allow non-fast-forward updates only if the remote is in the expected state.
+    pub leases: Vec<Lease>,
+    /// If `true`, either all reference updates are applied or none of them.
+    /// This requires the `atomic` capability to be supported by the remote.
+    pub atomic: bool,
+    /// Parameters in the form of `(name, optional value)` to add to the handshake.
+    ///
+    /// This is useful in case of custom servers.
+    pub handshake_parameters: Vec<(String, Option<String>)>,
+}
+
+/// The outcome of [`Prepare::transmit()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The outcome of the handshake with the server, containing the remote references as they were before the push.
+    pub handshake: gix_protocol::handshake::Outcome,
+    /// All reference updates we considered, along with what happened to them.
+    pub updates: Vec<update::Update>,
+    /// If `Some`, the remote reported that it couldn't unpack the objects we sent, with the given reason.
+    pub unpack_error: Option<BString>,
+}
+
+impl Outcome {
+    /// Return `true` if all updates were applied by the remote, or didn't need to be applied as the remote was up-to-date.
+    pub fn is_success(&self) -> bool {
+        self.unpack_error.is_none()
+            && self
+                .updates
+                .iter()
+                .all(|u| matches!(u.status, update::Status::Ok | update::Status::UpToDate))
+    }
+}
+
+///
+pub mod update {
+    use crate::bstr::BString;
+
+    /// The kind of update as determined locally, before anything is sent to the remote.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Mode {
+        /// The remote reference will be created.
+        Create,
+        /// The remote reference will be updated, and the new value has the old value as ancestor.
+        FastForward,
+        /// The remote reference will be updated even though the new value doesn't have the old value as ancestor,
+        /// as permitted by the refspec or a lease.
+        Forced,
+        /// The remote reference will be deleted.
+        Delete,
+        /// The remote reference already has the desired value.
+        NoChangeNeeded,
+        /// The update will not be sent for the given reason.
+        Rejected(Rejection),
+    }
+
+    /// The reason for not sending an update to the remote.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Rejection {
+        /// The new value doesn't have the remote value as ancestor, and forcing wasn't permitted.
+        NonFastForward,
+        /// The remote reference points to an object we don't have locally, so we can't tell if this is a fast-forward.
+        FetchFirst,
+        /// The remote reference is a tag which already exists, and forcing wasn't permitted.
+        AlreadyExists,
+        /// The remote reference wasn't in the state the [lease](super::Lease) expected.
+        StaleLease,
+        /// The remote reference to delete doesn't exist.
+        DeleteNonExisting,
+        /// The remote doesn't allow deleting references.
+        DeleteUnsupported,
+        /// An atomic push was requested, but another update was rejected so nothing was sent.
+        AtomicPushFailed,
+    }
+
+    /// What happened to an update after the push.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Status {
+        /// There was nothing to do as the remote already had the desired value.
+        UpToDate,
+        /// The remote applied the update.
+        Ok,
+        /// The update wasn't sent as it was rejected locally.
+        Rejected(Rejection),
+        /// The remote rejected the update for the given `reason`, or didn't mention it in its status report.
+        RemoteRejected {
+            /// The reason as given by the remote, like `non-fast-forward` or `pre-receive hook declined`.
+            reason: BString,
+        },
+    }
+
+    /// A single update of a remote reference.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Update {
+        /// The full name of the reference on the remote side.
+        pub remote_ref: gix_ref::FullName,
+        /// The local reference the new value was obtained from, if it was a reference at all.
+        pub local_ref: Option<gix_ref::FullName>,
+        /// The value of the remote reference before the push, or `None` if it didn't exist.
+        pub old: Option<gix_hash::ObjectId>,
+        /// The value the remote reference should have after the push, or `None` if it should be deleted.
+        pub new: Option<gix_hash::ObjectId>,
+        /// The kind of update we determined locally.
+        pub mode: Mode,
+        /// What happened to the update, which is `Rejected` or `UpToDate` for all updates that aren't sent.
+        pub status: Status,
+    }
+}
+
+///
+pub mod prepare {
+    use crate::bstr::BString;
+
+    /// The error returned by [`prepare_push()`][super::Connection::prepare_push()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to configure the transport before connecting to {url:?}")]
+        GatherTransportConfig {
+            url: BString,
+            source: crate::config::transport::Error,
+        },
+        #[error("Failed to configure the transport layer")]
+        ConfigureTransport(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        Handshake(#[from] gix_protocol::handshake::Error),
+        #[error(transparent)]
+        ConfigureCredentials(#[from] crate::config::credential_helpers::Error),
+        #[error("Cannot push to a remote that uses {remote} while local repository uses {local} for object hashes")]
+        IncompatibleObjectHash {
+            local: gix_hash::Kind,
+            remote: gix_hash::Kind,
+        },
+        #[error("The remote uses the object format {format:?}, which isn't supported")]
+        UnknownObjectFormat { format: BString },
+        #[error("The remote didn't provide its references during the handshake")]
+        MissingRemoteRefs,
+        #[error("The remote doesn't support atomic pushes")]
+        AtomicUnsupported,
+        #[error(transparent)]
+        Resolve(#[from] super::resolve::Error),
+    }
+
+    impl gix_protocol::transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Handshake(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+
+impl<'remote, 'repo, T> Connection<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    /// Perform a handshake with `git-receive-pack` on the remote and determine which remote references to update with `options`.
+    /// Note that at this point, the `transport` should already be configured using the [`transport_mut()`][Self::transport_mut()]
+    /// method, as it will be consumed here.
+    ///
+    /// Use [`Prepare::updates()`] to see what would be pushed, and [`Prepare::transmit()`] to actually push.
+    ///
+    /// ### Configuration
+    ///
+    /// - `push.default` is used if neither `options` nor the remote provide push refspecs.
+    /// - `gitoxide.userAgent` is read to obtain the application user agent for git servers and for HTTP servers as well.
+    #[allow(clippy::result_large_err)]
+    pub fn prepare_push(
+        mut self,
+        mut progress: impl Progress,
+        options: Options,
+    ) -> Result<Prepare<'remote, 'repo, T>, prepare::Error> {
+        let _span = gix_trace::coarse!("remote::Connection::prepare_push()");
+        let mut credentials_storage;
+        let url = self.transport.inner.to_url();
+        let authenticate = match self.authenticate.as_mut() {
+            Some(f) => f,
+            None => {
+                let url = self.remote.url(Direction::Push).map_or_else(
+                    || gix_url::parse(url.as_ref()).expect("valid URL to be provided by transport"),
+                    ToOwned::to_owned,
+                );
+                credentials_storage = self.configured_credentials(url)?;
+                &mut credentials_storage
+            }
+        };
+
+        let repo = self.remote.repo;
+        if self.transport_options.is_none() {
+            self.transport_options = repo
+                .transport_options(url.as_ref(), self.remote.name().map(crate::remote::Name::as_bstr))
+                .map_err(|err| prepare::Error::GatherTransportConfig {
+                    source: err,
+                    url: url.into_owned(),
+                })?;
+        }
+        if let Some(config) = self.transport_options.as_ref() {
+            self.transport.inner.configure(&**config)?;
+        }
+        let handshake = gix_protocol::push::handshake(
+            &mut self.transport.inner,
+            authenticate,
+            options.handshake_parameters.clone(),
+            &mut progress,
+        )?;
+        let remote_refs = handshake.refs.as_deref().ok_or(prepare::Error::MissingRemoteRefs)?;
+
+        let remote_object_hash = match handshake
+            .capabilities
+            .capability("object-format")
+            .and_then(|c| c.value())
+        {
+            Some(format) if format == "sha1" => gix_hash::Kind::Sha1,
+            Some(format) if format == "sha256" => gix_hash::Kind::Sha256,
+            Some(unknown) => {
+                return Err(prepare::Error::UnknownObjectFormat {
+                    format: unknown.to_owned(),
+                })
+            }
+            None => gix_hash::Kind::Sha1,
+        };
+        if remote_object_hash != repo.object_hash() {
+            return Err(prepare::Error::IncompatibleObjectHash {
+                local: repo.object_hash(),
+                remote: remote_object_hash,
+            });
+        }
+
+        let mut arguments =
+            gix_protocol::push::Arguments::new(&handshake.capabilities, repo.config.user_agent_tuple().1.as_deref());
+        if options.atomic {
+            if !arguments.can_use_atomic() {
+                return Err(prepare::Error::AtomicUnsupported);
+            }
+            arguments.use_atomic();
+        }
+        let updates = resolve::updates(self.remote, remote_refs, &options, arguments.can_delete_refs())?;
+
+        Ok(Prepare {
+            con: Some(self),
+            handshake,
+            arguments,
+            updates,
+            dry_run: false,
+        })
+    }
+}
+
+/// A structure to hold the result of the handshake with the remote along with all reference updates we want to perform,
+/// ready to [transmit](Prepare::transmit()) them.
+pub struct Prepare<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    con: Option<Connection<'remote, 'repo, T>>,
+    handshake: gix_protocol::handshake::Outcome,
+    arguments: gix_protocol::push::Arguments,
+    updates: Vec<update::Update>,
+    dry_run: bool,
+}
+
+/// Builder
+impl<T> Prepare<'_, '_, T>
+where
+    T: Transport,
+{
+    /// If dry run is enabled, no pack will be sent and the remote won't be changed.
+    ///
+    /// All updates that would be sent will be reported with [`Status::Ok`](update::Status::Ok).
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+}
+
+/// Access
+impl<T> Prepare<'_, '_, T>
+where
+    T: Transport,
+{
+    /// Return all updates we would perform, along with those that we rejected locally.
+    pub fn updates(&self) -> &[update::Update] {
+        &self.updates
+    }
+
+    /// Return the outcome of the handshake, which contains the remote references.
+    pub fn handshake(&self) -> &gix_protocol::handshake::Outcome {
+        &self.handshake
+    }
+}
@@ -0,0 +1,346 @@
+use gix_hash::ObjectId;
+use gix_protocol::handshake::Ref;
+use gix_refspec::match_group::SourceRef;
+
+use super::{
+    update::{Mode, Rejection, Status, Update},
+    Options,
+};
+use crate::{bstr::BString, ext::ObjectIdExt, remote, Remote, Repository};
+
+/// The error returned when determining which remote references to update.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not iterate local references")]
+    IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("Could not access local references")]
+    OpenReferences(#[from] crate::reference::iter::Error),
+    #[error("Could not initialize the iteration of local references")]
+    IterReferencesInit(#[from] crate::reference::iter::init::Error),
+    #[error("Could not resolve the local reference to push")]
+    FollowLocalRef(#[from] crate::reference::follow::to_object::Error),
+    #[error("Could not find the remote-tracking branch to obtain the lease for {remote_ref:?}")]
+    FindTrackingRef {
+        remote_ref: gix_ref::FullName,
+        source: crate::reference::find::Error,
+    },
+    #[error("Could not determine the push destination of the current branch")]
+    PushDefault(#[from] crate::repository::branch_remote_ref_name::Error),
+    #[error("There is nothing to push as no refspec was given and `push.default` doesn't match the current branch")]
+    NothingToPush,
+    #[error("The remote reference name {name:?} is invalid")]
+    InvalidRemoteRefName {
+        name: BString,
+        source: gix_validate::reference::name::Error,
+    },
+    #[error("Pushing the object {id} requires a full reference name as destination, got {destination:?}")]
+    DestinationForObject { id: ObjectId, destination: BString },
+    #[error("Could not find the object {id} to be pushed")]
+    FindObject {
+        id: ObjectId,
+        source: crate::object::find::existing::Error,
+    },
+}
+
+/// Obtain all updates to send, along with those we rejected locally.
+pub(super) fn updates(
+    remote: &Remote<'_>,
+    remote_refs: &[Ref],
+    options: &Options,
+    can_delete_refs: bool,
+) -> Result<Vec<Update>, Error> {
+    let repo = remote.repo;
+    let mut specs = if options.refspecs.is_empty() {
+        remote.refspecs(remote::Direction::Push).to_vec()
+    } else {
+        options.refspecs.clone()
+    };
+    if specs.is_empty() {
+        specs.push(push_default_spec(repo)?);
+    }
+
+    let local_refs = local_refs(repo)?;
+    let items = local_refs
+        .iter()
+        .map(|(name, target, object)| gix_refspec::match_group::Item {
+            full_ref_name: name.as_ref(),
+            target,
+            object: object.as_deref(),
+        });
+
+    let mut desired = Vec::<(gix_ref::FullName, Option<gix_ref::FullName>, Option<ObjectId>, bool)>::new();
+    let mut add =
+        |remote_ref: gix_ref::FullName, local_ref: Option<gix_ref::FullName>, new: Option<ObjectId>, force: bool| {
+            // later specs don't override earlier ones, similar to what `git` does when it detects duplicates.
+            if !desired.iter().any(|(name, ..)| *name == remote_ref) {
+                desired.push((remote_ref, local_ref, new, force));
+            }
+        };
+    for spec in &specs {
+        let spec = spec.to_ref();
+        match spec.instruction() {
+            gix_refspec::Instruction::Push(gix_refspec::instruction::Push::AllMatchingBranches {
+                allow_non_fast_forward,
+            }) => {
+                for (name, target, _) in local_refs.iter().filter(|(name, ..)| name.starts_with(b"refs/heads/")) {
+                    if remote_refs.iter().any(|r| r.unpack().0 == name) {
+                        let name = full_name(name.clone())?;
+                        add(name.clone(), Some(name), Some(*target), allow_non_fast_forward);
+                    }
+                }
+            }
+            gix_refspec::Instruction::Push(gix_refspec::instruction::Push::Delete { ref_or_pattern }) => {
+                let spec = format!("{ref_or_pattern}:");
+                let group = gix_refspec::MatchGroup {
+                    specs: vec![
+                        gix_refspec::parse(spec.as_str().into(), gix_refspec::parse::Operation::Fetch)
+                            .expect("a valid push spec is a valid fetch spec without destination"),
+                    ],
+                };
+                let null = repo.object_hash().null();
+                let out = group.match_lhs(remote_refs.iter().map(|r| gix_refspec::match_group::Item {
+                    full_ref_name: r.unpack().0,
+                    target: r.unpack().1.unwrap_or(&null),
+                    object: None,
+                }));
+                if out.mappings.is_empty() && !ref_or_pattern.contains(&b'*') {
+                    // Deleting what doesn't exist is rejected, so we still want to see it.
+                    add(expand_partial_name(ref_or_pattern.into())?, None, None, false);
+                }
+                for mapping in out.mappings {
+                    if let SourceRef::FullName(name) = mapping.lhs {
+                        add(full_name(name.into_owned())?, None, None, false);
+                    }
+                }
+            }
+            gix_refspec::Instruction::Push(gix_refspec::instruction::Push::Matching {
+                allow_non_fast_forward, ..
+            }) => {
+                let out = gix_refspec::MatchGroup { specs: vec![spec] }.match_lhs(items.clone());
+                for mapping in out.mappings {
+                    let (local_ref, new) = match (mapping.lhs, mapping.item_index) {
+                        (SourceRef::FullName(_), Some(idx)) => {
+                            let (name, target, _) = &local_refs[idx];
+                            let name = if name == "HEAD" {
+                                repo.head_name().ok().flatten()
+                            } else {
+                                Some(full_name(name.clone())?)
+                            };
+                            (name, *target)
+                        }
+                        (SourceRef::ObjectId(id), _) => {
+                            repo.find_object(id)
+                                .map_err(|err| Error::FindObject { id, source: err })?;
+                            (None, id)
+                        }
+                        (SourceRef::FullName(_), None) => unreachable!("names are only matched with an item"),
+                    };
+                    let remote_ref = match (mapping.rhs, &local_ref) {
+                        (Some(rhs), _) => full_name(rhs.into_owned())?,
+                        (None, Some(local)) => local.clone(),
+                        (None, None) => {
+                            return Err(Error::DestinationForObject {
+                                id: new,
+                                destination: spec.source().unwrap_or_default().to_owned(),
+                            })
+                        }
+                    };
+                    add(remote_ref, local_ref, Some(new), allow_non_fast_forward);
+                }
+            }
+            gix_refspec::Instruction::Fetch(_) => unreachable!("push specs are parsed as such"),
+        }
+    }
+
+    let mut updates = Vec::with_capacity(desired.len());
+    for (remote_ref, local_ref, new, force) in desired {
+        let old = remote_refs
+            .iter()
+            .find(|r| r.unpack().0 == remote_ref.as_bstr())
+            .and_then(|r| r.unpack().1.map(ToOwned::to_owned));
+        let mode = match lease_for(remote, &remote_ref, options)? {
+            Some(expected) if expected != old => Mode::Rejected(Rejection::StaleLease),
+            lease => determine_mode(repo, &remote_ref, old, new, force || lease.is_some(), can_delete_refs),
+        };
+        let status = match mode {
+            Mode::NoChangeNeeded => Status::UpToDate,
+            Mode::Rejected(reason) => Status::Rejected(reason),
+            Mode::Create | Mode::FastForward | Mode::Forced | Mode::Delete => Status::Ok,
+        };
+        updates.push(Update {
+            remote_ref,
+            local_ref,
+            old,
+            new,
+            mode,
+            status,
+        });
+    }
+
+    if options.atomic && updates.iter().any(|u| matches!(u.mode, Mode::Rejected(_))) {
+        for update in updates
+            .iter_mut()
+            .filter(|u| !matches!(u.mode, Mode::Rejected(_) | Mode::NoChangeNeeded))
+        {
+            update.mode = Mode::Rejected(Rejection::AtomicPushFailed);
+            update.status = Status::Rejected(Rejection::AtomicPushFailed);
+        }
+    }
+    Ok(updates)
+}
+
+/// Return the name of the remote-tracking branch that `remote_ref` is fetched into, according to the fetch refspecs of `remote`.
+pub(super) fn tracking_ref_name(remote: &Remote<'_>, remote_ref: &gix_ref::FullNameRef) -> Option<gix_ref::FullName> {
+    let null = remote.repo.object_hash().null();
+    let out =
+        gix_refspec::MatchGroup::from_fetch_specs(remote.refspecs(remote::Direction::Fetch).iter().map(|s| s.to_ref()))
+            .match_lhs(
+                Some(gix_refspec::match_group::Item {
+                    full_ref_name: remote_ref.as_bstr(),
+                    target: &null,
+                    object: None,
+                })
+                .into_iter(),
+            );
+    out.mappings.into_iter().find_map(|m| {
+        m.rhs
+            .and_then(|name| gix_ref::FullName::try_from(name.into_owned()).ok())
+    })
+}
+
+fn determine_mode(
+    repo: &Repository,
+    remote_ref: &gix_ref::FullName,
+    old: Option<ObjectId>,
+    new: Option<ObjectId>,
+    force: bool,
+    can_delete_refs: bool,
+) -> Mode {
+    match (old, new) {
+        (None, None) => Mode::Rejected(Rejection::DeleteNonExisting),
+        (Some(_), None) if !can_delete_refs => Mode::Rejected(Rejection::DeleteUnsupported),
+        (Some(_), None) => Mode::Delete,
+        (None, Some(_)) => Mode::Create,
+        (Some(old), Some(new)) if old == new => Mode::NoChangeNeeded,
+        (Some(_), Some(_)) if force => Mode::Forced,
+        (Some(_), Some(_)) if remote_ref.category() == Some(gix_ref::Category::Tag) => {
+            Mode::Rejected(Rejection::AlreadyExists)
+        }
+        (Some(old), Some(new)) => {
+            if !repo.has_object(old) {
+                return Mode::Rejected(Rejection::FetchFirst);
+            }
+            if is_fast_forward(repo, old, new) {
+                Mode::FastForward
+            } else {
+                Mode::Rejected(Rejection::NonFastForward)
+            }
+        }
+    }
+}
+
+fn is_fast_forward(repo: &Repository, old: ObjectId, new: ObjectId) -> bool {
+    let Some(old_time) = repo
+        .find_object(old)
+        .ok()
+        .and_then(|obj| obj.try_into_commit().ok())
+        .and_then(|c| c.committer().ok().map(|a| a.time.seconds))
+    else {
+        return false;
+    };
+    let Some(new) = repo.find_object(new).ok().and_then(|obj| obj.peel_tags_to_end().ok()) else {
+        return false;
+    };
+    new.id
+        .attach(repo)
+        .ancestors()
+        .sorting(crate::revision::walk::Sorting::ByCommitTimeCutoff {
+            order: Default::default(),
+            seconds: old_time,
+        })
+        .all()
+        .is_ok_and(|mut ancestors| ancestors.any(|c| c.is_ok_and(|c| c.id == old)))
+}
+
+/// Return `Some(expected_value)` if there is a lease for `remote_ref`.
+fn lease_for(
+    remote: &Remote<'_>,
+    remote_ref: &gix_ref::FullName,
+    options: &Options,
+) -> Result<Option<Option<ObjectId>>, Error> {
+    let Some(lease) = options.leases.iter().find(|l| l.remote_ref == *remote_ref) else {
+        return Ok(None);
+    };
+    Ok(Some(match lease.expected {
+        Some(id) => (!id.is_null()).then_some(id),
+        None => match tracking_ref_name(remote, remote_ref.as_ref()) {
+            Some(tracking) => remote
+                .repo
+                .try_find_reference(tracking.as_ref())
+                .map_err(|err| Error::FindTrackingRef {
+                    remote_ref: remote_ref.clone(),
+                    source: err,
+                })?
+                .and_then(|r| r.try_id().map(crate::Id::detach)),
+            // Without tracking branch, the lease can't be satisfied.
+            None => return Ok(Some(Some(remote.repo.object_hash().null()))),
+        },
+    }))
+}
+
+fn push_default_spec(repo: &Repository) -> Result<gix_refspec::RefSpec, Error> {
+    let head = repo.head_name().ok().flatten().ok_or(Error::NothingToPush)?;
+    let remote_ref = repo
+        .branch_remote_ref_name(head.as_ref(), remote::Direction::Push)
+        .ok_or(Error::NothingToPush)??;
+    let spec = format!("{}:{}", head.as_bstr(), remote_ref.as_bstr());
+    Ok(
+        gix_refspec::parse(spec.as_str().into(), gix_refspec::parse::Operation::Push)
+            .expect("valid full names always form a valid spec")
+            .to_owned(),
+    )
+}
+
+/// Return `(name, target, peeled)` tuples for all local references, with symbolic references followed.
+#[allow(clippy::type_complexity)]
+fn local_refs(repo: &Repository) -> Result<Vec<(BString, ObjectId, Option<ObjectId>)>, Error> {
+    let platform = repo.references()?;
+    let mut out = Vec::new();
+    if let Ok(head_id) = repo.head_id() {
+        out.push(("HEAD".into(), head_id.detach(), None));
+    }
+    for reference in platform.all()? {
+        let mut reference = reference.map_err(Error::IterReferences)?;
+        let Ok(target) = reference.follow_to_object() else {
+            continue;
+        };
+        let target = target.detach();
+        let peeled = reference.peel_to_id_in_place().ok().map(crate::Id::detach);
+        out.push((
+            reference.name().as_bstr().to_owned(),
+            target,
+            peeled.filter(|id| *id != target),
+        ));
+    }
+    Ok(out)
+}
+
+fn expand_partial_name(name: BString) -> Result<gix_ref::FullName, Error> {
+    let name = if name.starts_with(b"refs/") {
+        name
+    } else {
+        let mut full = BString::from(if name.starts_with(b"tags/") {
+            "refs/"
+        } else {
+            "refs/heads/"
+        });
+        full.extend_from_slice(&name);
+        full
+    };
+    full_name(name)
+}
+
+fn full_name(name: BString) -> Result<gix_ref::FullName, Error> {
+    gix_ref::FullName::try_from(name.clone()).map_err(|err| Error::InvalidRemoteRefName { name, source: err })
+}
@@ -0,0 +1,252 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_features::progress::{Count, DynNestedProgress, NestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_protocol::push::response::RefStatus;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+use super::{
+    resolve,
+    update::{Mode, Status, Update},
+    Error, Outcome, Prepare,
+};
+use crate::{remote::Connection, Repository};
+
+impl<T> Prepare<'_, '_, T>
+where
+    T: gix_protocol::transport::client::Transport,
+{
+    /// Send all updates to the remote along with a pack of all objects it needs to apply them,
+    /// and return an outcome which tells what happened to each update.
+    ///
+    /// If the remote accepted an update, the corresponding remote-tracking branch is updated locally as well,
+    /// unless [dry-run](Prepare::with_dry_run()) is enabled.
+    ///
+    /// Note that updates that were rejected locally are never sent, and that a remote which doesn't report
+    /// the status of updates is assumed to have applied all of them.
+    pub fn transmit<P>(mut self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
+    where
+        P: NestedProgress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("remote::push::Prepare::transmit()");
+        let mut con = self.con.take().expect("transmit() can only be called once");
+        let repo = con.remote.repo;
+
+        for update in self.updates.iter().filter(|u| is_sendable(u.mode)) {
+            let null = repo.object_hash().null();
+            self.arguments.update(gix_protocol::push::Update {
+                name: update.remote_ref.as_bstr().to_owned(),
+                old: update.old.unwrap_or(null),
+                new: update.new.unwrap_or(null),
+            });
+        }
+        if self.dry_run || self.arguments.is_empty() {
+            return Ok(Outcome {
+                handshake: self.handshake,
+                updates: self.updates,
+                unpack_error: None,
+            });
+        }
+
+        let (tips, boundary) = tips_and_boundary(repo, &self.handshake, &self.updates);
+        let report = gix_protocol::push(
+            &mut con.transport.inner,
+            &self.arguments,
+            |out| write_pack(repo, tips, boundary, out, &mut progress, should_interrupt),
+            con.trace,
+        )?;
+
+        let unpack_error = report.as_ref().and_then(|r| r.unpack_error.clone());
+        for update in self.updates.iter_mut().filter(|u| is_sendable(u.mode)) {
+            let status = report
+                .as_ref()
+                .and_then(|r| r.refs.iter().find(|s| s.name() == update.remote_ref.as_bstr()));
+            update.status = match (status, &unpack_error) {
+                (_, Some(err)) => Status::RemoteRejected { reason: err.clone() },
+                (Some(RefStatus::Rejected { reason, .. }), None) => Status::RemoteRejected { reason: reason.clone() },
+                (Some(RefStatus::Ok { .. }), None) => Status::Ok,
+                // Without `report-status` we can't know better, but a report that misses updates is a failure.
+                (None, None) if report.is_some() => Status::RemoteRejected {
+                    reason: "remote failed to report status".into(),
+                },
+                (None, None) => Status::Ok,
+            };
+        }
+        update_tracking_refs(&con, &self.updates)?;
+
+        Ok(Outcome {
+            handshake: self.handshake,
+            updates: self.updates,
+            unpack_error,
+        })
+    }
+}
+
+fn is_sendable(mode: Mode) -> bool {
+    matches!(mode, Mode::Create | Mode::FastForward | Mode::Forced | Mode::Delete)
+}
+
+/// Return the objects to send along with all remote objects we have locally, which are known to the remote
+/// and thus don't need to be sent.
+fn tips_and_boundary(
+    repo: &Repository,
+    handshake: &gix_protocol::handshake::Outcome,
+    updates: &[Update],
+) -> (Vec<ObjectId>, Vec<ObjectId>) {
+    let boundary: Vec<_> = handshake
+        .refs
+        .iter()
+        .flatten()
+        .flat_map(|r| {
+            let (_name, target, peeled) = r.unpack();
+            target.into_iter().chain(peeled).map(ToOwned::to_owned)
+        })
+        .filter(|id| repo.has_object(id))
+        .collect();
+    let mut tips: Vec<_> = updates
+        .iter()
+        .filter(|u| is_sendable(u.mode))
+        .filter_map(|u| u.new)
+        .filter(|id| !boundary.contains(id))
+        .collect();
+    tips.sort();
+    tips.dedup();
+    (tips, boundary)
+}
+
+fn write_pack(
+    repo: &Repository,
+    tips: Vec<ObjectId>,
+    boundary: Vec<ObjectId>,
+    out: &mut dyn std::io::Write,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut db = repo.objects.clone().into_arc()?.into_inner();
+    db.prevent_pack_unload();
+    db.ignore_replacements = true;
+    let bitmap = match repo.bitmap_index_if_enabled() {
+        Ok(bitmap) => bitmap,
+        Err(crate::repository::bitmap_index_if_enabled::Error::ConfigBoolean(err)) => return Err(err.into()),
+        Err(_err) => {
+            gix_trace::warn!("ignoring reachability bitmap that failed to open: {_err}");
+            None
+        }
+    };
+    let mut counting = progress.add_child("counting".into());
+    counting.init(None, gix_features::progress::count("objects"));
+    let counts = match bitmap {
+        Some(bitmap) => {
+            gix_pack::data::output::count::objects_with_bitmap(
+                &db,
+                &bitmap,
+                tips,
+                boundary,
+                &counting,
+                should_interrupt,
+            )
+            .map_err(Error::from)?
+            .0
+        }
+        None => {
+            // Annotated tags and other non-commits are sent as is, and their targets are expanded during counting.
+            let (commit_tips, other_tips): (Vec<_>, Vec<_>) =
+                tips.into_iter().partition(|id| repo.find_commit(*id).is_ok());
+            let commit_tips = commit_tips.into_iter().chain(
+                other_tips
+                    .iter()
+                    .filter_map(|id| repo.find_object(*id).ok()?.peel_tags_to_end().ok())
+                    .filter(|obj| obj.kind == gix_object::Kind::Commit && !boundary.contains(&obj.id))
+                    .map(|obj| obj.id),
+            );
+            let commits: Vec<_> = repo
+                .rev_walk(commit_tips)
+                .with_boundary(boundary.iter().copied())
+                .sorting(crate::revision::walk::Sorting::BreadthFirst)
+                .all()
+                .map_err(Error::from)?
+                .map(|info| info.map(|info| info.id))
+                .collect::<Result<_, _>>()?;
+
+            gix_pack::data::output::count::objects_unthreaded(
+                &db,
+                &mut commits
+                    .into_iter()
+                    .chain(other_tips)
+                    .map(Ok::<_, Box<dyn std::error::Error + Send + Sync + 'static>>),
+                &counting,
+                should_interrupt,
+                gix_pack::data::output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+            )
+            .map_err(Error::from)?
+            .0
+        }
+    };
+
+    let num_objects = counts.len();
+    let entries = crate::parallel::InOrderIter::from(gix_pack::data::output::entry::iter_from_counts(
+        counts,
+        db,
+        Box::new(progress.add_child("creating entries".into())),
+        gix_pack::data::output::entry::iter_from_counts::Options {
+            thread_limit: None,
+            mode: gix_pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+            allow_thin_pack: false,
+            chunk_size: 1000,
+            version: Default::default(),
+        },
+    ));
+    let mut writing = progress.add_child("writing".into());
+    writing.init(None, gix_features::progress::bytes());
+    for written in gix_pack::data::output::bytes::FromEntriesIter::new(
+        entries,
+        out,
+        num_objects as u32,
+        gix_pack::data::Version::default(),
+        repo.object_hash(),
+    ) {
+        writing.inc_by(written? as usize);
+    }
+    Ok(())
+}
+
+fn update_tracking_refs<T>(con: &Connection<'_, '_, T>, updates: &[Update]) -> Result<(), Error>
+where
+    T: gix_protocol::transport::client::Transport,
+{
+    let edits: Vec<_> = updates
+        .iter()
+        .filter(|u| is_sendable(u.mode) && u.status == Status::Ok)
+        .filter_map(|u| {
+            let name = resolve::tracking_ref_name(con.remote, u.remote_ref.as_ref())?;
+            Some(match u.new {
+                Some(new) => RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: "update by push".into(),
+                        },
+                        expected: PreviousValue::Any,
+                        new: gix_ref::Target::Object(new),
+                    },
+                    name,
+                    deref: false,
+                },
+                None => RefEdit {
+                    change: Change::Delete {
+                        expected: PreviousValue::Any,
+                        log: RefLog::AndReference,
+                    },
+                    name,
+                    deref: false,
+                },
+            })
+        })
+        .collect();
+    if !edits.is_empty() {
+        con.remote.repo.edit_references(edits)?;
+    }
+    Ok(())
+}
@@ -14,6 +14,10 @@ pub mod find {
             remote_name: BString,
             source: config::refspec::Error,
         },
+        #[error("The value for 'remote.<name>.promisor' is invalid")]
+        Promisor(#[source] config::boolean::Error),
+        #[error("The value for 'remote.<name>.partialCloneFilter' is invalid")]
+        PartialCloneFilter(#[source] config::key::GenericErrorWithValue),
         #[error("Neither 'url` nor 'pushUrl' fields were set in the remote's configuration.")]
         UrlMissing,
         #[error("The {kind} url under `remote.{remote_name}` was invalid")]
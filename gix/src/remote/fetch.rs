@@ -9,7 +9,7 @@ pub mod negotiate {
 
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 pub use super::connection::fetch::{
-    outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
+    download, outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
 };
 
 /// If `Yes`, don't really make changes but do as much as possible to get an idea of what would be done.
@@ -34,4 +34,4 @@ pub(crate) enum WritePackedRefs {
 
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 pub use gix_protocol::fetch::{refmap, RefMap};
-pub use gix_protocol::fetch::{Shallow, Tags};
+pub use gix_protocol::fetch::{Filter, Shallow, Tags};
@@ -0,0 +1 @@
+pub use super::connection::push::{prepare, update, Error, Lease, Options, Outcome, Prepare};
@@ -54,6 +54,8 @@ impl Remote<'_> {
                 config::tree::Remote::FETCH.name,
                 config::tree::Remote::PUSH.name,
                 config::tree::Remote::TAG_OPT.name,
+                config::tree::Remote::PROMISOR.name,
+                config::tree::Remote::PARTIAL_CLONE_FILTER.name,
             ];
             for id in section_ids {
                 let mut section = config.section_mut_by_id(id).expect("just queried");
@@ -92,6 +94,15 @@ impl Remote<'_> {
                 .into(),
             );
         }
+        if self.promisor {
+            section.push(as_key(config::tree::Remote::PROMISOR.name), Some("true".into()));
+        }
+        if let Some(filter) = self.filter {
+            section.push(
+                as_key(config::tree::Remote::PARTIAL_CLONE_FILTER.name),
+                Some(filter.to_string().as_str().into()),
+            );
+        }
         for (key, spec) in self
             .fetch_specs
             .iter()
@@ -117,4 +117,16 @@ impl SchemePermission {
             |allow| allow.to_bool(self.user_allowed),
         )
     }
+
+    /// Like [`allow()`](Self::allow()), but for URLs that were not provided by the user but sent by a remote, so `user`
+    /// forbids the `scheme`, which is also the default for all protocols but `git`, `ssh` and `http(s)`.
+    pub fn allow_from_remote(&self, scheme: &gix_url::Scheme) -> bool {
+        self.allow_per_scheme.get(scheme).or(self.allow.as_ref()).map_or_else(
+            || {
+                use gix_url::Scheme::*;
+                matches!(scheme, Git | Ssh | Http | Https)
+            },
+            |allow| allow.to_bool(Some(false)),
+        )
+    }
 }
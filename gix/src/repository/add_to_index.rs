@@ -0,0 +1,217 @@
+use std::collections::BTreeSet;
+
+use gix_index::entry::{Flags, Mode, Stage};
+use gix_status::index_as_worktree::{Change, EntryStatus};
+
+use crate::bstr::{BStr, BString};
+use crate::status::{index_worktree::Item, Submodule, UntrackedFiles};
+
+/// The error returned by [Repository::add_to_index()](crate::Repository::add_to_index()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot add files to the index in a bare repository")]
+    BareRepository,
+    #[error(transparent)]
+    Status(#[from] crate::status::Error),
+    #[error(transparent)]
+    StatusIter(#[from] crate::status::into_iter::Error),
+    #[error(transparent)]
+    IndexWorktreeStatus(#[from] crate::status::index_worktree::Error),
+    #[error(transparent)]
+    FilesystemOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    WorktreeFileToObject(#[from] crate::filter::pipeline::worktree_file_to_object::Error),
+    #[error("Could not obtain metadata of '{}'", path.display())]
+    Metadata {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Options for use in [Repository::add_to_index()](crate::Repository::add_to_index()).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// If `true`, files that are ignored by `.gitignore` files or other exclude sources are added as well,
+    /// like `git add --force` does. Otherwise, they are skipped.
+    pub include_ignored: bool,
+    /// If `true`, only entries that are already tracked are updated or removed, and untracked files aren't added,
+    /// like `git add --update` does.
+    pub tracked_only: bool,
+}
+
+/// The outcome of [Repository::add_to_index()](crate::Repository::add_to_index()), with all paths sorted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The paths of files that weren't tracked before, and now have an entry in the index.
+    pub added: Vec<BString>,
+    /// The paths of tracked files whose entry was updated to match the worktree.
+    pub modified: Vec<BString>,
+    /// The paths of tracked files that were deleted in the worktree, and whose entries were removed from the index.
+    pub removed: Vec<BString>,
+}
+
+impl Outcome {
+    /// Return `true` if the index was changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+enum Edit {
+    Add,
+    Modify,
+    Remove,
+    UpdateStat(gix_index::entry::Stat),
+}
+
+impl crate::Repository {
+    /// Make the entries of `index` that match `patterns` match the files in the worktree, similar to `git add --all <patterns>`,
+    /// and configured by `options`.
+    /// Empty `patterns` match all files.
+    ///
+    /// Changed or untracked files are passed through the clean filters configured in `.gitattributes` before they are written
+    /// to the object database, and tracked files that were deleted are removed from the index.
+    /// Conflicting entries are resolved by replacing all of their stages with the file in the worktree.
+    /// Untracked files are found by a directory walk which honors `.gitignore` files, so ignored files are skipped unless
+    /// [`Options::include_ignored`] is set.
+    ///
+    /// Entries are kept sorted and the tree-cache extension is invalidated for all changed paths, but note that `index`
+    /// has to be written by the caller for the changes to be visible to others.
+    pub fn add_to_index(
+        &self,
+        index: &mut gix_index::File,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let workdir = self.workdir().ok_or(Error::BareRepository)?;
+        let mut status = self
+            .status(crate::progress::Discard)?
+            .index(crate::worktree::IndexPersistedOrInMemory::InMemory(index.clone()))
+            .index_worktree_rewrites(None)
+            .index_worktree_submodules(Submodule::Given {
+                ignore: crate::submodule::config::Ignore::Dirty,
+                check_dirty: false,
+            })
+            .untracked_files(if options.tracked_only {
+                UntrackedFiles::None
+            } else {
+                UntrackedFiles::Files
+            });
+        if options.include_ignored {
+            status = status.dirwalk_options(|opts| opts.emit_ignored(Some(gix_dir::walk::EmissionMode::Matching)));
+        }
+
+        let mut edits = Vec::new();
+        for item in
+            status.into_index_worktree_iter(patterns.into_iter().map(|p| p.as_ref().to_owned()).collect::<Vec<_>>())?
+        {
+            let edit = match item? {
+                Item::Modification { rela_path, status, .. } => match status {
+                    EntryStatus::NeedsUpdate(stat) => (rela_path, Edit::UpdateStat(stat)),
+                    EntryStatus::Change(Change::Removed) => (rela_path, Edit::Remove),
+                    EntryStatus::Change(_) | EntryStatus::Conflict(_) | EntryStatus::IntentToAdd => {
+                        (rela_path, Edit::Modify)
+                    }
+                },
+                Item::DirectoryContents { entry, .. } => {
+                    use gix_dir::entry::{Kind, Status};
+                    let is_addable = match entry.status {
+                        Status::Untracked => true,
+                        Status::Ignored(_) => options.include_ignored,
+                        Status::Pruned | Status::Tracked => false,
+                    };
+                    if !is_addable || !matches!(entry.disk_kind, Some(Kind::File | Kind::Symlink | Kind::Repository)) {
+                        continue;
+                    }
+                    (entry.rela_path, Edit::Add)
+                }
+                Item::Rewrite { .. } => unreachable!("BUG: rewrite tracking was disabled"),
+            };
+            edits.push(edit);
+        }
+
+        let capabilities = self.filesystem_options()?;
+        let (mut pipeline, _) = self.filter_pipeline(None)?;
+        let mut out = Outcome::default();
+        let mut removals = BTreeSet::new();
+        let mut new_entries = Vec::new();
+        for (rela_path, edit) in edits {
+            let rela_path_ref: &BStr = rela_path.as_ref();
+            let object = match edit {
+                Edit::UpdateStat(stat) => {
+                    if let Some(entry) = index.entry_mut_by_path_and_stage(rela_path_ref, Stage::Unconflicted) {
+                        entry.stat = stat;
+                    }
+                    continue;
+                }
+                Edit::Remove => None,
+                Edit::Add | Edit::Modify => pipeline.worktree_file_to_object(rela_path_ref, index)?,
+            };
+            if let Some(tree) = index.tree_mut() {
+                tree.invalidate_path(rela_path_ref);
+            }
+            let is_tracked = index.entry_range(rela_path_ref).is_some();
+            let Some((id, kind, _)) = object else {
+                if is_tracked {
+                    removals.insert(rela_path.clone());
+                    out.removed.push(rela_path);
+                }
+                continue;
+            };
+
+            let path = workdir.join(crate::path::from_bstr(rela_path_ref));
+            let stat = gix_index::fs::Metadata::from_path_no_follow(&path)
+                .map_err(|source| Error::Metadata { path, source })
+                .map(|md| gix_index::entry::Stat::from_fs(&md).unwrap_or_default())?;
+            match index.entry_mut_by_path_and_stage(rela_path_ref, Stage::Unconflicted) {
+                Some(entry) => {
+                    entry.mode = entry_mode(kind.into(), Some(entry.mode), capabilities);
+                    entry.id = id;
+                    entry.stat = stat;
+                    entry.flags.remove(Flags::INTENT_TO_ADD);
+                }
+                None => {
+                    if is_tracked {
+                        // Only conflicting stages exist, which are replaced by the new entry.
+                        removals.insert(rela_path.clone());
+                    }
+                    new_entries.push((stat, id, entry_mode(kind.into(), None, capabilities), rela_path.clone()));
+                }
+            }
+            if is_tracked {
+                out.modified.push(rela_path);
+            } else {
+                out.added.push(rela_path);
+            }
+        }
+
+        if !removals.is_empty() {
+            index.remove_entries(|_, path, _| removals.contains(path));
+        }
+        if !new_entries.is_empty() {
+            for (stat, id, mode, path) in new_entries {
+                index.dangerously_push_entry(stat, id, Flags::empty(), mode, path.as_ref());
+            }
+            index.sort_entries();
+        }
+        out.added.sort();
+        out.modified.sort();
+        out.removed.sort();
+        Ok(out)
+    }
+}
+
+/// Determine the mode of a `new` entry, keeping the executable bit or symlink-ness of the `previous` entry if the
+/// filesystem can't represent it according to `capabilities`.
+fn entry_mode(new: Mode, previous: Option<Mode>, capabilities: gix_fs::Capabilities) -> Mode {
+    let is_file = |mode: Mode| mode == Mode::FILE || mode == Mode::FILE_EXECUTABLE;
+    match previous {
+        Some(Mode::SYMLINK) if is_file(new) && !capabilities.symlink => Mode::SYMLINK,
+        Some(previous) if is_file(previous) && is_file(new) && !capabilities.executable_bit => previous,
+        None if new == Mode::FILE_EXECUTABLE && !capabilities.executable_bit => Mode::FILE,
+        _ => new,
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_hash::ObjectId;
+use gix_ref::{transaction::PreviousValue, FullName};
+
+use crate::bstr::{BStr, BString};
+
+/// The error returned by [Repository::add_worktree()](crate::Repository::add_worktree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("'{}' already exists and is not an empty directory", path.display())]
+    PathExists { path: PathBuf },
+    #[error("Cannot derive the name of a worktree from '{}'", path.display())]
+    InvalidPath { path: PathBuf },
+    #[error("Branch '{branch}' is already checked out at '{}'", path.display())]
+    BranchCheckedOut { branch: BString, path: PathBuf },
+    #[error("A branch named '{name}' already exists")]
+    BranchExists { name: BString },
+    #[error(transparent)]
+    RevParse(#[from] crate::revision::spec::parse::single::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    HeadCommit(#[from] crate::reference::head_commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    BranchName(#[from] gix_validate::reference::name::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    CreateBranch(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    OpenMainRepository(#[from] crate::open::Error),
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::worktree::proxy::into_repo::Error),
+    #[error("Could not create index from tree at {id}")]
+    IndexFromTree {
+        id: ObjectId,
+        source: gix_index::init::from_tree::Error,
+    },
+    #[error(transparent)]
+    ProtectOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    Realpath(#[from] gix_path::realpath::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Options for use in [Repository::add_worktree()](crate::Repository::add_worktree()).
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Create a branch with this name at the commit to check out, and check it out, like `git worktree add -b <branch>` does.
+    pub new_branch: Option<BString>,
+    /// If `true`, `HEAD` of the new worktree is detached at the commit to check out even if it names a branch,
+    /// like `git worktree add --detach` does.
+    pub detach: bool,
+    /// If `true`, a branch that is checked out in another worktree can be checked out nonetheless, and
+    /// [`new_branch`](Self::new_branch) is reset to the commit to check out if it exists already,
+    /// like `git worktree add --force -B <branch>` does.
+    pub force: bool,
+    /// If `true`, only set up the worktree without checking out any files, like `git worktree add --no-checkout` does.
+    pub no_checkout: bool,
+    /// If set, the new worktree is locked with the given reason, which may be empty, like `git worktree add --lock --reason` does.
+    pub lock: Option<BString>,
+}
+
+impl crate::Repository {
+    /// Create a new linked worktree at `path` and check out `commitish` into it, configured by `options`, similar to
+    /// `git worktree add <path> [<commitish>]`, and return the repository of the new worktree.
+    ///
+    /// The name of the worktree is derived from the last component of `path`, and its private data is placed into
+    /// `worktrees/<name>` of the common directory of this repository, with a number appended to `<name>` if it's taken already.
+    ///
+    /// `HEAD` of the new worktree is set as follows:
+    ///
+    /// * If [`Options::new_branch`] is set, a branch of that name is created at `commitish`, or at `HEAD` if it's `None`.
+    /// * If [`Options::detach`] is set, it's detached at `commitish`, or at `HEAD` if it's `None`.
+    /// * If `commitish` is the name of a local branch, that branch is checked out.
+    /// * If it's any other revision, it's detached at the commit it names.
+    /// * Otherwise, the branch named after the worktree is checked out, and created from `HEAD` if it doesn't exist.
+    ///
+    /// A branch is never checked out if it's already checked out in another worktree, unless [`Options::force`] is set.
+    /// If something fails, the worktree and its private data are removed again, but a newly created branch is kept.
+    pub fn add_worktree(
+        &self,
+        path: impl AsRef<Path>,
+        commitish: Option<&BStr>,
+        options: &Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<crate::Repository, Error> {
+        let path = path.as_ref();
+        let path = if path.is_relative() {
+            std::env::current_dir()?.join(path)
+        } else {
+            path.to_owned()
+        };
+        let path_existed = path.exists();
+        if path_existed && std::fs::read_dir(&path)?.next().is_some() {
+            return Err(Error::PathExists { path });
+        }
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(sanitize_name)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| Error::InvalidPath { path: path.clone() })?;
+
+        let worktrees_dir = gix_path::realpath(self.common_dir())?.join("worktrees");
+        let mut id = name.clone();
+        let mut count = 0;
+        while worktrees_dir.join(&id).exists() {
+            count += 1;
+            id = format!("{name}{count}");
+        }
+        let (head, new_branch) = self.worktree_head(name.as_str().into(), commitish, options)?;
+
+        let private_git_dir = worktrees_dir.join(&id);
+        let res = self.add_worktree_inner(&path, &private_git_dir, head, new_branch, options, should_interrupt);
+        if res.is_err() {
+            std::fs::remove_dir_all(&private_git_dir).ok();
+            if path_existed {
+                if let Ok(entries) = std::fs::read_dir(&path) {
+                    for entry in entries.filter_map(Result::ok) {
+                        let path = entry.path();
+                        if path.is_dir() {
+                            std::fs::remove_dir_all(path).ok();
+                        } else {
+                            std::fs::remove_file(path).ok();
+                        }
+                    }
+                }
+            } else {
+                std::fs::remove_dir_all(&path).ok();
+            }
+        }
+        res
+    }
+
+    /// Determine what `HEAD` of a new worktree should be, along with a branch to create, if any.
+    fn worktree_head(
+        &self,
+        name: &BStr,
+        commitish: Option<&BStr>,
+        options: &Options,
+    ) -> Result<(gix_ref::Target, Option<(FullName, ObjectId)>), Error> {
+        let commit_id = |spec: Option<&BStr>| -> Result<ObjectId, Error> {
+            Ok(match spec {
+                Some(spec) => self.rev_parse_single(spec)?.object()?.peel_to_commit()?.id,
+                None => self.head_commit()?.id,
+            })
+        };
+        if let Some(branch) = &options.new_branch {
+            let full_name = FullName::try_from(format!("refs/heads/{branch}"))?;
+            if self.try_find_reference(full_name.as_ref())?.is_some() {
+                if !options.force {
+                    return Err(Error::BranchExists { name: branch.clone() });
+                }
+                self.assure_not_checked_out(&full_name)?;
+            }
+            let id = commit_id(commitish)?;
+            return Ok((gix_ref::Target::Symbolic(full_name.clone()), Some((full_name, id))));
+        }
+        if options.detach {
+            return Ok((gix_ref::Target::Object(commit_id(commitish)?), None));
+        }
+
+        let branch_name = commitish.unwrap_or(name);
+        let existing_branch = match FullName::try_from(format!("refs/heads/{branch_name}")) {
+            Ok(full_name) => self.try_find_reference(full_name.as_ref())?.map(|_| full_name),
+            Err(_) => None,
+        };
+        match (existing_branch, commitish) {
+            (Some(full_name), _) => {
+                if !options.force {
+                    self.assure_not_checked_out(&full_name)?;
+                }
+                Ok((gix_ref::Target::Symbolic(full_name), None))
+            }
+            (None, Some(spec)) => Ok((gix_ref::Target::Object(commit_id(Some(spec))?), None)),
+            (None, None) => {
+                let full_name = FullName::try_from(format!("refs/heads/{name}"))?;
+                let id = commit_id(None)?;
+                Ok((gix_ref::Target::Symbolic(full_name.clone()), Some((full_name, id))))
+            }
+        }
+    }
+
+    /// Fail if the branch `name` is checked out in the main worktree or any linked worktree.
+    fn assure_not_checked_out(&self, name: &FullName) -> Result<(), Error> {
+        let main = self.main_repo()?;
+        if let Some(workdir) = main.workdir() {
+            if main.head_name().ok().flatten().as_ref() == Some(name) {
+                return Err(Error::BranchCheckedOut {
+                    branch: name.shorten().to_owned(),
+                    path: workdir.to_owned(),
+                });
+            }
+        }
+        for proxy in self.worktrees()? {
+            if proxy.head_ref_name().as_ref() == Some(name) {
+                return Err(Error::BranchCheckedOut {
+                    branch: name.shorten().to_owned(),
+                    path: proxy.base()?,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn add_worktree_inner(
+        &self,
+        path: &Path,
+        private_git_dir: &Path,
+        head: gix_ref::Target,
+        new_branch: Option<(FullName, ObjectId)>,
+        options: &Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<crate::Repository, Error> {
+        std::fs::create_dir_all(private_git_dir)?;
+        // Lock it right away so it's not pruned while it's incomplete, like `git` does.
+        std::fs::write(private_git_dir.join("locked"), "initializing\n")?;
+        std::fs::create_dir_all(path)?;
+        let path = gix_path::realpath(path)?;
+        let dot_git = path.join(gix_discover::DOT_GIT_DIR);
+        crate::worktree::proxy::write_gitdir_file(private_git_dir, &dot_git)?;
+        std::fs::write(private_git_dir.join("commondir"), "../..\n")?;
+        crate::worktree::proxy::write_dot_git_file(&dot_git, private_git_dir)?;
+
+        if let Some((name, id)) = new_branch {
+            self.reference(
+                name,
+                id,
+                if options.force {
+                    PreviousValue::Any
+                } else {
+                    PreviousValue::MustNotExist
+                },
+                "branch: Created from worktree add",
+            )?;
+        }
+        let head = match head {
+            gix_ref::Target::Symbolic(name) => format!("ref: {}\n", name.as_bstr()),
+            gix_ref::Target::Object(id) => format!("{id}\n"),
+        };
+        std::fs::write(private_git_dir.join("HEAD"), head)?;
+
+        let repo = crate::worktree::Proxy::new(self, private_git_dir).into_repo()?;
+        if !options.no_checkout {
+            checkout(&repo, should_interrupt)?;
+        }
+        match &options.lock {
+            Some(reason) => std::fs::write(private_git_dir.join("locked"), reason.as_slice())?,
+            None => std::fs::remove_file(private_git_dir.join("locked"))?,
+        }
+        Ok(repo)
+    }
+}
+
+/// Check out the tree of `HEAD` into the empty worktree of `repo`, and write its index.
+fn checkout(repo: &crate::Repository, should_interrupt: &AtomicBool) -> Result<(), Error> {
+    let workdir = repo.workdir().expect("linked worktrees have a worktree");
+    let tree = repo.head_commit()?.tree_id()?.detach();
+    let state = gix_index::State::from_tree(&tree, &repo.objects, repo.config.protect_options()?)
+        .map_err(|err| Error::IndexFromTree { id: tree, source: err })?;
+    let mut index = gix_index::File::from_state(state, repo.index_path());
+
+    let mut opts = repo.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
+    opts.destination_is_initially_empty = true;
+    gix_worktree_state::checkout(
+        &mut index,
+        workdir,
+        repo.objects.clone().into_arc()?,
+        &gix_features::progress::Discard,
+        &gix_features::progress::Discard,
+        should_interrupt,
+        opts,
+    )?;
+    index.write(Default::default())?;
+    Ok(())
+}
+
+/// Turn `name` into a valid name for the private directory of a worktree, similar to what `git` does.
+fn sanitize_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    name.trim_start_matches('.').trim_end_matches(".lock").to_owned()
+}
@@ -0,0 +1,256 @@
+use std::{io::Write, sync::atomic::AtomicBool};
+
+use gix_bundle::{Header, Prerequisite, Ref, Version};
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+
+use crate::bstr::BStr;
+
+/// The error returned by [Repository::create_bundle()](crate::Repository::create_bundle()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(
+        "The revision specification {spec:?} isn't supported in bundles, use a single revision, '^rev' or 'from..to'"
+    )]
+    UnsupportedSpec { spec: String },
+    #[error("Refusing to create an empty bundle as none of the revisions is a reference")]
+    NoReferences,
+    #[error(transparent)]
+    RevParse(#[from] crate::revision::spec::parse::Error),
+    #[error(transparent)]
+    InitReferenceIteration(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    InitReferenceIterationPlatform(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    IterReferences(Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FollowReference(#[from] crate::reference::follow::to_object::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelObject(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkIter(#[from] crate::revision::walk::iter::Error),
+    #[error("Could not open the object database to create a pack from it")]
+    OpenObjectDatabase(#[source] std::io::Error),
+    #[error(transparent)]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error("Could not write the bundle header")]
+    WriteHeader(#[source] std::io::Error),
+}
+
+/// Options for use in [Repository::create_bundle()](crate::Repository::create_bundle()).
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    /// The version of the bundle to write.
+    ///
+    /// Note that [`Version::V3`] is used no matter what if the repository doesn't use SHA1 object ids,
+    /// as older versions can't represent them.
+    pub version: Version,
+    /// If `true`, add `HEAD` and all references to the bundle along with their history, like `git bundle create --all`.
+    pub all: bool,
+}
+
+impl crate::Repository {
+    /// Write a bundle with the objects that the revision specifications `specs` resolve to and all of their history to `out`,
+    /// similar to `git bundle create`, configured by `options`, and return its header.
+    ///
+    /// Each of `specs` is either a single revision to include, like `main` or `v1.0`, a revision to exclude along with its history,
+    /// like `^main`, or a range like `main..topic`. Included revisions that are references are recorded in the bundle,
+    /// and those that aren't only contribute objects.
+    /// Commits that are excluded but are parents of included commits become prerequisites, which a repository must have to
+    /// [unbundle](Self::unbundle()) the bundle.
+    ///
+    /// Use `progress` to learn about the objects being counted and written, and `should_interrupt` to abort the operation.
+    ///
+    /// ### Deviation
+    ///
+    /// Filters aren't supported, and `from...to` is rejected instead of excluding the merge-base of both sides.
+    pub fn create_bundle(
+        &self,
+        specs: impl IntoIterator<Item = impl AsRef<BStr>>,
+        mut out: impl Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        Options { version, all }: Options,
+    ) -> Result<Header, Error> {
+        let mut tips = Vec::new();
+        let mut hidden_tips = Vec::new();
+        let mut refs = Vec::<Ref>::new();
+        for spec in specs {
+            let spec = spec.as_ref();
+            let parsed = self.rev_parse(spec)?;
+            let (first_reference, second_reference) =
+                (parsed.first_reference().cloned(), parsed.second_reference().cloned());
+            let (reference, id) = match parsed.detach() {
+                gix_revision::Spec::Include(id) => (first_reference, id),
+                gix_revision::Spec::Exclude(id) => {
+                    hidden_tips.push(id);
+                    continue;
+                }
+                gix_revision::Spec::Range { from, to } => {
+                    hidden_tips.push(from);
+                    (second_reference, to)
+                }
+                gix_revision::Spec::Merge { .. }
+                | gix_revision::Spec::IncludeOnlyParents(_)
+                | gix_revision::Spec::ExcludeParents(_) => {
+                    return Err(Error::UnsupportedSpec { spec: spec.to_string() })
+                }
+            };
+            tips.push(id);
+            if let Some(reference) = reference {
+                let mut reference = crate::Reference::from_ref(reference, self);
+                if reference.follow_to_object()? == id {
+                    push_ref(&mut refs, reference.name().as_bstr(), id);
+                }
+            }
+        }
+        if all {
+            for reference in self.references()?.all()? {
+                let mut reference = reference.map_err(Error::IterReferences)?;
+                let id = reference.follow_to_object()?.detach();
+                tips.push(id);
+                push_ref(&mut refs, reference.name().as_bstr(), id);
+            }
+            if let Some(id) = self.head()?.id() {
+                tips.push(id.detach());
+                push_ref(&mut refs, "HEAD".into(), id.detach());
+            }
+        }
+        if refs.is_empty() {
+            return Err(Error::NoReferences);
+        }
+
+        let mut hidden_commits = Vec::new();
+        for id in hidden_tips {
+            hidden_commits.push(self.find_object(id)?.peel_to_commit()?.id);
+        }
+        let hidden: gix_hashtable::HashSet = self
+            .rev_walk(hidden_commits)
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<_, _>>()?;
+
+        // Annotated tags and other non-commits are added as is, and their targets are expanded during counting.
+        let mut commit_tips = Vec::new();
+        let mut other_tips = Vec::new();
+        for id in tips {
+            let object = self.find_object(id)?;
+            if object.kind != gix_object::Kind::Commit {
+                other_tips.push(id);
+            }
+            let peeled = object.peel_tags_to_end()?;
+            if peeled.kind == gix_object::Kind::Commit && !hidden.contains(&peeled.id) {
+                commit_tips.push(peeled.id);
+            }
+        }
+        let commits: Vec<_> = self
+            .rev_walk(commit_tips)
+            .selected(move |id| !hidden.contains(id))?
+            .map(|info| info.map(|info| (info.id, info.parent_ids().map(crate::Id::detach).collect::<Vec<_>>())))
+            .collect::<Result<_, _>>()?;
+
+        let included: gix_hashtable::HashSet = commits.iter().map(|(id, _parents)| *id).collect();
+        let mut prerequisites = Vec::<Prerequisite>::new();
+        for parent in commits.iter().flat_map(|(_id, parents)| parents) {
+            if included.contains(parent) || prerequisites.iter().any(|p| p.id == *parent) {
+                continue;
+            }
+            let comment = self
+                .find_commit(*parent)
+                .ok()
+                .and_then(|commit| commit.message().ok().map(|message| message.summary().into_owned()))
+                .unwrap_or_default();
+            prerequisites.push(Prerequisite { id: *parent, comment });
+        }
+
+        let header = Header {
+            version: if self.object_hash() == gix_hash::Kind::Sha1 {
+                version
+            } else {
+                Version::V3
+            },
+            object_hash: self.object_hash(),
+            filter: None,
+            prerequisites,
+            refs,
+        };
+        header.write_to(&mut out).map_err(Error::WriteHeader)?;
+        self.write_bundle_pack(
+            commits.into_iter().map(|(id, _parents)| id).chain(other_tips),
+            &mut out,
+            progress,
+            should_interrupt,
+        )?;
+        Ok(header)
+    }
+
+    fn write_bundle_pack(
+        &self,
+        objects: impl Iterator<Item = ObjectId>,
+        out: &mut dyn Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error> {
+        let mut db = self
+            .objects
+            .clone()
+            .into_arc()
+            .map_err(Error::OpenObjectDatabase)?
+            .into_inner();
+        db.prevent_pack_unload();
+        db.ignore_replacements = true;
+
+        let mut counting = progress.add_child("counting".into());
+        counting.init(None, gix_features::progress::count("objects"));
+        let (counts, _outcome) = gix_pack::data::output::count::objects_unthreaded(
+            &db,
+            &mut objects.map(Ok::<_, Box<dyn std::error::Error + Send + Sync + 'static>>),
+            &counting,
+            should_interrupt,
+            gix_pack::data::output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+        )?;
+
+        let num_objects = counts.len();
+        let entries = crate::parallel::InOrderIter::from(gix_pack::data::output::entry::iter_from_counts(
+            counts,
+            db,
+            Box::new(progress.add_child("creating entries".into())),
+            gix_pack::data::output::entry::iter_from_counts::Options {
+                thread_limit: None,
+                mode: gix_pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+            },
+        ));
+        let mut writing = progress.add_child("writing".into());
+        writing.init(None, gix_features::progress::bytes());
+        for written in gix_pack::data::output::bytes::FromEntriesIter::new(
+            entries,
+            out,
+            num_objects as u32,
+            gix_pack::data::Version::default(),
+            self.object_hash(),
+        ) {
+            writing.inc_by(written? as usize);
+        }
+        Ok(())
+    }
+}
+
+fn push_ref(refs: &mut Vec<Ref>, name: &BStr, id: ObjectId) {
+    if !refs.iter().any(|r| r.name == name) {
+        refs.push(Ref { id, name: name.into() });
+    }
+}
@@ -61,8 +61,8 @@ impl Repository {
         let new_tree = new_tree.into().unwrap_or(&empty_tree);
         let mut out = Vec::new();
         gix_diff::tree_with_rewrites(
-            TreeRefIter::from_bytes(&old_tree.data),
-            TreeRefIter::from_bytes(&new_tree.data),
+            TreeRefIter::from_bytes(&old_tree.data, self.object_hash()),
+            TreeRefIter::from_bytes(&new_tree.data, self.object_hash()),
             &mut cache,
             &mut Default::default(),
             &self.objects,
@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+/// The error returned by [Repository::fetch_head()](crate::Repository::fetch_head()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read FETCH_HEAD at \"{}\"", path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error(transparent)]
+    Decode(#[from] gix_fetchhead::decode::Error),
+}
+
+impl crate::Repository {
+    /// Read the `FETCH_HEAD` file written by the most recent fetch, or return `None` if there is none.
+    ///
+    /// Use [`FetchHead::merge_heads()`](gix_fetchhead::FetchHead::merge_heads()) to learn which of the fetched refs to merge,
+    /// like `git pull` would.
+    pub fn fetch_head(&self) -> Result<Option<gix_fetchhead::FetchHead>, Error> {
+        let path = self.git_dir().join("FETCH_HEAD");
+        match std::fs::read(&path) {
+            Ok(data) => Ok(Some(gix_fetchhead::FetchHead::from_bytes(&data)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::Io { source, path }),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::atomic::AtomicBool;
+
+use crate::{bstr::BString, remote, ObjectId};
+
+/// The error returned by [Repository::fetch_missing_objects()](crate::Repository::fetch_missing_objects()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The repository isn't a partial clone as 'extensions.partialClone' isn't set")]
+    NoPromisorRemote,
+    #[error(transparent)]
+    FindRemote(#[from] remote::find::existing::Error),
+    #[error("The promisor remote {name:?} has no url to fetch from")]
+    MissingUrl { name: BString },
+    #[error(transparent)]
+    RemoteInit(#[from] remote::init::Error),
+    #[error(transparent)]
+    Connect(#[from] remote::connect::Error),
+    #[error(transparent)]
+    PrepareFetch(#[from] remote::fetch::prepare::Error),
+    #[error(transparent)]
+    Fetch(#[from] remote::fetch::Error),
+}
+
+impl crate::Repository {
+    /// Fetch all objects in `ids` from the promisor remote of this partial clone, as named by `extensions.partialClone`,
+    /// in a single batch and without updating any reference.
+    ///
+    /// This is what happens automatically when objects are found to be missing in a partial clone, but calling it with
+    /// all objects known to be needed avoids fetching them one by one.
+    /// Note that callers should only pass objects that are actually missing, which is what
+    /// [`fetch_missing()`](gix_odb::Cache::fetch_missing()) on [`objects`](crate::Repository::objects) does.
+    ///
+    /// ### Deviation
+    ///
+    /// Like `git`, the objects are fetched with the `blob:none` filter so that wanted trees don't bring in their blobs,
+    /// but only one promisor remote is supported.
+    pub fn fetch_missing_objects(&self, ids: &[ObjectId]) -> Result<(), Error> {
+        let name = self
+            .config
+            .resolved
+            .string(crate::config::tree::Extensions::PARTIAL_CLONE)
+            .ok_or(Error::NoPromisorRemote)?;
+        let promisor = self.find_remote(name.as_ref())?;
+        let url = promisor
+            .url(remote::Direction::Fetch)
+            .ok_or_else(|| Error::MissingUrl {
+                name: name.clone().into_owned(),
+            })?
+            .to_owned();
+        let remote = self
+            .remote_at_without_url_rewrite(url)?
+            .with_fetch_tags(remote::fetch::Tags::None)
+            .with_filter(Some(remote::fetch::Filter::BlobNone));
+        let options = remote::ref_map::Options {
+            extra_refspecs: ids
+                .iter()
+                .map(|id| {
+                    gix_refspec::parse(id.to_string().as_str().into(), gix_refspec::parse::Operation::Fetch)
+                        .expect("object ids are valid refspecs")
+                        .to_owned()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        remote
+            .connect(remote::Direction::Fetch)?
+            .prepare_fetch(gix_features::progress::Discard, options)?
+            .with_write_fetch_head(false)
+            .receive(gix_features::progress::Discard, &AtomicBool::default())?;
+        Ok(())
+    }
+}
@@ -42,4 +42,20 @@ impl crate::Repository {
                 _ => Err(err),
             })?)
     }
+
+    /// Return the reachability bitmap of the object database if it is available *and* enabled in the Git configuration.
+    ///
+    /// It allows to learn which objects are reachable from commits without traversing them, and like in `git`,
+    /// the bitmap of the multi-pack index is preferred over the one of a pack.
+    pub fn bitmap_index_if_enabled(
+        &self,
+    ) -> Result<Option<gix_pack::bitmap::Index>, super::bitmap_index_if_enabled::Error> {
+        if !self.config.may_use_bitmaps()? {
+            return Ok(None);
+        }
+        Ok(gix_pack::bitmap::Index::discover(
+            self.objects.store_ref().path(),
+            self.object_hash(),
+        )?)
+    }
 }
@@ -46,7 +46,8 @@ impl PartialEq<crate::Repository> for crate::Repository {
 
 impl From<&crate::ThreadSafeRepository> for crate::Repository {
     fn from(repo: &crate::ThreadSafeRepository) -> Self {
-        crate::Repository::from_refs_and_objects(
+        #[cfg_attr(not(feature = "blocking-network-client"), allow(unused_mut))]
+        let mut out = crate::Repository::from_refs_and_objects(
             repo.refs.clone(),
             gix_odb::memory::Proxy::from(gix_odb::Cache::from(repo.objects.to_handle())).with_write_passthrough(),
             repo.work_tree.clone(),
@@ -58,13 +59,17 @@ impl From<&crate::ThreadSafeRepository> for crate::Repository {
             repo.shallow_commits.clone(),
             #[cfg(feature = "attributes")]
             repo.modules.clone(),
-        )
+        );
+        #[cfg(feature = "blocking-network-client")]
+        crate::repository::init::setup_fetch_missing(&mut out);
+        out
     }
 }
 
 impl From<crate::ThreadSafeRepository> for crate::Repository {
     fn from(repo: crate::ThreadSafeRepository) -> Self {
-        crate::Repository::from_refs_and_objects(
+        #[cfg_attr(not(feature = "blocking-network-client"), allow(unused_mut))]
+        let mut out = crate::Repository::from_refs_and_objects(
             repo.refs,
             gix_odb::memory::Proxy::from(gix_odb::Cache::from(repo.objects.to_handle())).with_write_passthrough(),
             repo.work_tree,
@@ -76,7 +81,10 @@ impl From<crate::ThreadSafeRepository> for crate::Repository {
             repo.shallow_commits,
             #[cfg(feature = "attributes")]
             repo.modules.clone(),
-        )
+        );
+        #[cfg(feature = "blocking-network-client")]
+        crate::repository::init::setup_fetch_missing(&mut out);
+        out
     }
 }
 
@@ -150,6 +158,7 @@ impl gix_object::Find for crate::Repository {
             buffer.clear();
             return Ok(Some(gix_object::Data {
                 kind: gix_object::Kind::Tree,
+                hash_kind: self.object_hash(),
                 data: &[],
             }));
         }
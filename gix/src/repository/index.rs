@@ -149,6 +149,16 @@ impl crate::Repository {
             self.git_dir().join("index"),
         ))
     }
+
+    /// Write all entries of `index` as trees into the object database and return the id of the root tree,
+    /// similar to `git write-tree`.
+    ///
+    /// Trees that are still valid in the tree-cache extension of `index` are reused without writing them again, and the
+    /// extension is updated to reflect the written trees, so that `index` should be written as well to benefit from it next time.
+    /// Use [`commit()`](Self::commit()) with the returned tree to create a commit from the index.
+    pub fn write_tree(&self, index: &mut gix_index::State) -> Result<crate::Id<'_>, gix_index::write_tree::Error> {
+        Ok(crate::Id::from_id(index.write_tree(&self.objects)?, self))
+    }
 }
 
 impl std::ops::Deref for IndexPersistedOrInMemory {
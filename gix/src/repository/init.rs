@@ -36,6 +36,30 @@ impl crate::Repository {
     }
 }
 
+/// Let `repo` fetch objects that are missing in a partial clone on demand, or stop doing so if it isn't a partial clone (anymore).
+///
+/// Note that objects that are found missing while fetching don't cause another fetch.
+#[cfg(feature = "blocking-network-client")]
+pub(crate) fn setup_fetch_missing(repo: &mut crate::Repository) {
+    if repo
+        .config
+        .resolved
+        .string(crate::config::tree::Extensions::PARTIAL_CLONE)
+        .is_none()
+    {
+        repo.objects.unset_fetch_missing();
+        return;
+    }
+    let git_dir = repo.git_dir().to_owned();
+    let options = repo.options.clone();
+    repo.objects.set_fetch_missing(move |ids| {
+        let mut repo = crate::open_opts(&git_dir, options.clone())?;
+        repo.objects.unset_fetch_missing();
+        repo.fetch_missing_objects(ids)?;
+        Ok(())
+    });
+}
+
 #[cfg_attr(not(feature = "max-performance-safe"), allow(unused_variables, unused_mut))]
 pub(crate) fn setup_objects(objects: &mut crate::OdbHandle, config: &crate::config::Cache) {
     #[cfg(feature = "max-performance-safe")]
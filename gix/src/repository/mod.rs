@@ -17,12 +17,21 @@ pub enum Kind {
     },
 }
 
+///
+#[cfg(feature = "status")]
+pub mod add_to_index;
+///
+#[cfg(all(feature = "worktree-mutation", feature = "revision"))]
+pub mod add_worktree;
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
 mod cache;
 #[cfg(feature = "worktree-mutation")]
 mod checkout;
 mod config;
+///
+#[cfg(feature = "bundle")]
+pub mod create_bundle;
 
 ///
 #[cfg(feature = "blob-diff")]
@@ -31,6 +40,11 @@ mod diff;
 #[cfg(feature = "dirwalk")]
 mod dirwalk;
 ///
+pub mod fetch_head;
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod fetch_missing_objects;
+///
 #[cfg(feature = "attributes")]
 pub mod filter;
 ///
@@ -51,15 +65,38 @@ mod merge;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+///
+#[cfg(feature = "receive-pack")]
+pub mod receive_pack;
 mod reference;
 mod remote;
+///
+#[cfg(feature = "status")]
+pub mod remove_from_index;
+///
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+pub mod reset;
+///
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+pub mod restore;
 mod revision;
 mod shallow;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
+///
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+pub mod switch;
 mod thread_safe;
+///
+#[cfg(feature = "bundle")]
+pub mod unbundle;
+///
+#[cfg(feature = "upload-pack")]
+pub mod upload_pack;
 mod worktree;
+///
+pub mod write_commit_graph;
 
 ///
 #[cfg(feature = "blob-diff")]
@@ -314,6 +351,19 @@ pub mod commit_graph_if_enabled {
     }
 }
 
+///
+pub mod bitmap_index_if_enabled {
+    /// The error returned by [Repository::bitmap_index_if_enabled()](crate::Repository::bitmap_index_if_enabled()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        OpenBitmap(#[from] gix_pack::bitmap::init::Error),
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod index_from_tree {
@@ -0,0 +1,69 @@
+use std::{
+    io::{Read, Write},
+    sync::atomic::AtomicBool,
+};
+
+use gix_protocol::{handshake::Ref, receive_pack::Hooks};
+
+/// The error returned by [Repository::receive_pack()](crate::Repository::receive_pack()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    InitReferenceIteration(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    InitReferenceIterationPlatform(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    IterReferences(Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FollowReference(#[from] crate::reference::follow::to_object::Error),
+    #[error(transparent)]
+    ParseCommitterTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    ReceivePack(#[from] gix_protocol::receive_pack::Error),
+}
+
+impl crate::Repository {
+    /// Accept a push from a client connected through `input` and `output` just like `git receive-pack` would, using the given
+    /// `protocol` version.
+    ///
+    /// All references are advertised, and the client may update any of them as long as it knows their current value.
+    /// Received objects are quarantined until all updates were checked, with `hooks` being able to reject them, and the
+    /// committer of this repository is used for the reflog of updated references.
+    /// `input` and `output` are typically connected to a process's `stdin` and `stdout`, or to a socket.
+    ///
+    /// Set `should_interrupt` to abort receiving a pack.
+    pub fn receive_pack(
+        &self,
+        input: impl Read,
+        output: impl Write,
+        protocol: gix_protocol::transport::Protocol,
+        hooks: &mut dyn Hooks,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_protocol::receive_pack::Outcome, Error> {
+        let mut refs = Vec::new();
+        for r in self.references()?.all()? {
+            let mut r = r.map_err(Error::IterReferences)?;
+            let full_ref_name = r.name().as_bstr().to_owned();
+            let object = r.follow_to_object()?.detach();
+            refs.push(Ref::Direct { full_ref_name, object });
+        }
+        Ok(gix_protocol::receive_pack(
+            &self.objects,
+            &refs,
+            &self.refs,
+            self.objects.store_ref().path(),
+            input,
+            output,
+            hooks,
+            gix_protocol::receive_pack::Options {
+                protocol,
+                object_hash: self.object_hash(),
+                agent: Some(crate::env::agent().into()),
+                committer: self.committer().transpose()?.map(Into::into),
+                trace: false,
+            },
+            should_interrupt,
+        )?)
+    }
+}
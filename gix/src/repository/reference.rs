@@ -44,12 +44,12 @@ impl crate::Repository {
     ///
     /// Namespaces allow to partition references, and is configured per `Easy`.
     pub fn namespace(&self) -> Option<&gix_ref::Namespace> {
-        self.refs.namespace.as_ref()
+        self.refs.namespace()
     }
 
     /// Remove the currently set reference namespace and return it, affecting only this `Easy`.
     pub fn clear_namespace(&mut self) -> Option<gix_ref::Namespace> {
-        self.refs.namespace.take()
+        self.refs.set_namespace(None)
     }
 
     /// Set the reference namespace to the given value, like `"foo"` or `"foo/bar"`.
@@ -64,7 +64,7 @@ impl crate::Repository {
         gix_validate::reference::name::Error: From<E>,
     {
         let namespace = gix_ref::namespace::expand(namespace)?;
-        Ok(self.refs.namespace.replace(namespace))
+        Ok(self.refs.set_namespace(Some(namespace)))
     }
 
     // TODO: more tests or usage
@@ -261,14 +261,14 @@ impl crate::Repository {
     pub fn find_reference<'a, Name, E>(&self, name: Name) -> Result<Reference<'_>, reference::find::existing::Error>
     where
         Name: TryInto<&'a PartialNameRef, Error = E> + Clone,
-        gix_ref::file::find::Error: From<E>,
+        gix_ref::store::find::Error: From<E>,
     {
         // TODO: is there a way to just pass `partial_name` to `try_find_reference()`? Compiler freaks out then
         //       as it still wants to see `E` there, not `Infallible`.
         let partial_name = name
             .clone()
             .try_into()
-            .map_err(|err| reference::find::Error::Find(gix_ref::file::find::Error::from(err)))?;
+            .map_err(|err| reference::find::Error::Find(gix_ref::store::find::Error::from(err)))?;
         self.try_find_reference(name)?
             .ok_or_else(|| reference::find::existing::Error::NotFound {
                 name: partial_name.to_owned(),
@@ -293,7 +293,7 @@ impl crate::Repository {
     pub fn try_find_reference<'a, Name, E>(&self, name: Name) -> Result<Option<Reference<'_>>, reference::find::Error>
     where
         Name: TryInto<&'a PartialNameRef, Error = E>,
-        gix_ref::file::find::Error: From<E>,
+        gix_ref::store::find::Error: From<E>,
     {
         match self.refs.try_find(name) {
             Ok(r) => match r {
@@ -182,6 +182,32 @@ impl crate::Repository {
             Some(Err(err)) => return Some(Err(err)),
             None => Default::default(),
         };
+        let promisor = match config
+            .boolean_filter(format!("remote.{}.{}", name_or_url, "promisor"), &mut filter)
+            .map(|value| config::tree::Remote::PROMISOR.enrich_error(value))
+        {
+            Some(Ok(v)) => v,
+            Some(Err(err)) => return Some(Err(find::Error::Promisor(err))),
+            None => false,
+        } || config
+            .string(config::tree::Extensions::PARTIAL_CLONE)
+            .is_some_and(|name| name.as_ref() == name_or_url);
+        let partial_clone_filter = promisor
+            .then(|| {
+                config
+                    .string_filter(format!("remote.{}.{}", name_or_url, "partialCloneFilter"), &mut filter)
+                    .map(|value| {
+                        config::tree::Remote::PARTIAL_CLONE_FILTER
+                            .try_into_filter(value)
+                            .map_err(find::Error::PartialCloneFilter)
+                    })
+            })
+            .flatten();
+        let partial_clone_filter = match partial_clone_filter {
+            Some(Ok(v)) => Some(v),
+            Some(Err(err)) => return Some(Err(err)),
+            None => None,
+        };
 
         match (url, fetch_specs, push_url, push_specs) {
             (None, None, None, None) => None,
@@ -217,6 +243,8 @@ impl crate::Repository {
                         push_specs,
                         rewrite_urls,
                         fetch_tags,
+                        partial_clone_filter,
+                        promisor,
                         self,
                     )
                     .map_err(Into::into),
@@ -0,0 +1,171 @@
+use std::collections::BTreeSet;
+
+use gix_index::entry::Stage;
+use gix_status::index_as_worktree::{Change, EntryStatus};
+
+use crate::bstr::{BStr, BString};
+use crate::status::{index_worktree::Item, Submodule, UntrackedFiles};
+
+/// The error returned by [Repository::remove_from_index()](crate::Repository::remove_from_index()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot remove files from the worktree of a bare repository")]
+    BareRepository,
+    #[error("'{path}' has staged content different from both the file and HEAD")]
+    StagedAndLocalChanges { path: BString },
+    #[error("'{path}' has changes staged in the index")]
+    StagedChanges { path: BString },
+    #[error("'{path}' has local modifications")]
+    LocalChanges { path: BString },
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    HeadTree(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    Status(#[from] crate::status::Error),
+    #[error(transparent)]
+    StatusIter(#[from] crate::status::into_iter::Error),
+    #[error(transparent)]
+    IndexWorktreeStatus(#[from] crate::status::index_worktree::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Options for use in [Repository::remove_from_index()](crate::Repository::remove_from_index()).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// If `true`, only the entries are removed from the index, while files in the worktree remain untouched,
+    /// like `git rm --cached` does.
+    pub cached: bool,
+    /// If `true`, entries and files are removed even if that would lose changes that exist only in the index or
+    /// the worktree, like `git rm --force` does.
+    pub force: bool,
+}
+
+impl crate::Repository {
+    /// Remove all entries matching `patterns` from `index`, along with their files in the worktree unless [`Options::cached`]
+    /// is set, similar to `git rm -r <patterns>`, and return the paths of all removed entries in order.
+    /// Empty `patterns` match nothing.
+    ///
+    /// Unless [`Options::force`] is set, the operation fails before making any change if an entry differs from `HEAD` and
+    /// its file would be removed, or if its file has local modifications, as these changes would be lost otherwise.
+    /// Conflicting entries are always removed.
+    ///
+    /// The tree-cache extension is invalidated for all removed paths, but note that `index` has to be written by the caller
+    /// for the changes to be visible to others.
+    pub fn remove_from_index(
+        &self,
+        index: &mut gix_index::File,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: Options,
+    ) -> Result<Vec<BString>, Error> {
+        let patterns: Vec<BString> = patterns.into_iter().map(|p| p.as_ref().to_owned()).collect();
+        if patterns.is_empty() {
+            return Ok(Vec::new());
+        }
+        let workdir = self.workdir();
+        if workdir.is_none() && !options.cached {
+            return Err(Error::BareRepository);
+        }
+
+        let mut pathspec = self.pathspec(
+            true, /* empty patterns match prefix */
+            patterns.iter(),
+            true, /* inherit ignore case */
+            index,
+            gix_worktree::stack::state::attributes::Source::IdMapping,
+        )?;
+        let mut paths = BTreeSet::new();
+        let mut conflicts = BTreeSet::new();
+        for entry in index
+            .prefixed_entries(pathspec.search().common_prefix())
+            .unwrap_or_default()
+        {
+            let path = entry.path(index);
+            if !pathspec.is_included(path, Some(false)) {
+                continue;
+            }
+            if entry.stage() != Stage::Unconflicted {
+                conflicts.insert(path.to_owned());
+            }
+            paths.insert(path.to_owned());
+        }
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if !options.force {
+            let head_index = match self.head()?.id() {
+                Some(_) => Some(self.index_from_tree(&self.head_tree_id()?)?),
+                None => None,
+            };
+            let local_changes = if workdir.is_some() {
+                self.local_changes(index, patterns)?
+            } else {
+                BTreeSet::new()
+            };
+            for path in paths.iter().filter(|path| !conflicts.contains(*path)) {
+                let path_ref: &BStr = path.as_ref();
+                let entry = index
+                    .entry_by_path_and_stage(path_ref, Stage::Unconflicted)
+                    .expect("unconflicted entries have stage 0");
+                let is_staged = !head_index
+                    .as_ref()
+                    .and_then(|head| head.entry_by_path_and_stage(path_ref, Stage::Unconflicted))
+                    .is_some_and(|head| head.id == entry.id && head.mode == entry.mode);
+                let is_local = local_changes.contains(path_ref);
+                let err = match (is_staged, is_local) {
+                    (true, true) => Error::StagedAndLocalChanges { path: path.clone() },
+                    (true, false) if !options.cached => Error::StagedChanges { path: path.clone() },
+                    (false, true) if !options.cached => Error::LocalChanges { path: path.clone() },
+                    _ => continue,
+                };
+                return Err(err);
+            }
+        }
+
+        index.remove_entries(|_, path, _| paths.contains(path));
+        if let Some(tree) = index.tree_mut() {
+            for path in &paths {
+                tree.invalidate_path(path.as_ref());
+            }
+        }
+        if let Some(workdir) = workdir.filter(|_| !options.cached) {
+            for path in &paths {
+                crate::util::remove_file_and_empty_parents(workdir, path.as_ref())?;
+            }
+        }
+        Ok(paths.into_iter().collect())
+    }
+
+    /// Return the paths of all entries in `index` matching `patterns` whose worktree file was modified, without deleted files.
+    fn local_changes(&self, index: &gix_index::File, patterns: Vec<BString>) -> Result<BTreeSet<BString>, Error> {
+        let iter = self
+            .status(crate::progress::Discard)?
+            .index(crate::worktree::IndexPersistedOrInMemory::InMemory(index.clone()))
+            .index_worktree_rewrites(None)
+            .untracked_files(UntrackedFiles::None)
+            .index_worktree_submodules(Submodule::Given {
+                ignore: crate::submodule::config::Ignore::Dirty,
+                check_dirty: false,
+            })
+            .into_index_worktree_iter(patterns)?;
+        let mut out = BTreeSet::new();
+        for item in iter {
+            if let Item::Modification { rela_path, status, .. } = item? {
+                if !matches!(
+                    status,
+                    EntryStatus::NeedsUpdate(_) | EntryStatus::Change(Change::Removed)
+                ) {
+                    out.insert(rela_path);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeSet;
+
+use gix_hash::ObjectId;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::bstr::{BStr, BString};
+use crate::worktree::update;
+
+/// The error returned by [Repository::reset()](crate::Repository::reset()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot reset the index in a bare repository")]
+    BareRepository,
+    #[error("Cannot do a soft reset in the middle of a merge")]
+    SoftResetDuringMerge,
+    #[error(transparent)]
+    RevParse(#[from] crate::revision::spec::parse::single::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelObject(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    CheckoutTree(#[from] crate::repository::switch::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error("Could not remove the state file at '{}'", path.display())]
+    RemoveStateFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Determine what [Repository::reset()](crate::Repository::reset()) changes besides `HEAD`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Only move `HEAD`, and keep the index and the worktree as they are, like `git reset --soft` does.
+    Soft,
+    /// Move `HEAD` and make the index match the target commit, but keep the worktree as it is, like `git reset --mixed` does.
+    #[default]
+    Mixed,
+    /// Move `HEAD` and make the index and the worktree match the target commit, discarding all changes to tracked files,
+    /// like `git reset --hard` does. Untracked files are kept.
+    Hard,
+}
+
+/// The outcome of [Repository::reset()](crate::Repository::reset()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The commit `HEAD` pointed to before, or `None` if it was unborn. It's also stored in `ORIG_HEAD`.
+    pub previous: Option<ObjectId>,
+    /// The commit `HEAD` now points to.
+    pub id: ObjectId,
+    /// The sorted paths of all files that were written to or deleted from the worktree, which is only done in [`Mode::Hard`].
+    pub updated: Vec<BString>,
+}
+
+/// Files in the `.git` directory that track the state of operations which are aborted by a reset.
+const STATE_FILES: &[&str] = &[
+    "MERGE_HEAD",
+    "MERGE_MSG",
+    "MERGE_MODE",
+    "MERGE_RR",
+    "AUTO_MERGE",
+    "SQUASH_MSG",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+];
+
+impl crate::Repository {
+    /// Set `HEAD`, or the branch it points to, to the commit that the revision specification `target` resolves to, and
+    /// depending on `mode`, make the index and the worktree match it, similar to `git reset --soft|--mixed|--hard <target>`.
+    ///
+    /// The previous commit is written to `ORIG_HEAD`, and the change is recorded in the reference logs of `HEAD` and the
+    /// branch it points to.
+    /// State files of operations that are in progress, like `MERGE_HEAD` of an unfinished merge, are removed as the
+    /// operation is aborted.
+    pub fn reset(&self, target: impl AsRef<BStr>, mode: Mode) -> Result<Outcome, Error> {
+        let target = target.as_ref();
+        let commit = self.rev_parse_single(target)?.object()?.peel_to_commit()?;
+        let previous = self.head()?.id().map(crate::Id::detach);
+
+        let updated = match mode {
+            Mode::Soft => {
+                let is_merging = self.state() == Some(crate::state::InProgress::Merge)
+                    || self.try_index()?.is_some_and(|index| update::is_unmerged(&index));
+                if is_merging {
+                    return Err(Error::SoftResetDuringMerge);
+                }
+                Vec::new()
+            }
+            Mode::Mixed => {
+                if self.workdir().is_none() {
+                    return Err(Error::BareRepository);
+                }
+                let mut index = self.index_from_tree(&commit.tree_id()?)?;
+                if let Some(previous) = self.try_index()? {
+                    update::carry_over_stat(&mut index, &previous, &BTreeSet::new());
+                }
+                index.write(Default::default())?;
+                Vec::new()
+            }
+            Mode::Hard => {
+                self.checkout_tree(
+                    commit.tree_id()?,
+                    crate::repository::switch::Options {
+                        force: true,
+                        ..Default::default()
+                    },
+                )?
+                .updated
+            }
+        };
+
+        let message = format!("reset: moving to {target}");
+        let mut edits = vec![RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(commit.id),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        }];
+        if let Some(previous) = previous {
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    expected: PreviousValue::Any,
+                    new: Target::Object(previous),
+                },
+                name: "ORIG_HEAD".try_into().expect("valid"),
+                deref: false,
+            });
+        }
+        self.edit_references(edits)?;
+
+        for name in STATE_FILES {
+            let path = self.path().join(name);
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(Error::RemoveStateFile { path, source }),
+            }
+        }
+        Ok(Outcome {
+            previous,
+            id: commit.id,
+            updated,
+        })
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::BTreeSet;
+
+use gix_hash::ObjectId;
+use gix_index::entry::Stage;
+
+use crate::bstr::{BStr, BString};
+use crate::worktree::update;
+
+/// The error returned by [Repository::restore()](crate::Repository::restore()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot restore files in the worktree of a bare repository")]
+    BareRepository,
+    #[error("'{path}' has unresolved conflicts and can't be restored from the index")]
+    Unmerged { path: BString },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelObject(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    HeadTree(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Options for use in [Repository::restore()](crate::Repository::restore()).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// The tree, or the commit or tag that can be peeled to a tree, to restore entries and files from.
+    ///
+    /// If `None`, the index is used when restoring the worktree only, and the tree of `HEAD` is used otherwise,
+    /// like `git restore` does.
+    pub source: Option<ObjectId>,
+    /// If `true`, restore the entries in the index, like `git restore --staged` does.
+    pub staged: bool,
+    /// If `true`, restore the files in the worktree, like `git restore --worktree` does.
+    ///
+    /// Note that the worktree is restored if neither this field nor [`staged`](Self::staged) is set.
+    pub worktree: bool,
+}
+
+impl crate::Repository {
+    /// Restore all index entries and worktree files that match `patterns` from the source configured in `options`,
+    /// similar to `git restore`, and return the sorted paths of all restored entries or files.
+    /// Empty `patterns` match nothing.
+    ///
+    /// Entries and files that don't exist in the source are removed, and local changes to restored files are discarded.
+    /// Files are written through the filter pipeline, and untracked files are never touched.
+    ///
+    /// Note that `index` has to be written by the caller for the changes to be visible to others, which is also
+    /// needed if only the worktree is restored from the index as the stat information of entries is updated.
+    pub fn restore(
+        &self,
+        index: &mut gix_index::File,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: Options,
+    ) -> Result<Vec<BString>, Error> {
+        let patterns: Vec<BString> = patterns.into_iter().map(|p| p.as_ref().to_owned()).collect();
+        if patterns.is_empty() {
+            return Ok(Vec::new());
+        }
+        let worktree = options.worktree || !options.staged;
+        if worktree && self.workdir().is_none() {
+            return Err(Error::BareRepository);
+        }
+        let source = match options.source {
+            Some(id) => Some(self.find_object(id)?.peel_to_tree()?.id),
+            None if options.staged => Some(self.head_tree_id_or_empty()?.detach()),
+            None => None,
+        };
+        let mut source = match source {
+            Some(tree) => self.index_from_tree(&tree)?,
+            None => index.clone(),
+        };
+
+        let mut pathspec = self.pathspec(
+            true, /* empty patterns match prefix */
+            patterns,
+            true, /* inherit ignore case */
+            index,
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+        )?;
+        let mut paths = BTreeSet::new();
+        for state in [&**index, &*source] {
+            for entry in state
+                .prefixed_entries(pathspec.search().common_prefix())
+                .unwrap_or_default()
+            {
+                let path = entry.path(state);
+                if pathspec.is_included(path, Some(false)) {
+                    paths.insert(path.to_owned());
+                }
+            }
+        }
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+        if options.source.is_none() && !options.staged {
+            if let Some(path) = paths.iter().find(|path| {
+                source
+                    .entry_by_path_and_stage(path.as_ref(), Stage::Unconflicted)
+                    .is_none()
+            }) {
+                return Err(Error::Unmerged { path: path.clone() });
+            }
+        }
+
+        if worktree {
+            update::checkout_paths::<Error>(self, &mut source, &paths)?;
+        }
+        if options.staged {
+            index.remove_entries(|_, path, _| paths.contains(path));
+            for path in &paths {
+                if let Some(entry) = source.entry_by_path_and_stage(path.as_ref(), Stage::Unconflicted) {
+                    let stat = if worktree { entry.stat } else { Default::default() };
+                    index.dangerously_push_entry(stat, entry.id, entry.flags, entry.mode, path.as_ref());
+                }
+            }
+            index.sort_entries();
+            if let Some(tree) = index.tree_mut() {
+                for path in &paths {
+                    tree.invalidate_path(path.as_ref());
+                }
+            }
+        } else if worktree && options.source.is_none() {
+            for entry in source.entries() {
+                let path = entry.path(&source);
+                if paths.contains(path) {
+                    if let Some(restored) = index.entry_mut_by_path_and_stage(path, Stage::Unconflicted) {
+                        restored.stat = entry.stat;
+                    }
+                }
+            }
+        }
+        Ok(paths.into_iter().collect())
+    }
+}
@@ -93,4 +93,14 @@ impl Repository {
                 }),
         ))
     }
+
+    /// Return the submodule named `name`, or `None` if there is no submodule configuration or no submodule with that name.
+    pub fn try_find_submodule(
+        &self,
+        name: &crate::bstr::BStr,
+    ) -> Result<Option<crate::Submodule<'_>>, submodule::modules::Error> {
+        Ok(self
+            .submodules()?
+            .and_then(|mut modules| modules.find(|sm| sm.name() == name)))
+    }
 }
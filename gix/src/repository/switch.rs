@@ -0,0 +1,276 @@
+use std::collections::BTreeSet;
+
+use gix_hash::ObjectId;
+use gix_index::entry::Stage;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+use crate::worktree::update;
+
+/// The error returned by [Repository::switch()](crate::Repository::switch()) and
+/// [Repository::checkout_tree()](crate::Repository::checkout_tree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot check out a tree in a bare repository")]
+    BareRepository,
+    #[error("Cannot check out a tree while the index has unresolved conflicts")]
+    UnmergedIndex,
+    #[error("Local changes to the following files would be overwritten: {}", paths.iter().map(|p| p.to_str_lossy()).collect::<Vec<_>>().join(", "))]
+    WouldOverwriteLocalChanges { paths: Vec<BString> },
+    #[error("The following untracked files would be overwritten: {}", paths.iter().map(|p| p.to_str_lossy()).collect::<Vec<_>>().join(", "))]
+    WouldOverwriteUntrackedFiles { paths: Vec<BString> },
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    FindExistingReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::to_kind::Error),
+    #[error(transparent)]
+    RevParse(#[from] crate::revision::spec::parse::single::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelObject(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    HeadTree(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    Status(#[from] crate::status::Error),
+    #[error(transparent)]
+    StatusIter(#[from] crate::status::into_iter::Error),
+    #[error(transparent)]
+    IndexWorktreeStatus(#[from] crate::status::index_worktree::Error),
+    #[error(transparent)]
+    DirwalkOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Dirwalk(#[from] crate::dirwalk::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Options for use in [Repository::switch()](crate::Repository::switch()) and
+/// [Repository::checkout_tree()](crate::Repository::checkout_tree()).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// If `true`, all local changes to tracked files in the index and the worktree are discarded, and untracked files
+    /// that are in the way are overwritten, like `git checkout --force` does.
+    /// Otherwise, the operation fails before making any change if changes would be lost.
+    pub force: bool,
+    /// If `true`, `HEAD` is detached at the target commit even if the target names a local branch, like
+    /// `git switch --detach` does.
+    pub detach: bool,
+}
+
+/// The outcome of [Repository::switch()](crate::Repository::switch()) and
+/// [Repository::checkout_tree()](crate::Repository::checkout_tree()), with all paths sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The tree that is now checked out.
+    pub tree: ObjectId,
+    /// The paths of all files that were written to or deleted from the worktree.
+    pub updated: Vec<BString>,
+    /// The paths of tracked files whose changes in the index or the worktree were carried over as they didn't conflict
+    /// with the checked out tree, similar to what `git checkout` lists with `M`.
+    pub local_changes: Vec<BString>,
+}
+
+impl crate::Repository {
+    /// Switch to `target`, which is the name of a local branch like `main` or `refs/heads/main`, or any revision specification
+    /// that resolves to a commit, similar to `git switch <branch>` or `git checkout <commit>`, and configured by `options`.
+    ///
+    /// The tree of the target commit is checked out with [`checkout_tree()`](Self::checkout_tree()), and `HEAD` is made
+    /// to point to the branch, or detached at the commit if `target` isn't a local branch or [`Options::detach`] is set.
+    /// The change of `HEAD` is recorded in its reference log.
+    pub fn switch(&self, target: impl AsRef<BStr>, options: Options) -> Result<Outcome, Error> {
+        let target = target.as_ref();
+        let branch = if options.detach {
+            None
+        } else {
+            let name: BString = if target.starts_with(b"refs/heads/") {
+                target.to_owned()
+            } else {
+                format!("refs/heads/{target}").into()
+            };
+            match FullName::try_from(name) {
+                Ok(name) => self.try_find_reference(name.as_ref())?,
+                Err(_) => None,
+            }
+        };
+        let (branch, commit) = match branch {
+            Some(mut branch) => {
+                let commit = branch.peel_to_commit()?;
+                (Some(branch.inner.name), commit)
+            }
+            None => (None, self.rev_parse_single(target)?.object()?.peel_to_commit()?),
+        };
+
+        let head = self.find_reference("HEAD")?.inner;
+        let from = match &head.target {
+            Target::Symbolic(name) => name.as_ref().shorten().to_owned(),
+            Target::Object(id) => id.to_string().into(),
+        };
+        let outcome = self.checkout_tree(commit.tree_id()?, options)?;
+        let new = match branch {
+            Some(name) => Target::Symbolic(name),
+            None => Target::Object(commit.id),
+        };
+        if new != head.target {
+            self.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("checkout: moving from {from} to {target}").into(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(head.target),
+                    new,
+                },
+                name: head.name,
+                deref: false,
+            })?;
+        }
+        Ok(outcome)
+    }
+
+    /// Make the index and the worktree match `tree`, with a two-way merge between the tree of `HEAD` and `tree` that keeps
+    /// local changes which don't conflict, similar to what `git checkout` does before updating `HEAD`, and configured by `options`.
+    ///
+    /// Files that differ between both trees are deleted, added or rewritten through the filter pipeline, along with their
+    /// entries in the index, which is written afterwards.
+    /// Unless [`Options::force`] is set, the operation fails before making any change if one of these files has local
+    /// modifications, if its entry in the index has staged changes, or if an untracked file would be overwritten.
+    /// Ignored files are considered expendable.
+    ///
+    /// Note that `HEAD` isn't changed, use [`switch()`](Self::switch()) for that.
+    pub fn checkout_tree(&self, tree: impl Into<ObjectId>, options: Options) -> Result<Outcome, Error> {
+        let workdir = self.workdir().ok_or(Error::BareRepository)?;
+        let tree = tree.into();
+        let index = gix_index::File::clone(&*self.index_or_empty()?);
+        if !options.force && update::is_unmerged(&index) {
+            return Err(Error::UnmergedIndex);
+        }
+        let head = self.index_from_tree(&self.head_tree_id_or_empty()?)?;
+        let target = self.index_from_tree(&tree)?;
+
+        let worktree_changes = update::worktree_changes::<Error>(self, &index)?;
+        let mut paths = BTreeSet::new();
+        update::changed_paths(&index, &target, &mut paths);
+
+        if options.force {
+            // Deleted files are restored as well.
+            paths.extend(worktree_changes);
+            let mut target = target;
+            update::carry_over_stat(&mut target, &index, &paths);
+            update::checkout_paths::<Error>(self, &mut target, &paths)?;
+            target.write(Default::default())?;
+            return Ok(Outcome {
+                tree,
+                updated: paths.into_iter().collect(),
+                local_changes: Vec::new(),
+            });
+        }
+
+        let local_changes: BTreeSet<BString> = worktree_changes
+            .into_iter()
+            .filter(|path| {
+                workdir
+                    .join(gix_path::from_bstr(path.as_bstr()))
+                    .symlink_metadata()
+                    .is_ok()
+            })
+            .collect();
+        let same = |a: Option<&gix_index::Entry>, b: Option<&gix_index::Entry>| match (a, b) {
+            (Some(a), Some(b)) => a.id == b.id && a.mode == b.mode,
+            (None, None) => true,
+            _ => false,
+        };
+        let mut kept = BTreeSet::new();
+        let mut overwritten = Vec::new();
+        let mut new_paths = Vec::new();
+        paths.retain(|path| {
+            let path = path.as_bstr();
+            let entry = index.entry_by_path_and_stage(path, Stage::Unconflicted);
+            let in_head = head.entry_by_path_and_stage(path, Stage::Unconflicted);
+            let in_target = target.entry_by_path_and_stage(path, Stage::Unconflicted);
+            if same(in_head, in_target) {
+                kept.insert(path.to_owned());
+                return false;
+            }
+            if !same(entry, in_head) || (entry.is_some() && local_changes.contains(path)) {
+                overwritten.push(path.to_owned());
+            } else if entry.is_none() {
+                new_paths.push(path.to_owned());
+            }
+            true
+        });
+        if !overwritten.is_empty() {
+            return Err(Error::WouldOverwriteLocalChanges { paths: overwritten });
+        }
+        if !new_paths.is_empty() {
+            let untracked: BTreeSet<BString> = update::untracked_files::<Error>(self, &index)?.into_iter().collect();
+            let in_the_way: Vec<_> = new_paths
+                .into_iter()
+                .filter(|path| is_in_the_way(path.as_bstr(), &untracked))
+                .collect();
+            if !in_the_way.is_empty() {
+                return Err(Error::WouldOverwriteUntrackedFiles { paths: in_the_way });
+            }
+        }
+
+        let mut index = index;
+        index.remove_entries(|_, path, _| paths.contains(path));
+        for path in &paths {
+            if let Some(entry) = target.entry_by_path_and_stage(path.as_bstr(), Stage::Unconflicted) {
+                index.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path.as_bstr());
+            }
+            if let Some(tree) = index.tree_mut() {
+                tree.invalidate_path(path.as_bstr());
+            }
+        }
+        index.sort_entries();
+        update::checkout_paths::<Error>(self, &mut index, &paths)?;
+        index.write(Default::default())?;
+
+        kept.extend(local_changes.into_iter().filter(|path| !paths.contains(path)));
+        Ok(Outcome {
+            tree,
+            updated: paths.into_iter().collect(),
+            local_changes: kept.into_iter().collect(),
+        })
+    }
+}
+
+/// Return `true` if checking out a new file at `path` would overwrite one of the `untracked` files, either because it's
+/// at the same location, in one of its leading directories, or in a directory at `path`.
+fn is_in_the_way(path: &BStr, untracked: &BTreeSet<BString>) -> bool {
+    let mut dir_prefix = BString::from(path);
+    dir_prefix.push(b'/');
+    let has_untracked_in_directory = untracked
+        .range(dir_prefix.clone()..)
+        .next()
+        .is_some_and(|candidate| candidate.starts_with(&dir_prefix));
+    has_untracked_in_directory
+        || path
+            .char_indices()
+            .filter(|(_, _, c)| *c == '/')
+            .map(|(start, _, _)| path[..start].as_bstr())
+            .chain(Some(path))
+            .any(|leading| untracked.contains(leading))
+}
@@ -0,0 +1,104 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_features::progress::DynNestedProgress;
+
+use crate::config::{cache::util::ApplyLeniency, tree::Pack};
+
+/// The error returned by [Repository::unbundle()](crate::Repository::unbundle()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The bundle uses {bundle} object ids, but the repository uses {local}")]
+    IncompatibleObjectHash {
+        local: gix_hash::Kind,
+        bundle: gix_hash::Kind,
+    },
+    #[error("The repository lacks the prerequisite commit(s) {}", ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    MissingPrerequisites { ids: Vec<gix_hash::ObjectId> },
+    #[error("Could not open the pack of the bundle")]
+    OpenPack(#[source] std::io::Error),
+    #[error(transparent)]
+    PackThreads(#[from] crate::config::unsigned_integer::Error),
+    #[error(transparent)]
+    PackIndexVersion(#[from] crate::config::key::GenericError),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+}
+
+impl crate::Repository {
+    /// Return the prerequisites of the bundle with `header` that aren't present in this repository, similar to `git bundle verify`.
+    ///
+    /// If the returned list is empty, the bundle can be [unbundled](Self::unbundle()), or fetched from.
+    ///
+    /// ### Deviation
+    ///
+    /// `git` also requires prerequisites to be reachable from a reference, but here it's enough for them to be present.
+    pub fn missing_bundle_prerequisites<'a>(
+        &self,
+        header: &'a gix_bundle::Header,
+    ) -> Vec<&'a gix_bundle::Prerequisite> {
+        header
+            .prerequisites
+            .iter()
+            .filter(|prerequisite| !self.has_object(prerequisite.id))
+            .collect()
+    }
+
+    /// Add the objects in the pack of `bundle` to the object database, after assuring that all of its prerequisites
+    /// are present, similar to `git bundle unbundle`.
+    ///
+    /// References aren't changed, and it's up to the caller to update them from the references listed in the
+    /// [bundle header](gix_bundle::File::header).
+    /// Use `progress` to learn about the indexing of the pack, and `should_interrupt` to abort it.
+    ///
+    /// Note that `pack.threads` and `pack.indexVersion` are respected like when fetching.
+    pub fn unbundle(
+        &self,
+        bundle: &gix_bundle::File,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_pack::bundle::write::Outcome, Error> {
+        if bundle.header.object_hash != self.object_hash() {
+            return Err(Error::IncompatibleObjectHash {
+                local: self.object_hash(),
+                bundle: bundle.header.object_hash,
+            });
+        }
+        let missing = self.missing_bundle_prerequisites(&bundle.header);
+        if !missing.is_empty() {
+            return Err(Error::MissingPrerequisites {
+                ids: missing.into_iter().map(|prerequisite| prerequisite.id).collect(),
+            });
+        }
+
+        let thread_limit = self
+            .config
+            .resolved
+            .integer_filter(Pack::THREADS, &mut self.filter_config_section())
+            .map(|threads| Pack::THREADS.try_into_usize(threads))
+            .transpose()
+            .with_leniency(self.options.lenient_config)?;
+        let index_version = self
+            .config
+            .resolved
+            .integer(Pack::INDEX_VERSION)
+            .map(|value| Pack::INDEX_VERSION.try_into_index_version(value))
+            .transpose()
+            .with_leniency(self.options.lenient_config)?
+            .unwrap_or(gix_pack::index::Version::V2);
+        let mut pack = bundle.pack().map_err(Error::OpenPack)?;
+        Ok(gix_pack::Bundle::write_to_directory(
+            &mut pack,
+            Some(&self.objects.store_ref().path().join("pack")),
+            progress,
+            should_interrupt,
+            Some(Box::new(self.objects.clone())),
+            gix_pack::bundle::write::Options {
+                thread_limit,
+                index_version,
+                iteration_mode: gix_pack::data::input::Mode::Verify,
+                object_hash: self.object_hash(),
+            },
+        )?)
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    io::{Read, Write},
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use gix_hash::ObjectId;
+use gix_protocol::handshake::Ref;
+
+use crate::{
+    bstr::{BString, ByteVec},
+    config::tree::UploadPack,
+};
+
+/// The error returned by [Repository::upload_pack()](crate::Repository::upload_pack()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    InitReferenceIteration(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    InitReferenceIterationPlatform(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    IterReferences(Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FollowReference(#[from] crate::reference::follow::to_object::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error("Could not open the object database to serve packs from it")]
+    OpenObjectDatabase(#[from] std::io::Error),
+    #[error(transparent)]
+    BitmapConfig(#[from] crate::config::boolean::Error),
+    #[error("The bundle list configured in the `bundle` section is invalid")]
+    BundleList(#[from] gix_protocol::bundle_uri::parse::Error),
+    #[error(transparent)]
+    UploadPack(#[from] gix_protocol::upload_pack::Error),
+}
+
+impl crate::Repository {
+    /// Serve a client connected through `input` and `output` just like `git upload-pack` would, using the given `protocol` version.
+    ///
+    /// All references are advertised, with `HEAD` first, and the client may ask for any object in the object database.
+    /// This is the server-side counterpart of fetching or cloning, and `input` and `output` are typically connected to a process's
+    /// `stdin` and `stdout`, or to a socket.
+    ///
+    /// If enabled by `pack.useBitmaps`, a [reachability bitmap](Self::bitmap_index_if_enabled()) is used to quickly
+    /// learn which objects to send, and it's ignored if it can't be opened.
+    ///
+    /// If `uploadpack.advertiseBundleURIs` is set, the `bundle-uri` capability is advertised in protocol `V2` and
+    /// the bundle list configured in the `bundle` section is offered to clients.
+    ///
+    /// Set `should_interrupt` to abort sending a pack.
+    pub fn upload_pack(
+        &self,
+        input: impl Read,
+        output: impl Write,
+        protocol: gix_protocol::transport::Protocol,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_protocol::upload_pack::Outcome, Error> {
+        let refs = self.advertised_refs()?;
+        let mut db = self.objects.clone().into_arc()?.into_inner();
+        db.prevent_pack_unload();
+        db.ignore_replacements = true;
+        let bitmap = match self.bitmap_index_if_enabled() {
+            Ok(bitmap) => bitmap.map(Arc::new),
+            Err(crate::repository::bitmap_index_if_enabled::Error::ConfigBoolean(err)) => return Err(err.into()),
+            Err(_err) => {
+                gix_trace::warn!("ignoring reachability bitmap that failed to open: {_err}");
+                None
+            }
+        };
+        Ok(gix_protocol::upload_pack(
+            db,
+            &refs,
+            input,
+            output,
+            gix_protocol::upload_pack::Options {
+                protocol,
+                object_hash: self.object_hash(),
+                agent: Some(crate::env::agent().into()),
+                trace: false,
+                bitmap,
+                bundle_uri: self.advertised_bundle_list()?,
+            },
+            should_interrupt,
+        )?)
+    }
+
+    fn advertised_bundle_list(&self) -> Result<Option<gix_protocol::bundle_uri::List>, Error> {
+        if !self
+            .config
+            .resolved
+            .boolean(UploadPack::ADVERTISE_BUNDLE_URIS)
+            .map(|val| UploadPack::ADVERTISE_BUNDLE_URIS.enrich_error(val))
+            .transpose()?
+            .unwrap_or(false)
+        {
+            return Ok(None);
+        }
+        let mut lines = Vec::new();
+        for section in self.config.resolved.sections_by_name("bundle").into_iter().flatten() {
+            let mut prefix = BString::from("bundle.");
+            if let Some(subsection) = section.header().subsection_name() {
+                prefix.push_str(subsection);
+                prefix.push(b'.');
+            }
+            for name in section.value_names() {
+                if let Some(value) = section.value(name.as_ref()) {
+                    let mut line = prefix.clone();
+                    line.push_str(name.as_ref());
+                    line.push(b'=');
+                    line.push_str(value.as_ref());
+                    lines.push(line);
+                }
+            }
+        }
+        Ok(Some(gix_protocol::bundle_uri::List::from_lines(lines)?))
+    }
+
+    fn advertised_refs(&self) -> Result<Vec<Ref>, Error> {
+        let mut out = Vec::new();
+        match self.head()?.kind {
+            crate::head::Kind::Unborn(name) => out.push(Ref::Unborn {
+                full_ref_name: "HEAD".into(),
+                target: name.as_bstr().into(),
+            }),
+            crate::head::Kind::Detached { target, .. } => out.push(self.to_ref("HEAD".into(), None, target)?),
+            crate::head::Kind::Symbolic(r) => {
+                let mut r = crate::Reference::from_ref(r, self);
+                let target = r.name().as_bstr().to_owned();
+                let id = r.follow_to_object()?.detach();
+                out.push(self.to_ref("HEAD".into(), Some(target), id)?);
+            }
+        }
+        for r in self.references()?.all()? {
+            let mut r = r.map_err(Error::IterReferences)?;
+            let name = r.name().as_bstr().to_owned();
+            let symbolic_target = r.target().try_name().map(|name| name.as_bstr().to_owned());
+            let id = r.follow_to_object()?.detach();
+            out.push(self.to_ref(name, symbolic_target, id)?);
+        }
+        Ok(out)
+    }
+
+    fn to_ref(&self, name: BString, symbolic_target: Option<BString>, id: ObjectId) -> Result<Ref, Error> {
+        let peeled = if self.find_header(id)?.kind() == gix_object::Kind::Tag {
+            Some(self.find_object(id)?.peel_tags_to_end()?.id)
+        } else {
+            None
+        };
+        Ok(match (symbolic_target, peeled) {
+            (Some(target), peeled) => Ref::Symbolic {
+                full_ref_name: name,
+                target,
+                tag: peeled.map(|_| id),
+                object: peeled.unwrap_or(id),
+            },
+            (None, Some(peeled)) => Ref::Peeled {
+                full_ref_name: name,
+                tag: id,
+                object: peeled,
+            },
+            (None, None) => Ref::Direct {
+                full_ref_name: name,
+                object: id,
+            },
+        })
+    }
+}
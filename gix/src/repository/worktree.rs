@@ -1,4 +1,4 @@
-use crate::{worktree, Worktree};
+use crate::{bstr::BString, worktree, Worktree};
 
 /// Interact with individual worktrees and their information.
 impl crate::Repository {
@@ -30,6 +30,98 @@ impl crate::Repository {
         res.sort_by(|a, b| a.git_dir.cmp(&b.git_dir));
         Ok(res)
     }
+    /// Delete the private data of all linked worktrees that are [prunable](worktree::Proxy::prunable()), similar to
+    /// `git worktree prune`, and return their names along with the reason for pruning them, sorted by name.
+    /// If `dry_run` is `true`, nothing is deleted.
+    ///
+    /// Locked worktrees are never pruned.
+    pub fn prune_worktrees(&self, dry_run: bool) -> std::io::Result<Vec<(BString, worktree::proxy::Prunable)>> {
+        let worktrees_dir = self.common_dir().join("worktrees");
+        let iter = match std::fs::read_dir(&worktrees_dir) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut out = Vec::new();
+        for entry in iter {
+            let proxy = worktree::Proxy::new(self, entry?.path());
+            let Some(reason) = proxy.prunable() else {
+                continue;
+            };
+            if !dry_run {
+                if reason == worktree::proxy::Prunable::NotADirectory {
+                    std::fs::remove_file(proxy.git_dir())?;
+                } else {
+                    std::fs::remove_dir_all(proxy.git_dir())?;
+                }
+            }
+            out.push((proxy.id().to_owned(), reason));
+        }
+        if !dry_run {
+            // Like `git`, don't leave an empty directory behind.
+            std::fs::remove_dir(&worktrees_dir).ok();
+        }
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(out)
+    }
+
+    /// Reconnect linked worktrees with their private data in the common directory after either was moved manually,
+    /// similar to `git worktree repair [<path>…]`, and return the changes that were made.
+    ///
+    /// The `.git` files of all known worktrees are made to point to their private data.
+    /// Worktrees that were moved have to be passed as `worktree_paths` so their private data can be made to point to their
+    /// new location, which is found by the name of the worktree in their `.git` file.
+    pub fn repair_worktrees(
+        &self,
+        worktree_paths: impl IntoIterator<Item = impl AsRef<std::path::Path>>,
+    ) -> std::io::Result<Vec<worktree::Repair>> {
+        let mut out = Vec::new();
+        let worktrees_dir = gix_path::realpath(self.common_dir())
+            .map_err(std::io::Error::other)?
+            .join("worktrees");
+        for path in worktree_paths {
+            let path = gix_path::realpath(path.as_ref()).map_err(std::io::Error::other)?;
+            let dot_git = path.join(gix_discover::DOT_GIT_DIR);
+            let Ok(private_git_dir) = gix_discover::path::from_gitdir_file(&dot_git) else {
+                continue;
+            };
+            let Some(id) = private_git_dir.file_name() else {
+                continue;
+            };
+            let private_git_dir = worktrees_dir.join(id);
+            if !private_git_dir.is_dir() {
+                continue;
+            }
+            let proxy = worktree::Proxy::new(self, private_git_dir);
+            if proxy.base().ok().as_deref() != Some(path.as_path()) {
+                worktree::proxy::write_gitdir_file(proxy.git_dir(), &dot_git)?;
+                out.push(worktree::Repair::GitDirFile {
+                    id: proxy.id().to_owned(),
+                });
+            }
+        }
+
+        for proxy in self.worktrees()? {
+            let Ok(base) = proxy.base() else {
+                continue;
+            };
+            let dot_git = base.join(gix_discover::DOT_GIT_DIR);
+            if !dot_git.is_file() {
+                continue;
+            }
+            let private_git_dir = gix_path::realpath(proxy.git_dir()).map_err(std::io::Error::other)?;
+            let points_to_private_git_dir = gix_discover::path::from_gitdir_file(&dot_git)
+                .ok()
+                .and_then(|dir| gix_path::realpath(dir).ok())
+                .is_some_and(|dir| dir == private_git_dir);
+            if !points_to_private_git_dir {
+                worktree::proxy::write_dot_git_file(&dot_git, &private_git_dir)?;
+                out.push(worktree::Repair::DotGitFile { path: dot_git });
+            }
+        }
+        Ok(out)
+    }
+
     /// Return the repository owning the main worktree, typically from a linked worktree.
     ///
     /// Note that it might be the one that is currently open if this repository doesn't point to a linked worktree.
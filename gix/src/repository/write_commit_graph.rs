@@ -0,0 +1,393 @@
+use std::path::{Path, PathBuf};
+
+use gix_commitgraph::{bloom, file::write, Graph};
+use gix_diff::tree::Visit;
+use gix_hash::ObjectId;
+use gix_object::{
+    bstr::{BStr, BString},
+    FindExt,
+};
+
+/// The error returned by [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenCommitGraph(#[from] gix_commitgraph::init::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindTree(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    DiffTree(#[from] gix_diff::tree::Error),
+    #[error(transparent)]
+    GenerationVersion(#[from] crate::config::unsigned_integer::Error),
+    #[error(transparent)]
+    LockTimeout(#[from] crate::config::lock_timeout::Error),
+    #[error(transparent)]
+    Lock(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    Write(#[from] write::Error),
+    #[error("Could not update commit-graph files on disk")]
+    Io(#[from] std::io::Error),
+}
+
+/// Determine how the commit-graph is written.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Mode {
+    /// Write a single `objects/info/commit-graph` file with all commits reachable from the tips,
+    /// replacing all existing commit-graph files, like `git commit-graph write --reachable` does.
+    #[default]
+    Single,
+    /// Write only new commits into a new layer on top of the existing commit-graph chain, like
+    /// `git commit-graph write --reachable --split` does.
+    ///
+    /// An existing `objects/info/commit-graph` file becomes the base of the chain.
+    Split {
+        /// Merge the topmost layer into the new one as long as it doesn't have more than `size_multiple` times
+        /// as many commits as the new layer would have. Git uses `2` by default.
+        size_multiple: u32,
+    },
+}
+
+/// Options for use in [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// How to write the commit-graph.
+    pub mode: Mode,
+    /// If `true`, compute changed-path Bloom filters for all commits, which accelerate path-limited traversals.
+    ///
+    /// Note that they are also written if the existing commit-graph has them, like `git` does.
+    pub changed_paths: bool,
+}
+
+/// The outcome of [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+#[derive(Debug, Clone, Copy)]
+pub struct Outcome {
+    /// The checksum of the newly written file, or `None` if there was nothing to write.
+    pub checksum: Option<ObjectId>,
+    /// The amount of commits in the newly written file.
+    pub num_commits: u32,
+    /// The amount of files the commit-graph consists of after writing.
+    pub num_layers: usize,
+}
+
+impl crate::Repository {
+    /// Write the commit-graph with all commits reachable from `tips` according to `options`, to accelerate future traversals.
+    ///
+    /// Existing commit-graph files are used to avoid decoding commits and computing changed-path Bloom filters they already contain.
+    /// Corrected commit dates are written unless `commitGraph.generationVersion` is `1`.
+    pub fn write_commit_graph(
+        &self,
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let info_dir = self.objects.store_ref().path().join("info");
+        let graph = match Graph::from_info_dir(&info_dir) {
+            Ok(graph) => Some(graph),
+            Err(_) if !info_dir.join("commit-graph").is_file() && !chain_path(&info_dir).is_file() => None,
+            Err(err) => return Err(err.into()),
+        };
+        let existing_bloom_settings = graph.as_ref().and_then(|g| {
+            g.files()
+                .iter()
+                .rev()
+                .find_map(gix_commitgraph::File::bloom_filter_settings)
+        });
+        let write_options = write::Options {
+            object_hash: self.object_hash(),
+            corrected_commit_dates: self.config.commit_graph_generation_version()? != 1,
+            changed_paths: existing_bloom_settings.or_else(|| options.changed_paths.then(bloom::Settings::default)),
+        };
+        let (lock_mode, _) = self.config.lock_timeout()?;
+
+        match options.mode {
+            Mode::Single => {
+                let mut commits = self.collect_commits(tips, graph.as_ref(), None, write_options.changed_paths)?;
+                let num_existing_layers = graph.as_ref().map_or(0, |g| g.files().len());
+                drop(graph);
+                if commits.is_empty() {
+                    return Ok(Outcome {
+                        checksum: None,
+                        num_commits: 0,
+                        num_layers: num_existing_layers,
+                    });
+                }
+
+                self.add_changed_paths(&mut commits, write_options.changed_paths)?;
+                let mut lock =
+                    gix_lock::File::acquire_to_update_resource(info_dir.join("commit-graph"), lock_mode, None)?;
+                let outcome = gix_commitgraph::File::write_to(commits, None, &mut lock, write_options)?;
+                lock.commit().map_err(|err| err.error)?;
+                remove_chain(&info_dir)?;
+                Ok(Outcome {
+                    checksum: Some(outcome.checksum),
+                    num_commits: outcome.num_commits,
+                    num_layers: 1,
+                })
+            }
+            Mode::Split { size_multiple } => {
+                let Some(graph) = graph else {
+                    let commits = self.collect_commits(tips, None, None, None)?;
+                    return self.write_layer(&info_dir, commits, None, Vec::new(), write_options, lock_mode);
+                };
+                let mut commits = self.collect_commits(tips, Some(&graph), Some(&graph), None)?;
+                if commits.is_empty() {
+                    return Ok(Outcome {
+                        checksum: None,
+                        num_commits: 0,
+                        num_layers: graph.files().len(),
+                    });
+                }
+
+                let mut num_kept = graph.files().len();
+                let mut num_commits = commits.len() as u64;
+                while let Some(top) = num_kept.checked_sub(1).map(|idx| &graph.files()[idx]) {
+                    if u64::from(top.num_commits()) > u64::from(size_multiple) * num_commits {
+                        break;
+                    }
+                    num_commits += u64::from(top.num_commits());
+                    commits.extend(
+                        top.iter_commits()
+                            .map(|c| commit_from_graph(&graph, c, write_options.changed_paths)),
+                    );
+                    num_kept -= 1;
+                }
+
+                let mut files = graph.into_files();
+                let merged: Vec<_> = files.drain(num_kept..).map(|f| f.path().to_owned()).collect();
+                let base = if files.is_empty() {
+                    None
+                } else {
+                    Some(Graph::new(files)?)
+                };
+                self.write_layer(&info_dir, commits, base, merged, write_options, lock_mode)
+            }
+        }
+    }
+
+    /// Write `commits` as new layer on top of `base`, and remove the files of `merged` layers.
+    fn write_layer(
+        &self,
+        info_dir: &Path,
+        commits: Vec<write::Commit>,
+        base: Option<Graph>,
+        merged: Vec<PathBuf>,
+        write_options: write::Options,
+        lock_mode: gix_lock::acquire::Fail,
+    ) -> Result<Outcome, Error> {
+        let mut commits = commits;
+        self.add_changed_paths(&mut commits, write_options.changed_paths)?;
+        let graphs_dir = info_dir.join("commit-graphs");
+        let mut tempfile = gix_tempfile::new(
+            &graphs_dir,
+            gix_tempfile::ContainingDirectory::CreateAllRaceProof(Default::default()),
+            gix_tempfile::AutoRemove::Tempfile,
+        )?;
+        let outcome = gix_commitgraph::File::write_to(commits, base.as_ref(), &mut tempfile, write_options)?;
+
+        let mut chain = Vec::new();
+        let mut monolithic_base = None;
+        for file in base.into_iter().flat_map(Graph::into_files) {
+            let checksum = file.checksum().to_owned();
+            if file.path().parent() != Some(graphs_dir.as_path()) {
+                monolithic_base = Some((file.path().to_owned(), layer_path(&graphs_dir, &checksum)));
+            }
+            chain.push(checksum);
+        }
+        chain.push(outcome.checksum);
+
+        if let Some((from, to)) = monolithic_base {
+            std::fs::rename(from, to)?;
+        }
+        tempfile
+            .persist(layer_path(&graphs_dir, &outcome.checksum))
+            .map_err(|err| err.error)?;
+
+        let mut lock = gix_lock::File::acquire_to_update_resource(chain_path(info_dir), lock_mode, None)?;
+        for checksum in &chain {
+            use std::io::Write;
+            writeln!(lock, "{checksum}")?;
+        }
+        lock.commit().map_err(|err| err.error)?;
+
+        for path in merged {
+            remove_if_exists(&path)?;
+        }
+        Ok(Outcome {
+            checksum: Some(outcome.checksum),
+            num_commits: outcome.num_commits,
+            num_layers: chain.len(),
+        })
+    }
+
+    /// Collect all commits reachable from `tips` that aren't in `stop_at`, using `graph` to avoid decoding commits if possible.
+    /// Bloom filters stored in `graph` are reused if they were computed with `bloom_settings`.
+    fn collect_commits(
+        &self,
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        graph: Option<&Graph>,
+        stop_at: Option<&Graph>,
+        bloom_settings: Option<bloom::Settings>,
+    ) -> Result<Vec<write::Commit>, Error> {
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut queue: Vec<ObjectId> = tips.into_iter().map(Into::into).collect();
+        let mut out = Vec::new();
+        let mut buf = Vec::new();
+        while let Some(id) = queue.pop() {
+            if !seen.insert(id) || stop_at.is_some_and(|g| g.lookup(id).is_some()) {
+                continue;
+            }
+            let commit = match graph.and_then(|g| g.commit_by_id(id).map(|c| (g, c))) {
+                Some((graph, commit)) => commit_from_graph(graph, commit, bloom_settings),
+                None => {
+                    let commit = self.objects.find_commit(&id, &mut buf)?;
+                    write::Commit {
+                        id,
+                        tree: commit.tree(),
+                        parents: commit.parents().collect(),
+                        committer_timestamp: commit.committer().time.seconds.max(0) as u64,
+                        changed_paths: None,
+                    }
+                }
+            };
+            queue.extend(commit.parents.iter().filter(|id| !seen.contains(*id)).copied());
+            out.push(commit);
+        }
+        Ok(out)
+    }
+
+    /// Compute changed-path Bloom filters with `settings` for all `commits` that don't have one yet.
+    fn add_changed_paths(&self, commits: &mut [write::Commit], settings: Option<bloom::Settings>) -> Result<(), Error> {
+        let Some(settings) = settings else {
+            return Ok(());
+        };
+        let mut state = gix_diff::tree::State::default();
+        let (mut buf, mut lhs_buf, mut rhs_buf) = (Vec::new(), Vec::new(), Vec::new());
+        for commit in commits.iter_mut().filter(|c| c.changed_paths.is_none()) {
+            let lhs = match commit.parents.first() {
+                Some(parent) => {
+                    let tree = self.objects.find_commit(parent, &mut buf)?.tree();
+                    self.objects.find_tree_iter(&tree, &mut lhs_buf)?
+                }
+                None => gix_object::TreeRefIter::from_bytes(&[], self.object_hash()),
+            };
+            let rhs = self.objects.find_tree_iter(&commit.tree, &mut rhs_buf)?;
+            let mut delegate = ChangedPaths::new(settings.max_changed_paths as usize);
+            let filter = match gix_diff::tree(lhs, rhs, &mut state, &self.objects, &mut delegate) {
+                Ok(()) => bloom::Filter::from_changed_paths(delegate.paths.iter().map(AsRef::as_ref), &settings),
+                Err(gix_diff::tree::Error::Cancelled) => bloom::Filter::too_large(),
+                Err(err) => return Err(err.into()),
+            };
+            commit.changed_paths = Some(filter);
+        }
+        Ok(())
+    }
+}
+
+/// Collect the paths of all changed entries that aren't trees, and cancel once there are more than `max_paths` of them.
+struct ChangedPaths {
+    recorder: gix_diff::tree::Recorder,
+    paths: Vec<BString>,
+    max_paths: usize,
+}
+
+impl ChangedPaths {
+    fn new(max_paths: usize) -> Self {
+        ChangedPaths {
+            recorder: Default::default(),
+            paths: Vec::new(),
+            max_paths,
+        }
+    }
+}
+
+impl Visit for ChangedPaths {
+    fn pop_front_tracked_path_and_set_current(&mut self) {
+        self.recorder.pop_front_tracked_path_and_set_current();
+    }
+
+    fn push_back_tracked_path_component(&mut self, component: &BStr) {
+        self.recorder.push_back_tracked_path_component(component);
+    }
+
+    fn push_path_component(&mut self, component: &BStr) {
+        self.recorder.push_path_component(component);
+    }
+
+    fn pop_path_component(&mut self) {
+        self.recorder.pop_path_component();
+    }
+
+    fn visit(&mut self, change: gix_diff::tree::visit::Change) -> gix_diff::tree::visit::Action {
+        use gix_diff::tree::visit::{Action, Change};
+        let is_tree = match change {
+            Change::Addition { entry_mode, .. } | Change::Deletion { entry_mode, .. } => entry_mode.is_tree(),
+            Change::Modification {
+                previous_entry_mode,
+                entry_mode,
+                ..
+            } => previous_entry_mode.is_tree() && entry_mode.is_tree(),
+        };
+        if !is_tree {
+            self.paths.push(self.recorder.path_clone());
+            if self.paths.len() > self.max_paths {
+                return Action::Cancel;
+            }
+        }
+        Action::Continue
+    }
+}
+
+fn commit_from_graph(
+    graph: &Graph,
+    commit: gix_commitgraph::file::Commit<'_>,
+    bloom_settings: Option<bloom::Settings>,
+) -> write::Commit {
+    write::Commit {
+        id: commit.id().into(),
+        tree: commit.root_tree_id().into(),
+        parents: commit
+            .iter_parents()
+            .map(|pos| graph.id_at(pos.expect("graph was verified when loading")).into())
+            .collect(),
+        committer_timestamp: commit.committer_timestamp(),
+        changed_paths: commit
+            .bloom_filter()
+            .filter(|filter| Some(filter.settings()) == bloom_settings)
+            .map(bloom::FilterRef::into_owned),
+    }
+}
+
+fn chain_path(info_dir: &Path) -> PathBuf {
+    info_dir.join("commit-graphs").join("commit-graph-chain")
+}
+
+fn layer_path(graphs_dir: &Path, checksum: &gix_hash::oid) -> PathBuf {
+    graphs_dir.join(format!("graph-{checksum}.graph"))
+}
+
+/// Remove the commit-graph chain along with all of its layers.
+fn remove_chain(info_dir: &Path) -> std::io::Result<()> {
+    let graphs_dir = info_dir.join("commit-graphs");
+    let entries = match std::fs::read_dir(&graphs_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    remove_if_exists(&chain_path(info_dir))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "graph") {
+            remove_if_exists(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
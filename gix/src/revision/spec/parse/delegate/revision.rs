@@ -19,7 +19,26 @@ impl delegate::Revision for Delegate<'_> {
         if !self.err.is_empty() && self.refs[self.idx].is_some() {
             return None;
         }
-        match self.repo.refs.find(name) {
+        let fetch_head_id = if name == "FETCH_HEAD" {
+            match self.repo.fetch_head() {
+                Ok(fetch_head) => fetch_head.and_then(|fetch_head| fetch_head.id()),
+                Err(err) => {
+                    self.err.push(err.into());
+                    return None;
+                }
+            }
+        } else {
+            None
+        };
+        let res = match fetch_head_id {
+            Some(id) => Ok(gix_ref::Reference {
+                name: "FETCH_HEAD".try_into().expect("valid"),
+                target: gix_ref::Target::Object(id),
+                peeled: None,
+            }),
+            None => self.repo.refs.find(name),
+        };
+        match res {
             Ok(r) => {
                 assert!(self.refs[self.idx].is_none(), "BUG: cannot set the same ref twice");
                 self.refs[self.idx] = Some(r);
@@ -41,7 +60,7 @@ impl delegate::Revision for Delegate<'_> {
         let mut candidates = Some(HashSet::default());
         self.prefix[self.idx] = Some(prefix);
 
-        let empty_tree_id = gix_hash::ObjectId::empty_tree(prefix.as_oid().kind());
+        let empty_tree_id = gix_hash::ObjectId::empty_tree(self.repo.object_hash());
         let res = if prefix.as_oid() == empty_tree_id {
             candidates.as_mut().expect("set").insert(empty_tree_id);
             Ok(Some(Err(())))
@@ -187,7 +206,7 @@ impl delegate::Revision for Delegate<'_> {
     fn nth_checked_out_branch(&mut self, branch_no: usize) -> Option<()> {
         self.unset_disambiguate_call();
         fn prior_checkouts_iter<'a>(
-            platform: &'a mut gix_ref::file::log::iter::Platform<'static, '_>,
+            platform: &'a mut gix_ref::store::log::Platform<'static, '_>,
         ) -> Result<impl Iterator<Item = (BString, ObjectId)> + 'a, Error> {
             match platform.rev().ok().flatten() {
                 Some(log) => Ok(log.filter_map(Result::ok).filter_map(|line| {
@@ -115,7 +115,7 @@ pub enum Error {
     #[error(transparent)]
     RevWalkIterInit(#[from] crate::reference::iter::init::Error),
     #[error(transparent)]
-    RevWalkAllReferences(#[from] gix_ref::packed::buffer::open::Error),
+    RevWalkAllReferences(#[from] gix_ref::store::iter::Error),
     #[cfg(feature = "revparse-regex")]
     #[error(transparent)]
     InvalidRegex(#[from] regex::Error),
@@ -152,7 +152,9 @@ pub enum Error {
     #[error(transparent)]
     IdFromHex(#[from] gix_hash::decode::Error),
     #[error(transparent)]
-    FindReference(#[from] gix_ref::file::find::existing::Error),
+    FindReference(#[from] gix_ref::store::find::existing::Error),
+    #[error(transparent)]
+    FetchHead(#[from] crate::repository::fetch_head::Error),
     #[error(transparent)]
     FindObject(#[from] object::find::existing::Error),
     #[error(transparent)]
@@ -0,0 +1,134 @@
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::Target;
+
+use crate::bstr::BString;
+use crate::sequencer::{
+    plumbing::message,
+    replay::{mainline_parent, new_message},
+    todo::Action,
+    Error, Outcome, Platform, State,
+};
+use crate::worktree::update;
+
+impl Platform<'_> {
+    /// Continue the sequence in progress after it stopped due to conflicts, similar to `git cherry-pick --continue`
+    /// or `git revert --continue`.
+    ///
+    /// The conflicts must have been resolved and added to the index, which is then committed with `message` if set, or
+    /// with the prepared message in `MERGE_MSG` without comment lines otherwise. If the resolution leaves no changes,
+    /// the commit is dropped unless redundant commits are kept.
+    /// If the commit was already created, for instance with `git commit`, the sequence continues with the next commit.
+    pub fn resume(&self, message: Option<BString>) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let state = self.state()?;
+        let pick_head = self.pick_head()?;
+        if state.is_none() && pick_head.is_none() {
+            return Err(Error::NotInProgress);
+        }
+
+        if let Some((action, id)) = pick_head {
+            let mut index = gix_index::File::clone(&*repo.index_or_empty()?);
+            if update::is_unmerged(&index) {
+                return Err(Error::UnresolvedConflicts);
+            }
+            let options = state.as_ref().map(|state| state.options).unwrap_or_default();
+            let tree = repo.write_tree(&mut index)?.detach();
+            let head = repo.head_commit()?;
+            if tree != head.tree_id()?.detach() || options.keep_redundant_commits {
+                let commit = repo.find_commit(id)?.decode()?.into_owned();
+                let message = match message {
+                    Some(message) => message,
+                    None => match self.read_merge_message()? {
+                        Some(message) => message::cleanup(message.as_ref()),
+                        None => new_message(action, id, &commit, mainline_parent(id, &commit, &options)?, &options),
+                    },
+                };
+                let author = match action {
+                    Action::Pick => commit.author,
+                    Action::Revert => self.author()?,
+                };
+                let new = self.write_commit(tree, vec![head.id], author, message.clone())?;
+                self.set_head(new, action, message.as_ref())?;
+            }
+            self.remove_pick_head()?;
+        }
+        self.continue_with_next(state)
+    }
+
+    /// Discard the changes of the commit that couldn't be applied cleanly along with all other changes to tracked files,
+    /// and continue with the next commit, similar to `git cherry-pick --skip` or `git revert --skip`.
+    pub fn skip(&self) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let state = self.state()?;
+        if state.is_none() && self.pick_head()?.is_none() {
+            return Err(Error::NotInProgress);
+        }
+        repo.checkout_tree(
+            repo.head_tree_id()?,
+            crate::repository::switch::Options {
+                force: true,
+                ..Default::default()
+            },
+        )?;
+        self.remove_pick_head()?;
+        self.continue_with_next(state)
+    }
+
+    /// Stop the sequence in progress and reset `HEAD` to the commit it started from, discarding all changes to tracked
+    /// files, similar to `git cherry-pick --abort` or `git revert --abort`.
+    ///
+    /// Return `false` if `HEAD` was moved since the sequence created its last commit, in which case it's left as is,
+    /// just like git does, while the state of the sequence is removed nonetheless.
+    pub fn abort(&self) -> Result<bool, Error> {
+        let repo = self.repo;
+        let state = self.state()?;
+        if state.is_none() && self.pick_head()?.is_none() {
+            return Err(Error::NotInProgress);
+        }
+        let head = repo.head_id()?.detach();
+        let (target, rewind) = match &state {
+            Some(state) => (state.head, state.abort_safety.map_or(true, |id| id == head)),
+            None => (head, true),
+        };
+        if rewind {
+            repo.checkout_tree(
+                repo.find_commit(target)?.tree_id()?,
+                crate::repository::switch::Options {
+                    force: true,
+                    ..Default::default()
+                },
+            )?;
+            if target != head {
+                repo.edit_reference(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: format!("reset: moving to {target}").into(),
+                        },
+                        expected: PreviousValue::MustExistAndMatch(Target::Object(head)),
+                        new: Target::Object(target),
+                    },
+                    name: "HEAD".try_into().expect("valid"),
+                    deref: true,
+                })?;
+            }
+        }
+        self.remove_state()?;
+        Ok(rewind)
+    }
+
+    /// Drop the current commit from the todo list of `state` and continue with the next one, or finish if there is no
+    /// `state` as only a single commit was picked or reverted.
+    fn continue_with_next(&self, state: Option<State>) -> Result<Outcome, Error> {
+        let head = self.repo.head_id()?.detach();
+        let Some(mut state) = state else {
+            return Ok(Outcome::Finished { head });
+        };
+        if !state.todo.lines.is_empty() {
+            state.todo.lines.remove(0);
+        }
+        state.abort_safety = Some(head);
+        self.run(state)
+    }
+}
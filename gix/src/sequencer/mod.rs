@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+
+use crate::merge::tree::Conflict;
+use crate::Repository;
+
+pub use gix_sequencer as plumbing;
+pub use gix_sequencer::{todo, Options, State};
+
+mod error {
+    /// The error returned by all operations of the [sequencer platform](crate::sequencer::Platform).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot cherry-pick or revert in a bare repository")]
+        BareRepository,
+        #[error("Cannot cherry-pick or revert before the initial commit")]
+        UnbornHead,
+        #[error("Cannot start a sequence while another operation is in progress")]
+        InProgress,
+        #[error("There is no cherry-pick or revert in progress")]
+        NotInProgress,
+        #[error("Cannot cherry-pick or revert with uncommitted changes in the index or the worktree")]
+        DirtyWorktree,
+        #[error("The index has unresolved conflicts that need to be resolved and added first")]
+        UnresolvedConflicts,
+        #[error("Commit {id} is a merge, but no mainline was specified")]
+        MergeWithoutMainline { id: gix_hash::ObjectId },
+        #[error("Commit {id} has no parent {mainline} to use as mainline")]
+        InvalidMainline { id: gix_hash::ObjectId, mainline: usize },
+        #[error("The revision specification '{spec}' doesn't name a single commit or a range of commits")]
+        UnsupportedSpec { spec: String },
+        #[error("Author identity is not configured")]
+        AuthorMissing,
+        #[error("Committer identity is not configured")]
+        CommitterMissing,
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        State(#[from] gix_sequencer::state::Error),
+        #[error(transparent)]
+        RevParse(#[from] crate::revision::spec::parse::Error),
+        #[error(transparent)]
+        RevParseSingle(#[from] crate::revision::spec::parse::single::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        PeelObject(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        FindExistingReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        HeadTree(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        HeadCommit(#[from] crate::reference::head_commit::Error),
+        #[error(transparent)]
+        IsDirty(#[from] crate::status::is_dirty::Error),
+        #[error(transparent)]
+        CommitGraph(#[from] crate::repository::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        MergeBase(#[from] gix_revision::merge_base::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkIter(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        MergeOptions(#[from] crate::repository::tree_merge_options::Error),
+        #[error(transparent)]
+        MergeTrees(#[from] crate::repository::merge_trees::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        WriteIndexTree(#[from] gix_index::write_tree::Error),
+        #[error(transparent)]
+        Checkout(#[from] crate::repository::switch::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        WriteCommit(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error("Could not read or write '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+pub use error::Error;
+
+/// The outcome of the operations on the [sequencer platform](Platform) that pick or revert commits.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    /// All commits were picked or reverted, and the sequence is complete.
+    Finished {
+        /// The commit `HEAD` points to afterwards.
+        head: gix_hash::ObjectId,
+    },
+    /// The changes of `commit` couldn't be applied cleanly, and the index and the worktree contain the conflicts that
+    /// have to be resolved and added to the index before [resuming](Platform::resume()).
+    ///
+    /// The state is kept so that the sequence can be resumed, by git as well.
+    Stopped {
+        /// The commit that was picked or reverted.
+        commit: gix_hash::ObjectId,
+        /// The conflicts encountered when merging, resolved or not.
+        conflicts: Vec<Conflict>,
+    },
+}
+
+/// A platform to cherry-pick or revert sequences of commits onto `HEAD`, similar to `git cherry-pick` and `git revert`,
+/// and to continue, skip or abort sequences in progress, including those started by git.
+///
+/// Obtain it with [`Repository::sequencer()`].
+///
+/// The state of a sequence is kept in the [`sequencer`](plumbing::STATE_DIR) directory in the format used by git.
+/// Commits are picked by merging the changes between their parent and themselves into the tree of `HEAD`, and reverted
+/// by merging the changes between themselves and their parent, and the branch `HEAD` points to, if any, is updated
+/// with each new commit.
+///
+/// ### Deviation
+///
+/// * commits that become empty are dropped unless [redundant commits are kept](Options::keep_redundant_commits),
+///   whereas git would stop.
+/// * messages aren't edited interactively, but can be passed when [resuming](Platform::resume()).
+#[derive(Clone, Copy)]
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+}
+
+/// Cherry-picking and reverting
+impl Repository {
+    /// Obtain a platform to start, continue, skip or abort sequences of cherry-picks and reverts.
+    pub fn sequencer(&self) -> Platform<'_> {
+        Platform { repo: self }
+    }
+}
+
+impl Platform<'_> {
+    /// Return the state of the sequence in progress, or `None` if there is none.
+    ///
+    /// Note that a single commit picked or reverted by git stops without such a state, which can still be
+    /// [resumed](Self::resume()), [skipped](Self::skip()) or [aborted](Self::abort()).
+    pub fn state(&self) -> Result<Option<State>, Error> {
+        Ok(State::from_dir(&self.state_dir())?)
+    }
+
+    pub(super) fn state_dir(&self) -> PathBuf {
+        self.repo.path().join(plumbing::STATE_DIR)
+    }
+}
+
+mod control;
+mod replay;
+mod start;
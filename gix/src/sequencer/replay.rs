@@ -0,0 +1,279 @@
+use gix_hash::{ObjectId, Prefix};
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::Target;
+
+use crate::bstr::{BStr, BString};
+use crate::merge::tree::{apply_index_entries::RemovalMode, Conflict, TreatAsUnresolved};
+use crate::prelude::ObjectIdExt;
+use crate::sequencer::{plumbing::message, todo::Action, Error, Options, Outcome, Platform, State};
+
+/// The name of the reference that points to the commit being picked while stopped due to conflicts.
+pub(super) const CHERRY_PICK_HEAD: &str = "CHERRY_PICK_HEAD";
+/// The name of the reference that points to the commit being reverted while stopped due to conflicts.
+pub(super) const REVERT_HEAD: &str = "REVERT_HEAD";
+/// The name of the file in the git directory that holds the message to commit once conflicts are resolved.
+pub(super) const MERGE_MSG: &str = "MERGE_MSG";
+
+/// The result of picking or reverting a single commit.
+enum Replayed {
+    /// `HEAD` points to the given commit, which is the previous `HEAD` if the new commit would have been empty.
+    Commit(ObjectId),
+    /// The changes couldn't be applied cleanly, and the index and the worktree contain the conflicts.
+    Conflicts { conflicts: Vec<Conflict>, message: BString },
+}
+
+impl Platform<'_> {
+    /// Pick or revert the commits of the todo list in `state` until it's empty or a commit can't be applied cleanly.
+    pub(super) fn run(&self, mut state: State) -> Result<Outcome, Error> {
+        let dir = self.state_dir();
+        while let Some(line) = state.todo.lines.first().cloned() {
+            state.write_to_dir(&dir)?;
+            let id = self.resolve(&line.commit)?;
+            match self.replay(line.action, id, &state.options)? {
+                Replayed::Commit(head) => {
+                    state.todo.lines.remove(0);
+                    state.abort_safety = Some(head);
+                }
+                Replayed::Conflicts { conflicts, message } => {
+                    self.set_pick_head(line.action, id)?;
+                    self.write_merge_message(message.as_ref())?;
+                    return Ok(Outcome::Stopped { commit: id, conflicts });
+                }
+            }
+        }
+        self.remove_state()?;
+        Ok(Outcome::Finished {
+            head: self.repo.head_id()?.detach(),
+        })
+    }
+
+    /// Apply the changes of the commit `id`, or their inverse when reverting as directed by `action`, to the tree of
+    /// `HEAD` and update the index and the worktree accordingly.
+    fn replay(&self, action: Action, id: ObjectId, options: &Options) -> Result<Replayed, Error> {
+        let repo = self.repo;
+        let commit = repo.find_commit(id)?.decode()?.into_owned();
+        let parent = mainline_parent(id, &commit, options)?;
+        let head = repo.head_commit()?;
+        let head_id = head.id;
+        let head_tree = head.tree_id()?.detach();
+        let parent_tree = match parent {
+            Some(parent) => repo.find_commit(parent)?.tree_id()?.detach(),
+            None => ObjectId::empty_tree(repo.object_hash()),
+        };
+
+        let label = format!(
+            "{} ({})",
+            id.attach(repo).shorten_or_id(),
+            summary(commit.message.as_ref())
+        );
+        let parent_label = format!("parent of {label}");
+        let (base, base_label, other, other_label) = match action {
+            Action::Pick => (parent_tree, &parent_label, commit.tree, &label),
+            Action::Revert => (commit.tree, &label, parent_tree, &parent_label),
+        };
+        let mut merge = repo.merge_trees(
+            base,
+            head_tree,
+            other,
+            gix_merge::blob::builtin_driver::text::Labels {
+                ancestor: Some(base_label.as_str().into()),
+                current: Some("HEAD".into()),
+                other: Some(other_label.as_str().into()),
+            },
+            repo.tree_merge_options()?,
+        )?;
+        let tree = merge.tree.write()?.detach();
+        repo.checkout_tree(tree, Default::default())?;
+
+        let message = new_message(action, id, &commit, parent, options);
+        let how = TreatAsUnresolved::default();
+        if merge.has_unresolved_conflicts(how) {
+            let mut index = repo.open_index()?;
+            merge.index_changed_after_applying_conflicts(&mut index, how, RemovalMode::Prune);
+            index.write(Default::default())?;
+            return Ok(Replayed::Conflicts {
+                conflicts: merge.conflicts,
+                message,
+            });
+        }
+        if tree == head_tree && !options.keep_redundant_commits {
+            // All changes are present already, so the commit would be empty and is dropped.
+            return Ok(Replayed::Commit(head_id));
+        }
+        let author = match action {
+            Action::Pick => commit.author,
+            Action::Revert => self.author()?,
+        };
+        let new = self.write_commit(tree, vec![head_id], author, message.clone())?;
+        self.set_head(new, action, message.as_ref())?;
+        Ok(Replayed::Commit(new))
+    }
+
+    /// Return the commit that `prefix` of a todo list line refers to.
+    pub(super) fn resolve(&self, prefix: &Prefix) -> Result<ObjectId, Error> {
+        let id = self.repo.rev_parse_single(prefix.to_string().as_str())?;
+        self.peel_to_commit(id)
+    }
+
+    /// Return the configured author, who is the author of all reverts.
+    pub(super) fn author(&self) -> Result<gix_actor::Signature, Error> {
+        Ok(self.repo.author().ok_or(Error::AuthorMissing)??.into())
+    }
+
+    /// Write a new commit with the given properties, with the configured committer.
+    pub(super) fn write_commit(
+        &self,
+        tree: ObjectId,
+        parents: Vec<ObjectId>,
+        author: gix_actor::Signature,
+        message: BString,
+    ) -> Result<ObjectId, Error> {
+        let repo = self.repo;
+        let committer = repo.committer().ok_or(Error::CommitterMissing)??;
+        let commit = gix_object::Commit {
+            message,
+            tree,
+            author,
+            committer: committer.into(),
+            encoding: None,
+            parents: parents.into(),
+            extra_headers: Default::default(),
+        };
+        Ok(repo.write_object(&commit)?.detach())
+    }
+
+    /// Point `HEAD`, or the branch it points to, to `id`, and log the change as caused by `action` creating a commit
+    /// with `message`.
+    pub(super) fn set_head(&self, id: ObjectId, action: Action, message: &BStr) -> Result<(), Error> {
+        let action = match action {
+            Action::Pick => "cherry-pick",
+            Action::Revert => "revert",
+        };
+        self.repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("{action}: {}", summary(message)).into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(id),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        })?;
+        Ok(())
+    }
+
+    /// Return the commit that was picked or reverted when stopping due to conflicts, along with the action that was
+    /// applied to it, or `None` if there is none.
+    pub(super) fn pick_head(&self) -> Result<Option<(Action, ObjectId)>, Error> {
+        for (action, name) in [(Action::Pick, CHERRY_PICK_HEAD), (Action::Revert, REVERT_HEAD)] {
+            if let Some(reference) = self.repo.try_find_reference(name)? {
+                return Ok(Some((action, reference.id().detach())));
+            }
+        }
+        Ok(None)
+    }
+
+    fn set_pick_head(&self, action: Action, id: ObjectId) -> Result<(), Error> {
+        let name = match action {
+            Action::Pick => CHERRY_PICK_HEAD,
+            Action::Revert => REVERT_HEAD,
+        };
+        self.repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                expected: PreviousValue::Any,
+                new: Target::Object(id),
+            },
+            name: name.try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(())
+    }
+
+    fn write_merge_message(&self, message: &BStr) -> Result<(), Error> {
+        let path = self.repo.path().join(MERGE_MSG);
+        std::fs::write(&path, message).map_err(|source| Error::Io { path, source })
+    }
+
+    /// Return the message prepared for the commit to create after resolving conflicts, or `None` if there is none.
+    pub(super) fn read_merge_message(&self) -> Result<Option<BString>, Error> {
+        let path = self.repo.path().join(MERGE_MSG);
+        match std::fs::read(&path) {
+            Ok(message) => Ok(Some(message.into())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::Io { path, source }),
+        }
+    }
+
+    /// Remove the references to the commit that was picked or reverted when stopping due to conflicts, along with the
+    /// prepared message.
+    pub(super) fn remove_pick_head(&self) -> Result<(), Error> {
+        for name in [CHERRY_PICK_HEAD, REVERT_HEAD, MERGE_MSG] {
+            let path = self.repo.path().join(name);
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(Error::Io { path, source }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the state directory along with all information about a commit that couldn't be applied cleanly.
+    pub(super) fn remove_state(&self) -> Result<(), Error> {
+        let dir = self.state_dir();
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(source) => return Err(Error::Io { path: dir, source }),
+        }
+        self.remove_pick_head()
+    }
+}
+
+/// Return the parent of `commit` with `id` whose changes are picked or reverted, as selected by the mainline in `options`.
+pub(super) fn mainline_parent(
+    id: ObjectId,
+    commit: &gix_object::Commit,
+    options: &Options,
+) -> Result<Option<ObjectId>, Error> {
+    Ok(match options.mainline {
+        None if commit.parents.len() > 1 => return Err(Error::MergeWithoutMainline { id }),
+        None => commit.parents.first().copied(),
+        Some(mainline) => Some(
+            mainline
+                .checked_sub(1)
+                .and_then(|idx| commit.parents.get(idx))
+                .copied()
+                .ok_or(Error::InvalidMainline { id, mainline })?,
+        ),
+    })
+}
+
+/// Return the message of the commit that applies `action` to `commit` with `id`, against its `parent`.
+pub(super) fn new_message(
+    action: Action,
+    id: ObjectId,
+    commit: &gix_object::Commit,
+    parent: Option<ObjectId>,
+    options: &Options,
+) -> BString {
+    match action {
+        Action::Pick => message::cherry_pick(commit.message.as_ref(), &id, options.record_origin),
+        Action::Revert => message::revert(
+            commit.message.as_ref(),
+            &id,
+            parent.as_deref().filter(|_| commit.parents.len() > 1),
+        ),
+    }
+}
+
+/// Return the first line of `message`.
+pub(super) fn summary(message: &BStr) -> BString {
+    gix_object::commit::MessageRef::from_bytes(message)
+        .summary()
+        .into_owned()
+}
@@ -0,0 +1,104 @@
+use gix_hash::ObjectId;
+
+use crate::bstr::BStr;
+use crate::sequencer::{replay::summary, todo, Error, Options, Outcome, Platform, State};
+
+impl Platform<'_> {
+    /// Generate the todo list that applies `action` to the commits that the revision specification `spec` resolves to,
+    /// which is either a single commit like `HEAD~2`, or a range like `main..topic` of all commits reachable from `topic`
+    /// but not from `main`.
+    ///
+    /// Commits to pick are listed oldest first, and commits to revert newest first, just like git would list them.
+    pub fn todo(&self, action: todo::Action, spec: impl AsRef<BStr>) -> Result<todo::List, Error> {
+        let spec = spec.as_ref();
+        let mut commits = match self.repo.rev_parse(spec)?.detach() {
+            gix_revision::Spec::Include(id) | gix_revision::Spec::ExcludeParents(id) => vec![self.peel_to_commit(id)?],
+            gix_revision::Spec::Range { from, to } => {
+                let (from, to) = (self.peel_to_commit(from)?, self.peel_to_commit(to)?);
+                self.commits_between(from, to)?
+            }
+            gix_revision::Spec::Exclude(_)
+            | gix_revision::Spec::Merge { .. }
+            | gix_revision::Spec::IncludeOnlyParents(_) => {
+                return Err(Error::UnsupportedSpec { spec: spec.to_string() })
+            }
+        };
+        if action == todo::Action::Pick {
+            commits.reverse();
+        }
+
+        let mut lines = Vec::with_capacity(commits.len());
+        for id in commits {
+            let commit = self.repo.find_commit(id)?;
+            lines.push(todo::Line {
+                action,
+                commit: id.into(),
+                subject: summary(commit.message_raw_sloppy()),
+            });
+        }
+        Ok(lines.into())
+    }
+
+    /// Pick the commits that the revision specification `spec` resolves to onto `HEAD`, similar to `git cherry-pick <spec>`,
+    /// and configured by `options`.
+    ///
+    /// See [`todo()`](Self::todo()) for the supported specifications, and [`start()`](Self::start()) for details.
+    pub fn cherry_pick(&self, spec: impl AsRef<BStr>, options: Options) -> Result<Outcome, Error> {
+        self.start(self.todo(todo::Action::Pick, spec)?, options)
+    }
+
+    /// Revert the commits that the revision specification `spec` resolves to on top of `HEAD`, similar to `git revert <spec>`,
+    /// and configured by `options`.
+    ///
+    /// See [`todo()`](Self::todo()) for the supported specifications, and [`start()`](Self::start()) for details.
+    pub fn revert(&self, spec: impl AsRef<BStr>, options: Options) -> Result<Outcome, Error> {
+        self.start(self.todo(todo::Action::Revert, spec)?, options)
+    }
+
+    /// Execute each line of `todo` in turn, configured by `options`, creating one commit on top of `HEAD` for each
+    /// commit that is picked or reverted.
+    ///
+    /// The index and the worktree must not have changes, but untracked files are kept as long as they are not in the way.
+    pub fn start(&self, todo: todo::List, options: Options) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        if repo.workdir().is_none() {
+            return Err(Error::BareRepository);
+        }
+        if repo.state().is_some() || self.state_dir().exists() {
+            return Err(Error::InProgress);
+        }
+        let head = repo.head()?.id().ok_or(Error::UnbornHead)?.detach();
+        if repo.is_dirty()? {
+            return Err(Error::DirtyWorktree);
+        }
+
+        let state = State::new(head, todo, options);
+        state.write_to_dir(&self.state_dir())?;
+        self.run(state)
+    }
+
+    pub(super) fn peel_to_commit(&self, id: impl Into<ObjectId>) -> Result<ObjectId, Error> {
+        Ok(self.repo.find_object(id)?.peel_to_commit()?.id)
+    }
+
+    /// Return all commits reachable from `to` but not from `from`, newest first.
+    fn commits_between(&self, from: ObjectId, to: ObjectId) -> Result<Vec<ObjectId>, Error> {
+        let repo = self.repo;
+        let commit_graph = repo.commit_graph_if_enabled()?;
+        let mut graph = repo.revision_graph(commit_graph.as_ref());
+        let merge_bases: Vec<ObjectId> = repo
+            .merge_bases_many_with_graph(to, &[from], &mut graph)?
+            .into_iter()
+            .map(crate::Id::detach)
+            .collect();
+
+        let mut out = Vec::new();
+        for info in repo
+            .rev_walk(Some(to))
+            .selected(move |id| !merge_bases.iter().any(|base| *base == id))?
+        {
+            out.push(info?.id);
+        }
+        Ok(out)
+    }
+}
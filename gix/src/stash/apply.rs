@@ -0,0 +1,194 @@
+use std::collections::BTreeSet;
+
+use gix_index::entry::Stage;
+
+use crate::bstr::{BStr, BString};
+use crate::merge::tree::{apply_index_entries::RemovalMode, Conflict, TreatAsUnresolved};
+use crate::stash::{util, Platform};
+use crate::worktree::update;
+
+mod error {
+    use crate::bstr::{BString, ByteSlice};
+
+    /// The error returned by [`Platform::apply()`](crate::stash::Platform::apply()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot apply a stash in a bare repository")]
+        BareRepository,
+        #[error("There is no stash at index {index}")]
+        NotFound { index: usize },
+        #[error("Commit {id} isn't a stash as it doesn't have at least two parents")]
+        NotAStash { id: gix_hash::ObjectId },
+        #[error("Cannot apply a stash while the index has unresolved conflicts")]
+        UnmergedIndex,
+        #[error("Local changes to the following files would be overwritten: {}", paths.iter().map(|p| p.to_str_lossy()).collect::<Vec<_>>().join(", "))]
+        WouldOverwriteLocalChanges { paths: Vec<BString> },
+        #[error("Cannot restore untracked file '{path}' as it already exists")]
+        UntrackedFileExists { path: BString },
+        #[error(transparent)]
+        List(#[from] crate::stash::list::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error(transparent)]
+        EditTree(#[from] crate::repository::edit_tree::Error),
+        #[error(transparent)]
+        EditTreeEntry(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        MergeOptions(#[from] crate::repository::tree_merge_options::Error),
+        #[error(transparent)]
+        MergeTrees(#[from] crate::repository::merge_trees::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error(transparent)]
+        StatusIter(#[from] crate::status::into_iter::Error),
+        #[error(transparent)]
+        IndexWorktreeStatus(#[from] crate::status::index_worktree::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+pub use error::Error;
+
+/// The outcome of [`Platform::apply()`] and [`Platform::pop()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The tree that resulted from merging the stash into the tree of the current index, and that is now checked out.
+    ///
+    /// Note that it contains conflict markers if there are [unresolved conflicts](Self::has_unresolved_conflicts()).
+    pub tree: gix_hash::ObjectId,
+    /// The conflicts encountered when merging the stash, resolved or not.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Outcome {
+    /// Return `true` if there is any conflict that still needs to be resolved, using `how` to determine what's unresolved.
+    pub fn has_unresolved_conflicts(&self, how: TreatAsUnresolved) -> bool {
+        self.conflicts.iter().any(|conflict| conflict.is_unresolved(how))
+    }
+}
+
+impl Platform<'_> {
+    /// Apply the stash at `index`, with `0` being the most recent one, onto the index and worktree, similar to `git stash apply`.
+    ///
+    /// The changes of the stash are merged into the tree of the current index, with the commit the stash was created on
+    /// as merge-base.
+    /// Without unresolved conflicts, files that are new in the stash are added to the index, and all other changes remain
+    /// in the worktree only.
+    /// Otherwise, the index contains the merge result, along with the conflicting stages of each unresolved conflict,
+    /// while conflicting files in the worktree contain conflict markers.
+    /// Stashed untracked files are restored, and the operation fails if one of them already exists.
+    ///
+    /// The operation also fails before making any change if files with local modifications would have to be changed.
+    /// The stash itself is never removed, use [`pop()`](Self::pop()) for that.
+    pub fn apply(&self, index: usize) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let workdir = repo.workdir().ok_or(Error::BareRepository)?;
+        let stash = self.get(index)?.ok_or(Error::NotFound { index })?;
+        let stash = repo.find_commit(stash.id)?;
+        let parents: Vec<_> = stash.parent_ids().map(crate::Id::detach).collect();
+        let Some(base) = parents.first().filter(|_| parents.len() >= 2) else {
+            return Err(Error::NotAStash { id: stash.id });
+        };
+        let base_tree = repo.find_commit(*base)?.tree_id()?;
+        let stash_tree = stash.tree_id()?;
+        let untracked = match parents.get(2) {
+            Some(id) => {
+                let untracked = repo.index_from_tree(&repo.find_commit(*id)?.tree_id()?)?;
+                for entry in untracked.entries() {
+                    let path = entry.path(&untracked);
+                    if workdir.join(gix_path::from_bstr(path)).symlink_metadata().is_ok() {
+                        return Err(Error::UntrackedFileExists { path: path.to_owned() });
+                    }
+                }
+                Some(untracked)
+            }
+            None => None,
+        };
+
+        let mut index = repo.open_index()?;
+        if update::is_unmerged(&index) {
+            return Err(Error::UnmergedIndex);
+        }
+        let index_tree = util::write_tree::<Error>(repo, &index)?;
+        let mut merge = repo.merge_trees(
+            base_tree,
+            index_tree,
+            stash_tree,
+            gix_merge::blob::builtin_driver::text::Labels {
+                ancestor: Some("Stash base".into()),
+                current: Some("Updated upstream".into()),
+                other: Some("Stashed changes".into()),
+            },
+            repo.tree_merge_options()?,
+        )?;
+        let tree = merge.tree.write()?.detach();
+        let mut merged = repo.index_from_tree(&tree)?;
+
+        let mut paths = BTreeSet::new();
+        update::changed_paths(&index, &merged, &mut paths);
+        let local_changes = update::worktree_changes::<Error>(repo, &index)?;
+        let overwritten: Vec<BString> = paths
+            .iter()
+            .filter(|path| {
+                let path: &BStr = path.as_ref();
+                local_changes.contains(path)
+                    || (index.entry_by_path(path).is_none()
+                        && workdir.join(gix_path::from_bstr(path)).symlink_metadata().is_ok())
+            })
+            .cloned()
+            .collect();
+        if !overwritten.is_empty() {
+            return Err(Error::WouldOverwriteLocalChanges { paths: overwritten });
+        }
+
+        update::carry_over_stat(&mut merged, &index, &paths);
+        update::checkout_paths::<Error>(repo, &mut merged, &paths)?;
+        let how = TreatAsUnresolved::default();
+        if merge.has_unresolved_conflicts(how) {
+            merge.index_changed_after_applying_conflicts(&mut merged, how, RemovalMode::Prune);
+            index = merged;
+        } else {
+            for path in &paths {
+                match index.entry_mut_by_path_and_stage(path.as_ref(), Stage::Unconflicted) {
+                    Some(entry) => entry.stat = Default::default(),
+                    None => {
+                        if let Some(entry) = merged.entry_by_path_and_stage(path.as_ref(), Stage::Unconflicted) {
+                            index.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path.as_ref());
+                        }
+                    }
+                }
+            }
+            index.sort_entries();
+            index.remove_tree();
+        }
+        index.write(Default::default())?;
+
+        if let Some(mut untracked) = untracked {
+            let paths = untracked
+                .entries()
+                .iter()
+                .map(|entry| entry.path(&untracked).to_owned())
+                .collect();
+            update::checkout_paths::<Error>(repo, &mut untracked, &paths)?;
+        }
+        Ok(Outcome {
+            tree,
+            conflicts: merge.conflicts,
+        })
+    }
+}
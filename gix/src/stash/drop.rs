@@ -0,0 +1,98 @@
+use gix_hash::ObjectId;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::{FullNameRef, Target};
+
+use crate::stash::{Platform, REFERENCE};
+
+mod error {
+    /// The error returned by [`Platform::drop()`](crate::stash::Platform::drop()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("There is no stash at index {index}")]
+        NotFound { index: usize },
+        #[error("Stashes can only be dropped in repositories that store references in files")]
+        UnsupportedRefStorage,
+        #[error(transparent)]
+        Reflog(#[from] gix_ref::store::log::Error),
+        #[error(transparent)]
+        LockReflog(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        WriteReflog(#[from] std::io::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+pub use error::Error;
+
+impl Platform<'_> {
+    /// Remove the stash at `index`, with `0` being the most recent one, and return the id of its commit, similar to `git stash drop`.
+    ///
+    /// The entry is removed from the log of [`refs/stash`](REFERENCE), which then points to the most recent remaining stash.
+    /// If there is none, the reference is deleted along with its log.
+    ///
+    /// Note that this is only supported if references are stored in files, as reflogs are rewritten in place.
+    pub fn drop(&self, index: usize) -> Result<ObjectId, Error> {
+        let repo = self.repo;
+        let name: &FullNameRef = REFERENCE.try_into().expect("valid");
+        let Some(files) = repo.refs.as_file_store() else {
+            return Err(Error::UnsupportedRefStorage);
+        };
+        let mut lines = repo.refs.reflog(name)?.unwrap_or_default();
+        if index >= lines.len() {
+            return Err(Error::NotFound { index });
+        }
+        let position = lines.len() - 1 - index;
+        let dropped = lines.remove(position).new_oid;
+        let Some(most_recent) = lines.last().map(|line| line.new_oid) else {
+            repo.edit_reference(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(Target::Object(dropped)),
+                    log: RefLog::AndReference,
+                },
+                name: name.to_owned(),
+                deref: false,
+            })?;
+            return Ok(dropped);
+        };
+        // Keep the log consistent by letting the successor continue where the predecessor left off.
+        if position < lines.len() {
+            lines[position].previous_oid = match position.checked_sub(1) {
+                Some(predecessor) => lines[predecessor].new_oid,
+                None => ObjectId::null(repo.object_hash()),
+            };
+        }
+
+        let (lock_fail, packed_refs_lock_fail) = repo
+            .config
+            .lock_timeout()
+            .map_err(crate::reference::edit::Error::from)?;
+        let mut log = gix_lock::File::acquire_to_update_resource(files.reflog_path(name), lock_fail, None)?;
+        for line in &lines {
+            line.write_to(&mut log)?;
+        }
+        if index == 0 {
+            let mut refs = repo.refs.clone();
+            refs.set_write_reflog(gix_ref::store::WriteReflog::Disable);
+            refs.transaction()
+                .prepare(
+                    Some(RefEdit {
+                        change: Change::Update {
+                            log: LogChange::default(),
+                            expected: PreviousValue::MustExistAndMatch(Target::Object(dropped)),
+                            new: Target::Object(most_recent),
+                        },
+                        name: name.to_owned(),
+                        deref: false,
+                    }),
+                    lock_fail,
+                    packed_refs_lock_fail,
+                )
+                .map_err(crate::reference::edit::Error::from)?
+                .commit(None)
+                .map_err(crate::reference::edit::Error::from)?;
+        }
+        log.commit().map_err(|err| err.error)?;
+        Ok(dropped)
+    }
+}
@@ -0,0 +1,48 @@
+use crate::bstr::BString;
+use crate::stash::{Platform, REFERENCE};
+
+mod error {
+    /// The error returned by [`Platform::list()`](crate::stash::Platform::list()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Reflog(#[from] gix_ref::store::log::Error),
+    }
+}
+pub use error::Error;
+
+/// A single stash as obtained by [`Platform::list()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The position of the stash, with `0` being the most recent one, as used in `stash@{<index>}`.
+    pub index: usize,
+    /// The id of the stash commit, whose tree is the state of the worktree at the time the stash was created.
+    pub id: gix_hash::ObjectId,
+    /// The message describing the stash, like `WIP on main: 1234567 subject`.
+    pub message: BString,
+}
+
+impl Platform<'_> {
+    /// Return all stashes, most recent first, or an empty list if there is none.
+    pub fn list(&self) -> Result<Vec<Entry>, Error> {
+        let Some(lines) = self.repo.refs.reflog(REFERENCE)? else {
+            return Ok(Vec::new());
+        };
+        Ok(lines
+            .into_iter()
+            .rev()
+            .enumerate()
+            .map(|(index, line)| Entry {
+                index,
+                id: line.new_oid,
+                message: line.message,
+            })
+            .collect())
+    }
+
+    /// Return the stash at `index`, with `0` being the most recent one, or `None` if there is no such stash.
+    pub fn get(&self, index: usize) -> Result<Option<Entry>, Error> {
+        Ok(self.list()?.into_iter().nth(index))
+    }
+}
@@ -0,0 +1,35 @@
+use crate::Repository;
+
+/// The name of the reference that points to the most recent stash, with its reference log holding all stashes.
+pub const REFERENCE: &str = "refs/stash";
+
+/// A platform to save local changes as stash, and to list, apply and drop stashes, similar to `git stash`.
+///
+/// Obtain it with [`Repository::stash()`].
+///
+/// Stashes are identified by their index, with `0` being the most recent one, just like `stash@{<index>}`.
+#[derive(Clone, Copy)]
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+}
+
+/// Stashing
+impl Repository {
+    /// Obtain a platform to save changes in the index and worktree into [`refs/stash`](REFERENCE), and to restore them.
+    pub fn stash(&self) -> Platform<'_> {
+        Platform { repo: self }
+    }
+}
+
+///
+pub mod apply;
+///
+pub mod drop;
+///
+pub mod list;
+///
+pub mod pop;
+///
+pub mod push;
+
+mod util;
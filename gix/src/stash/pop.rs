@@ -0,0 +1,29 @@
+use crate::stash::{apply, Platform};
+
+mod error {
+    /// The error returned by [`Platform::pop()`](crate::stash::Platform::pop()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Apply(#[from] crate::stash::apply::Error),
+        #[error(transparent)]
+        Drop(#[from] crate::stash::drop::Error),
+    }
+}
+pub use error::Error;
+
+impl Platform<'_> {
+    /// [Apply](Self::apply()) the stash at `index`, with `0` being the most recent one, and [drop](Self::drop()) it if
+    /// there are no unresolved conflicts, similar to `git stash pop`.
+    ///
+    /// Otherwise, the stash is kept so it can be applied again after the conflicts were dealt with.
+    /// Conflicts are considered unresolved as determined by the default [`TreatAsUnresolved`](crate::merge::tree::TreatAsUnresolved).
+    pub fn pop(&self, index: usize) -> Result<apply::Outcome, Error> {
+        let outcome = self.apply(index)?;
+        if !outcome.has_unresolved_conflicts(Default::default()) {
+            self.drop(index)?;
+        }
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,216 @@
+use gix_hash::ObjectId;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_ref::Target;
+
+use crate::bstr::BString;
+use crate::prelude::ObjectIdExt;
+use crate::stash::{util, Platform, REFERENCE};
+use crate::worktree::update;
+
+mod error {
+    /// The error returned by [`Platform::push()`](crate::stash::Platform::push()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot stash changes in a bare repository")]
+        BareRepository,
+        #[error("Cannot stash changes before the initial commit")]
+        UnbornHead,
+        #[error("Cannot stash changes while the index has unresolved conflicts")]
+        UnmergedIndex,
+        #[error("Committer identity is not configured")]
+        CommitterMissing,
+        #[error("Author identity is not configured")]
+        AuthorMissing,
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        PeelHead(#[from] crate::head::peel::to_commit::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error(transparent)]
+        EditTree(#[from] crate::repository::edit_tree::Error),
+        #[error(transparent)]
+        EditTreeEntry(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error(transparent)]
+        StatusIter(#[from] crate::status::into_iter::Error),
+        #[error(transparent)]
+        IndexWorktreeStatus(#[from] crate::status::index_worktree::Error),
+        #[error(transparent)]
+        DirwalkOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        Dirwalk(#[from] crate::dirwalk::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        WorktreeFileToObject(#[from] crate::filter::pipeline::worktree_file_to_object::Error),
+        #[error(transparent)]
+        WriteCommit(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+pub use error::Error;
+
+/// Options for use in [`Platform::push()`].
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// The message to describe the stash with, which is recorded as `On <branch>: <message>`.
+    ///
+    /// If `None`, the stash is described by the commit it was created on, as in `WIP on <branch>: <commit> <summary>`.
+    pub message: Option<BString>,
+    /// If `true`, untracked files are stashed as well and removed from the worktree, like `git stash --include-untracked` does.
+    /// Ignored files are never stashed.
+    pub include_untracked: bool,
+    /// If `true`, the index and the changes it contains stay as they are, and only the worktree is reset to the index,
+    /// like `git stash --keep-index` does.
+    pub keep_index: bool,
+}
+
+impl<'repo> Platform<'repo> {
+    /// Save the changes of the index and worktree compared to `HEAD` as new stash and reset the index and worktree to `HEAD`,
+    /// configured by `options`.
+    /// Return the id of the stash commit, or `None` if there were no changes to save.
+    ///
+    /// The stash commit records the state of the worktree as tree, and has `HEAD` as first parent, a commit with the
+    /// state of the index as second parent, and if untracked files are stashed, a commit without parents that holds them
+    /// as third parent, just like `git stash push`.
+    /// It's stored in [`refs/stash`](REFERENCE), which makes it the most recent stash.
+    pub fn push(&self, options: Options) -> Result<Option<crate::Id<'repo>>, Error> {
+        let repo = self.repo;
+        if repo.workdir().is_none() {
+            return Err(Error::BareRepository);
+        }
+        let mut head = repo.head()?;
+        if head.is_unborn() {
+            return Err(Error::UnbornHead);
+        }
+        let branch = head
+            .referent_name()
+            .map_or_else(|| "(no branch)".into(), |name| name.shorten().to_owned());
+        let head_commit = head.peel_to_commit_in_place()?;
+        let head_tree = head_commit.tree_id()?.detach();
+        let description = format!(
+            "{branch}: {} {}",
+            head_commit.id().shorten_or_id(),
+            head_commit.message()?.summary()
+        );
+
+        let mut index = repo.open_index()?;
+        if update::is_unmerged(&index) {
+            return Err(Error::UnmergedIndex);
+        }
+        let index_tree = util::write_tree::<Error>(repo, &index)?;
+
+        let (mut pipeline, _) = repo.filter_pipeline(None)?;
+        let mut paths = update::worktree_changes::<Error>(repo, &index)?;
+        let worktree_tree = if paths.is_empty() {
+            index_tree
+        } else {
+            let mut editor = repo.edit_tree(index_tree)?;
+            for path in &paths {
+                match pipeline.worktree_file_to_object(path.as_ref(), &index)? {
+                    Some((id, kind, _)) => editor.upsert(path, kind, id)?,
+                    None => editor.remove(path)?,
+                };
+            }
+            editor.write()?.detach()
+        };
+
+        let untracked = if options.include_untracked {
+            update::untracked_files::<Error>(repo, &index)?
+        } else {
+            Vec::new()
+        };
+        let untracked_tree = if untracked.is_empty() {
+            None
+        } else {
+            let mut editor = repo.edit_tree(ObjectId::empty_tree(repo.object_hash()))?;
+            for path in &untracked {
+                if let Some((id, kind, _)) = pipeline.worktree_file_to_object(path.as_ref(), &index)? {
+                    editor.upsert(path, kind, id)?;
+                }
+            }
+            Some(editor.write()?.detach())
+        };
+
+        if index_tree == head_tree && worktree_tree == index_tree && untracked_tree.is_none() {
+            return Ok(None);
+        }
+
+        let author = repo.author().ok_or(Error::AuthorMissing)??;
+        let committer = repo.committer().ok_or(Error::CommitterMissing)??;
+        let write_commit = |message: String, tree: ObjectId, parents: &[ObjectId]| -> Result<ObjectId, Error> {
+            let commit = gix_object::Commit {
+                message: message.into(),
+                tree,
+                author: author.into(),
+                committer: committer.into(),
+                encoding: None,
+                parents: parents.iter().copied().collect(),
+                extra_headers: Default::default(),
+            };
+            Ok(repo.write_object(&commit)?.detach())
+        };
+
+        let mut parents = vec![head_commit.id];
+        parents.push(write_commit(
+            format!("index on {description}\n"),
+            index_tree,
+            &[head_commit.id],
+        )?);
+        if let Some(tree) = untracked_tree {
+            parents.push(write_commit(format!("untracked files on {description}\n"), tree, &[])?);
+        }
+        let message = match &options.message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {description}"),
+        };
+        let stash = write_commit(format!("{message}\n"), worktree_tree, &parents)?;
+        repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: true,
+                    message: message.into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(stash),
+            },
+            name: REFERENCE.try_into().expect("valid"),
+            deref: false,
+        })?;
+
+        if options.keep_index {
+            paths.extend(untracked);
+            update::checkout_paths::<Error>(repo, &mut index, &paths)?;
+        } else {
+            let mut head_index = repo.index_from_tree(&head_tree)?;
+            update::changed_paths(&index, &head_index, &mut paths);
+            update::carry_over_stat(&mut head_index, &index, &paths);
+            paths.extend(untracked);
+            update::checkout_paths::<Error>(repo, &mut head_index, &paths)?;
+            index = head_index;
+        }
+        index.write(Default::default())?;
+        Ok(Some(stash.attach(repo)))
+    }
+}
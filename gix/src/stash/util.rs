@@ -0,0 +1,25 @@
+use gix_hash::ObjectId;
+use gix_index::entry::Flags;
+
+use crate::Repository;
+
+/// Write a tree with all entries of `index`, which is assumed to be free of conflicts.
+/// Entries that are only intended to be added are skipped, just like `git write-tree` does.
+pub(super) fn write_tree<E>(repo: &Repository, index: &gix_index::State) -> Result<ObjectId, E>
+where
+    E: From<crate::repository::edit_tree::Error>
+        + From<gix_object::tree::editor::Error>
+        + From<crate::object::tree::editor::write::Error>,
+{
+    let mut editor = repo.edit_tree(ObjectId::empty_tree(repo.object_hash()))?;
+    for entry in index.entries() {
+        if entry.flags.contains(Flags::INTENT_TO_ADD) {
+            continue;
+        }
+        let Some(mode) = entry.mode.to_tree_entry_mode() else {
+            continue;
+        };
+        editor.upsert(entry.path(index), mode.kind(), entry.id)?;
+    }
+    Ok(editor.write()?.detach())
+}
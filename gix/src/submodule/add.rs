@@ -0,0 +1,147 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_index::entry::{Flags, Mode, Stat};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote,
+    submodule::init::resolve_url,
+    Repository,
+};
+
+/// The error returned by [Repository::add_submodule()](crate::Repository::add_submodule()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot add submodules to a bare repository")]
+    BareRepository,
+    #[error("A submodule named '{name}' exists already")]
+    NameExists { name: BString },
+    #[error("'{path}' exists already and is not an empty directory")]
+    PathExists { path: BString },
+    #[error("'{path}' is already tracked in the index")]
+    PathTracked { path: BString },
+    #[error(transparent)]
+    Modules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    ResolveUrl(#[from] remote::find::existing::Error),
+    #[error(transparent)]
+    BranchName(#[from] gix_validate::reference::name::Error),
+    #[error(transparent)]
+    Clone(#[from] crate::submodule::update::Error),
+    #[error(transparent)]
+    HeadCommit(#[from] crate::reference::head_commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::repository::switch::Error),
+    #[error(transparent)]
+    OpenModulesFile(#[from] gix_config::file::init::from_paths::Error),
+    #[error(transparent)]
+    EditModulesFile(#[from] gix_config::file::set_raw_value::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    AddToIndex(#[from] crate::repository::add_to_index::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    Init(#[from] crate::submodule::init::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Options for use in [Repository::add_submodule()](crate::Repository::add_submodule()).
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// The name of the submodule, or `None` to use its path as name, like `git submodule add --name` does.
+    pub name: Option<BString>,
+    /// The branch to check out instead of the one `HEAD` of the remote points to, which is also stored as
+    /// `submodule.<name>.branch` in `.gitmodules`, like `git submodule add --branch` does.
+    pub branch: Option<BString>,
+}
+
+impl Repository {
+    /// Clone the repository at `url` as submodule at `path` relative to the worktree, and register it in `.gitmodules` and the index,
+    /// similar to `git submodule add <url> <path>`. Return the name of the new submodule, which is also [initialized](Self::init_submodule()).
+    ///
+    /// The repository is cloned into `.git/modules/<name>` and `HEAD` of the remote, or [`Options::branch`], is checked out
+    /// into the worktree at `path`. Relative urls like `../other` are stored as is, but resolved like in `init_submodule()`
+    /// for cloning.
+    ///
+    /// Note that nothing is committed, and that the index as well as `.gitmodules` are changed on disk.
+    pub fn add_submodule(
+        &mut self,
+        url: &BStr,
+        path: &BStr,
+        options: &Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<BString, Error> {
+        let workdir = self.workdir().ok_or(Error::BareRepository)?.to_owned();
+        let path: BString = path.trim_end_with(|c| c == '/').into();
+        let name = options.name.clone().unwrap_or_else(|| path.clone());
+        if let Some(modules) = self.modules()? {
+            if modules.names().any(|n| n == name) {
+                return Err(Error::NameExists { name });
+            }
+        }
+        let mut index = gix_index::File::clone(&**self.index_or_empty()?);
+        if index.entry_by_path(path.as_ref()).is_some() {
+            return Err(Error::PathTracked { path });
+        }
+        let work_dir = workdir.join(gix_path::from_bstr(path.as_bstr()));
+        if work_dir.exists() && std::fs::read_dir(&work_dir).map_or(true, |mut entries| entries.next().is_some()) {
+            return Err(Error::PathExists { path });
+        }
+
+        let resolved_url = resolve_url(self, url)?;
+        let branch = options
+            .branch
+            .as_ref()
+            .map(|branch| <&gix_ref::PartialNameRef>::try_from(branch.as_bstr()))
+            .transpose()?;
+        let git_dir = self
+            .common_dir()
+            .join("modules")
+            .join(gix_path::from_bstr(name.as_bstr()));
+        let repo = crate::submodule::update::clone(
+            self,
+            resolved_url.as_ref(),
+            &git_dir,
+            &work_dir,
+            false,
+            branch,
+            should_interrupt,
+        )?;
+        let commit = repo.head_commit()?;
+        repo.checkout_tree(
+            commit.tree_id()?,
+            crate::repository::switch::Options {
+                force: true,
+                ..Default::default()
+            },
+        )?;
+
+        let modules_path = workdir.join(crate::submodule::MODULES_FILE);
+        let mut modules = if modules_path.is_file() {
+            gix_config::File::from_path_no_includes(modules_path.clone(), gix_config::Source::Worktree)?
+        } else {
+            gix_config::File::new(gix_config::file::Metadata::from(gix_config::Source::Worktree))
+        };
+        modules.set_raw_value_by("submodule", Some(name.as_bstr()), "path", path.as_bstr())?;
+        modules.set_raw_value_by("submodule", Some(name.as_bstr()), "url", url)?;
+        if let Some(branch) = &options.branch {
+            modules.set_raw_value_by("submodule", Some(name.as_bstr()), "branch", branch.as_bstr())?;
+        }
+        std::fs::write(&modules_path, modules.to_bstring())?;
+        self.modules = gix_fs::SharedFileSnapshotMut::new().into();
+
+        index.dangerously_push_entry(Stat::default(), commit.id, Flags::empty(), Mode::COMMIT, path.as_bstr());
+        index.sort_entries();
+        self.add_to_index(&mut index, [crate::submodule::MODULES_FILE], Default::default())?;
+        index.write(Default::default())?;
+
+        self.init_submodule(name.as_ref())?;
+        Ok(name)
+    }
+}
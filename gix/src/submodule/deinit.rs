@@ -0,0 +1,80 @@
+use crate::{
+    bstr::{BStr, BString},
+    submodule::config,
+    Repository,
+};
+
+/// The error returned by [Repository::deinit_submodule()](crate::Repository::deinit_submodule()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("There is no submodule named '{name}'")]
+    NotFound { name: BString },
+    #[error("The submodule '{name}' contains local modifications, which would be lost")]
+    LocalModifications { name: BString },
+    #[error("The submodule '{name}' keeps its repository in its worktree, which would be lost")]
+    OldForm { name: BString },
+    #[error(transparent)]
+    Modules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    Path(#[from] config::path::Error),
+    #[error(transparent)]
+    Open(#[from] crate::submodule::open::Error),
+    #[error(transparent)]
+    IsDirty(#[from] crate::status::is_dirty::Error),
+    #[error("Could not remove the worktree of the submodule at '{}'", path.display())]
+    RemoveWorktree {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    WriteConfig(#[from] crate::submodule::write_config::Error),
+}
+
+impl Repository {
+    /// De-initialize the submodule `name` by emptying its worktree and removing its section from the configuration of
+    /// this repository, similar to `git submodule deinit <path>`.
+    ///
+    /// The repository of the submodule in `.git/modules` is kept, so it can be checked out again quickly after it was
+    /// [initialized](Self::init_submodule()) once more.
+    /// Unless `force` is `true`, the operation fails if the worktree of the submodule has changes in the index or the worktree.
+    /// Submodules whose repository is located in their worktree are never de-initialized as it would be lost.
+    ///
+    /// ### Deviation
+    ///
+    /// Untracked files aren't considered local modifications, and are removed along with the rest of the worktree.
+    pub fn deinit_submodule(&mut self, name: &BStr, force: bool) -> Result<(), Error> {
+        let submodule = self
+            .try_find_submodule(name)?
+            .ok_or_else(|| Error::NotFound { name: name.to_owned() })?;
+        let state = submodule.state()?;
+        if state.is_old_form && state.worktree_checkout {
+            return Err(Error::OldForm { name: name.to_owned() });
+        }
+        if !force && state.worktree_checkout {
+            if let Some(repo) = submodule.open()? {
+                if repo.is_dirty()? {
+                    return Err(Error::LocalModifications { name: name.to_owned() });
+                }
+            }
+        }
+
+        let work_dir = submodule.work_dir()?;
+        drop(submodule);
+        if work_dir.is_dir() {
+            std::fs::remove_dir_all(&work_dir)
+                .and_then(|_| std::fs::create_dir(&work_dir))
+                .map_err(|source| Error::RemoveWorktree { path: work_dir, source })?;
+        }
+        if state.superproject_configuration {
+            crate::submodule::edit_local_config(self, |config, filter| {
+                while config
+                    .remove_section_filter("submodule", Some(name), &mut *filter)
+                    .is_some()
+                {}
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::BString,
+    remote,
+    submodule::config::{self, FetchRecurse},
+    Repository,
+};
+
+/// The error returned by [Repository::fetch_submodules()](crate::Repository::fetch_submodules()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Modules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    Path(#[from] config::path::Error),
+    #[error(transparent)]
+    Open(#[from] crate::submodule::open::Error),
+    #[error(transparent)]
+    FetchRecurse(#[from] crate::submodule::fetch_recurse::Error),
+    #[error(transparent)]
+    FindRemote(#[from] remote::find::existing::Error),
+    #[error(transparent)]
+    Connect(#[from] remote::connect::Error),
+    #[error(transparent)]
+    PrepareFetch(#[from] remote::fetch::prepare::Error),
+    #[error("Could not fetch into submodule '{name}'")]
+    Fetch {
+        name: BString,
+        source: Box<remote::fetch::Error>,
+    },
+}
+
+/// Options for use in [Repository::fetch_submodules()](crate::Repository::fetch_submodules()).
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Decide in which submodules to fetch, overriding the `submodule.<name>.fetchRecurseSubmodules` and
+    /// `fetch.recurseSubmodules` configuration, like `git fetch --recurse-submodules=<mode>` does.
+    /// If `None` and neither is configured, [`FetchRecurse::OnDemand`] is assumed.
+    ///
+    /// It's passed on to the fetches in submodules, so they recurse into their submodules in the same way.
+    pub recurse: Option<FetchRecurse>,
+    /// The commits of the superproject that were just fetched, to determine in which submodules to fetch
+    /// [on demand](FetchRecurse::OnDemand): a submodule is fetched if one of the commits recorded for it in these commits is missing.
+    pub commits: Vec<ObjectId>,
+}
+
+/// The outcome of fetching into a single submodule, as produced by [Repository::fetch_submodules()](crate::Repository::fetch_submodules()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The name of the submodule.
+    pub name: BString,
+    /// The outcome of fetching from the default remote of the submodule.
+    pub fetch: remote::fetch::Outcome,
+}
+
+impl Repository {
+    /// Fetch from the default remote of all populated submodules as configured by `options`, similar to what
+    /// `git fetch --recurse-submodules` does after fetching into the superproject.
+    ///
+    /// Submodules that weren't cloned yet or whose repository doesn't have a default remote are skipped.
+    pub fn fetch_submodules(&self, options: &Options, should_interrupt: &AtomicBool) -> Result<Vec<Outcome>, Error> {
+        let Some(submodules) = self.submodules()? else {
+            return Ok(Vec::new());
+        };
+        let mut out = Vec::new();
+        for submodule in submodules {
+            let mode = match options.recurse {
+                Some(mode) => mode,
+                None => submodule.fetch_recurse()?.unwrap_or(FetchRecurse::OnDemand),
+            };
+            if mode == FetchRecurse::Never {
+                continue;
+            }
+            let Some(repo) = submodule.open()? else {
+                continue;
+            };
+            if mode == FetchRecurse::OnDemand {
+                let path = submodule.path()?;
+                let path = gix_path::from_bstr(path.as_ref());
+                let has_missing_commit = options
+                    .commits
+                    .iter()
+                    .filter_map(|id| self.find_commit(*id).ok()?.tree().ok())
+                    .filter_map(|mut tree| tree.peel_to_entry_by_path(&path).ok().flatten())
+                    .any(|entry| entry.mode().is_commit() && !repo.has_object(entry.oid()));
+                if !has_missing_commit {
+                    continue;
+                }
+            }
+            let Some(remote) = repo.find_default_remote(remote::Direction::Fetch).transpose()? else {
+                continue;
+            };
+            let fetch = remote
+                .connect(remote::Direction::Fetch)?
+                .prepare_fetch(gix_features::progress::Discard, Default::default())?
+                .with_recurse_submodules(options.recurse)
+                .receive(gix_features::progress::Discard, should_interrupt)
+                .map_err(|err| Error::Fetch {
+                    name: submodule.name().to_owned(),
+                    source: err.into(),
+                })?;
+            out.push(Outcome {
+                name: submodule.name().to_owned(),
+                fetch,
+            });
+        }
+        Ok(out)
+    }
+}
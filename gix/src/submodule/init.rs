@@ -0,0 +1,145 @@
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    remote,
+    submodule::config,
+    Repository,
+};
+
+/// The error returned by [Repository::init_submodule()](crate::Repository::init_submodule()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("There is no submodule named '{name}'")]
+    NotFound { name: BString },
+    #[error(transparent)]
+    Modules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    Url(#[from] config::url::Error),
+    #[error(transparent)]
+    Update(#[from] config::update::Error),
+    #[error(transparent)]
+    ResolveUrl(#[from] remote::find::existing::Error),
+    #[error(transparent)]
+    WriteConfig(#[from] crate::submodule::write_config::Error),
+}
+
+impl Repository {
+    /// Initialize the submodule `name` by copying its url from `.gitmodules` into the configuration of this repository,
+    /// similar to `git submodule init <path>`.
+    /// Return `false` if it was initialized already, i.e. if its url was configured, or `true` if it is now initialized.
+    ///
+    /// Relative urls like `../other` are resolved against the url of the default remote, or against the worktree of this
+    /// repository if there is no such remote.
+    /// The `update` mode is copied as well unless it is a command, and the submodule is marked as active unless
+    /// `submodule.active` is configured, which then decides on its own.
+    ///
+    /// Both the local configuration file and the in-memory configuration of this instance are changed.
+    pub fn init_submodule(&mut self, name: &BStr) -> Result<bool, Error> {
+        let modules = self
+            .modules()?
+            .ok_or_else(|| Error::NotFound { name: name.to_owned() })?;
+        if !modules.names().any(|n| n == name) {
+            return Err(Error::NotFound { name: name.to_owned() });
+        }
+        if self.config.resolved.string(format!("submodule.{name}.url")).is_some() {
+            return Ok(false);
+        }
+
+        let url = resolve_url(self, modules_url(&modules, name)?.as_ref())?;
+        let update = match modules.update(name) {
+            Ok(Some(config::Update::Command(_)) | None) => None,
+            Ok(Some(update)) => Some(update_mode_name(&update)),
+            Err(config::update::Error::CommandForbiddenInModulesConfiguration { .. }) => None,
+            Err(err) => return Err(err.into()),
+        };
+        drop(modules);
+        let set_active = self.config.resolved.string("submodule.active").is_none();
+        crate::submodule::edit_local_config(self, |config, filter| {
+            config.set_raw_value_filter_by("submodule", Some(name), "url", url.as_bstr(), &mut *filter)?;
+            if set_active {
+                config.set_raw_value_filter_by("submodule", Some(name), "active", "true", &mut *filter)?;
+            }
+            if let Some(update) = update {
+                config.set_raw_value_filter_by("submodule", Some(name), "update", update, &mut *filter)?;
+            }
+            Ok(())
+        })?;
+        Ok(true)
+    }
+}
+
+/// Return the url of the submodule `name` as stored in `.gitmodules`, ignoring overrides from the configuration.
+pub(crate) fn modules_url(modules: &crate::submodule::File, name: &BStr) -> Result<BString, config::url::Error> {
+    // Sections of the `.gitmodules` file are marked as coming from the API, unlike the overrides of the configuration.
+    let url = modules
+        .config()
+        .string_filter(format!("submodule.{name}.url"), |meta| {
+            meta.source == gix_config::Source::Api
+        })
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| config::url::Error::Missing {
+            submodule: name.to_owned(),
+        })?;
+    gix_url::Url::from_bytes(url.as_ref()).map_err(|err| config::url::Error::Parse {
+        submodule: name.to_owned(),
+        source: err,
+    })?;
+    Ok(url.into_owned())
+}
+
+/// Resolve `url` against the url of the default remote of `repo` if it's relative, like `../other`, or against its worktree
+/// if there is no default remote.
+pub(crate) fn resolve_url(repo: &Repository, url: &BStr) -> Result<BString, remote::find::existing::Error> {
+    if !(url.starts_with(b"./") || url.starts_with(b"../")) {
+        return Ok(url.to_owned());
+    }
+    let base = match repo.find_default_remote(remote::Direction::Fetch).transpose()? {
+        Some(remote) => remote.url(remote::Direction::Fetch).map(gix_url::Url::to_bstring),
+        None => None,
+    };
+    let base = base.unwrap_or_else(|| {
+        let dir = repo.workdir().unwrap_or_else(|| repo.git_dir());
+        let dir = gix_path::realpath(dir).unwrap_or_else(|_| dir.to_owned());
+        gix_path::into_bstr(dir).into_owned()
+    });
+    Ok(join_relative_url(base.as_ref(), url))
+}
+
+/// Join the relative `url` to `base`, removing one path component of `base` for each leading `../` in `url`.
+fn join_relative_url(base: &BStr, mut url: &BStr) -> BString {
+    let mut base: BString = base.trim_end_with(|c| c == '/').into();
+    let mut separator = b'/';
+    loop {
+        if let Some(rest) = url.strip_prefix(b"./") {
+            url = rest.as_bstr();
+        } else if let Some(rest) = url.strip_prefix(b"../") {
+            url = rest.as_bstr();
+            match base.rfind_byteset(b"/:") {
+                Some(pos) => {
+                    separator = base[pos];
+                    base.truncate(pos);
+                }
+                None => base.clear(),
+            }
+        } else {
+            break;
+        }
+    }
+    if base.is_empty() {
+        return url.to_owned();
+    }
+    base.push(separator);
+    base.push_str(url);
+    base
+}
+
+/// Return the name of `update` as used in the configuration.
+fn update_mode_name(update: &config::Update) -> &'static str {
+    match update {
+        config::Update::Checkout => "checkout",
+        config::Update::Rebase => "rebase",
+        config::Update::Merge => "merge",
+        config::Update::None => "none",
+        config::Update::Command(_) => unreachable!("commands are never copied"),
+    }
+}
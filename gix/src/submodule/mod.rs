@@ -21,6 +21,31 @@ pub(crate) const MODULES_FILE: &str = ".gitmodules";
 mod errors;
 pub use errors::*;
 
+///
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "status"
+))]
+pub mod add;
+///
+#[cfg(feature = "status")]
+pub mod deinit;
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod fetch;
+///
+pub mod init;
+///
+pub mod sync;
+///
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "status"
+))]
+pub mod update;
+
 /// A platform maintaining state needed to interact with submodules, created by [`Repository::submodules()].
 pub(crate) struct SharedState<'repo> {
     pub repo: &'repo Repository,
@@ -469,3 +494,51 @@ pub struct State {
     /// Note that the presence of a single section is enough, independently of the actual values.
     pub superproject_configuration: bool,
 }
+
+///
+pub mod write_config {
+    /// The error returned when changing the local configuration of a repository while initializing, synchronizing
+    /// or de-initializing submodules.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Open(#[from] gix_config::file::init::from_paths::Error),
+        #[error(transparent)]
+        SetValue(#[from] gix_config::file::set_raw_value::Error),
+        #[error(transparent)]
+        Lock(#[from] gix_lock::acquire::Error),
+        #[error("Could not write the configuration file at '{}'", path.display())]
+        Write {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Apply(#[from] crate::config::Error),
+    }
+}
+
+/// Apply `edit` to the configuration file of `repo` in its `.git` directory, and to the in-memory configuration of `repo`.
+///
+/// `edit` is called with a filter to only let it change values in sections of the local configuration file.
+pub(crate) fn edit_local_config(
+    repo: &mut Repository,
+    mut edit: impl FnMut(
+        &mut gix_config::File<'static>,
+        &mut dyn FnMut(&gix_config::file::Metadata) -> bool,
+    ) -> Result<(), gix_config::file::set_raw_value::Error>,
+) -> Result<(), write_config::Error> {
+    let mut is_local = |meta: &gix_config::file::Metadata| meta.source == gix_config::Source::Local;
+    let path = repo.common_dir().join("config");
+    let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+    let mut file = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+    edit(&mut file, &mut is_local)?;
+    file.write_to(&mut lock)
+        .and_then(|_| lock.commit().map(|_| ()).map_err(|err| err.error))
+        .map_err(|source| write_config::Error::Write { path, source })?;
+
+    let mut config = repo.config_snapshot_mut();
+    edit(&mut config, &mut is_local)?;
+    config.commit()?;
+    Ok(())
+}
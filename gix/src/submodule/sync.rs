@@ -0,0 +1,65 @@
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote,
+    submodule::{config, init::modules_url, init::resolve_url},
+    Repository,
+};
+
+/// The error returned by [Repository::sync_submodule()](crate::Repository::sync_submodule()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("There is no submodule named '{name}'")]
+    NotFound { name: BString },
+    #[error(transparent)]
+    Modules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    Url(#[from] config::url::Error),
+    #[error(transparent)]
+    ResolveUrl(#[from] remote::find::existing::Error),
+    #[error(transparent)]
+    Open(#[from] crate::submodule::open::Error),
+    #[error(transparent)]
+    WriteConfig(#[from] crate::submodule::write_config::Error),
+}
+
+impl Repository {
+    /// Synchronize the url of the submodule `name` as stored in `.gitmodules` to the configuration of this repository if the
+    /// submodule is initialized, and to the default remote of the submodule repository if it was cloned, similar to
+    /// `git submodule sync <path>`.
+    /// Return the url the submodule is now configured to use, with relative urls resolved like in
+    /// [`init_submodule()`](Self::init_submodule()).
+    ///
+    /// This is useful after the url was changed upstream, which doesn't affect submodules that were initialized before.
+    pub fn sync_submodule(&mut self, name: &BStr) -> Result<BString, Error> {
+        let submodule = self
+            .try_find_submodule(name)?
+            .ok_or_else(|| Error::NotFound { name: name.to_owned() })?;
+        let url = resolve_url(self, modules_url(&submodule.state.modules, name)?.as_ref())?;
+        let submodule_repo = submodule.open()?;
+        drop(submodule);
+
+        if self.config.resolved.string(format!("submodule.{name}.url")).is_some() {
+            crate::submodule::edit_local_config(self, |config, filter| {
+                config.set_raw_value_filter_by("submodule", Some(name), "url", url.as_bstr(), &mut *filter)?;
+                Ok(())
+            })?;
+        }
+        if let Some(mut repo) = submodule_repo {
+            let remote_name: BString = repo
+                .remote_default_name(remote::Direction::Fetch)
+                .map_or_else(|| "origin".into(), std::borrow::Cow::into_owned);
+            crate::submodule::edit_local_config(&mut repo, |config, filter| {
+                config.set_raw_value_filter_by(
+                    "remote",
+                    Some(remote_name.as_ref()),
+                    "url",
+                    url.as_bstr(),
+                    &mut *filter,
+                )?;
+                Ok(())
+            })?;
+        }
+        Ok(url)
+    }
+}
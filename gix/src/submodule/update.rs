@@ -0,0 +1,499 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote,
+    submodule::config::{self, Update},
+    Repository,
+};
+
+/// The error returned by [Repository::update_submodule()](crate::Repository::update_submodule()) and
+/// [Repository::update_submodules()](crate::Repository::update_submodules()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot update submodules in a bare repository")]
+    BareRepository,
+    #[error("There is no submodule named '{name}'")]
+    NotFound { name: BString },
+    #[error("The commit {id} recorded for submodule '{name}' doesn't exist in its repository, even after fetching")]
+    CommitNotFound { name: BString, id: ObjectId },
+    #[error("The update mode {mode:?} of submodule '{name}' needs the '{feature}' feature to be enabled")]
+    UnsupportedUpdateMode {
+        name: BString,
+        mode: Update,
+        feature: &'static str,
+    },
+    #[error("Merging {id} into submodule '{name}' caused conflicts")]
+    MergeConflicts { name: BString, id: ObjectId },
+    #[error("Rebasing submodule '{name}' onto {id} stopped before it was finished")]
+    RebaseStopped { name: BString, id: ObjectId },
+    #[error("Could not run '{command}' to update submodule '{name}'")]
+    SpawnCommand {
+        name: BString,
+        command: BString,
+        source: std::io::Error,
+    },
+    #[error("'{command}' failed with {status} when updating submodule '{name}'")]
+    CommandFailed {
+        name: BString,
+        command: BString,
+        status: std::process::ExitStatus,
+    },
+    #[error(transparent)]
+    Modules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    Init(#[from] crate::submodule::init::Error),
+    #[error(transparent)]
+    Path(#[from] config::path::Error),
+    #[error(transparent)]
+    UpdateConfiguration(#[from] config::update::Error),
+    #[error(transparent)]
+    ShallowConfiguration(#[from] gix_config::value::Error),
+    #[error(transparent)]
+    IsActive(#[from] crate::submodule::is_active::Error),
+    #[error(transparent)]
+    IndexId(#[from] crate::submodule::index_id::Error),
+    #[error(transparent)]
+    Open(#[from] crate::submodule::open::Error),
+    #[error(transparent)]
+    OpenRepository(#[from] crate::open::Error),
+    #[error(transparent)]
+    ParseUrl(#[from] gix_url::parse::Error),
+    #[error(transparent)]
+    PrepareClone(#[from] crate::clone::Error),
+    #[error(transparent)]
+    Clone(#[from] crate::clone::fetch::Error),
+    #[error(transparent)]
+    WriteConfig(#[from] crate::submodule::write_config::Error),
+    #[error(transparent)]
+    FindRemote(#[from] remote::find::existing::Error),
+    #[error(transparent)]
+    Connect(#[from] remote::connect::Error),
+    #[error(transparent)]
+    PrepareFetch(#[from] remote::fetch::prepare::Error),
+    #[error(transparent)]
+    Fetch(#[from] remote::fetch::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::repository::switch::Error),
+    #[cfg(feature = "rebase")]
+    #[error(transparent)]
+    Rebase(#[from] crate::rebase::Error),
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    #[error(transparent)]
+    MergeBase(#[from] crate::repository::merge_base::Error),
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    #[error(transparent)]
+    MergeOptions(#[from] crate::repository::tree_merge_options::Error),
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    #[error(transparent)]
+    MergeCommits(#[from] crate::repository::merge_commits::Error),
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    #[error(transparent)]
+    WriteTree(#[from] crate::object::tree::editor::write::Error),
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    #[error(transparent)]
+    Commit(#[from] crate::commit::Error),
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    Realpath(#[from] gix_path::realpath::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Options for use in [Repository::update_submodule()](crate::Repository::update_submodule()).
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// If `true`, submodules that aren't initialized yet are [initialized](crate::Repository::init_submodule()) first,
+    /// like `git submodule update --init` does. Otherwise, they are skipped.
+    pub init: bool,
+    /// If `true`, the submodules of each updated submodule are updated as well, with the same options, like
+    /// `git submodule update --recursive` does.
+    pub recursive: bool,
+    /// If `true`, the recorded commit isn't fetched if it's missing in the submodule repository, like
+    /// `git submodule update --no-fetch` does. Submodules that weren't cloned yet are cloned nonetheless.
+    pub no_fetch: bool,
+    /// The way to update the submodule instead of the one configured in `submodule.<name>.update`, like
+    /// `git submodule update --checkout|--rebase|--merge` does.
+    pub mode: Option<Update>,
+}
+
+/// What happened to a submodule when [updating](crate::Repository::update_submodule()) it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The submodule isn't recorded in the index, isn't initialized or active, or its update mode is `none`,
+    /// so it was left alone.
+    Skipped,
+    /// The submodule already was at the recorded commit.
+    UpToDate,
+    /// The recorded commit was checked out on a detached `HEAD`.
+    CheckedOut,
+    /// The current branch of the submodule was rebased onto the recorded commit.
+    Rebased,
+    /// The recorded commit was merged into the current branch of the submodule.
+    Merged,
+    /// The configured command was run with the recorded commit as argument.
+    Command,
+}
+
+/// The outcome of [Repository::update_submodule()](crate::Repository::update_submodule()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The name of the submodule.
+    pub name: BString,
+    /// The commit recorded for the submodule in the index of the superproject, or `None` if there is none.
+    pub commit: Option<ObjectId>,
+    /// If `true`, the submodule repository was cloned as it didn't exist yet.
+    pub cloned: bool,
+    /// What was done to bring the submodule to the recorded commit.
+    pub change: Change,
+    /// The outcome of updating the submodules of the submodule, if [`Options::recursive`] was set.
+    pub submodules: Vec<Outcome>,
+}
+
+impl Repository {
+    /// Bring the submodule `name` to the commit recorded in the index of this repository as configured by `options`,
+    /// similar to `git submodule update <path>`.
+    ///
+    /// If the submodule repository doesn't exist yet, it's cloned into `.git/modules/<name>` from the url configured by
+    /// [initializing](Self::init_submodule()) the submodule, and the recorded commit is checked out on a detached `HEAD`
+    /// into its worktree. This also happens for submodules whose worktree was emptied by [de-initializing](Self::deinit_submodule()) them.
+    /// Otherwise, the recorded commit is fetched from the default remote of the submodule if it's missing, and the submodule
+    /// is updated according to its `update` mode in `submodule.<name>.update`, or [`Options::mode`]:
+    ///
+    /// * `checkout` - the recorded commit is checked out on a detached `HEAD`, which is the default.
+    /// * `rebase` - the current branch is rebased onto the recorded commit. Needs the `rebase` feature.
+    /// * `merge` - the recorded commit is merged into the current branch. Needs the `merge` and `revision` features.
+    /// * `!command` - the command is run in the worktree of the submodule with the recorded commit as argument.
+    /// * `none` - the submodule is left alone.
+    ///
+    /// Local changes in the worktree of the submodule are kept if they don't conflict, or cause the update to fail otherwise.
+    pub fn update_submodule(
+        &mut self,
+        name: &BStr,
+        options: &Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        if self.workdir().is_none() {
+            return Err(Error::BareRepository);
+        }
+        let not_found = || Error::NotFound { name: name.to_owned() };
+        let commit = self.try_find_submodule(name)?.ok_or_else(not_found)?.index_id()?;
+        let mut out = Outcome {
+            name: name.to_owned(),
+            commit,
+            cloned: false,
+            change: Change::Skipped,
+            submodules: Vec::new(),
+        };
+        let Some(commit) = commit else {
+            return Ok(out);
+        };
+        if self.config.resolved.string(format!("submodule.{name}.url")).is_none() {
+            if !options.init {
+                return Ok(out);
+            }
+            self.init_submodule(name)?;
+        }
+
+        let submodule = self.try_find_submodule(name)?.ok_or_else(not_found)?;
+        if !submodule.is_active()? {
+            return Ok(out);
+        }
+        let mode = match &options.mode {
+            Some(mode) => mode.clone(),
+            None => submodule.update()?.unwrap_or_default(),
+        };
+        if mode == Update::None {
+            return Ok(out);
+        }
+        let url = self
+            .config
+            .resolved
+            .string(format!("submodule.{name}.url"))
+            .expect("initialized")
+            .into_owned();
+        let git_dir = submodule.git_dir();
+        let work_dir = submodule.work_dir()?;
+        let shallow = submodule.shallow()?.unwrap_or(false);
+        let existing = submodule.open()?;
+        drop(submodule);
+
+        let mut repo = match existing {
+            Some(repo) => repo,
+            None => {
+                out.cloned = true;
+                clone(self, url.as_ref(), &git_dir, &work_dir, shallow, None, should_interrupt)?
+            }
+        };
+        let is_populated = work_dir.join(gix_discover::DOT_GIT_DIR).exists();
+        if !is_populated {
+            connect_worktree(&git_dir, &work_dir, self.options.clone())?;
+            repo = crate::open_opts(&git_dir, self.options.clone())?;
+        }
+        if !repo.has_object(commit) && !options.no_fetch {
+            fetch(&repo, None, should_interrupt)?;
+            if !repo.has_object(commit) {
+                fetch(&repo, Some(commit), should_interrupt)?;
+            }
+        }
+        if !repo.has_object(commit) {
+            return Err(Error::CommitNotFound {
+                name: name.to_owned(),
+                id: commit,
+            });
+        }
+
+        let head = repo.head_id().ok().map(crate::Id::detach);
+        let checkout = |repo: &Repository, force: bool| {
+            repo.switch(
+                commit.to_string().as_str(),
+                crate::repository::switch::Options { force, detach: true },
+            )
+        };
+        out.change = if out.cloned || !is_populated {
+            checkout(&repo, true)?;
+            Change::CheckedOut
+        } else if head == Some(commit) {
+            Change::UpToDate
+        } else {
+            match mode {
+                Update::Checkout => {
+                    checkout(&repo, false)?;
+                    Change::CheckedOut
+                }
+                Update::Rebase => rebase(&repo, name, commit)?,
+                Update::Merge => merge(&repo, name, commit, head)?,
+                Update::Command(command) => {
+                    let status = std::process::Command::from(
+                        gix_command::prepare(gix_path::from_bstr(command.as_bstr()).into_owned())
+                            .with_shell()
+                            .arg(commit.to_string()),
+                    )
+                    .current_dir(&work_dir)
+                    .status()
+                    .map_err(|source| Error::SpawnCommand {
+                        name: name.to_owned(),
+                        command: command.clone(),
+                        source,
+                    })?;
+                    if !status.success() {
+                        return Err(Error::CommandFailed {
+                            name: name.to_owned(),
+                            command,
+                            status,
+                        });
+                    }
+                    Change::Command
+                }
+                Update::None => unreachable!("handled early"),
+            }
+        };
+
+        if options.recursive {
+            out.submodules = repo.update_submodules(options, should_interrupt)?;
+        }
+        Ok(out)
+    }
+
+    /// [Update](Self::update_submodule()) all submodules as configured by `options`, similar to `git submodule update`.
+    pub fn update_submodules(
+        &mut self,
+        options: &Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<Outcome>, Error> {
+        let names: Vec<BString> = match self.submodules()? {
+            Some(submodules) => submodules.map(|sm| sm.name().to_owned()).collect(),
+            None => return Ok(Vec::new()),
+        };
+        names
+            .iter()
+            .map(|name| self.update_submodule(name.as_ref(), options, should_interrupt))
+            .collect()
+    }
+}
+
+/// Clone `url` into `git_dir` and make `work_dir` its worktree like `git` does for submodules, without checking anything out.
+/// Clone only a single commit if `shallow` is `true`, and use `ref_name` as `HEAD` if set.
+pub(crate) fn clone(
+    superproject: &Repository,
+    url: &BStr,
+    git_dir: &Path,
+    work_dir: &Path,
+    shallow: bool,
+    ref_name: Option<&gix_ref::PartialNameRef>,
+    should_interrupt: &AtomicBool,
+) -> Result<Repository, Error> {
+    std::fs::create_dir_all(git_dir)?;
+    let mut prepare = crate::clone::PrepareFetch::new(
+        gix_url::parse(url)?,
+        git_dir,
+        crate::create::Kind::Bare,
+        Default::default(),
+        superproject.options.clone(),
+    )?
+    .with_ref_name(ref_name)
+    .unwrap_or_else(|err| match err {});
+    if shallow {
+        prepare = prepare.with_shallow(remote::fetch::Shallow::DepthAtRemote(1.try_into().expect("non-zero")));
+    }
+    prepare.fetch_only(gix_features::progress::Discard, should_interrupt)?;
+    connect_worktree(git_dir, work_dir, superproject.options.clone())?;
+    Ok(crate::open_opts(git_dir, superproject.options.clone())?)
+}
+
+/// Make `work_dir` the worktree of the repository at `git_dir` by writing a `.git` file that points to `git_dir`,
+/// and by configuring `core.worktree` accordingly, using relative paths just like `git`.
+fn connect_worktree(git_dir: &Path, work_dir: &Path, options: crate::open::Options) -> Result<(), Error> {
+    std::fs::create_dir_all(work_dir)?;
+    let git_dir = gix_path::realpath(git_dir)?;
+    let work_dir = gix_path::realpath(work_dir)?;
+    let to_git_dir = gix_path::to_unix_separators_on_windows(gix_path::into_bstr(relative_path(&work_dir, &git_dir)));
+    std::fs::write(
+        work_dir.join(gix_discover::DOT_GIT_DIR),
+        format!("gitdir: {to_git_dir}\n"),
+    )?;
+
+    let to_work_dir = gix_path::to_unix_separators_on_windows(gix_path::into_bstr(relative_path(&git_dir, &work_dir)));
+    let mut repo = crate::open_opts(&git_dir, options)?;
+    crate::submodule::edit_local_config(&mut repo, |config, filter| {
+        config.set_raw_value_filter_by("core", None, "bare", "false", &mut *filter)?;
+        config.set_raw_value_filter_by("core", None, "worktree", to_work_dir.as_ref(), &mut *filter)?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Return the path that leads from the directory `from` to `to`, both of which are absolute.
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut out: PathBuf = std::iter::repeat("..").take(from.len() - common).collect();
+    out.extend(&to[common..]);
+    out
+}
+
+/// Fetch from the default remote of `repo`, along with `commit` if set.
+fn fetch(repo: &Repository, commit: Option<ObjectId>, should_interrupt: &AtomicBool) -> Result<(), Error> {
+    let Some(remote) = repo.find_default_remote(remote::Direction::Fetch).transpose()? else {
+        return Ok(());
+    };
+    let options = remote::ref_map::Options {
+        extra_refspecs: commit
+            .map(|id| {
+                gix_refspec::parse(id.to_string().as_str().into(), gix_refspec::parse::Operation::Fetch)
+                    .expect("object ids are valid refspecs")
+                    .to_owned()
+            })
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    };
+    remote
+        .connect(remote::Direction::Fetch)?
+        .prepare_fetch(gix_features::progress::Discard, options)?
+        .receive(gix_features::progress::Discard, should_interrupt)?;
+    Ok(())
+}
+
+#[cfg(feature = "rebase")]
+fn rebase(repo: &Repository, name: &BStr, commit: ObjectId) -> Result<Change, Error> {
+    match repo.rebase().start(commit.to_string().as_str(), Default::default())? {
+        crate::rebase::Outcome::Finished { .. } => Ok(Change::Rebased),
+        crate::rebase::Outcome::Stopped(_) => Err(Error::RebaseStopped {
+            name: name.to_owned(),
+            id: commit,
+        }),
+    }
+}
+
+#[cfg(not(feature = "rebase"))]
+fn rebase(_repo: &Repository, name: &BStr, _commit: ObjectId) -> Result<Change, Error> {
+    Err(Error::UnsupportedUpdateMode {
+        name: name.to_owned(),
+        mode: Update::Rebase,
+        feature: "rebase",
+    })
+}
+
+#[cfg(all(feature = "merge", feature = "revision"))]
+fn merge(repo: &Repository, name: &BStr, commit: ObjectId, head: Option<ObjectId>) -> Result<Change, Error> {
+    use gix_ref::transaction::{Change as RefChange, LogChange, PreviousValue, RefEdit, RefLog};
+
+    let base = match head {
+        Some(head) => repo.merge_base(head, commit)?.detach(),
+        None => commit,
+    };
+    if Some(base) == head || head.is_none() {
+        repo.checkout_tree(repo.find_commit(commit)?.tree_id()?, Default::default())?;
+        repo.edit_reference(RefEdit {
+            change: RefChange::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("merge {commit}: Fast-forward").into(),
+                },
+                expected: match head {
+                    Some(head) => PreviousValue::MustExistAndMatch(gix_ref::Target::Object(head)),
+                    None => PreviousValue::Any,
+                },
+                new: gix_ref::Target::Object(commit),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        })?;
+        return Ok(Change::Merged);
+    }
+    let head = head.expect("handled above");
+    if base == commit {
+        return Ok(Change::UpToDate);
+    }
+
+    let other = commit.to_string();
+    let mut outcome = repo.merge_commits(
+        head,
+        commit,
+        gix_merge::blob::builtin_driver::text::Labels {
+            ancestor: None,
+            current: Some("HEAD".into()),
+            other: Some(other.as_str().into()),
+        },
+        repo.tree_merge_options()?.into(),
+    )?;
+    if outcome
+        .tree_merge
+        .has_unresolved_conflicts(crate::merge::tree::TreatAsUnresolved::default())
+    {
+        return Err(Error::MergeConflicts {
+            name: name.to_owned(),
+            id: commit,
+        });
+    }
+    let tree = outcome.tree_merge.tree.write()?.detach();
+    repo.checkout_tree(tree, Default::default())?;
+    repo.commit("HEAD", format!("Merge commit '{commit}'"), tree, [head, commit])?;
+    Ok(Change::Merged)
+}
+
+#[cfg(not(all(feature = "merge", feature = "revision")))]
+fn merge(_repo: &Repository, name: &BStr, _commit: ObjectId, _head: Option<ObjectId>) -> Result<Change, Error> {
+    Err(Error::UnsupportedUpdateMode {
+        name: name.to_owned(),
+        mode: Update::Merge,
+        feature: "merge",
+    })
+}
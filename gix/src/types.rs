@@ -233,6 +233,10 @@ pub struct Remote<'repo> {
     pub(crate) push_specs: Vec<gix_refspec::RefSpec>,
     /// Tell us what to do with tags when fetched.
     pub(crate) fetch_tags: remote::fetch::Tags,
+    /// The filter to use when fetching, to omit objects that are then fetched on demand.
+    pub(crate) filter: Option<remote::fetch::Filter>,
+    /// If `true`, the remote promises to provide objects that are missing locally.
+    pub(crate) promisor: bool,
     // /// Delete local tracking branches that don't exist on the remote anymore.
     // pub(crate) prune: bool,
     // /// Delete tags that don't exist on the remote anymore, equivalent to pruning the refspec `refs/tags/*:refs/tags/*`.
@@ -86,3 +86,24 @@ pub fn parallel_iter_drop<T, U, V>(
     )
     .ok();
 }
+
+/// Remove the file at `rela_path` within `workdir` if it exists, along with all directories that become empty because of that.
+/// Directories, like those of submodules, are left alone.
+#[cfg(feature = "status")]
+pub fn remove_file_and_empty_parents(workdir: &std::path::Path, rela_path: &crate::bstr::BStr) -> std::io::Result<()> {
+    let path = workdir.join(gix_path::from_bstr(rela_path));
+    match std::fs::symlink_metadata(&path) {
+        Ok(md) if md.is_dir() => return Ok(()),
+        Ok(_) => std::fs::remove_file(&path)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    }
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
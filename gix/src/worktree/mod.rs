@@ -9,6 +9,9 @@ pub use gix_worktree_state as state;
 #[cfg(feature = "worktree-stream")]
 pub use gix_worktree_stream as stream;
 
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+pub(crate) mod update;
+
 use crate::{
     bstr::{BStr, BString},
     Repository,
@@ -57,6 +60,22 @@ pub struct Proxy<'repo> {
     pub(crate) git_dir: PathBuf,
 }
 
+/// A change made by [Repository::repair_worktrees()](crate::Repository::repair_worktrees()) to reconnect a linked worktree
+/// with its private data in the common directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Repair {
+    /// The `.git` file at `path` in the worktree was changed to point to the private data of the worktree.
+    DotGitFile {
+        /// The path to the `.git` file.
+        path: PathBuf,
+    },
+    /// The `gitdir` file in the private data of the worktree with the given `id` was changed to point to the worktree.
+    GitDirFile {
+        /// The name of the worktree.
+        id: BString,
+    },
+}
+
 /// Access
 impl<'repo> crate::Worktree<'repo> {
     /// Read the location of the checkout, the base of the work tree
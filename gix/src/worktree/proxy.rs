@@ -24,6 +24,79 @@ pub mod into_repo {
     }
 }
 
+///
+pub mod lock {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::lock()`](super::Proxy::lock()) and [`Proxy::unlock()`](super::Proxy::unlock()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is already locked")]
+        AlreadyLocked { id: BString },
+        #[error("Worktree '{id}' is not locked")]
+        NotLocked { id: BString },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+#[cfg(feature = "status")]
+pub mod remove {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::remove()`](super::Proxy::remove()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked")]
+        Locked { id: BString },
+        #[error("Worktree '{id}' contains local modifications, which would be lost")]
+        LocalModifications { id: BString },
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        IsDirty(#[from] crate::status::is_dirty::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+pub mod move_to {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::move_to()`](super::Proxy::move_to()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked")]
+        Locked { id: BString },
+        #[error("'{}' exists already", path.display())]
+        DestinationExists { path: PathBuf },
+        #[error(transparent)]
+        Realpath(#[from] gix_path::realpath::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+/// The reason for a worktree to be prunable, as returned by [`Proxy::prunable()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Prunable {
+    /// The private directory of the worktree isn't a directory.
+    NotADirectory,
+    /// The `gitdir` file that points to the worktree doesn't exist.
+    GitDirFileMissing,
+    /// The `gitdir` file that points to the worktree couldn't be read or is empty.
+    InvalidGitDirFile,
+    /// The worktree the `gitdir` file points to doesn't exist anymore.
+    WorktreeMissing,
+}
+
 impl<'repo> Proxy<'repo> {
     pub(crate) fn new(parent: &'repo Repository, git_dir: impl Into<PathBuf>) -> Self {
         Proxy {
@@ -74,6 +147,120 @@ impl Proxy<'_> {
             .map(|contents| contents.trim().into())
     }
 
+    /// Return the name of the branch that is checked out in this worktree, or `None` if its `HEAD` is detached or can't be read.
+    pub fn head_ref_name(&self) -> Option<gix_ref::FullName> {
+        let head = std::fs::read(self.git_dir.join("HEAD")).ok()?;
+        let name = head.trim_end().strip_prefix(b"ref: ")?;
+        gix_ref::FullName::try_from(name.as_bstr()).ok()
+    }
+
+    /// Return the reason for this worktree to be removable by [`Repository::prune_worktrees()`](crate::Repository::prune_worktrees()),
+    /// or `None` if it's still in use or [locked](Self::is_locked()), similar to what `git worktree list` shows as `prunable`.
+    pub fn prunable(&self) -> Option<Prunable> {
+        if !self.git_dir.is_dir() {
+            return Some(Prunable::NotADirectory);
+        }
+        if self.is_locked() {
+            return None;
+        }
+        let dot_git = match std::fs::read(self.git_dir.join("gitdir")) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Some(Prunable::GitDirFileMissing),
+            Err(_) => return Some(Prunable::InvalidGitDirFile),
+        };
+        let dot_git = dot_git.trim();
+        if dot_git.is_empty() {
+            return Some(Prunable::InvalidGitDirFile);
+        }
+        let dot_git = self.git_dir.join(gix_path::from_bstr(dot_git.as_bstr()));
+        (!dot_git.exists()).then_some(Prunable::WorktreeMissing)
+    }
+
+    /// Lock this worktree to prevent it from being pruned, moved or removed, and record `reason` for doing so, similar to
+    /// `git worktree lock --reason <reason>`. This is useful for worktrees on removable storage.
+    pub fn lock(&self, reason: Option<&BStr>) -> Result<(), lock::Error> {
+        if self.is_locked() {
+            return Err(lock::Error::AlreadyLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::write(self.git_dir.join("locked"), reason.unwrap_or_default())?;
+        Ok(())
+    }
+
+    /// Unlock this worktree after it was [locked](Self::lock()), similar to `git worktree unlock`.
+    pub fn unlock(&self) -> Result<(), lock::Error> {
+        if !self.is_locked() {
+            return Err(lock::Error::NotLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::remove_file(self.git_dir.join("locked"))?;
+        Ok(())
+    }
+
+    /// Delete the worktree along with its private data in the common directory, similar to `git worktree remove`.
+    ///
+    /// Unless `force` is `true`, locked worktrees and those with changes in the index or the worktree aren't removed.
+    ///
+    /// ### Deviation
+    ///
+    /// Untracked files aren't considered local modifications, and are removed along with the rest of the worktree.
+    #[cfg(feature = "status")]
+    pub fn remove(self, force: bool) -> Result<(), remove::Error> {
+        if !force && self.is_locked() {
+            return Err(remove::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let base = self.base().ok().filter(|base| base.is_dir());
+        if let Some(base) = &base {
+            if !force {
+                let repo: Repository = ThreadSafeRepository::open_from_paths(
+                    self.git_dir.clone(),
+                    base.clone().into(),
+                    self.parent.options.clone(),
+                )?
+                .into();
+                if repo.is_dirty()? {
+                    return Err(remove::Error::LocalModifications {
+                        id: self.id().to_owned(),
+                    });
+                }
+            }
+            std::fs::remove_dir_all(base)?;
+        }
+        std::fs::remove_dir_all(&self.git_dir)?;
+        Ok(())
+    }
+
+    /// Move the worktree to `new_base` and update its private data accordingly, similar to `git worktree move`,
+    /// and return the new location of the worktree.
+    ///
+    /// If `new_base` is an existing directory, the worktree is moved into it.
+    /// Locked worktrees can't be moved, and the move fails if the destination is on another device.
+    pub fn move_to(&self, new_base: impl AsRef<Path>) -> Result<PathBuf, move_to::Error> {
+        if self.is_locked() {
+            return Err(move_to::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let base = self.base()?;
+        let mut new_base = new_base.as_ref().to_owned();
+        if new_base.is_dir() {
+            new_base.push(base.file_name().ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "worktree location has no name")
+            })?);
+        }
+        if new_base.exists() {
+            return Err(move_to::Error::DestinationExists { path: new_base });
+        }
+        std::fs::rename(&base, &new_base)?;
+        let new_base = gix_path::realpath(&new_base)?;
+        write_gitdir_file(&self.git_dir, &new_base.join(gix_discover::DOT_GIT_DIR))?;
+        Ok(new_base)
+    }
+
     /// Transform this proxy into a [`Repository`] while ignoring issues reading `base()` and ignoring that it might not exist.
     ///
     /// Most importantly, the `Repository` might be initialized with a non-existing work tree directory as the checkout
@@ -99,3 +286,25 @@ impl Proxy<'_> {
         Ok(repo.into())
     }
 }
+
+/// Write the `gitdir` file into `private_git_dir` so that it points to the `.git` file at `dot_git` in the worktree.
+pub(crate) fn write_gitdir_file(private_git_dir: &Path, dot_git: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        private_git_dir.join("gitdir"),
+        format!(
+            "{}\n",
+            gix_path::to_unix_separators_on_windows(gix_path::into_bstr(dot_git))
+        ),
+    )
+}
+
+/// Write the `.git` file at `dot_git` in a worktree so that it points to `private_git_dir`.
+pub(crate) fn write_dot_git_file(dot_git: &Path, private_git_dir: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        dot_git,
+        format!(
+            "gitdir: {}\n",
+            gix_path::to_unix_separators_on_windows(gix_path::into_bstr(private_git_dir))
+        ),
+    )
+}
@@ -0,0 +1,143 @@
+//! Utilities to change files in the worktree along with their entries in the index.
+
+use std::collections::BTreeSet;
+use std::sync::atomic::AtomicBool;
+
+use gix_index::entry::Stage;
+use gix_status::index_as_worktree::EntryStatus;
+
+use crate::bstr::{BStr, BString};
+use crate::Repository;
+
+/// Return `true` if `index` has entries of unresolved conflicts.
+pub(crate) fn is_unmerged(index: &gix_index::State) -> bool {
+    index.entries().iter().any(|entry| entry.stage() != Stage::Unconflicted)
+}
+
+/// Return the paths of all entries in `index` whose worktree file doesn't match, or was deleted.
+pub(crate) fn worktree_changes<E>(repo: &Repository, index: &gix_index::File) -> Result<BTreeSet<BString>, E>
+where
+    E: From<crate::status::Error> + From<crate::status::into_iter::Error> + From<crate::status::index_worktree::Error>,
+{
+    let iter = repo
+        .status(crate::progress::Discard)?
+        .index(crate::worktree::IndexPersistedOrInMemory::InMemory(index.clone()))
+        .untracked_files(crate::status::UntrackedFiles::None)
+        .index_worktree_submodules(crate::status::Submodule::Given {
+            ignore: crate::submodule::config::Ignore::Dirty,
+            check_dirty: false,
+        })
+        .into_index_worktree_iter(Vec::new())?;
+    let mut out = BTreeSet::new();
+    for item in iter {
+        if let crate::status::index_worktree::Item::Modification { rela_path, status, .. } = item? {
+            if !matches!(status, EntryStatus::NeedsUpdate(_)) {
+                out.insert(rela_path);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Return the paths to all untracked files and symlinks in the worktree, without ignored ones.
+pub(crate) fn untracked_files<E>(repo: &Repository, index: &gix_index::State) -> Result<Vec<BString>, E>
+where
+    E: From<crate::config::boolean::Error> + From<crate::dirwalk::Error>,
+{
+    use gix_dir::entry::{Kind, Status};
+
+    let options = repo
+        .dirwalk_options()?
+        .emit_untracked(gix_dir::walk::EmissionMode::Matching);
+    let mut collect = gix_dir::walk::delegate::Collect::default();
+    repo.dirwalk(index, None::<&BStr>, &AtomicBool::default(), options, &mut collect)?;
+    Ok(collect
+        .into_entries_by_path()
+        .into_iter()
+        .filter(|(entry, _)| {
+            entry.status == Status::Untracked && matches!(entry.disk_kind, Some(Kind::File | Kind::Symlink))
+        })
+        .map(|(entry, _)| entry.rela_path)
+        .collect())
+}
+
+/// Put the paths of all entries that differ between `a` and `b`, or that exist in only one of them, into `out`.
+pub(crate) fn changed_paths(a: &gix_index::State, b: &gix_index::State, out: &mut BTreeSet<BString>) {
+    for (lhs, rhs) in [(a, b), (b, a)] {
+        for entry in lhs.entries() {
+            let path = entry.path(lhs);
+            let unchanged = rhs
+                .entry_by_path_and_stage(path, entry.stage())
+                .is_some_and(|other| other.id == entry.id && other.mode == entry.mode);
+            if !unchanged {
+                out.insert(path.to_owned());
+            }
+        }
+    }
+}
+
+/// Copy the stat information of entries in `previous` to their unchanged counterparts in `index`, unless their path
+/// is contained in `exclude`, to avoid having to re-hash their worktree files later.
+pub(crate) fn carry_over_stat(index: &mut gix_index::State, previous: &gix_index::State, exclude: &BTreeSet<BString>) {
+    for (entry, path) in index.entries_mut_with_paths() {
+        if exclude.contains(path) {
+            continue;
+        }
+        if let Some(previous) = previous
+            .entry_by_path_and_stage(path, entry.stage())
+            .filter(|previous| previous.id == entry.id && previous.mode == entry.mode)
+        {
+            entry.stat = previous.stat;
+        }
+    }
+}
+
+/// Make the worktree files at `paths` match their unconflicted entries in `index`, and delete those that have no entry.
+/// The stat information of all written entries is updated in `index`.
+///
+/// Local changes to these files are overwritten, so the caller has to assure they are expendable.
+pub(crate) fn checkout_paths<E>(
+    repo: &Repository,
+    index: &mut gix_index::State,
+    paths: &BTreeSet<BString>,
+) -> Result<(), E>
+where
+    E: From<std::io::Error> + From<crate::config::checkout_options::Error> + From<gix_worktree_state::checkout::Error>,
+{
+    let workdir = repo.workdir().expect("BUG: caller assures there is a worktree");
+    for path in paths {
+        let path: &BStr = path.as_ref();
+        if index.entry_by_path_and_stage(path, Stage::Unconflicted).is_some() {
+            // Existing files are recreated so their type and executable bit match the entry.
+            let file = workdir.join(gix_path::from_bstr(path));
+            if std::fs::symlink_metadata(&file).is_ok_and(|md| !md.is_dir()) {
+                std::fs::remove_file(file)?;
+            }
+        } else {
+            crate::util::remove_file_and_empty_parents(workdir, path)?;
+        }
+    }
+
+    let mut files = index.clone();
+    files.remove_entries(|_, path, entry| entry.stage() != Stage::Unconflicted || !paths.contains(path));
+    if files.entries().is_empty() {
+        return Ok(());
+    }
+    let mut options = repo.checkout_options(gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping)?;
+    options.overwrite_existing = true;
+    gix_worktree_state::checkout(
+        &mut files,
+        workdir,
+        repo.objects.clone().into_arc()?,
+        &crate::progress::Discard,
+        &crate::progress::Discard,
+        &AtomicBool::default(),
+        options,
+    )?;
+    for entry in files.entries() {
+        if let Some(written) = index.entry_mut_by_path_and_stage(entry.path(&files), Stage::Unconflicted) {
+            written.stat = entry.stat;
+        }
+    }
+    Ok(())
+}
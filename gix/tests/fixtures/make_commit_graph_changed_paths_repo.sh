@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+mkdir -p dir/sub
+echo a > a
+echo b > dir/b
+echo c > dir/sub/c
+ln -s a link
+git add . && git commit -q -m initial
+
+git checkout -q -b side
+echo c2 > dir/sub/c
+git commit -q -am "change dir/sub/c"
+
+git checkout -q main
+rm dir/b
+mkdir dir/b
+echo d > dir/b/d
+git add -A && git commit -q -m "turn dir/b into a directory"
+
+git merge -q side -m merge
+git branch -q -D side
+
+mkdir many
+for i in $(seq 513); do echo $i > many/$i; done
+git add many && git commit -q -m "change too many paths"
+
+git commit-graph write --no-progress --reachable --changed-paths
+mv .git/objects/info/commit-graph expected-commit-graph
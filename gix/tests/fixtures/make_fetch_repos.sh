@@ -20,6 +20,22 @@ git clone --shared base two-origins
   git symbolic-ref refs/heads/symbolic refs/heads/main
 )
 
+git clone --shared base fetch-head
+(cd fetch-head
+  function add_remote() {
+    git remote add changes-on-top-of-origin "$PWD/../clone-as-base-with-changes"
+    git config branch.main.remote changes-on-top-of-origin
+    git config branch.main.merge refs/heads/main
+  }
+  add_remote
+  git fetch changes-on-top-of-origin
+  mv .git/FETCH_HEAD ../fetch-head.baseline
+  # undo the fetch so it can be repeated
+  git remote remove changes-on-top-of-origin
+  git tag -d v1.0
+  add_remote
+)
+
 git clone --shared base worktree-root
 (cd worktree-root
 
@@ -0,0 +1,15 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo "*.ignored" > .gitignore
+echo "*.crlf text eol=crlf" > .gitattributes
+mkdir -p dir/sub other
+echo a > a
+echo b > dir/b
+echo c > dir/sub/c
+echo d > other/d
+git add .
+git commit -q -m "initial"
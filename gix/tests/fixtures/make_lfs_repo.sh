@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo '*.bin filter=lfs diff=lfs merge=lfs -text' > .gitattributes
+echo content > file
+git add . && git commit -q -m "init"
+
+git checkout -q -b with-lfs
+# The pointer of 'large content\n', whose object is in the LFS store.
+printf 'version https://git-lfs.github.com/spec/v1\noid sha256:ca27f3a1fa46b52b9b770c0f09ec44d48b0f6a1e6b0c8955b8ac1976b6d6c3cb\nsize 14\n' > large.bin
+git add . && git commit -q -m "add LFS files"
+git checkout -q main
+
+mkdir -p .git/lfs/objects/ca/27
+printf 'large content\n' > .git/lfs/objects/ca/27/ca27f3a1fa46b52b9b770c0f09ec44d48b0f6a1e6b0c8955b8ac1976b6d6c3cb
+
+# Configure the filter like `git lfs install` does, which must not be run as the filter is built-in.
+git config filter.lfs.process "git-lfs-is-not-installed filter-process"
+git config filter.lfs.required true
+git remote add origin https://example.com/org/repo
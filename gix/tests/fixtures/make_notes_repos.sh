@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  for name in c1 c2 c3; do
+    git commit -q --allow-empty -m "$name"
+    git tag "$name"
+  done
+
+  git notes add -m "first" c1
+  git notes append -m "second" c1
+  git notes add -m "copied" c2
+  git notes copy c2 c3
+  git notes remove c2 2>/dev/null
+
+  git notes --ref ci add -m "flaky" c2
+  git update-ref refs/notes/ci-behind refs/notes/ci
+  git notes --ref ci add -m "passed" c3
+  git update-ref refs/notes/ci-local refs/notes/ci
+  git notes --ref ci add -m "build 2" c1
+  git notes --ref ci-local add -m "local" c1
+  git config notes.ci-local.mergeStrategy union
+
+  git update-ref refs/notes/ci-union refs/notes/ci-local
+  git notes --ref ci-union merge -q -s union ci
+)
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --bare remote.git
+
+git init -q local
+(cd local
+  git remote add origin ../remote.git
+  git commit -q --allow-empty -m a
+  git commit -q --allow-empty -m b
+  git branch other
+  git push -q -u origin main other
+  git fetch -q origin
+
+  echo c > file && git add file && git commit -q -m c
+  git tag -a v1 -m "tag v1"
+  git branch rewritten HEAD~2
+  git checkout -q -b feature
+  echo d > file && git commit -q -am d
+  git checkout -q main
+)
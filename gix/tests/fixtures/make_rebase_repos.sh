@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  echo base > base
+  git add base
+  git commit -q -m "base"
+
+  git checkout -q -b topic
+  echo a > a
+  git add a
+  git commit -q -m "add a"
+  echo b > b
+  git add b
+  git commit -q -m "add b"
+  echo "a fixed" > a
+  git commit -q -am "fixup! add a"
+  echo "b extended" >> b
+  git commit -q -am "squash! add b" -m "extend b"
+
+  git checkout -q main
+  echo upstream > upstream
+  git add upstream
+  git commit -q -m "upstream"
+
+  git checkout -q -b conflicting
+  echo "b conflicting" > b
+  git add b
+  git commit -q -m "b conflicting"
+
+  git checkout -q topic
+)
+
+git clone -q repo git-started
+(cd git-started
+  GIT_SEQUENCE_EDITOR="sed -i.bak -e '1s/^pick/edit/'" git rebase -q -i origin/main
+)
@@ -381,3 +381,9 @@ git init empty-core-askpass
 (cd empty-core-askpass
   echo "    askpass =" >> .git/config
 )
+
+git clone --bare base base.promisor
+(cd base.promisor
+  git config uploadpack.allowFilter true
+  git config uploadpack.allowAnySHA1InWant true
+)
@@ -0,0 +1,41 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  echo base > f
+  git add f
+  git commit -q -m "base"
+  git branch release
+
+  git checkout -q -b topic
+  echo one > g
+  git add g
+  git commit -q -m "one"
+  echo topic > f
+  git commit -q -am "two" -m "Signed-off-by: author <author@example.com>"
+  echo three > h
+  git add h
+  git commit -q -m "three"
+
+  git checkout -q -b side release
+  echo side > s
+  git add s
+  git commit -q -m "side"
+  git checkout -q -b merged release
+  echo mainline > m
+  git add m
+  git commit -q -m "mainline"
+  git merge -q --no-ff -m "merge side" side
+
+  git checkout -q main
+  echo main > f
+  git commit -q -am "main change"
+)
+
+git clone -q repo git-started
+(cd git-started
+  git checkout -q main
+  git cherry-pick -x origin/release..origin/topic >/dev/null 2>&1 || :
+)
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --object-format=sha256
+
+git checkout -b main
+touch this
+git add this
+git commit -q -m c1
+echo hello >> this
+git commit -q -am c2
+git tag -m "annotated" v1
+
+git repack -adq
+git multi-pack-index write
+git commit-graph write --reachable --no-progress
+git pack-refs --all
+
+echo hello >> this
+echo hello >> that
+git add . && git commit -q -m "loose"
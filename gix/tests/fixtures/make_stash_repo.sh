@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo '*.ignored' > .gitignore
+echo a > a
+echo b > b
+mkdir dir
+echo c > dir/c
+git add .
+git commit -q -m "initial"
+
+echo "a changed" > a
+git stash push -q -m "first"
+
+echo "b changed" > b
+echo new > new
+git add new
+git stash push -q
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo "*.ignored" > .gitignore
+mkdir dir
+echo a > a
+echo b > dir/b
+echo c > c
+echo same > same
+git add .
+git commit -q -m "initial"
+
+git checkout -q -b other
+echo "a in other" > a
+git rm -q c
+echo new > dir/new
+mkdir new-dir
+echo x > new-dir/x
+chmod +x dir/b
+git add .
+git commit -q -m "other"
+
+git checkout -q main
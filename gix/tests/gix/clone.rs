@@ -317,7 +317,7 @@ mod blocking_io {
             .cached_packed_buffer()?
             .expect("packed refs should be present");
         assert_eq!(
-            repo.refs.loose_iter()?.count(),
+            repo.refs.as_file_store().expect("files backend").loose_iter()?.count(),
             2,
             "HEAD and an actual symbolic ref we received"
         );
@@ -469,7 +469,7 @@ mod blocking_io {
         Ok(())
     }
 
-    fn assert_reflog(log: std::io::Result<Option<gix_ref::file::log::iter::Forward<'_>>>) {
+    fn assert_reflog(log: Result<Option<gix_ref::file::log::iter::Forward<'_>>, gix_ref::store::log::Error>) {
         let lines = log
             .unwrap()
             .expect("log present")
@@ -506,6 +506,45 @@ mod blocking_io {
         assure_index_entries_on_disk(&index, repo.workdir().expect("non-bare"));
         Ok(())
     }
+    #[test]
+    #[cfg(feature = "status")]
+    fn fetch_and_checkout_with_submodules() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            tmp.path().join("with-submodules"),
+            tmp.path().join("clone"),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_recurse_submodules(true);
+        let (mut checkout, _out) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+
+        let sm = repo
+            .submodules()?
+            .expect("modules present")
+            .next()
+            .expect("one submodule");
+        assert_eq!(sm.name(), "m1", "only the committed submodule is known");
+        assert!(
+            repo.git_dir().join("modules").join("m1").is_dir(),
+            "the submodule repository is placed into the superproject"
+        );
+        let sm_repo = sm.open()?.expect("cloned");
+        assert_eq!(
+            sm_repo.head_id()?,
+            sm.index_id()?.expect("recorded"),
+            "the recorded commit is checked out"
+        );
+        assure_index_entries_on_disk(&sm_repo.index()?, sm_repo.workdir().expect("non-bare"));
+        assert!(
+            sm.state()?.superproject_configuration,
+            "submodules are initialized as well"
+        );
+        Ok(())
+    }
+
     #[test]
     fn fetch_and_checkout_specific_ref() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -696,6 +735,125 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn fetch_and_checkout_partial_clone_fetches_missing_blobs_on_demand() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            remote::repo("base.promisor").path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter(Some(gix::remote::fetch::Filter::BlobNone));
+        let (mut checkout, _out) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+
+        let config = repo.config_snapshot();
+        assert_eq!(config.boolean("remote.origin.promisor"), Some(true));
+        assert_eq!(
+            config.string("remote.origin.partialCloneFilter").as_deref(),
+            Some("blob:none".into())
+        );
+        assert_eq!(
+            config.string("extensions.partialClone").as_deref(),
+            Some("origin".into())
+        );
+        assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+
+        let remote = repo.find_remote("origin")?;
+        assert!(remote.is_promisor());
+        assert_eq!(remote.filter(), Some(gix::remote::fetch::Filter::BlobNone));
+
+        let promisor_files = || -> std::io::Result<usize> {
+            Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+                .filter_map(Result::ok)
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "promisor"))
+                .count())
+        };
+        assert_eq!(
+            promisor_files()?,
+            2,
+            "one for the clone, one for the batch of blobs fetched for checkout"
+        );
+
+        let index = repo.index()?;
+        assure_index_entries_on_disk(&index, repo.workdir().expect("non-bare"));
+
+        let first_commit = repo
+            .rev_parse_single("HEAD")?
+            .ancestors()
+            .all()?
+            .last()
+            .expect("at least one commit")?
+            .id;
+        let missing = repo
+            .find_object(first_commit)?
+            .peel_to_tree()?
+            .traverse()
+            .breadthfirst
+            .files()?
+            .into_iter()
+            .filter(|e| e.mode.is_blob() && !repo.has_object(e.oid))
+            .map(|e| e.oid)
+            .next()
+            .expect("the first commit has blobs that aren't checked out");
+        let blob = repo.find_object(missing)?;
+        assert_eq!(
+            blob.kind,
+            gix_object::Kind::Blob,
+            "missing objects are fetched on demand"
+        );
+        assert!(repo.has_object(missing));
+        assert_eq!(promisor_files()?, 3, "each lazy fetch yields its own promisor pack");
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_and_checkout_treeless_partial_clone() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            remote::repo("base.promisor").path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter(Some(gix::remote::fetch::Filter::TreeDepth(0)));
+        let (mut checkout, _out) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+
+        assert_eq!(
+            repo.config_snapshot()
+                .string("remote.origin.partialCloneFilter")
+                .as_deref(),
+            Some("tree:0".into())
+        );
+        let index = repo.index()?;
+        assure_index_entries_on_disk(&index, repo.workdir().expect("non-bare"));
+        Ok(())
+    }
+
+    #[test]
+    fn partial_clone_fails_if_server_does_not_support_filters() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let err = gix::clone::PrepareFetch::new(
+            remote::repo("base").path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter(Some(gix::remote::fetch::Filter::BlobNone))
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("filter"),
+            "the server has to advertise the 'filter' capability: {err}"
+        );
+        Ok(())
+    }
+
     #[test]
     fn fetch_only_without_configuration() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -719,6 +877,140 @@ mod blocking_io {
         }
         Ok(())
     }
+
+    /// Create a copy of the `base` repository with a new loose blob in its `HEAD` commit, and write a pack containing only
+    /// that blob into the repository directory.
+    /// Return the directory of the copy, the blob id, the pack's hash and the path to the pack.
+    fn source_with_blob_pack() -> crate::Result<(
+        gix_testtools::tempfile::TempDir,
+        gix::ObjectId,
+        String,
+        std::path::PathBuf,
+    )> {
+        use std::io::Write;
+
+        let source_tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix_testtools::copy_recursively_into_existing_dir(remote::repo_path("base"), source_tmp.path())?;
+        // Only loose blobs are left out of packs by `git pack-objects`.
+        std::fs::write(source_tmp.path().join("uri-blob"), "downloaded separately")?;
+        for args in [
+            &["add", "uri-blob"][..],
+            &[
+                "-c",
+                "user.name=a",
+                "-c",
+                "user.email=a@b",
+                "commit",
+                "-qm",
+                "add uri-blob",
+            ],
+            // `git upload-pack` only honors `packfile-uris` if `sideband-all` is used as well.
+            &["config", "uploadpack.allowSidebandAll", "true"],
+        ] {
+            assert!(gix_testtools::run_git(source_tmp.path(), args)?.success());
+        }
+        let source = gix::open_opts(source_tmp.path(), gix::open::Options::isolated())?;
+        let blob = source.rev_parse_single("HEAD:uri-blob")?.detach();
+
+        let mut pack_objects = std::process::Command::new(gix::path::env::exe_invocation())
+            .args(["pack-objects", "-q"])
+            .arg(source_tmp.path().join("blob"))
+            .current_dir(source_tmp.path())
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()?;
+        writeln!(pack_objects.stdin.take().expect("piped"), "{blob}")?;
+        let pack_objects = pack_objects.wait_with_output()?;
+        assert!(pack_objects.status.success());
+        let pack_hash = pack_objects.stdout.trim().to_str()?.to_owned();
+        let pack_path = source_tmp.path().join(format!("blob-{pack_hash}.pack"));
+        Ok((source_tmp, blob, pack_hash, pack_path))
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    ))]
+    fn fetch_only_with_packfile_uris() -> crate::Result {
+        let (source_tmp, blob, pack_hash, pack_path) = source_with_blob_pack()?;
+        let (pack_url, http_server) = remote::serve_over_http_once("blob.pack", std::fs::read(pack_path)?)?;
+        assert!(gix_testtools::run_git(
+            source_tmp.path(),
+            &[
+                "config",
+                "uploadpack.blobPackfileUri",
+                &format!("{blob} {pack_hash} {pack_url}")
+            ]
+        )?
+        .success());
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::clone::PrepareFetch::new(
+            source_tmp.path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted().config_overrides(["protocol.version=2", "fetch.uriProtocols=http"]),
+        )?
+        .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        http_server.join().expect("no panic")?;
+        assert!(
+            repo.has_object(blob),
+            "the blob was left out of the pack by the server, and downloaded separately"
+        );
+        assert_eq!(
+            repo.objects.store_ref().path().join("pack").read_dir()?.count(),
+            4,
+            "the pack sent by the server and the one that was downloaded, each with its index"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_with_file_packfile_uris_is_rejected() -> crate::Result {
+        let (source_tmp, blob, pack_hash, pack_path) = source_with_blob_pack()?;
+        let pack_url = format!("file://{}", pack_path.display());
+        assert!(gix_testtools::run_git(
+            source_tmp.path(),
+            &[
+                "config",
+                "uploadpack.blobPackfileUri",
+                &format!("{blob} {pack_hash} {pack_url}")
+            ]
+        )?
+        .success());
+
+        for overrides in [
+            &["protocol.version=2", "fetch.uriProtocols=file"][..],
+            &[
+                "protocol.version=2",
+                "fetch.uriProtocols=file",
+                "protocol.file.allow=user",
+            ],
+        ] {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let err = gix::clone::PrepareFetch::new(
+                source_tmp.path(),
+                tmp.path(),
+                gix::create::Kind::Bare,
+                Default::default(),
+                restricted().config_overrides(overrides.iter().copied()),
+            )?
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())
+            .unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    gix::clone::fetch::Error::Fetch(gix::remote::fetch::Error::PackfileUri(
+                        gix::remote::fetch::download::Error::ForbiddenScheme { .. }
+                    ))
+                ),
+                "file:// URIs sent by the server aren't user-initiated and thus not allowed by default: {err:?}"
+            );
+        }
+        Ok(())
+    }
 }
 
 #[test]
@@ -392,6 +392,35 @@ mod merge {
     }
 }
 
+#[cfg(feature = "note")]
+mod notes {
+    use crate::config::tree::bcow;
+    use gix::config::tree::{Key, Notes};
+    use gix::note::Strategy;
+
+    #[test]
+    fn merge_strategy() -> crate::Result {
+        for (actual, expected) in [
+            ("manual", Strategy::Manual),
+            ("ours", Strategy::Ours),
+            ("theirs", Strategy::Theirs),
+            ("union", Strategy::Union),
+            ("cat_sort_uniq", Strategy::CatSortUniq),
+        ] {
+            assert_eq!(Notes::MERGE_STRATEGY.try_into_strategy(bcow(actual))?, expected);
+            assert!(Notes::MERGE_STRATEGY.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Notes::MERGE_STRATEGY
+                .try_into_strategy(bcow("resolve"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"notes.mergeStrategy=resolve\" was invalid"
+        );
+        Ok(())
+    }
+}
+
 mod core {
     use std::time::Duration;
 
@@ -694,6 +723,10 @@ mod extensions {
             gix_hash::Kind::Sha1,
             "case-insensitive"
         );
+        assert_eq!(
+            Extensions::OBJECT_FORMAT.try_into_object_format(bcow("sha256"))?,
+            gix_hash::Kind::Sha256
+        );
         assert_eq!(
             Extensions::OBJECT_FORMAT
                 .try_into_object_format(bcow("invalid"))
@@ -702,9 +735,34 @@ mod extensions {
             "The key \"extensions.objectFormat=invalid\" was invalid"
         );
         assert!(Extensions::OBJECT_FORMAT.validate("sha1".into()).is_ok());
+        assert!(Extensions::OBJECT_FORMAT.validate("sha256".into()).is_ok());
         assert!(Extensions::OBJECT_FORMAT.validate("invalid".into()).is_err());
         Ok(())
     }
+
+    #[test]
+    fn ref_storage() -> crate::Result {
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_storage(bcow("files"))?,
+            gix::refs::store::Backend::Files
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_storage(bcow("reftable"))?,
+            gix::refs::store::Backend::Reftable
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE
+                .try_into_ref_storage(bcow("Reftable"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"extensions.refStorage=Reftable\" was invalid",
+            "like git, values are case-sensitive"
+        );
+        assert!(Extensions::REF_STORAGE.validate("files".into()).is_ok());
+        assert!(Extensions::REF_STORAGE.validate("reftable".into()).is_ok());
+        assert!(Extensions::REF_STORAGE.validate("invalid".into()).is_err());
+        Ok(())
+    }
 }
 
 mod checkout {
@@ -1096,6 +1154,27 @@ mod remote {
         Ok(())
     }
 
+    #[test]
+    fn partial_clone_filter() -> crate::Result {
+        assert_eq!(
+            Remote::PARTIAL_CLONE_FILTER.try_into_filter(bcow("blob:none"))?,
+            remote::fetch::Filter::BlobNone
+        );
+        assert_eq!(
+            Remote::PARTIAL_CLONE_FILTER.try_into_filter(bcow("tree:0"))?,
+            remote::fetch::Filter::TreeDepth(0)
+        );
+        assert!(Remote::PARTIAL_CLONE_FILTER.validate("blob:limit=1m".into()).is_ok());
+        assert_eq!(
+            Remote::PARTIAL_CLONE_FILTER
+                .try_into_filter(bcow("sparse:oid=HEAD"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"remote.<name>.partialCloneFilter=sparse:oid=HEAD\" was invalid"
+        );
+        Ok(())
+    }
+
     #[test]
     fn url_and_push_url() {
         assert!(Remote::URL.try_into_url(bcow("http://example.org")).is_ok());
@@ -74,6 +74,52 @@ mod non_bare {
         );
         Ok(())
     }
+    #[test]
+    fn init_with_sha256_object_format() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            gix::create::Options {
+                object_hash: gix_hash::Kind::Sha256,
+                ..Default::default()
+            },
+            gix::open::Options::isolated().config_overrides(["user.name=a", "user.email=b"]),
+        )?
+        .into();
+        assert_eq!(repo.object_hash(), gix_hash::Kind::Sha256);
+        let config = std::fs::read_to_string(repo.git_dir().join("config"))?;
+        assert!(config.contains("repositoryformatversion = 1"), "{config}");
+        assert!(config.contains("[extensions]\n\tobjectformat = sha256"), "{config}");
+
+        let blob = repo.write_blob("hello")?;
+        assert_eq!(
+            blob,
+            crate::util::hex_to_id("8aec4e4876f854f688d0ebfc8f37598f38e5fd6903cccc850ca36591175aeb60"),
+            "objects are hashed with SHA256"
+        );
+        assert_eq!(repo.find_blob(blob)?.data, b"hello");
+
+        let tree = repo.empty_tree().id;
+        assert_eq!(tree, gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha256));
+        let commit = repo.commit("HEAD", "initial", tree, gix::commit::NO_PARENT_IDS)?;
+        assert_eq!(commit.kind(), gix_hash::Kind::Sha256);
+        assert_eq!(
+            repo.head_commit()?.tree_id()?,
+            tree,
+            "refs and commits can hold SHA256 ids"
+        );
+
+        let repo = gix::open_opts(repo.git_dir(), gix::open::Options::isolated())?;
+        assert_eq!(
+            repo.object_hash(),
+            gix_hash::Kind::Sha256,
+            "the object format is picked up when opening the repository"
+        );
+        assert_eq!(repo.head_id()?, commit);
+        Ok(())
+    }
+
     #[test]
     fn init_into_empty_directory_creates_a_dot_git_dir() -> crate::Result {
         let tmp = tempfile::tempdir()?;
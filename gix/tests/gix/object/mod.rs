@@ -7,7 +7,7 @@ use gix_testtools::size_ok;
 #[test]
 fn object_ref_size_in_memory() {
     let actual = std::mem::size_of::<gix::Object<'_>>();
-    let expected = 56;
+    let expected = 72;
     assert!(
         size_ok(actual, expected),
         "the size of this structure should not change unexpectedly: {actual} <~ {expected}"
@@ -17,7 +17,7 @@ fn object_ref_size_in_memory() {
 #[test]
 fn oid_size_in_memory() {
     let actual = std::mem::size_of::<gix::Id<'_>>();
-    let expected = 32;
+    let expected = 48;
     assert!(
         size_ok(actual, expected),
         "the size of this structure should not change unexpectedly: {actual} <~ {expected}"
@@ -518,6 +518,78 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_writes_fetch_head_like_git() -> crate::Result {
+        use gix::bstr::ByteSlice;
+
+        let (repo, tmp) = repo_rw("fetch-head");
+        let expected = std::fs::read(tmp.path().join("fetch-head.baseline"))?;
+        assert_eq!(repo.fetch_head()?, None, "the fetch performed by git was undone");
+
+        let remote = repo.find_remote("changes-on-top-of-origin")?;
+        remote
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_dry_run(true)
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(repo.fetch_head()?, None, "dry-runs don't write FETCH_HEAD");
+
+        remote
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        let actual = std::fs::read(repo.git_dir().join("FETCH_HEAD"))?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "we write exactly what git writes");
+
+        let fetch_head = repo.fetch_head()?.expect("just written");
+        let merge_heads: Vec<_> = fetch_head
+            .merge_heads()
+            .map(|entry| entry.parse_description().expect("valid").to_full_name())
+            .collect();
+        assert_eq!(
+            merge_heads,
+            ["refs/heads/main"],
+            "`branch.main.merge` is used as `branch.main.remote` is the remote we fetched from"
+        );
+        assert_eq!(
+            repo.rev_parse_single("FETCH_HEAD")?.detach(),
+            fetch_head.id().expect("not empty"),
+            "FETCH_HEAD resolves to the first entry"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_write_fetch_head_can_be_disabled_by_configuration() -> crate::Result {
+        let (mut repo, _tmp) = repo_rw("fetch-head");
+        repo.config_snapshot_mut()
+            .set_value(&gix::config::tree::Fetch::WRITE_FETCH_HEAD, "false")?;
+
+        let remote = repo.find_remote("changes-on-top-of-origin")?;
+        remote
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(
+            repo.fetch_head()?,
+            None,
+            "`fetch.writeFetchHead=false` prevents writing FETCH_HEAD"
+        );
+
+        remote
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_write_fetch_head(true)
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(
+            repo.fetch_head()?.is_some(),
+            "the builder method overrides the configuration"
+        );
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
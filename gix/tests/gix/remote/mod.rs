@@ -64,8 +64,92 @@ pub(crate) fn into_daemon_remote_if_async<'repo, 'a>(
     }
 }
 
+/// Serve `data` to the first client requesting it via HTTP, as a stand-in for static storage like a CDN, and
+/// return the URL under which it's available.
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+pub(crate) fn serve_over_http_once(
+    name: &str,
+    data: Vec<u8>,
+) -> std::io::Result<(String, std::thread::JoinHandle<std::io::Result<()>>)> {
+    use std::io::{BufRead, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}/{name}", listener.local_addr()?);
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept()?;
+        let mut request = std::io::BufReader::new(&stream);
+        let mut line = String::new();
+        while request.read_line(&mut line)? != 0 && line != "\r\n" {
+            line.clear();
+        }
+        let mut stream = &stream;
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            data.len()
+        )?;
+        stream.write_all(&data)?;
+        stream.flush()
+    });
+    Ok((url, handle))
+}
+
+/// Serve the first client connecting via `git://` on a separate thread, by reading its request for `service` and passing the
+/// connection along with the requested protocol version to `serve()`.
+/// Return the URL to connect to, along with the handle of the serving thread.
+#[cfg(feature = "blocking-network-client")]
+pub(crate) fn serve_over_git_once<T: Send + 'static>(
+    service: &'static str,
+    serve: impl FnOnce(&std::net::TcpStream, gix::protocol::transport::Protocol) -> T + Send + 'static,
+) -> std::io::Result<(String, std::thread::JoinHandle<T>)> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("git://{}/repo.git", listener.local_addr()?);
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("client connects");
+        let protocol = read_daemon_request(&mut stream, service).expect("valid request");
+        serve(&stream, protocol)
+    });
+    Ok((url, handle))
+}
+
+/// Read the initial request line sent to a `git-daemon`, assert it's for `service`, and extract the requested protocol version from it.
+#[cfg(feature = "blocking-network-client")]
+fn read_daemon_request(
+    stream: &mut std::net::TcpStream,
+    service: &str,
+) -> std::io::Result<gix::protocol::transport::Protocol> {
+    use std::io::Read;
+
+    use gix::protocol::transport::Protocol;
+
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = usize::from_str_radix(std::str::from_utf8(&len).expect("hex"), 16).expect("valid hex");
+    let mut request = vec![0; len - 4];
+    stream.read_exact(&mut request)?;
+    assert!(
+        request.starts_with(format!("{service} ").as_bytes()),
+        "we only accept requests for {service}"
+    );
+    Ok(
+        match request
+            .split(|b| *b == 0)
+            .find_map(|parameter| parameter.strip_prefix(b"version="))
+        {
+            Some(b"2") => Protocol::V2,
+            Some(b"1") => Protocol::V1,
+            _ => Protocol::V0,
+        },
+    )
+}
+
 mod connect;
 pub(crate) mod fetch;
+#[cfg(feature = "blocking-network-client")]
+mod push;
 mod ref_map;
 mod save;
 mod name {
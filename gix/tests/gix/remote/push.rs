@@ -0,0 +1,246 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::remote::{
+    push::{
+        update::{Mode, Rejection, Status},
+        Lease, Options, Outcome,
+    },
+    Direction::Push,
+};
+use gix_features::progress;
+use gix_testtools::tempfile::TempDir;
+
+fn repos() -> crate::Result<(gix::Repository, gix::Repository, TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable("make_push_repos.sh")?;
+    let local = gix::open_opts(dir.path().join("local"), crate::restricted())?;
+    let remote = gix::open_opts(dir.path().join("remote.git"), crate::restricted())?;
+    Ok((local, remote, dir))
+}
+
+fn options(specs: &[&str]) -> Options {
+    Options {
+        refspecs: specs
+            .iter()
+            .map(|spec| {
+                gix::refspec::parse((*spec).into(), gix::refspec::parse::Operation::Push)
+                    .expect("valid")
+                    .to_owned()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn push(repo: &gix::Repository, dir: &TempDir, options: Options, dry_run: bool) -> crate::Result<Outcome> {
+    let remote = repo.find_remote("origin")?.push_url(dir.path().join("remote.git"))?;
+    let outcome = remote
+        .connect(Push)?
+        .prepare_push(progress::Discard, options)?
+        .with_dry_run(dry_run)
+        .transmit(progress::Discard, &AtomicBool::default())?;
+    Ok(outcome)
+}
+
+fn id(repo: &gix::Repository, spec: &str) -> gix_hash::ObjectId {
+    repo.rev_parse_single(spec).expect("valid spec").detach()
+}
+
+#[test]
+fn new_branch_tag_and_fast_forward() -> crate::Result {
+    let (local, remote, dir) = repos()?;
+    let outcome = push(
+        &local,
+        &dir,
+        options(&[
+            "main",
+            "refs/heads/feature:refs/heads/feature",
+            "refs/tags/v1:refs/tags/v1",
+        ]),
+        false,
+    )?;
+    let modes: Vec<_> = outcome.updates.iter().map(|u| u.mode).collect();
+    assert_eq!(modes, [Mode::FastForward, Mode::Create, Mode::Create]);
+    assert!(outcome.is_success(), "{outcome:?}");
+    assert!(outcome.updates.iter().all(|u| u.status == Status::Ok));
+
+    for name in ["refs/heads/main", "refs/heads/feature", "refs/tags/v1"] {
+        assert_eq!(id(&remote, name), id(&local, name), "{name} was updated on the remote");
+    }
+    let feature_tree = remote.find_commit(id(&local, "feature"))?.tree()?;
+    assert!(
+        feature_tree.find_entry("file").is_some(),
+        "all objects needed by the remote were sent"
+    );
+    assert_eq!(
+        local.find_reference("refs/remotes/origin/main")?.id(),
+        id(&local, "main"),
+        "remote-tracking branches are updated as well"
+    );
+    assert_eq!(
+        local.find_reference("refs/remotes/origin/feature")?.id(),
+        id(&local, "feature")
+    );
+    Ok(())
+}
+
+#[test]
+fn new_branch_and_tag_with_reachability_bitmap() -> crate::Result {
+    let (local, remote, dir) = repos()?;
+    assert!(gix_testtools::run_git(local.workdir().expect("non-bare"), &["repack", "-adbq"])?.success());
+    let local = gix::open_opts(local.path(), crate::restricted())?;
+    assert!(local.bitmap_index_if_enabled()?.is_some());
+
+    let outcome = push(
+        &local,
+        &dir,
+        options(&["refs/heads/feature:refs/heads/feature", "refs/tags/v1:refs/tags/v1"]),
+        false,
+    )?;
+    assert!(outcome.is_success(), "{outcome:?}");
+    for name in ["refs/heads/feature", "refs/tags/v1"] {
+        assert_eq!(id(&remote, name), id(&local, name), "{name} was created on the remote");
+    }
+    let feature_tree = remote.find_commit(id(&local, "feature"))?.tree()?;
+    assert!(
+        feature_tree.find_entry("file").is_some(),
+        "all objects needed by the remote were found with the bitmap and sent"
+    );
+    Ok(())
+}
+
+#[test]
+fn push_default_pushes_the_current_branch_to_its_upstream() -> crate::Result {
+    let (local, remote, dir) = repos()?;
+    let outcome = push(&local, &dir, Options::default(), false)?;
+    assert_eq!(outcome.updates.len(), 1);
+    assert_eq!(outcome.updates[0].remote_ref.as_bstr(), "refs/heads/main");
+    assert_eq!(id(&remote, "main"), id(&local, "main"));
+    Ok(())
+}
+
+#[test]
+fn non_fast_forward_is_rejected_unless_forced() -> crate::Result {
+    let (local, remote, dir) = repos()?;
+    let before = id(&remote, "main");
+    let outcome = push(&local, &dir, options(&["rewritten:main"]), false)?;
+    assert!(!outcome.is_success());
+    assert_eq!(outcome.updates[0].mode, Mode::Rejected(Rejection::NonFastForward));
+    assert_eq!(id(&remote, "main"), before, "nothing was sent");
+
+    let outcome = push(&local, &dir, options(&["+rewritten:main"]), false)?;
+    assert!(outcome.is_success());
+    assert_eq!(outcome.updates[0].mode, Mode::Forced);
+    assert_eq!(id(&remote, "main"), id(&local, "rewritten"));
+    Ok(())
+}
+
+#[test]
+fn leases_allow_forced_updates_only_if_the_remote_is_as_expected() -> crate::Result {
+    let (local, remote, dir) = repos()?;
+    let lease = |expected| Lease {
+        remote_ref: "refs/heads/main".try_into().expect("valid"),
+        expected,
+    };
+    let mut opts = options(&["rewritten:main"]);
+    opts.leases = vec![lease(Some(id(&local, "rewritten")))];
+    let outcome = push(&local, &dir, opts, true)?;
+    assert_eq!(outcome.updates[0].mode, Mode::Rejected(Rejection::StaleLease));
+
+    let mut opts = options(&["rewritten:main"]);
+    opts.leases = vec![lease(None)];
+    let outcome = push(&local, &dir, opts, false)?;
+    assert_eq!(
+        outcome.updates[0].mode,
+        Mode::Forced,
+        "the remote-tracking branch matches the remote"
+    );
+    assert_eq!(id(&remote, "main"), id(&local, "rewritten"));
+    Ok(())
+}
+
+#[test]
+fn deletion() -> crate::Result {
+    let (local, remote, dir) = repos()?;
+    let outcome = push(&local, &dir, options(&[":refs/heads/other", ":does-not-exist"]), false)?;
+    assert_eq!(outcome.updates[0].mode, Mode::Delete);
+    assert_eq!(outcome.updates[0].status, Status::Ok);
+    assert_eq!(outcome.updates[1].mode, Mode::Rejected(Rejection::DeleteNonExisting));
+    assert!(remote.try_find_reference("refs/heads/other")?.is_none());
+    assert!(
+        local.try_find_reference("refs/remotes/origin/other")?.is_none(),
+        "the remote-tracking branch is removed as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn dry_run_and_atomic() -> crate::Result {
+    let (local, remote, dir) = repos()?;
+    let before = id(&remote, "main");
+    let outcome = push(&local, &dir, options(&["main"]), true)?;
+    assert_eq!(outcome.updates[0].status, Status::Ok);
+    assert_eq!(id(&remote, "main"), before, "dry-runs don't change the remote");
+
+    let mut opts = options(&["main", "rewritten:other"]);
+    opts.atomic = true;
+    let outcome = push(&local, &dir, opts, false)?;
+    assert_eq!(
+        outcome.updates[0].mode,
+        Mode::Rejected(Rejection::AtomicPushFailed),
+        "one rejection causes all updates to be rejected"
+    );
+    assert_eq!(id(&remote, "main"), before);
+    Ok(())
+}
+
+#[test]
+fn updates_missing_from_the_status_report_are_rejected() -> crate::Result {
+    use std::io::{Read, Write};
+
+    let (local, _remote, _dir) = repos()?;
+    let main = id(&local, "main");
+    let (url, server) = crate::remote::serve_over_git_once("git-receive-pack", move |mut stream, _protocol| {
+        let mut write_line = |line: &str| write!(stream, "{:04x}{line}", line.len() + 4);
+        write_line(&format!("{main} refs/heads/a\0report-status delete-refs\n"))?;
+        write_line(&format!("{main} refs/heads/b\n"))?;
+        stream.write_all(b"0000")?;
+
+        let mut commands = Vec::new();
+        loop {
+            let mut len = [0u8; 4];
+            stream.read_exact(&mut len)?;
+            let len = usize::from_str_radix(std::str::from_utf8(&len).expect("hex"), 16).expect("valid hex");
+            if len == 0 {
+                break;
+            }
+            let mut line = vec![0; len - 4];
+            stream.read_exact(&mut line)?;
+            commands.push(line);
+        }
+
+        // A report that only mentions the first update, as if it was cut short.
+        let mut write_line = |line: &str| write!(stream, "{:04x}{line}", line.len() + 4);
+        write_line("unpack ok\n")?;
+        write_line("ok refs/heads/a\n")?;
+        stream.write_all(b"0000")?;
+        std::io::Result::Ok(commands.len())
+    })?;
+
+    let remote = local.find_remote("origin")?.push_url(url.as_str())?;
+    let outcome = remote
+        .connect(Push)?
+        .prepare_push(progress::Discard, options(&[":refs/heads/a", ":refs/heads/b"]))?
+        .transmit(progress::Discard, &AtomicBool::default())?;
+    assert_eq!(server.join().expect("no panic")?, 2, "both deletions were sent");
+
+    assert_eq!(outcome.updates[0].status, Status::Ok);
+    assert_eq!(
+        outcome.updates[1].status,
+        Status::RemoteRejected {
+            reason: "remote failed to report status".into()
+        },
+        "updates the remote didn't report on can't be assumed to be applied"
+    );
+    assert!(!outcome.is_success());
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::{bundle::Version, remote::Direction, repository::create_bundle::Options};
+use gix_testtools::tempfile::TempDir;
+
+use crate::{remote, util::restricted};
+
+fn create(repo: &gix::Repository, path: &Path, specs: &[&str], options: Options) -> crate::Result<gix::bundle::File> {
+    let header = repo.create_bundle(
+        specs.iter().copied(),
+        std::fs::File::create(path)?,
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        options,
+    )?;
+    let bundle = gix::bundle::File::at(path)?;
+    assert_eq!(bundle.header, header, "the returned header is what was written");
+    Ok(bundle)
+}
+
+fn empty_repo(tmp: &TempDir) -> crate::Result<gix::Repository> {
+    Ok(gix::ThreadSafeRepository::init_opts(
+        tmp.path().join("empty.git"),
+        gix::create::Kind::Bare,
+        Default::default(),
+        restricted(),
+    )?
+    .to_thread_local())
+}
+
+fn fetch_all_branches(
+    repo: &gix::Repository,
+    bundle: &Path,
+) -> Result<gix::remote::fetch::Outcome, gix::remote::fetch::Error> {
+    repo.remote_at(bundle)
+        .expect("valid url")
+        .with_refspecs(Some("+refs/heads/*:refs/heads/*"), Direction::Fetch)
+        .expect("valid refspec")
+        .connect(Direction::Fetch)
+        .expect("bundles can be opened")
+        .prepare_fetch(gix::progress::Discard, Default::default())
+        .expect("refs can be listed")
+        .receive(gix::progress::Discard, &AtomicBool::default())
+}
+
+#[test]
+fn create_and_unbundle_with_complete_history() -> crate::Result {
+    let source = remote::repo("base");
+    let tmp = TempDir::new()?;
+    let path = tmp.path().join("main.bundle");
+    let bundle = create(&source, &path, &["main", "b-tag", "HEAD~1"], Options::default())?;
+
+    assert_eq!(bundle.header.version, Version::V2);
+    assert!(bundle.header.prerequisites.is_empty(), "everything is included");
+    assert_eq!(
+        bundle
+            .header
+            .refs
+            .iter()
+            .map(|r| (r.name.to_string(), r.id))
+            .collect::<Vec<_>>(),
+        [
+            ("refs/heads/main".into(), source.rev_parse_single("main")?.detach()),
+            ("refs/tags/b-tag".into(), source.rev_parse_single("b-tag")?.detach()),
+        ],
+        "only revisions that are references are recorded, and annotated tags aren't peeled"
+    );
+
+    let out = std::process::Command::new(gix::path::env::exe_invocation())
+        .args(["bundle", "verify", "-q"])
+        .arg(&path)
+        .current_dir(source.git_dir())
+        .output()?;
+    assert!(out.status.success(), "git accepts the bundle: {out:?}");
+
+    let repo = empty_repo(&tmp)?;
+    assert!(repo.missing_bundle_prerequisites(&bundle.header).is_empty());
+    let outcome = repo.unbundle(&bundle, &mut gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        outcome.index.num_objects, 31,
+        "all commits, trees, blobs and the annotated tag"
+    );
+    for r in &bundle.header.refs {
+        assert!(repo.has_object(r.id), "{} was unbundled", r.name);
+    }
+    assert_eq!(
+        repo.references()?.all()?.count(),
+        0,
+        "references are left to the caller"
+    );
+    Ok(())
+}
+
+#[test]
+fn create_with_prerequisites_and_unbundle() -> crate::Result {
+    let source = remote::repo("base");
+    let tmp = TempDir::new()?;
+    let path = tmp.path().join("incremental.bundle");
+    let bundle = create(
+        &source,
+        &path,
+        &["d..main"],
+        Options {
+            version: Version::V3,
+            all: false,
+        },
+    )?;
+
+    assert_eq!(bundle.header.version, Version::V3);
+    assert_eq!(bundle.header.refs.len(), 1);
+    assert_eq!(
+        bundle.header.refs[0].name, "refs/heads/main",
+        "the tip of the range is recorded"
+    );
+    assert_eq!(bundle.header.prerequisites.len(), 1);
+    let prerequisite = &bundle.header.prerequisites[0];
+    assert_eq!(prerequisite.id, source.rev_parse_single("d")?.detach());
+    assert_eq!(prerequisite.comment, "D", "the commit summary is the comment");
+
+    let repo = empty_repo(&tmp)?;
+    assert_eq!(repo.missing_bundle_prerequisites(&bundle.header).len(), 1);
+    let err = repo
+        .unbundle(&bundle, &mut gix::progress::Discard, &AtomicBool::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::repository::unbundle::Error::MissingPrerequisites { ref ids } if ids == &[prerequisite.id]),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn create_with_all_references() -> crate::Result {
+    let source = remote::repo("base");
+    let tmp = TempDir::new()?;
+    let bundle = create(
+        &source,
+        &tmp.path().join("all.bundle"),
+        &[],
+        Options {
+            all: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        bundle.header.refs.len(),
+        source.references()?.all()?.count() + 1,
+        "all references and HEAD"
+    );
+    assert_eq!(bundle.header.refs.last().expect("present").name, "HEAD");
+
+    let err = source
+        .create_bundle(
+            ["main~1"],
+            Vec::new(),
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            Options::default(),
+        )
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::repository::create_bundle::Error::NoReferences),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn clone_and_fetch_from_bundles() -> crate::Result {
+    let source = remote::repo("base");
+    let tmp = TempDir::new()?;
+    let full = tmp.path().join("all.bundle");
+    create(
+        &source,
+        &full,
+        &[],
+        Options {
+            all: true,
+            ..Default::default()
+        },
+    )?;
+
+    let (repo, _outcome) = gix::clone::PrepareFetch::new(
+        full.as_path(),
+        tmp.path().join("clone"),
+        gix::create::Kind::Bare,
+        Default::default(),
+        restricted(),
+    )?
+    .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/main",
+        "HEAD is set up like in the bundled repository"
+    );
+    assert_eq!(repo.head_id()?, source.head_id()?);
+    assert_eq!(
+        repo.find_reference("refs/tags/b-tag")?.id(),
+        source.find_reference("refs/tags/b-tag")?.id()
+    );
+
+    let incremental = tmp.path().join("incremental.bundle");
+    create(&source, &incremental, &["d..main"], Options::default())?;
+    let repo = empty_repo(&tmp)?;
+    let err = fetch_all_branches(&repo, &incremental).unwrap_err();
+    assert!(
+        matches!(err, gix::remote::fetch::Error::MissingBundlePrerequisites { .. }),
+        "{err:?}"
+    );
+
+    create(&source, &tmp.path().join("d.bundle"), &["d"], Options::default())?;
+    fetch_all_branches(&repo, &tmp.path().join("d.bundle"))?;
+    assert_eq!(repo.find_reference("refs/heads/d")?.id(), source.rev_parse_single("d")?);
+
+    fetch_all_branches(&repo, &incremental)?;
+    assert_eq!(
+        repo.find_reference("refs/heads/main")?.id(),
+        source.rev_parse_single("main")?,
+        "with the prerequisite present, the bundle can be fetched from"
+    );
+    Ok(())
+}
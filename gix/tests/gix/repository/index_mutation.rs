@@ -0,0 +1,268 @@
+use crate::util::{blob, bstrings, exists, repo_rw, write};
+
+fn paths(index: &gix::index::State) -> Vec<String> {
+    index
+        .entries()
+        .iter()
+        .map(|entry| entry.path(index).to_string())
+        .collect()
+}
+
+mod add {
+    use gix::repository::add_to_index;
+
+    use super::*;
+
+    #[test]
+    fn all_changes() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_index_mutation_repo.sh")?;
+        write(&repo, "a", "a modified\n")?;
+        std::fs::remove_file(repo.workdir().expect("non-bare").join("dir/b"))?;
+        write(&repo, "new/file", "new\n")?;
+        write(&repo, "file.crlf", "crlf\r\n")?;
+        write(&repo, "file.ignored", "ignored\n")?;
+
+        let mut index = repo.open_index()?;
+        let out = repo.add_to_index(&mut index, None::<&str>, Default::default())?;
+        assert_eq!(
+            out,
+            add_to_index::Outcome {
+                added: bstrings(&["file.crlf", "new/file"]),
+                modified: bstrings(&["a"]),
+                removed: bstrings(&["dir/b"]),
+            }
+        );
+        assert_eq!(
+            paths(&index),
+            [
+                ".gitattributes",
+                ".gitignore",
+                "a",
+                "dir/sub/c",
+                "file.crlf",
+                "new/file",
+                "other/d"
+            ],
+            "entries are sorted, and ignored files aren't added"
+        );
+        index.verify_entries()?;
+        assert_eq!(blob(&repo, &index, "a")?, b"a modified\n");
+        assert_eq!(
+            blob(&repo, &index, "file.crlf")?,
+            b"crlf\n",
+            "clean filters are applied"
+        );
+
+        let out = repo.add_to_index(&mut index, None::<&str>, Default::default())?;
+        assert!(out.is_empty(), "the index matches the worktree now");
+        Ok(())
+    }
+
+    #[test]
+    fn with_pathspec_and_options() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_index_mutation_repo.sh")?;
+        write(&repo, "a", "a modified\n")?;
+        write(&repo, "dir/b", "b modified\n")?;
+        write(&repo, "dir/new", "new\n")?;
+        write(&repo, "dir/file.ignored", "ignored\n")?;
+
+        let mut index = repo.open_index()?;
+        let out = repo.add_to_index(
+            &mut index,
+            Some("dir"),
+            add_to_index::Options {
+                tracked_only: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            out.modified,
+            bstrings(&["dir/b"]),
+            "only tracked files within the pathspec"
+        );
+        assert!(out.added.is_empty());
+        assert_eq!(
+            blob(&repo, &index, "a")?,
+            b"a\n",
+            "files outside of the pathspec are unchanged"
+        );
+
+        let out = repo.add_to_index(
+            &mut index,
+            Some("dir"),
+            add_to_index::Options {
+                include_ignored: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(out.added, bstrings(&["dir/file.ignored", "dir/new"]));
+        assert!(out.modified.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn bare_repositories_are_rejected() -> crate::Result {
+        let repo = crate::named_subrepo_opts("make_basic_repo.sh", "bare.git", gix::open::Options::isolated())?;
+        let mut index = gix::index::File::clone(&*repo.index_or_empty()?);
+        assert!(matches!(
+            repo.add_to_index(&mut index, None::<&str>, Default::default()),
+            Err(add_to_index::Error::BareRepository)
+        ));
+        Ok(())
+    }
+}
+
+mod remove {
+    use gix::repository::remove_from_index;
+
+    use super::*;
+
+    #[test]
+    fn directory_and_files() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_index_mutation_repo.sh")?;
+        let mut index = repo.open_index()?;
+        assert!(repo
+            .remove_from_index(&mut index, None::<&str>, Default::default())?
+            .is_empty());
+
+        let removed = repo.remove_from_index(&mut index, ["dir", "a"], Default::default())?;
+        assert_eq!(removed, bstrings(&["a", "dir/b", "dir/sub/c"]));
+        assert_eq!(paths(&index), [".gitattributes", ".gitignore", "other/d"]);
+        assert!(!exists(&repo, "a"));
+        assert!(!exists(&repo, "dir"), "empty directories are removed as well");
+
+        let removed = repo.remove_from_index(
+            &mut index,
+            Some("other"),
+            remove_from_index::Options {
+                cached: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(removed, bstrings(&["other/d"]));
+        assert!(exists(&repo, "other/d"), "files are kept in cached mode");
+        Ok(())
+    }
+
+    #[test]
+    fn changes_are_protected() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_index_mutation_repo.sh")?;
+        let mut index = repo.open_index()?;
+        write(&repo, "a", "a modified\n")?;
+        assert!(matches!(
+            repo.remove_from_index(&mut index, Some("a"), Default::default()),
+            Err(remove_from_index::Error::LocalChanges { path }) if path == "a"
+        ));
+        let cached = remove_from_index::Options {
+            cached: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            repo.remove_from_index(&mut index.clone(), Some("a"), cached)?,
+            bstrings(&["a"]),
+            "the file remains, so the change isn't lost"
+        );
+
+        repo.add_to_index(&mut index, Some("a"), Default::default())?;
+        assert!(matches!(
+            repo.remove_from_index(&mut index, Some("a"), Default::default()),
+            Err(remove_from_index::Error::StagedChanges { path }) if path == "a"
+        ));
+        write(&repo, "a", "a modified again\n")?;
+        assert!(matches!(
+            repo.remove_from_index(&mut index, Some("a"), cached),
+            Err(remove_from_index::Error::StagedAndLocalChanges { path }) if path == "a"
+        ));
+
+        let removed = repo.remove_from_index(
+            &mut index,
+            Some("a"),
+            remove_from_index::Options {
+                force: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(removed, bstrings(&["a"]));
+        assert!(!exists(&repo, "a"));
+        Ok(())
+    }
+}
+
+mod write_tree {
+    use super::*;
+
+    #[test]
+    fn matches_head_and_reuses_the_tree_cache() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_index_mutation_repo.sh")?;
+        let head_tree = repo.head_tree_id()?;
+        let mut index = repo.open_index()?;
+        let cache = index.tree().expect("written by git").clone();
+        assert_eq!(repo.write_tree(&mut index)?, head_tree, "everything is reused");
+        assert_eq!(index.tree(), Some(&cache), "the cache is still valid");
+
+        let mut uncached = index.clone();
+        uncached.remove_tree();
+        assert_eq!(repo.write_tree(&mut uncached)?, head_tree);
+        assert_eq!(
+            uncached.tree(),
+            Some(&cache),
+            "the cache is recreated just like git does it"
+        );
+
+        write(&repo, "dir/sub/c", "c modified\n")?;
+        write(&repo, "dir/sub/new", "new\n")?;
+        repo.add_to_index(&mut index, Some("dir"), Default::default())?;
+        let tree = index.tree().expect("still present");
+        assert_eq!(tree.num_entries, None, "changed trees are invalidated");
+        assert!(tree
+            .children
+            .iter()
+            .any(|child| child.name.as_slice() == b"other" && child.num_entries.is_some()));
+
+        let id = repo.write_tree(&mut index)?;
+        let tree = index.tree().expect("still present");
+        assert_eq!(tree.id, id);
+        assert_eq!(tree.num_entries, Some(7));
+        tree.verify(true, &repo.objects)?;
+        let mut tree = id.object()?.into_tree();
+        assert_eq!(
+            tree.peel_to_entry_by_path("dir/sub/new")?
+                .expect("present")
+                .object()?
+                .data,
+            b"new\n"
+        );
+        assert_eq!(
+            id.object()?
+                .into_tree()
+                .find_entry("other")
+                .expect("present")
+                .object_id(),
+            repo.head_tree()?.find_entry("other").expect("present").object_id(),
+            "unchanged trees stay the same"
+        );
+
+        let commit = repo.commit("HEAD", "add new file", id, [repo.head_id()?])?;
+        index.write(Default::default())?;
+        assert!(!repo.is_dirty()?, "the commit represents the worktree");
+        assert_eq!(commit.object()?.into_commit().tree_id()?, id);
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts_are_rejected() -> crate::Result {
+        let repo = crate::named_repo("make_basic_repo.sh")?;
+        let mut index = repo.index_from_tree(&repo.head_tree_id()?)?;
+        let entry = index.entries()[0].clone();
+        let path = entry.path(&index).to_owned();
+        let mut flags = entry.flags;
+        flags.insert(gix::index::entry::Flags::from_stage(gix::index::entry::Stage::Ours));
+        index.dangerously_push_entry(entry.stat, entry.id, flags, entry.mode, path.as_ref());
+        index.sort_entries();
+        assert!(matches!(
+            repo.write_tree(&mut index),
+            Err(gix::index::write_tree::Error::Unmerged { path: actual }) if actual == path
+        ));
+        Ok(())
+    }
+}
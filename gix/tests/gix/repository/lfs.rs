@@ -0,0 +1,118 @@
+use std::{io::Read, path::Path};
+
+use gix::bstr::ByteSlice;
+use gix_filter::driver::apply::Delay;
+
+use crate::util::{named_repo, repo_rw};
+
+const POINTER: &str = "version https://git-lfs.github.com/spec/v1
+oid sha256:ca27f3a1fa46b52b9b770c0f09ec44d48b0f6a1e6b0c8955b8ac1976b6d6c3cb
+size 14
+";
+const CONTENT: &str = "large content\n";
+
+#[test]
+fn store_and_endpoint() -> crate::Result {
+    let mut repo = named_repo("make_lfs_repo.sh")?;
+    assert_eq!(repo.lfs_store().path(), repo.git_dir().join("lfs").join("objects"));
+    assert_eq!(
+        repo.lfs_endpoint()?.as_deref(),
+        Some("https://example.com/org/repo.git/info/lfs"),
+        "derived from the URL of the default remote"
+    );
+
+    repo.config_snapshot_mut().set_raw_value_by(
+        "remote",
+        Some("origin".into()),
+        "lfsurl",
+        "https://lfs.example.com/remote",
+    )?;
+    assert_eq!(repo.lfs_endpoint()?.as_deref(), Some("https://lfs.example.com/remote"));
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value(&"lfs.url", "https://lfs.example.com/global")?;
+    config.set_raw_value(&"lfs.storage", "elsewhere")?;
+    let repo = config.commit()?;
+    assert_eq!(repo.lfs_endpoint()?.as_deref(), Some("https://lfs.example.com/global"));
+    assert_eq!(
+        repo.lfs_store().path(),
+        repo.git_dir().join("elsewhere").join("objects"),
+        "relative storage paths are relative to the git directory"
+    );
+    Ok(())
+}
+
+#[test]
+fn pipeline_smudges_and_cleans_without_running_git_lfs() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_lfs_repo.sh")?;
+    let (mut pipe, index) = repo.filter_pipeline(None)?;
+
+    let mut out = pipe.convert_to_worktree(POINTER.as_bytes(), "large.bin".into(), Delay::Forbid)?;
+    let mut buf = Vec::new();
+    out.read_to_end(&mut buf)?;
+    assert_eq!(
+        buf.as_bstr(),
+        CONTENT,
+        "the pointer is replaced with the object from the store"
+    );
+    drop(out);
+
+    let mut out = pipe.convert_to_git("new content\n".as_bytes(), Path::new("new.bin"), &index)?;
+    buf.clear();
+    out.read_to_end(&mut buf)?;
+    let pointer = gix::lfs::plumbing::Pointer::from_bytes(&buf)?;
+    assert_eq!(pointer.size, 12, "the content is turned into a pointer");
+    assert!(repo.lfs_store().contains(&pointer), "…and its object is stored");
+    drop(out);
+
+    let missing = POINTER.replace("ca27", "ffff");
+    assert!(
+        pipe.convert_to_worktree(missing.as_bytes(), "missing.bin".into(), Delay::Forbid)
+            .is_err(),
+        "objects that are missing and can't be downloaded are an error"
+    );
+
+    let mut out = pipe.convert_to_worktree(b"not a pointer", "other.bin".into(), Delay::Forbid)?;
+    buf.clear();
+    out.read_to_end(&mut buf)?;
+    assert_eq!(
+        buf.as_bstr(),
+        "not a pointer",
+        "files that aren't pointers are left alone"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+fn checkout_materialises_content() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_lfs_repo.sh")?;
+    repo.reset("with-lfs", gix::repository::reset::Mode::Hard)?;
+    assert_eq!(
+        std::fs::read_to_string(repo.workdir().expect("non-bare").join("large.bin"))?,
+        CONTENT
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "worktree-stream")]
+fn archive_materialises_content() -> crate::Result {
+    let repo = named_repo("make_lfs_repo.sh")?;
+    let tree = repo
+        .rev_parse_single("with-lfs")?
+        .object()?
+        .peel_to_commit()?
+        .tree_id()?;
+    let (mut stream, _index) = repo.worktree_stream(tree)?;
+    let mut buf = Vec::new();
+    while let Some(mut entry) = stream.next_entry()? {
+        if entry.relative_path() == "large.bin" {
+            entry.read_to_end(&mut buf)?;
+        } else {
+            std::io::copy(&mut entry, &mut std::io::sink())?;
+        }
+    }
+    assert_eq!(buf.as_bstr(), CONTENT);
+    Ok(())
+}
@@ -1,23 +1,49 @@
 use gix::Repository;
 
+#[cfg(all(feature = "bundle", feature = "blocking-network-client"))]
+mod bundle;
 mod config;
 #[cfg(feature = "excludes")]
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "status")]
+mod index_mutation;
+#[cfg(feature = "lfs")]
+mod lfs;
 #[cfg(feature = "merge")]
 mod merge;
+#[cfg(feature = "note")]
+mod note;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "rebase")]
+mod rebase;
+#[cfg(all(feature = "receive-pack", feature = "blocking-network-client"))]
+mod receive_pack;
 mod reference;
 mod remote;
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+mod reset;
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+mod restore;
+#[cfg(feature = "sequencer")]
+mod sequencer;
+mod sha256;
 mod shallow;
 mod state;
+#[cfg(feature = "stash")]
+mod stash;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+mod switch;
+#[cfg(all(feature = "upload-pack", feature = "blocking-network-client"))]
+mod upload_pack;
 mod worktree;
+mod write_commit_graph;
 
 #[cfg(feature = "revision")]
 mod revision {
@@ -0,0 +1,165 @@
+use gix::bstr::ByteSlice;
+use gix::config::tree::Core;
+use gix::note::{merge::Outcome, Error, Strategy};
+
+fn repo_rw() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable("make_notes_repos.sh")?;
+    let repo = gix::open_opts(tmp.path().join("repo"), crate::util::restricted())?;
+    Ok((repo, tmp))
+}
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+fn note(notes: &gix::note::Platform<'_>, object: gix::ObjectId) -> crate::Result<Option<String>> {
+    Ok(notes.find(object)?.map(|blob| blob.data.to_str_lossy().into_owned()))
+}
+
+fn tree_of(repo: &gix::Repository, name: &str) -> crate::Result<gix::ObjectId> {
+    id(repo, &format!("refs/notes/{name}^{{tree}}"))
+}
+
+#[test]
+fn find_notes_written_by_git() -> crate::Result {
+    let repo = crate::util::named_subrepo_opts("make_notes_repos.sh", "repo", crate::util::restricted())?;
+    let notes = repo.notes()?;
+    assert_eq!(notes.name().as_bstr(), "refs/notes/commits");
+    assert_eq!(note(&notes, id(&repo, "c1")?)?.as_deref(), Some("first\n\nsecond\n"));
+    assert_eq!(note(&notes, id(&repo, "c2")?)?, None);
+    assert_eq!(note(&notes, id(&repo, "c3")?)?.as_deref(), Some("copied\n"));
+    assert_eq!(notes.list()?.by_object.len(), 2);
+
+    let ci = repo.notes_at("ci".into())?;
+    assert_eq!(ci.name().as_bstr(), "refs/notes/ci");
+    assert_eq!(note(&ci, id(&repo, "c3")?)?.as_deref(), Some("passed\n"));
+
+    let missing = repo.notes_at("notes/missing".into())?;
+    assert_eq!(missing.name().as_bstr(), "refs/notes/missing");
+    assert_eq!(missing.commit_id()?, None);
+    assert_eq!(note(&missing, id(&repo, "c1")?)?, None);
+    assert!(missing.list()?.by_object.is_empty());
+    Ok(())
+}
+
+#[test]
+fn notes_ref_is_configurable() -> crate::Result {
+    let mut repo = crate::util::named_subrepo_opts("make_notes_repos.sh", "repo", crate::util::restricted())?;
+    repo.config_snapshot_mut()
+        .set_value(&Core::NOTES_REF, "refs/notes/ci")?;
+    assert_eq!(repo.notes()?.name().as_bstr(), "refs/notes/ci");
+    Ok(())
+}
+
+#[test]
+fn edits_produce_the_same_notes_as_git() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let (c1, c2, c3) = (id(&repo, "c1")?, id(&repo, "c2")?, id(&repo, "c3")?);
+    let notes = repo.notes_at("by-gix".into())?;
+    notes.add(c1, "first".into(), false)?;
+    assert!(matches!(
+        notes.add(c1, "again".into(), false),
+        Err(Error::NoteExists { object }) if object == c1
+    ));
+    notes.append(c1, "second".into())?;
+    notes.add(c2, "copied".into(), false)?;
+    notes.copy(c2, c3, false)?;
+    assert!(matches!(notes.copy(c2, c3, false), Err(Error::NoteExists { .. })));
+    assert!(notes.remove(c2)?);
+    assert!(!notes.remove(c2)?, "nothing to remove");
+    assert!(matches!(
+        notes.copy(c2, c1, true),
+        Err(Error::NoteMissing { object }) if object == c2
+    ));
+
+    assert_eq!(tree_of(&repo, "by-gix")?, tree_of(&repo, "commits")?);
+    let message = |name: &str| -> crate::Result<String> {
+        Ok(repo
+            .find_commit(id(&repo, &format!("refs/notes/{name}"))?)?
+            .message_raw()?
+            .to_string())
+    };
+    assert_eq!(message("by-gix")?, message("commits")?);
+    assert_eq!(
+        repo.find_commit(notes.commit_id()?.expect("present"))?
+            .ancestors()
+            .all()?
+            .count(),
+        5,
+        "one commit per successful edit"
+    );
+
+    assert_eq!(notes.add(c1, "".into(), true)?, None, "empty messages remove notes");
+    assert_eq!(note(&notes, c1)?, None);
+    Ok(())
+}
+
+#[test]
+fn many_notes_are_fanned_out() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let notes = repo.notes_at("many".into())?;
+    let mut objects = Vec::new();
+    for i in 0..150 {
+        let object = repo.write_blob(format!("{i}"))?.detach();
+        notes.add(object, format!("note {i}").as_str().into(), false)?;
+        objects.push(object);
+    }
+    for (i, object) in objects.into_iter().enumerate() {
+        assert_eq!(note(&notes, object)?, Some(format!("note {i}\n")));
+    }
+    let tree = repo.find_tree(tree_of(&repo, "many")?)?;
+    assert!(
+        tree.decode()?.entries.iter().all(|entry| entry.mode.is_tree()),
+        "all notes are in subtrees"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let ci = id(&repo, "refs/notes/ci")?;
+    assert_eq!(
+        repo.notes_at("ci".into())?.merge("ci-behind".into(), None)?,
+        Outcome::UpToDate
+    );
+    assert_eq!(
+        repo.notes_at("ci-behind".into())?.merge("ci".into(), None)?,
+        Outcome::FastForward { commit: ci }
+    );
+    assert_eq!(id(&repo, "refs/notes/ci-behind")?, ci);
+    assert_eq!(
+        repo.notes_at("new".into())?.merge("ci".into(), None)?,
+        Outcome::FastForward { commit: ci },
+        "missing notes are created"
+    );
+
+    let local = repo.notes_at("ci-local".into())?;
+    let before = local.commit_id()?;
+    let Outcome::Conflicts { conflicts } = local.merge("ci".into(), Some(Strategy::Manual))? else {
+        panic!("both sides changed the note of c1")
+    };
+    assert_eq!(
+        conflicts.into_iter().map(|c| c.object).collect::<Vec<_>>(),
+        [id(&repo, "c1")?]
+    );
+    assert_eq!(local.commit_id()?, before, "nothing changes on conflict");
+
+    let Outcome::Merged { commit } = local.merge("ci".into(), None)? else {
+        panic!("the configured union strategy resolves the conflict")
+    };
+    assert_eq!(
+        repo.find_commit(commit)?
+            .parent_ids()
+            .map(gix::Id::detach)
+            .collect::<Vec<_>>(),
+        [before.expect("present"), ci]
+    );
+    assert_eq!(note(&local, id(&repo, "c1")?)?.as_deref(), Some("local\n\nbuild 2\n"));
+    assert_eq!(tree_of(&repo, "ci-local")?, tree_of(&repo, "ci-union")?);
+    assert!(matches!(
+        local.merge("missing".into(), None),
+        Err(Error::NotFound { .. })
+    ));
+    Ok(())
+}
@@ -69,15 +69,65 @@ fn non_bare_reftable() -> crate::Result {
         }
         Err(err) => panic!("{err}"),
     };
-    assert!(
-        repo.head_id().is_err(),
-        "Trying to do anything with head will fail as we don't support reftables yet"
-    );
     assert!(!repo.is_bare());
-    assert_ne!(
-        repo.workdir(),
-        None,
-        "Otherwise it can be used, but it's hard to do without refs"
+    assert_eq!(
+        repo.refs.backend(),
+        gix::refs::store::Backend::Reftable,
+        "the backend is chosen by `extensions.refStorage`"
+    );
+
+    let head_id = repo.head_id()?;
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/main",
+        "HEAD is read from the tables, not from the placeholder file"
+    );
+    assert_eq!(repo.head_commit()?.message()?.summary().as_ref(), "c2");
+    assert_eq!(repo.find_reference("origin/main")?.id(), head_id);
+    assert_eq!(
+        repo.find_reference("refs/remotes/origin/HEAD")?
+            .into_fully_peeled_id()?,
+        head_id,
+        "symbolic references are followed within the tables"
+    );
+    assert!(repo.try_find_reference("does-not-exist")?.is_none());
+
+    let names = repo
+        .references()?
+        .all()?
+        .map(|r| r.map(|r| r.name().as_bstr().to_owned()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        names,
+        [
+            "refs/heads/main",
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/main"
+        ]
+    );
+
+    let mut log = repo.head()?.log_iter();
+    let line = log.rev()?.expect("reflog exists").next().expect("one entry")?;
+    assert_eq!(line.new_oid, head_id);
+    assert!(line.message.starts_with(b"clone: from "), "{:?}", line.message);
+    Ok(())
+}
+
+#[test]
+fn unknown_ref_storage_is_rejected() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let repo = gix::init_bare(tmp.path())?;
+    let config_path = repo.git_dir().join("config");
+    let mut config = std::fs::read_to_string(&config_path)?;
+    config = config.replace("repositoryformatversion = 0", "repositoryformatversion = 1");
+    config.push_str("[extensions]\n\trefStorage = unknown\n");
+    std::fs::write(&config_path, config)?;
+
+    let err = gix::open_opts(tmp.path(), gix::open::Options::isolated()).unwrap_err();
+    assert_eq!(
+        err.source().expect("present").to_string(),
+        "The key \"extensions.refStorage=unknown\" was invalid",
+        "like git, we refuse to open repositories whose references we can't read"
     );
     Ok(())
 }
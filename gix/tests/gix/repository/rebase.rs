@@ -0,0 +1,296 @@
+use gix::rebase::{todo, Error, Options, Outcome, Stop};
+
+use crate::util::{head_messages, named_subrepo_rw, read, write};
+
+fn finished(outcome: Outcome) -> gix::ObjectId {
+    match outcome {
+        Outcome::Finished { head } => head,
+        Outcome::Stopped(stop) => panic!("expected the rebase to finish, but it stopped: {stop:?}"),
+    }
+}
+
+#[test]
+fn onto_upstream_with_autosquash() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_rebase_repos.sh", "repo")?;
+    let orig_head = repo.head_id()?.detach();
+    let todo = repo.rebase().todo("main", true)?;
+    assert_eq!(
+        todo.lines
+            .iter()
+            .map(|line| format!("{} {}", line.command.name(), line.subject))
+            .collect::<Vec<_>>(),
+        ["pick add a", "fixup fixup! add a", "pick add b", "squash squash! add b"],
+        "commits are oldest first, with fixups moved after their targets"
+    );
+
+    let head = finished(repo.rebase().start(
+        "main",
+        Options {
+            autosquash: true,
+            ..Default::default()
+        },
+    )?);
+    assert_eq!(repo.head_id()?, head);
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/topic");
+    assert_eq!(
+        head_messages(&repo)?,
+        ["add b\n\nextend b\n", "add a\n", "upstream\n", "base\n"],
+        "fixups keep the message, squashes append theirs without the subject"
+    );
+    assert_eq!(read(&repo, "a")?, "a fixed\n");
+    assert_eq!(read(&repo, "b")?, "b\nb extended\n");
+    assert_eq!(read(&repo, "upstream")?, "upstream\n");
+    assert!(!repo.is_dirty()?);
+    assert!(repo.rebase().state()?.is_none(), "the state was removed");
+    assert_eq!(repo.find_reference("ORIG_HEAD")?.id(), orig_head);
+
+    let log: Vec<_> = repo
+        .find_reference("HEAD")?
+        .log_iter()
+        .all()?
+        .expect("present")
+        .map(|line| line.map(|line| line.message.to_string()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        log[log.len() - 5..],
+        [
+            "rebase (start): checkout main",
+            "rebase (pick): add a",
+            "rebase (fixup): add a",
+            "rebase (pick): add b",
+            "rebase (squash): add b"
+        ],
+        "symbolic changes to HEAD aren't logged"
+    );
+    Ok(())
+}
+
+#[test]
+fn with_onto_and_without_changes_to_replay() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_rebase_repos.sh", "repo")?;
+    let base = repo.rev_parse_single("main~1")?.detach();
+    let head = finished(repo.rebase().start(
+        "topic",
+        Options {
+            onto: Some(base),
+            ..Default::default()
+        },
+    )?);
+    assert_eq!(
+        repo.find_reference("topic")?.id(),
+        head,
+        "nothing to replay, so the branch is reset to the commit to replay onto"
+    );
+    assert_eq!(head, base);
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/topic");
+    Ok(())
+}
+
+#[test]
+fn conflicts_stop_until_resolved() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_rebase_repos.sh", "repo")?;
+    let add_b = repo.rev_parse_single("topic~2")?.detach();
+    let outcome = repo.rebase().start("conflicting", Default::default())?;
+    let Outcome::Stopped(Stop::Conflicts { commit, conflicts }) = outcome else {
+        panic!("expected conflicts, got {outcome:?}")
+    };
+    assert_eq!(commit, add_b);
+    assert_eq!(conflicts.len(), 1);
+    assert!(repo.head()?.is_detached());
+    assert_eq!(repo.find_reference("REBASE_HEAD")?.id(), add_b);
+    assert!(read(&repo, "b")?.starts_with("<<<<<<< HEAD\nb conflicting\n"));
+
+    let state = repo.rebase().state()?.expect("in progress");
+    assert_eq!(state.stopped_at, Some(add_b.into()));
+    assert_eq!(state.message.as_ref().expect("set"), "add b\n");
+    assert_eq!(state.todo.lines.len(), 2, "the remaining commands");
+    assert!(matches!(repo.rebase().resume(None), Err(Error::UnresolvedConflicts)));
+    assert!(matches!(
+        repo.rebase().start("main", Default::default()),
+        Err(Error::InProgress)
+    ));
+
+    write(&repo, "b", "b resolved\n")?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, Some("b"), Default::default())?;
+    index.write(Default::default())?;
+    finished(repo.rebase().resume(None)?);
+    assert_eq!(
+        head_messages(&repo)?,
+        [
+            "squash! add b\n\nextend b\n",
+            "fixup! add a\n",
+            "add b\n",
+            "add a\n",
+            "b conflicting\n",
+            "upstream\n",
+            "base\n"
+        ],
+        "without autosquash, fixups are picked as is"
+    );
+    let head = repo.head_commit()?;
+    assert_eq!(head.author()?.name, "author", "the original author is kept");
+    assert_eq!(
+        read(&repo, "b")?,
+        "b resolved\nb extended\n",
+        "the squash applied cleanly on top of the resolution"
+    );
+    assert!(!repo.is_dirty()?);
+    assert!(!repo.path().join("REBASE_HEAD").exists());
+    Ok(())
+}
+
+#[test]
+fn todo_list_with_all_stopping_commands() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_rebase_repos.sh", "repo")?;
+    let mut todo = repo.rebase().todo("main", false)?;
+    let commit = |idx: usize| *todo.lines[idx].command.commit().expect("pick");
+    let (add_a, add_b, fixup_a, squash_b) = (commit(0), commit(1), commit(2), commit(3));
+    todo.lines = vec![
+        todo::Line {
+            command: todo::Command::Edit { commit: add_a },
+            subject: Default::default(),
+        },
+        todo::Line {
+            command: todo::Command::Drop { commit: fixup_a },
+            subject: Default::default(),
+        },
+        todo::Line {
+            command: todo::Command::Reword { commit: add_b },
+            subject: Default::default(),
+        },
+        todo::Line {
+            command: todo::Command::Exec {
+                command: "test -f does-not-exist".into(),
+            },
+            subject: Default::default(),
+        },
+        todo::Line {
+            command: todo::Command::Break,
+            subject: Default::default(),
+        },
+        todo::Line {
+            command: todo::Command::Fixup {
+                commit: squash_b,
+                message: Default::default(),
+            },
+            subject: Default::default(),
+        },
+    ];
+
+    let outcome = repo.rebase().start(
+        "main",
+        Options {
+            todo: Some(todo),
+            ..Default::default()
+        },
+    )?;
+    let Outcome::Stopped(Stop::Edit { commit }) = outcome else {
+        panic!("expected to stop for editing: {outcome:?}")
+    };
+    assert_eq!(repo.head_id()?, commit);
+    assert!(repo.rebase().state()?.expect("in progress").interactive);
+
+    write(&repo, "a", "a edited\n")?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, Some("a"), Default::default())?;
+    index.write(Default::default())?;
+    let outcome = repo.rebase().resume(None)?;
+    assert!(
+        matches!(outcome, Outcome::Stopped(Stop::Reword { .. })),
+        "{outcome:?}: the fixup was dropped"
+    );
+
+    let outcome = repo.rebase().resume(Some("reworded b\n".into()))?;
+    let Outcome::Stopped(Stop::Exec { command, status }) = outcome else {
+        panic!("the command fails: {outcome:?}")
+    };
+    assert_eq!(command, "test -f does-not-exist");
+    assert!(!status.success());
+
+    let outcome = repo.rebase().resume(None)?;
+    assert!(matches!(outcome, Outcome::Stopped(Stop::Break)), "{outcome:?}");
+    finished(repo.rebase().resume(None)?);
+
+    assert_eq!(
+        head_messages(&repo)?,
+        ["reworded b\n", "add a\n", "upstream\n", "base\n"]
+    );
+    assert_eq!(read(&repo, "a")?, "a edited\n", "the edit was amended");
+    assert_eq!(read(&repo, "b")?, "b\nb extended\n");
+    assert!(!repo.is_dirty()?);
+    Ok(())
+}
+
+#[test]
+fn skip_and_abort() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_rebase_repos.sh", "repo")?;
+    let orig_head = repo.head_id()?.detach();
+    let outcome = repo.rebase().start("conflicting", Default::default())?;
+    assert!(matches!(outcome, Outcome::Stopped(Stop::Conflicts { .. })));
+
+    repo.rebase().abort()?;
+    assert!(repo.rebase().state()?.is_none());
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/topic");
+    assert_eq!(repo.head_id()?, orig_head);
+    assert_eq!(read(&repo, "b")?, "b\nb extended\n");
+    assert!(!repo.path().join("REBASE_HEAD").exists());
+    assert!(!repo.is_dirty()?);
+    assert!(matches!(repo.rebase().abort(), Err(Error::NotInProgress)));
+
+    let outcome = repo.rebase().start("conflicting", Default::default())?;
+    assert!(matches!(outcome, Outcome::Stopped(Stop::Conflicts { .. })));
+    finished(repo.rebase().skip()?);
+    assert_eq!(
+        head_messages(&repo)?,
+        [
+            "squash! add b\n\nextend b\n",
+            "fixup! add a\n",
+            "add a\n",
+            "b conflicting\n",
+            "upstream\n",
+            "base\n"
+        ],
+        "the conflicting commit was skipped"
+    );
+    assert_eq!(read(&repo, "b")?, "b conflicting\nb extended\n");
+    assert!(!repo.is_dirty()?);
+    Ok(())
+}
+
+#[test]
+fn resume_rebase_started_by_git() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_rebase_repos.sh", "git-started")?;
+    let state = repo.rebase().state()?.expect("stopped for editing");
+    assert_eq!(state.head_name.as_ref().expect("branch").as_bstr(), "refs/heads/topic");
+    assert!(state.interactive);
+    assert_eq!(state.amend, Some(repo.head_id()?.detach()));
+    assert_eq!(state.done.lines.len(), 1);
+    assert_eq!(state.done.lines[0].command.name(), "edit");
+    assert_eq!(
+        state
+            .todo
+            .lines
+            .iter()
+            .map(|line| line.subject.to_string())
+            .collect::<Vec<_>>(),
+        ["add b", "fixup! add a", "squash! add b"]
+    );
+    assert_eq!(state.author.as_ref().expect("written by git").name, "author");
+
+    finished(repo.rebase().resume(None)?);
+    assert_eq!(
+        head_messages(&repo)?,
+        [
+            "squash! add b\n\nextend b\n",
+            "fixup! add a\n",
+            "add b\n",
+            "add a\n",
+            "upstream\n",
+            "base\n"
+        ]
+    );
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/topic");
+    assert!(!repo.is_dirty()?);
+    Ok(())
+}
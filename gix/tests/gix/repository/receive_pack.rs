@@ -0,0 +1,318 @@
+use std::{sync::atomic::AtomicBool, thread::JoinHandle};
+
+use gix::{
+    bstr::BString,
+    protocol::{push::Update, receive_pack::Hooks, transport::Protocol},
+    remote::{
+        push::{update::Status, Options, Outcome},
+        Direction::Push,
+    },
+};
+use gix_features::progress;
+use gix_testtools::tempfile::TempDir;
+
+/// Record all calls, and reject updates of references in `reject_update`, or all of them if `reject_all` is set.
+#[derive(Default, Clone)]
+struct Recorder {
+    reject_all: bool,
+    reject_update: Vec<&'static str>,
+    calls: Vec<(&'static str, Vec<BString>)>,
+}
+
+impl Hooks for Recorder {
+    fn pre_receive(&mut self, updates: &[Update], _objects: &dyn gix::objs::Find) -> Result<(), BString> {
+        self.calls
+            .push(("pre-receive", updates.iter().map(|u| u.name.clone()).collect()));
+        if self.reject_all {
+            return Err("no pushes allowed".into());
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, update: &Update, objects: &dyn gix::objs::Find) -> Result<(), BString> {
+        self.calls.push(("update", vec![update.name.clone()]));
+        if !update.is_delete() {
+            assert!(
+                objects
+                    .try_find(&update.new, &mut Vec::new())
+                    .expect("no error")
+                    .is_some(),
+                "quarantined objects are accessible"
+            );
+        }
+        if self.reject_update.iter().any(|name| update.name == *name) {
+            return Err("not this one".into());
+        }
+        Ok(())
+    }
+
+    fn post_receive(&mut self, updates: &[Update]) {
+        self.calls
+            .push(("post-receive", updates.iter().map(|u| u.name.clone()).collect()));
+    }
+}
+
+fn repos() -> crate::Result<(gix::Repository, gix::Repository, TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable("make_push_repos.sh")?;
+    let local = gix::open_opts(dir.path().join("local"), crate::restricted())?;
+    let remote = gix::open_opts(dir.path().join("remote.git"), crate::restricted())?;
+    Ok((local, remote, dir))
+}
+
+type Server =
+    JoinHandle<Result<(gix::protocol::receive_pack::Outcome, Recorder), gix::repository::receive_pack::Error>>;
+
+/// Accept a single push from the first client connecting via `git://` to `remote`, and return the url to connect to.
+fn serve_once(remote: gix::Repository, mut hooks: Recorder) -> crate::Result<(String, Server)> {
+    let remote = remote.into_sync();
+    Ok(crate::remote::serve_over_git_once(
+        "git-receive-pack",
+        move |stream, protocol| {
+            let outcome =
+                remote
+                    .to_thread_local()
+                    .receive_pack(stream, stream, protocol, &mut hooks, &AtomicBool::default())?;
+            Ok((outcome, hooks))
+        },
+    )?)
+}
+
+fn push(local: &gix::Repository, url: &str, specs: &[&str]) -> crate::Result<Outcome> {
+    let options = Options {
+        refspecs: specs
+            .iter()
+            .map(|spec| {
+                gix::refspec::parse((*spec).into(), gix::refspec::parse::Operation::Push)
+                    .expect("valid")
+                    .to_owned()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    Ok(local
+        .find_remote("origin")?
+        .push_url(url)?
+        .connect(Push)?
+        .prepare_push(progress::Discard, options)?
+        .transmit(progress::Discard, &AtomicBool::default())?)
+}
+
+fn id(repo: &gix::Repository, spec: &str) -> gix_hash::ObjectId {
+    repo.rev_parse_single(spec).expect("valid spec").detach()
+}
+
+fn pack_count(repo: &gix::Repository) -> crate::Result<usize> {
+    Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .is_ok_and(|e| e.path().extension().is_some_and(|ext| ext == "pack"))
+        })
+        .count())
+}
+
+fn assert_quarantine_removed(repo: &gix::Repository) -> crate::Result {
+    for entry in std::fs::read_dir(repo.objects.store_ref().path())? {
+        let name = entry?.file_name();
+        assert!(
+            !name.to_string_lossy().starts_with("incoming-"),
+            "quarantine directories are always removed"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn updates_are_applied_after_the_hooks_accepted_them() -> crate::Result {
+    let (local, remote, _dir) = repos()?;
+    let (url, server) = serve_once(remote.clone(), Recorder::default())?;
+    let outcome = push(
+        &local,
+        &url,
+        &[
+            "main",
+            "refs/heads/feature:refs/heads/feature",
+            "refs/tags/v1:refs/tags/v1",
+            ":refs/heads/other",
+        ],
+    )?;
+    let (server_outcome, hooks) = server.join().expect("no panic")?;
+    assert!(outcome.is_success(), "{outcome:?}");
+    assert!(server_outcome.objects > 0, "a pack was received");
+    assert!(server_outcome.report.expect("sent").is_success());
+
+    for name in ["refs/heads/main", "refs/heads/feature", "refs/tags/v1"] {
+        assert_eq!(id(&remote, name), id(&local, name), "{name} was updated on the remote");
+    }
+    assert!(remote.try_find_reference("refs/heads/other")?.is_none());
+    let feature_tree = remote.find_commit(id(&local, "feature"))?.tree()?;
+    assert!(
+        feature_tree.find_entry("file").is_some(),
+        "objects were moved into the object database"
+    );
+    assert_quarantine_removed(&remote)?;
+
+    let all: Vec<BString> = [
+        "refs/heads/main",
+        "refs/heads/feature",
+        "refs/tags/v1",
+        "refs/heads/other",
+    ]
+    .into_iter()
+    .map(Into::into)
+    .collect();
+    let mut expected = vec![("pre-receive", all.clone())];
+    expected.extend(all.iter().map(|name| ("update", vec![name.clone()])));
+    expected.push(("post-receive", all));
+    assert_eq!(hooks.calls, expected, "hooks are called in order, similar to `git`");
+    Ok(())
+}
+
+#[test]
+fn update_hook_rejects_individual_updates() -> crate::Result {
+    let (local, remote, _dir) = repos()?;
+    let before = id(&remote, "main");
+    let (url, server) = serve_once(
+        remote.clone(),
+        Recorder {
+            reject_update: vec!["refs/heads/main"],
+            ..Default::default()
+        },
+    )?;
+    let outcome = push(&local, &url, &["main", "refs/heads/feature:refs/heads/feature"])?;
+    let (_server_outcome, hooks) = server.join().expect("no panic")?;
+    assert_eq!(
+        outcome.updates[0].status,
+        Status::RemoteRejected {
+            reason: "hook declined: not this one".into()
+        }
+    );
+    assert_eq!(outcome.updates[1].status, Status::Ok);
+    assert_eq!(id(&remote, "main"), before, "the rejected update wasn't applied");
+    assert_eq!(id(&remote, "refs/heads/feature"), id(&local, "feature"));
+    assert_eq!(
+        hooks.calls.last().expect("post-receive"),
+        &("post-receive", vec!["refs/heads/feature".into()]),
+        "only applied updates are passed to the post-receive hook"
+    );
+    Ok(())
+}
+
+#[test]
+fn pre_receive_hook_rejects_all_updates_and_objects() -> crate::Result {
+    let (local, remote, _dir) = repos()?;
+    let before = id(&remote, "main");
+    let packs_before = pack_count(&remote)?;
+    let (url, server) = serve_once(
+        remote.clone(),
+        Recorder {
+            reject_all: true,
+            ..Default::default()
+        },
+    )?;
+    let outcome = push(&local, &url, &["main", "refs/heads/feature:refs/heads/feature"])?;
+    let (server_outcome, hooks) = server.join().expect("no panic")?;
+    assert!(server_outcome.objects > 0, "a pack was received");
+    for update in &outcome.updates {
+        assert_eq!(
+            update.status,
+            Status::RemoteRejected {
+                reason: "pre-receive hook declined: no pushes allowed".into()
+            }
+        );
+    }
+    assert_eq!(id(&remote, "main"), before);
+    assert!(remote.try_find_reference("refs/heads/feature")?.is_none());
+    assert_eq!(pack_count(&remote)?, packs_before, "quarantined objects are discarded");
+    assert_quarantine_removed(&remote)?;
+    assert_eq!(
+        hooks.calls.len(),
+        2,
+        "no update hook is called, but post-receive is called without updates"
+    );
+    Ok(())
+}
+
+#[test]
+fn hook_messages_are_sent_through_the_side_band() -> crate::Result {
+    let (_local, remote, _dir) = repos()?;
+    let command = format!(
+        "{} {} refs/heads/other\0report-status side-band-64k\n",
+        id(&remote, "refs/heads/other"),
+        remote.object_hash().null()
+    );
+    let input = format!("{:04x}{command}0000", command.len() + 4);
+    let mut output = Vec::new();
+    let outcome = remote.receive_pack(
+        input.as_bytes(),
+        &mut output,
+        Protocol::V0,
+        &mut Recorder {
+            reject_all: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(
+        outcome.report.expect("requested").refs,
+        [gix::protocol::push::response::RefStatus::Rejected {
+            name: "refs/heads/other".into(),
+            reason: "pre-receive hook declined: no pushes allowed".into(),
+        }]
+    );
+    let progress = "\x02no pushes allowed\n";
+    let progress = format!("{:04x}{progress}", progress.len() + 4);
+    assert!(
+        output.windows(progress.len()).any(|w| w == progress.as_bytes()),
+        "the message of the hook is sent to the progress channel, like git does with the output of hooks"
+    );
+    assert!(remote.try_find_reference("refs/heads/other")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn refs_to_trees_with_missing_objects_are_rejected() -> crate::Result {
+    use std::io::Write;
+
+    let (local, remote, _dir) = repos()?;
+    let blob = local.write_blob("only present locally")?.detach();
+    let tree = local
+        .edit_tree(gix::ObjectId::empty_tree(local.object_hash()))?
+        .upsert("file", gix::object::tree::EntryKind::Blob, blob)?
+        .write()?
+        .detach();
+
+    // Without `--revs`, only the given objects are packed, leaving out the blob the tree refers to.
+    let mut pack_objects = std::process::Command::new(gix::path::env::exe_invocation())
+        .args(["pack-objects", "-q", "--stdout"])
+        .current_dir(local.git_dir())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()?;
+    writeln!(pack_objects.stdin.take().expect("piped"), "{tree}")?;
+    let pack = pack_objects.wait_with_output()?;
+    assert!(pack.status.success());
+
+    let command = format!("{} {tree} refs/heads/tree\0report-status\n", local.object_hash().null());
+    let mut input = format!("{:04x}{command}0000", command.len() + 4).into_bytes();
+    input.extend_from_slice(&pack.stdout);
+    let outcome = remote.receive_pack(
+        input.as_slice(),
+        Vec::new(),
+        Protocol::V0,
+        &mut Recorder::default(),
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(outcome.objects, 1, "only the tree was sent");
+    assert_eq!(
+        outcome.report.expect("requested").refs,
+        [gix::protocol::push::response::RefStatus::Rejected {
+            name: "refs/heads/tree".into(),
+            reason: "missing necessary objects".into(),
+        }],
+        "the entries of pushed trees are checked as well"
+    );
+    assert!(remote.try_find_reference("refs/heads/tree")?.is_none());
+    assert_quarantine_removed(&remote)?;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use gix::repository::reset::{Error, Mode};
+
+use crate::util::{read, repo_rw, write};
+
+fn last_log_message(repo: &gix::Repository, name: &str) -> crate::Result<String> {
+    let reference = repo.find_reference(name)?;
+    let mut log = reference.log_iter();
+    let last = log.rev()?.expect("present").next().expect("at least one line")?;
+    Ok(last.message.to_string())
+}
+
+fn tree_of(repo: &gix::Repository, rev: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo
+        .rev_parse_single(rev)?
+        .object()?
+        .peel_to_commit()?
+        .tree_id()?
+        .detach())
+}
+
+#[test]
+fn hard() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    let main = repo.head_id()?.detach();
+    let other = repo.rev_parse_single("other")?.detach();
+    write(&repo, "a", "a, but changed\n")?;
+    write(&repo, "untracked", "untracked\n")?;
+    std::fs::write(repo.path().join("MERGE_HEAD"), format!("{other}\n"))?;
+
+    let out = repo.reset("other", Mode::Hard)?;
+    assert_eq!(out.previous, Some(main));
+    assert_eq!(out.id, other);
+    assert_eq!(out.updated.len(), 5, "a, c, dir/b, dir/new and new-dir/x");
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    assert_eq!(repo.head_id()?, other);
+    assert_eq!(read(&repo, "a")?, "a in other\n", "local changes are discarded");
+    assert_eq!(read(&repo, "untracked")?, "untracked\n", "untracked files are kept");
+    assert!(!repo.is_dirty()?);
+
+    assert_eq!(repo.find_reference("ORIG_HEAD")?.id(), main);
+    assert!(!repo.path().join("MERGE_HEAD").exists(), "the merge was aborted");
+    for name in ["HEAD", "main"] {
+        assert_eq!(last_log_message(&repo, name)?, "reset: moving to other");
+    }
+    Ok(())
+}
+
+#[test]
+fn mixed() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    let main = repo.head_id()?.detach();
+    let out = repo.reset("other", Mode::Mixed)?;
+    assert_eq!(out.previous, Some(main));
+    assert!(out.updated.is_empty());
+    assert_eq!(read(&repo, "a")?, "a\n", "the worktree is unchanged");
+    assert_eq!(repo.write_tree(&mut *repo.open_index()?)?, tree_of(&repo, "other")?);
+    assert!(
+        repo.is_dirty()?,
+        "the worktree still has the content of the previous commit"
+    );
+
+    repo.reset(main.to_string().as_str(), Mode::Mixed)?;
+    assert!(!repo.is_dirty()?);
+    assert_eq!(last_log_message(&repo, "HEAD")?, format!("reset: moving to {main}"));
+    Ok(())
+}
+
+#[test]
+fn soft() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    let other = repo.rev_parse_single("other")?.detach();
+    std::fs::write(repo.path().join("MERGE_HEAD"), format!("{other}\n"))?;
+    assert!(matches!(
+        repo.reset("other", Mode::Soft),
+        Err(Error::SoftResetDuringMerge)
+    ));
+    std::fs::remove_file(repo.path().join("MERGE_HEAD"))?;
+
+    repo.reset("other", Mode::Soft)?;
+    assert_eq!(repo.head_id()?, other);
+    assert_eq!(
+        repo.write_tree(&mut *repo.open_index()?)?,
+        tree_of(&repo, "main@{1}")?,
+        "the index is unchanged"
+    );
+    assert_eq!(read(&repo, "a")?, "a\n");
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use gix::repository::restore::Options;
+
+use crate::util::{blob, bstrings, read, repo_rw, write};
+
+#[test]
+fn worktree_from_index() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    let mut index = repo.open_index()?;
+    write(&repo, "a", "a, but changed\n")?;
+    write(&repo, "same", "same, but changed\n")?;
+    assert!(repo.restore(&mut index, None::<&str>, Options::default())?.is_empty());
+
+    let restored = repo.restore(&mut index, Some("a"), Options::default())?;
+    assert_eq!(restored, bstrings(&["a"]));
+    assert_eq!(read(&repo, "a")?, "a\n");
+    assert_eq!(
+        read(&repo, "same")?,
+        "same, but changed\n",
+        "only matching files are restored"
+    );
+    Ok(())
+}
+
+#[test]
+fn staged_from_head() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    let mut index = repo.open_index()?;
+    write(&repo, "a", "a, but changed\n")?;
+    write(&repo, "new", "new\n")?;
+    repo.add_to_index(&mut index, None::<&str>, Default::default())?;
+
+    let restored = repo.restore(
+        &mut index,
+        ["a", "new"],
+        Options {
+            staged: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(restored, bstrings(&["a", "new"]));
+    assert_eq!(blob(&repo, &index, "a")?, b"a\n");
+    assert!(index.entry_by_path("new".into()).is_none(), "new entries are removed");
+    assert_eq!(read(&repo, "a")?, "a, but changed\n", "the worktree is unchanged");
+    assert_eq!(read(&repo, "new")?, "new\n");
+    assert_eq!(repo.write_tree(&mut index)?, repo.head_tree_id()?);
+    Ok(())
+}
+
+#[test]
+fn from_source() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    let mut index = repo.open_index()?;
+    let other = repo.rev_parse_single("other")?.detach();
+
+    let restored = repo.restore(
+        &mut index,
+        ["dir", "c"],
+        Options {
+            source: Some(other),
+            staged: true,
+            worktree: true,
+        },
+    )?;
+    assert_eq!(restored, bstrings(&["c", "dir/b", "dir/new"]));
+    assert!(index.entry_by_path("c".into()).is_none());
+    assert!(!repo.workdir().expect("non-bare").join("c").exists());
+    assert_eq!(read(&repo, "dir/new")?, "new\n");
+    assert_eq!(blob(&repo, &index, "dir/new")?, b"new\n");
+    index.write(Default::default())?;
+    assert!(
+        !repo
+            .status(gix::progress::Discard)?
+            .into_index_worktree_iter(None)?
+            .any(|item| item.is_ok()),
+        "the worktree matches the index"
+    );
+
+    let restored = repo.restore(
+        &mut index,
+        Some("a"),
+        Options {
+            source: Some(other),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(restored, bstrings(&["a"]));
+    assert_eq!(read(&repo, "a")?, "a in other\n");
+    assert_eq!(blob(&repo, &index, "a")?, b"a\n", "the index is unchanged");
+    Ok(())
+}
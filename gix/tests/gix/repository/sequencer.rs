@@ -0,0 +1,349 @@
+use gix::sequencer::{todo::Action, Error, Options, Outcome};
+
+use crate::util::{exists, head_messages, named_subrepo_rw, read};
+
+fn checkout(repo: &gix::Repository, branch: &str) -> crate::Result {
+    repo.switch(branch, Default::default())?;
+    Ok(())
+}
+
+fn resolve(repo: &gix::Repository, path: &str, content: &str) -> crate::Result {
+    std::fs::write(repo.workdir().expect("non-bare").join(path), content)?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, Some(path), Default::default())?;
+    index.write(Default::default())?;
+    Ok(())
+}
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+fn finished(outcome: Outcome) -> gix::ObjectId {
+    match outcome {
+        Outcome::Finished { head } => head,
+        Outcome::Stopped { commit, .. } => panic!("expected the sequence to finish, but it stopped at {commit}"),
+    }
+}
+
+#[test]
+fn todo_lists_picks_oldest_first_and_reverts_newest_first() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "repo")?;
+    let subjects = |action, spec: &str| -> crate::Result<Vec<String>> {
+        Ok(repo
+            .sequencer()
+            .todo(action, spec)?
+            .lines
+            .into_iter()
+            .map(|line| format!("{} {}", line.action.name(), line.subject))
+            .collect())
+    };
+    assert_eq!(
+        subjects(Action::Pick, "release..topic")?,
+        ["pick one", "pick two", "pick three"]
+    );
+    assert_eq!(
+        subjects(Action::Revert, "topic~2..topic")?,
+        ["revert three", "revert two"]
+    );
+    assert_eq!(subjects(Action::Pick, "topic~1")?, ["pick two"]);
+    assert!(matches!(
+        repo.sequencer().todo(Action::Pick, "release...topic"),
+        Err(Error::UnsupportedSpec { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn cherry_pick_range_onto_branch() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "repo")?;
+    checkout(&repo, "release")?;
+    let two = id(&repo, "topic~1")?;
+    let head = finished(repo.sequencer().cherry_pick(
+        "release..topic",
+        Options {
+            record_origin: true,
+            ..Default::default()
+        },
+    )?);
+
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/release");
+    assert_eq!(repo.find_reference("release")?.id(), head);
+    assert_eq!(
+        head_messages(&repo)?[..4],
+        [
+            format!("three\n\n(cherry picked from commit {})\n", id(&repo, "topic")?),
+            format!("two\n\nSigned-off-by: author <author@example.com>\n(cherry picked from commit {two})\n"),
+            format!("one\n\n(cherry picked from commit {})\n", id(&repo, "topic~2")?),
+            "base\n".into()
+        ],
+        "the origin is appended to existing trailers"
+    );
+    let commit = repo.head_commit()?;
+    assert_eq!(commit.author()?.name, "author", "authors are kept");
+    assert_ne!(head, id(&repo, "topic")?, "new commits are created");
+    assert_eq!(commit.tree_id()?, repo.find_commit(id(&repo, "topic")?)?.tree_id()?);
+    assert_eq!(read(&repo, "f")?, "topic\n");
+    assert!(exists(&repo, "h"));
+    assert!(!repo.is_dirty()?);
+    assert!(repo.sequencer().state()?.is_none(), "the state was removed");
+    assert_eq!(repo.state(), None);
+
+    let log: Vec<_> = repo
+        .find_reference("release")?
+        .log_iter()
+        .all()?
+        .expect("present")
+        .map(|line| line.map(|line| line.message.to_string()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        log[log.len() - 3..],
+        ["cherry-pick: one", "cherry-pick: two", "cherry-pick: three"],
+        "the branch is updated with each commit"
+    );
+    Ok(())
+}
+
+#[test]
+fn conflicts_stop_until_resolved() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "repo")?;
+    let two = id(&repo, "topic~1")?;
+    let orig_head = repo.head_id()?.detach();
+    let outcome = repo.sequencer().cherry_pick("release..topic", Default::default())?;
+    let Outcome::Stopped { commit, conflicts } = outcome else {
+        panic!("expected conflicts, got {outcome:?}")
+    };
+    assert_eq!(commit, two);
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(repo.state(), Some(gix::state::InProgress::CherryPickSequence));
+    assert_eq!(repo.find_reference("CHERRY_PICK_HEAD")?.id(), two);
+    assert!(read(&repo, "f")?.starts_with("<<<<<<< HEAD\nmain\n"));
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("MERGE_MSG"))?,
+        "two\n\nSigned-off-by: author <author@example.com>\n"
+    );
+
+    let state = repo.sequencer().state()?.expect("in progress");
+    assert_eq!(state.head, orig_head);
+    assert_eq!(state.abort_safety, Some(repo.head_id()?.detach()));
+    assert_eq!(
+        state
+            .todo
+            .lines
+            .iter()
+            .map(|line| line.subject.to_string())
+            .collect::<Vec<_>>(),
+        ["two", "three"],
+        "the commit that stopped the sequence comes first"
+    );
+    assert!(matches!(repo.sequencer().resume(None), Err(Error::UnresolvedConflicts)));
+    assert!(matches!(
+        repo.sequencer().cherry_pick("release..topic", Default::default()),
+        Err(Error::InProgress)
+    ));
+
+    resolve(&repo, "f", "resolved\n")?;
+    finished(repo.sequencer().resume(Some("two, resolved\n".into()))?);
+    assert_eq!(
+        head_messages(&repo)?[..5],
+        ["three\n", "two, resolved\n", "one\n", "main change\n", "base\n"]
+    );
+    assert_eq!(repo.head_commit()?.parent_ids().count(), 1);
+    assert_eq!(read(&repo, "f")?, "resolved\n");
+    assert!(!repo.is_dirty()?);
+    assert_eq!(repo.state(), None);
+    assert!(!repo.path().join("CHERRY_PICK_HEAD").exists());
+    assert!(!repo.path().join("MERGE_MSG").exists());
+    Ok(())
+}
+
+#[test]
+fn skip_and_abort() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "repo")?;
+    let orig_head = repo.head_id()?.detach();
+    let outcome = repo.sequencer().cherry_pick("release..topic", Default::default())?;
+    assert!(matches!(outcome, Outcome::Stopped { .. }));
+
+    assert!(repo.sequencer().abort()?, "HEAD wasn't moved, so it's reset");
+    assert_eq!(repo.head_id()?, orig_head, "the commit of 'one' is undone");
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    assert_eq!(read(&repo, "f")?, "main\n");
+    assert!(!exists(&repo, "g"));
+    assert!(!repo.is_dirty()?);
+    assert_eq!(repo.state(), None);
+    assert!(repo.sequencer().state()?.is_none());
+    assert!(matches!(repo.sequencer().abort(), Err(Error::NotInProgress)));
+
+    let outcome = repo.sequencer().cherry_pick("release..topic", Default::default())?;
+    assert!(matches!(outcome, Outcome::Stopped { .. }));
+    finished(repo.sequencer().skip()?);
+    assert_eq!(
+        head_messages(&repo)?[..4],
+        ["three\n", "one\n", "main change\n", "base\n"],
+        "the conflicting commit was skipped"
+    );
+    assert_eq!(read(&repo, "f")?, "main\n");
+    assert!(!repo.is_dirty()?);
+    assert_eq!(repo.state(), None);
+    Ok(())
+}
+
+#[test]
+fn abort_leaves_moved_head_alone() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "repo")?;
+    let outcome = repo.sequencer().cherry_pick("release..topic", Default::default())?;
+    assert!(matches!(outcome, Outcome::Stopped { .. }));
+
+    let mut state = repo.sequencer().state()?.expect("in progress");
+    state.abort_safety = Some(id(&repo, "release")?);
+    state.write_to_dir(&repo.path().join(gix::sequencer::plumbing::STATE_DIR))?;
+    let head = repo.head_id()?.detach();
+    assert!(!repo.sequencer().abort()?, "HEAD was moved, it seems, so it's kept");
+    assert_eq!(repo.head_id()?, head);
+    assert!(repo.sequencer().state()?.is_none());
+    assert!(!repo.path().join("CHERRY_PICK_HEAD").exists());
+    Ok(())
+}
+
+#[test]
+fn revert_range() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "repo")?;
+    checkout(&repo, "topic")?;
+    let (two, three) = (id(&repo, "topic~1")?, id(&repo, "topic")?);
+    finished(repo.sequencer().revert("topic~2..topic", Default::default())?);
+    assert_eq!(
+        head_messages(&repo)?[..3],
+        [
+            format!("Revert \"two\"\n\nThis reverts commit {two}.\n"),
+            format!("Revert \"three\"\n\nThis reverts commit {three}.\n"),
+            "three\n".into()
+        ]
+    );
+    assert_eq!(
+        repo.head_commit()?.author()?.name,
+        repo.author().expect("configured")?.name,
+        "reverts are authored by the configured author"
+    );
+    assert_eq!(read(&repo, "f")?, "base\n");
+    assert!(!exists(&repo, "h"));
+    assert!(exists(&repo, "g"));
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/topic");
+    assert!(!repo.is_dirty()?);
+    Ok(())
+}
+
+#[test]
+fn merge_commits_need_a_mainline() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "repo")?;
+    checkout(&repo, "release")?;
+    let merge = id(&repo, "merged")?;
+    let err = repo
+        .sequencer()
+        .cherry_pick("merged", Default::default())
+        .expect_err("merge commits can't be picked without mainline");
+    assert!(matches!(err, Error::MergeWithoutMainline { id } if id == merge));
+    repo.sequencer().abort()?;
+
+    let err = repo
+        .sequencer()
+        .cherry_pick(
+            "merged",
+            Options {
+                mainline: Some(3),
+                ..Default::default()
+            },
+        )
+        .expect_err("there is no third parent");
+    assert!(matches!(err, Error::InvalidMainline { mainline: 3, .. }));
+    repo.sequencer().abort()?;
+
+    finished(repo.sequencer().cherry_pick(
+        "merged",
+        Options {
+            mainline: Some(1),
+            ..Default::default()
+        },
+    )?);
+    assert_eq!(head_messages(&repo)?[..1], ["merge side\n"]);
+    assert_eq!(repo.head_commit()?.parent_ids().count(), 1, "the result isn't a merge");
+    assert!(exists(&repo, "s"), "the changes of the merged branch were picked");
+    assert!(!exists(&repo, "m"), "changes of the mainline aren't picked");
+
+    checkout(&repo, "merged")?;
+    let mainline = id(&repo, "merged~1")?;
+    finished(repo.sequencer().revert(
+        "merged",
+        Options {
+            mainline: Some(1),
+            ..Default::default()
+        },
+    )?);
+    assert_eq!(
+        head_messages(&repo)?[..1],
+        [format!(
+            "Revert \"merge side\"\n\nThis reverts commit {merge}, reversing\nchanges made to {mainline}.\n"
+        )]
+    );
+    assert!(!exists(&repo, "s"), "the changes of the merged branch were reverted");
+    assert!(exists(&repo, "m"));
+    Ok(())
+}
+
+#[test]
+fn empty_commits_are_dropped_unless_kept() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "repo")?;
+    checkout(&repo, "topic")?;
+    let head = repo.head_id()?.detach();
+    assert_eq!(
+        finished(repo.sequencer().cherry_pick("topic~2", Default::default())?),
+        head,
+        "the changes are present already"
+    );
+
+    let new = finished(repo.sequencer().cherry_pick(
+        "topic~2",
+        Options {
+            keep_redundant_commits: true,
+            ..Default::default()
+        },
+    )?);
+    assert_ne!(new, head);
+    assert_eq!(head_messages(&repo)?[..2], ["one\n", "three\n"]);
+    Ok(())
+}
+
+#[test]
+fn resume_sequence_started_by_git() -> crate::Result {
+    let (repo, _tmp) = named_subrepo_rw("make_sequencer_repos.sh", "git-started")?;
+    assert_eq!(repo.state(), Some(gix::state::InProgress::CherryPickSequence));
+    let state = repo.sequencer().state()?.expect("stopped due to conflicts");
+    assert!(state.options.record_origin);
+    assert_eq!(state.options.mainline, None);
+    assert_eq!(state.abort_safety, Some(repo.head_id()?.detach()));
+    assert_eq!(
+        state
+            .todo
+            .lines
+            .iter()
+            .map(|line| line.subject.to_string())
+            .collect::<Vec<_>>(),
+        ["two", "three"]
+    );
+
+    resolve(&repo, "f", "resolved\n")?;
+    finished(repo.sequencer().resume(None)?);
+    let two = id(&repo, "origin/topic~1")?;
+    assert_eq!(
+        head_messages(&repo)?[..3],
+        [
+            format!("three\n\n(cherry picked from commit {})\n", id(&repo, "origin/topic")?),
+            format!("two\n\nSigned-off-by: author <author@example.com>\n(cherry picked from commit {two})\n"),
+            format!("one\n\n(cherry picked from commit {})\n", id(&repo, "origin/topic~2")?),
+        ],
+        "the comments git added to the prepared message are removed"
+    );
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    assert_eq!(repo.state(), None);
+    assert!(!repo.is_dirty()?);
+    Ok(())
+}
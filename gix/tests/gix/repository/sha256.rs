@@ -0,0 +1,196 @@
+use crate::util::named_repo;
+#[cfg(feature = "tree-editor")]
+use crate::util::repo_rw;
+
+#[test]
+fn open_repository_created_by_git() -> crate::Result {
+    let repo = named_repo("make_sha256_repo.sh")?;
+    assert_eq!(repo.object_hash(), gix_hash::Kind::Sha256);
+
+    let head = repo.head_commit()?;
+    assert_eq!(head.id.kind(), gix_hash::Kind::Sha256);
+    assert_eq!(
+        head.message_raw()?,
+        "loose\n",
+        "the most recent commit is a loose object"
+    );
+
+    let commits: Vec<_> = head
+        .id()
+        .ancestors()
+        .all()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(commits.len(), 3);
+    let packed = repo.find_commit(commits[1])?;
+    assert_eq!(packed.message_raw()?, "c2\n", "the others are in a pack");
+
+    let graph = repo.commit_graph()?;
+    assert_eq!(graph.num_commits(), 2, "the loose commit was created after the graph");
+    assert!(graph.commit_by_id(commits[1]).is_some());
+    assert!(graph.commit_by_id(commits[0]).is_none());
+
+    let main = repo.find_reference("main")?;
+    assert_eq!(main.id(), head.id);
+    let mut tag = repo.find_reference("v1")?;
+    assert_eq!(
+        tag.peel_to_id_in_place()?,
+        commits[1],
+        "packed refs and annotated tags can be parsed"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "index")]
+fn index_of_repository_created_by_git() -> crate::Result {
+    let repo = named_repo("make_sha256_repo.sh")?;
+    let index = repo.index()?;
+    assert_eq!(index.checksum().expect("present").kind(), gix_hash::Kind::Sha256);
+    let entries: Vec<_> = index
+        .entries()
+        .iter()
+        .map(|e| (e.path(&index).to_string(), e.id))
+        .collect();
+    assert_eq!(entries.len(), 2);
+    for ((path, id), (expected_path, expected_data)) in entries
+        .into_iter()
+        .zip([("that", "hello\n"), ("this", "hello\nhello\n")])
+    {
+        assert_eq!(path, expected_path);
+        assert_eq!(id.kind(), gix_hash::Kind::Sha256);
+        assert_eq!(repo.find_blob(id)?.data, expected_data.as_bytes());
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "tree-editor")]
+fn write_and_read_trees() -> crate::Result {
+    use gix::objs::tree::EntryKind;
+
+    let (repo, _tmp) = repo_rw("make_sha256_repo.sh")?;
+    let head_tree = repo.head_tree()?;
+    let names: Vec<_> = head_tree
+        .decode()?
+        .entries
+        .iter()
+        .map(|e| (e.filename.to_string(), e.oid.kind()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("that".to_string(), gix_hash::Kind::Sha256),
+            ("this".to_string(), gix_hash::Kind::Sha256)
+        ],
+        "trees written by git can be decoded"
+    );
+
+    let blob = repo.write_blob("new\n")?;
+    let tree_id = repo
+        .edit_tree(head_tree.id)?
+        .upsert("dir/new", EntryKind::Blob, blob)?
+        .write()?;
+    assert_eq!(tree_id.kind(), gix_hash::Kind::Sha256);
+
+    let tree = tree_id.object()?.into_tree();
+    let names: Vec<_> = tree
+        .iter()
+        .map(|e| e.map(|e| e.filename().to_string()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(names, ["dir", "that", "this"], "trees we wrote can be read back");
+    let entry = tree.lookup_entry_by_path("dir/new")?.expect("present");
+    assert_eq!(entry.object_id(), blob, "nested trees are followed as well");
+    assert_eq!(
+        tree.find_entry("this").expect("present").object_id(),
+        head_tree.find_entry("this").expect("present").object_id()
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "status", feature = "worktree-mutation", feature = "tree-editor"))]
+fn checkout_tree() -> crate::Result {
+    use gix::objs::tree::EntryKind;
+
+    let (repo, _tmp) = repo_rw("make_sha256_repo.sh")?;
+    let blob = repo.write_blob("new\n")?;
+    let tree = repo
+        .edit_tree(repo.head_tree_id()?)?
+        .upsert("dir/new", EntryKind::Blob, blob)?
+        .remove("that")?
+        .write()?;
+
+    let outcome = repo.checkout_tree(tree, Default::default())?;
+    assert_eq!(outcome.updated, ["dir/new", "that"]);
+    let workdir = repo.workdir().expect("non-bare");
+    assert_eq!(std::fs::read_to_string(workdir.join("dir/new"))?, "new\n");
+    assert_eq!(std::fs::read_to_string(workdir.join("this"))?, "hello\nhello\n");
+    assert!(!workdir.join("that").exists(), "removed files are deleted");
+
+    let index = repo.open_index()?;
+    let entry = index.entry_by_path("dir/new".into()).expect("present");
+    assert_eq!(entry.id, blob);
+    assert_eq!(index.checksum().expect("present").kind(), gix_hash::Kind::Sha256);
+    Ok(())
+}
+
+#[cfg(all(feature = "worktree-mutation", feature = "blocking-network-client"))]
+mod clone {
+    use std::sync::atomic::AtomicBool;
+
+    use crate::util::restricted;
+
+    #[test]
+    fn fetch_and_checkout() -> crate::Result {
+        let remote = gix_testtools::scripted_fixture_read_only("make_sha256_repo.sh")?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            remote.as_path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            gix::create::Options {
+                object_hash: gix_hash::Kind::Sha256,
+                ..Default::default()
+            },
+            restricted(),
+        )?;
+        let (mut checkout, _out) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+
+        assert_eq!(repo.object_hash(), gix_hash::Kind::Sha256);
+        let head = repo.head_commit()?;
+        assert_eq!(head.id.kind(), gix_hash::Kind::Sha256);
+        assert_eq!(head.message_raw()?, "loose\n");
+        let workdir = repo.workdir().expect("non-bare");
+        assert_eq!(std::fs::read_to_string(workdir.join("this"))?, "hello\nhello\n");
+        assert_eq!(std::fs::read_to_string(workdir.join("that"))?, "hello\n");
+        Ok(())
+    }
+
+    #[test]
+    fn into_repository_with_different_object_format_fails() -> crate::Result {
+        let remote = gix_testtools::scripted_fixture_read_only("make_sha256_repo.sh")?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let err = gix::clone::PrepareFetch::new(
+            remote.as_path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::clone::fetch::Error::IncompatibleObjectHash {
+                    local: gix_hash::Kind::Sha1,
+                    remote: gix_hash::Kind::Sha256
+                }
+            ),
+            "the object format of the remote isn't adopted: {err:?}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use gix::bstr::BString;
+use gix::stash::push::Options;
+
+use crate::util::{read, repo_rw, write};
+
+fn messages(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    Ok(repo
+        .stash()
+        .list()?
+        .into_iter()
+        .map(|entry| entry.message.to_string())
+        .collect())
+}
+
+#[test]
+fn list() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let head = repo.head_id()?.shorten()?.to_string();
+    assert_eq!(
+        messages(&repo)?,
+        [format!("WIP on main: {head} initial"), "On main: first".to_string()]
+    );
+
+    let stashes = repo.stash().list()?;
+    assert_eq!(repo.find_reference("refs/stash")?.id(), stashes[0].id);
+    let stash = repo.find_commit(stashes[1].id)?;
+    assert_eq!(stash.parent_ids().count(), 2, "HEAD and the index");
+    assert_eq!(repo.stash().get(1)?, Some(stashes[1].clone()));
+    assert_eq!(repo.stash().get(2)?, None);
+    Ok(())
+}
+
+#[test]
+fn push_and_pop_with_untracked_files() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    assert_eq!(
+        repo.stash().push(Options::default())?,
+        None,
+        "there is nothing to stash in a clean worktree"
+    );
+
+    write(&repo, "a", "a modified\n")?;
+    write(&repo, "dir/c", "c modified\n")?;
+    std::fs::remove_file(repo.workdir().expect("non-bare").join("b"))?;
+    write(&repo, "untracked", "untracked\n")?;
+    write(&repo, "file.ignored", "ignored\n")?;
+    let id = repo
+        .stash()
+        .push(Options {
+            message: Some("with untracked".into()),
+            include_untracked: true,
+            keep_index: false,
+        })?
+        .expect("changes were stashed");
+
+    let stash = id.object()?.into_commit();
+    let parents: Vec<_> = stash.parent_ids().collect();
+    assert_eq!(parents.len(), 3, "HEAD, the index and the untracked files");
+    assert_eq!(parents[0], repo.head_id()?);
+    assert_eq!(
+        repo.find_commit(parents[1])?.tree_id()?,
+        repo.head_tree_id()?,
+        "the index was unchanged"
+    );
+    let untracked = repo.find_commit(parents[2])?;
+    assert_eq!(untracked.parent_ids().count(), 0);
+    let untracked_tree = untracked.tree()?;
+    assert!(untracked_tree.find_entry("untracked").is_some());
+    assert!(
+        untracked_tree.find_entry("file.ignored").is_none(),
+        "ignored files stay"
+    );
+    let mut tree = stash.tree()?;
+    assert!(tree.find_entry("b").is_none(), "deletions are recorded");
+    assert_eq!(
+        tree.peel_to_entry_by_path("dir/c")?.expect("present").object()?.data,
+        b"c modified\n"
+    );
+
+    assert_eq!(read(&repo, "a")?, "a\n", "the worktree was reset");
+    assert_eq!(read(&repo, "b")?, "b\n");
+    assert_eq!(read(&repo, "dir/c")?, "c\n");
+    assert!(read(&repo, "untracked").is_err(), "untracked files were removed");
+    assert_eq!(read(&repo, "file.ignored")?, "ignored\n");
+    assert!(!repo.is_dirty()?);
+    assert_eq!(messages(&repo)?.len(), 3);
+    assert_eq!(messages(&repo)?[0], "On main: with untracked");
+
+    let outcome = repo.stash().pop(0)?;
+    assert!(outcome.conflicts.is_empty());
+    assert_eq!(outcome.tree, stash.tree_id()?);
+    assert_eq!(read(&repo, "a")?, "a modified\n");
+    assert!(read(&repo, "b").is_err(), "the deletion was restored");
+    assert_eq!(read(&repo, "dir/c")?, "c modified\n");
+    assert_eq!(read(&repo, "untracked")?, "untracked\n");
+    let index = repo.open_index()?;
+    assert!(
+        index.entry_by_path("untracked".into()).is_none(),
+        "untracked files remain untracked"
+    );
+    assert!(index.entry_by_path("b".into()).is_some(), "changes aren't staged");
+    assert!(repo.is_dirty()?);
+    assert_eq!(messages(&repo)?.len(), 2, "the stash was dropped");
+    Ok(())
+}
+
+#[test]
+fn push_with_keep_index() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    write(&repo, "a", "a staged\n")?;
+    let mut index = repo.open_index()?;
+    let (mut pipeline, _) = repo.filter_pipeline(None)?;
+    let (id, _, _) = pipeline
+        .worktree_file_to_object("a".into(), &index)?
+        .expect("file exists");
+    index
+        .entry_mut_by_path_and_stage("a".into(), gix::index::entry::Stage::Unconflicted)
+        .expect("tracked")
+        .id = id;
+    index.write(Default::default())?;
+    write(&repo, "a", "a staged and modified\n")?;
+    write(&repo, "b", "b modified\n")?;
+
+    let stash = repo
+        .stash()
+        .push(Options {
+            keep_index: true,
+            ..Default::default()
+        })?
+        .expect("changes were stashed")
+        .object()?
+        .into_commit();
+    let index_commit = repo.find_commit(stash.parent_ids().nth(1).expect("index"))?;
+    assert_eq!(
+        index_commit
+            .tree()?
+            .peel_to_entry_by_path("a")?
+            .expect("present")
+            .object_id(),
+        id
+    );
+    assert_eq!(read(&repo, "a")?, "a staged\n", "the worktree matches the index");
+    assert_eq!(read(&repo, "b")?, "b\n");
+    assert_eq!(
+        repo.open_index()?.entry_by_path("a".into()).expect("present").id,
+        id,
+        "the index is kept"
+    );
+    Ok(())
+}
+
+#[test]
+fn apply_with_conflicts_keeps_the_stash() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    write(&repo, "a", "a committed\n")?;
+    let mut editor = repo.edit_tree(repo.head_tree_id()?)?;
+    editor.upsert(
+        "a",
+        gix::object::tree::EntryKind::Blob,
+        repo.write_blob("a committed\n")?,
+    )?;
+    let tree = editor.write()?;
+    let mut index = repo.index_from_tree(&tree)?;
+    index.write(Default::default())?;
+    repo.commit("HEAD", "change a", tree, [repo.head_id()?])?;
+
+    let err = repo.stash().apply(2).unwrap_err();
+    assert!(matches!(err, gix::stash::apply::Error::NotFound { index: 2 }));
+
+    write(&repo, "a", "a local\n")?;
+    let err = repo.stash().apply(1).unwrap_err();
+    assert!(
+        matches!(&err, gix::stash::apply::Error::WouldOverwriteLocalChanges { paths } if paths == &[BString::from("a")]),
+        "{err:?}"
+    );
+    write(&repo, "a", "a committed\n")?;
+
+    let outcome = repo.stash().pop(1)?;
+    assert!(outcome.has_unresolved_conflicts(Default::default()));
+    assert_eq!(outcome.conflicts.len(), 1);
+    let content = read(&repo, "a")?;
+    assert!(content.contains("<<<<<<< Updated upstream"), "{content}");
+    assert!(content.contains(">>>>>>> Stashed changes"), "{content}");
+    let index = repo.open_index()?;
+    assert_eq!(
+        index
+            .entries()
+            .iter()
+            .filter(|entry| entry.path(&index) == "a")
+            .map(gix::index::Entry::stage_raw)
+            .collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+    assert_eq!(messages(&repo)?.len(), 2, "the stash is kept when there are conflicts");
+    Ok(())
+}
+
+#[test]
+fn drop() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let stashes = repo.stash().list()?;
+    assert!(matches!(
+        repo.stash().drop(2),
+        Err(gix::stash::drop::Error::NotFound { index: 2 })
+    ));
+
+    assert_eq!(repo.stash().drop(1)?, stashes[1].id);
+    assert_eq!(repo.find_reference("refs/stash")?.id(), stashes[0].id);
+    let remaining = repo.stash().list()?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, stashes[0].id);
+    let stash = repo.find_reference("refs/stash")?;
+    let mut log = stash.log_iter();
+    let log = log.all()?.expect("present").collect::<Result<Vec<_>, _>>()?;
+    assert!(log[0].previous_oid().is_null(), "the log is rewritten to be consistent");
+
+    assert_eq!(repo.stash().drop(0)?, stashes[0].id);
+    assert!(repo.try_find_reference("refs/stash")?.is_none());
+    assert!(repo.stash().list()?.is_empty());
+    Ok(())
+}
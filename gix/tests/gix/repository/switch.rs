@@ -0,0 +1,192 @@
+use gix::repository::switch::{Error, Options};
+
+use crate::util::{bstrings, exists, read, repo_rw, write};
+
+fn head_log(repo: &gix::Repository) -> crate::Result<Vec<(gix::ObjectId, gix::ObjectId, String)>> {
+    let head = repo.find_reference("HEAD")?;
+    let mut log = head.log_iter();
+    Ok(log
+        .all()?
+        .expect("present")
+        .map(|line| line.map(|line| (line.previous_oid(), line.new_oid(), line.message.to_string())))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn to_branch_and_back() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    let main = repo.head_id()?.detach();
+    let other = repo.find_reference("other")?.into_fully_peeled_id()?.detach();
+
+    let out = repo.switch("other", Options::default())?;
+    assert_eq!(out.tree, repo.find_commit(other)?.tree_id()?);
+    assert_eq!(out.updated, bstrings(&["a", "c", "dir/b", "dir/new", "new-dir/x"]));
+    assert!(out.local_changes.is_empty());
+    assert_eq!(read(&repo, "a")?, "a in other\n");
+    assert!(!exists(&repo, "c"));
+    assert_eq!(read(&repo, "new-dir/x")?, "x\n");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(repo.workdir().expect("non-bare").join("dir/b"))?
+            .permissions()
+            .mode();
+        assert_ne!(mode & 0o111, 0, "the executable bit is set");
+    }
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/other");
+    let mut index = repo.open_index()?;
+    assert_eq!(repo.write_tree(&mut index)?, out.tree);
+    assert!(!repo.is_dirty()?, "the worktree and index match the new HEAD");
+
+    let out = repo.switch("refs/heads/main", Options::default())?;
+    assert_eq!(out.updated, bstrings(&["a", "c", "dir/b", "dir/new", "new-dir/x"]));
+    assert!(!exists(&repo, "new-dir"), "directories that become empty are removed");
+    assert_eq!(read(&repo, "c")?, "c\n");
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    assert!(!repo.is_dirty()?);
+
+    let log = head_log(&repo)?;
+    assert_eq!(
+        log[log.len() - 2..],
+        [
+            (main, other, "checkout: moving from main to other".into()),
+            (other, main, "checkout: moving from other to refs/heads/main".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn detached() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    let main = repo.head_id()?.detach();
+    let other = repo.find_reference("other")?.into_fully_peeled_id()?.detach();
+
+    repo.switch(
+        "other",
+        Options {
+            detach: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(repo.head()?.is_detached());
+    assert_eq!(repo.head_id()?, other);
+    assert!(!repo.is_dirty()?);
+
+    repo.switch(main.to_string().as_str(), Options::default())?;
+    assert!(
+        repo.head()?.is_detached(),
+        "commits that aren't branches are always detached"
+    );
+    assert_eq!(repo.head_id()?, main);
+    assert!(!repo.is_dirty()?);
+
+    let log = head_log(&repo)?;
+    assert_eq!(
+        log[log.len() - 2..],
+        [
+            (main, other, "checkout: moving from main to other".into()),
+            (other, main, format!("checkout: moving from {other} to {main}")),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn local_changes_are_carried_over_or_protected() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    write(&repo, "same", "same, but changed\n")?;
+    write(&repo, "c", "c, but changed\n")?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, Some("c"), Default::default())?;
+    index.write(Default::default())?;
+    write(&repo, "c", "c\n")?;
+    std::fs::remove_file(repo.workdir().expect("non-bare").join("a"))?;
+
+    let err = repo.switch("other", Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::WouldOverwriteLocalChanges { paths } if *paths == bstrings(&["c"])),
+        "{err:?}: staged changes would be lost when deleting the file"
+    );
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+
+    write(&repo, "a", "a, but changed\n")?;
+    repo.add_to_index(&mut index, Some("c"), Default::default())?;
+    index.write(Default::default())?;
+    let err = repo.switch("other", Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::WouldOverwriteLocalChanges { paths } if *paths == bstrings(&["a"])),
+        "{err:?}: the file was modified in the worktree"
+    );
+    assert_eq!(read(&repo, "a")?, "a, but changed\n", "nothing was changed");
+
+    std::fs::remove_file(repo.workdir().expect("non-bare").join("a"))?;
+    let out = repo.switch("other", Options::default())?;
+    assert_eq!(
+        out.local_changes,
+        bstrings(&["same"]),
+        "changes to files that are the same in both trees are kept"
+    );
+    assert_eq!(read(&repo, "same")?, "same, but changed\n");
+    assert_eq!(read(&repo, "a")?, "a in other\n", "deleted files are expendable");
+    assert!(!exists(&repo, "c"));
+    Ok(())
+}
+
+#[test]
+fn untracked_files_are_protected() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    write(&repo, "dir/new", "untracked\n")?;
+    write(&repo, "new-dir", "untracked file in place of a directory\n")?;
+    let err = repo.switch("other", Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::WouldOverwriteUntrackedFiles { paths } if *paths == bstrings(&["dir/new", "new-dir/x"])),
+        "{err:?}"
+    );
+
+    std::fs::remove_file(repo.workdir().expect("non-bare").join("new-dir"))?;
+    std::fs::create_dir(repo.workdir().expect("non-bare").join("new-dir"))?;
+    write(&repo, "new-dir/x.ignored", "ignored files are expendable\n")?;
+    let err = repo.switch("other", Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::WouldOverwriteUntrackedFiles { paths } if *paths == bstrings(&["dir/new"])),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn force_discards_all_changes() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_switch_repo.sh")?;
+    write(&repo, "a", "a, but changed\n")?;
+    write(&repo, "same", "same, but changed\n")?;
+    write(&repo, "dir/new", "untracked\n")?;
+
+    let out = repo.switch(
+        "other",
+        Options {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        out.updated,
+        bstrings(&["a", "c", "dir/b", "dir/new", "new-dir/x", "same"])
+    );
+    assert!(out.local_changes.is_empty());
+    assert_eq!(read(&repo, "a")?, "a in other\n");
+    assert_eq!(read(&repo, "same")?, "same\n");
+    assert_eq!(read(&repo, "dir/new")?, "new\n");
+    assert!(!repo.is_dirty()?);
+    Ok(())
+}
+
+#[test]
+fn bare_repositories_are_rejected() -> crate::Result {
+    let repo = crate::named_subrepo_opts("make_basic_repo.sh", "bare.git", gix::open::Options::isolated())?;
+    assert!(matches!(
+        repo.checkout_tree(repo.empty_tree().id, Options::default()),
+        Err(Error::BareRepository)
+    ));
+    Ok(())
+}
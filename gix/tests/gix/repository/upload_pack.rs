@@ -0,0 +1,196 @@
+use std::{sync::atomic::AtomicBool, thread::JoinHandle};
+
+use gix::{protocol::transport::Protocol, remote::fetch::Shallow};
+use gix_testtools::tempfile::TempDir;
+
+use crate::remote;
+
+/// Serve `repo` to the first client connecting via `git://`, and return the url to connect to.
+fn serve_once(
+    repo: gix::Repository,
+) -> crate::Result<(
+    String,
+    JoinHandle<Result<gix::protocol::upload_pack::Outcome, gix::repository::upload_pack::Error>>,
+)> {
+    let repo = repo.into_sync();
+    Ok(remote::serve_over_git_once(
+        "git-upload-pack",
+        move |stream, protocol| {
+            repo.to_thread_local()
+                .upload_pack(stream, stream, protocol, &AtomicBool::default())
+        },
+    )?)
+}
+
+fn clone(url: &str, protocol: Protocol, shallow: Shallow) -> crate::Result<(gix::Repository, TempDir)> {
+    let tmp = TempDir::new()?;
+    let (repo, _outcome) = gix::clone::PrepareFetch::new(
+        url,
+        tmp.path(),
+        gix::create::Kind::Bare,
+        Default::default(),
+        crate::restricted().config_overrides([format!("protocol.version={}", protocol as u8)]),
+    )?
+    .with_shallow(shallow)
+    .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+    Ok((repo, tmp))
+}
+
+/// Assert that all commits, trees and blobs reachable from `tips` in `source` are present in `clone`.
+fn assert_objects_complete(source: &gix::Repository, clone: &gix::Repository, tips: &[gix::ObjectId]) -> crate::Result {
+    for info in source.rev_walk(tips.iter().copied()).all()? {
+        let commit = clone.find_commit(info?.id)?;
+        for entry in commit.tree()?.traverse().breadthfirst.files()? {
+            if entry.mode.is_commit() {
+                continue;
+            }
+            assert!(clone.has_object(entry.oid), "{} must have been sent", entry.oid);
+        }
+    }
+    Ok(())
+}
+
+fn ref_tips(repo: &gix::Repository) -> crate::Result<Vec<gix::ObjectId>> {
+    let mut out = Vec::new();
+    for r in repo.references()?.all()? {
+        out.push(r?.peel_to_id_in_place()?.detach());
+    }
+    Ok(out)
+}
+
+#[test]
+fn clone_with_all_protocol_versions() -> crate::Result {
+    for protocol in [Protocol::V0, Protocol::V1, Protocol::V2] {
+        let source = remote::repo("base");
+        let (url, server) = serve_once(source.clone())?;
+        let (clone, _tmp) = clone(&url, protocol, Shallow::NoChange)?;
+        let outcome = server.join().expect("no panic")?;
+        assert_eq!(outcome.packs, 1, "{protocol:?}");
+        assert_eq!(
+            outcome.ls_refs,
+            usize::from(protocol == Protocol::V2),
+            "{protocol:?}: references are only listed with a command in V2"
+        );
+
+        let source_tips = ref_tips(&source)?;
+        assert_objects_complete(&source, &clone, &source_tips)?;
+        assert_eq!(
+            clone.head_id()?,
+            source.head_id()?,
+            "{protocol:?}: HEAD is advertised and checked out"
+        );
+        for name in ["refs/remotes/origin/main", "refs/tags/b-tag", "refs/tags/i-tag"] {
+            let source_name = name.replace("remotes/origin", "heads");
+            assert_eq!(
+                clone.find_reference(name)?.target().id(),
+                source.find_reference(source_name.as_str())?.target().id(),
+                "{protocol:?}: {name} points to the same object, annotated tags included"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn shallow_clone() -> crate::Result {
+    for protocol in [Protocol::V1, Protocol::V2] {
+        let source = remote::repo("base");
+        let (url, server) = serve_once(source.clone())?;
+        let (clone, _tmp) = clone(&url, protocol, Shallow::DepthAtRemote(1.try_into()?))?;
+        let outcome = server.join().expect("no panic")?;
+
+        let shallow = clone.shallow_commits()?.expect("shallow");
+        let head = clone.head_id()?.detach();
+        assert!(shallow.contains(&head), "{protocol:?}: HEAD is the shallow boundary");
+        assert!(
+            outcome.objects < remote::repo("base").objects.iter()?.count(),
+            "{protocol:?}: history isn't sent"
+        );
+        assert_objects_complete(&clone, &clone, &[head])?;
+    }
+    Ok(())
+}
+
+#[test]
+fn clone_with_reachability_bitmap() -> crate::Result {
+    let tmp = TempDir::new()?;
+    gix_testtools::copy_recursively_into_existing_dir(remote::repo_path("base"), tmp.path())?;
+    assert!(gix_testtools::run_git(tmp.path(), &["repack", "-adbq"])?.success());
+    let source = gix::open_opts(tmp.path(), gix::open::Options::isolated())?;
+    assert!(
+        source.bitmap_index_if_enabled()?.is_some(),
+        "the bitmap of the pack is discovered"
+    );
+
+    let (url, server) = serve_once(source.clone())?;
+    let (clone, _clone_tmp) = self::clone(&url, Protocol::V2, Shallow::NoChange)?;
+    let outcome = server.join().expect("no panic")?;
+    assert_objects_complete(&source, &clone, &ref_tips(&source)?)?;
+
+    let (url, server) = serve_once(remote::repo("base"))?;
+    let (_clone, _clone_tmp) = self::clone(&url, Protocol::V2, Shallow::NoChange)?;
+    assert_eq!(
+        outcome.objects,
+        server.join().expect("no panic")?.objects,
+        "the same objects are sent, just without traversing all trees"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+fn clone_with_bundle_uri() -> crate::Result {
+    let tmp = TempDir::new()?;
+    let bundle_path = tmp.path().join("main.bundle");
+    assert!(gix_testtools::run_git(
+        &remote::repo_path("base"),
+        &["bundle", "create", bundle_path.to_str().expect("valid UTF-8"), "main"]
+    )?
+    .success());
+    let (bundle_url, http_server) = remote::serve_over_http_once("main.bundle", std::fs::read(&bundle_path)?)?;
+    let source = gix::open_opts(
+        remote::repo_path("base"),
+        gix::open::Options::isolated().config_overrides([
+            "uploadpack.advertiseBundleURIs=true".to_owned(),
+            "bundle.version=1".into(),
+            "bundle.mode=all".into(),
+            format!("bundle.main.uri={bundle_url}"),
+        ]),
+    )?;
+
+    let (url, server) = serve_once(source.clone())?;
+    let clone_tmp = TempDir::new()?;
+    let (clone, _outcome) = gix::clone::PrepareFetch::new(
+        url.as_str(),
+        clone_tmp.path(),
+        gix::create::Kind::Bare,
+        Default::default(),
+        crate::restricted().config_overrides([
+            "protocol.version=2",
+            "transfer.bundleURI=true",
+            "fetch.uriProtocols=http",
+        ]),
+    )?
+    .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+    let outcome = server.join().expect("no panic")?;
+    http_server.join().expect("no panic")?;
+
+    assert_eq!(outcome.bundle_uri, 1, "the bundle list was requested");
+    assert_eq!(
+        clone.find_reference("refs/bundles/heads/main")?.target().id(),
+        source.find_reference("refs/heads/main")?.target().id(),
+        "the tips of the bundle are remembered"
+    );
+    assert_objects_complete(&source, &clone, &ref_tips(&source)?)?;
+
+    let (url, server) = serve_once(remote::repo("base"))?;
+    let (_clone, _clone_tmp) = self::clone(&url, Protocol::V2, Shallow::NoChange)?;
+    assert!(
+        outcome.objects < server.join().expect("no panic")?.objects,
+        "objects in the bundle aren't sent again"
+    );
+    Ok(())
+}
@@ -232,6 +232,282 @@ mod baseline {
     }
 }
 
+#[cfg(all(feature = "worktree-mutation", feature = "revision"))]
+mod management {
+    use gix::{
+        bstr::ByteSlice,
+        repository::add_worktree,
+        worktree::{proxy::Prunable, Repair},
+    };
+
+    fn repo() -> crate::Result<(gix_testtools::tempfile::TempDir, gix::Repository)> {
+        // Linked worktrees are recorded with absolute paths, so the fixture can't be copied.
+        let dir = gix_testtools::scripted_fixture_writable_with_args(
+            "make_worktree_repo.sh",
+            None::<String>,
+            gix_testtools::Creation::ExecuteScript,
+        )?;
+        let repo = gix::open_opts(dir.path().join("repo"), crate::restricted())?;
+        Ok((dir, repo))
+    }
+
+    fn proxy<'repo>(repo: &'repo gix::Repository, id: &str) -> gix::worktree::Proxy<'repo> {
+        repo.worktrees()
+            .unwrap()
+            .into_iter()
+            .find(|proxy| proxy.id() == id)
+            .expect("worktree exists")
+    }
+
+    #[test]
+    fn add_creates_a_branch_named_after_the_worktree_and_checks_it_out() -> crate::Result {
+        let (tmp, repo) = repo()?;
+        let path = tmp.path().join("new-wt");
+        let wt = repo.add_worktree(&path, None, &Default::default(), &Default::default())?;
+
+        assert_eq!(wt.head_name()?.expect("not detached").as_bstr(), "refs/heads/new-wt");
+        assert_eq!(wt.head_id()?, repo.head_id()?, "the branch starts at HEAD");
+        assert_eq!(std::fs::read(path.join("a"))?, b"hello\n", "files are checked out");
+        assert!(path.join("dir/c").is_file());
+        assert!(wt.index_path().is_file(), "the index was written");
+
+        let proxy = proxy(&repo, "new-wt");
+        assert!(!proxy.is_locked(), "the lock is only held while initializing");
+        assert_eq!(proxy.base()?, gix::path::realpath(&path)?);
+        assert_eq!(proxy.prunable(), None);
+        assert_eq!(wt.index()?.entries().len(), 3, "the index matches the checked out tree");
+        Ok(())
+    }
+
+    #[test]
+    fn add_with_commitish_detach_lock_and_no_checkout() -> crate::Result {
+        let (tmp, repo) = repo()?;
+        let path = tmp.path().join("detached");
+        let wt = repo.add_worktree(
+            &path,
+            Some("HEAD~1".into()),
+            &add_worktree::Options {
+                no_checkout: true,
+                lock: Some("on a usb stick".into()),
+                ..Default::default()
+            },
+            &Default::default(),
+        )?;
+        assert_eq!(wt.head_name()?, None, "commits that aren't branches are detached");
+        assert_eq!(wt.head_id()?, repo.rev_parse_single("HEAD~1")?);
+        assert!(!path.join("a").exists(), "nothing was checked out");
+        assert_eq!(
+            proxy(&repo, "detached").lock_reason().expect("locked"),
+            "on a usb stick"
+        );
+
+        let wt = repo.add_worktree(
+            tmp.path().join("detached"),
+            Some("main".into()),
+            &Default::default(),
+            &Default::default(),
+        );
+        assert!(
+            matches!(wt, Err(add_worktree::Error::PathExists { .. })),
+            "the worktree directory isn't empty anymore"
+        );
+
+        let wt = repo.add_worktree(
+            tmp.path().join("other"),
+            Some("main".into()),
+            &add_worktree::Options {
+                detach: true,
+                ..Default::default()
+            },
+            &Default::default(),
+        )?;
+        assert_eq!(wt.head_name()?, None, "--detach works on branches as well");
+        Ok(())
+    }
+
+    #[test]
+    fn add_refuses_branches_that_are_checked_out_elsewhere() -> crate::Result {
+        let (tmp, repo) = repo()?;
+        for branch in ["main", "wt-a"] {
+            let err = repo
+                .add_worktree(
+                    tmp.path().join("new"),
+                    Some(branch.into()),
+                    &Default::default(),
+                    &Default::default(),
+                )
+                .unwrap_err();
+            assert!(matches!(err, add_worktree::Error::BranchCheckedOut { .. }), "{err:?}");
+        }
+        assert!(!tmp.path().join("new").exists(), "nothing is left behind on error");
+        assert!(!repo.common_dir().join("worktrees/new").exists());
+
+        let err = repo
+            .add_worktree(
+                tmp.path().join("new"),
+                None,
+                &add_worktree::Options {
+                    new_branch: Some("wt-c-locked".into()),
+                    ..Default::default()
+                },
+                &Default::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, add_worktree::Error::BranchExists { .. }), "{err:?}");
+
+        let wt = repo.add_worktree(
+            tmp.path().join("new"),
+            Some("wt-a".into()),
+            &add_worktree::Options {
+                force: true,
+                ..Default::default()
+            },
+            &Default::default(),
+        )?;
+        assert_eq!(
+            wt.head_name()?.expect("not detached").as_bstr(),
+            "refs/heads/wt-a",
+            "forcing allows to check out branches twice"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lock_and_unlock() -> crate::Result {
+        let (_tmp, repo) = repo()?;
+        let wt = proxy(&repo, "wt-b");
+        wt.lock(Some("reason".into()))?;
+        assert_eq!(wt.lock_reason().expect("locked"), "reason");
+        assert!(matches!(
+            wt.lock(None),
+            Err(gix::worktree::proxy::lock::Error::AlreadyLocked { .. })
+        ));
+        wt.unlock()?;
+        assert!(!wt.is_locked());
+        assert!(matches!(
+            wt.unlock(),
+            Err(gix::worktree::proxy::lock::Error::NotLocked { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn move_to() -> crate::Result {
+        let (tmp, repo) = repo()?;
+        assert!(
+            matches!(
+                proxy(&repo, "wt-c-locked").move_to(tmp.path().join("elsewhere")),
+                Err(gix::worktree::proxy::move_to::Error::Locked { .. })
+            ),
+            "locked worktrees can't be moved"
+        );
+
+        let new_base = proxy(&repo, "wt-b").move_to(tmp.path().join("moved"))?;
+        assert_eq!(new_base, gix::path::realpath(tmp.path().join("moved"))?);
+        assert!(!tmp.path().join("wt-b").exists());
+        let wt = proxy(&repo, "wt-b");
+        assert_eq!(wt.base()?, new_base);
+        assert_eq!(wt.prunable(), None);
+        assert_eq!(
+            gix::open(&new_base)?.worktree().expect("linked").id(),
+            Some("wt-b".into())
+        );
+
+        let new_base = proxy(&repo, "wt-b").move_to(tmp.path().join("prev"))?;
+        assert_eq!(
+            new_base,
+            gix::path::realpath(tmp.path().join("prev/moved"))?,
+            "moving into an existing directory keeps the name"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn remove() -> crate::Result {
+        let (tmp, repo) = repo()?;
+        assert!(matches!(
+            proxy(&repo, "wt-c-locked").remove(false),
+            Err(gix::worktree::proxy::remove::Error::Locked { .. })
+        ));
+
+        std::fs::write(tmp.path().join("wt-b/a"), "changed")?;
+        assert!(matches!(
+            proxy(&repo, "wt-b").remove(false),
+            Err(gix::worktree::proxy::remove::Error::LocalModifications { .. })
+        ));
+        assert!(tmp.path().join("wt-b").is_dir(), "nothing was removed");
+
+        proxy(&repo, "wt-b").remove(true)?;
+        proxy(&repo, "wt-c-locked").remove(true)?;
+        assert!(!tmp.path().join("wt-b").exists());
+        assert!(!repo.common_dir().join("worktrees/wt-b").exists());
+        assert!(!tmp.path().join("wt-c-locked").exists());
+        assert!(repo
+            .worktrees()?
+            .iter()
+            .all(|wt| wt.id() != "wt-b" && wt.id() != "wt-c-locked"));
+        Ok(())
+    }
+
+    #[test]
+    fn prune() -> crate::Result {
+        let (tmp, repo) = repo()?;
+        std::fs::remove_dir_all(tmp.path().join("wt-c-locked"))?;
+        assert_eq!(proxy(&repo, "wt-deleted").prunable(), Some(Prunable::WorktreeMissing));
+        assert_eq!(
+            proxy(&repo, "wt-c-locked").prunable(),
+            None,
+            "locked worktrees are never pruned"
+        );
+
+        let expected = vec![("wt-deleted".into(), Prunable::WorktreeMissing)];
+        assert_eq!(repo.prune_worktrees(true)?, expected);
+        assert!(
+            repo.common_dir().join("worktrees/wt-deleted").is_dir(),
+            "dry-runs don't change anything"
+        );
+        assert_eq!(repo.prune_worktrees(false)?, expected);
+        assert!(!repo.common_dir().join("worktrees/wt-deleted").exists());
+        assert!(repo.prune_worktrees(false)?.is_empty());
+        assert_eq!(repo.worktrees()?.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn repair() -> crate::Result {
+        let (tmp, repo) = repo()?;
+        assert!(
+            repo.repair_worktrees(None::<&std::path::Path>)?.is_empty(),
+            "nothing to repair"
+        );
+
+        let new_path = tmp.path().join("manually-moved");
+        std::fs::rename(tmp.path().join("wt-b"), &new_path)?;
+        assert_eq!(proxy(&repo, "wt-b").prunable(), Some(Prunable::WorktreeMissing));
+        assert_eq!(
+            repo.repair_worktrees([&new_path])?,
+            vec![Repair::GitDirFile { id: "wt-b".into() }]
+        );
+        assert_eq!(proxy(&repo, "wt-b").base()?, gix::path::realpath(&new_path)?);
+
+        let dot_git = tmp.path().join("wt-a/.git");
+        std::fs::write(&dot_git, "gitdir: /does/not/exist\n")?;
+        assert_eq!(
+            repo.repair_worktrees(None::<&std::path::Path>)?,
+            vec![Repair::DotGitFile {
+                path: gix::path::realpath(&dot_git)?
+            }]
+        );
+        assert!(std::fs::read(&dot_git)?.starts_with_str("gitdir: "));
+        assert_eq!(
+            gix::open(tmp.path().join("wt-a"))?.worktree().expect("linked").id(),
+            Some("wt-a".into())
+        );
+        Ok(())
+    }
+}
+
 #[test]
 fn from_bare_parent_repo() {
     if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 31, 0) {
@@ -312,6 +588,11 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
             expected.prunable.is_none(),
             "in our case prunable repos have no worktree base"
         );
+        assert_eq!(
+            actual.prunable().is_some(),
+            expected.prunable.is_some(),
+            "we agree with git about which worktrees are prunable"
+        );
 
         let repo = if base.is_dir() {
             let repo = actual.into_repo().unwrap();
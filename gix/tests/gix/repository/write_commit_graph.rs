@@ -0,0 +1,121 @@
+use gix::repository::write_commit_graph::{Mode, Options};
+
+use crate::util::repo_rw;
+
+fn assert_graph(repo: &gix::Repository, expected_layers: usize, expected_commits: u32) -> crate::Result {
+    let graph = repo.commit_graph()?;
+    assert_eq!(graph.files().len(), expected_layers);
+    assert_eq!(graph.num_commits(), expected_commits);
+    assert!(graph.files().iter().all(gix::commitgraph::File::has_generation_data));
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    Ok(())
+}
+
+#[test]
+fn split_then_single() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let info_dir = repo.objects.store_ref().path().join("info");
+    assert_graph(&repo, 1, 3)?;
+    let head = repo.head_id()?.detach();
+
+    let split = Options {
+        mode: Mode::Split { size_multiple: 2 },
+        ..Default::default()
+    };
+    let outcome = repo.write_commit_graph(Some(head), split)?;
+    assert!(outcome.checksum.is_some());
+    assert_eq!(outcome.num_commits, 1, "only the merge commit is new");
+    assert_eq!(outcome.num_layers, 2, "the existing graph is large enough to be kept");
+    assert!(
+        !info_dir.join("commit-graph").exists(),
+        "the monolithic graph was moved into the chain"
+    );
+    assert_graph(&repo, 2, 4)?;
+
+    let outcome = repo.write_commit_graph(Some(head), split)?;
+    assert_eq!(outcome.checksum, None, "nothing to do");
+    assert_eq!(outcome.num_layers, 2);
+
+    let outcome = repo.write_commit_graph(Some(head), Options::default())?;
+    assert_eq!(outcome.num_commits, 4);
+    assert_eq!(outcome.num_layers, 1);
+    assert!(
+        !info_dir.join("commit-graphs").join("commit-graph-chain").exists(),
+        "the chain is replaced by a single file"
+    );
+    assert_graph(&repo, 1, 4)
+}
+
+#[test]
+fn split_layers_are_merged_if_small() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let head = repo.head_id()?.detach();
+    let outcome = repo.write_commit_graph(
+        Some(head),
+        Options {
+            mode: Mode::Split { size_multiple: 3 },
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.num_commits, 4,
+        "the existing graph was merged into the new layer"
+    );
+    assert_eq!(outcome.num_layers, 1);
+    assert!(!repo
+        .objects
+        .store_ref()
+        .path()
+        .join("info")
+        .join("commit-graph")
+        .exists());
+    assert_graph(&repo, 1, 4)
+}
+
+#[test]
+fn generation_version_1_omits_corrected_commit_dates() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::CommitGraph::GENERATION_VERSION, "1")?;
+    let head = repo.head_id()?.detach();
+    repo.write_commit_graph(Some(head), Options::default())?;
+    let graph = repo.commit_graph()?;
+    assert!(!graph.files()[0].has_generation_data());
+    assert_eq!(graph.num_commits(), 4);
+    Ok(())
+}
+
+#[test]
+fn changed_paths_are_written_like_git_does() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_commit_graph_changed_paths_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let outcome = repo.write_commit_graph(
+        Some(head),
+        Options {
+            changed_paths: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.num_commits, 5);
+
+    let info_dir = repo.objects.store_ref().path().join("info");
+    assert_eq!(
+        std::fs::read(info_dir.join("commit-graph"))?,
+        std::fs::read(tmp.path().join("expected-commit-graph"))?,
+        "generation data and Bloom filters are the same"
+    );
+
+    let graph = repo.commit_graph()?;
+    let commit = graph.commit_by_id(head).expect("present");
+    assert_eq!(commit.maybe_contains_path("many/1".into()), Some(true));
+
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    std::fs::copy(tmp.path().join("expected-commit-graph"), info_dir.join("commit-graph"))?;
+    let outcome = repo.write_commit_graph(Some(head), Options::default())?;
+    assert_eq!(outcome.num_commits, 5);
+    assert!(
+        repo.commit_graph()?.files()[0].bloom_filter_settings().is_some(),
+        "existing Bloom filters are kept"
+    );
+    Ok(())
+}
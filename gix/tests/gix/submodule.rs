@@ -419,3 +419,372 @@ mod open {
         Ok(())
     }
 }
+
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "status"
+))]
+mod lifecycle {
+    use std::{path::Path, sync::atomic::AtomicBool};
+
+    use gix::{
+        bstr::{BString, ByteSlice},
+        submodule::{config::Update, update},
+    };
+
+    use crate::util::restricted;
+
+    fn writable_repo(tmp: &Path, name: &str) -> crate::Result<gix::Repository> {
+        Ok(gix::open_opts(tmp.join(name), restricted())?)
+    }
+
+    /// Point the url of the initialized submodule `name` to `module1` in `tmp`, as the fixture contains absolute urls of the location it was created in.
+    fn set_url_in_memory(repo: &mut gix::Repository, name: &str, tmp: &Path) -> crate::Result {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value_by(
+            "submodule",
+            Some(name.into()),
+            "url",
+            gix::path::into_bstr(tmp.join("module1")).as_ref(),
+        )?;
+        config.commit()?;
+        Ok(())
+    }
+
+    fn find<'repo>(repo: &'repo gix::Repository, name: &str) -> crate::Result<gix::Submodule<'repo>> {
+        Ok(repo.try_find_submodule(name.into())?.expect("submodule present"))
+    }
+
+    #[test]
+    fn update_clones_and_checks_out_initialized_submodule() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "with-submodules-after-clone")?;
+        set_url_in_memory(&mut repo, "m1", tmp.path())?;
+
+        let outcome = repo.update_submodule("m1".into(), &Default::default(), &AtomicBool::default())?;
+        assert!(outcome.cloned, "the repository didn't exist yet");
+        assert_eq!(outcome.change, update::Change::CheckedOut);
+
+        let sm = find(&repo, "m1")?;
+        assert_eq!(
+            sm.git_dir(),
+            repo.git_dir().join("modules").join("m1"),
+            "the repository is cloned into the superproject"
+        );
+        let sm_repo = sm.open()?.expect("cloned");
+        assert_eq!(Some(sm_repo.head_id()?.detach()), outcome.commit);
+        assert!(
+            sm_repo.head()?.is_detached(),
+            "the recorded commit is checked out detached"
+        );
+        assert!(sm.work_dir()?.join("this").is_file(), "the worktree was checked out");
+        assert!(!sm_repo.is_dirty()?, "the index matches the worktree");
+
+        let outcome = repo.update_submodule("m1".into(), &Default::default(), &AtomicBool::default())?;
+        assert!(!outcome.cloned);
+        assert_eq!(
+            outcome.change,
+            update::Change::UpToDate,
+            "nothing to do the second time"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update_skips_uninitialized_submodules_unless_init_is_set() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "modified-and-untracked")?;
+        repo.deinit_submodule("m1".into(), true)?;
+
+        let outcome = repo.update_submodule("m1".into(), &Default::default(), &AtomicBool::default())?;
+        assert_eq!(outcome.change, update::Change::Skipped, "not initialized");
+
+        let outcome = repo.update_submodules(
+            &update::Options {
+                init: true,
+                ..Default::default()
+            },
+            &AtomicBool::default(),
+        )?;
+        assert_eq!(outcome.len(), 1);
+        assert!(!outcome[0].cloned, "the repository in .git/modules is reused");
+        assert_eq!(outcome[0].change, update::Change::CheckedOut);
+        let sm = find(&repo, "m1")?;
+        assert!(sm.state()?.superproject_configuration, "it was initialized");
+        assert!(
+            sm.work_dir()?.join("this").is_file(),
+            "the worktree was checked out again"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update_checks_out_the_recorded_commit() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "submodule-head-changed")?;
+
+        let outcome = repo.update_submodule(
+            "m1".into(),
+            &update::Options {
+                mode: Some(Update::None),
+                ..Default::default()
+            },
+            &AtomicBool::default(),
+        )?;
+        assert_eq!(outcome.change, update::Change::Skipped, "the update mode is `none`");
+
+        let outcome = repo.update_submodule("m1".into(), &Default::default(), &AtomicBool::default())?;
+        assert_eq!(outcome.change, update::Change::CheckedOut);
+        let sm = find(&repo, "m1")?;
+        assert_eq!(
+            Some(sm.open()?.expect("cloned").head_id()?.detach()),
+            sm.index_id()?,
+            "HEAD now matches the superproject"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "merge", feature = "revision"))]
+    fn update_by_merge_fast_forwards() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "submodule-head-changed")?;
+
+        let outcome = repo.update_submodule(
+            "m1".into(),
+            &update::Options {
+                mode: Some(Update::Merge),
+                ..Default::default()
+            },
+            &AtomicBool::default(),
+        )?;
+        assert_eq!(outcome.change, update::Change::Merged);
+        let sm = find(&repo, "m1")?;
+        assert_eq!(
+            Some(sm.open()?.expect("cloned").head_id()?.detach()),
+            sm.index_id()?,
+            "the recorded commit is a descendant of HEAD"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rebase")]
+    fn update_by_rebase() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "submodule-head-changed")?;
+
+        let outcome = repo.update_submodule(
+            "m1".into(),
+            &update::Options {
+                mode: Some(Update::Rebase),
+                ..Default::default()
+            },
+            &AtomicBool::default(),
+        )?;
+        assert_eq!(outcome.change, update::Change::Rebased);
+        let sm = find(&repo, "m1")?;
+        assert_eq!(
+            Some(sm.open()?.expect("cloned").head_id()?.detach()),
+            sm.index_id()?,
+            "there was nothing to replay on top of the recorded commit"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update_runs_commands() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "submodule-head-changed")?;
+
+        let outcome = repo.update_submodule(
+            "m1".into(),
+            &update::Options {
+                mode: Some(Update::Command("echo >commit".into())),
+                ..Default::default()
+            },
+            &AtomicBool::default(),
+        )?;
+        assert_eq!(outcome.change, update::Change::Command);
+        let sm = find(&repo, "m1")?;
+        assert_eq!(
+            std::fs::read(sm.work_dir()?.join("commit"))?.trim().as_bstr(),
+            outcome.commit.expect("recorded").to_string(),
+            "the command runs in the worktree with the commit as argument"
+        );
+        assert_ne!(
+            Some(sm.open()?.expect("cloned").head_id()?.detach()),
+            sm.index_id()?,
+            "the command is responsible for the update"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deinit_and_init() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "modified-and-untracked")?;
+
+        let err = repo.deinit_submodule("m1".into(), false).unwrap_err();
+        assert!(
+            matches!(err, gix::submodule::deinit::Error::LocalModifications { .. }),
+            "the submodule worktree is modified: {err:?}"
+        );
+
+        repo.deinit_submodule("m1".into(), true)?;
+        let sm = find(&repo, "m1")?;
+        assert_eq!(
+            sm.state()?,
+            gix::submodule::State {
+                repository_exists: true,
+                is_old_form: false,
+                worktree_checkout: false,
+                superproject_configuration: false,
+            },
+            "the worktree is empty and the configuration is gone, but the repository is kept"
+        );
+        assert_eq!(std::fs::read_dir(sm.work_dir()?)?.count(), 0);
+        assert!(
+            gix::open_opts(repo.git_dir(), restricted())?
+                .config_snapshot()
+                .string("submodule.m1.url")
+                .is_none(),
+            "the configuration file was changed as well"
+        );
+
+        assert!(repo.init_submodule("m1".into())?, "it was initialized");
+        assert!(!repo.init_submodule("m1".into())?, "nothing happens the second time");
+        let config = repo.config_snapshot();
+        assert_eq!(
+            config.string("submodule.m1.url").expect("set").as_ref(),
+            gix::path::into_bstr(gix::path::realpath(tmp.path())?.join("module1")).as_ref(),
+            "relative urls are resolved against the worktree if there is no remote"
+        );
+        assert_eq!(config.boolean("submodule.m1.active"), Some(true));
+        Ok(())
+    }
+
+    #[test]
+    fn deinit_refuses_old_form_submodules() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "old-form")?;
+        let err = repo.deinit_submodule("old".into(), true).unwrap_err();
+        assert!(matches!(err, gix::submodule::deinit::Error::OldForm { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn sync() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "with-submodules")?;
+
+        let expected = gix::path::into_bstr(gix::path::realpath(tmp.path())?.join("module1")).into_owned();
+        assert_eq!(repo.sync_submodule("m1".into())?, expected);
+        assert_eq!(
+            repo.config_snapshot().string("submodule.m1.url").expect("set").as_ref(),
+            expected,
+            "the superproject configuration is updated"
+        );
+        let sm_repo = find(&repo, "m1")?.open()?.expect("cloned");
+        assert_eq!(
+            sm_repo
+                .config_snapshot()
+                .string("remote.origin.url")
+                .expect("set")
+                .as_ref(),
+            expected,
+            "the remote of the submodule is updated"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn add() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut repo = writable_repo(tmp.path(), "submodule-head-changed")?;
+
+        let name = repo.add_submodule(
+            "../module1".into(),
+            "dir/m2".into(),
+            &Default::default(),
+            &AtomicBool::default(),
+        )?;
+        assert_eq!(name, "dir/m2", "the path is the default name");
+
+        let modules = std::fs::read(repo.workdir().expect("non-bare").join(".gitmodules"))?;
+        assert!(
+            modules.contains_str("[submodule \"dir/m2\"]\n\tpath = dir/m2\n\turl = ../module1\n"),
+            "the relative url is stored as is: {}",
+            modules.as_bstr()
+        );
+        let sm = find(&repo, "dir/m2")?;
+        assert!(sm.work_dir()?.join("this").is_file(), "the worktree was checked out");
+        assert_eq!(
+            sm.index_id()?,
+            Some(sm.open()?.expect("cloned").head_id()?.detach()),
+            "the index records HEAD of the new submodule"
+        );
+        assert_eq!(
+            sm.state()?,
+            gix::submodule::State {
+                repository_exists: true,
+                is_old_form: false,
+                worktree_checkout: true,
+                superproject_configuration: true,
+            }
+        );
+        let index = repo.open_index()?;
+        let entry = index.entry_by_path("dir/m2".into()).expect("added");
+        assert!(entry.mode.is_submodule());
+        assert!(
+            index.entry_by_path(".gitmodules".into()).is_some(),
+            ".gitmodules is added as well"
+        );
+
+        let err = repo
+            .add_submodule(
+                "../module1".into(),
+                "m1".into(),
+                &Default::default(),
+                &AtomicBool::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, gix::submodule::add::Error::NameExists { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_recurses_into_submodules_if_configured() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_submodules.sh")?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            tmp.path().join("with-submodules"),
+            tmp.path().join("clone"),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_recurse_submodules(true);
+        let (mut checkout, _) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+
+        let remote = repo.find_remote("origin")?;
+        let outcome = remote
+            .connect(gix::remote::Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(outcome.submodules.is_empty(), "recursion has to be enabled");
+
+        let outcome = remote
+            .connect(gix::remote::Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_recurse_submodules(Some(gix::submodule::config::FetchRecurse::Always))
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(
+            outcome.submodules.iter().map(|sm| sm.name.clone()).collect::<Vec<_>>(),
+            [BString::from("m1")],
+            "all populated submodules are fetched"
+        );
+        Ok(())
+    }
+}
@@ -75,6 +75,56 @@ pub fn repo_rw_opts(name: &str, opts: gix::open::Options) -> Result<(Repository,
     ))
 }
 
+/// Like [`repo_rw()`], but for the repository `name` within the directory produced by the `fixture` script.
+#[cfg(any(feature = "rebase", feature = "sequencer"))]
+pub fn named_subrepo_rw(fixture: &str, name: &str) -> Result<(Repository, tempfile::TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable(fixture)?;
+    let repo = gix::open_opts(tmp.path().join(name), restricted())?;
+    Ok((repo, tmp))
+}
+
+/// Return the content of the file at `path` in the worktree of `repo`.
+#[cfg(all(feature = "status", feature = "worktree-mutation"))]
+pub fn read(repo: &Repository, path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.workdir().expect("non-bare").join(path))
+}
+
+/// Write `content` to the file at `path` in the worktree of `repo`, creating leading directories as needed.
+#[cfg(feature = "status")]
+pub fn write(repo: &Repository, path: &str, content: &str) -> std::io::Result<()> {
+    let path = repo.workdir().expect("non-bare").join(path);
+    std::fs::create_dir_all(path.parent().expect("in worktree"))?;
+    std::fs::write(path, content)
+}
+
+/// Return `true` if `path` exists in the worktree of `repo`.
+#[cfg(feature = "status")]
+pub fn exists(repo: &Repository, path: &str) -> bool {
+    repo.workdir().expect("non-bare").join(path).exists()
+}
+
+#[cfg(feature = "status")]
+pub fn bstrings(paths: &[&str]) -> Vec<gix::bstr::BString> {
+    paths.iter().map(|path| (*path).into()).collect()
+}
+
+/// Return the content of the blob at `path` in `index`.
+#[cfg(feature = "status")]
+pub fn blob(repo: &Repository, index: &gix::index::State, path: &str) -> Result<Vec<u8>> {
+    let entry = index.entry_by_path(path.into()).expect("tracked");
+    Ok(repo.find_blob(entry.id)?.data.clone())
+}
+
+/// Return the messages of all commits reachable from `HEAD`, most recent first.
+#[cfg(any(feature = "rebase", feature = "sequencer"))]
+pub fn head_messages(repo: &Repository) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    for info in repo.rev_walk(Some(repo.head_id()?)).all()? {
+        out.push(info?.object()?.message_raw()?.to_string());
+    }
+    Ok(out)
+}
+
 pub fn basic_repo() -> Result<Repository> {
     repo("make_basic_repo.sh").map(|r| r.to_thread_local())
 }
@@ -292,15 +292,73 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
-        Subcommands::Log(crate::plumbing::options::log::Platform { pathspec }) => prepare_and_run(
+        Subcommands::Log(crate::plumbing::options::log::Platform { pathspec, notes }) => prepare_and_run(
             "log",
             trace,
             verbose,
             progress,
             progress_keep_open,
             None,
-            move |_progress, out, _err| core::repository::log::log(repository(Mode::Lenient)?, out, pathspec),
+            move |_progress, out, _err| core::repository::log::log(repository(Mode::Lenient)?, out, pathspec, notes),
         ),
+        Subcommands::Bundle(cmd) => match cmd {
+            crate::plumbing::options::bundle::Subcommands::Create { all, v3, file, specs } => prepare_and_run(
+                "bundle-create",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, _out, err| {
+                    core::repository::bundle::create(
+                        repository(Mode::Lenient)?,
+                        &file,
+                        specs,
+                        progress,
+                        err,
+                        gix::repository::create_bundle::Options {
+                            version: if v3 {
+                                gix::bundle::Version::V3
+                            } else {
+                                gix::bundle::Version::V2
+                            },
+                            all,
+                        },
+                    )
+                },
+            ),
+            crate::plumbing::options::bundle::Subcommands::Verify { file } => prepare_and_run(
+                "bundle-verify",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::bundle::verify(repository(Mode::Lenient)?, &file, out, format)
+                },
+            ),
+            crate::plumbing::options::bundle::Subcommands::ListHeads { file } => prepare_and_run(
+                "bundle-list-heads",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::bundle::list_heads(&file, out, format),
+            ),
+            crate::plumbing::options::bundle::Subcommands::Unbundle { file } => prepare_and_run(
+                "bundle-unbundle",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, out, _err| {
+                    core::repository::bundle::unbundle(repository(Mode::Lenient)?, &file, progress, out)
+                },
+            ),
+        },
         Subcommands::Worktree(crate::plumbing::options::worktree::Platform { cmd }) => match cmd {
             crate::plumbing::options::worktree::SubCommands::List => prepare_and_run(
                 "worktree-list",
@@ -311,6 +369,102 @@ pub fn main() -> Result<()> {
                 None,
                 move |_progress, out, _err| core::repository::worktree::list(repository(Mode::Lenient)?, out, format),
             ),
+            crate::plumbing::options::worktree::SubCommands::Add {
+                new_branch,
+                detach,
+                force,
+                no_checkout,
+                lock,
+                reason,
+                path,
+                commitish,
+            } => prepare_and_run(
+                "worktree-add",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::worktree::add(
+                        repository(Mode::Lenient)?,
+                        path,
+                        commitish,
+                        out,
+                        gix::repository::add_worktree::Options {
+                            new_branch: new_branch.map(Into::into),
+                            detach,
+                            force,
+                            no_checkout,
+                            lock: lock.then(|| reason.unwrap_or_default().into()),
+                        },
+                    )
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Remove { force, worktree } => prepare_and_run(
+                "worktree-remove",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| {
+                    core::repository::worktree::remove(repository(Mode::Lenient)?, &worktree, force)
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Move { worktree, new_path } => prepare_and_run(
+                "worktree-move",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| {
+                    core::repository::worktree::move_to(repository(Mode::Lenient)?, &worktree, &new_path)
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Lock { reason, worktree } => prepare_and_run(
+                "worktree-lock",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| {
+                    core::repository::worktree::lock(repository(Mode::Lenient)?, &worktree, reason.as_deref())
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Unlock { worktree } => prepare_and_run(
+                "worktree-unlock",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| core::repository::worktree::unlock(repository(Mode::Lenient)?, &worktree),
+            ),
+            crate::plumbing::options::worktree::SubCommands::Prune { dry_run } => prepare_and_run(
+                "worktree-prune",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::worktree::prune(repository(Mode::Lenient)?, dry_run, out, format)
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Repair { paths } => prepare_and_run(
+                "worktree-repair",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::worktree::repair(repository(Mode::Lenient)?, paths, out, format)
+                },
+            ),
         },
         Subcommands::IsClean | Subcommands::IsChanged => {
             let mode = if matches!(cmd, Subcommands::IsClean) {
@@ -544,6 +698,29 @@ pub fn main() -> Result<()> {
                 },
             )
             .map(|_| ()),
+            commitgraph::Subcommands::Write {
+                split,
+                changed_paths,
+                specs,
+            } => prepare_and_run(
+                "commitgraph-write",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::commitgraph::write(
+                        repository(Mode::Lenient)?,
+                        specs,
+                        split,
+                        changed_paths,
+                        out,
+                        format,
+                    )
+                },
+            )
+            .map(|_| ()),
             commitgraph::Subcommands::Verify { statistics } => prepare_and_run(
                 "commitgraph-verify",
                 trace,
@@ -594,6 +771,42 @@ pub fn main() -> Result<()> {
             )
         }
         #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::UploadPack { directory } => prepare_and_run(
+            "upload-pack",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::upload_pack(
+                    gix::open(directory)?,
+                    std::env::var("GIT_PROTOCOL").ok().as_deref(),
+                    std::io::stdin().lock(),
+                    out,
+                    &should_interrupt,
+                )
+            },
+        ),
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::ReceivePack { directory } => prepare_and_run(
+            "receive-pack",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::receive_pack(
+                    gix::open(directory)?,
+                    std::env::var("GIT_PROTOCOL").ok().as_deref(),
+                    std::io::stdin().lock(),
+                    out,
+                    &should_interrupt,
+                )
+            },
+        ),
+        #[cfg(feature = "gitoxide-core-blocking-client")]
         Subcommands::Fetch(crate::plumbing::options::fetch::Platform {
             dry_run,
             handshake_info,
@@ -848,6 +1061,9 @@ pub fn main() -> Result<()> {
                     repository,
                     expansion,
                     thin,
+                    delta_compression,
+                    window,
+                    depth,
                     statistics,
                     nondeterministic_count,
                     tips,
@@ -870,6 +1086,9 @@ pub fn main() -> Result<()> {
                             let context = core::pack::create::Context {
                                 thread_limit,
                                 thin,
+                                delta_compression,
+                                window,
+                                depth,
                                 nondeterministic_thread_count: nondeterministic_count.then_some(counting_threads),
                                 pack_cache_size_in_bytes: pack_cache_size_mb.unwrap_or(0) * 1_000_000,
                                 object_cache_size_in_bytes: object_cache_size_mb.unwrap_or(0) * 1_000_000,
@@ -1370,14 +1589,32 @@ pub fn main() -> Result<()> {
                 move |_progress, out, err| core::repository::odb::info(repository(Mode::Strict)?, format, out, err),
             ),
         },
-        Subcommands::Fsck(fsck::Platform { spec }) => prepare_and_run(
+        Subcommands::Fsck(fsck::Platform {
+            unreachable,
+            no_dangling,
+            lost_found,
+            specs,
+        }) => prepare_and_run(
             "fsck",
             trace,
             auto_verbose,
             progress,
             progress_keep_open,
-            None,
-            move |_progress, out, _err| core::repository::fsck(repository(Mode::Strict)?, spec, out),
+            core::repository::fsck::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::fsck(
+                    repository(Mode::Strict)?,
+                    specs,
+                    progress,
+                    out,
+                    &should_interrupt,
+                    core::repository::fsck::Context {
+                        unreachable,
+                        dangling: !no_dangling,
+                        lost_found,
+                    },
+                )
+            },
         ),
         Subcommands::Mailmap(cmd) => match cmd {
             mailmap::Subcommands::Entries => prepare_and_run(
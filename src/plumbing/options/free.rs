@@ -167,6 +167,22 @@ pub mod pack {
             /// Packs produced with this option enabled are only valid in transit, but not at rest.
             thin: bool,
 
+            #[clap(long, short = 'd', conflicts_with = "thin")]
+            /// if set, all objects will be decoded to find similar ones and store them as deltas against each other,
+            /// instead of copying existing deltas and base objects from packs as they are.
+            ///
+            /// This takes longer, but produces much smaller packs. Objects whose path has the `delta` attribute unset
+            /// will not be stored as deltas.
+            delta_compression: bool,
+
+            #[clap(long, requires = "delta_compression")]
+            /// The amount of objects to try as delta base for each object, defaulting to `pack.window`, or 10.
+            window: Option<usize>,
+
+            #[clap(long, requires = "delta_compression")]
+            /// The maximum length of delta chains, defaulting to `pack.depth`, or 50.
+            depth: Option<usize>,
+
             /// The directory into which to write the pack file.
             #[clap(long, short = 'o')]
             output_directory: Option<PathBuf>,
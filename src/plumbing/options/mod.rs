@@ -117,6 +117,22 @@ pub enum Subcommands {
     /// Clone a repository into a new directory.
     #[cfg(feature = "gitoxide-core-blocking-client")]
     Clone(clone::Platform),
+    /// Serve the repository at `directory` to a client connected to stdin and stdout, just like `git upload-pack`.
+    ///
+    /// The protocol version is taken from the `GIT_PROTOCOL` environment variable.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    UploadPack {
+        /// The repository to serve.
+        directory: PathBuf,
+    },
+    /// Accept a push into the repository at `directory` from a client connected to stdin and stdout, just like `git receive-pack`.
+    ///
+    /// The protocol version is taken from the `GIT_PROTOCOL` environment variable.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    ReceivePack {
+        /// The repository to push into.
+        directory: PathBuf,
+    },
     /// Interact with the mailmap.
     #[clap(subcommand)]
     Mailmap(mailmap::Subcommands),
@@ -157,6 +173,9 @@ pub enum Subcommands {
     Diff(diff::Platform),
     Log(log::Platform),
     Worktree(worktree::Platform),
+    /// Create, verify and unbundle bundles, files that transport objects and references between repositories.
+    #[clap(subcommand)]
+    Bundle(bundle::Subcommands),
     /// Subcommands that need no Git repository to run.
     #[clap(subcommand)]
     Free(free::Subcommands),
@@ -311,7 +330,47 @@ pub mod merge_base {
     }
 }
 
+pub mod bundle {
+    use std::path::PathBuf;
+
+    use gix::bstr::BString;
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Write a bundle with the given revisions and their history to `file`.
+        Create {
+            /// Add `HEAD` and all references along with their history.
+            #[clap(long)]
+            all: bool,
+            /// Write a version 3 bundle, which is the default if the repository doesn't use SHA1.
+            #[clap(long)]
+            v3: bool,
+            /// The file to write the bundle to.
+            file: PathBuf,
+            /// Revisions to include like `main`, exclude like `^main` or ranges like `main..topic`.
+            specs: Vec<BString>,
+        },
+        /// Check that the repository has all commits the bundle at `file` requires, and print its header.
+        Verify {
+            /// The bundle to verify.
+            file: PathBuf,
+        },
+        /// Print the references the bundle at `file` contains.
+        ListHeads {
+            /// The bundle to read.
+            file: PathBuf,
+        },
+        /// Add the objects of the bundle at `file` to the repository and print its references, without changing any reference.
+        Unbundle {
+            /// The bundle to unbundle.
+            file: PathBuf,
+        },
+    }
+}
+
 pub mod worktree {
+    use std::path::PathBuf;
+
     #[derive(Debug, clap::Parser)]
     #[command(about = "Commands for handling worktrees")]
     pub struct Platform {
@@ -323,6 +382,70 @@ pub mod worktree {
     pub enum SubCommands {
         /// List all worktrees, along with some accompanying information.
         List,
+        /// Create a new linked worktree and check out a branch or commit into it.
+        Add {
+            /// Create a new branch with the given name at the commit to check out, and check it out.
+            #[clap(long, short = 'b', value_name = "BRANCH")]
+            new_branch: Option<String>,
+            /// Detach `HEAD` at the commit to check out, even if it names a branch.
+            #[clap(long, conflicts_with = "new_branch")]
+            detach: bool,
+            /// Check out branches that are checked out elsewhere, and reset the new branch if it exists.
+            #[clap(long, short = 'f')]
+            force: bool,
+            /// Set up the worktree without checking out any files.
+            #[clap(long)]
+            no_checkout: bool,
+            /// Keep the worktree locked after creating it.
+            #[clap(long)]
+            lock: bool,
+            /// The reason for locking the worktree.
+            #[clap(long, requires = "lock")]
+            reason: Option<String>,
+            /// The directory to create the worktree in, which must not exist or be empty.
+            path: PathBuf,
+            /// The branch or commit to check out. Defaults to a branch named after the worktree.
+            commitish: Option<String>,
+        },
+        /// Delete a linked worktree along with its administrative data.
+        Remove {
+            /// Remove the worktree even if it is locked or has local modifications.
+            #[clap(long, short = 'f')]
+            force: bool,
+            /// The name or path of the worktree.
+            worktree: String,
+        },
+        /// Move a linked worktree to a new location.
+        Move {
+            /// The name or path of the worktree.
+            worktree: String,
+            /// The new location, or an existing directory to move the worktree into.
+            new_path: PathBuf,
+        },
+        /// Lock a linked worktree to prevent it from being pruned, moved or removed.
+        Lock {
+            /// The reason for locking the worktree.
+            #[clap(long)]
+            reason: Option<String>,
+            /// The name or path of the worktree.
+            worktree: String,
+        },
+        /// Unlock a linked worktree.
+        Unlock {
+            /// The name or path of the worktree.
+            worktree: String,
+        },
+        /// Delete the administrative data of worktrees that don't exist anymore.
+        Prune {
+            /// Only print which worktrees would be pruned.
+            #[clap(long, short = 'n')]
+            dry_run: bool,
+        },
+        /// Reconnect worktrees with their administrative data after either was moved manually.
+        Repair {
+            /// The new locations of worktrees that were moved.
+            paths: Vec<PathBuf>,
+        },
     }
 }
 
@@ -541,6 +664,10 @@ pub mod log {
         /// The git path specification to show a log for.
         #[clap(value_parser = crate::shared::AsBString)]
         pub pathspec: Option<BString>,
+        /// Show the notes of each commit, as stored in the notes reference configured in `core.notesRef`,
+        /// or in `refs/notes/commits`.
+        #[clap(long)]
+        pub notes: bool,
     }
 }
 
@@ -846,8 +973,17 @@ pub mod odb {
 pub mod fsck {
     #[derive(Debug, clap::Parser)]
     pub struct Platform {
-        /// A revspec to start the connectivity check from.
-        pub spec: Option<String>,
+        /// Print all objects that can't be reached from the starting points, not only the dangling ones.
+        #[clap(long)]
+        pub unreachable: bool,
+        /// Do not print dangling objects, i.e. unreachable objects that aren't referenced by any other object.
+        #[clap(long)]
+        pub no_dangling: bool,
+        /// Write dangling objects into `.git/lost-found`, with commits in `commit/` and all other objects in `other/`.
+        #[clap(long)]
+        pub lost_found: bool,
+        /// Revspecs to start the connectivity check from, instead of all references, their logs and the index.
+        pub specs: Vec<String>,
     }
 }
 
@@ -957,6 +1093,19 @@ pub mod commitgraph {
             #[clap(default_value = "@")]
             spec: std::ffi::OsString,
         },
+        /// Write a commit-graph file with all commits reachable from the given revisions, or from all references.
+        Write {
+            /// Write a new layer of a split commit-graph chain instead of a single file.
+            #[clap(long)]
+            split: bool,
+            /// Compute changed-path Bloom filters for new commits, to speed up path-limited traversals and blames.
+            ///
+            /// Filters are always written if the existing commit-graph already has them.
+            #[clap(long)]
+            changed_paths: bool,
+            /// The rev-specs to write reachable commits from.
+            specs: Vec<std::ffi::OsString>,
+        },
     }
 }
 
@@ -134,17 +134,13 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.preloadIndex",
         usage: Planned("It's enabled by default and allows parallel stat checks - it's using a lot of CPU for just minor performance boosts though")
     },
-    Record {
-        config: "commitGraph.generationVersion",
-        usage: NotPlanned("Couldn't find a test that would require corrected generation numbers, even 'git' has no test for this.")
-    },
     Record {
         config: "commitGraph.maxNewFilters",
-        usage: NotPlanned("Can be considered when the underlying feature is actually used or needed")
+        usage: NotPlanned("Changed-path Bloom filters are computed for all new commits when writing the commit-graph")
     },
     Record {
         config: "commitGraph.readChangedPaths",
-        usage: NotPlanned("Can be considered when the underlying feature is actually used or needed")
+        usage: NotPlanned("Changed-path Bloom filters are always used if the commit-graph has them")
     },
     Record {
         config: "index.sparse",